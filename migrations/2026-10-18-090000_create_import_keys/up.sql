@@ -0,0 +1,25 @@
+-- Your SQL goes here
+CREATE TABLE import_keys (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    application_id INT NOT NULL,
+    algorithm VARCHAR(50) NOT NULL,
+    iv VARCHAR(255) NOT NULL,
+    sum TEXT NOT NULL,
+    public_key TEXT NOT NULL,
+    expires_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    is_deleted BOOLEAN NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE,
+    deleted_at TIMESTAMP WITH TIME ZONE,
+    created_by_id UUID,
+    updated_by_id UUID,
+    deleted_by_id UUID
+);
+
+ALTER TABLE import_keys
+  ADD CONSTRAINT fk_import_keys_application FOREIGN KEY (application_id) REFERENCES applications(id),
+  ADD CONSTRAINT fk_import_keys_created_by FOREIGN KEY (created_by_id) REFERENCES users(id),
+  ADD CONSTRAINT fk_import_keys_updated_by FOREIGN KEY (updated_by_id) REFERENCES users(id),
+  ADD CONSTRAINT fk_import_keys_deleted_by FOREIGN KEY (deleted_by_id) REFERENCES users(id);
+
+CREATE INDEX index_import_keys_on_application_algorithm ON import_keys (application_id, algorithm);
@@ -0,0 +1,4 @@
+-- This file should undo anything in `up.sql`
+DROP TABLE IF EXISTS x_secret_cluster;
+DROP TABLE IF EXISTS secret_versions;
+DROP TABLE IF EXISTS secrets;
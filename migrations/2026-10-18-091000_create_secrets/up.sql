@@ -0,0 +1,67 @@
+-- Your SQL goes here
+CREATE TABLE secrets (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    application_id INT NOT NULL,
+    name VARCHAR(255) NOT NULL,
+    current_version INT NOT NULL,
+    is_deleted BOOLEAN NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE,
+    deleted_at TIMESTAMP WITH TIME ZONE,
+    created_by_id UUID,
+    updated_by_id UUID,
+    deleted_by_id UUID
+);
+
+ALTER TABLE secrets
+  ADD CONSTRAINT fk_secrets_application FOREIGN KEY (application_id) REFERENCES applications(id),
+  ADD CONSTRAINT fk_secrets_created_by FOREIGN KEY (created_by_id) REFERENCES users(id),
+  ADD CONSTRAINT fk_secrets_updated_by FOREIGN KEY (updated_by_id) REFERENCES users(id),
+  ADD CONSTRAINT fk_secrets_deleted_by FOREIGN KEY (deleted_by_id) REFERENCES users(id);
+
+CREATE UNIQUE INDEX index_secrets_on_application_id_name ON secrets (application_id, name) WHERE is_deleted = false;
+
+CREATE TABLE secret_versions (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    secret_id UUID NOT NULL,
+    version INT NOT NULL,
+    iv VARCHAR(255) NOT NULL,
+    sum TEXT NOT NULL,
+    FOREIGN KEY (secret_id) REFERENCES secrets(id) ON DELETE CASCADE,
+    is_deleted BOOLEAN NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE,
+    deleted_at TIMESTAMP WITH TIME ZONE,
+    created_by_id UUID,
+    updated_by_id UUID,
+    deleted_by_id UUID
+);
+
+ALTER TABLE secret_versions
+  ADD CONSTRAINT fk_secret_versions_created_by FOREIGN KEY (created_by_id) REFERENCES users(id),
+  ADD CONSTRAINT fk_secret_versions_updated_by FOREIGN KEY (updated_by_id) REFERENCES users(id),
+  ADD CONSTRAINT fk_secret_versions_deleted_by FOREIGN KEY (deleted_by_id) REFERENCES users(id);
+
+CREATE UNIQUE INDEX index_secret_versions_on_secret_id_version ON secret_versions (secret_id, version);
+
+CREATE TABLE x_secret_cluster (
+    id SERIAL PRIMARY KEY,
+    secret_id UUID NOT NULL,
+    cluster_id UUID NOT NULL,
+    FOREIGN KEY (secret_id) REFERENCES secrets(id) ON DELETE CASCADE,
+    FOREIGN KEY (cluster_id) REFERENCES clusters(id) ON DELETE CASCADE,
+    is_deleted BOOLEAN NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE,
+    deleted_at TIMESTAMP WITH TIME ZONE,
+    created_by_id UUID,
+    updated_by_id UUID,
+    deleted_by_id UUID
+);
+
+ALTER TABLE x_secret_cluster
+  ADD CONSTRAINT fk_x_secret_cluster_created_by FOREIGN KEY (created_by_id) REFERENCES users(id),
+  ADD CONSTRAINT fk_x_secret_cluster_updated_by FOREIGN KEY (updated_by_id) REFERENCES users(id),
+  ADD CONSTRAINT fk_x_secret_cluster_deleted_by FOREIGN KEY (deleted_by_id) REFERENCES users(id);
+
+CREATE INDEX index_x_secret_cluster_on_secret_id ON x_secret_cluster (secret_id);
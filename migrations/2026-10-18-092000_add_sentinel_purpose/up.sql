@@ -0,0 +1,2 @@
+-- Your SQL goes here
+ALTER TABLE sentinels ADD COLUMN purpose VARCHAR(50) NOT NULL DEFAULT 'encryption';
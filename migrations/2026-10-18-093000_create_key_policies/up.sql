@@ -0,0 +1,31 @@
+-- Your SQL goes here
+CREATE TABLE key_policies (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    sentinel_id UUID,
+    anonymous_sentinel_id UUID,
+    operations TEXT[] NOT NULL,
+    allowed_cidrs TEXT[] NOT NULL,
+    allowed_days INT[] NOT NULL,
+    hour_start INT,
+    hour_end INT,
+    max_reads INT,
+    read_count INT NOT NULL DEFAULT 0,
+    FOREIGN KEY (sentinel_id) REFERENCES sentinels(id) ON DELETE CASCADE,
+    FOREIGN KEY (anonymous_sentinel_id) REFERENCES anonymous_sentinels(id) ON DELETE CASCADE,
+    CHECK ((sentinel_id IS NULL) <> (anonymous_sentinel_id IS NULL)),
+    is_deleted BOOLEAN NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE,
+    deleted_at TIMESTAMP WITH TIME ZONE,
+    created_by_id UUID,
+    updated_by_id UUID,
+    deleted_by_id UUID
+);
+
+ALTER TABLE key_policies
+  ADD CONSTRAINT fk_key_policies_created_by FOREIGN KEY (created_by_id) REFERENCES users(id),
+  ADD CONSTRAINT fk_key_policies_updated_by FOREIGN KEY (updated_by_id) REFERENCES users(id),
+  ADD CONSTRAINT fk_key_policies_deleted_by FOREIGN KEY (deleted_by_id) REFERENCES users(id);
+
+CREATE UNIQUE INDEX index_key_policies_on_sentinel_id ON key_policies (sentinel_id) WHERE is_deleted = false;
+CREATE UNIQUE INDEX index_key_policies_on_anonymous_sentinel_id ON key_policies (anonymous_sentinel_id) WHERE is_deleted = false;
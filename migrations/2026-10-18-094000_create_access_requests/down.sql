@@ -0,0 +1,6 @@
+-- This file should undo anything in `up.sql`
+DROP TABLE IF EXISTS access_request_approvals;
+DROP TABLE IF EXISTS access_requests;
+ALTER TABLE clusters DROP COLUMN required_approvals;
+ALTER TABLE anonymous_sentinels DROP COLUMN required_approvals;
+ALTER TABLE sentinels DROP COLUMN required_approvals;
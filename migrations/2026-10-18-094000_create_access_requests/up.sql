@@ -0,0 +1,45 @@
+-- Your SQL goes here
+ALTER TABLE sentinels ADD COLUMN required_approvals INT NOT NULL DEFAULT 0;
+ALTER TABLE anonymous_sentinels ADD COLUMN required_approvals INT NOT NULL DEFAULT 0;
+ALTER TABLE clusters ADD COLUMN required_approvals INT NOT NULL DEFAULT 0;
+
+CREATE TABLE access_requests (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    sentinel_id UUID,
+    anonymous_sentinel_id UUID,
+    requester_id UUID NOT NULL,
+    required_approvals INT NOT NULL,
+    status VARCHAR(50) NOT NULL,
+    expires_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    FOREIGN KEY (sentinel_id) REFERENCES sentinels(id) ON DELETE CASCADE,
+    FOREIGN KEY (anonymous_sentinel_id) REFERENCES anonymous_sentinels(id) ON DELETE CASCADE,
+    FOREIGN KEY (requester_id) REFERENCES users(id) ON DELETE CASCADE,
+    CHECK ((sentinel_id IS NULL) <> (anonymous_sentinel_id IS NULL)),
+    is_deleted BOOLEAN NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE,
+    deleted_at TIMESTAMP WITH TIME ZONE,
+    created_by_id UUID,
+    updated_by_id UUID,
+    deleted_by_id UUID
+);
+
+ALTER TABLE access_requests
+  ADD CONSTRAINT fk_access_requests_created_by FOREIGN KEY (created_by_id) REFERENCES users(id),
+  ADD CONSTRAINT fk_access_requests_updated_by FOREIGN KEY (updated_by_id) REFERENCES users(id),
+  ADD CONSTRAINT fk_access_requests_deleted_by FOREIGN KEY (deleted_by_id) REFERENCES users(id);
+
+CREATE INDEX index_access_requests_on_sentinel_id ON access_requests (sentinel_id);
+CREATE INDEX index_access_requests_on_anonymous_sentinel_id ON access_requests (anonymous_sentinel_id);
+CREATE INDEX index_access_requests_on_requester_id ON access_requests (requester_id);
+
+CREATE TABLE access_request_approvals (
+    id SERIAL PRIMARY KEY,
+    access_request_id UUID NOT NULL,
+    approver_id UUID NOT NULL,
+    FOREIGN KEY (access_request_id) REFERENCES access_requests(id) ON DELETE CASCADE,
+    FOREIGN KEY (approver_id) REFERENCES users(id) ON DELETE CASCADE,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW()
+);
+
+CREATE UNIQUE INDEX index_access_request_approvals_on_access_request_id_approver_id ON access_request_approvals (access_request_id, approver_id);
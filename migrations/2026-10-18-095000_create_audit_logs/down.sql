@@ -0,0 +1,12 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE anonymous_sentinels
+  DROP CONSTRAINT IF EXISTS fk_anonymous_sentinels_break_glass_by,
+  DROP COLUMN IF EXISTS break_glass_by_id,
+  DROP COLUMN IF EXISTS break_glass_at;
+
+ALTER TABLE sentinels
+  DROP CONSTRAINT IF EXISTS fk_sentinels_break_glass_by,
+  DROP COLUMN IF EXISTS break_glass_by_id,
+  DROP COLUMN IF EXISTS break_glass_at;
+
+DROP TABLE IF EXISTS audit_logs;
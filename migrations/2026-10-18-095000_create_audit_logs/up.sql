@@ -0,0 +1,28 @@
+-- Your SQL goes here
+CREATE TABLE audit_logs (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    application_id INT,
+    user_id UUID,
+    action VARCHAR(100) NOT NULL,
+    severity VARCHAR(20) NOT NULL,
+    target_type VARCHAR(50),
+    target_id UUID,
+    justification TEXT,
+    ip VARCHAR(100),
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    FOREIGN KEY (application_id) REFERENCES applications(id) ON DELETE CASCADE,
+    FOREIGN KEY (user_id) REFERENCES users(id)
+);
+
+CREATE INDEX index_audit_logs_on_application_id_and_created_at ON audit_logs (application_id, created_at);
+CREATE INDEX index_audit_logs_on_target_id ON audit_logs (target_id);
+
+ALTER TABLE sentinels
+  ADD COLUMN break_glass_at TIMESTAMP WITH TIME ZONE,
+  ADD COLUMN break_glass_by_id UUID,
+  ADD CONSTRAINT fk_sentinels_break_glass_by FOREIGN KEY (break_glass_by_id) REFERENCES users(id);
+
+ALTER TABLE anonymous_sentinels
+  ADD COLUMN break_glass_at TIMESTAMP WITH TIME ZONE,
+  ADD COLUMN break_glass_by_id UUID,
+  ADD CONSTRAINT fk_anonymous_sentinels_break_glass_by FOREIGN KEY (break_glass_by_id) REFERENCES users(id);
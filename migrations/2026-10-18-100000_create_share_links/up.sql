@@ -0,0 +1,27 @@
+-- Your SQL goes here
+CREATE TABLE share_links (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    sentinel_id UUID NOT NULL,
+    token_hash VARCHAR(64) NOT NULL,
+    max_uses INT NOT NULL,
+    use_count INT NOT NULL DEFAULT 0,
+    expires_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    recipient_public_key TEXT,
+    FOREIGN KEY (sentinel_id) REFERENCES sentinels(id) ON DELETE CASCADE,
+    CHECK (max_uses > 0),
+    is_deleted BOOLEAN NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE,
+    deleted_at TIMESTAMP WITH TIME ZONE,
+    created_by_id UUID,
+    updated_by_id UUID,
+    deleted_by_id UUID
+);
+
+ALTER TABLE share_links
+  ADD CONSTRAINT fk_share_links_created_by FOREIGN KEY (created_by_id) REFERENCES users(id),
+  ADD CONSTRAINT fk_share_links_updated_by FOREIGN KEY (updated_by_id) REFERENCES users(id),
+  ADD CONSTRAINT fk_share_links_deleted_by FOREIGN KEY (deleted_by_id) REFERENCES users(id);
+
+CREATE UNIQUE INDEX index_share_links_on_token_hash ON share_links (token_hash);
+CREATE INDEX index_share_links_on_sentinel_id ON share_links (sentinel_id);
@@ -0,0 +1,30 @@
+-- Your SQL goes here
+CREATE TABLE service_accounts (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    user_id UUID NOT NULL,
+    application_id INT NOT NULL,
+    name VARCHAR(100) NOT NULL,
+    client_id VARCHAR(64) NOT NULL,
+    client_secret_hash VARCHAR(64),
+    public_key TEXT,
+    last_used_at TIMESTAMP WITH TIME ZONE,
+    FOREIGN KEY (user_id) REFERENCES users(id),
+    FOREIGN KEY (application_id) REFERENCES applications(id),
+    CHECK (client_secret_hash IS NOT NULL OR public_key IS NOT NULL),
+    is_deleted BOOLEAN NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE,
+    deleted_at TIMESTAMP WITH TIME ZONE,
+    created_by_id UUID,
+    updated_by_id UUID,
+    deleted_by_id UUID
+);
+
+ALTER TABLE service_accounts
+  ADD CONSTRAINT fk_service_accounts_created_by FOREIGN KEY (created_by_id) REFERENCES users(id),
+  ADD CONSTRAINT fk_service_accounts_updated_by FOREIGN KEY (updated_by_id) REFERENCES users(id),
+  ADD CONSTRAINT fk_service_accounts_deleted_by FOREIGN KEY (deleted_by_id) REFERENCES users(id);
+
+CREATE UNIQUE INDEX index_service_accounts_on_client_id ON service_accounts (client_id);
+CREATE UNIQUE INDEX index_service_accounts_on_user_id ON service_accounts (user_id);
+CREATE INDEX index_service_accounts_on_application_id ON service_accounts (application_id);
@@ -0,0 +1,53 @@
+-- Your SQL goes here
+CREATE TABLE oauth_clients (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    application_id INT NOT NULL,
+    name VARCHAR(100) NOT NULL,
+    client_id VARCHAR(64) NOT NULL,
+    client_secret_hash VARCHAR(64),
+    redirect_uris TEXT[] NOT NULL,
+    FOREIGN KEY (application_id) REFERENCES applications(id),
+    is_deleted BOOLEAN NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE,
+    deleted_at TIMESTAMP WITH TIME ZONE,
+    created_by_id UUID,
+    updated_by_id UUID,
+    deleted_by_id UUID
+);
+
+ALTER TABLE oauth_clients
+  ADD CONSTRAINT fk_oauth_clients_created_by FOREIGN KEY (created_by_id) REFERENCES users(id),
+  ADD CONSTRAINT fk_oauth_clients_updated_by FOREIGN KEY (updated_by_id) REFERENCES users(id),
+  ADD CONSTRAINT fk_oauth_clients_deleted_by FOREIGN KEY (deleted_by_id) REFERENCES users(id);
+
+CREATE UNIQUE INDEX index_oauth_clients_on_client_id ON oauth_clients (client_id);
+CREATE INDEX index_oauth_clients_on_application_id ON oauth_clients (application_id);
+
+CREATE TABLE oauth_codes (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    oauth_client_id UUID NOT NULL,
+    user_id UUID NOT NULL,
+    code_hash VARCHAR(64) NOT NULL,
+    redirect_uri TEXT NOT NULL,
+    code_challenge VARCHAR(128) NOT NULL,
+    scope TEXT,
+    expires_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    used_at TIMESTAMP WITH TIME ZONE,
+    FOREIGN KEY (oauth_client_id) REFERENCES oauth_clients(id) ON DELETE CASCADE,
+    FOREIGN KEY (user_id) REFERENCES users(id),
+    is_deleted BOOLEAN NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE,
+    deleted_at TIMESTAMP WITH TIME ZONE,
+    created_by_id UUID,
+    updated_by_id UUID,
+    deleted_by_id UUID
+);
+
+ALTER TABLE oauth_codes
+  ADD CONSTRAINT fk_oauth_codes_created_by FOREIGN KEY (created_by_id) REFERENCES users(id),
+  ADD CONSTRAINT fk_oauth_codes_updated_by FOREIGN KEY (updated_by_id) REFERENCES users(id),
+  ADD CONSTRAINT fk_oauth_codes_deleted_by FOREIGN KEY (deleted_by_id) REFERENCES users(id);
+
+CREATE UNIQUE INDEX index_oauth_codes_on_code_hash ON oauth_codes (code_hash);
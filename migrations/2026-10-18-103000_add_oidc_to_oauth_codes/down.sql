@@ -0,0 +1,5 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE oauth_codes
+  DROP COLUMN IF EXISTS nonce,
+  DROP COLUMN IF EXISTS auth_time,
+  DROP COLUMN IF EXISTS amr;
@@ -0,0 +1,5 @@
+-- Your SQL goes here
+ALTER TABLE oauth_codes
+  ADD COLUMN nonce TEXT,
+  ADD COLUMN auth_time TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+  ADD COLUMN amr TEXT[] NOT NULL DEFAULT '{}';
@@ -0,0 +1,26 @@
+-- Your SQL goes here
+CREATE TABLE signing_keys (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    kid VARCHAR(64) NOT NULL,
+    algorithm VARCHAR(10) NOT NULL DEFAULT 'RS256',
+    public_key TEXT NOT NULL,
+    private_key TEXT NOT NULL,
+    iv VARCHAR(32) NOT NULL,
+    activates_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    retired_at TIMESTAMP WITH TIME ZONE,
+    is_deleted BOOLEAN NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE,
+    deleted_at TIMESTAMP WITH TIME ZONE,
+    created_by_id UUID,
+    updated_by_id UUID,
+    deleted_by_id UUID
+);
+
+ALTER TABLE signing_keys
+  ADD CONSTRAINT fk_signing_keys_created_by FOREIGN KEY (created_by_id) REFERENCES users(id),
+  ADD CONSTRAINT fk_signing_keys_updated_by FOREIGN KEY (updated_by_id) REFERENCES users(id),
+  ADD CONSTRAINT fk_signing_keys_deleted_by FOREIGN KEY (deleted_by_id) REFERENCES users(id);
+
+CREATE UNIQUE INDEX index_signing_keys_on_kid ON signing_keys (kid);
+CREATE INDEX index_signing_keys_on_activates_at ON signing_keys (activates_at);
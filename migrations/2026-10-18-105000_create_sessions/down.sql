@@ -0,0 +1,9 @@
+-- This file should undo anything in `up.sql`
+DROP TABLE IF EXISTS sessions;
+
+-- the revoked tokens stay hashed, a hash never matches a token
+DROP INDEX IF EXISTS index_revoked_tokens_on_token_hash;
+DROP INDEX IF EXISTS index_revoked_tokens_on_expires_at;
+ALTER TABLE revoked_tokens ALTER COLUMN token_hash TYPE TEXT;
+ALTER TABLE revoked_tokens RENAME COLUMN token_hash TO token;
+ALTER TABLE revoked_tokens DROP COLUMN expires_at;
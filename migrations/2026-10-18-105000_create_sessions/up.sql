@@ -0,0 +1,52 @@
+-- Your SQL goes here
+CREATE TABLE sessions (
+    id UUID PRIMARY KEY DEFAULT uuid_generate_v4(),
+    user_id UUID NOT NULL,
+    application_id INT NOT NULL,
+    connexion_id INT,
+    refresh_jti UUID NOT NULL,
+    expires_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    revoked_at TIMESTAMP WITH TIME ZONE,
+    FOREIGN KEY (user_id) REFERENCES users(id),
+    FOREIGN KEY (application_id) REFERENCES applications(id),
+    FOREIGN KEY (connexion_id) REFERENCES connexions(id),
+    is_deleted BOOLEAN NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMP WITH TIME ZONE,
+    deleted_at TIMESTAMP WITH TIME ZONE,
+    created_by_id UUID,
+    updated_by_id UUID,
+    deleted_by_id UUID
+);
+
+ALTER TABLE sessions
+  ADD CONSTRAINT fk_sessions_created_by FOREIGN KEY (created_by_id) REFERENCES users(id),
+  ADD CONSTRAINT fk_sessions_updated_by FOREIGN KEY (updated_by_id) REFERENCES users(id),
+  ADD CONSTRAINT fk_sessions_deleted_by FOREIGN KEY (deleted_by_id) REFERENCES users(id);
+
+CREATE INDEX index_sessions_on_user_id ON sessions (user_id);
+
+-- the revoked refresh tokens are kept as SHA-256 until they expire, the expiration is read from the `exp` claim
+ALTER TABLE revoked_tokens ADD COLUMN expires_at TIMESTAMP WITH TIME ZONE;
+
+UPDATE revoked_tokens SET expires_at = to_timestamp(substring(encode(decode(
+    rpad(
+        translate(split_part(token, '.', 2), '-_', '+/'),
+        ((length(split_part(token, '.', 2)) + 3) / 4) * 4,
+        '='
+    ),
+    'base64'
+), 'escape') FROM '"exp":\s*(\d+)')::BIGINT);
+
+DELETE FROM revoked_tokens WHERE expires_at IS NULL OR expires_at < NOW();
+
+UPDATE revoked_tokens SET token = encode(sha256(token::BYTEA), 'hex');
+
+ALTER TABLE revoked_tokens RENAME COLUMN token TO token_hash;
+ALTER TABLE revoked_tokens ALTER COLUMN token_hash TYPE VARCHAR(64);
+ALTER TABLE revoked_tokens ALTER COLUMN expires_at SET NOT NULL;
+
+DELETE FROM revoked_tokens a USING revoked_tokens b WHERE a.token_hash = b.token_hash AND a.id > b.id;
+
+CREATE UNIQUE INDEX index_revoked_tokens_on_token_hash ON revoked_tokens (token_hash);
+CREATE INDEX index_revoked_tokens_on_expires_at ON revoked_tokens (expires_at);
@@ -0,0 +1,7 @@
+-- Your SQL goes here
+ALTER TABLE sessions ADD COLUMN last_used_at TIMESTAMP WITH TIME ZONE;
+
+UPDATE sessions SET last_used_at = COALESCE(updated_at, created_at);
+
+ALTER TABLE sessions ALTER COLUMN last_used_at SET NOT NULL;
+ALTER TABLE sessions ALTER COLUMN last_used_at SET DEFAULT NOW();
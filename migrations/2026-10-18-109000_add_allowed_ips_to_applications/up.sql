@@ -0,0 +1,2 @@
+-- Your SQL goes here
+ALTER TABLE applications ADD COLUMN allowed_ips TEXT[] NOT NULL DEFAULT '{}';
@@ -0,0 +1,2 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE service_accounts DROP COLUMN signing_key;
@@ -0,0 +1,2 @@
+-- Your SQL goes here
+ALTER TABLE service_accounts ADD COLUMN signing_key TEXT;
@@ -0,0 +1,50 @@
+use dotenv::dotenv;
+use std::fs;
+
+use crate::{
+    core::nodes_config::NodesConfig, db::connect::DbPool, services::backup::BackupService,
+    utils::cli::CLIUtils,
+};
+
+/// ### CommandBackup
+///
+/// launch this command to create an encrypted and signed backup archive
+/// the archive contains the postgres metadata and the fragments of every key
+/// ```
+/// let _ = CommandBackup::exec(pool: DbPool, nodes_config: NodesConfig, path: &str).await;
+/// ```
+///
+/// the archive is encrypted with the `BACKUP_KEY` setting
+/// and signed with the key of `BACKUP_SIGNING_KEY_PATH`
+pub struct CommandBackup;
+
+impl CommandBackup {
+    pub async fn exec(pool: DbPool, nodes_config: NodesConfig, path: &str) {
+        dotenv().ok();
+        CLIUtils::empty_line();
+        CLIUtils::separator();
+        CLIUtils::empty_line();
+        let backup_service = BackupService::new(&pool, &nodes_config);
+        let archive = match backup_service.create_archive() {
+            Err(e) => {
+                CLIUtils::write(&format!("backup failed : {}", e));
+                return;
+            }
+            Ok(archive) => archive,
+        };
+        let serialized = serde_json::to_string(&archive).expect("Failed to serialize archive");
+        match fs::write(path, serialized) {
+            Err(e) => CLIUtils::write(&format!("backup failed : {}", e)),
+            Ok(_) => {
+                CLIUtils::write("Backup created");
+                CLIUtils::empty_line();
+                CLIUtils::separator();
+                println!("archive path:  {}", path);
+                println!("created at:  {}", archive.created_at);
+            }
+        }
+        CLIUtils::empty_line();
+        CLIUtils::separator();
+        CLIUtils::empty_line();
+    }
+}
@@ -0,0 +1,59 @@
+use dotenv::dotenv;
+use uuid::Uuid;
+
+use crate::{
+    core::nodes_config::NodesConfig, db::connect::DbPool,
+    repositories::application::ApplicationRepository, services::recovery_kit::RecoveryKitService,
+    traits::application::ApplicationContract, utils::cli::CLIUtils,
+};
+
+/// ### CommandExportRecoveryKit
+///
+/// launch this command to export a sentinel key as a paper recovery kit
+/// the key is split in `shares` printable shares, any `threshold` of them rebuild it
+/// ```
+/// let _ = CommandExportRecoveryKit::exec(pool: DbPool, nodes_config: NodesConfig, sentinel_id: &str, threshold: u8, shares: u8).await;
+/// ```
+///
+/// each share is displayed on the command line interface, to be printed and given to a custodian
+pub struct CommandExportRecoveryKit;
+
+impl CommandExportRecoveryKit {
+    pub async fn exec(
+        pool: DbPool,
+        nodes_config: NodesConfig,
+        sentinel_id: &str,
+        threshold: u8,
+        shares: u8,
+    ) {
+        dotenv().ok();
+        CLIUtils::empty_line();
+        CLIUtils::separator();
+        CLIUtils::empty_line();
+        let sentinel_uuid = match Uuid::parse_str(sentinel_id) {
+            Err(_) => {
+                CLIUtils::write("Bad Sentinel uuid");
+                return;
+            }
+            Ok(uuid) => uuid,
+        };
+        let application_repository: ApplicationRepository = ApplicationContract::new(&pool);
+        let recovery_kit_service =
+            RecoveryKitService::new(&pool, application_repository, &nodes_config);
+        match recovery_kit_service.export(sentinel_uuid, threshold, shares) {
+            Err(e) => CLIUtils::write(&format!("export failed : {}", e)),
+            Ok(recovery_shares) => {
+                CLIUtils::write("Recovery kit created");
+                for recovery_share in recovery_shares {
+                    CLIUtils::empty_line();
+                    CLIUtils::separator();
+                    CLIUtils::empty_line();
+                    CLIUtils::write(&recovery_share.to_text());
+                }
+            }
+        }
+        CLIUtils::empty_line();
+        CLIUtils::separator();
+        CLIUtils::empty_line();
+    }
+}
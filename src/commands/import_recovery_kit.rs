@@ -0,0 +1,66 @@
+use dotenv::dotenv;
+use std::fs;
+
+use crate::{
+    core::nodes_config::NodesConfig, db::connect::DbPool,
+    repositories::application::ApplicationRepository, services::recovery_kit::RecoveryKitService,
+    traits::application::ApplicationContract, utils::cli::CLIUtils,
+};
+
+/// ### CommandImportRecoveryKit
+///
+/// launch this command to re-create a sentinel from the shares of its paper recovery kit
+/// the shares are read from the given files, or typed by the custodians when no file is given
+/// ```
+/// let _ = CommandImportRecoveryKit::exec(pool: DbPool, nodes_config: NodesConfig, paths: Vec<String>).await;
+/// ```
+///
+/// the sentinel is re-created under its original id
+pub struct CommandImportRecoveryKit;
+
+impl CommandImportRecoveryKit {
+    pub async fn exec(pool: DbPool, nodes_config: NodesConfig, paths: Vec<String>) {
+        dotenv().ok();
+        CLIUtils::empty_line();
+        CLIUtils::separator();
+        CLIUtils::empty_line();
+        let mut shares = vec![];
+        for path in paths.iter() {
+            match fs::read_to_string(path) {
+                Err(e) => {
+                    CLIUtils::write(&format!("import failed : {} ({})", e, path));
+                    return;
+                }
+                Ok(share) => shares.push(share),
+            }
+        }
+        if shares.is_empty() {
+            loop {
+                let share = CLIUtils::prompt(&format!(
+                    "recovery share {} (on one line, leave empty when done)",
+                    shares.len() + 1
+                ));
+                if share.trim().is_empty() {
+                    break;
+                }
+                shares.push(share);
+            }
+        }
+        let application_repository: ApplicationRepository = ApplicationContract::new(&pool);
+        let recovery_kit_service =
+            RecoveryKitService::new(&pool, application_repository, &nodes_config);
+        match recovery_kit_service.import(shares) {
+            Err(e) => CLIUtils::write(&format!("import failed : {}", e)),
+            Ok(sentinel) => {
+                CLIUtils::write("Sentinel re-created");
+                CLIUtils::empty_line();
+                CLIUtils::separator();
+                println!("sentinel id:  {}", sentinel.id);
+                println!("application id:  {}", sentinel.application_id);
+            }
+        }
+        CLIUtils::empty_line();
+        CLIUtils::separator();
+        CLIUtils::empty_line();
+    }
+}
@@ -1,3 +1,8 @@
 pub mod init;
 pub mod hsm_init;
-pub mod create_application;
\ No newline at end of file
+pub mod create_application;
+pub mod backup;
+pub mod restore;
+pub mod export_recovery_kit;
+pub mod import_recovery_kit;
+pub mod rotate_signing_key;
\ No newline at end of file
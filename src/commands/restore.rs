@@ -0,0 +1,60 @@
+use dotenv::dotenv;
+use std::fs;
+
+use crate::{
+    core::nodes_config::NodesConfig, db::connect::DbPool, dto::backup::backup_archive::BackupArchive,
+    services::backup::BackupService, utils::cli::CLIUtils,
+};
+
+/// ### CommandRestore
+///
+/// launch this command to restore a backup archive on a fresh instance
+/// the metadata are inserted and the fragments are dealt to the configured nodes
+/// ```
+/// let _ = CommandRestore::exec(pool: DbPool, nodes_config: NodesConfig, path: &str).await;
+/// ```
+///
+/// the `BACKUP_KEY` and the master key must be the ones of the saved instance,
+/// the key signing the archive must be the signing key or one of `BACKUP_VERIFY_KEY_PATHS`
+pub struct CommandRestore;
+
+impl CommandRestore {
+    pub async fn exec(pool: DbPool, nodes_config: NodesConfig, path: &str) {
+        dotenv().ok();
+        CLIUtils::empty_line();
+        CLIUtils::separator();
+        CLIUtils::empty_line();
+        let archive: BackupArchive = match fs::read_to_string(path)
+            .map_err(|e| e.to_string())
+            .and_then(|raw| serde_json::from_str(&raw).map_err(|e| e.to_string()))
+        {
+            Err(e) => {
+                CLIUtils::write(&format!("restore failed : {}", e));
+                return;
+            }
+            Ok(archive) => archive,
+        };
+        let backup_service = BackupService::new(&pool, &nodes_config);
+        match backup_service.restore_archive(archive) {
+            Err(e) => CLIUtils::write(&format!("restore failed : {}", e)),
+            Ok(content) => {
+                CLIUtils::write("Backup restored");
+                CLIUtils::empty_line();
+                CLIUtils::separator();
+                println!("applications:  {}", content.applications.len());
+                println!("users:  {}", content.users.len());
+                println!("clusters:  {}", content.clusters.len());
+                println!("sentinels:  {}", content.sentinels.len());
+                println!("anonymous sentinels:  {}", content.anonymous_sentinels.len());
+                println!("service accounts:  {}", content.service_accounts.len());
+                println!("oauth clients:  {}", content.oauth_clients.len());
+                println!("passkeys:  {}", content.webauthn_credentials.len());
+                println!("recovery codes:  {}", content.recovery_codes.len());
+                println!("keys dealt to the nodes:  {}", content.fragments.len());
+            }
+        }
+        CLIUtils::empty_line();
+        CLIUtils::separator();
+        CLIUtils::empty_line();
+    }
+}
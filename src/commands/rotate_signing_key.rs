@@ -0,0 +1,48 @@
+use dotenv::dotenv;
+
+use crate::{
+    db::connect::DbPool,
+    services::signing_key::SigningKeyService,
+    utils::{cli::CLIUtils, key_ring::KeyRing},
+};
+
+/// ### CommandRotateSigningKey
+///
+/// launch this command to publish a new token signing key without waiting for the scheduled rotation
+/// ```
+/// let _ = CommandRotateSigningKey::exec(pool: DbPool).await;
+/// ```
+///
+/// the key is published through the JWKS at once and signs once the `SIGNING_KEY_PUBLICATION_DELAY` is over,
+/// the tokens already issued stay valid
+pub struct CommandRotateSigningKey;
+
+impl CommandRotateSigningKey {
+    pub async fn exec(pool: DbPool) {
+        dotenv().ok();
+        CLIUtils::empty_line();
+        CLIUtils::separator();
+        CLIUtils::empty_line();
+        let signing_key_service = SigningKeyService::new(&pool);
+        let rotated = signing_key_service
+            .load()
+            .and_then(|_| signing_key_service.rotate());
+        match rotated {
+            Err(e) => CLIUtils::write(&format!("rotation failed : {}", e)),
+            Ok(signing_key) => {
+                CLIUtils::write("Signing key published");
+                CLIUtils::empty_line();
+                CLIUtils::separator();
+                println!("kid:  {}", signing_key.kid);
+                println!("signs from:  {}", signing_key.activates_at);
+                println!(
+                    "signing now:  {}",
+                    KeyRing::signing_kid().unwrap_or_default()
+                );
+            }
+        }
+        CLIUtils::empty_line();
+        CLIUtils::separator();
+        CLIUtils::empty_line();
+    }
+}
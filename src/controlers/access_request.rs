@@ -0,0 +1,170 @@
+use crate::dto::access_request::access_request_output::AccessRequestOutput;
+use crate::enums::roles::Role;
+use crate::guards::security::Security;
+use crate::repositories::access_request::AccessRequestRepository;
+use crate::services::access_request::AccessRequestService;
+use crate::traits::access_request::AccessRequestContract;
+use rocket::http::Status;
+use rocket::post;
+use rocket::serde::json::Json;
+use rocket_okapi::openapi;
+use uuid::Uuid;
+
+use crate::core::errors::{CustomError, ErrorObject};
+use crate::db::connect::DbPool;
+
+/// # List Access Requests
+///
+/// Allows users with `ROLE_USER` to list their own access requests and the pending requests on the keys of their clusters, waiting for their approval.
+///
+/// ## Roles
+///
+/// - `ROLE_USER`
+///
+#[openapi(tag = "Access_Requests")]
+#[get("/access_requests")]
+pub async fn get_all(
+    authorised: Security,
+    pool: &rocket::State<DbPool>,
+) -> Result<Json<Vec<AccessRequestOutput>>, CustomError> {
+    let pool = pool.inner().to_owned();
+    let access_request_repository: AccessRequestRepository = AccessRequestContract::new(&pool);
+    let access_request_service = AccessRequestService::new(access_request_repository);
+    match authorised.check_roles(Role::USER) {
+        false => Err(ErrorObject::create(Status::Unauthorized, None)),
+        true => Ok(Json(
+            access_request_service
+                .get_all(&authorised.user)
+                .into_iter()
+                .map(|(access_request, approvals)| AccessRequestOutput::new(access_request, approvals))
+                .collect(),
+        )),
+    }
+}
+
+/// # Get Access Request
+///
+/// Allows users with `ROLE_USER` to retrieve an access request by its ID. A request is visible by its requester and by the members of the clusters of its key.
+///
+/// ## Roles
+///
+/// - `ROLE_USER`
+///
+/// ## Parameters
+///
+/// - `access_request_id`: A string representing the UUID of the access request.
+///
+#[openapi(tag = "Access_Requests")]
+#[get("/access_requests/<access_request_id>")]
+pub async fn get_by_id(
+    authorised: Security,
+    pool: &rocket::State<DbPool>,
+    access_request_id: &str,
+) -> Result<Json<AccessRequestOutput>, CustomError> {
+    let pool = pool.inner().to_owned();
+    let access_request_repository: AccessRequestRepository = AccessRequestContract::new(&pool);
+    let access_request_service = AccessRequestService::new(access_request_repository);
+    let access_request_uuid = match Uuid::parse_str(access_request_id) {
+        Err(_) => {
+            return Err(ErrorObject::create(
+                Status::BadRequest,
+                Some("Bad Access request uuid"),
+            ))
+        }
+        Ok(uuid) => uuid,
+    };
+    match authorised.check_roles(Role::USER) {
+        false => Err(ErrorObject::create(Status::Unauthorized, None)),
+        true => match access_request_service.get_by_id(access_request_uuid, &authorised.user) {
+            Ok((access_request, approvals)) => {
+                Ok(Json(AccessRequestOutput::new(access_request, approvals)))
+            }
+            Err((status, msg)) => Err(ErrorObject::create(status, msg)),
+        },
+    }
+}
+
+/// # Approve Access Request
+///
+/// Allows users with `ROLE_USER` who are members of a cluster of the key to approve a pending access request. The requester can not approve their own request.
+/// The request is approved once it reaches its required number of approvals, the requester can then retrieve the key once before the request expires.
+///
+/// ## Roles
+///
+/// - `ROLE_USER`
+///
+/// ## Parameters
+///
+/// - `access_request_id`: A string representing the UUID of the access request.
+///
+#[openapi(tag = "Access_Requests")]
+#[post("/access_requests/<access_request_id>/approve")]
+pub async fn approve(
+    authorised: Security,
+    pool: &rocket::State<DbPool>,
+    access_request_id: &str,
+) -> Result<Json<AccessRequestOutput>, CustomError> {
+    let pool = pool.inner().to_owned();
+    let access_request_repository: AccessRequestRepository = AccessRequestContract::new(&pool);
+    let access_request_service = AccessRequestService::new(access_request_repository);
+    let access_request_uuid = match Uuid::parse_str(access_request_id) {
+        Err(_) => {
+            return Err(ErrorObject::create(
+                Status::BadRequest,
+                Some("Bad Access request uuid"),
+            ))
+        }
+        Ok(uuid) => uuid,
+    };
+    match authorised.check_roles(Role::USER) {
+        false => Err(ErrorObject::create(Status::Unauthorized, None)),
+        true => match access_request_service.approve(access_request_uuid, &authorised.user) {
+            Ok((access_request, approvals)) => {
+                Ok(Json(AccessRequestOutput::new(access_request, approvals)))
+            }
+            Err((status, msg)) => Err(ErrorObject::create(status, msg)),
+        },
+    }
+}
+
+/// # Reject Access Request
+///
+/// Allows users with `ROLE_USER` who are members of a cluster of the key to reject a pending access request. A single rejection closes the request.
+///
+/// ## Roles
+///
+/// - `ROLE_USER`
+///
+/// ## Parameters
+///
+/// - `access_request_id`: A string representing the UUID of the access request.
+///
+#[openapi(tag = "Access_Requests")]
+#[post("/access_requests/<access_request_id>/reject")]
+pub async fn reject(
+    authorised: Security,
+    pool: &rocket::State<DbPool>,
+    access_request_id: &str,
+) -> Result<Json<AccessRequestOutput>, CustomError> {
+    let pool = pool.inner().to_owned();
+    let access_request_repository: AccessRequestRepository = AccessRequestContract::new(&pool);
+    let access_request_service = AccessRequestService::new(access_request_repository);
+    let access_request_uuid = match Uuid::parse_str(access_request_id) {
+        Err(_) => {
+            return Err(ErrorObject::create(
+                Status::BadRequest,
+                Some("Bad Access request uuid"),
+            ))
+        }
+        Ok(uuid) => uuid,
+    };
+    match authorised.check_roles(Role::USER) {
+        false => Err(ErrorObject::create(Status::Unauthorized, None)),
+        true => match access_request_service.reject(access_request_uuid, &authorised.user) {
+            Ok((access_request, approvals)) => {
+                Ok(Json(AccessRequestOutput::new(access_request, approvals)))
+            }
+            Err((status, msg)) => Err(ErrorObject::create(status, msg)),
+        },
+    }
+}
@@ -1,20 +1,28 @@
 use crate::core::nodes_config::NodesConfig;
+use crate::dto::break_glass::break_glass_input::BreakGlassInput;
+use crate::dto::access_request::dual_control_input::DualControlInput;
 use crate::dto::anonymous_sentinel::anonymous_sentinel_output::AnonymousSentinelOutput;
 use crate::dto::anonymous_sentinel::anonymous_sentinel_public_input::AnonymousSentinelPublicInput;
 use crate::dto::anonymous_sentinel::anonymous_sentinel_public_output::AnonymousSentinelPublicOutput;
+use crate::dto::key_policy::key_policy_input::KeyPolicyInput;
+use crate::dto::key_policy::key_policy_output::KeyPolicyOutput;
 use crate::dto::sentinel::sentinel_input::SentinelInput;
+use crate::enums::key_operation::KeyOperation;
 use crate::enums::roles::Role;
+use crate::guards::key_encryption_key::KeyEncryptionKey;
+use crate::guards::scoped_security::ScopedSecurity;
 use crate::guards::security::Security;
 use crate::repositories::application::ApplicationRepository;
 use crate::services::anonymous_sentinel::AnonymousSentinelService;
 use crate::services::sentinel_log::SentinelLogService;
 use crate::traits::application::ApplicationContract;
+use crate::utils::key_encoding::KeyEncoding;
 use rocket::http::Status;
 use rocket::post;
 use rocket::serde::json::Json;
 use rocket::tokio::spawn;
 use rocket_okapi::openapi;
-use std::net::SocketAddr;
+use crate::guards::client_ip::ClientIp;
 use uuid::Uuid;
 
 use crate::core::errors::{CustomError, ErrorObject};
@@ -96,12 +104,15 @@ pub async fn create_public(
 ///
 /// - `anonymous_sentinel_id`: A string representing the UUID of the Anonymous sentinel to be retrieved.
 ///
+/// - `format`: The export format of the public key: `hex` (default), `raw-base64` or `pem` (SubjectPublicKeyInfo with the ML-KEM OID, ML-KEM keys only).
+///
 #[openapi(tag = "Anonymous_Sentinels")]
-#[get("/anonymous_sentinels/public/<anonymous_sentinel_id>")]
+#[get("/anonymous_sentinels/public/<anonymous_sentinel_id>?<format>")]
 pub async fn get_public_by_id(
     pool: &rocket::State<DbPool>,
     nodes_config: &rocket::State<NodesConfig>,
     anonymous_sentinel_id: &str,
+    format: Option<&str>,
 ) -> Result<Json<AnonymousSentinelPublicOutput>, CustomError> {
     let pool = pool.inner().to_owned();
     let nodes_config = nodes_config.inner().to_owned();
@@ -116,15 +127,28 @@ pub async fn get_public_by_id(
         }
         Ok(uuid) => uuid,
     };
+    let format = match KeyEncoding::export_format(format, &None) {
+        Err(e) => return Err(ErrorObject::create(Status::BadRequest, Some(e))),
+        Ok(format) => format,
+    };
     match sentinel_service.get_public(sentinel_uuid) {
         Err((status, msg)) => Err(ErrorObject::create(status, msg)),
-        Ok(anonymous_sentinel) => Ok(Json(AnonymousSentinelPublicOutput::new(anonymous_sentinel))),
+        Ok(anonymous_sentinel) => {
+            match AnonymousSentinelPublicOutput::formatted(anonymous_sentinel, &format) {
+                Err(e) => Err(ErrorObject::create(Status::BadRequest, Some(e))),
+                Ok(output) => Ok(Json(output)),
+            }
+        }
     }
 }
 
 /// # Get Anonymous Sentinel
 ///
 /// Allows users with `ROLE_USER` to retrieve a Anonymous sentinel by its ID. The user must be authenticated and authorized to perform this action.
+/// Only the creator and the members of the clusters of the Anonymous sentinel can read it, the other keys of the application are read through break-glass.
+///
+/// When the Anonymous sentinel is under dual control, the first call opens an access request and answers `202 Accepted`; the secret key is released once, after the request is approved by enough members of its clusters.
+/// A scoped token is accepted when its scope allows `read` on the anonymous sentinel.
 ///
 /// ## Roles
 ///
@@ -134,14 +158,18 @@ pub async fn get_public_by_id(
 ///
 /// - `anonymous_sentinel_id`: A string representing the UUID of the Anonymous sentinel to be retrieved.
 ///
+/// - `format`: The export format of the secret key: `hex` (default), `jwk`, `raw-base64` or `wrapped` (RFC 3394 key wrap under the hex key-encryption key given in the `X-Key-Encryption-Key` header).
+///
 #[openapi(tag = "Anonymous_Sentinels")]
-#[get("/anonymous_sentinels/<anonymous_sentinel_id>")]
+#[get("/anonymous_sentinels/<anonymous_sentinel_id>?<format>")]
 pub async fn get_by_id(
-    authorised: Security,
+    authorised: ScopedSecurity,
+    key_encryption_key: KeyEncryptionKey,
     pool: &rocket::State<DbPool>,
     nodes_config: &rocket::State<NodesConfig>,
     anonymous_sentinel_id: &str,
-    addr: SocketAddr,
+    format: Option<&str>,
+    addr: ClientIp,
 ) -> Result<Json<AnonymousSentinelOutput>, CustomError> {
     let pool = pool.inner().to_owned();
     let nodes_config = nodes_config.inner().to_owned();
@@ -156,6 +184,10 @@ pub async fn get_by_id(
         }
         Ok(uuid) => uuid,
     };
+    let format = match KeyEncoding::export_format(format, &key_encryption_key.key) {
+        Err(e) => return Err(ErrorObject::create(Status::BadRequest, Some(e))),
+        Ok(format) => format,
+    };
     match authorised.check_roles(Role::USER) {
         false => {
             let anonymous_sentinel_id = anonymous_sentinel_id.to_string();
@@ -170,7 +202,10 @@ pub async fn get_by_id(
             });
             Err(ErrorObject::create(Status::Unauthorized, None))
         }
-        true => match sentinel_service.get_by_id(sentinel_uuid, authorised.user.clone()) {
+        true => match sentinel_service
+            .check_scope(authorised.scope.as_ref(), &sentinel_uuid, KeyOperation::Read)
+            .and_then(|_| sentinel_service.get_by_id(sentinel_uuid, authorised.user.clone(), &addr.ip()))
+        {
             Err((status, msg)) => {
                 let sentinel_uuid = sentinel_uuid.to_string();
                 spawn(async move {
@@ -195,9 +230,15 @@ pub async fn get_by_id(
                     )
                     .await;
                 });
-                let res = Json(AnonymousSentinelOutput::new(anonymous_sentinel, secret_key));
-
-                Ok(res)
+                match AnonymousSentinelOutput::formatted(
+                    anonymous_sentinel,
+                    secret_key,
+                    &format,
+                    key_encryption_key.key,
+                ) {
+                    Err(e) => Err(ErrorObject::create(Status::BadRequest, Some(e))),
+                    Ok(output) => Ok(Json(output)),
+                }
             }
         },
     }
@@ -222,6 +263,7 @@ pub async fn delete_by_id(
     pool: &rocket::State<DbPool>,
     nodes_config: &rocket::State<NodesConfig>,
     anonymous_sentinel_id: &str,
+    addr: ClientIp,
 ) -> Result<Status, CustomError> {
     let pool = pool.inner().to_owned();
     let nodes_config = nodes_config.inner().to_owned();
@@ -242,6 +284,352 @@ pub async fn delete_by_id(
             sentinel_uuid,
             authorised.clone().user,
             authorised.check_roles(Role::ADMIN),
+            &addr.ip(),
+        ) {
+            Ok(()) => Ok(Status::NoContent),
+            Err((status, msg)) => Err(ErrorObject::create(status, msg)),
+        },
+    }
+}
+
+/// # Get the Policy of an Anonymous Sentinel
+///
+/// Allows users with `ROLE_USER` to get the usage policy of an Anonymous sentinel.
+///
+/// ## Roles
+///
+/// - `ROLE_USER`
+///
+/// ## Parameters
+///
+/// - `anonymous_sentinel_id`: A string representing the UUID of the Anonymous sentinel.
+///
+#[openapi(tag = "Anonymous_Sentinels")]
+#[get("/anonymous_sentinels/<anonymous_sentinel_id>/policy")]
+pub async fn get_policy(
+    authorised: Security,
+    pool: &rocket::State<DbPool>,
+    nodes_config: &rocket::State<NodesConfig>,
+    anonymous_sentinel_id: &str,
+) -> Result<Json<KeyPolicyOutput>, CustomError> {
+    let pool = pool.inner().to_owned();
+    let nodes_config = nodes_config.inner().to_owned();
+    let application_repository: ApplicationRepository = ApplicationContract::new(&pool);
+    let sentinel_service = AnonymousSentinelService::new(&pool, application_repository, &nodes_config);
+    let sentinel_uuid = match Uuid::parse_str(anonymous_sentinel_id) {
+        Err(_) => {
+            return Err(ErrorObject::create(
+                Status::BadRequest,
+                Some("Bad Sentinel uuid"),
+            ))
+        }
+        Ok(uuid) => uuid,
+    };
+    match authorised.check_roles(Role::USER) {
+        false => Err(ErrorObject::create(Status::Unauthorized, None)),
+        true => match sentinel_service.get_policy(sentinel_uuid, authorised.user) {
+            Ok(policy) => Ok(Json(KeyPolicyOutput::new(policy))),
+            Err((status, msg)) => Err(ErrorObject::create(status, msg)),
+        },
+    }
+}
+
+/// # Set the Policy of an Anonymous Sentinel
+///
+/// Allows the creator of an Anonymous sentinel, or an admin, to replace its usage policy. Only an admin can relax an existing policy, by allowing more operations, ranges, days or hours, or more reads. The policy is checked before the fragments of the secret key are fetched.
+///
+/// ## Roles
+///
+/// - `ROLE_USER`
+///
+/// - `ROLE_ADMIN`
+///
+/// ## Parameters
+///
+/// - `anonymous_sentinel_id`: A string representing the UUID of the Anonymous sentinel.
+///
+/// - `operations`: An optional array of the allowed operations, `read` and `delete` (all by default).
+///
+/// - `allowed_cidrs`: An optional array of IPv4 or IPv6 CIDR ranges the key can be used from (any address when empty).
+///
+/// - `allowed_days`: An optional array of the allowed days, from 1 (monday) to 7 (sunday), in UTC (every day when empty).
+///
+/// - `hour_start` / `hour_end`: Optional numbers representing the allowed UTC hours, `hour_end` is excluded. The window wraps around midnight when `hour_start` is greater than `hour_end`.
+///
+/// - `max_reads`: An optional number representing the maximum number of reads of the secret key.
+///
+#[openapi(tag = "Anonymous_Sentinels")]
+#[put(
+    "/anonymous_sentinels/<anonymous_sentinel_id>/policy",
+    format = "json",
+    data = "<key_policy_input>"
+)]
+pub async fn set_policy(
+    authorised: Security,
+    pool: &rocket::State<DbPool>,
+    nodes_config: &rocket::State<NodesConfig>,
+    anonymous_sentinel_id: &str,
+    key_policy_input: Json<KeyPolicyInput>,
+) -> Result<Json<KeyPolicyOutput>, CustomError> {
+    let pool = pool.inner().to_owned();
+    let nodes_config = nodes_config.inner().to_owned();
+    let input = key_policy_input.into_inner();
+    let application_repository: ApplicationRepository = ApplicationContract::new(&pool);
+    let sentinel_service = AnonymousSentinelService::new(&pool, application_repository, &nodes_config);
+    let sentinel_uuid = match Uuid::parse_str(anonymous_sentinel_id) {
+        Err(_) => {
+            return Err(ErrorObject::create(
+                Status::BadRequest,
+                Some("Bad Sentinel uuid"),
+            ))
+        }
+        Ok(uuid) => uuid,
+    };
+    match authorised.check_roles(Role::USER) || authorised.check_roles(Role::ADMIN) {
+        false => Err(ErrorObject::create(Status::Unauthorized, None)),
+        true => match sentinel_service.set_policy(
+            sentinel_uuid,
+            input,
+            authorised.clone().user,
+            authorised.check_roles(Role::ADMIN),
+        ) {
+            Ok(policy) => Ok(Json(KeyPolicyOutput::new(policy))),
+            Err((status, msg)) => Err(ErrorObject::create(status, msg)),
+        },
+    }
+}
+
+/// # Delete the Policy of an Anonymous Sentinel
+///
+/// Allows an admin to remove the usage policy of an Anonymous sentinel.
+///
+/// ## Roles
+///
+/// - `ROLE_ADMIN`
+///
+/// ## Parameters
+///
+/// - `anonymous_sentinel_id`: A string representing the UUID of the Anonymous sentinel.
+///
+#[openapi(tag = "Anonymous_Sentinels")]
+#[delete("/anonymous_sentinels/<anonymous_sentinel_id>/policy")]
+pub async fn delete_policy(
+    authorised: Security,
+    pool: &rocket::State<DbPool>,
+    nodes_config: &rocket::State<NodesConfig>,
+    anonymous_sentinel_id: &str,
+) -> Result<Status, CustomError> {
+    let pool = pool.inner().to_owned();
+    let nodes_config = nodes_config.inner().to_owned();
+    let application_repository: ApplicationRepository = ApplicationContract::new(&pool);
+    let sentinel_service = AnonymousSentinelService::new(&pool, application_repository, &nodes_config);
+    let sentinel_uuid = match Uuid::parse_str(anonymous_sentinel_id) {
+        Err(_) => {
+            return Err(ErrorObject::create(
+                Status::BadRequest,
+                Some("Bad Sentinel uuid"),
+            ))
+        }
+        Ok(uuid) => uuid,
+    };
+    match authorised.check_roles(Role::USER) || authorised.check_roles(Role::ADMIN) {
+        false => Err(ErrorObject::create(Status::Unauthorized, None)),
+        true => match sentinel_service.delete_policy(
+            sentinel_uuid,
+            authorised.clone().user,
+            authorised.check_roles(Role::ADMIN),
+        ) {
+            Ok(()) => Ok(Status::NoContent),
+            Err((status, msg)) => Err(ErrorObject::create(status, msg)),
+        },
+    }
+}
+
+/// # Set the Dual Control of an Anonymous Sentinel
+///
+/// Allows the creator of an Anonymous sentinel, or an admin, to set the number of approvals needed to release its secret key. Only an admin can lower it.
+///
+/// ## Roles
+///
+/// - `ROLE_USER`
+///
+/// - `ROLE_ADMIN`
+///
+/// ## Parameters
+///
+/// - `anonymous_sentinel_id`: A string representing the UUID of the Anonymous sentinel.
+///
+/// - `required_approvals`: A number representing the approvals needed from other members of the clusters of the Anonymous sentinel (`0` disables the dual control).
+///
+#[openapi(tag = "Anonymous_Sentinels")]
+#[put(
+    "/anonymous_sentinels/<anonymous_sentinel_id>/dual_control",
+    format = "json",
+    data = "<dual_control_input>"
+)]
+pub async fn set_dual_control(
+    authorised: Security,
+    pool: &rocket::State<DbPool>,
+    nodes_config: &rocket::State<NodesConfig>,
+    anonymous_sentinel_id: &str,
+    dual_control_input: Json<DualControlInput>,
+) -> Result<Status, CustomError> {
+    let pool = pool.inner().to_owned();
+    let nodes_config = nodes_config.inner().to_owned();
+    let input = dual_control_input.into_inner();
+    let application_repository: ApplicationRepository = ApplicationContract::new(&pool);
+    let anonymous_sentinel_service =
+        AnonymousSentinelService::new(&pool, application_repository, &nodes_config);
+    let anonymous_sentinel_uuid = match Uuid::parse_str(anonymous_sentinel_id) {
+        Err(_) => {
+            return Err(ErrorObject::create(
+                Status::BadRequest,
+                Some("Bad Sentinel uuid"),
+            ))
+        }
+        Ok(uuid) => uuid,
+    };
+    match authorised.check_roles(Role::USER) || authorised.check_roles(Role::ADMIN) {
+        false => Err(ErrorObject::create(Status::Unauthorized, None)),
+        true => match anonymous_sentinel_service.set_dual_control(
+            anonymous_sentinel_uuid,
+            input,
+            authorised.clone().user,
+            authorised.check_roles(Role::ADMIN),
+        ) {
+            Ok(_) => Ok(Status::NoContent),
+            Err((status, msg)) => Err(ErrorObject::create(status, msg)),
+        },
+    }
+}
+
+/// # Break-Glass Read of an Anonymous Sentinel
+///
+/// Allows users with `ROLE_BREAK_GLASS` to read the secret key of any Anonymous sentinel of their application, outside of its clusters, in an emergency.
+/// A written justification is mandatory. Every read writes a high severity audit record, emails every admin of the application and flags the Anonymous sentinel until an admin acknowledges it.
+/// The dual control and the usage policy of the Anonymous sentinel still apply.
+///
+/// ## Roles
+///
+/// - `ROLE_BREAK_GLASS`
+///
+/// ## Parameters
+///
+/// - `anonymous_sentinel_id`: A string representing the UUID of the Anonymous sentinel.
+///
+/// - `justification`: A string representing the reason of the access, at least 10 characters.
+///
+/// - `format`: The export format of the secret key: `hex` (default), `jwk`, `raw-base64` or `wrapped` (RFC 3394 key wrap under the hex key-encryption key given in the `X-Key-Encryption-Key` header).
+///
+#[openapi(tag = "Anonymous_Sentinels")]
+#[allow(clippy::too_many_arguments)]
+#[post(
+    "/anonymous_sentinels/<anonymous_sentinel_id>/break_glass?<format>",
+    format = "json",
+    data = "<break_glass_input>"
+)]
+pub async fn break_glass(
+    authorised: Security,
+    key_encryption_key: KeyEncryptionKey,
+    pool: &rocket::State<DbPool>,
+    nodes_config: &rocket::State<NodesConfig>,
+    anonymous_sentinel_id: &str,
+    format: Option<&str>,
+    break_glass_input: Json<BreakGlassInput>,
+    addr: ClientIp,
+) -> Result<Json<AnonymousSentinelOutput>, CustomError> {
+    let pool = pool.inner().to_owned();
+    let nodes_config = nodes_config.inner().to_owned();
+    let input = break_glass_input.into_inner();
+    let application_repository: ApplicationRepository = ApplicationContract::new(&pool);
+    let sentinel_service = AnonymousSentinelService::new(&pool, application_repository, &nodes_config);
+    let sentinel_uuid = match Uuid::parse_str(anonymous_sentinel_id) {
+        Err(_) => {
+            return Err(ErrorObject::create(
+                Status::BadRequest,
+                Some("Bad Sentinel uuid"),
+            ))
+        }
+        Ok(uuid) => uuid,
+    };
+    let format = match KeyEncoding::export_format(format, &key_encryption_key.key) {
+        Err(e) => return Err(ErrorObject::create(Status::BadRequest, Some(e))),
+        Ok(format) => format,
+    };
+    if !authorised.check_roles(Role::BREAKGLASS) {
+        return Err(ErrorObject::create(Status::Unauthorized, None));
+    }
+    let res = sentinel_service.break_glass(
+        sentinel_uuid,
+        &input.justification,
+        authorised.user.clone(),
+        &addr.ip(),
+    );
+    let anonymous_sentinel_id = anonymous_sentinel_id.to_string();
+    let success = res.is_ok();
+    spawn(async move {
+        let _ = SentinelLogService::new_sentinel_log(
+            anonymous_sentinel_id,
+            &authorised.user,
+            success,
+            &addr.ip().to_string(),
+        )
+        .await;
+    });
+    match res {
+        Err((status, msg)) => Err(ErrorObject::create(status, msg)),
+        Ok((anonymous_sentinel, secret_key)) => match AnonymousSentinelOutput::formatted(
+            anonymous_sentinel,
+            secret_key,
+            &format,
+            key_encryption_key.key,
+        ) {
+            Err(e) => Err(ErrorObject::create(Status::BadRequest, Some(e))),
+            Ok(output) => Ok(Json(output)),
+        },
+    }
+}
+
+/// # Acknowledge a Break-Glass Read of an Anonymous Sentinel
+///
+/// Allows users with `ROLE_ADMIN` to clear the break-glass flag of an Anonymous sentinel of their application once the access was reviewed. The acknowledgement is audited.
+///
+/// ## Roles
+///
+/// - `ROLE_ADMIN`
+///
+/// ## Parameters
+///
+/// - `anonymous_sentinel_id`: A string representing the UUID of the Anonymous sentinel.
+///
+#[openapi(tag = "Anonymous_Sentinels")]
+#[post("/anonymous_sentinels/<anonymous_sentinel_id>/break_glass/acknowledge")]
+pub async fn acknowledge_break_glass(
+    authorised: Security,
+    pool: &rocket::State<DbPool>,
+    nodes_config: &rocket::State<NodesConfig>,
+    anonymous_sentinel_id: &str,
+    addr: ClientIp,
+) -> Result<Status, CustomError> {
+    let pool = pool.inner().to_owned();
+    let nodes_config = nodes_config.inner().to_owned();
+    let application_repository: ApplicationRepository = ApplicationContract::new(&pool);
+    let sentinel_service = AnonymousSentinelService::new(&pool, application_repository, &nodes_config);
+    let sentinel_uuid = match Uuid::parse_str(anonymous_sentinel_id) {
+        Err(_) => {
+            return Err(ErrorObject::create(
+                Status::BadRequest,
+                Some("Bad Sentinel uuid"),
+            ))
+        }
+        Ok(uuid) => uuid,
+    };
+    match authorised.check_roles(Role::ADMIN) {
+        false => Err(ErrorObject::create(Status::Unauthorized, None)),
+        true => match sentinel_service.acknowledge_break_glass(
+            sentinel_uuid,
+            authorised.user,
+            &addr.ip(),
         ) {
             Ok(()) => Ok(Status::NoContent),
             Err((status, msg)) => Err(ErrorObject::create(status, msg)),
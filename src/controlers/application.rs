@@ -99,6 +99,8 @@ pub async fn delete_application(
 ///
 /// - `contact_email`: An optionnal String represanting the Updated Application contact email
 ///
+/// - `allowed_ips`: An optional array of IPv4 / IPv6 addresses or CIDR ranges the users of the application connect from, an empty array allows every IP
+///
 #[openapi(tag = "Applications")]
 #[put("/applications", format = "json", data = "<application_update_input>")]
 pub async fn update_application(
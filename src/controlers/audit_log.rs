@@ -0,0 +1,42 @@
+use crate::dto::audit_log::audit_log_output::AuditLogOutput;
+use crate::enums::roles::Role;
+use crate::guards::security::Security;
+use crate::services::audit::AuditService;
+use rocket::http::Status;
+use rocket::serde::json::Json;
+use rocket_okapi::openapi;
+
+use crate::core::errors::{CustomError, ErrorObject};
+use crate::db::connect::DbPool;
+
+/// # List Audit Logs
+///
+/// Allows users with `ROLE_ADMIN` to list the latest audit records of their application, newest first.
+///
+/// ## Roles
+///
+/// - `ROLE_ADMIN`
+///
+/// ## Parameters
+///
+/// - `severity`: An optional string to keep only the records of a severity, `low`, `medium` or `high`.
+///
+#[openapi(tag = "Audit_Logs")]
+#[get("/audit_logs?<severity>")]
+pub async fn get_all(
+    authorised: Security,
+    pool: &rocket::State<DbPool>,
+    severity: Option<String>,
+) -> Result<Json<Vec<AuditLogOutput>>, CustomError> {
+    let pool = pool.inner().to_owned();
+    let audit_service = AuditService::new(&pool);
+    match authorised.check_roles(Role::ADMIN) {
+        false => Err(ErrorObject::create(Status::Unauthorized, None)),
+        true => match audit_service.get_all(&authorised.user, severity) {
+            Ok(audit_logs) => Ok(Json(
+                audit_logs.into_iter().map(AuditLogOutput::new).collect(),
+            )),
+            Err((status, msg)) => Err(ErrorObject::create(status, msg)),
+        },
+    }
+}
@@ -1,13 +1,18 @@
+use rocket::http::Status;
 use rocket::post;
 use rocket::response::status::Custom;
 use rocket::serde::json::Json;
 use rocket_okapi::openapi;
-use std::net::SocketAddr;
+use crate::guards::client_ip::ClientIp;
+use crate::guards::dpop::DpopHeader;
 
 use crate::core::errors::{CustomError, ErrorObject};
 use crate::db::connect::DbPool;
 use crate::dto::auth::auth_refresh_input::AuthRefreshInput;
 use crate::dto::auth::{auth_input::AuthInput, auth_output::AuthOutput};
+use crate::dto::auth::{scoped_token_input::ScopedTokenInput, scoped_token_output::ScopedTokenOutput};
+use crate::enums::roles::Role;
+use crate::guards::security::Security;
 use crate::guards::user_agent::UserAgent;
 use crate::repositories::application::ApplicationRepository;
 use crate::repositories::connexion::ConnexionRepository;
@@ -20,12 +25,16 @@ use crate::services::user::UserService;
 use crate::traits::application::ApplicationContract;
 use crate::traits::connexion::ConnexionContract;
 use crate::traits::revoked_token::RevokedTokenContract;
+use crate::utils::jwt::SessionClaims;
+use crate::utils::open_id::Authentication;
 use crate::LICENSE_VALID;
 
 /// # User Authentication Endpoint
 ///
 /// Authenticates a user and returns an `access_token` ,`refresh_token` and `open_id`.
 ///
+/// After `RATE_LIMIT_MAX_ATTEMPTS` wrong passwords or 2FA codes the account is locked, each new lock lasting twice the previous one, and an IP failing `RATE_LIMIT_MAX_ATTEMPTS_PER_IP` times is refused until its window ends. Both answer `429 Too Many Requests`.
+///
 /// ## Roles
 ///
 /// - `PUBLIC`
@@ -40,14 +49,21 @@ use crate::LICENSE_VALID;
 ///
 /// - `fingerprint`: A string representing the user's unique device identifier.
 ///
-/// - `code_2fa`: An optional string representing the 6-digit Time-based One-Time Password (TOTP) if two-factor authentication is enabled.
+/// - `code_2fa`: An optional string representing the 6-digit Time-based One-Time Password (TOTP) if two-factor authentication is enabled, or one of the recovery codes given when it was activated. A code is only accepted once.
+///
+/// - `webauthn`: An optional passkey assertion for a challenge of `POST /auth/webauthn/start`, the second factor in place of `code_2fa`. It is required from an unfamiliar IP or device for the users with a passkey and without TOTP.
+///
+/// ## DPoP
+///
+/// The `DPoP` header carries a proof (RFC 9449) signed by a key pair of the client for `POST <API_URL>/auth`. The tokens are bound to its public key: `token_type` is `DPoP` and every request sends `Authorization: DPoP <access_token>` with a new proof of the key carrying the `ath` hash of the token. The proof is required unless `TOKEN_BINDING` is `legacy`, where the tokens are bound to `fingerprint` and the `User-Agent` and every request sends the `X-FINGERPRINT` and a new `X-NONCE` headers.
 ///
 #[openapi(tag = "Auth", ignore = "user_agent_guard")]
 #[post("/auth", format = "json", data = "<auth_input>")]
 pub async fn login(
     user_agent_guard: UserAgent,
     pool: &rocket::State<DbPool>,
-    addr: SocketAddr,
+    addr: ClientIp,
+    dpop: DpopHeader,
     auth_input: Json<AuthInput>,
 ) -> Result<Json<AuthOutput>, CustomError> {
     let input = auth_input.into_inner();
@@ -73,22 +89,47 @@ pub async fn login(
             let connexion_repository: ConnexionRepository = ConnexionContract::new(&pool);
             let user_service = UserService::new(&pool, application_repository, connexion_repository);
             let auth_service = AuthService::new(&pool, revoked_repository, user_service, connexion_service);
+            let jkt = auth_service
+                .bind_client(&dpop)
+                .map_err(|(status, msg)| ErrorObject::create(status, msg))?;
             match auth_service
-                .check_creds(&input.login, &input.password, &app)
+                .check_creds(&input.login, &input.password, &app, &addr.ip())
                 .await
             {
                 Err(custom) => Err(custom),
                 Ok(user) => {
                     let ip = addr.ip().to_string();
                     let user_agent = user_agent_guard.user_agent.unwrap_or_else(|| format!(""));
+                    let authentication = match input.webauthn.is_some() {
+                        true => Authentication::password_and_passkey(),
+                        false => Authentication::password(&user),
+                    };
                     match auth_service
-                        .check_otp(&user, input.code_2fa, &ip, &input.fingerprint, &user_agent)
+                        .check_otp(
+                            &user,
+                            input.code_2fa,
+                            input.webauthn,
+                            &ip,
+                            &input.fingerprint,
+                            &user_agent,
+                        )
                         .await
                     {
                         Err((status, msg)) => Err(ErrorObject::create(status, msg)),
-                        Ok(()) => {
+                        Ok(connexion) => {
+                            let session = auth_service.open_session(&user, &app, Some(&connexion))?;
                             let creds = auth_service
-                                .generate_creds(&user, &app, &input.fingerprint, &user_agent)
+                                .generate_creds(
+                                    &user,
+                                    &app,
+                                    &input.fingerprint,
+                                    &user_agent,
+                                    &SessionClaims {
+                                        jkt: jkt.as_ref(),
+                                        authentication: &authentication,
+                                        session: &session,
+                                    },
+                                )
                                 .await;
 
                             let license = LicenceService::new().await.is_valid();
@@ -110,6 +151,8 @@ pub async fn login(
 ///
 /// Refresh user Authentication and returns an `access_token` ,`refresh_token` and `open_id`.
 ///
+/// Every refresh replaces the `refresh_token`, the previous one is no longer accepted. Presenting a refresh token already replaced revokes the whole session, its access and refresh tokens, and is audited.
+///
 /// ## Roles
 ///
 /// - `PUBLIC`
@@ -122,12 +165,17 @@ pub async fn login(
 ///
 /// - `fingerprint`: A string representing the user's unique device identifier.
 ///
+/// ## DPoP
+///
+/// The `DPoP` header carries a proof for `POST <API_URL>/auth/refresh`, the new tokens are bound to its key. A refresh token bound to a key is only refreshed with a proof of the same key.
+///
 #[openapi(tag = "Auth", ignore = "user_agent_guard")]
 #[post("/auth/refresh", format = "json", data = "<auth_refresh_input>")]
 pub async fn refresh(
     user_agent_guard: UserAgent,
     pool: &rocket::State<DbPool>,
-    addr: SocketAddr,
+    addr: ClientIp,
+    dpop: DpopHeader,
     auth_refresh_input: Json<AuthRefreshInput>,
 ) -> Result<Json<AuthOutput>, CustomError> {
     let input = auth_refresh_input.into_inner();
@@ -156,9 +204,12 @@ pub async fn refresh(
             let connexion_repository: ConnexionRepository = ConnexionContract::new(&pool);
             let connexion_service = ConnexionService::new(connexion_repository);
 
-            match auth_service.check_refresh_token(&input.refresh_token, &app) {
+            let jkt = auth_service
+                .bind_client(&dpop)
+                .map_err(|(status, msg)| ErrorObject::create(status, msg))?;
+            match auth_service.check_refresh_token(&input.refresh_token, &app, &addr.ip(), jkt.as_ref()) {
                 Err(custom) => Err(custom),
-                Ok(user) => {
+                Ok((user, authentication, session)) => {
                     let ip = addr.ip().to_string();
                     let user_agent = user_agent_guard.user_agent.unwrap();
                     connexion_service.create_connexion(
@@ -168,12 +219,84 @@ pub async fn refresh(
                         &user,
                     );
                     let creds = auth_service
-                        .generate_creds(&user, &app, &input.fingerprint, &user_agent)
+                        .generate_creds(
+                            &user,
+                            &app,
+                            &input.fingerprint,
+                            &user_agent,
+                            &SessionClaims {
+                                jkt: jkt.as_ref(),
+                                authentication: &authentication,
+                                session: &session,
+                            },
+                        )
                         .await;
-                    auth_service.revoke_token(&input.refresh_token);
                     Ok(Json(creds))
                 }
             }
         }
     }
 }
+
+/// # Scoped Token Endpoint
+///
+/// Mints a short-lived access token restricted to a list of sentinels and clusters and to some key operations, for automation.
+/// The token is only accepted by the key operation endpoints (read, HMAC, streaming encryption, delete) on the sentinels of its scope, which the user must still be able to access; every other endpoint refuses it. It is bound to the device and the DPoP key of the session minting it and can not be refreshed.
+///
+/// ## Roles
+///
+/// - `ROLE_USER`
+///
+/// ## Parameters
+///
+/// - `sentinels`: An optional array of strings representing the UUIDs of the sentinels and anonymous sentinels the token can use.
+///
+/// - `clusters`: An optional array of strings representing the UUIDs of the clusters whose sentinels the token can use.
+///
+/// - `operations`: An array of strings representing the allowed operations: `read`, `encrypt`, `decrypt` or `delete`.
+///
+/// - `expires_in`: An optional number representing the validity of the token in seconds (900 by default, at most `SCOPED_TOKEN_MAX_DURATION`).
+///
+#[openapi(tag = "Auth")]
+#[post("/auth/scoped_token", format = "json", data = "<scoped_token_input>")]
+pub async fn scoped_token(
+    authorised: Security,
+    pool: &rocket::State<DbPool>,
+    scoped_token_input: Json<ScopedTokenInput>,
+) -> Result<Json<ScopedTokenOutput>, CustomError> {
+    let input = scoped_token_input.into_inner();
+    let pool = pool.inner().to_owned();
+    if !authorised.check_roles(Role::USER) {
+        return Err(ErrorObject::create(Status::Unauthorized, None));
+    }
+    let application_repository: ApplicationRepository = ApplicationContract::new(&pool);
+    let app_service = ApplicationService::new(application_repository);
+    let app = match authorised.user.application {
+        None => return Err(ErrorObject::create(Status::Unauthorized, None)),
+        Some(application_id) => match app_service.get_application_by_id(application_id).await {
+            Err(status) => return Err(ErrorObject::create(status, Some("Failed to get application"))),
+            Ok(app) => app,
+        },
+    };
+    let revoked_repository: RevokedTokenRepository = RevokedTokenContract::new(&pool);
+    let application_repository: ApplicationRepository = ApplicationContract::new(&pool);
+    let connexion_repository: ConnexionRepository = ConnexionContract::new(&pool);
+    let connexion_service = ConnexionService::new(connexion_repository);
+    let connexion_repository: ConnexionRepository = ConnexionContract::new(&pool);
+    let user_service = UserService::new(&pool, application_repository, connexion_repository);
+    let auth_service = AuthService::new(&pool, revoked_repository, user_service, connexion_service);
+    match auth_service
+        .mint_scoped_token(
+            &authorised.user,
+            &app,
+            authorised.device_id.clone(),
+            authorised.dpop_jkt.clone(),
+            authorised.session_id,
+            input,
+        )
+        .await
+    {
+        Err((status, msg)) => Err(ErrorObject::create(status, msg)),
+        Ok(output) => Ok(Json(output)),
+    }
+}
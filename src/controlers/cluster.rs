@@ -1,7 +1,9 @@
+use crate::dto::access_request::dual_control_input::DualControlInput;
 use crate::dto::cluster::cluster_anonymous_sentinels_input::ClusterAnonymousSentinelsInput;
 use crate::dto::cluster::cluster_input::ClusterInput;
 use crate::dto::cluster::cluster_memberships_input::ClusterMembershipsInput;
 use crate::dto::cluster::cluster_output::ClusterOutput;
+use crate::dto::cluster::cluster_secrets_input::ClusterSecretsInput;
 use crate::dto::cluster::cluster_sentinels_input::ClusterSentinelsInput;
 use crate::dto::list::ListDto;
 use crate::dto::user::user_output::UserOutput;
@@ -199,6 +201,56 @@ pub async fn delete_cluster(
     }
 }
 
+/// # Set the Dual Control of a Cluster
+///
+/// Sets the number of approvals needed to release the keys of the cluster. A key under dual control is released once its access request is approved by enough members of its clusters,
+/// the highest requirement between the key and its clusters applies. Users with `ROLE_ADMIN` can change any cluster within their application,
+/// users with `ROLE_USER` only the clusters they have created, and only an admin can lower it.
+///
+/// ## Roles
+///
+/// - `ROLE_ADMIN`
+///
+/// - `ROLE_USER`
+///
+/// ## Parameters
+///
+/// - `cluster_id`: A string representing the UUID of the cluster.
+///
+/// - `required_approvals`: A number representing the approvals needed (`0` disables the dual control of the cluster).
+///
+#[openapi(tag = "Clusters")]
+#[put(
+    "/clusters/<cluster_id>/dual_control",
+    format = "json",
+    data = "<dual_control_input>"
+)]
+pub async fn set_dual_control(
+    authorised: Security,
+    pool: &rocket::State<DbPool>,
+    dual_control_input: Json<DualControlInput>,
+    cluster_id: &str,
+) -> Result<Json<ClusterOutput>, CustomError> {
+    let cluster_uuid = match Uuid::parse_str(cluster_id) {
+        Err(_) => {
+            return Err(ErrorObject::create(
+                Status::BadRequest,
+                Some("Bad Cluster uuid"),
+            ))
+        }
+        Ok(uuid) => uuid,
+    };
+    let pool = pool.inner().to_owned();
+    let input = dual_control_input.into_inner();
+    let application_repository: ApplicationRepository = ApplicationContract::new(&pool);
+    let connexion_repository: ConnexionRepository = ConnexionContract::new(&pool);
+    let cluster_service = ClusterService::new(&pool, application_repository, connexion_repository);
+    match cluster_service.set_dual_control(cluster_uuid, input, authorised) {
+        Err((status, msg)) => Err(ErrorObject::create(status, msg)),
+        Ok(cluster) => Ok(Json(ClusterOutput::new(cluster))),
+    }
+}
+
 /// # Add Sentinels to a Cluster
 ///
 /// Adds a list of Sentinels to the specified cluster. This operation can be performed by `ROLE_ADMIN` on any cluster within their application,
@@ -393,6 +445,103 @@ pub async fn remove_anonymous_sentinels(
     }
 }
 
+/// # Add Secrets to a Cluster
+///
+/// Shares a list of secrets with the members of the specified cluster. This operation can be performed by `ROLE_ADMIN` on any cluster within their application,
+/// and by `ROLE_USER` on any cluster they have created, with the secrets they have created.
+///
+/// ## Roles
+///
+/// - `ROLE_ADMIN`
+///
+/// - `ROLE_USER`
+///
+/// ## Parameters
+///
+/// - `cluster_id`: A string representing the UUID of the cluster.
+///
+/// - `secrets`: An array of strings representing the UUIDs of the secrets to be added.
+///
+#[openapi(tag = "Clusters")]
+#[put(
+    "/clusters/<cluster_id>/add_secrets",
+    format = "json",
+    data = "<cluster_secrets_input>"
+)]
+pub async fn add_secrets(
+    authorised: Security,
+    pool: &rocket::State<DbPool>,
+    cluster_secrets_input: Json<ClusterSecretsInput>,
+    cluster_id: &str,
+) -> Result<Json<ClusterOutput>, CustomError> {
+    let cluster_uuid = match Uuid::parse_str(cluster_id) {
+        Err(_) => {
+            return Err(ErrorObject::create(
+                Status::BadRequest,
+                Some("Bad Cluster uuid"),
+            ))
+        }
+        Ok(uuid) => uuid,
+    };
+    let pool = pool.inner().to_owned();
+    let input = cluster_secrets_input.into_inner();
+    let application_repository: ApplicationRepository = ApplicationContract::new(&pool);
+    let connexion_repository: ConnexionRepository = ConnexionContract::new(&pool);
+    let cluster_service = ClusterService::new(&pool, application_repository, connexion_repository);
+    match cluster_service.add_secrets(cluster_uuid, input, authorised) {
+        Err((status, msg)) => Err(ErrorObject::create(status, msg)),
+        Ok(cluster) => Ok(Json(ClusterOutput::new(cluster))),
+    }
+}
+
+/// # Remove Secrets from a Cluster
+///
+/// Removes a list of secrets from the specified cluster. This operation can be performed by `ROLE_ADMIN` on any cluster within their application, and by `ROLE_USER` on any cluster they have created.
+///
+/// ## Roles
+///
+/// - `ROLE_ADMIN`
+///
+/// - `ROLE_USER`
+///
+/// ## Parameters
+///
+/// - `cluster_id`: A string representing the UUID of the cluster.
+///
+/// - `secrets`: An array of strings representing the UUIDs of the secrets to be removed.
+///
+#[openapi(tag = "Clusters")]
+#[put(
+    "/clusters/<cluster_id>/remove_secrets",
+    format = "json",
+    data = "<cluster_secrets_input>"
+)]
+pub async fn remove_secrets(
+    authorised: Security,
+    pool: &rocket::State<DbPool>,
+    cluster_secrets_input: Json<ClusterSecretsInput>,
+    cluster_id: &str,
+) -> Result<Json<ClusterOutput>, CustomError> {
+    let cluster_uuid = match Uuid::parse_str(cluster_id) {
+        Err(_) => {
+            return Err(ErrorObject::create(
+                Status::BadRequest,
+                Some("Bad Cluster uuid"),
+            ))
+        }
+        Ok(uuid) => uuid,
+    };
+    let pool = pool.inner().to_owned();
+    let input = cluster_secrets_input.into_inner();
+    let application_repository: ApplicationRepository = ApplicationContract::new(&pool);
+    let connexion_repository: ConnexionRepository = ConnexionContract::new(&pool);
+    let cluster_service = ClusterService::new(&pool, application_repository, connexion_repository);
+    match cluster_service.remove_secrets(cluster_uuid, input, authorised) {
+        Err((status, msg)) => Err(ErrorObject::create(status, msg)),
+        Ok(cluster) => Ok(Json(ClusterOutput::new(cluster))),
+    }
+}
+
 /// # Get Cluster Users
 ///
 /// Retrieves a list of users who are members of the specified cluster. This operation can be performed by `ROLE_ADMIN` on any cluster within their application.
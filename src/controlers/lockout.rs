@@ -0,0 +1,70 @@
+use crate::dto::lockout::locked_account_output::LockedAccountOutput;
+use crate::enums::roles::Role;
+use crate::guards::security::Security;
+use crate::services::lockout::LockoutService;
+use rocket::http::Status;
+use rocket::serde::json::Json;
+use rocket_okapi::openapi;
+use crate::guards::client_ip::ClientIp;
+use uuid::Uuid;
+
+use crate::core::errors::{CustomError, ErrorObject};
+use crate::db::connect::DbPool;
+
+/// # Get Locked Accounts
+///
+/// Lists the users of the application of the admin whose login, 2FA, account validation or password reset is locked after too many failed attempts, with the flow locked and the end of the lock.
+///
+/// ## Roles
+///
+/// - `ROLE_ADMIN`
+///
+#[openapi(tag = "Lockouts")]
+#[get("/users/locked")]
+pub async fn get_locked(
+    authorised: Security,
+    pool: &rocket::State<DbPool>,
+) -> Result<Json<Vec<LockedAccountOutput>>, CustomError> {
+    let pool = pool.inner().to_owned();
+    match authorised.check_roles(Role::ADMIN) {
+        false => Err(ErrorObject::create(Status::Unauthorized, None)),
+        true => match LockoutService::new(&pool).get_locked(&authorised.user) {
+            Err((status, msg)) => Err(ErrorObject::create(status, msg)),
+            Ok(locked) => Ok(Json(locked)),
+        },
+    }
+}
+
+/// # Unlock Account
+///
+/// Lifts every lock of a user of the application of the admin and forgets their failed attempts. The unlock is audited.
+///
+/// ## Roles
+///
+/// - `ROLE_ADMIN`
+///
+/// ## Parameters
+///
+/// - `user_id`: A string representing the UUID of the user to unlock.
+///
+#[openapi(tag = "Lockouts")]
+#[delete("/users/<user_id>/lock")]
+pub async fn unlock(
+    authorised: Security,
+    pool: &rocket::State<DbPool>,
+    user_id: &str,
+    addr: ClientIp,
+) -> Result<Status, CustomError> {
+    let pool = pool.inner().to_owned();
+    let user_uuid = match Uuid::parse_str(user_id) {
+        Err(_) => return Err(ErrorObject::create(Status::BadRequest, Some("Bad user uuid"))),
+        Ok(uuid) => uuid,
+    };
+    match authorised.check_roles(Role::ADMIN) {
+        false => Err(ErrorObject::create(Status::Unauthorized, None)),
+        true => match LockoutService::new(&pool).unlock(&user_uuid, &authorised.user, &addr.ip()) {
+            Err((status, msg)) => Err(ErrorObject::create(status, msg)),
+            Ok(()) => Ok(Status::NoContent),
+        },
+    }
+}
@@ -6,4 +6,12 @@ pub mod oidc;
 pub mod cluster;
 pub mod sentinel;
 pub mod system;
-pub mod anonymous_sentinel;
\ No newline at end of file
+pub mod anonymous_sentinel;
+pub mod secret;
+pub mod access_request;
+pub mod audit_log;
+pub mod share_link;
+pub mod service_account;
+pub mod session;
+pub mod webauthn;
+pub mod lockout;
\ No newline at end of file
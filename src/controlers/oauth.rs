@@ -1,89 +1,109 @@
-use crate::dto::auth::auth_output::AuthOutput;
+use crate::dto::oauth::oauth_access_token_output::OauthAccessTokenOutput;
 use crate::dto::oauth::oauth_authorize_output::OauthAuthorizeOutput;
+use crate::dto::oauth::oauth_client_input::OauthClientInput;
+use crate::dto::oauth::oauth_client_output::OauthClientOutput;
+use crate::dto::oauth::oauth_introspection_output::OauthIntrospectionOutput;
 use crate::dto::oauth::oauth_token_input::OauthTokenInput;
+use crate::dto::oauth::oauth_token_request_input::OauthTokenRequestInput;
 use crate::enums::roles::Role;
+use crate::guards::client_authorization::ClientAuthorization;
 use crate::guards::security::Security;
-use crate::guards::user_agent::UserAgent;
-use crate::repositories::application::ApplicationRepository;
-use crate::repositories::connexion::ConnexionRepository;
-use crate::repositories::revoked_token::RevokedTokenRepository;
-use crate::services::auth::AuthService;
-use crate::services::connexion::ConnexionService;
-use crate::services::user::UserService;
-use crate::traits::application::ApplicationContract;
-use crate::traits::connexion::ConnexionContract;
-use crate::traits::revoked_token::RevokedTokenContract;
+use crate::services::service_account::ServiceAccountService;
+use rocket::form::Form;
 use rocket::http::Status;
 use rocket::post;
+use rocket::response::status::Custom;
 use rocket::serde::json::Json;
 use rocket_okapi::openapi;
-use std::net::SocketAddr;
 use uuid::Uuid;
 
-use crate::core::errors::{CustomError, ErrorObject};
+use crate::core::errors::{CustomError, ErrorObject, OauthError, OauthErrorObject};
 use crate::db::connect::DbPool;
 use crate::services::oauth::OauthService;
+use crate::services::token::{TokenCaller, TokenService};
 
-/// # Requests a temporary authorization code for a user account.
+/// # Requests an authorization code for an OAuth client.
 ///
-/// Initiates the authorization process for a `ROLE_ADMIN` to obtain a temporary authorization code on behalf of a `ROLE_USER`.
+/// Allows an authenticated user to authorize a registered client of their application (RFC 6749 authorization code grant with PKCE, RFC 7636).
 ///
-/// This endpoint assumes that the `ROLE_ADMIN` is already authenticated. The `ROLE_ADMIN` specifies the user account for which the authorization is requested and provides a unique `state` for this session.
+/// The user agent is then sent to the returned `redirect_uri`, the callback of the client carrying the code and the state. The code is single use and expires after 60 seconds.
+/// Unknown clients and unregistered redirect uris are answered with an error and no callback. The other errors come with the callback of the client carrying them.
 ///
 /// ## Roles
 ///
-/// - `ROLE_ADMIN`
+/// - `ROLE_USER`
 ///
 /// ## Parameters
 ///
-/// - `client_id`: A string representing the unique identifier (user id) of the user account for which the authorization is requested.
+/// - `response_type`: Must be `code`.
+///
+/// - `client_id`: A string representing the client id of a registered client.
+///
+/// - `redirect_uri`: One of the redirect uris of the client, optional when the client has only one.
+///
+/// - `state`: An opaque value returned to the client with the code.
+///
+/// - `code_challenge`: The url safe base64 SHA-256 of the code verifier of the client.
 ///
-/// - `state`: A string representing a unique state value to maintain state between the request and callback.
+/// - `code_challenge_method`: Must be `S256`.
+///
+/// - `scope`: The scope requested by the client, returned with the access token. With `openid` an ID token is issued too, with the `profile` and `email` claims allowed by the scope.
+///
+/// - `nonce`: An opaque value returned to the client in the ID token.
 ///
 #[openapi(tag = "Oauth")]
-#[get("/oauth/authorize?<client_id>&<state>")]
+#[allow(clippy::too_many_arguments)]
+#[get("/oauth/authorize?<response_type>&<client_id>&<redirect_uri>&<state>&<code_challenge>&<code_challenge_method>&<scope>&<nonce>")]
 pub async fn authorize(
     authorised: Security,
     pool: &rocket::State<DbPool>,
+    response_type: String,
     client_id: String,
-    state: String,
-) -> Result<Json<OauthAuthorizeOutput>, CustomError> {
+    redirect_uri: Option<String>,
+    state: Option<String>,
+    code_challenge: Option<String>,
+    code_challenge_method: Option<String>,
+    scope: Option<String>,
+    nonce: Option<String>,
+) -> Result<Json<OauthAuthorizeOutput>, OauthError> {
     let pool = pool.inner().to_owned();
-    match authorised.check_roles(Role::ADMIN) {
-        false => Err(ErrorObject::create(Status::Forbidden, None)),
-        true => {
-            let user_to_uuid = match Uuid::parse_str(&client_id) {
-                Err(_) => {
-                    return Err(ErrorObject::create(
-                        Status::BadRequest,
-                        Some("Bad user uuid"),
-                    ))
-                }
-                Ok(uuid) => uuid,
-            };
-            let revoked_repository: RevokedTokenRepository = RevokedTokenContract::new(&pool);
-            let application_repository: ApplicationRepository = ApplicationContract::new(&pool);
-            let connexion_repository: ConnexionRepository = ConnexionContract::new(&pool);
-            let user_service =
-                UserService::new(&pool, application_repository, connexion_repository);
-            let connexion_repository: ConnexionRepository = ConnexionContract::new(&pool);
-            let connexion_service = ConnexionService::new(connexion_repository);
-            let auth_service =
-                AuthService::new(&pool, revoked_repository, user_service, connexion_service);
-            let application_repository: ApplicationRepository = ApplicationContract::new(&pool);
-            let oauth_service = OauthService::new(&pool, application_repository, auth_service);
-
-            match oauth_service.authorize(authorised.user, user_to_uuid, state) {
-                Err((status, msg)) => Err(ErrorObject::create(status, msg)),
-                Ok(authorize_code) => Ok(Json(OauthAuthorizeOutput::new(authorize_code))),
-            }
-        }
+    // only the user can authorize a client, not a machine or another client
+    if !authorised.check_roles(Role::USER)
+        || authorised.check_roles(Role::SERVICEACCOUNT)
+        || authorised.oauth_client_id.is_some()
+    {
+        return Err(OauthErrorObject::create(Status::Forbidden, "access_denied", None));
+    }
+    let oauth_service = OauthService::new(&pool);
+    match oauth_service.authorize(
+        &authorised.user,
+        &response_type,
+        &client_id,
+        redirect_uri,
+        state,
+        code_challenge,
+        code_challenge_method,
+        scope,
+        nonce,
+        &authorised.authentication,
+    ) {
+        Ok(output) => Ok(Json(output)),
+        Err(((status, error, description), redirect_uri)) => Err(Custom(
+            status,
+            Json(OauthErrorObject::new(error, description, redirect_uri)),
+        )),
     }
 }
 
-/// # Exchanges authorization code for an access token.
+/// # Exchanges a grant for an access token.
+///
+/// The token endpoint of the OAuth clients and service accounts (RFC 6749), it accepts JSON and `application/x-www-form-urlencoded` bodies.
+///
+/// - `authorization_code`: a registered client exchanges a code from `/oauth/authorize` with its PKCE verifier. A confidential client authenticates with its secret.
 ///
-/// This endpoint allows `ROLE_USER` to exchange the authorization code, obtained by `ROLE_ADMIN`, for an access token. The request must include the original state value for validation.
+/// - `client_credentials`: a service account authenticates with its secret, or with a `private_key_jwt` assertion signed by its private key for the audience `<API_URL>/oauth/token`.
+///
+/// The client secret is sent in the body or with HTTP Basic authentication. Errors follow RFC 6749 section 5.2.
 ///
 /// ## Roles
 ///
@@ -91,47 +111,394 @@ pub async fn authorize(
 ///
 /// ## Parameters
 ///
-/// - `authorization_code`: A string given by the server to exchange to an lagertha access token .
+/// - `grant_type`: `authorization_code` or `client_credentials`
+///
+/// - `code`: The authorization code (authorization code)
 ///
-/// - `state`: A string representing a unique state value to maintain state between the request and callback (provided by server).
+/// - `redirect_uri`: The redirect uri the code was sent to (authorization code)
 ///
-/// - `fingerprint`: A unique device authentifier
+/// - `code_verifier`: The PKCE verifier of the code challenge (authorization code)
 ///
-/// - `code_2fa`: If MFA is acticated, provide the 6 number T-otp code
+/// - `client_id`: The client id of the client or of the service account
 ///
-#[openapi(tag = "Oauth", ignore = "user_agent_guard")]
+/// - `client_secret`: The secret of the client or of the service account
+///
+/// - `client_assertion_type`: `urn:ietf:params:oauth:client-assertion-type:jwt-bearer` (client credentials)
+///
+/// - `client_assertion`: The JWT signed by the service account, valid 5 minutes at most and used once (client credentials)
+///
+#[openapi(tag = "Oauth", ignore = "client_authorization")]
 #[post("/oauth/token", format = "json", data = "<oauth_token_input>")]
 pub async fn token(
-    user_agent_guard: UserAgent,
-    addr: SocketAddr,
+    client_authorization: ClientAuthorization,
     pool: &rocket::State<DbPool>,
     oauth_token_input: Json<OauthTokenInput>,
-) -> Result<Json<AuthOutput>, CustomError> {
+) -> Result<Json<OauthAccessTokenOutput>, OauthError> {
+    let pool = pool.inner().to_owned();
+    grant(&pool, oauth_token_input.into_inner(), client_authorization).await
+}
+
+/// the token endpoint with a form body, as sent by the OAuth libraries
+#[post("/oauth/token", format = "form", data = "<oauth_token_input>", rank = 2)]
+pub async fn token_form(
+    client_authorization: ClientAuthorization,
+    pool: &rocket::State<DbPool>,
+    oauth_token_input: Form<OauthTokenInput>,
+) -> Result<Json<OauthAccessTokenOutput>, OauthError> {
+    let pool = pool.inner().to_owned();
+    grant(&pool, oauth_token_input.into_inner(), client_authorization).await
+}
+
+async fn grant(
+    pool: &DbPool,
+    mut input: OauthTokenInput,
+    client_authorization: ClientAuthorization,
+) -> Result<Json<OauthAccessTokenOutput>, OauthError> {
+    merge_client_authorization(
+        &mut input.client_id,
+        &mut input.client_secret,
+        client_authorization,
+    )?;
+    let result = match input.grant_type.as_str() {
+        "authorization_code" => OauthService::new(pool).exchange_code(input).await,
+        "client_credentials" => {
+            ServiceAccountService::new(pool)
+                .client_credentials(
+                    input.client_id,
+                    input.client_secret,
+                    input.client_assertion_type,
+                    input.client_assertion,
+                )
+                .await
+        }
+        _ => Err((Status::BadRequest, "unsupported_grant_type", None)),
+    };
+    match result {
+        Ok(output) => Ok(Json(output)),
+        Err((status, error, description)) => {
+            Err(OauthErrorObject::create(status, error, description))
+        }
+    }
+}
+
+/// the HTTP Basic credentials replace the ones of the body, a client sending both is refused
+fn merge_client_authorization(
+    client_id: &mut Option<String>,
+    client_secret: &mut Option<String>,
+    client_authorization: ClientAuthorization,
+) -> Result<(), OauthError> {
+    if client_authorization.client_id.is_some() {
+        if client_secret.is_some()
+            || client_id.as_ref().is_some_and(|id| Some(id) != client_authorization.client_id.as_ref())
+        {
+            return Err(OauthErrorObject::create(
+                Status::BadRequest,
+                "invalid_request",
+                Some("The client authenticates with one method only"),
+            ));
+        }
+        *client_id = client_authorization.client_id;
+        *client_secret = client_authorization.client_secret;
+    }
+    Ok(())
+}
+
+/// # Token Introspection
+///
+/// Returns the state of a token of the application (RFC 7662), for the resource servers. A token unknown, expired, revoked, of another application or whose user, client or service account no longer exists is only `active: false`.
+///
+/// The caller authenticates as a confidential client or a service account, with its secret in the body or with HTTP Basic authentication, or with a `private_key_jwt` assertion for a service account.
+///
+/// ## Roles
+///
+/// - `PUBLIC`
+///
+/// ## Parameters
+///
+/// - `token`: The access token or refresh token
+///
+/// - `token_type_hint`: `access_token` or `refresh_token`, optional
+///
+/// - `client_id`: The client id of the client or of the service account
+///
+/// - `client_secret`: The secret of the client or of the service account
+///
+/// - `client_assertion_type`: `urn:ietf:params:oauth:client-assertion-type:jwt-bearer` (service account)
+///
+/// - `client_assertion`: The JWT signed by the service account (service account)
+///
+#[openapi(tag = "Oauth", ignore = "client_authorization")]
+#[post("/oauth/introspect", format = "json", data = "<oauth_token_request_input>")]
+pub async fn introspect(
+    client_authorization: ClientAuthorization,
+    pool: &rocket::State<DbPool>,
+    oauth_token_request_input: Json<OauthTokenRequestInput>,
+) -> Result<Json<OauthIntrospectionOutput>, OauthError> {
     let pool = pool.inner().to_owned();
-    let input = oauth_token_input.into_inner();
-
-    let revoked_repository: RevokedTokenRepository = RevokedTokenContract::new(&pool);
-    let application_repository: ApplicationRepository = ApplicationContract::new(&pool);
-    let connexion_repository: ConnexionRepository = ConnexionContract::new(&pool);
-    let user_service = UserService::new(&pool, application_repository, connexion_repository);
-    let connexion_repository: ConnexionRepository = ConnexionContract::new(&pool);
-    let connexion_service = ConnexionService::new(connexion_repository);
-    let auth_service = AuthService::new(&pool, revoked_repository, user_service, connexion_service);
-    let application_repository: ApplicationRepository = ApplicationContract::new(&pool);
-    let oauth_service = OauthService::new(&pool, application_repository, auth_service);
-
-    let ip = addr.ip().to_string();
-    match oauth_service
-        .check(
-            input,
-            user_agent_guard
-                .user_agent
-                .unwrap_or_else(|| String::from("")),
-            ip,
+    introspect_token(&pool, oauth_token_request_input.into_inner(), client_authorization)
+}
+
+/// the introspection endpoint with a form body, as sent by the OAuth libraries
+#[post("/oauth/introspect", format = "form", data = "<oauth_token_request_input>", rank = 2)]
+pub async fn introspect_form(
+    client_authorization: ClientAuthorization,
+    pool: &rocket::State<DbPool>,
+    oauth_token_request_input: Form<OauthTokenRequestInput>,
+) -> Result<Json<OauthIntrospectionOutput>, OauthError> {
+    let pool = pool.inner().to_owned();
+    introspect_token(&pool, oauth_token_request_input.into_inner(), client_authorization)
+}
+
+fn introspect_token(
+    pool: &DbPool,
+    mut input: OauthTokenRequestInput,
+    client_authorization: ClientAuthorization,
+) -> Result<Json<OauthIntrospectionOutput>, OauthError> {
+    merge_client_authorization(
+        &mut input.client_id,
+        &mut input.client_secret,
+        client_authorization,
+    )?;
+    let token_service = TokenService::new(pool);
+    token_service
+        .authenticate_caller(
+            input.client_id,
+            input.client_secret,
+            input.client_assertion_type,
+            input.client_assertion,
         )
-        .await
-    {
-        Err((status, msg)) => Err(ErrorObject::create(status, msg.as_deref())),
-        Ok(creds) => Ok(Json(creds)),
+        .and_then(|caller| token_service.introspect(&caller, &input.token))
+        .map(Json)
+        .map_err(|(status, error, description)| OauthErrorObject::create(status, error, description))
+}
+
+/// # Token Revocation
+///
+/// Revokes an access token or a refresh token (RFC 7009), it is refused until it expires. An invalid or expired token is accepted as already revoked.
+///
+/// A client revokes the tokens issued to it and authenticates like at the token endpoint, a public client with its client id alone. A service account revokes its own tokens.
+/// Without client credentials, a user of the application revokes their own tokens with their bearer token.
+///
+/// ## Roles
+///
+/// - `PUBLIC`
+///
+/// ## Parameters
+///
+/// - `token`: The access token or refresh token
+///
+/// - `token_type_hint`: `access_token` or `refresh_token`, optional
+///
+/// - `client_id`: The client id of the client or of the service account
+///
+/// - `client_secret`: The secret of the client or of the service account
+///
+/// - `client_assertion_type`: `urn:ietf:params:oauth:client-assertion-type:jwt-bearer` (service account)
+///
+/// - `client_assertion`: The JWT signed by the service account (service account)
+///
+#[openapi(tag = "Oauth", ignore = "client_authorization")]
+#[post("/oauth/revoke", format = "json", data = "<oauth_token_request_input>")]
+pub async fn revoke(
+    client_authorization: ClientAuthorization,
+    authorised: Option<Security>,
+    pool: &rocket::State<DbPool>,
+    oauth_token_request_input: Json<OauthTokenRequestInput>,
+) -> Result<Status, OauthError> {
+    let pool = pool.inner().to_owned();
+    revoke_token(
+        &pool,
+        oauth_token_request_input.into_inner(),
+        client_authorization,
+        authorised,
+    )
+}
+
+/// the revocation endpoint with a form body, as sent by the OAuth libraries
+#[post("/oauth/revoke", format = "form", data = "<oauth_token_request_input>", rank = 2)]
+pub async fn revoke_form(
+    client_authorization: ClientAuthorization,
+    authorised: Option<Security>,
+    pool: &rocket::State<DbPool>,
+    oauth_token_request_input: Form<OauthTokenRequestInput>,
+) -> Result<Status, OauthError> {
+    let pool = pool.inner().to_owned();
+    revoke_token(
+        &pool,
+        oauth_token_request_input.into_inner(),
+        client_authorization,
+        authorised,
+    )
+}
+
+fn revoke_token(
+    pool: &DbPool,
+    mut input: OauthTokenRequestInput,
+    client_authorization: ClientAuthorization,
+    authorised: Option<Security>,
+) -> Result<Status, OauthError> {
+    merge_client_authorization(
+        &mut input.client_id,
+        &mut input.client_secret,
+        client_authorization,
+    )?;
+    let token_service = TokenService::new(pool);
+    let caller = match (input.client_id.is_some(), authorised) {
+        (true, _) => token_service.authenticate_caller(
+            input.client_id,
+            input.client_secret,
+            input.client_assertion_type,
+            input.client_assertion,
+        ),
+        // a token issued to a client can not revoke the other sessions of its user
+        (false, Some(authorised)) if authorised.oauth_client_id.is_none() => {
+            Ok(TokenCaller::User(Box::new(authorised.user)))
+        }
+        (false, _) => Err((Status::Unauthorized, "invalid_client", None)),
+    };
+    caller
+        .and_then(|caller| token_service.revoke(&caller, &input.token))
+        .map(|_| Status::Ok)
+        .map_err(|(status, error, description)| OauthErrorObject::create(status, error, description))
+}
+
+/// # Register OAuth Client
+///
+/// Allows users with `ROLE_ADMIN` to register a third party client of their application, allowed to request authorization codes for its redirect uris.
+/// A confidential client gets a client secret, only returned by this call. A public client (single page or mobile application) relies on PKCE alone.
+///
+/// ## Roles
+///
+/// - `ROLE_ADMIN`
+///
+/// ## Parameters
+///
+/// - `name`: A string representing the name of the client.
+///
+/// - `redirect_uris`: The callbacks of the client, absolute https uris, loopback http uris or private-use scheme uris, compared exactly.
+///
+/// - `is_public`: An optional boolean, true for a client without secret.
+///
+#[openapi(tag = "Oauth")]
+#[post("/oauth/clients", format = "json", data = "<oauth_client_input>")]
+pub async fn create_client(
+    authorised: Security,
+    pool: &rocket::State<DbPool>,
+    oauth_client_input: Json<OauthClientInput>,
+) -> Result<Json<OauthClientOutput>, CustomError> {
+    let pool = pool.inner().to_owned();
+    let oauth_service = OauthService::new(&pool);
+    match authorised.check_roles(Role::ADMIN) {
+        false => Err(ErrorObject::create(Status::Unauthorized, None)),
+        true => match oauth_service.create_client(oauth_client_input.into_inner(), &authorised.user) {
+            Ok((oauth_client, secret)) => Ok(Json(OauthClientOutput::new(oauth_client, secret))),
+            Err((status, msg)) => Err(ErrorObject::create(status, msg)),
+        },
+    }
+}
+
+/// # List OAuth Clients
+///
+/// Allows users with `ROLE_ADMIN` to list the clients registered for their application.
+///
+/// ## Roles
+///
+/// - `ROLE_ADMIN`
+///
+#[openapi(tag = "Oauth")]
+#[get("/oauth/clients")]
+pub async fn get_clients(
+    authorised: Security,
+    pool: &rocket::State<DbPool>,
+) -> Result<Json<Vec<OauthClientOutput>>, CustomError> {
+    let pool = pool.inner().to_owned();
+    let oauth_service = OauthService::new(&pool);
+    match authorised.check_roles(Role::ADMIN) {
+        false => Err(ErrorObject::create(Status::Unauthorized, None)),
+        true => Ok(Json(
+            oauth_service
+                .get_clients(&authorised.user)
+                .into_iter()
+                .map(|oauth_client| OauthClientOutput::new(oauth_client, None))
+                .collect(),
+        )),
+    }
+}
+
+/// # Rotate OAuth Client Secret
+///
+/// Allows users with `ROLE_ADMIN` to replace the secret of a confidential client. The previous secret stops working immediately, the new one is only returned by this call.
+///
+/// ## Roles
+///
+/// - `ROLE_ADMIN`
+///
+/// ## Parameters
+///
+/// - `oauth_client_id`: A string representing the UUID of the client.
+///
+#[openapi(tag = "Oauth")]
+#[post("/oauth/clients/<oauth_client_id>/secret")]
+pub async fn rotate_client_secret(
+    authorised: Security,
+    pool: &rocket::State<DbPool>,
+    oauth_client_id: &str,
+) -> Result<Json<OauthClientOutput>, CustomError> {
+    let pool = pool.inner().to_owned();
+    let oauth_service = OauthService::new(&pool);
+    let oauth_client_uuid = match Uuid::parse_str(oauth_client_id) {
+        Err(_) => {
+            return Err(ErrorObject::create(
+                Status::BadRequest,
+                Some("Bad Client uuid"),
+            ))
+        }
+        Ok(uuid) => uuid,
+    };
+    match authorised.check_roles(Role::ADMIN) {
+        false => Err(ErrorObject::create(Status::Unauthorized, None)),
+        true => match oauth_service.rotate_secret(&oauth_client_uuid, &authorised.user) {
+            Ok((oauth_client, secret)) => Ok(Json(OauthClientOutput::new(oauth_client, secret))),
+            Err((status, msg)) => Err(ErrorObject::create(status, msg)),
+        },
+    }
+}
+
+/// # Delete OAuth Client
+///
+/// Allows users with `ROLE_ADMIN` to delete a client. Its pending codes and the access tokens issued to it stop working immediately.
+///
+/// ## Roles
+///
+/// - `ROLE_ADMIN`
+///
+/// ## Parameters
+///
+/// - `oauth_client_id`: A string representing the UUID of the client.
+///
+#[openapi(tag = "Oauth")]
+#[delete("/oauth/clients/<oauth_client_id>")]
+pub async fn delete_client(
+    authorised: Security,
+    pool: &rocket::State<DbPool>,
+    oauth_client_id: &str,
+) -> Result<Status, CustomError> {
+    let pool = pool.inner().to_owned();
+    let oauth_service = OauthService::new(&pool);
+    let oauth_client_uuid = match Uuid::parse_str(oauth_client_id) {
+        Err(_) => {
+            return Err(ErrorObject::create(
+                Status::BadRequest,
+                Some("Bad Client uuid"),
+            ))
+        }
+        Ok(uuid) => uuid,
+    };
+    match authorised.check_roles(Role::ADMIN) {
+        false => Err(ErrorObject::create(Status::Unauthorized, None)),
+        true => match oauth_service.delete_client(&oauth_client_uuid, &authorised.user) {
+            Ok(()) => Ok(Status::NoContent),
+            Err((status, msg)) => Err(ErrorObject::create(status, msg)),
+        },
     }
 }
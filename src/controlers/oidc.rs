@@ -1,17 +1,21 @@
+use crate::dto::oidc::jwks_output::JwksOutput;
+use crate::dto::oidc::openid_configuration_output::OpenidConfigurationOutput;
+use crate::dto::oidc::userinfo_output::UserinfoOutput;
 use crate::dto::oidc::OidcVerifyInput;
 use crate::dto::user::user_output::UserOutput;
+use crate::guards::open_id_security::OpenIdSecurity;
 use crate::guards::security::Security;
 use crate::services::oidc::OidcService;
 use rocket::post;
 use rocket::serde::json::Json;
 use rocket_okapi::openapi;
 
-use crate::core::errors::{CustomError, ErrorObject};
+use crate::core::errors::{CustomError, ErrorObject, OauthError, OauthErrorObject};
 use crate::db::connect::DbPool;
 
 /// # Verifies an OpenID Connect Token.
 /// 
-/// This endpoint verifies the validity of an OpenID Connect Token. It checks the signature and expiration of the token. 
+/// This endpoint verifies the validity of an OpenID Connect Token. It checks the signature, the issuer and the expiration of the token. 
 /// If valid, it returns basic information about the user.
 ///
 /// ## Roles
@@ -36,4 +40,75 @@ pub async fn verify(
         Err((status, msg)) => Err(ErrorObject::create(status, msg.as_deref())),
         Ok(user) => Ok(Json(UserOutput::new(user))),
     }
-}
\ No newline at end of file
+}
+
+/// # OpenID Connect Discovery
+///
+/// Returns the OpenID Connect provider metadata: the issuer, the endpoints, the supported scopes, grants and claims. The issuer is `API_URL`.
+///
+/// ## Roles
+///
+/// - `PUBLIC`
+///
+#[openapi(tag = "Oidc")]
+#[get("/.well-known/openid-configuration")]
+pub async fn configuration(pool: &rocket::State<DbPool>) -> Json<OpenidConfigurationOutput> {
+    let pool = pool.inner().to_owned();
+    let oidc_service = OidcService::new(&pool);
+    Json(oidc_service.configuration())
+}
+
+/// # JSON Web Key Set
+///
+/// Returns the public keys checking the signature of the ID tokens and access tokens, a rotated key is published here before it signs and stays until it is retired. The `kid` of a key is its RFC 7638 thumbprint.
+///
+/// ## Roles
+///
+/// - `PUBLIC`
+///
+#[openapi(tag = "Oidc")]
+#[get("/jwks.json")]
+pub async fn jwks(pool: &rocket::State<DbPool>) -> Json<JwksOutput> {
+    let pool = pool.inner().to_owned();
+    let oidc_service = OidcService::new(&pool);
+    Json(oidc_service.jwks())
+}
+
+/// # Userinfo
+///
+/// Returns the claims of the user of the access token. A token issued to an OAuth client needs the `openid` scope and only gets the `profile` and `email` claims allowed by its scope.
+///
+/// ## Roles
+///
+/// - `ROLE_USER`
+///
+#[openapi(tag = "Oidc")]
+#[get("/userinfo")]
+pub async fn userinfo(
+    authorised: OpenIdSecurity,
+    pool: &rocket::State<DbPool>,
+) -> Result<Json<UserinfoOutput>, OauthError> {
+    let pool = pool.inner().to_owned();
+    let oidc_service = OidcService::new(&pool);
+    match oidc_service.userinfo(&authorised.security) {
+        Ok(userinfo) => Ok(Json(userinfo)),
+        Err((status, error)) => Err(OauthErrorObject::create(status, error, None)),
+    }
+}
+
+/// # Userinfo
+///
+/// Same as `GET /userinfo`, for the clients sending their request with POST.
+///
+/// ## Roles
+///
+/// - `ROLE_USER`
+///
+#[openapi(tag = "Oidc")]
+#[post("/userinfo")]
+pub async fn userinfo_post(
+    authorised: OpenIdSecurity,
+    pool: &rocket::State<DbPool>,
+) -> Result<Json<UserinfoOutput>, OauthError> {
+    userinfo(authorised, pool).await
+}
@@ -0,0 +1,268 @@
+use crate::core::nodes_config::NodesConfig;
+use crate::dto::secret::secret_input::SecretInput;
+use crate::dto::secret::secret_output::SecretOutput;
+use crate::dto::secret::secret_value_output::SecretValueOutput;
+use crate::dto::secret::secret_version_input::SecretVersionInput;
+use crate::dto::secret::secret_version_output::SecretVersionOutput;
+use crate::enums::roles::Role;
+use crate::guards::security::Security;
+use crate::repositories::secret::SecretRepository;
+use crate::services::secret::SecretService;
+use crate::services::sentinel_log::SentinelLogService;
+use crate::traits::secret::SecretContract;
+use rocket::http::Status;
+use rocket::serde::json::Json;
+use rocket::tokio::spawn;
+use rocket::post;
+use rocket_okapi::openapi;
+use crate::guards::client_ip::ClientIp;
+use uuid::Uuid;
+
+use crate::core::errors::{CustomError, ErrorObject};
+use crate::db::connect::DbPool;
+
+/// # Create a New Secret
+///
+/// Allows users with `ROLE_USER` to store a named secret (password, API token, certificate...). The value is encrypted and split across the nodes like a sentinel key.
+///
+/// ## Roles
+///
+/// - `ROLE_USER`
+///
+/// ## Parameters
+///
+/// - `name`: A string representing the unique name of the secret in the application.
+///
+/// - `value`: A string representing the value of the secret (64 KiB max).
+///
+/// - `clusters`: An array of strings representing the UUIDs of the clusters to be added.
+///
+#[openapi(tag = "Secrets")]
+#[post("/secrets", format = "json", data = "<secret_input>")]
+pub async fn create(
+    authorised: Security,
+    pool: &rocket::State<DbPool>,
+    nodes_config: &rocket::State<NodesConfig>,
+    secret_input: Json<SecretInput>,
+) -> Result<Json<SecretOutput>, CustomError> {
+    let pool = pool.inner().to_owned();
+    let nodes_config = nodes_config.inner().to_owned();
+    let input = secret_input.into_inner();
+    let secret_repository: SecretRepository = SecretContract::new(&pool);
+    let secret_service = SecretService::new(secret_repository, &nodes_config);
+    match authorised.check_roles(Role::USER) {
+        false => Err(ErrorObject::create(Status::Unauthorized, None)),
+        true => match secret_service.create(input, authorised.user) {
+            Ok(secret) => Ok(Json(SecretOutput::new(secret))),
+            Err((status, msg)) => Err(ErrorObject::create(status, msg)),
+        },
+    }
+}
+
+/// # Add a Secret Version
+///
+/// Allows users with `ROLE_USER` to store a new value for a secret they can access. The previous versions stay readable.
+///
+/// ## Roles
+///
+/// - `ROLE_USER`
+///
+/// ## Parameters
+///
+/// - `secret_id`: A string representing the UUID of the secret.
+///
+/// - `value`: A string representing the new value of the secret (64 KiB max).
+///
+#[openapi(tag = "Secrets")]
+#[put("/secrets/<secret_id>", format = "json", data = "<secret_version_input>")]
+pub async fn add_version(
+    authorised: Security,
+    pool: &rocket::State<DbPool>,
+    nodes_config: &rocket::State<NodesConfig>,
+    secret_id: &str,
+    secret_version_input: Json<SecretVersionInput>,
+) -> Result<Json<SecretOutput>, CustomError> {
+    let pool = pool.inner().to_owned();
+    let nodes_config = nodes_config.inner().to_owned();
+    let input = secret_version_input.into_inner();
+    let secret_repository: SecretRepository = SecretContract::new(&pool);
+    let secret_service = SecretService::new(secret_repository, &nodes_config);
+    let secret_uuid = match Uuid::parse_str(secret_id) {
+        Err(_) => {
+            return Err(ErrorObject::create(
+                Status::BadRequest,
+                Some("Bad Secret uuid"),
+            ))
+        }
+        Ok(uuid) => uuid,
+    };
+    match authorised.check_roles(Role::USER) {
+        false => Err(ErrorObject::create(Status::Unauthorized, None)),
+        true => match secret_service.add_version(secret_uuid, input, authorised.user) {
+            Ok(secret) => Ok(Json(SecretOutput::new(secret))),
+            Err((status, msg)) => Err(ErrorObject::create(status, msg)),
+        },
+    }
+}
+
+/// # Get Secret
+///
+/// Allows users with `ROLE_USER` to retrieve the value of a secret by its ID, the secret must be created by the user or shared with one of their clusters.
+///
+/// ## Roles
+///
+/// - `ROLE_USER`
+///
+/// ## Parameters
+///
+/// - `secret_id`: A string representing the UUID of the secret to be retrieved.
+///
+/// - `version`: An optional integer representing the version to be retrieved (default: the current version).
+///
+#[openapi(tag = "Secrets")]
+#[get("/secrets/<secret_id>?<version>")]
+pub async fn get_by_id(
+    authorised: Security,
+    pool: &rocket::State<DbPool>,
+    nodes_config: &rocket::State<NodesConfig>,
+    secret_id: &str,
+    version: Option<i32>,
+    addr: ClientIp,
+) -> Result<Json<SecretValueOutput>, CustomError> {
+    let pool = pool.inner().to_owned();
+    let nodes_config = nodes_config.inner().to_owned();
+    let secret_repository: SecretRepository = SecretContract::new(&pool);
+    let secret_service = SecretService::new(secret_repository, &nodes_config);
+    let secret_uuid = match Uuid::parse_str(secret_id) {
+        Err(_) => {
+            return Err(ErrorObject::create(
+                Status::BadRequest,
+                Some("Bad Secret uuid"),
+            ))
+        }
+        Ok(uuid) => uuid,
+    };
+    if !authorised.check_roles(Role::USER) {
+        let secret_id = secret_id.to_string();
+        spawn(async move {
+            let _ = SentinelLogService::new_sentinel_log(
+                secret_id,
+                &authorised.user,
+                false,
+                &addr.ip().to_string(),
+            )
+            .await;
+        });
+        return Err(ErrorObject::create(Status::Unauthorized, None));
+    }
+    let res = secret_service.get_by_id(secret_uuid, version, authorised.user.clone());
+    let secret_id = secret_id.to_string();
+    let success = res.is_ok();
+    spawn(async move {
+        let _ = SentinelLogService::new_sentinel_log(
+            secret_id,
+            &authorised.user,
+            success,
+            &addr.ip().to_string(),
+        )
+        .await;
+    });
+    match res {
+        Err((status, msg)) => Err(ErrorObject::create(status, msg)),
+        Ok((secret, secret_version, value)) => {
+            Ok(Json(SecretValueOutput::new(secret, secret_version, value)))
+        }
+    }
+}
+
+/// # Get Secret Versions
+///
+/// Allows users with `ROLE_USER` to list the versions of a secret they can access, the values are not returned.
+///
+/// ## Roles
+///
+/// - `ROLE_USER`
+///
+/// ## Parameters
+///
+/// - `secret_id`: A string representing the UUID of the secret.
+///
+#[openapi(tag = "Secrets")]
+#[get("/secrets/<secret_id>/versions")]
+pub async fn get_versions(
+    authorised: Security,
+    pool: &rocket::State<DbPool>,
+    nodes_config: &rocket::State<NodesConfig>,
+    secret_id: &str,
+) -> Result<Json<Vec<SecretVersionOutput>>, CustomError> {
+    let pool = pool.inner().to_owned();
+    let nodes_config = nodes_config.inner().to_owned();
+    let secret_repository: SecretRepository = SecretContract::new(&pool);
+    let secret_service = SecretService::new(secret_repository, &nodes_config);
+    let secret_uuid = match Uuid::parse_str(secret_id) {
+        Err(_) => {
+            return Err(ErrorObject::create(
+                Status::BadRequest,
+                Some("Bad Secret uuid"),
+            ))
+        }
+        Ok(uuid) => uuid,
+    };
+    match authorised.check_roles(Role::USER) {
+        false => Err(ErrorObject::create(Status::Unauthorized, None)),
+        true => match secret_service.get_versions(secret_uuid, authorised.user) {
+            Ok(versions) => Ok(Json(
+                versions.into_iter().map(SecretVersionOutput::new).collect(),
+            )),
+            Err((status, msg)) => Err(ErrorObject::create(status, msg)),
+        },
+    }
+}
+
+/// # Delete a Secret
+///
+/// Allows users with `ROLE_USER` to delete a secret they created, and `ROLE_ADMIN` any secret of the application. Every version is deleted.
+///
+/// ## Roles
+///
+/// - `ROLE_USER`
+///
+/// - `ROLE_ADMIN`
+///
+/// ## Parameters
+///
+/// - `secret_id`: A string representing the UUID of the secret to be deleted.
+///
+#[openapi(tag = "Secrets")]
+#[delete("/secrets/<secret_id>")]
+pub async fn delete_by_id(
+    authorised: Security,
+    pool: &rocket::State<DbPool>,
+    nodes_config: &rocket::State<NodesConfig>,
+    secret_id: &str,
+) -> Result<Status, CustomError> {
+    let pool = pool.inner().to_owned();
+    let nodes_config = nodes_config.inner().to_owned();
+    let secret_repository: SecretRepository = SecretContract::new(&pool);
+    let secret_service = SecretService::new(secret_repository, &nodes_config);
+    let secret_uuid = match Uuid::parse_str(secret_id) {
+        Err(_) => {
+            return Err(ErrorObject::create(
+                Status::BadRequest,
+                Some("Bad Secret uuid"),
+            ))
+        }
+        Ok(uuid) => uuid,
+    };
+    match authorised.check_roles(Role::USER) || authorised.check_roles(Role::ADMIN) {
+        false => Err(ErrorObject::create(Status::Unauthorized, None)),
+        true => match secret_service.delete_one(
+            secret_uuid,
+            authorised.clone().user,
+            authorised.check_roles(Role::ADMIN),
+        ) {
+            Ok(()) => Ok(Status::NoContent),
+            Err((status, msg)) => Err(ErrorObject::create(status, msg)),
+        },
+    }
+}
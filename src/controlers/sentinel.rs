@@ -1,23 +1,47 @@
 use crate::core::nodes_config::NodesConfig;
+use crate::dto::break_glass::break_glass_input::BreakGlassInput;
+use crate::dto::access_request::dual_control_input::DualControlInput;
+use crate::dto::key_policy::key_policy_input::KeyPolicyInput;
+use crate::dto::key_policy::key_policy_output::KeyPolicyOutput;
+use crate::dto::import_key::import_key_output::ImportKeyOutput;
+use crate::dto::sentinel::sentinel_hmac_input::SentinelHmacInput;
+use crate::dto::sentinel::sentinel_hmac_output::SentinelHmacOutput;
+use crate::dto::sentinel::sentinel_hmac_verify_input::SentinelHmacVerifyInput;
+use crate::dto::sentinel::sentinel_hmac_verify_output::SentinelHmacVerifyOutput;
+use crate::dto::sentinel::sentinel_import_input::SentinelImportInput;
 use crate::dto::sentinel::sentinel_input::SentinelInput;
 use crate::dto::sentinel::sentinel_output::SentinelOutput;
+use crate::dto::share_link::share_link_input::ShareLinkInput;
+use crate::dto::share_link::share_link_output::ShareLinkOutput;
+use crate::enums::key_operation::KeyOperation;
 use crate::enums::roles::Role;
+use crate::guards::key_encryption_key::KeyEncryptionKey;
+use crate::guards::digested_json::DigestedJson;
+use crate::guards::scoped_security::ScopedSecurity;
 use crate::guards::security::Security;
 use crate::repositories::application::ApplicationRepository;
+use crate::services::import_key::ImportKeyService;
 use crate::services::sentinel::SentinelService;
 use crate::services::sentinel_log::SentinelLogService;
 use crate::traits::application::ApplicationContract;
-use rocket::http::Status;
+use crate::utils::key_encoding::KeyEncoding;
+use crate::utils::stream_cipher::{stream_max_size, StreamCipherReader};
+use rocket::data::{ByteUnit, Data, DataStream};
+use rocket::http::{ContentType, Status};
 use rocket::post;
+use rocket::response::stream::{One, ReaderStream};
 use rocket::serde::json::Json;
 use rocket::tokio::spawn;
 use rocket_okapi::openapi;
-use std::net::SocketAddr;
+use crate::guards::client_ip::ClientIp;
 use uuid::Uuid;
 
 use crate::core::errors::{CustomError, ErrorObject};
 use crate::db::connect::DbPool;
 
+/// the encrypted or decrypted request body, streamed back
+type CipherStream<'r> = (ContentType, ReaderStream<One<StreamCipherReader<DataStream<'r>>>>);
+
 /// # Create a New Sentinel
 ///
 /// Allows users with `ROLE_USER` to create a new sentinel. The user must be authenticated and authorized to perform this action.
@@ -30,6 +54,8 @@ use crate::db::connect::DbPool;
 ///
 /// - `clusters`: An array of strings representing the UUIDs of the clusters to be added.
 ///
+/// - `purpose`: An optional string, `encryption` (default) or `mac`. The key of a `mac` sentinel is never returned, it is only used by the HMAC endpoints.
+///
 #[openapi(tag = "Sentinels")]
 #[post("/sentinels", format = "json", data = "<sentinel_input>")]
 pub async fn create(
@@ -52,9 +78,92 @@ pub async fn create(
     }
 }
 
+/// # Get Import Public Key
+///
+/// Allows users with `ROLE_USER` to get the public key used to wrap the keys they import.
+/// The same key is returned until it expires, then a new one is published.
+///
+/// ## Roles
+///
+/// - `ROLE_USER`
+///
+/// ## Parameters
+///
+/// - `algorithm`: A string representing the wrapping algorithm, `RSA-OAEP-256` (PEM public key) or `ML-KEM-768` (hex encapsulation key).
+///
+#[openapi(tag = "Sentinels")]
+#[get("/sentinels/import/public_key?<algorithm>")]
+pub async fn get_import_public_key(
+    authorised: Security,
+    pool: &rocket::State<DbPool>,
+    nodes_config: &rocket::State<NodesConfig>,
+    algorithm: &str,
+) -> Result<Json<ImportKeyOutput>, CustomError> {
+    let pool = pool.inner().to_owned();
+    let nodes_config = nodes_config.inner().to_owned();
+    let import_key_service = ImportKeyService::new(&pool, &nodes_config);
+    match authorised.check_roles(Role::USER) {
+        false => Err(ErrorObject::create(Status::Unauthorized, None)),
+        true => match import_key_service.get_or_create(algorithm, &authorised.user) {
+            Ok(import_key) => Ok(Json(ImportKeyOutput::new(import_key))),
+            Err((status, msg)) => Err(ErrorObject::create(status, msg)),
+        },
+    }
+}
+
+/// # Import a Sentinel
+///
+/// Allows users with `ROLE_USER` to create a sentinel from a key they generated themselves.
+/// The AES-128 or AES-256 key is wrapped with an import public key:
+/// - `RSA-OAEP-256`: `wrapped_key` is the RSA-OAEP (SHA-256) encrypted key.
+/// - `ML-KEM-768`: `encapsulated_key` is the ML-KEM ciphertext, `wrapped_key` is the key encrypted
+///   with AES-256-GCM under the shared secret and `nonce` (12 bytes).
+///
+/// ## Roles
+///
+/// - `ROLE_USER`
+///
+/// ## Parameters
+///
+/// - `import_key_id`: A string representing the UUID of the import key used.
+///
+/// - `wrapped_key`: A hex string representing the wrapped key.
+///
+/// - `encapsulated_key`: A hex string representing the ML-KEM ciphertext (ML-KEM-768 only).
+///
+/// - `nonce`: A hex string representing the AES-GCM nonce (ML-KEM-768 only).
+///
+/// - `clusters`: An array of strings representing the UUIDs of the clusters to be added.
+///
+#[openapi(tag = "Sentinels")]
+#[post("/sentinels/import", format = "json", data = "<sentinel_import_input>")]
+pub async fn import(
+    authorised: Security,
+    pool: &rocket::State<DbPool>,
+    nodes_config: &rocket::State<NodesConfig>,
+    sentinel_import_input: Json<SentinelImportInput>,
+) -> Result<Json<SentinelOutput>, CustomError> {
+    let pool = pool.inner().to_owned();
+    let nodes_config = nodes_config.inner().to_owned();
+    let input = sentinel_import_input.into_inner();
+    let application_repository: ApplicationRepository = ApplicationContract::new(&pool);
+    let sentinel_service = SentinelService::new(&pool, application_repository, &nodes_config);
+    match authorised.check_roles(Role::USER) {
+        false => Err(ErrorObject::create(Status::Unauthorized, None)),
+        true => match sentinel_service.import(input, authorised.user) {
+            Ok((sentinel, cipher)) => Ok(Json(SentinelOutput::new(sentinel, cipher))),
+            Err((status, msg)) => Err(ErrorObject::create(status, msg)),
+        },
+    }
+}
+
 /// # Get Sentinel
 ///
 /// Allows users with `ROLE_USER` to retrieve a sentinel by its ID. The user must be authenticated and authorized to perform this action.
+/// Only the creator and the members of the clusters of the sentinel can read it, the other keys of the application are read through break-glass.
+///
+/// When the sentinel is under dual control, the first call opens an access request and answers `202 Accepted`; the key is released once, after the request is approved by enough members of its clusters.
+/// A scoped token is accepted when its scope allows `read` on the sentinel.
 ///
 /// ## Roles
 ///
@@ -64,14 +173,18 @@ pub async fn create(
 ///
 /// - `sentinel_id`: A string representing the UUID of the sentinel to be retrieved.
 ///
+/// - `format`: The export format of the key: `hex` (default), `jwk`, `raw-base64` or `wrapped` (RFC 3394 key wrap under the hex key-encryption key given in the `X-Key-Encryption-Key` header).
+///
 #[openapi(tag = "Sentinels")]
-#[get("/sentinels/<sentinel_id>")]
+#[get("/sentinels/<sentinel_id>?<format>")]
 pub async fn get_by_id(
-    authorised: Security,
+    authorised: ScopedSecurity,
+    key_encryption_key: KeyEncryptionKey,
     pool: &rocket::State<DbPool>,
     nodes_config: &rocket::State<NodesConfig>,
     sentinel_id: &str,
-    addr: SocketAddr,
+    format: Option<&str>,
+    addr: ClientIp,
 ) -> Result<Json<SentinelOutput>, CustomError> {
     let pool = pool.inner().to_owned();
     let nodes_config = nodes_config.inner().to_owned();
@@ -86,6 +199,10 @@ pub async fn get_by_id(
         }
         Ok(uuid) => uuid,
     };
+    let format = match KeyEncoding::export_format(format, &key_encryption_key.key) {
+        Err(e) => return Err(ErrorObject::create(Status::BadRequest, Some(e))),
+        Ok(format) => format,
+    };
     match authorised.check_roles(Role::USER) {
         false => {
             let sentinel_id = sentinel_id.to_string();
@@ -100,7 +217,10 @@ pub async fn get_by_id(
             });
             Err(ErrorObject::create(Status::Unauthorized, None))
         }
-        true => match sentinel_service.get_by_id(sentinel_uuid, authorised.user.clone()) {
+        true => match sentinel_service
+            .check_scope(authorised.scope.as_ref(), &sentinel_uuid, KeyOperation::Read)
+            .and_then(|_| sentinel_service.get_by_id(sentinel_uuid, authorised.user.clone(), &addr.ip()))
+        {
             Err((status, msg)) => {
                 let sentinel_id = sentinel_id.to_string();
                 spawn(async move {
@@ -125,9 +245,10 @@ pub async fn get_by_id(
                     )
                     .await;
                 });
-                let res = Json(SentinelOutput::new(sentinel, cipher));
-
-                Ok(res)
+                match SentinelOutput::formatted(sentinel, cipher, &format, key_encryption_key.key) {
+                    Err(e) => Err(ErrorObject::create(Status::BadRequest, Some(e))),
+                    Ok(output) => Ok(Json(output)),
+                }
             }
         },
     }
@@ -136,6 +257,7 @@ pub async fn get_by_id(
 /// # Delete a Sentinel
 ///
 /// Allows users with `ROLE_USER` to delete a sentinel. The user must be authenticated and authorized to perform this action.
+/// A scoped token is accepted when its scope allows `delete` on the sentinel.
 ///
 /// ## Roles
 ///
@@ -148,10 +270,11 @@ pub async fn get_by_id(
 #[openapi(tag = "Sentinels")]
 #[delete("/sentinels/<sentinel_id>")]
 pub async fn delete_by_id(
-    authorised: Security,
+    authorised: ScopedSecurity,
     pool: &rocket::State<DbPool>,
     nodes_config: &rocket::State<NodesConfig>,
     sentinel_id: &str,
+    addr: ClientIp,
 ) -> Result<Status, CustomError> {
     let pool = pool.inner().to_owned();
     let nodes_config = nodes_config.inner().to_owned();
@@ -168,8 +291,742 @@ pub async fn delete_by_id(
     };
     match authorised.check_roles(Role::USER) || authorised.check_roles(Role::ADMIN) {
         false => Err(ErrorObject::create(Status::Unauthorized, None)),
-        true => match sentinel_service.delete_one(
+        true => match sentinel_service
+            .check_scope(authorised.scope.as_ref(), &sentinel_uuid, KeyOperation::Delete)
+            .and_then(|_| {
+                sentinel_service.delete_one(
+                    sentinel_uuid,
+                    authorised.clone().user,
+                    authorised.check_roles(Role::ADMIN),
+                    &addr.ip(),
+                )
+            }) {
+            Ok(()) => Ok(Status::NoContent),
+            Err((status, msg)) => Err(ErrorObject::create(status, msg)),
+        },
+    }
+}
+
+/// # Compute an HMAC
+///
+/// Allows users with `ROLE_USER` to compute the HMAC of a payload with the key of a `mac` sentinel. The key never leaves the server.
+/// A scoped token is accepted when its scope allows `encrypt` on the sentinel.
+///
+/// ## Roles
+///
+/// - `ROLE_USER`
+///
+/// ## Parameters
+///
+/// - `sentinel_id`: A string representing the UUID of the `mac` sentinel.
+///
+/// - `algorithm`: A string representing the MAC algorithm, `HS256` (HMAC-SHA256) or `HS512` (HMAC-SHA512).
+///
+/// - `payload`: A string representing the base64 encoded payload.
+///
+#[openapi(tag = "Sentinels")]
+#[post("/sentinels/<sentinel_id>/hmac", format = "json", data = "<sentinel_hmac_input>")]
+pub async fn hmac(
+    authorised: ScopedSecurity,
+    pool: &rocket::State<DbPool>,
+    nodes_config: &rocket::State<NodesConfig>,
+    sentinel_id: &str,
+    sentinel_hmac_input: DigestedJson<SentinelHmacInput>,
+    addr: ClientIp,
+) -> Result<Json<SentinelHmacOutput>, CustomError> {
+    let pool = pool.inner().to_owned();
+    let nodes_config = nodes_config.inner().to_owned();
+    let input = sentinel_hmac_input.into_inner();
+    let application_repository: ApplicationRepository = ApplicationContract::new(&pool);
+    let sentinel_service = SentinelService::new(&pool, application_repository, &nodes_config);
+    let sentinel_uuid = match Uuid::parse_str(sentinel_id) {
+        Err(_) => {
+            return Err(ErrorObject::create(
+                Status::BadRequest,
+                Some("Bad Sentinel uuid"),
+            ))
+        }
+        Ok(uuid) => uuid,
+    };
+    if !authorised.check_roles(Role::USER) {
+        return Err(ErrorObject::create(Status::Unauthorized, None));
+    }
+    if let Err((status, msg)) =
+        sentinel_service.check_scope(authorised.scope.as_ref(), &sentinel_uuid, KeyOperation::Encrypt)
+    {
+        return Err(ErrorObject::create(status, msg));
+    }
+    let res = sentinel_service.hmac(sentinel_uuid, input, authorised.user.clone(), &addr.ip());
+    let sentinel_id = sentinel_id.to_string();
+    let success = res.is_ok();
+    spawn(async move {
+        let _ = SentinelLogService::new_sentinel_log(
+            sentinel_id,
+            &authorised.user,
+            success,
+            &addr.ip().to_string(),
+        )
+        .await;
+    });
+    match res {
+        Err((status, msg)) => Err(ErrorObject::create(status, msg)),
+        Ok((sentinel, algorithm, mac)) => Ok(Json(SentinelHmacOutput::new(sentinel, algorithm, mac))),
+    }
+}
+
+/// # Verify an HMAC
+///
+/// Allows users with `ROLE_USER` to check the HMAC of a payload with the key of a `mac` sentinel. The comparison is done in constant time.
+/// A scoped token is accepted when its scope allows `decrypt` on the sentinel.
+///
+/// ## Roles
+///
+/// - `ROLE_USER`
+///
+/// ## Parameters
+///
+/// - `sentinel_id`: A string representing the UUID of the `mac` sentinel.
+///
+/// - `algorithm`: A string representing the MAC algorithm, `HS256` (HMAC-SHA256) or `HS512` (HMAC-SHA512).
+///
+/// - `payload`: A string representing the base64 encoded payload.
+///
+/// - `mac`: A string representing the base64 encoded MAC to check.
+///
+#[openapi(tag = "Sentinels")]
+#[post(
+    "/sentinels/<sentinel_id>/hmac/verify",
+    format = "json",
+    data = "<sentinel_hmac_verify_input>"
+)]
+pub async fn hmac_verify(
+    authorised: ScopedSecurity,
+    pool: &rocket::State<DbPool>,
+    nodes_config: &rocket::State<NodesConfig>,
+    sentinel_id: &str,
+    sentinel_hmac_verify_input: DigestedJson<SentinelHmacVerifyInput>,
+    addr: ClientIp,
+) -> Result<Json<SentinelHmacVerifyOutput>, CustomError> {
+    let pool = pool.inner().to_owned();
+    let nodes_config = nodes_config.inner().to_owned();
+    let input = sentinel_hmac_verify_input.into_inner();
+    let application_repository: ApplicationRepository = ApplicationContract::new(&pool);
+    let sentinel_service = SentinelService::new(&pool, application_repository, &nodes_config);
+    let sentinel_uuid = match Uuid::parse_str(sentinel_id) {
+        Err(_) => {
+            return Err(ErrorObject::create(
+                Status::BadRequest,
+                Some("Bad Sentinel uuid"),
+            ))
+        }
+        Ok(uuid) => uuid,
+    };
+    if !authorised.check_roles(Role::USER) {
+        return Err(ErrorObject::create(Status::Unauthorized, None));
+    }
+    if let Err((status, msg)) =
+        sentinel_service.check_scope(authorised.scope.as_ref(), &sentinel_uuid, KeyOperation::Decrypt)
+    {
+        return Err(ErrorObject::create(status, msg));
+    }
+    let res = sentinel_service.hmac_verify(sentinel_uuid, input, authorised.user.clone(), &addr.ip());
+    let sentinel_id = sentinel_id.to_string();
+    let success = res.is_ok();
+    spawn(async move {
+        let _ = SentinelLogService::new_sentinel_log(
+            sentinel_id,
+            &authorised.user,
+            success,
+            &addr.ip().to_string(),
+        )
+        .await;
+    });
+    match res {
+        Err((status, msg)) => Err(ErrorObject::create(status, msg)),
+        Ok((sentinel, algorithm, valid)) => Ok(Json(SentinelHmacVerifyOutput::new(
+            sentinel, algorithm, valid,
+        ))),
+    }
+}
+
+/// # Encrypt a Stream
+///
+/// Allows users with `ROLE_USER` to encrypt a large `application/octet-stream` body with the key of an `encryption` sentinel.
+/// The body is read and encrypted segment by segment (AES-256-GCM STREAM construction), the key never leaves the server.
+/// The response is the ciphertext stream: a header followed by the sealed segments. If the body exceeds `STREAM_MAX_SIZE` the connection is aborted.
+/// A scoped token is accepted when its scope allows `encrypt` on the sentinel.
+///
+/// ## Roles
+///
+/// - `ROLE_USER`
+///
+/// ## Parameters
+///
+/// - `sentinel_id`: A string representing the UUID of the `encryption` sentinel.
+///
+#[post(
+    "/sentinels/<sentinel_id>/encrypt_stream",
+    format = "application/octet-stream",
+    data = "<data>"
+)]
+pub async fn encrypt_stream<'r>(
+    authorised: ScopedSecurity,
+    pool: &rocket::State<DbPool>,
+    nodes_config: &rocket::State<NodesConfig>,
+    sentinel_id: &str,
+    data: Data<'r>,
+    addr: ClientIp,
+) -> Result<CipherStream<'r>, CustomError> {
+    let (key, max_size) = stream_key(
+        authorised,
+        pool,
+        nodes_config,
+        sentinel_id,
+        KeyOperation::Encrypt,
+        addr,
+    )?;
+    match StreamCipherReader::encrypt(data.open(ByteUnit::from(max_size + 1)), &key, max_size) {
+        Err(e) => Err(ErrorObject::create(Status::InternalServerError, Some(e))),
+        Ok(reader) => Ok((ContentType::Binary, ReaderStream::from(One::from(reader)))),
+    }
+}
+
+/// # Decrypt a Stream
+///
+/// Allows users with `ROLE_USER` to decrypt a stream produced by the stream encryption endpoint with the same `encryption` sentinel.
+/// Each segment is authenticated before it is returned; a tampered, reordered or truncated stream aborts the connection, so a response is only complete when the whole stream was valid.
+/// A scoped token is accepted when its scope allows `decrypt` on the sentinel.
+///
+/// ## Roles
+///
+/// - `ROLE_USER`
+///
+/// ## Parameters
+///
+/// - `sentinel_id`: A string representing the UUID of the `encryption` sentinel.
+///
+#[post(
+    "/sentinels/<sentinel_id>/decrypt_stream",
+    format = "application/octet-stream",
+    data = "<data>"
+)]
+pub async fn decrypt_stream<'r>(
+    authorised: ScopedSecurity,
+    pool: &rocket::State<DbPool>,
+    nodes_config: &rocket::State<NodesConfig>,
+    sentinel_id: &str,
+    data: Data<'r>,
+    addr: ClientIp,
+) -> Result<CipherStream<'r>, CustomError> {
+    let (key, max_size) = stream_key(
+        authorised,
+        pool,
+        nodes_config,
+        sentinel_id,
+        KeyOperation::Decrypt,
+        addr,
+    )?;
+    match StreamCipherReader::decrypt(data.open(ByteUnit::from(max_size + 1)), &key, max_size) {
+        Err(e) => Err(ErrorObject::create(Status::InternalServerError, Some(e))),
+        Ok(reader) => Ok((ContentType::Binary, ReaderStream::from(One::from(reader)))),
+    }
+}
+
+/// rebuild the key of the sentinel used by the stream endpoints and log the access
+fn stream_key(
+    authorised: ScopedSecurity,
+    pool: &rocket::State<DbPool>,
+    nodes_config: &rocket::State<NodesConfig>,
+    sentinel_id: &str,
+    operation: KeyOperation,
+    addr: ClientIp,
+) -> Result<(Vec<u8>, u64), CustomError> {
+    let pool = pool.inner().to_owned();
+    let nodes_config = nodes_config.inner().to_owned();
+    let application_repository: ApplicationRepository = ApplicationContract::new(&pool);
+    let sentinel_service = SentinelService::new(&pool, application_repository, &nodes_config);
+    let sentinel_uuid = match Uuid::parse_str(sentinel_id) {
+        Err(_) => {
+            return Err(ErrorObject::create(
+                Status::BadRequest,
+                Some("Bad Sentinel uuid"),
+            ))
+        }
+        Ok(uuid) => uuid,
+    };
+    if !authorised.check_roles(Role::USER) {
+        return Err(ErrorObject::create(Status::Unauthorized, None));
+    }
+    if authorised.is_signed {
+        // the stream is processed as it arrives, before its digest could be checked
+        return Err(ErrorObject::create(
+            Status::BadRequest,
+            Some("The streams do not accept signed requests"),
+        ));
+    }
+    if let Err((status, msg)) =
+        sentinel_service.check_scope(authorised.scope.as_ref(), &sentinel_uuid, operation)
+    {
+        return Err(ErrorObject::create(status, msg));
+    }
+    let res = sentinel_service.stream_key(sentinel_uuid, authorised.user.clone(), operation, &addr.ip());
+    let sentinel_id = sentinel_id.to_string();
+    let success = res.is_ok();
+    spawn(async move {
+        let _ = SentinelLogService::new_sentinel_log(
+            sentinel_id,
+            &authorised.user,
+            success,
+            &addr.ip().to_string(),
+        )
+        .await;
+    });
+    match res {
+        Err((status, msg)) => Err(ErrorObject::create(status, msg)),
+        Ok((_, key)) => Ok((key, stream_max_size())),
+    }
+}
+
+/// # Get the Policy of a Sentinel
+///
+/// Allows users with `ROLE_USER` to get the usage policy of a sentinel.
+///
+/// ## Roles
+///
+/// - `ROLE_USER`
+///
+/// ## Parameters
+///
+/// - `sentinel_id`: A string representing the UUID of the sentinel.
+///
+#[openapi(tag = "Sentinels")]
+#[get("/sentinels/<sentinel_id>/policy")]
+pub async fn get_policy(
+    authorised: Security,
+    pool: &rocket::State<DbPool>,
+    nodes_config: &rocket::State<NodesConfig>,
+    sentinel_id: &str,
+) -> Result<Json<KeyPolicyOutput>, CustomError> {
+    let pool = pool.inner().to_owned();
+    let nodes_config = nodes_config.inner().to_owned();
+    let application_repository: ApplicationRepository = ApplicationContract::new(&pool);
+    let sentinel_service = SentinelService::new(&pool, application_repository, &nodes_config);
+    let sentinel_uuid = match Uuid::parse_str(sentinel_id) {
+        Err(_) => {
+            return Err(ErrorObject::create(
+                Status::BadRequest,
+                Some("Bad Sentinel uuid"),
+            ))
+        }
+        Ok(uuid) => uuid,
+    };
+    match authorised.check_roles(Role::USER) {
+        false => Err(ErrorObject::create(Status::Unauthorized, None)),
+        true => match sentinel_service.get_policy(sentinel_uuid, authorised.user) {
+            Ok(policy) => Ok(Json(KeyPolicyOutput::new(policy))),
+            Err((status, msg)) => Err(ErrorObject::create(status, msg)),
+        },
+    }
+}
+
+/// # Set the Policy of a Sentinel
+///
+/// Allows the creator of a sentinel, or an admin, to replace its usage policy. Only an admin can relax an existing policy, by allowing more operations, ranges, days or hours, or more reads. The policy is checked on every use of the key, before its fragments are fetched.
+///
+/// ## Roles
+///
+/// - `ROLE_USER`
+///
+/// - `ROLE_ADMIN`
+///
+/// ## Parameters
+///
+/// - `sentinel_id`: A string representing the UUID of the sentinel.
+///
+/// - `operations`: An optional array of the allowed operations, `read`, `encrypt`, `decrypt` and `delete` (all by default). `encrypt` and `decrypt` cover the stream encryption and the HMAC computation and verification.
+///
+/// - `allowed_cidrs`: An optional array of IPv4 or IPv6 CIDR ranges the key can be used from (any address when empty).
+///
+/// - `allowed_days`: An optional array of the allowed days, from 1 (monday) to 7 (sunday), in UTC (every day when empty).
+///
+/// - `hour_start` / `hour_end`: Optional numbers representing the allowed UTC hours, `hour_end` is excluded. The window wraps around midnight when `hour_start` is greater than `hour_end`.
+///
+/// - `max_reads`: An optional number representing the maximum number of reads of the key. The reads already done are kept when the policy is replaced.
+///
+#[openapi(tag = "Sentinels")]
+#[put("/sentinels/<sentinel_id>/policy", format = "json", data = "<key_policy_input>")]
+pub async fn set_policy(
+    authorised: Security,
+    pool: &rocket::State<DbPool>,
+    nodes_config: &rocket::State<NodesConfig>,
+    sentinel_id: &str,
+    key_policy_input: Json<KeyPolicyInput>,
+) -> Result<Json<KeyPolicyOutput>, CustomError> {
+    let pool = pool.inner().to_owned();
+    let nodes_config = nodes_config.inner().to_owned();
+    let input = key_policy_input.into_inner();
+    let application_repository: ApplicationRepository = ApplicationContract::new(&pool);
+    let sentinel_service = SentinelService::new(&pool, application_repository, &nodes_config);
+    let sentinel_uuid = match Uuid::parse_str(sentinel_id) {
+        Err(_) => {
+            return Err(ErrorObject::create(
+                Status::BadRequest,
+                Some("Bad Sentinel uuid"),
+            ))
+        }
+        Ok(uuid) => uuid,
+    };
+    match authorised.check_roles(Role::USER) || authorised.check_roles(Role::ADMIN) {
+        false => Err(ErrorObject::create(Status::Unauthorized, None)),
+        true => match sentinel_service.set_policy(
+            sentinel_uuid,
+            input,
+            authorised.clone().user,
+            authorised.check_roles(Role::ADMIN),
+        ) {
+            Ok(policy) => Ok(Json(KeyPolicyOutput::new(policy))),
+            Err((status, msg)) => Err(ErrorObject::create(status, msg)),
+        },
+    }
+}
+
+/// # Delete the Policy of a Sentinel
+///
+/// Allows an admin to remove the usage policy of a sentinel.
+///
+/// ## Roles
+///
+/// - `ROLE_ADMIN`
+///
+/// ## Parameters
+///
+/// - `sentinel_id`: A string representing the UUID of the sentinel.
+///
+#[openapi(tag = "Sentinels")]
+#[delete("/sentinels/<sentinel_id>/policy")]
+pub async fn delete_policy(
+    authorised: Security,
+    pool: &rocket::State<DbPool>,
+    nodes_config: &rocket::State<NodesConfig>,
+    sentinel_id: &str,
+) -> Result<Status, CustomError> {
+    let pool = pool.inner().to_owned();
+    let nodes_config = nodes_config.inner().to_owned();
+    let application_repository: ApplicationRepository = ApplicationContract::new(&pool);
+    let sentinel_service = SentinelService::new(&pool, application_repository, &nodes_config);
+    let sentinel_uuid = match Uuid::parse_str(sentinel_id) {
+        Err(_) => {
+            return Err(ErrorObject::create(
+                Status::BadRequest,
+                Some("Bad Sentinel uuid"),
+            ))
+        }
+        Ok(uuid) => uuid,
+    };
+    match authorised.check_roles(Role::USER) || authorised.check_roles(Role::ADMIN) {
+        false => Err(ErrorObject::create(Status::Unauthorized, None)),
+        true => match sentinel_service.delete_policy(
+            sentinel_uuid,
+            authorised.clone().user,
+            authorised.check_roles(Role::ADMIN),
+        ) {
+            Ok(()) => Ok(Status::NoContent),
+            Err((status, msg)) => Err(ErrorObject::create(status, msg)),
+        },
+    }
+}
+
+/// # Set the Dual Control of a Sentinel
+///
+/// Allows the creator of a sentinel, or an admin, to set the number of approvals needed to release its key. Only an admin can lower it.
+///
+/// ## Roles
+///
+/// - `ROLE_USER`
+///
+/// - `ROLE_ADMIN`
+///
+/// ## Parameters
+///
+/// - `sentinel_id`: A string representing the UUID of the sentinel.
+///
+/// - `required_approvals`: A number representing the approvals needed from other members of the clusters of the sentinel (`0` disables the dual control).
+///
+#[openapi(tag = "Sentinels")]
+#[put("/sentinels/<sentinel_id>/dual_control", format = "json", data = "<dual_control_input>")]
+pub async fn set_dual_control(
+    authorised: Security,
+    pool: &rocket::State<DbPool>,
+    nodes_config: &rocket::State<NodesConfig>,
+    sentinel_id: &str,
+    dual_control_input: Json<DualControlInput>,
+) -> Result<Status, CustomError> {
+    let pool = pool.inner().to_owned();
+    let nodes_config = nodes_config.inner().to_owned();
+    let input = dual_control_input.into_inner();
+    let application_repository: ApplicationRepository = ApplicationContract::new(&pool);
+    let sentinel_service = SentinelService::new(&pool, application_repository, &nodes_config);
+    let sentinel_uuid = match Uuid::parse_str(sentinel_id) {
+        Err(_) => {
+            return Err(ErrorObject::create(
+                Status::BadRequest,
+                Some("Bad Sentinel uuid"),
+            ))
+        }
+        Ok(uuid) => uuid,
+    };
+    match authorised.check_roles(Role::USER) || authorised.check_roles(Role::ADMIN) {
+        false => Err(ErrorObject::create(Status::Unauthorized, None)),
+        true => match sentinel_service.set_dual_control(
+            sentinel_uuid,
+            input,
+            authorised.clone().user,
+            authorised.check_roles(Role::ADMIN),
+        ) {
+            Ok(_) => Ok(Status::NoContent),
+            Err((status, msg)) => Err(ErrorObject::create(status, msg)),
+        },
+    }
+}
+
+/// # Break-Glass Read of a Sentinel
+///
+/// Allows users with `ROLE_BREAK_GLASS` to read any `encryption` sentinel of their application, outside of its clusters, in an emergency.
+/// A written justification is mandatory. Every read writes a high severity audit record, emails every admin of the application and flags the sentinel until an admin acknowledges it.
+/// The dual control and the usage policy of the sentinel still apply.
+///
+/// ## Roles
+///
+/// - `ROLE_BREAK_GLASS`
+///
+/// ## Parameters
+///
+/// - `sentinel_id`: A string representing the UUID of the sentinel.
+///
+/// - `justification`: A string representing the reason of the access, at least 10 characters.
+///
+/// - `format`: The export format of the key: `hex` (default), `jwk`, `raw-base64` or `wrapped` (RFC 3394 key wrap under the hex key-encryption key given in the `X-Key-Encryption-Key` header).
+///
+#[openapi(tag = "Sentinels")]
+#[allow(clippy::too_many_arguments)]
+#[post(
+    "/sentinels/<sentinel_id>/break_glass?<format>",
+    format = "json",
+    data = "<break_glass_input>"
+)]
+pub async fn break_glass(
+    authorised: Security,
+    key_encryption_key: KeyEncryptionKey,
+    pool: &rocket::State<DbPool>,
+    nodes_config: &rocket::State<NodesConfig>,
+    sentinel_id: &str,
+    format: Option<&str>,
+    break_glass_input: Json<BreakGlassInput>,
+    addr: ClientIp,
+) -> Result<Json<SentinelOutput>, CustomError> {
+    let pool = pool.inner().to_owned();
+    let nodes_config = nodes_config.inner().to_owned();
+    let input = break_glass_input.into_inner();
+    let application_repository: ApplicationRepository = ApplicationContract::new(&pool);
+    let sentinel_service = SentinelService::new(&pool, application_repository, &nodes_config);
+    let sentinel_uuid = match Uuid::parse_str(sentinel_id) {
+        Err(_) => {
+            return Err(ErrorObject::create(
+                Status::BadRequest,
+                Some("Bad Sentinel uuid"),
+            ))
+        }
+        Ok(uuid) => uuid,
+    };
+    let format = match KeyEncoding::export_format(format, &key_encryption_key.key) {
+        Err(e) => return Err(ErrorObject::create(Status::BadRequest, Some(e))),
+        Ok(format) => format,
+    };
+    if !authorised.check_roles(Role::BREAKGLASS) {
+        return Err(ErrorObject::create(Status::Unauthorized, None));
+    }
+    let res = sentinel_service.break_glass(
+        sentinel_uuid,
+        &input.justification,
+        authorised.user.clone(),
+        &addr.ip(),
+    );
+    let sentinel_id = sentinel_id.to_string();
+    let success = res.is_ok();
+    spawn(async move {
+        let _ = SentinelLogService::new_sentinel_log(
+            sentinel_id,
+            &authorised.user,
+            success,
+            &addr.ip().to_string(),
+        )
+        .await;
+    });
+    match res {
+        Err((status, msg)) => Err(ErrorObject::create(status, msg)),
+        Ok((sentinel, cipher)) => {
+            match SentinelOutput::formatted(sentinel, cipher, &format, key_encryption_key.key) {
+                Err(e) => Err(ErrorObject::create(Status::BadRequest, Some(e))),
+                Ok(output) => Ok(Json(output)),
+            }
+        }
+    }
+}
+
+/// # Acknowledge a Break-Glass Read of a Sentinel
+///
+/// Allows users with `ROLE_ADMIN` to clear the break-glass flag of a sentinel of their application once the access was reviewed. The acknowledgement is audited.
+///
+/// ## Roles
+///
+/// - `ROLE_ADMIN`
+///
+/// ## Parameters
+///
+/// - `sentinel_id`: A string representing the UUID of the sentinel.
+///
+#[openapi(tag = "Sentinels")]
+#[post("/sentinels/<sentinel_id>/break_glass/acknowledge")]
+pub async fn acknowledge_break_glass(
+    authorised: Security,
+    pool: &rocket::State<DbPool>,
+    nodes_config: &rocket::State<NodesConfig>,
+    sentinel_id: &str,
+    addr: ClientIp,
+) -> Result<Status, CustomError> {
+    let pool = pool.inner().to_owned();
+    let nodes_config = nodes_config.inner().to_owned();
+    let application_repository: ApplicationRepository = ApplicationContract::new(&pool);
+    let sentinel_service = SentinelService::new(&pool, application_repository, &nodes_config);
+    let sentinel_uuid = match Uuid::parse_str(sentinel_id) {
+        Err(_) => {
+            return Err(ErrorObject::create(
+                Status::BadRequest,
+                Some("Bad Sentinel uuid"),
+            ))
+        }
+        Ok(uuid) => uuid,
+    };
+    match authorised.check_roles(Role::ADMIN) {
+        false => Err(ErrorObject::create(Status::Unauthorized, None)),
+        true => match sentinel_service.acknowledge_break_glass(
+            sentinel_uuid,
+            authorised.user,
+            &addr.ip(),
+        ) {
+            Ok(()) => Ok(Status::NoContent),
+            Err((status, msg)) => Err(ErrorObject::create(status, msg)),
+        },
+    }
+}
+
+/// # Create a Share Link for a Sentinel
+///
+/// Allows users with `ROLE_USER` to create a one-time (or N-use) link releasing the key of an `encryption` sentinel they can read, without authentication, until it expires.
+/// The token is only returned in this response; the server stores its hash. Keys under dual control can not be shared, and the usage policy of the sentinel is checked again against the address of the redeemer.
+///
+/// ## Roles
+///
+/// - `ROLE_USER`
+///
+/// ## Parameters
+///
+/// - `sentinel_id`: A string representing the UUID of the sentinel to be shared.
+///
+/// - `expires_in`: An optional number representing the validity of the link in minutes (60 by default, at most `SHARE_LINK_MAX_TTL`).
+///
+/// - `max_uses`: An optional number representing how many times the link can be redeemed, between 1 (default) and 100.
+///
+/// - `recipient_public_key`: An optional hex ML-KEM-768 public key; when set, the key is only released encapsulated to it.
+///
+#[openapi(tag = "Sentinels")]
+#[post("/sentinels/<sentinel_id>/share_links", format = "json", data = "<share_link_input>")]
+pub async fn create_share_link(
+    authorised: Security,
+    pool: &rocket::State<DbPool>,
+    nodes_config: &rocket::State<NodesConfig>,
+    sentinel_id: &str,
+    share_link_input: Json<ShareLinkInput>,
+    addr: ClientIp,
+) -> Result<Json<ShareLinkOutput>, CustomError> {
+    let pool = pool.inner().to_owned();
+    let nodes_config = nodes_config.inner().to_owned();
+    let input = share_link_input.into_inner();
+    let application_repository: ApplicationRepository = ApplicationContract::new(&pool);
+    let sentinel_service = SentinelService::new(&pool, application_repository, &nodes_config);
+    let sentinel_uuid = match Uuid::parse_str(sentinel_id) {
+        Err(_) => {
+            return Err(ErrorObject::create(
+                Status::BadRequest,
+                Some("Bad Sentinel uuid"),
+            ))
+        }
+        Ok(uuid) => uuid,
+    };
+    match authorised.check_roles(Role::USER) {
+        false => Err(ErrorObject::create(Status::Unauthorized, None)),
+        true => match sentinel_service.create_share_link(
+            sentinel_uuid,
+            input,
+            authorised.user,
+            &addr.ip(),
+        ) {
+            Ok((share_link, token)) => Ok(Json(ShareLinkOutput::new(share_link, token))),
+            Err((status, msg)) => Err(ErrorObject::create(status, msg)),
+        },
+    }
+}
+
+/// # Revoke a Share Link of a Sentinel
+///
+/// Allows the creator of a share link, or an admin, to invalidate it before it expires.
+///
+/// ## Roles
+///
+/// - `ROLE_USER`
+///
+/// - `ROLE_ADMIN`
+///
+/// ## Parameters
+///
+/// - `sentinel_id`: A string representing the UUID of the sentinel.
+///
+/// - `share_link_id`: A string representing the UUID of the share link to be revoked.
+///
+#[openapi(tag = "Sentinels")]
+#[delete("/sentinels/<sentinel_id>/share_links/<share_link_id>")]
+pub async fn revoke_share_link(
+    authorised: Security,
+    pool: &rocket::State<DbPool>,
+    nodes_config: &rocket::State<NodesConfig>,
+    sentinel_id: &str,
+    share_link_id: &str,
+) -> Result<Status, CustomError> {
+    let pool = pool.inner().to_owned();
+    let nodes_config = nodes_config.inner().to_owned();
+    let application_repository: ApplicationRepository = ApplicationContract::new(&pool);
+    let sentinel_service = SentinelService::new(&pool, application_repository, &nodes_config);
+    let (sentinel_uuid, share_link_uuid) =
+        match (Uuid::parse_str(sentinel_id), Uuid::parse_str(share_link_id)) {
+            (Err(_), _) => {
+                return Err(ErrorObject::create(
+                    Status::BadRequest,
+                    Some("Bad Sentinel uuid"),
+                ))
+            }
+            (_, Err(_)) => {
+                return Err(ErrorObject::create(
+                    Status::BadRequest,
+                    Some("Bad Share link uuid"),
+                ))
+            }
+            (Ok(sentinel_uuid), Ok(share_link_uuid)) => (sentinel_uuid, share_link_uuid),
+        };
+    match authorised.check_roles(Role::USER) || authorised.check_roles(Role::ADMIN) {
+        false => Err(ErrorObject::create(Status::Unauthorized, None)),
+        true => match sentinel_service.revoke_share_link(
             sentinel_uuid,
+            share_link_uuid,
             authorised.clone().user,
             authorised.check_roles(Role::ADMIN),
         ) {
@@ -0,0 +1,225 @@
+use crate::dto::service_account::service_account_credentials_input::ServiceAccountCredentialsInput;
+use crate::dto::service_account::service_account_input::ServiceAccountInput;
+use crate::dto::service_account::service_account_output::ServiceAccountOutput;
+use crate::dto::service_account::service_account_signing_key_input::ServiceAccountSigningKeyInput;
+use crate::enums::roles::Role;
+use crate::guards::security::Security;
+use crate::services::service_account::ServiceAccountService;
+use rocket::http::Status;
+use rocket::post;
+use rocket::serde::json::Json;
+use rocket_okapi::openapi;
+use uuid::Uuid;
+
+use crate::core::errors::{CustomError, ErrorObject};
+use crate::db::connect::DbPool;
+
+/// # Create Service Account
+///
+/// Allows users with `ROLE_ADMIN` to create a service account, a machine identity of their application.
+/// The account authenticates on `POST /oauth/token` with the `client_credentials` grant and gets the cluster memberships of its `user_id`.
+/// Without a public key a client secret is generated, it is only returned by this call.
+///
+/// ## Roles
+///
+/// - `ROLE_ADMIN`
+///
+/// ## Parameters
+///
+/// - `name`: A string representing the name of the service account.
+///
+/// - `public_key`: An optional PEM public key (RSA, EC or Ed25519) checking the `private_key_jwt` client assertions.
+///
+#[openapi(tag = "Service_Accounts")]
+#[post("/service_accounts", format = "json", data = "<service_account_input>")]
+pub async fn create(
+    authorised: Security,
+    pool: &rocket::State<DbPool>,
+    service_account_input: Json<ServiceAccountInput>,
+) -> Result<Json<ServiceAccountOutput>, CustomError> {
+    let pool = pool.inner().to_owned();
+    let service_account_service = ServiceAccountService::new(&pool);
+    match authorised.check_roles(Role::ADMIN) {
+        false => Err(ErrorObject::create(Status::Unauthorized, None)),
+        true => match service_account_service
+            .create(service_account_input.into_inner(), &authorised.user)
+        {
+            Ok((service_account, secret)) => {
+                Ok(Json(ServiceAccountOutput::new(service_account, secret)))
+            }
+            Err((status, msg)) => Err(ErrorObject::create(status, msg)),
+        },
+    }
+}
+
+/// # List Service Accounts
+///
+/// Allows users with `ROLE_ADMIN` to list the service accounts of their application.
+///
+/// ## Roles
+///
+/// - `ROLE_ADMIN`
+///
+#[openapi(tag = "Service_Accounts")]
+#[get("/service_accounts")]
+pub async fn get_all(
+    authorised: Security,
+    pool: &rocket::State<DbPool>,
+) -> Result<Json<Vec<ServiceAccountOutput>>, CustomError> {
+    let pool = pool.inner().to_owned();
+    let service_account_service = ServiceAccountService::new(&pool);
+    match authorised.check_roles(Role::ADMIN) {
+        false => Err(ErrorObject::create(Status::Unauthorized, None)),
+        true => Ok(Json(
+            service_account_service
+                .get_all(&authorised.user)
+                .into_iter()
+                .map(|service_account| ServiceAccountOutput::new(service_account, None))
+                .collect(),
+        )),
+    }
+}
+
+/// # Rotate Service Account Credentials
+///
+/// Allows users with `ROLE_ADMIN` to replace the credentials of a service account. The previous secret or public key stops working immediately.
+/// Without a public key a new client secret is generated, it is only returned by this call.
+///
+/// ## Roles
+///
+/// - `ROLE_ADMIN`
+///
+/// ## Parameters
+///
+/// - `service_account_id`: A string representing the UUID of the service account.
+///
+/// - `public_key`: An optional PEM public key (RSA, EC or Ed25519) replacing the client secret.
+///
+#[openapi(tag = "Service_Accounts")]
+#[post(
+    "/service_accounts/<service_account_id>/credentials",
+    format = "json",
+    data = "<credentials_input>"
+)]
+pub async fn rotate_credentials(
+    authorised: Security,
+    pool: &rocket::State<DbPool>,
+    service_account_id: &str,
+    credentials_input: Json<ServiceAccountCredentialsInput>,
+) -> Result<Json<ServiceAccountOutput>, CustomError> {
+    let pool = pool.inner().to_owned();
+    let service_account_service = ServiceAccountService::new(&pool);
+    let service_account_uuid = match Uuid::parse_str(service_account_id) {
+        Err(_) => {
+            return Err(ErrorObject::create(
+                Status::BadRequest,
+                Some("Bad Service account uuid"),
+            ))
+        }
+        Ok(uuid) => uuid,
+    };
+    match authorised.check_roles(Role::ADMIN) {
+        false => Err(ErrorObject::create(Status::Unauthorized, None)),
+        true => match service_account_service.rotate_credentials(
+            &service_account_uuid,
+            credentials_input.into_inner(),
+            &authorised.user,
+        ) {
+            Ok((service_account, secret)) => {
+                Ok(Json(ServiceAccountOutput::new(service_account, secret)))
+            }
+            Err((status, msg)) => Err(ErrorObject::create(status, msg)),
+        },
+    }
+}
+
+/// # Set Service Account Signing Key
+///
+/// Allows users with `ROLE_ADMIN` to register the public key a service account signs its requests with, in place of a bearer token.
+/// The key operation endpoints (read, HMAC, delete) accept the requests signed with HTTP message signatures (RFC 9421): the `Signature-Input` names the `client_id` of the account as its `keyid` and covers at least `@method`, `@path` and `date`, with `@query` when the URL has a query and `content-digest` (RFC 9530, `sha-256` or `sha-512`) when the request has a body.
+/// A signature is accepted once, within `SIGNATURE_MAX_AGE` seconds of its `Date`. The streams do not accept signed requests.
+///
+/// ## Roles
+///
+/// - `ROLE_ADMIN`
+///
+/// ## Parameters
+///
+/// - `service_account_id`: A string representing the UUID of the service account.
+///
+/// - `signing_key`: An optional PEM public key (RSA, EC P-256 or P-384, or Ed25519), none to stop accepting the signed requests.
+///
+#[openapi(tag = "Service_Accounts")]
+#[put(
+    "/service_accounts/<service_account_id>/signing_key",
+    format = "json",
+    data = "<signing_key_input>"
+)]
+pub async fn set_signing_key(
+    authorised: Security,
+    pool: &rocket::State<DbPool>,
+    service_account_id: &str,
+    signing_key_input: Json<ServiceAccountSigningKeyInput>,
+) -> Result<Json<ServiceAccountOutput>, CustomError> {
+    let pool = pool.inner().to_owned();
+    let service_account_service = ServiceAccountService::new(&pool);
+    let service_account_uuid = match Uuid::parse_str(service_account_id) {
+        Err(_) => {
+            return Err(ErrorObject::create(
+                Status::BadRequest,
+                Some("Bad Service account uuid"),
+            ))
+        }
+        Ok(uuid) => uuid,
+    };
+    match authorised.check_roles(Role::ADMIN) {
+        false => Err(ErrorObject::create(Status::Unauthorized, None)),
+        true => match service_account_service.set_signing_key(
+            &service_account_uuid,
+            signing_key_input.into_inner(),
+            &authorised.user,
+        ) {
+            Ok(service_account) => Ok(Json(ServiceAccountOutput::new(service_account, None))),
+            Err((status, msg)) => Err(ErrorObject::create(status, msg)),
+        },
+    }
+}
+
+/// # Delete Service Account
+///
+/// Allows users with `ROLE_ADMIN` to delete a service account with its identity and cluster memberships. Its credentials stop working immediately.
+///
+/// ## Roles
+///
+/// - `ROLE_ADMIN`
+///
+/// ## Parameters
+///
+/// - `service_account_id`: A string representing the UUID of the service account.
+///
+#[openapi(tag = "Service_Accounts")]
+#[delete("/service_accounts/<service_account_id>")]
+pub async fn delete_by_id(
+    authorised: Security,
+    pool: &rocket::State<DbPool>,
+    service_account_id: &str,
+) -> Result<Status, CustomError> {
+    let pool = pool.inner().to_owned();
+    let service_account_service = ServiceAccountService::new(&pool);
+    let service_account_uuid = match Uuid::parse_str(service_account_id) {
+        Err(_) => {
+            return Err(ErrorObject::create(
+                Status::BadRequest,
+                Some("Bad Service account uuid"),
+            ))
+        }
+        Ok(uuid) => uuid,
+    };
+    match authorised.check_roles(Role::ADMIN) {
+        false => Err(ErrorObject::create(Status::Unauthorized, None)),
+        true => match service_account_service.delete(&service_account_uuid, &authorised.user) {
+            Ok(()) => Ok(Status::NoContent),
+            Err((status, msg)) => Err(ErrorObject::create(status, msg)),
+        },
+    }
+}
@@ -0,0 +1,155 @@
+use crate::dto::session::session_output::SessionOutput;
+use crate::enums::roles::Role;
+use crate::guards::client_ip::ClientIp;
+use crate::guards::security::Security;
+use crate::services::session::SessionService;
+use rocket::http::Status;
+use rocket::serde::json::Json;
+use rocket_okapi::openapi;
+use uuid::Uuid;
+
+use crate::core::errors::{CustomError, ErrorObject};
+use crate::db::connect::DbPool;
+
+/// # List My Sessions
+///
+/// Lists the active sessions of the authenticated user, a session being a login and its refreshes. Each session comes with the IP, fingerprint and device of its login,
+/// the device, operating system and browser being parsed from the user agent, its creation, its latest refresh and the session of the calling token is flagged `current`.
+///
+/// ## Roles
+///
+/// - `ROLE_USER`
+///
+#[openapi(tag = "Sessions")]
+#[get("/users/me/sessions")]
+pub async fn get_mine(
+    authorised: Security,
+    pool: &rocket::State<DbPool>,
+) -> Result<Json<Vec<SessionOutput>>, CustomError> {
+    let pool = pool.inner().to_owned();
+    get_sessions(&pool, &authorised.user.id, &authorised)
+}
+
+/// # Revoke My Session
+///
+/// Ends a session of the authenticated user, its access and refresh tokens are refused immediately.
+///
+/// ## Roles
+///
+/// - `ROLE_USER`
+///
+/// ## Parameters
+///
+/// - `session_id`: A string representing the UUID of the session.
+///
+#[openapi(tag = "Sessions")]
+#[delete("/users/me/sessions/<session_id>")]
+pub async fn revoke_mine(
+    authorised: Security,
+    pool: &rocket::State<DbPool>,
+    session_id: &str,
+    addr: ClientIp,
+) -> Result<Status, CustomError> {
+    let pool = pool.inner().to_owned();
+    revoke_session(&pool, &authorised.user.id, session_id, &authorised, addr)
+}
+
+/// # List User Sessions
+///
+/// Allows users with `ROLE_ADMIN` to list the active sessions of a user of their application, with the same details as `GET /users/me/sessions`.
+///
+/// ## Roles
+///
+/// - `ROLE_ADMIN`
+///
+/// ## Parameters
+///
+/// - `user_id`: A string representing the UUID of the user.
+///
+#[openapi(tag = "Sessions")]
+#[get("/users/<user_id>/sessions", rank = 2)]
+pub async fn get_by_user(
+    authorised: Security,
+    pool: &rocket::State<DbPool>,
+    user_id: &str,
+) -> Result<Json<Vec<SessionOutput>>, CustomError> {
+    let pool = pool.inner().to_owned();
+    let user_uuid = match Uuid::parse_str(user_id) {
+        Err(_) => return Err(ErrorObject::create(Status::BadRequest, Some("Bad user uuid"))),
+        Ok(uuid) => uuid,
+    };
+    match authorised.check_roles(Role::ADMIN) {
+        false => Err(ErrorObject::create(Status::Unauthorized, None)),
+        true => get_sessions(&pool, &user_uuid, &authorised),
+    }
+}
+
+/// # Revoke User Session
+///
+/// Allows users with `ROLE_ADMIN` to end a session of a user of their application, its access and refresh tokens are refused immediately.
+/// The revocation is audited.
+///
+/// ## Roles
+///
+/// - `ROLE_ADMIN`
+///
+/// ## Parameters
+///
+/// - `user_id`: A string representing the UUID of the user.
+///
+/// - `session_id`: A string representing the UUID of the session.
+///
+#[openapi(tag = "Sessions")]
+#[delete("/users/<user_id>/sessions/<session_id>", rank = 2)]
+pub async fn revoke_by_user(
+    authorised: Security,
+    pool: &rocket::State<DbPool>,
+    user_id: &str,
+    session_id: &str,
+    addr: ClientIp,
+) -> Result<Status, CustomError> {
+    let pool = pool.inner().to_owned();
+    let user_uuid = match Uuid::parse_str(user_id) {
+        Err(_) => return Err(ErrorObject::create(Status::BadRequest, Some("Bad user uuid"))),
+        Ok(uuid) => uuid,
+    };
+    match authorised.check_roles(Role::ADMIN) {
+        false => Err(ErrorObject::create(Status::Unauthorized, None)),
+        true => revoke_session(&pool, &user_uuid, session_id, &authorised, addr),
+    }
+}
+
+fn get_sessions(
+    pool: &DbPool,
+    user_uuid: &Uuid,
+    authorised: &Security,
+) -> Result<Json<Vec<SessionOutput>>, CustomError> {
+    match SessionService::new(pool).get_all(user_uuid, &authorised.user) {
+        Err((status, msg)) => Err(ErrorObject::create(status, msg)),
+        Ok(sessions) => Ok(Json(
+            sessions
+                .into_iter()
+                .map(|(session, connexion)| {
+                    SessionOutput::new(session, connexion, authorised.session_id)
+                })
+                .collect(),
+        )),
+    }
+}
+
+fn revoke_session(
+    pool: &DbPool,
+    user_uuid: &Uuid,
+    session_id: &str,
+    authorised: &Security,
+    addr: ClientIp,
+) -> Result<Status, CustomError> {
+    let session_uuid = match Uuid::parse_str(session_id) {
+        Err(_) => return Err(ErrorObject::create(Status::BadRequest, Some("Bad session uuid"))),
+        Ok(uuid) => uuid,
+    };
+    match SessionService::new(pool).revoke(&session_uuid, user_uuid, &authorised.user, &addr.ip()) {
+        Err((status, msg)) => Err(ErrorObject::create(status, msg)),
+        Ok(()) => Ok(Status::NoContent),
+    }
+}
@@ -0,0 +1,42 @@
+use crate::core::nodes_config::NodesConfig;
+use crate::dto::share_link::share_link_redeem_output::ShareLinkRedeemOutput;
+use crate::repositories::application::ApplicationRepository;
+use crate::services::sentinel::SentinelService;
+use crate::traits::application::ApplicationContract;
+use rocket::serde::json::Json;
+use rocket_okapi::openapi;
+use crate::guards::client_ip::ClientIp;
+
+use crate::core::errors::{CustomError, ErrorObject};
+use crate::db::connect::DbPool;
+
+/// # Redeem a Share Link
+///
+/// This Endpoint releases the key of a share link without authentication. Each call uses the link once, including the calls refused by the usage policy of the sentinel.
+/// When the link is bound to a recipient ML-KEM-768 public key, the key is returned encapsulated to it: decapsulate `encapsulated_key` and decrypt `wrapped_key` with AES-256-GCM under the shared secret and `nonce`.
+///
+/// ## Roles
+///
+/// - `PUBLIC`
+///
+/// ## Parameters
+///
+/// - `token`: A string representing the token of the share link.
+///
+#[openapi(tag = "Share_Links")]
+#[get("/share/<token>")]
+pub async fn redeem(
+    pool: &rocket::State<DbPool>,
+    nodes_config: &rocket::State<NodesConfig>,
+    token: &str,
+    addr: ClientIp,
+) -> Result<Json<ShareLinkRedeemOutput>, CustomError> {
+    let pool = pool.inner().to_owned();
+    let nodes_config = nodes_config.inner().to_owned();
+    let application_repository: ApplicationRepository = ApplicationContract::new(&pool);
+    let sentinel_service = SentinelService::new(&pool, application_repository, &nodes_config);
+    match sentinel_service.redeem_share_link(token, &addr.ip()) {
+        Ok(output) => Ok(Json(output)),
+        Err((status, msg)) => Err(ErrorObject::create(status, msg)),
+    }
+}
@@ -1,4 +1,5 @@
 use rocket::post;
+use crate::guards::client_ip::ClientIp;
 use rocket::{http::Status, serde::json::Json};
 use rocket_okapi::openapi;
 use uuid::Uuid;
@@ -6,12 +7,15 @@ use uuid::Uuid;
 use crate::core::errors::{CustomError, ErrorObject};
 use crate::db::connect::DbPool;
 use crate::dto::user::user_2fa_activate_input::User2faActivateInput;
+use crate::dto::user::user_break_glass_input::UserBreakGlassInput;
 use crate::dto::user::user_forget_input::UserForgetInput;
 use crate::dto::user::user_input::UserInput;
 use crate::dto::user::user_output::UserOutput;
 use crate::dto::user::user_password_input::UserPasswordInput;
 use crate::dto::user::user_public_input::UserPublicInput;
+use crate::dto::user::user_recovery_codes::UserRecoveryCodes;
 use crate::dto::user::user_reset_input::UserResetInput;
+use crate::dto::user::user_restricted_ip_input::UserRestrictedIpInput;
 use crate::dto::user::user_totp_code::UserTotpCode;
 use crate::dto::user::user_validation_input::UserValidationInput;
 use crate::enums::roles::Role;
@@ -128,6 +132,8 @@ pub async fn post_user(
 ///
 /// This endpoint requires authentication and the `ROLE_VALIDATION` role. It accepts a validation code to validate a user account.
 ///
+/// A code failing three times is replaced by a new one sent by email. Too many failures lock the validation a while and answer `429 Too Many Requests`.
+///
 /// If the validation is successful, it returns basic information about the user.
 ///
 /// ## Roles
@@ -147,6 +153,7 @@ pub async fn post_user(
 pub async fn validate_user(
     authorised: Security,
     pool: &rocket::State<DbPool>,
+    addr: ClientIp,
     user_validation_input: Json<UserValidationInput>,
 ) -> Result<Json<UserOutput>, CustomError> {
     let pool = pool.inner().to_owned();
@@ -160,7 +167,7 @@ pub async fn validate_user(
             Some("Only user with ROLE_VALIDATION role"),
         )),
         true => match user_service
-            .validate_user(&authorised.user, &input.validation_code)
+            .validate_user(&authorised.user, &input.validation_code, &addr.ip())
             .await
         {
             Err(status) => {
@@ -224,6 +231,8 @@ pub async fn forget_user_password(
 /// It accepts the user's login, application ID,
 /// validation code, and the new password to reset the user's password.
 ///
+/// The code can only be used once. After three failures a new code must be requested, and too many failures for the login or from the IP lock the reset a while with `429 Too Many Requests`.
+///
 /// ## Roles
 ///
 /// - `PUBLIC`
@@ -246,6 +255,7 @@ pub async fn forget_user_password(
 )]
 pub async fn reset_user_password(
     pool: &rocket::State<DbPool>,
+    addr: ClientIp,
     user_reset_input: Json<UserResetInput>,
 ) -> Result<Json<UserOutput>, CustomError> {
     let pool = pool.inner().to_owned();
@@ -258,6 +268,7 @@ pub async fn reset_user_password(
         input.application_id,
         input.code,
         input.new_password,
+        &addr.ip(),
     ) {
         Err(status) => Err(ErrorObject::create(status, None)),
         Ok(user) => Ok(Json(UserOutput::new(user))),
@@ -372,6 +383,110 @@ pub async fn delete_user(
     }
 }
 
+/// # Set the Break-Glass Role of a User
+///
+/// Allows users with `ROLE_ADMIN` to grant or revoke `ROLE_BREAK_GLASS` to a user of their application. A user with this role can read any key of the application
+/// through the break-glass endpoints, each read being audited and alerted to the admins. The change of role is audited.
+///
+/// ## Roles
+///
+/// - `ROLE_ADMIN`
+///
+/// ## Parameters
+///
+/// - `user_id`: A string representing the UUID of the user.
+///
+/// - `enabled`: A boolean, `true` to grant the role and `false` to revoke it.
+///
+#[openapi(tag = "Users")]
+#[put("/users/<user_id>/break_glass", format = "json", data = "<user_break_glass_input>")]
+pub async fn set_break_glass(
+    pool: &rocket::State<DbPool>,
+    authorised: Security,
+    user_id: String,
+    user_break_glass_input: Json<UserBreakGlassInput>,
+    addr: ClientIp,
+) -> Result<Json<UserOutput>, CustomError> {
+    let pool = pool.inner().to_owned();
+    let input = user_break_glass_input.into_inner();
+    let application_repository: ApplicationRepository = ApplicationContract::new(&pool);
+    let connexion_repository: ConnexionRepository = ConnexionContract::new(&pool);
+    let user_service = UserService::new(&pool, application_repository, connexion_repository);
+    let user_to_uuid = match Uuid::parse_str(&user_id) {
+        Err(_) => {
+            return Err(ErrorObject::create(
+                Status::BadRequest,
+                Some("Bad user uuid"),
+            ))
+        }
+        Ok(uuid) => uuid,
+    };
+    match authorised.check_roles(Role::ADMIN) {
+        false => Err(ErrorObject::create(Status::Unauthorized, None)),
+        true => match user_service.set_break_glass(
+            user_to_uuid,
+            input.enabled,
+            authorised.user,
+            &addr.ip(),
+        ) {
+            Err(status) => Err(ErrorObject::create(status, None)),
+            Ok(user) => Ok(Json(UserOutput::new(user))),
+        },
+    }
+}
+
+/// # Set the Restricted IPs of a User
+///
+/// Allows users with `ROLE_ADMIN` to restrict a user of their application to some IP addresses or CIDR ranges, IPv4 or IPv6. The user can then only log in and call the API from these IPs,
+/// and from the allowlist of the application when it has one. The denials and the change of restriction are audited.
+///
+/// ## Roles
+///
+/// - `ROLE_ADMIN`
+///
+/// ## Parameters
+///
+/// - `user_id`: A string representing the UUID of the user.
+///
+/// - `restricted_ip`: An array of IP addresses or CIDR ranges (`10.0.0.0/8`, `2001:db8::/32`), an empty array allows every IP.
+///
+#[openapi(tag = "Users")]
+#[put("/users/<user_id>/restricted_ip", format = "json", data = "<user_restricted_ip_input>")]
+pub async fn set_restricted_ip(
+    pool: &rocket::State<DbPool>,
+    authorised: Security,
+    user_id: String,
+    user_restricted_ip_input: Json<UserRestrictedIpInput>,
+    addr: ClientIp,
+) -> Result<Json<UserOutput>, CustomError> {
+    let pool = pool.inner().to_owned();
+    let input = user_restricted_ip_input.into_inner();
+    let application_repository: ApplicationRepository = ApplicationContract::new(&pool);
+    let connexion_repository: ConnexionRepository = ConnexionContract::new(&pool);
+    let user_service = UserService::new(&pool, application_repository, connexion_repository);
+    let user_to_uuid = match Uuid::parse_str(&user_id) {
+        Err(_) => {
+            return Err(ErrorObject::create(
+                Status::BadRequest,
+                Some("Bad user uuid"),
+            ))
+        }
+        Ok(uuid) => uuid,
+    };
+    match authorised.check_roles(Role::ADMIN) {
+        false => Err(ErrorObject::create(Status::Unauthorized, None)),
+        true => match user_service.set_restricted_ip(
+            user_to_uuid,
+            input.restricted_ip,
+            authorised.user,
+            &addr.ip(),
+        ) {
+            Err(status) => Err(ErrorObject::create(status, None)),
+            Ok(user) => Ok(Json(UserOutput::new(user))),
+        },
+    }
+}
+
 /// # Get 2FA Code
 ///
 /// If 2FA is not enabled, this endpoint generates and returns a unique url to activate 2FA.
@@ -420,7 +535,9 @@ pub async fn get_2fa_code(
 /// This endpoint requires authentication.
 ///
 /// It accepts a TOTP code and a flag to activate or deactivate 2FA
-/// for the user's account.
+/// for the user's account. The activation returns the one-time recovery
+/// codes, usable in place of a TOTP code. They are only shown once and
+/// replace the previous ones.
 ///
 /// ## Roles
 ///
@@ -432,7 +549,7 @@ pub async fn get_2fa_code(
 ///
 /// ## Parameters
 ///
-/// - `code`: A string representing the TOTP code.
+/// - `code`: A string representing the TOTP code, or a recovery code to deactivate 2FA.
 ///       
 /// - `is_activate`: A boolean indicating whether to activate or deactivate 2FA.
 ///
@@ -445,8 +562,9 @@ pub async fn get_2fa_code(
 pub async fn activate_2fa(
     authorised: Security,
     pool: &rocket::State<DbPool>,
+    addr: ClientIp,
     user_2fa_activate_input: Json<User2faActivateInput>,
-) -> Result<Status, CustomError> {
+) -> Result<Json<UserRecoveryCodes>, CustomError> {
     let pool = pool.inner().to_owned();
     let application_repository: ApplicationRepository = ApplicationContract::new(&pool);
     let connexion_repository: ConnexionRepository = ConnexionContract::new(&pool);
@@ -455,8 +573,8 @@ pub async fn activate_2fa(
     let code = user_2fa_activate_input.clone().into_inner().code;
     let activate = user_2fa_activate_input.into_inner().is_activate;
 
-    match user_service.activate_2fa(code, secret_key, authorised.user, activate) {
-        Ok(_) => Ok(Status::Created),
+    match user_service.activate_2fa(code, secret_key, authorised.user, activate, &addr.ip()) {
+        Ok(recovery_codes) => Ok(Json(recovery_codes)),
         Err(status) => Err(ErrorObject::create(status, None)),
     }
 }
@@ -0,0 +1,255 @@
+use rocket::http::Status;
+use rocket::post;
+use rocket::serde::json::Json;
+use rocket_okapi::openapi;
+use crate::guards::client_ip::ClientIp;
+use crate::guards::dpop::DpopHeader;
+use uuid::Uuid;
+
+use crate::core::errors::{CustomError, ErrorObject};
+use crate::db::connect::DbPool;
+use crate::dto::auth::auth_output::AuthOutput;
+use crate::dto::webauthn::webauthn_assertion_options::WebauthnAssertionOptions;
+use crate::dto::webauthn::webauthn_assertion_start_input::WebauthnAssertionStartInput;
+use crate::dto::webauthn::webauthn_credential_output::WebauthnCredentialOutput;
+use crate::dto::webauthn::webauthn_login_input::WebauthnLoginInput;
+use crate::dto::webauthn::webauthn_registration_input::WebauthnRegistrationInput;
+use crate::dto::webauthn::webauthn_registration_options::WebauthnRegistrationOptions;
+use crate::enums::roles::Role;
+use crate::guards::security::Security;
+use crate::guards::user_agent::UserAgent;
+use crate::repositories::application::ApplicationRepository;
+use crate::repositories::connexion::ConnexionRepository;
+use crate::repositories::revoked_token::RevokedTokenRepository;
+use crate::services::application::ApplicationService;
+use crate::services::auth::AuthService;
+use crate::services::connexion::ConnexionService;
+use crate::services::licence::LicenceService;
+use crate::services::user::UserService;
+use crate::services::webauthn::WebauthnService;
+use crate::traits::application::ApplicationContract;
+use crate::traits::connexion::ConnexionContract;
+use crate::traits::revoked_token::RevokedTokenContract;
+use crate::utils::jwt::SessionClaims;
+use crate::utils::open_id::Authentication;
+use crate::LICENSE_VALID;
+
+/// # Start Passkey Registration
+///
+/// Starts the registration of a passkey (WebAuthn credential) for the authenticated user. Returns the `publicKey` options of `navigator.credentials.create()`,
+/// the binary values being url safe base64. The challenge is valid for 5 minutes.
+///
+/// ## Roles
+///
+/// - `ROLE_USER`
+///
+#[openapi(tag = "Webauthn")]
+#[post("/users/webauthn/register/start")]
+pub async fn register_start(
+    authorised: Security,
+    pool: &rocket::State<DbPool>,
+) -> Result<Json<WebauthnRegistrationOptions>, CustomError> {
+    let pool = pool.inner().to_owned();
+    if !authorised.check_roles(Role::USER) {
+        return Err(ErrorObject::create(Status::Unauthorized, None));
+    }
+    match WebauthnService::new(&pool).registration_options(&authorised.user) {
+        Err((status, msg)) => Err(ErrorObject::create(status, msg)),
+        Ok(options) => Ok(Json(options)),
+    }
+}
+
+/// # Finish Passkey Registration
+///
+/// Stores the passkey created by the authenticator for the challenge of `POST /users/webauthn/register/start`. ES256, EdDSA and RS256 keys are accepted.
+///
+/// ## Roles
+///
+/// - `ROLE_USER`
+///
+/// ## Parameters
+///
+/// - `name`: An optional string naming the passkey (`Passkey` by default).
+///
+/// - `id`: A string representing the url safe base64 id of the credential.
+///
+/// - `client_data_json`: A string representing the url safe base64 `clientDataJSON` of the response.
+///
+/// - `attestation_object`: A string representing the url safe base64 `attestationObject` of the response.
+///
+#[openapi(tag = "Webauthn")]
+#[post("/users/webauthn/register/finish", format = "json", data = "<registration_input>")]
+pub async fn register_finish(
+    authorised: Security,
+    pool: &rocket::State<DbPool>,
+    registration_input: Json<WebauthnRegistrationInput>,
+) -> Result<Json<WebauthnCredentialOutput>, CustomError> {
+    let pool = pool.inner().to_owned();
+    if !authorised.check_roles(Role::USER) {
+        return Err(ErrorObject::create(Status::Unauthorized, None));
+    }
+    match WebauthnService::new(&pool).register(&authorised.user, registration_input.into_inner()) {
+        Err((status, msg)) => Err(ErrorObject::create(status, msg)),
+        Ok(credential) => Ok(Json(WebauthnCredentialOutput::new(credential))),
+    }
+}
+
+/// # List My Passkeys
+///
+/// Lists the passkeys of the authenticated user.
+///
+/// ## Roles
+///
+/// - `ROLE_USER`
+///
+#[openapi(tag = "Webauthn")]
+#[get("/users/webauthn/credentials")]
+pub async fn get_credentials(
+    authorised: Security,
+    pool: &rocket::State<DbPool>,
+) -> Result<Json<Vec<WebauthnCredentialOutput>>, CustomError> {
+    let pool = pool.inner().to_owned();
+    Ok(Json(
+        WebauthnService::new(&pool)
+            .get_all(&authorised.user)
+            .into_iter()
+            .map(WebauthnCredentialOutput::new)
+            .collect(),
+    ))
+}
+
+/// # Delete My Passkey
+///
+/// Deletes a passkey of the authenticated user, it can no longer log in.
+///
+/// ## Roles
+///
+/// - `ROLE_USER`
+///
+/// ## Parameters
+///
+/// - `credential_id`: A string representing the UUID of the passkey.
+///
+#[openapi(tag = "Webauthn")]
+#[delete("/users/webauthn/credentials/<credential_id>")]
+pub async fn delete_credential(
+    authorised: Security,
+    pool: &rocket::State<DbPool>,
+    credential_id: &str,
+) -> Result<Status, CustomError> {
+    let pool = pool.inner().to_owned();
+    let credential_uuid = match Uuid::parse_str(credential_id) {
+        Err(_) => return Err(ErrorObject::create(Status::BadRequest, Some("Bad passkey uuid"))),
+        Ok(uuid) => uuid,
+    };
+    match WebauthnService::new(&pool).delete(&credential_uuid, &authorised.user) {
+        Err((status, msg)) => Err(ErrorObject::create(status, msg)),
+        Ok(()) => Ok(Status::NoContent),
+    }
+}
+
+/// # Start Passkey Login
+///
+/// Starts a login with a passkey. Returns the `publicKey` options of `navigator.credentials.get()`, the binary values being url safe base64. The challenge is valid for 5 minutes and used once.
+///
+/// With a `login` the passkeys of the user are allowed, for the second factor of `POST /auth`. Without one the authenticator offers its passkeys, for `POST /auth/webauthn/finish`.
+///
+/// ## Roles
+///
+/// - `PUBLIC`
+///
+/// ## Parameters
+///
+/// - `application_id`:  A i32 representing the user's application id.
+///
+/// - `login`: An optional string representing the user's login.
+///
+#[openapi(tag = "Webauthn")]
+#[post("/auth/webauthn/start", format = "json", data = "<assertion_start_input>")]
+pub async fn login_start(
+    pool: &rocket::State<DbPool>,
+    assertion_start_input: Json<WebauthnAssertionStartInput>,
+) -> Result<Json<WebauthnAssertionOptions>, CustomError> {
+    let pool = pool.inner().to_owned();
+    match WebauthnService::new(&pool).assertion_options(assertion_start_input.into_inner()) {
+        Err((status, msg)) => Err(ErrorObject::create(status, msg)),
+        Ok(options) => Ok(Json(options)),
+    }
+}
+
+/// # Passwordless Login
+///
+/// Authenticates a user with a passkey and returns an `access_token` ,`refresh_token` and `open_id`. The authenticator must have verified the user (PIN or biometrics).
+///
+/// ## Roles
+///
+/// - `PUBLIC`
+///
+/// ## Parameters
+///
+/// - `application_id`:  A i32 representing the user's application id.
+///
+/// - `fingerprint`: A string representing the user's unique device identifier.
+///
+/// - `assertion`: The passkey assertion for a challenge of `POST /auth/webauthn/start`: the url safe base64 `id`, `client_data_json`, `authenticator_data`, `signature` and optional `user_handle`.
+///
+/// The `DPoP` header carries a proof for `POST <API_URL>/auth/webauthn/finish` binding the tokens to its key, as on `POST /auth`.
+///
+#[openapi(tag = "Webauthn", ignore = "user_agent_guard")]
+#[post("/auth/webauthn/finish", format = "json", data = "<login_input>")]
+pub async fn login_finish(
+    user_agent_guard: UserAgent,
+    pool: &rocket::State<DbPool>,
+    addr: ClientIp,
+    dpop: DpopHeader,
+    login_input: Json<WebauthnLoginInput>,
+) -> Result<Json<AuthOutput>, CustomError> {
+    let input = login_input.into_inner();
+    let pool = pool.inner().to_owned();
+    let application_repository: ApplicationRepository = ApplicationContract::new(&pool);
+    let app_service = ApplicationService::new(application_repository);
+    let app = match app_service.get_application_by_id(input.application_id).await {
+        Err(status) => return Err(ErrorObject::create(status, Some("Failed to get application"))),
+        Ok(app) => app,
+    };
+    let revoked_repository: RevokedTokenRepository = RevokedTokenContract::new(&pool);
+    let application_repository: ApplicationRepository = ApplicationContract::new(&pool);
+    let connexion_repository: ConnexionRepository = ConnexionContract::new(&pool);
+    let connexion_service = ConnexionService::new(connexion_repository);
+    let connexion_repository: ConnexionRepository = ConnexionContract::new(&pool);
+    let user_service = UserService::new(&pool, application_repository, connexion_repository);
+    let auth_service = AuthService::new(&pool, revoked_repository, user_service, connexion_service);
+    let jkt = auth_service
+        .bind_client(&dpop)
+        .map_err(|(status, msg)| ErrorObject::create(status, msg))?;
+    let ip = addr.ip().to_string();
+    let user_agent = user_agent_guard.user_agent.unwrap_or_default();
+    match auth_service
+        .check_passkey(&app, &input.assertion, &ip, &input.fingerprint, &user_agent)
+        .await
+    {
+        Err((status, msg)) => Err(ErrorObject::create(status, msg)),
+        Ok((user, connexion)) => {
+            let session = auth_service.open_session(&user, &app, Some(&connexion))?;
+            let creds = auth_service
+                .generate_creds(
+                    &user,
+                    &app,
+                    &input.fingerprint,
+                    &user_agent,
+                    &SessionClaims {
+                        jkt: jkt.as_ref(),
+                        authentication: &Authentication::passkey(),
+                        session: &session,
+                    },
+                )
+                .await;
+            let license = LicenceService::new().await.is_valid();
+            {
+                let mut license_valid = LICENSE_VALID.lock().unwrap();
+                *license_valid = license;
+            }
+            Ok(Json(creds))
+        }
+    }
+}
@@ -1,6 +1,6 @@
 use crate::db::connect::DbPool;
 
-use super::{errors, log::LogHandler, nodes_config::NodesConfig};
+use super::{errors, key_ring::KeyRingHandler, log::LogHandler, nodes_config::NodesConfig};
 use std::env;
 
 use rocket::{Build, Rocket};
@@ -38,7 +38,10 @@ impl CoreApi {
             );
         }
 
-        building_rocket = building_rocket.attach(CoreCORS).attach(LogHandler);
+        building_rocket = building_rocket
+            .attach(KeyRingHandler)
+            .attach(CoreCORS)
+            .attach(LogHandler);
         building_rocket
     }
 }
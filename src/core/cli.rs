@@ -4,15 +4,19 @@ use std::process::{self};
 
 use crate::{
     commands::{
-        create_application::CommandCreateApplication, hsm_init::CommandHsmInit, init::CommandInit,
+        backup::CommandBackup, create_application::CommandCreateApplication,
+        export_recovery_kit::CommandExportRecoveryKit, hsm_init::CommandHsmInit,
+        import_recovery_kit::CommandImportRecoveryKit, init::CommandInit, restore::CommandRestore,
+        rotate_signing_key::CommandRotateSigningKey,
     },
+    core::nodes_config::NodesConfig,
     db::connect::DbPool,
 };
 
 pub struct CoreCli;
 
 impl CoreCli {
-    pub async fn exec(args: Vec<String>, pool: DbPool) -> Rocket<Build> {
+    pub async fn exec(args: Vec<String>, pool: DbPool, nodes_config: NodesConfig) -> Rocket<Build> {
         let mut _build = rocket::build();
         let command = &*args[1];
         let _ = match command {
@@ -29,6 +33,29 @@ impl CoreCli {
                     },
                 };
             }
+            "backup" => match args.get(2) {
+                None => println!("missing archive path argument"),
+                Some(path) => CommandBackup::exec(pool, nodes_config, path).await,
+            },
+            "restore" => match args.get(2) {
+                None => println!("missing archive path argument"),
+                Some(path) => CommandRestore::exec(pool, nodes_config, path).await,
+            },
+            "export_recovery_kit" => {
+                let threshold = args.get(3).and_then(|arg| arg.parse::<u8>().ok());
+                let shares = args.get(4).and_then(|arg| arg.parse::<u8>().ok());
+                match (args.get(2), threshold, shares) {
+                    (Some(sentinel_id), Some(threshold), Some(shares)) => {
+                        CommandExportRecoveryKit::exec(pool, nodes_config, sentinel_id, threshold, shares)
+                            .await
+                    }
+                    _ => println!("usage: export_recovery_kit <sentinel_id> <threshold> <shares>"),
+                }
+            }
+            "import_recovery_kit" => {
+                CommandImportRecoveryKit::exec(pool, nodes_config, args[2..].to_vec()).await
+            }
+            "rotate_signing_key" => CommandRotateSigningKey::exec(pool).await,
             _ => {
                 println!("bad_request");
             }
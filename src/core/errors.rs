@@ -33,6 +33,35 @@ impl ErrorObject {
 
 pub type CustomError = Custom<Json<ErrorObject>>;
 
+/// ### OauthErrorObject
+///
+/// the error response of the OAuth endpoints (RFC 6749 section 5.2)
+#[derive(Debug, Serialize, Deserialize, Clone, JsonSchema)]
+pub struct OauthErrorObject {
+    pub error: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error_description: Option<String>,
+    /// the callback of the client carrying the error, set once the client and its redirect uri are validated
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub redirect_uri: Option<String>,
+}
+
+impl OauthErrorObject {
+    pub fn new(error: &str, description: Option<&str>, redirect_uri: Option<String>) -> Self {
+        OauthErrorObject {
+            error: error.to_string(),
+            error_description: description.map(|description| description.to_string()),
+            redirect_uri,
+        }
+    }
+
+    pub fn create(status: Status, error: &str, description: Option<&str>) -> OauthError {
+        Custom(status, Json(OauthErrorObject::new(error, description, None)))
+    }
+}
+
+pub type OauthError = Custom<Json<OauthErrorObject>>;
+
 // #[catch(401)]
 // pub async fn unauthorized(req: &Request<'_>) -> Json<ErrorObject> {
 //     let (_, todo_error) = req.guard::<Security>().await.failed().unwrap();
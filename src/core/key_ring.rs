@@ -0,0 +1,66 @@
+use std::env;
+
+use rocket::fairing::{self, Fairing, Info, Kind};
+use rocket::tokio::{spawn, time};
+use rocket::{Build, Orbit, Rocket};
+
+use crate::db::connect::DbPool;
+use crate::services::signing_key::SigningKeyService;
+
+/// ### KeyRingHandler
+///
+/// load the signing key ring before the launch, then reload it and run the scheduled
+/// rotation every `SIGNING_KEY_REFRESH_INTERVAL` seconds
+pub struct KeyRingHandler;
+
+impl KeyRingHandler {
+    fn refresh_interval() -> u64 {
+        env::var("SIGNING_KEY_REFRESH_INTERVAL")
+            .unwrap_or_else(|_| "60".to_string())
+            .parse::<u64>()
+            .unwrap_or(60)
+            .max(1)
+    }
+}
+
+#[rocket::async_trait]
+impl Fairing for KeyRingHandler {
+    fn info(&self) -> Info {
+        Info {
+            name: "Signing Key Ring",
+            kind: Kind::Ignite | Kind::Liftoff,
+        }
+    }
+
+    async fn on_ignite(&self, rocket: Rocket<Build>) -> fairing::Result {
+        let loaded = match rocket.state::<DbPool>() {
+            None => Err("no database pool"),
+            Some(pool) => SigningKeyService::new(pool).load(),
+        };
+        match loaded {
+            Ok(_) => Ok(rocket),
+            Err(e) => {
+                println!("Unable to load the signing key ring: {}", e);
+                Err(rocket)
+            }
+        }
+    }
+
+    async fn on_liftoff(&self, rocket: &Rocket<Orbit>) {
+        let pool = match rocket.state::<DbPool>() {
+            None => return,
+            Some(pool) => pool.clone(),
+        };
+        spawn(async move {
+            let signing_key_service = SigningKeyService::new(&pool);
+            let mut interval = time::interval(time::Duration::from_secs(Self::refresh_interval()));
+            interval.tick().await;
+            loop {
+                interval.tick().await;
+                if let Err(e) = signing_key_service.rotate_if_due() {
+                    println!("Signing key rotation failed: {}", e);
+                }
+            }
+        });
+    }
+}
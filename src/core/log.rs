@@ -7,6 +7,8 @@ use rocket::tokio::spawn;
 use rocket::{Request, Response};
 use serde::{Deserialize, Serialize};
 
+use crate::guards::client_ip::ClientIp;
+
 pub struct LogHandler;
 
 #[derive(Serialize, Deserialize)]
@@ -56,9 +58,8 @@ impl Fairing for LogHandler {
     }
 
     async fn on_response<'r>(&self, request: &'r Request<'_>, response: &mut Response<'r>) {
-        let ip = request
-            .client_ip()
-            .map(|ip| ip.to_string())
+        let ip = ClientIp::from_request(request)
+            .map(|client_ip| client_ip.ip().to_string())
             .unwrap_or_else(|| "-".to_string());
         let timestamp = Utc::now().to_rfc3339();
         let method = request.method();
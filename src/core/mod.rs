@@ -5,4 +5,5 @@ pub mod routes;
 pub mod settings;
 pub mod cli;
 pub mod nodes_config;
-pub mod log;
\ No newline at end of file
+pub mod log;
+pub mod key_ring;
\ No newline at end of file
@@ -1,5 +1,5 @@
 use crate::controlers::{
-    anonymous_sentinel, application, auth, cluster, oauth, oidc, sentinel, system, user,
+    access_request, anonymous_sentinel, application, audit_log, auth, cluster, lockout, oauth, oidc, secret, sentinel, service_account, session, share_link, system, user, webauthn,
 };
 use rocket::Route;
 use rocket_okapi::openapi_get_routes;
@@ -7,15 +7,33 @@ pub struct CoreRoutes;
 
 impl CoreRoutes {
     pub fn get() -> Vec<Route> {
-        let routes = openapi_get_routes![
+        let mut routes = openapi_get_routes![
             // auth controller
             auth::login,
             auth::refresh,
+            auth::scoped_token,
+            // webauthn controller
+            webauthn::register_start,
+            webauthn::register_finish,
+            webauthn::get_credentials,
+            webauthn::delete_credential,
+            webauthn::login_start,
+            webauthn::login_finish,
             // oauth controller
             oauth::authorize,
             oauth::token,
+            oauth::create_client,
+            oauth::get_clients,
+            oauth::rotate_client_secret,
+            oauth::delete_client,
+            oauth::introspect,
+            oauth::revoke,
             // oidc controller
             oidc::verify,
+            oidc::configuration,
+            oidc::jwks,
+            oidc::userinfo,
+            oidc::userinfo_post,
             // application controller
             application::post_application,
             application::update_application,
@@ -30,6 +48,8 @@ impl CoreRoutes {
             user::delete_user,
             user::get_2fa_code,
             user::activate_2fa,
+            user::set_break_glass,
+            user::set_restricted_ip,
             // cluster controller
             cluster::create,
             cluster::add_memberships,
@@ -38,22 +58,76 @@ impl CoreRoutes {
             cluster::remove_sentinels,
             cluster::add_anonymous_sentinels,
             cluster::remove_anonymous_sentinels,
+            cluster::add_secrets,
+            cluster::remove_secrets,
             cluster::delete_cluster,
+            cluster::set_dual_control,
             cluster::get_cluster_users,
             // sentinel controller
             sentinel::create,
+            sentinel::get_import_public_key,
+            sentinel::import,
             sentinel::get_by_id,
+            sentinel::hmac,
+            sentinel::hmac_verify,
             sentinel::delete_by_id,
+            sentinel::get_policy,
+            sentinel::set_policy,
+            sentinel::delete_policy,
+            sentinel::set_dual_control,
+            sentinel::break_glass,
+            sentinel::acknowledge_break_glass,
+            sentinel::create_share_link,
+            sentinel::revoke_share_link,
+            // share link controller
+            share_link::redeem,
             // anonymous sentinel controller
             anonymous_sentinel::create,
             anonymous_sentinel::create_public,
             anonymous_sentinel::get_public_by_id,
             anonymous_sentinel::get_by_id,
             anonymous_sentinel::delete_by_id,
+            anonymous_sentinel::get_policy,
+            anonymous_sentinel::set_policy,
+            anonymous_sentinel::delete_policy,
+            anonymous_sentinel::set_dual_control,
+            anonymous_sentinel::break_glass,
+            anonymous_sentinel::acknowledge_break_glass,
+            // access request controller
+            access_request::get_all,
+            access_request::get_by_id,
+            access_request::approve,
+            access_request::reject,
+            // service account controller
+            service_account::create,
+            service_account::get_all,
+            service_account::rotate_credentials,
+            service_account::set_signing_key,
+            service_account::delete_by_id,
+            // session controller
+            session::get_mine,
+            session::revoke_mine,
+            session::get_by_user,
+            session::revoke_by_user,
+            // lockout controller
+            lockout::get_locked,
+            lockout::unlock,
+            // audit log controller
+            audit_log::get_all,
+            // secret controller
+            secret::create,
+            secret::add_version,
+            secret::get_by_id,
+            secret::get_versions,
+            secret::delete_by_id,
             // system controller
             system::get_version,
             system::get_system_informations
         ];
+        // the stream routes borrow the request body, they are not documented by openapi
+        routes.extend(routes![sentinel::encrypt_stream, sentinel::decrypt_stream]);
+        // the form variants of the token, introspection and revocation endpoints share the documented json ones
+        routes.extend(routes![oauth::token_form, oauth::introspect_form, oauth::revoke_form]);
         routes
     }
 }
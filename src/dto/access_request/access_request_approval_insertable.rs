@@ -0,0 +1,24 @@
+use chrono::{DateTime, Utc};
+use diesel::prelude::Insertable;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::schema::access_request_approvals;
+
+#[derive(Deserialize, Serialize, Debug, Insertable)]
+#[diesel(table_name = access_request_approvals)]
+pub struct AccessRequestApprovalInsertable {
+    pub access_request_id: Uuid,
+    pub approver_id: Uuid,
+    pub created_at: DateTime<Utc>,
+}
+
+impl AccessRequestApprovalInsertable {
+    pub fn new(access_request_id: Uuid, approver_id: Uuid) -> Self {
+        AccessRequestApprovalInsertable {
+            access_request_id,
+            approver_id,
+            created_at: Utc::now(),
+        }
+    }
+}
@@ -0,0 +1,50 @@
+use chrono::{DateTime, Utc};
+use diesel::prelude::Insertable;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{enums::access_request_status::AccessRequestStatus, schema::access_requests};
+
+#[derive(Deserialize, Serialize, Debug, Insertable)]
+#[diesel(table_name = access_requests)]
+pub struct AccessRequestInsertable {
+    pub sentinel_id: Option<Uuid>,
+    pub anonymous_sentinel_id: Option<Uuid>,
+    pub requester_id: Uuid,
+    pub required_approvals: i32,
+    pub status: String,
+    pub expires_at: DateTime<Utc>,
+    pub is_deleted: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: Option<DateTime<Utc>>,
+    pub deleted_at: Option<DateTime<Utc>>,
+    pub created_by_id: Option<uuid::Uuid>,
+    pub updated_by_id: Option<uuid::Uuid>,
+    pub deleted_by_id: Option<uuid::Uuid>,
+}
+
+impl AccessRequestInsertable {
+    pub fn new(
+        sentinel_id: Option<Uuid>,
+        anonymous_sentinel_id: Option<Uuid>,
+        requester_id: Uuid,
+        required_approvals: i32,
+        expires_at: DateTime<Utc>,
+    ) -> Self {
+        AccessRequestInsertable {
+            sentinel_id,
+            anonymous_sentinel_id,
+            requester_id,
+            required_approvals,
+            status: AccessRequestStatus::Pending.as_str().to_string(),
+            expires_at,
+            is_deleted: false,
+            created_at: Utc::now(),
+            updated_at: None,
+            deleted_at: None,
+            created_by_id: Some(requester_id),
+            updated_by_id: None,
+            deleted_by_id: None,
+        }
+    }
+}
@@ -0,0 +1,44 @@
+use rocket_okapi::okapi::schemars;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::enums::access_request_status::AccessRequestStatus;
+use crate::models::access_request::AccessRequest;
+
+#[derive(Deserialize, Serialize, Clone, Debug, JsonSchema)]
+pub struct AccessRequestOutput {
+    pub id: String,
+    pub sentinel_id: Option<String>,
+    pub anonymous_sentinel_id: Option<String>,
+    pub requester_id: String,
+    pub required_approvals: i32,
+    pub approvals: i64,
+    pub status: String,
+    pub expires_at: String,
+    pub created_at: String,
+}
+
+impl AccessRequestOutput {
+    /// an open request past its expiry is shown as `expired`
+    pub fn new(access_request: AccessRequest, approvals: i64) -> Self {
+        let open = matches!(
+            AccessRequestStatus::from_str(&access_request.status),
+            Some(AccessRequestStatus::Pending | AccessRequestStatus::Approved)
+        );
+        let status = match open && access_request.is_expired() {
+            true => String::from("expired"),
+            false => access_request.status.clone(),
+        };
+        AccessRequestOutput {
+            id: access_request.id.to_string(),
+            sentinel_id: access_request.sentinel_id.map(|id| id.to_string()),
+            anonymous_sentinel_id: access_request.anonymous_sentinel_id.map(|id| id.to_string()),
+            requester_id: access_request.requester_id.to_string(),
+            required_approvals: access_request.required_approvals,
+            approvals,
+            status,
+            expires_at: access_request.expires_at.to_string(),
+            created_at: access_request.created_at.to_string(),
+        }
+    }
+}
@@ -0,0 +1,4 @@
+pub mod access_request_insertable;
+pub mod access_request_approval_insertable;
+pub mod access_request_output;
+pub mod dual_control_input;
\ No newline at end of file
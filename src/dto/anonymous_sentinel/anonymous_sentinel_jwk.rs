@@ -0,0 +1,27 @@
+use rocket_okapi::okapi::schemars;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// a post-quantum key pair as an `AKP` (algorithm key pair) JWK
+#[derive(Deserialize, Serialize, Clone, Debug, JsonSchema)]
+pub struct AnonymousSentinelJwk {
+    pub kty: String,
+    pub alg: String,
+    pub kid: String,
+    #[serde(rename = "pub")]
+    pub public: String,
+    #[serde(rename = "priv")]
+    pub private: String,
+}
+
+impl AnonymousSentinelJwk {
+    pub fn new(kid: String, alg: String, public: String, private: String) -> Self {
+        AnonymousSentinelJwk {
+            kty: String::from("AKP"),
+            alg,
+            kid,
+            public,
+            private,
+        }
+    }
+}
@@ -2,7 +2,11 @@ use rocket_okapi::okapi::schemars;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use crate::models::anonymous_sentinel::AnonymousSentinel;
+use crate::{
+    dto::anonymous_sentinel::anonymous_sentinel_jwk::AnonymousSentinelJwk,
+    enums::key_format::KeyFormat, models::anonymous_sentinel::AnonymousSentinel,
+    utils::{key_encoding::KeyEncoding, pq_kyber::PQKyber},
+};
 
 #[derive(Deserialize, Serialize, Clone, Debug, JsonSchema)]
 
@@ -11,6 +15,12 @@ pub struct AnonymousSentinelOutput {
     pub secret_key: String,
     pub sum: String,
     pub key_size: String,
+    pub format: String,
+    /// set while a break-glass read is not acknowledged by an admin
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub break_glass_at: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub jwk: Option<AnonymousSentinelJwk>,
 }
 
 impl AnonymousSentinelOutput {
@@ -24,7 +34,48 @@ impl AnonymousSentinelOutput {
             id: sentinel.id.to_string(),
             secret_key,
             sum: sentinel.sum,
-            key_size
+            key_size,
+            format: KeyFormat::Hex.as_str().to_string(),
+            break_glass_at: sentinel.break_glass_at.map(|date| date.to_string()),
+            jwk: None,
         }
     }
+
+    /// the output with the secret key in the requested export format,
+    /// `key_encryption_key` is the hex KEK used by the `wrapped` format
+    pub fn formatted(
+        sentinel: AnonymousSentinel,
+        secret_key: String,
+        format: &KeyFormat,
+        key_encryption_key: Option<String>,
+    ) -> Result<Self, &'static str> {
+        let mut output = AnonymousSentinelOutput::new(sentinel.clone(), secret_key.clone());
+        output.format = format.as_str().to_string();
+        match format {
+            KeyFormat::Hex => {}
+            KeyFormat::RawBase64 => output.secret_key = KeyEncoding::to_base64(&secret_key)?,
+            KeyFormat::Jwk => {
+                // the 512 keys are ML-KEM (FIPS 203), the 1024 keys are round 3 Kyber
+                let alg = match sentinel.key_size {
+                    512 => String::from("ML-KEM-512"),
+                    _ => output.key_size.clone(),
+                };
+                let public_key = PQKyber::decrypt_key(sentinel.public_key, sentinel.iv);
+                let private = KeyEncoding::to_base64_url(&secret_key)?;
+                output.jwk = Some(AnonymousSentinelJwk::new(
+                    sentinel.id.to_string(),
+                    alg,
+                    KeyEncoding::to_base64_url(&public_key)?,
+                    private.clone(),
+                ));
+                output.secret_key = private;
+            }
+            KeyFormat::Wrapped => match key_encryption_key {
+                None => return Err("The wrapped format requires a key-encryption key"),
+                Some(kek) => output.secret_key = KeyEncoding::wrap(&secret_key, &kek)?,
+            },
+            KeyFormat::Pem => return Err("The pem format is only available for public keys"),
+        }
+        Ok(output)
+    }
 }
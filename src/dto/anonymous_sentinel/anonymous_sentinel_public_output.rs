@@ -2,7 +2,10 @@ use rocket_okapi::okapi::schemars;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use crate::{models::anonymous_sentinel::AnonymousSentinel, utils::pq_kyber::PQKyber};
+use crate::{
+    enums::key_format::KeyFormat, models::anonymous_sentinel::AnonymousSentinel,
+    utils::{key_encoding::KeyEncoding, pq_kyber::PQKyber},
+};
 
 #[derive(Deserialize, Serialize, Clone, Debug, JsonSchema)]
 pub struct AnonymousSentinelPublicOutput {
@@ -10,7 +13,7 @@ pub struct AnonymousSentinelPublicOutput {
     pub public_key: String,
     pub sum: String,
     pub key_size: String,
-
+    pub format: String,
 }
 
 impl AnonymousSentinelPublicOutput {
@@ -24,7 +27,26 @@ impl AnonymousSentinelPublicOutput {
             id: sentinel.id.to_string(),
             public_key: PQKyber::decrypt_key(sentinel.public_key, sentinel.iv),
             sum: sentinel.sum,
-            key_size
+            key_size,
+            format: KeyFormat::Hex.as_str().to_string(),
+        }
+    }
+
+    /// the output with the public key in the requested export format
+    pub fn formatted(sentinel: AnonymousSentinel, format: &KeyFormat) -> Result<Self, &'static str> {
+        let key_size = sentinel.key_size;
+        let mut output = AnonymousSentinelPublicOutput::new(sentinel);
+        output.format = format.as_str().to_string();
+        match format {
+            KeyFormat::Hex => {}
+            KeyFormat::RawBase64 => output.public_key = KeyEncoding::to_base64(&output.public_key)?,
+            KeyFormat::Pem => match key_size {
+                // only the 512 keys are ML-KEM, the 1024 keys are round 3 Kyber and have no OID
+                512 => output.public_key = KeyEncoding::ml_kem_public_pem(&output.public_key, 512)?,
+                _ => return Err("The pem format is only available for ML-KEM keys"),
+            },
+            _ => return Err("Unsupported format for a public key"),
         }
+        Ok(output)
     }
 }
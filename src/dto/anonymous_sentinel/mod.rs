@@ -1,4 +1,5 @@
 pub mod anonymous_sentinel_insertable;
 pub mod anonymous_sentinel_output;
 pub mod anonymous_sentinel_public_output;
-pub mod anonymous_sentinel_public_input;
\ No newline at end of file
+pub mod anonymous_sentinel_public_input;
+pub mod anonymous_sentinel_jwk;
\ No newline at end of file
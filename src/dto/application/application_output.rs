@@ -12,6 +12,7 @@ pub struct ApplicationOutput {
     keys_number: i32,
     is_system: bool,
     contact_email: String,
+    allowed_ips: Vec<String>,
     created_at: String,
 }
 
@@ -24,6 +25,7 @@ impl ApplicationOutput {
             keys_number: application.keys_number,
             name: application.name,
             contact_email: application.contact_email,
+            allowed_ips: application.allowed_ips,
             created_at: application.created_at.to_string()
         }
     }
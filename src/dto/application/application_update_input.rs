@@ -1,4 +1,4 @@
-use crate::utils::validator::option_email;
+use crate::utils::validator::{option_email, option_vec_ip};
 use rocket_okapi::okapi::schemars;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
@@ -9,4 +9,7 @@ pub struct ApplicationUpdateInput {
     pub name: Option<String>,
     #[serde(deserialize_with = "option_email")]
     pub contact_email: Option<String>,
+    /// the IP addresses or CIDR ranges the users connect from, an empty list allows every IP
+    #[serde(default, deserialize_with = "option_vec_ip")]
+    pub allowed_ips: Option<Vec<String>>,
 }
@@ -0,0 +1,71 @@
+use std::net::IpAddr;
+
+use chrono::{DateTime, Utc};
+use diesel::prelude::Insertable;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::{
+    enums::{audit_action::AuditAction, audit_severity::AuditSeverity},
+    models::user::User,
+    schema::audit_logs,
+};
+
+#[derive(Deserialize, Serialize, Debug, Insertable)]
+#[diesel(table_name = audit_logs)]
+pub struct AuditLogInsertable {
+    pub application_id: Option<i32>,
+    pub user_id: Option<Uuid>,
+    pub action: String,
+    pub severity: String,
+    pub target_type: Option<String>,
+    pub target_id: Option<Uuid>,
+    pub justification: Option<String>,
+    pub ip: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl AuditLogInsertable {
+    /// `target` is the kind and the id of the object the action was done on
+    pub fn new(
+        user_from: &User,
+        action: AuditAction,
+        severity: AuditSeverity,
+        target: Option<(&str, Uuid)>,
+        justification: Option<String>,
+        ip: Option<&IpAddr>,
+    ) -> Self {
+        AuditLogInsertable {
+            application_id: user_from.application,
+            user_id: Some(user_from.id),
+            action: action.as_str().to_string(),
+            severity: severity.as_str().to_string(),
+            target_type: target.map(|(target_type, _)| target_type.to_string()),
+            target_id: target.map(|(_, target_id)| target_id),
+            justification,
+            ip: ip.map(|ip| ip.to_string()),
+            created_at: Utc::now(),
+        }
+    }
+
+    /// an action done without an authenticated user, like the redemption of a share link
+    pub fn anonymous(
+        application_id: i32,
+        action: AuditAction,
+        severity: AuditSeverity,
+        target: (&str, Uuid),
+        ip: &IpAddr,
+    ) -> Self {
+        AuditLogInsertable {
+            application_id: Some(application_id),
+            user_id: None,
+            action: action.as_str().to_string(),
+            severity: severity.as_str().to_string(),
+            target_type: Some(target.0.to_string()),
+            target_id: Some(target.1),
+            justification: None,
+            ip: Some(ip.to_string()),
+            created_at: Utc::now(),
+        }
+    }
+}
@@ -0,0 +1,34 @@
+use rocket_okapi::okapi::schemars;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::models::audit_log::AuditLog;
+
+#[derive(Deserialize, Serialize, Clone, Debug, JsonSchema)]
+pub struct AuditLogOutput {
+    pub id: String,
+    pub user_id: Option<String>,
+    pub action: String,
+    pub severity: String,
+    pub target_type: Option<String>,
+    pub target_id: Option<String>,
+    pub justification: Option<String>,
+    pub ip: Option<String>,
+    pub created_at: String,
+}
+
+impl AuditLogOutput {
+    pub fn new(audit_log: AuditLog) -> Self {
+        AuditLogOutput {
+            id: audit_log.id.to_string(),
+            user_id: audit_log.user_id.map(|id| id.to_string()),
+            action: audit_log.action,
+            severity: audit_log.severity,
+            target_type: audit_log.target_type,
+            target_id: audit_log.target_id.map(|id| id.to_string()),
+            justification: audit_log.justification,
+            ip: audit_log.ip,
+            created_at: audit_log.created_at.to_string(),
+        }
+    }
+}
@@ -0,0 +1,35 @@
+use std::net::IpAddr;
+
+use uuid::Uuid;
+
+use crate::models::user::User;
+
+/// the mail sent to an admin of the application after a break-glass read
+#[derive(Debug, Clone, PartialEq)]
+pub struct BreakGlassAlert {
+    pub email: String,
+    pub login: String,
+    pub requester: String,
+    pub key: String,
+    pub justification: String,
+    pub ip: String,
+}
+
+impl BreakGlassAlert {
+    pub fn new(
+        admin: &User,
+        user_from: &User,
+        target: (&str, Uuid),
+        justification: &str,
+        ip: &IpAddr,
+    ) -> Self {
+        BreakGlassAlert {
+            email: admin.email.clone(),
+            login: admin.login.clone(),
+            requester: user_from.login.clone(),
+            key: format!("{} {}", target.0, target.1),
+            justification: justification.to_string(),
+            ip: ip.to_string(),
+        }
+    }
+}
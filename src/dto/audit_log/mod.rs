@@ -0,0 +1,3 @@
+pub mod audit_log_insertable;
+pub mod audit_log_output;
+pub mod break_glass_alert;
@@ -2,6 +2,8 @@ use serde::{Deserialize, Serialize};
 use rocket_okapi::okapi::schemars;
 use schemars::JsonSchema;
 
+use crate::dto::webauthn::webauthn_assertion_input::WebauthnAssertionInput;
+
 
 #[derive(Deserialize, Serialize, Debug, JsonSchema, Clone)]
 pub struct AuthInput {
@@ -9,5 +11,7 @@ pub struct AuthInput {
     pub password: String,
     pub application_id: i32,
     pub fingerprint: String,
-    pub code_2fa: Option<String>
+    pub code_2fa: Option<String>,
+    /// a passkey assertion, the second factor in place of `code_2fa`
+    pub webauthn: Option<WebauthnAssertionInput>,
 }
@@ -1,3 +1,5 @@
 pub mod auth_input;
 pub mod auth_output;
-pub mod auth_refresh_input;
\ No newline at end of file
+pub mod auth_refresh_input;
+pub mod scoped_token_input;
+pub mod scoped_token_output;
\ No newline at end of file
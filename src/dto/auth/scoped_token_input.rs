@@ -0,0 +1,11 @@
+use serde::{Deserialize, Serialize};
+use rocket_okapi::okapi::schemars;
+use schemars::JsonSchema;
+
+#[derive(Deserialize, Serialize, Debug, Clone, JsonSchema)]
+pub struct ScopedTokenInput {
+    pub sentinels: Option<Vec<String>>,
+    pub clusters: Option<Vec<String>>,
+    pub operations: Vec<String>,
+    pub expires_in: Option<i64>,
+}
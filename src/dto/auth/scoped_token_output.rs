@@ -0,0 +1,28 @@
+use rocket_okapi::okapi::schemars;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::utils::jwt::TokenScope;
+
+#[derive(Deserialize, Serialize, Debug, Clone, JsonSchema)]
+pub struct ScopedTokenOutput {
+    pub access_token: String,
+    pub token_type: String,
+    pub expires_at: i64,
+    pub sentinels: Vec<String>,
+    pub clusters: Vec<String>,
+    pub operations: Vec<String>,
+}
+
+impl ScopedTokenOutput {
+    pub fn new(access_token: String, expires_at: i64, scope: TokenScope) -> Self {
+        ScopedTokenOutput {
+            access_token,
+            token_type: String::from("Bearer"),
+            expires_at,
+            sentinels: scope.sentinels.iter().map(|id| id.to_string()).collect(),
+            clusters: scope.clusters.iter().map(|id| id.to_string()).collect(),
+            operations: scope.operations,
+        }
+    }
+}
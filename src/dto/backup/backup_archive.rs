@@ -0,0 +1,44 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+pub const BACKUP_ARCHIVE_VERSION: u8 = 2;
+
+/// ### BackupArchive
+///
+/// the envelope written on disk by the `backup` command
+/// - `key_id` is the id of the key signing the archive
+/// - `payload` is the AES-256-GCM encrypted `BackupContent` (base64)
+/// - `signature` is the SHA-256 signature of `version.key_id.created_at.nonce.payload` (base64)
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct BackupArchive {
+    pub version: u8,
+    pub key_id: String,
+    pub created_at: DateTime<Utc>,
+    pub nonce: String,
+    pub payload: String,
+    pub signature: String,
+}
+
+impl BackupArchive {
+    pub fn new(key_id: String, created_at: DateTime<Utc>, nonce: String, payload: String) -> Self {
+        BackupArchive {
+            version: BACKUP_ARCHIVE_VERSION,
+            key_id,
+            created_at,
+            nonce,
+            payload,
+            signature: String::new(),
+        }
+    }
+
+    pub fn signed_data(&self) -> String {
+        format!(
+            "{}.{}.{}.{}.{}",
+            self.version,
+            self.key_id,
+            self.created_at.to_rfc3339(),
+            self.nonce,
+            self.payload
+        )
+    }
+}
@@ -0,0 +1,87 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::models::{
+    anonymous_sentinel::AnonymousSentinel, application::Application, audit_log::AuditLog,
+    cluster::Cluster, key_policy::KeyPolicy, oauth_client::OauthClient, recovery_code::RecoveryCode, revoked_token::RevokedToken,
+    secret::Secret, secret_version::SecretVersion, sentinel::Sentinel, service_account::ServiceAccount, signing_key::SigningKey, user::User,
+    x_anonymous_sentinel_cluster::XAnonymousSentinelCluster, x_secret_cluster::XSecretCluster,
+    x_sentinel_cluster::XSentinelCluster, x_user_cluster::XUserCluster, webauthn_credential::WebauthnCredential,
+};
+
+use super::backup_key_fragments::BackupKeyFragments;
+
+/// ### BackupContent
+///
+/// the clear content of a backup archive:
+/// the postgres metadata and enough fragments to rebuild every key
+#[derive(Deserialize, Serialize, Debug, Default)]
+pub struct BackupContent {
+    pub applications: Vec<Application>,
+    pub users: Vec<User>,
+    pub clusters: Vec<Cluster>,
+    pub sentinels: Vec<Sentinel>,
+    pub anonymous_sentinels: Vec<AnonymousSentinel>,
+    pub x_user_cluster: Vec<XUserCluster>,
+    pub x_sentinel_cluster: Vec<XSentinelCluster>,
+    pub x_anonymous_sentinel_cluster: Vec<XAnonymousSentinelCluster>,
+    #[serde(default)]
+    pub secrets: Vec<Secret>,
+    #[serde(default)]
+    pub secret_versions: Vec<SecretVersion>,
+    #[serde(default)]
+    pub x_secret_cluster: Vec<XSecretCluster>,
+    #[serde(default)]
+    pub key_policies: Vec<KeyPolicy>,
+    #[serde(default)]
+    pub service_accounts: Vec<ServiceAccount>,
+    #[serde(default)]
+    pub oauth_clients: Vec<OauthClient>,
+    #[serde(default)]
+    pub webauthn_credentials: Vec<WebauthnCredential>,
+    #[serde(default)]
+    pub recovery_codes: Vec<RecoveryCode>,
+    #[serde(default)]
+    pub signing_keys: Vec<SigningKey>,
+    #[serde(default)]
+    pub revoked_tokens: Vec<RevokedToken>,
+    #[serde(default)]
+    pub audit_logs: Vec<AuditLog>,
+    pub fragments: Vec<BackupKeyFragments>,
+}
+
+impl BackupContent {
+    /// the keys that are not deleted, each one needs its fragments in the archive
+    pub fn live_key_ids(&self) -> Vec<Uuid> {
+        self.sentinels
+            .iter()
+            .filter(|sentinel| !sentinel.is_deleted)
+            .map(|sentinel| sentinel.id)
+            .chain(
+                self.anonymous_sentinels
+                    .iter()
+                    .filter(|anonymous_sentinel| !anonymous_sentinel.is_deleted)
+                    .map(|anonymous_sentinel| anonymous_sentinel.id),
+            )
+            .chain(
+                self.secret_versions
+                    .iter()
+                    .filter(|secret_version| !secret_version.is_deleted)
+                    .map(|secret_version| secret_version.id),
+            )
+            .collect()
+    }
+
+    /// a restore must not bring back a key it can not rebuild
+    pub fn check_fragments(&self) -> Result<(), String> {
+        match self.live_key_ids().into_iter().find(|key_id| {
+            !self
+                .fragments
+                .iter()
+                .any(|key_fragments| key_fragments.key_id == *key_id)
+        }) {
+            None => Ok(()),
+            Some(key_id) => Err(format!("The backup has no fragments for the key {}", key_id)),
+        }
+    }
+}
@@ -0,0 +1,14 @@
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct BackupKeyFragments {
+    pub key_id: Uuid,
+    pub fragments: Vec<String>,
+}
+
+impl BackupKeyFragments {
+    pub fn new(key_id: Uuid, fragments: Vec<String>) -> Self {
+        BackupKeyFragments { key_id, fragments }
+    }
+}
@@ -0,0 +1,3 @@
+pub mod backup_archive;
+pub mod backup_content;
+pub mod backup_key_fragments;
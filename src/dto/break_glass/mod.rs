@@ -0,0 +1 @@
+pub mod break_glass_input;
\ No newline at end of file
@@ -11,6 +11,7 @@ pub struct ClusterOutput {
     pub id: String,
     pub name: String,
     pub description: Option<String>,
+    pub required_approvals: i32,
     pub created_at: String,
 }
 
@@ -20,6 +21,7 @@ impl ClusterOutput {
             id: cluster.id.to_string(),
             name: cluster.name,
             description: cluster.description,
+            required_approvals: cluster.required_approvals,
             created_at: cluster.created_at.to_string(),
         }
     }
@@ -0,0 +1,8 @@
+use serde::{Deserialize, Serialize};
+use rocket_okapi::okapi::schemars;
+use schemars::JsonSchema;
+
+#[derive(Deserialize, Serialize, Debug, JsonSchema, Clone)]
+pub struct ClusterSecretsInput {
+    pub secrets: Vec<String>
+}
@@ -4,3 +4,5 @@ pub mod cluster_output;
 pub mod cluster_memberships_input;
 pub mod cluster_sentinels_input;
 pub mod cluster_anonymous_sentinels_input;
+
+pub mod cluster_secrets_input;
\ No newline at end of file
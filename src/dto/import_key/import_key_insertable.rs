@@ -0,0 +1,52 @@
+use chrono::{DateTime, Utc};
+use diesel::prelude::Insertable;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::schema::import_keys::{self};
+
+#[derive(Deserialize, Serialize, Debug, Insertable)]
+#[diesel(table_name = import_keys)]
+pub struct ImportKeyInsertable {
+    pub application_id: i32,
+    pub algorithm: String,
+    pub iv: String,
+    pub sum: String,
+    pub public_key: String,
+    pub expires_at: DateTime<Utc>,
+    pub is_deleted: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: Option<DateTime<Utc>>,
+    pub deleted_at: Option<DateTime<Utc>>,
+    pub created_by_id: Option<uuid::Uuid>,
+    pub updated_by_id: Option<uuid::Uuid>,
+    pub deleted_by_id: Option<uuid::Uuid>,
+}
+
+impl ImportKeyInsertable {
+    pub fn new(
+        algorithm: String,
+        iv: String,
+        sum: String,
+        public_key: String,
+        expires_at: DateTime<Utc>,
+        application_id: i32,
+        user_from_id: Uuid,
+    ) -> Self {
+        ImportKeyInsertable {
+            application_id,
+            algorithm,
+            iv,
+            sum,
+            public_key,
+            expires_at,
+            is_deleted: false,
+            created_at: Utc::now(),
+            updated_at: None,
+            deleted_at: None,
+            created_by_id: Some(user_from_id),
+            updated_by_id: None,
+            deleted_by_id: None,
+        }
+    }
+}
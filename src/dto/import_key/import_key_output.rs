@@ -0,0 +1,24 @@
+use rocket_okapi::okapi::schemars;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::models::import_key::ImportKey;
+
+#[derive(Deserialize, Serialize, Clone, Debug, JsonSchema)]
+pub struct ImportKeyOutput {
+    pub id: String,
+    pub algorithm: String,
+    pub public_key: String,
+    pub expires_at: String,
+}
+
+impl ImportKeyOutput {
+    pub fn new(import_key: ImportKey) -> Self {
+        ImportKeyOutput {
+            id: import_key.id.to_string(),
+            algorithm: import_key.algorithm,
+            public_key: import_key.public_key,
+            expires_at: import_key.expires_at.to_rfc3339(),
+        }
+    }
+}
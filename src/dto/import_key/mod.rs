@@ -0,0 +1,2 @@
+pub mod import_key_insertable;
+pub mod import_key_output;
\ No newline at end of file
@@ -0,0 +1,14 @@
+use serde::{Deserialize, Serialize};
+use rocket_okapi::okapi::schemars;
+use schemars::JsonSchema;
+
+
+#[derive(Deserialize, Serialize, Debug, JsonSchema, Clone)]
+pub struct KeyPolicyInput {
+    pub operations: Option<Vec<String>>,
+    pub allowed_cidrs: Option<Vec<String>>,
+    pub allowed_days: Option<Vec<i32>>,
+    pub hour_start: Option<i32>,
+    pub hour_end: Option<i32>,
+    pub max_reads: Option<i32>,
+}
@@ -0,0 +1,58 @@
+use chrono::{DateTime, Utc};
+use diesel::prelude::Insertable;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::schema::key_policies;
+
+use super::key_policy_input::KeyPolicyInput;
+
+#[derive(Deserialize, Serialize, Debug, Insertable)]
+#[diesel(table_name = key_policies)]
+pub struct KeyPolicyInsertable {
+    pub sentinel_id: Option<Uuid>,
+    pub anonymous_sentinel_id: Option<Uuid>,
+    pub operations: Vec<Option<String>>,
+    pub allowed_cidrs: Vec<Option<String>>,
+    pub allowed_days: Vec<Option<i32>>,
+    pub hour_start: Option<i32>,
+    pub hour_end: Option<i32>,
+    pub max_reads: Option<i32>,
+    pub read_count: i32,
+    pub is_deleted: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: Option<DateTime<Utc>>,
+    pub deleted_at: Option<DateTime<Utc>>,
+    pub created_by_id: Option<uuid::Uuid>,
+    pub updated_by_id: Option<uuid::Uuid>,
+    pub deleted_by_id: Option<uuid::Uuid>,
+}
+
+impl KeyPolicyInsertable {
+    /// `input` is the validated policy, its operations are already defaulted
+    pub fn new(
+        sentinel_id: Option<Uuid>,
+        anonymous_sentinel_id: Option<Uuid>,
+        input: KeyPolicyInput,
+        user_from_id: Uuid,
+    ) -> Self {
+        KeyPolicyInsertable {
+            sentinel_id,
+            anonymous_sentinel_id,
+            operations: input.operations.unwrap_or_default().into_iter().map(Some).collect(),
+            allowed_cidrs: input.allowed_cidrs.unwrap_or_default().into_iter().map(Some).collect(),
+            allowed_days: input.allowed_days.unwrap_or_default().into_iter().map(Some).collect(),
+            hour_start: input.hour_start,
+            hour_end: input.hour_end,
+            max_reads: input.max_reads,
+            read_count: 0,
+            is_deleted: false,
+            created_at: Utc::now(),
+            updated_at: None,
+            deleted_at: None,
+            created_by_id: Some(user_from_id),
+            updated_by_id: None,
+            deleted_by_id: None,
+        }
+    }
+}
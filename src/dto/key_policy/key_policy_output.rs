@@ -0,0 +1,34 @@
+use rocket_okapi::okapi::schemars;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::models::key_policy::KeyPolicy;
+
+#[derive(Deserialize, Serialize, Clone, Debug, JsonSchema)]
+pub struct KeyPolicyOutput {
+    pub id: String,
+    pub operations: Vec<String>,
+    pub allowed_cidrs: Vec<String>,
+    pub allowed_days: Vec<i32>,
+    pub hour_start: Option<i32>,
+    pub hour_end: Option<i32>,
+    pub max_reads: Option<i32>,
+    pub read_count: i32,
+    pub created_at: String,
+}
+
+impl KeyPolicyOutput {
+    pub fn new(policy: KeyPolicy) -> Self {
+        KeyPolicyOutput {
+            id: policy.id.to_string(),
+            operations: policy.operations.into_iter().flatten().collect(),
+            allowed_cidrs: policy.allowed_cidrs.into_iter().flatten().collect(),
+            allowed_days: policy.allowed_days.into_iter().flatten().collect(),
+            hour_start: policy.hour_start,
+            hour_end: policy.hour_end,
+            max_reads: policy.max_reads,
+            read_count: policy.read_count,
+            created_at: policy.created_at.to_string(),
+        }
+    }
+}
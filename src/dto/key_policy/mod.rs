@@ -0,0 +1,3 @@
+pub mod key_policy_input;
+pub mod key_policy_insertable;
+pub mod key_policy_output;
\ No newline at end of file
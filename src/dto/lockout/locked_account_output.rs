@@ -0,0 +1,26 @@
+use chrono::{DateTime, Utc};
+use rocket_okapi::okapi::schemars;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{enums::attempt_scope::AttemptScope, models::user::User};
+
+#[derive(Deserialize, Serialize, Clone, Debug, JsonSchema)]
+pub struct LockedAccountOutput {
+    pub user_id: String,
+    pub login: String,
+    /// the flow failing too often: `login`, `2fa`, `validation` or `password_reset`
+    pub scope: String,
+    pub locked_until: String,
+}
+
+impl LockedAccountOutput {
+    pub fn new(user: &User, scope: AttemptScope, locked_until: DateTime<Utc>) -> Self {
+        LockedAccountOutput {
+            user_id: user.id.to_string(),
+            login: user.login.clone(),
+            scope: scope.as_str().to_string(),
+            locked_until: locked_until.to_string(),
+        }
+    }
+}
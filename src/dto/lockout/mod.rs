@@ -0,0 +1 @@
+pub mod locked_account_output;
\ No newline at end of file
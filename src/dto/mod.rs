@@ -13,4 +13,19 @@ pub mod sentinel_log;
 pub mod system;
 pub mod list;
 pub mod anonymous_sentinel;
-pub mod x_anonymous_sentinel_cluster;
\ No newline at end of file
+pub mod x_anonymous_sentinel_cluster;
+pub mod backup;
+pub mod import_key;
+pub mod secret;
+pub mod x_secret_cluster;
+pub mod key_policy;
+pub mod access_request;
+pub mod audit_log;
+pub mod break_glass;
+pub mod share_link;
+pub mod service_account;
+pub mod signing_key;
+pub mod session;
+pub mod webauthn;
+pub mod recovery_code;
+pub mod lockout;
\ No newline at end of file
@@ -1,2 +1,9 @@
 pub mod oauth_authorize_output;
-pub mod oauth_token_input;
\ No newline at end of file
+pub mod oauth_token_input;
+pub mod oauth_access_token_output;
+pub mod oauth_client_input;
+pub mod oauth_client_insertable;
+pub mod oauth_client_output;
+pub mod oauth_code_insertable;
+pub mod oauth_token_request_input;
+pub mod oauth_introspection_output;
\ No newline at end of file
@@ -0,0 +1,29 @@
+use rocket_okapi::okapi::schemars;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// the access token response of the client credentials and authorization code grants,
+/// there is no refresh token: the client authenticates again when it expires
+#[derive(Deserialize, Serialize, Clone, Debug, JsonSchema)]
+pub struct OauthAccessTokenOutput {
+    pub access_token: String,
+    pub token_type: String,
+    pub expires_in: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scope: Option<String>,
+    /// the OpenID Connect ID token, when the `openid` scope is granted
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id_token: Option<String>,
+}
+
+impl OauthAccessTokenOutput {
+    pub fn new(access_token: String, expires_in: i64, scope: Option<String>) -> Self {
+        OauthAccessTokenOutput {
+            access_token,
+            token_type: String::from("Bearer"),
+            expires_in,
+            scope,
+            id_token: None,
+        }
+    }
+}
@@ -1,20 +1,23 @@
 use serde::{Deserialize, Serialize};
 use rocket_okapi::okapi::schemars;
 use schemars::JsonSchema;
-use uuid::Uuid;
-
-use crate::models::user::User;
 
 #[derive(Deserialize, Serialize, Clone, Debug, JsonSchema)]
 
 pub struct OauthAuthorizeOutput {
-    pub authorization_code: String,
+    pub code: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub state: Option<String>,
+    /// the callback of the client with the code and the state, where the user agent is sent
+    pub redirect_uri: String,
 }
 
 impl OauthAuthorizeOutput {
-    pub fn new(authorization_code: String) -> Self {
+    pub fn new(code: String, state: Option<String>, redirect_uri: String) -> Self {
         Self  {
-            authorization_code
+            code,
+            state,
+            redirect_uri
         }
     }
 
@@ -0,0 +1,12 @@
+use serde::{Deserialize, Serialize};
+use rocket_okapi::okapi::schemars;
+use schemars::JsonSchema;
+
+
+#[derive(Deserialize, Serialize, Debug, JsonSchema, Clone)]
+pub struct OauthClientInput {
+    pub name: String,
+    pub redirect_uris: Vec<String>,
+    /// a public client (single page or mobile application) has no secret
+    pub is_public: Option<bool>,
+}
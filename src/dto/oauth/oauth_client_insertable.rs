@@ -0,0 +1,49 @@
+use chrono::{DateTime, Utc};
+use diesel::prelude::Insertable;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::schema::oauth_clients;
+
+#[derive(Deserialize, Serialize, Debug, Insertable)]
+#[diesel(table_name = oauth_clients)]
+pub struct OauthClientInsertable {
+    pub application_id: i32,
+    pub name: String,
+    pub client_id: String,
+    pub client_secret_hash: Option<String>,
+    pub redirect_uris: Vec<Option<String>>,
+    pub is_deleted: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: Option<DateTime<Utc>>,
+    pub deleted_at: Option<DateTime<Utc>>,
+    pub created_by_id: Option<uuid::Uuid>,
+    pub updated_by_id: Option<uuid::Uuid>,
+    pub deleted_by_id: Option<uuid::Uuid>,
+}
+
+impl OauthClientInsertable {
+    pub fn new(
+        application_id: i32,
+        name: String,
+        client_id: String,
+        client_secret_hash: Option<String>,
+        redirect_uris: Vec<String>,
+        user_from_id: Uuid,
+    ) -> Self {
+        OauthClientInsertable {
+            application_id,
+            name,
+            client_id,
+            client_secret_hash,
+            redirect_uris: redirect_uris.into_iter().map(Some).collect(),
+            is_deleted: false,
+            created_at: Utc::now(),
+            updated_at: None,
+            deleted_at: None,
+            created_by_id: Some(user_from_id),
+            updated_by_id: None,
+            deleted_by_id: None,
+        }
+    }
+}
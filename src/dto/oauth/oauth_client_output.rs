@@ -0,0 +1,32 @@
+use rocket_okapi::okapi::schemars;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::models::oauth_client::OauthClient;
+
+#[derive(Deserialize, Serialize, Clone, Debug, JsonSchema)]
+pub struct OauthClientOutput {
+    pub id: String,
+    pub name: String,
+    pub client_id: String,
+    pub redirect_uris: Vec<String>,
+    pub is_public: bool,
+    /// only returned when the secret is created
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub client_secret: Option<String>,
+    pub created_at: String,
+}
+
+impl OauthClientOutput {
+    pub fn new(oauth_client: OauthClient, client_secret: Option<String>) -> Self {
+        OauthClientOutput {
+            id: oauth_client.id.to_string(),
+            name: oauth_client.name,
+            client_id: oauth_client.client_id,
+            redirect_uris: oauth_client.redirect_uris.into_iter().flatten().collect(),
+            is_public: oauth_client.client_secret_hash.is_none(),
+            client_secret,
+            created_at: oauth_client.created_at.to_string(),
+        }
+    }
+}
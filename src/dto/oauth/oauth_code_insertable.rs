@@ -0,0 +1,73 @@
+use chrono::{DateTime, Utc};
+use diesel::prelude::Insertable;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::schema::oauth_codes;
+use crate::utils::open_id::Authentication;
+
+/// ### OauthCodeGrant
+///
+/// the parameters of the authorization request a code is bound to,
+/// checked again when the code is exchanged
+#[derive(Debug)]
+pub struct OauthCodeGrant {
+    pub redirect_uri: String,
+    /// the S256 PKCE challenge
+    pub code_challenge: String,
+    pub scope: Option<String>,
+    pub nonce: Option<String>,
+}
+
+#[derive(Deserialize, Serialize, Debug, Insertable)]
+#[diesel(table_name = oauth_codes)]
+pub struct OauthCodeInsertable {
+    pub oauth_client_id: Uuid,
+    pub user_id: Uuid,
+    pub code_hash: String,
+    pub redirect_uri: String,
+    pub code_challenge: String,
+    pub scope: Option<String>,
+    pub expires_at: DateTime<Utc>,
+    pub is_deleted: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: Option<DateTime<Utc>>,
+    pub deleted_at: Option<DateTime<Utc>>,
+    pub created_by_id: Option<uuid::Uuid>,
+    pub updated_by_id: Option<uuid::Uuid>,
+    pub deleted_by_id: Option<uuid::Uuid>,
+    pub nonce: Option<String>,
+    pub auth_time: DateTime<Utc>,
+    pub amr: Vec<Option<String>>,
+}
+
+impl OauthCodeInsertable {
+    pub fn new(
+        oauth_client_id: Uuid,
+        user_id: Uuid,
+        code_hash: String,
+        grant: OauthCodeGrant,
+        authentication: &Authentication,
+        expires_at: DateTime<Utc>,
+    ) -> Self {
+        OauthCodeInsertable {
+            oauth_client_id,
+            user_id,
+            code_hash,
+            redirect_uri: grant.redirect_uri,
+            code_challenge: grant.code_challenge,
+            scope: grant.scope,
+            expires_at,
+            is_deleted: false,
+            created_at: Utc::now(),
+            updated_at: None,
+            deleted_at: None,
+            created_by_id: Some(user_id),
+            updated_by_id: None,
+            deleted_by_id: None,
+            nonce: grant.nonce,
+            auth_time: DateTime::from_timestamp(authentication.auth_time, 0).unwrap_or_else(Utc::now),
+            amr: authentication.amr.iter().cloned().map(Some).collect(),
+        }
+    }
+}
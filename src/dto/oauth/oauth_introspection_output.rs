@@ -0,0 +1,72 @@
+use rocket_okapi::okapi::schemars;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::utils::{dpop::Confirmation, jwt::Jwt};
+
+/// the introspection response (RFC 7662), an unknown, expired, revoked or foreign token is only `active: false`
+#[derive(Deserialize, Serialize, Clone, Debug, JsonSchema)]
+pub struct OauthIntrospectionOutput {
+    pub active: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scope: Option<String>,
+    /// the OAuth client the token was issued to
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub client_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub username: Option<String>,
+    /// `Bearer` or `DPoP` for the access tokens, `refresh_token` for the refresh tokens
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub token_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exp: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub iat: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sub: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub iss: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub jti: Option<String>,
+    /// the thumbprint of the DPoP key the token is bound to
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cnf: Option<Confirmation>,
+}
+
+impl OauthIntrospectionOutput {
+    pub fn inactive() -> Self {
+        OauthIntrospectionOutput {
+            active: false,
+            scope: None,
+            client_id: None,
+            username: None,
+            token_type: None,
+            exp: None,
+            iat: None,
+            sub: None,
+            iss: None,
+            jti: None,
+            cnf: None,
+        }
+    }
+
+    pub fn new(token: &Jwt, issuer: String) -> Self {
+        OauthIntrospectionOutput {
+            active: true,
+            scope: token.oauth_scope.clone(),
+            client_id: token.oauth_client_id.clone(),
+            username: Some(token.login.to_string()),
+            token_type: Some(String::from(match (token.is_refresh, &token.cnf) {
+                (true, _) => "refresh_token",
+                (false, Some(_)) => "DPoP",
+                (false, None) => "Bearer",
+            })),
+            exp: Some(token.exp),
+            iat: Some(token.iat),
+            sub: Some(token.id.to_string()),
+            iss: Some(issuer),
+            jti: token.jti.map(|jti| jti.to_string()),
+            cnf: token.cnf.clone(),
+        }
+    }
+}
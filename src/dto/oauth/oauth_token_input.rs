@@ -1,12 +1,18 @@
+use rocket::FromForm;
 use serde::{Deserialize, Serialize};
 use rocket_okapi::okapi::schemars;
 use schemars::JsonSchema;
 
 
-#[derive(Deserialize, Serialize, Debug, JsonSchema, Clone)]
+#[derive(Deserialize, Serialize, Debug, JsonSchema, Clone, FromForm)]
 pub struct OauthTokenInput {
-    pub authorization_code: String,
-    pub state: String,
-    pub fingerprint: String,
-    pub code_2fa: Option<String>
+    /// `authorization_code` or `client_credentials`
+    pub grant_type: String,
+    pub code: Option<String>,
+    pub redirect_uri: Option<String>,
+    pub code_verifier: Option<String>,
+    pub client_id: Option<String>,
+    pub client_secret: Option<String>,
+    pub client_assertion_type: Option<String>,
+    pub client_assertion: Option<String>,
 }
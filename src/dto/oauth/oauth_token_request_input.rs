@@ -0,0 +1,16 @@
+use rocket::FromForm;
+use serde::{Deserialize, Serialize};
+use rocket_okapi::okapi::schemars;
+use schemars::JsonSchema;
+
+/// the body of the introspection (RFC 7662) and revocation (RFC 7009) endpoints
+#[derive(Deserialize, Serialize, Debug, JsonSchema, Clone, FromForm)]
+pub struct OauthTokenRequestInput {
+    pub token: String,
+    /// `access_token` or `refresh_token`, the type of the token is read from the token itself
+    pub token_type_hint: Option<String>,
+    pub client_id: Option<String>,
+    pub client_secret: Option<String>,
+    pub client_assertion_type: Option<String>,
+    pub client_assertion: Option<String>,
+}
@@ -0,0 +1,34 @@
+use rocket_okapi::okapi::schemars;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// the public keys checking the tokens of the API (RFC 7517)
+#[derive(Deserialize, Serialize, Clone, Debug, JsonSchema)]
+pub struct JwksOutput {
+    pub keys: Vec<JwkOutput>,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, JsonSchema)]
+pub struct JwkOutput {
+    pub kty: String,
+    #[serde(rename = "use")]
+    pub key_use: String,
+    pub alg: String,
+    pub kid: String,
+    pub n: String,
+    pub e: String,
+}
+
+impl JwkOutput {
+    /// a RSA signing key, `n` and `e` url safe base64
+    pub fn rsa(kid: String, n: String, e: String) -> Self {
+        JwkOutput {
+            kty: String::from("RSA"),
+            key_use: String::from("sig"),
+            alg: String::from("RS256"),
+            kid,
+            n,
+            e,
+        }
+    }
+}
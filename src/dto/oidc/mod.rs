@@ -7,3 +7,8 @@ use serde::{Deserialize, Serialize};
 pub struct OidcVerifyInput {
     pub open_id_token: String,
 }
+
+
+pub mod openid_configuration_output;
+pub mod jwks_output;
+pub mod userinfo_output;
\ No newline at end of file
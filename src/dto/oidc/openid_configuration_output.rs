@@ -0,0 +1,66 @@
+use rocket_okapi::okapi::schemars;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// the OpenID Connect discovery document
+#[derive(Deserialize, Serialize, Clone, Debug, JsonSchema)]
+pub struct OpenidConfigurationOutput {
+    pub issuer: String,
+    pub authorization_endpoint: String,
+    pub token_endpoint: String,
+    pub userinfo_endpoint: String,
+    pub jwks_uri: String,
+    pub introspection_endpoint: String,
+    pub revocation_endpoint: String,
+    pub scopes_supported: Vec<String>,
+    pub response_types_supported: Vec<String>,
+    pub grant_types_supported: Vec<String>,
+    pub subject_types_supported: Vec<String>,
+    pub id_token_signing_alg_values_supported: Vec<String>,
+    pub token_endpoint_auth_methods_supported: Vec<String>,
+    pub code_challenge_methods_supported: Vec<String>,
+    pub claims_supported: Vec<String>,
+}
+
+impl OpenidConfigurationOutput {
+    pub fn new(issuer: String) -> Self {
+        let strings = |values: &[&str]| values.iter().map(|value| value.to_string()).collect();
+        OpenidConfigurationOutput {
+            authorization_endpoint: format!("{}/oauth/authorize", issuer),
+            token_endpoint: format!("{}/oauth/token", issuer),
+            userinfo_endpoint: format!("{}/userinfo", issuer),
+            jwks_uri: format!("{}/jwks.json", issuer),
+            introspection_endpoint: format!("{}/oauth/introspect", issuer),
+            revocation_endpoint: format!("{}/oauth/revoke", issuer),
+            issuer,
+            scopes_supported: strings(&["openid", "profile", "email"]),
+            response_types_supported: strings(&["code"]),
+            grant_types_supported: strings(&["authorization_code", "client_credentials"]),
+            subject_types_supported: strings(&["public"]),
+            id_token_signing_alg_values_supported: strings(&["RS256"]),
+            token_endpoint_auth_methods_supported: strings(&[
+                "client_secret_basic",
+                "client_secret_post",
+                "private_key_jwt",
+                "none",
+            ]),
+            code_challenge_methods_supported: strings(&["S256"]),
+            claims_supported: strings(&[
+                "iss",
+                "sub",
+                "aud",
+                "iat",
+                "exp",
+                "auth_time",
+                "amr",
+                "nonce",
+                "name",
+                "given_name",
+                "family_name",
+                "preferred_username",
+                "email",
+                "email_verified",
+            ]),
+        }
+    }
+}
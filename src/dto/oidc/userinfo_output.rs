@@ -0,0 +1,21 @@
+use rocket_okapi::okapi::schemars;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{models::user::User, utils::open_id::UserClaims};
+
+#[derive(Deserialize, Serialize, Clone, Debug, JsonSchema)]
+pub struct UserinfoOutput {
+    pub sub: String,
+    #[serde(flatten)]
+    pub claims: UserClaims,
+}
+
+impl UserinfoOutput {
+    pub fn new(user: &User, scope: Option<&str>) -> Self {
+        UserinfoOutput {
+            sub: user.id.to_string(),
+            claims: UserClaims::new(user, scope),
+        }
+    }
+}
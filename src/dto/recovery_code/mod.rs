@@ -0,0 +1 @@
+pub mod recovery_code_insertable;
\ No newline at end of file
@@ -0,0 +1,38 @@
+use chrono::{DateTime, Utc};
+use diesel::prelude::Insertable;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::schema::recovery_codes;
+
+#[derive(Deserialize, Serialize, Debug, Insertable)]
+#[diesel(table_name = recovery_codes)]
+pub struct RecoveryCodeInsertable {
+    pub user_id: Uuid,
+    pub code_hash: String,
+    pub used_at: Option<DateTime<Utc>>,
+    pub is_deleted: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: Option<DateTime<Utc>>,
+    pub deleted_at: Option<DateTime<Utc>>,
+    pub created_by_id: Option<uuid::Uuid>,
+    pub updated_by_id: Option<uuid::Uuid>,
+    pub deleted_by_id: Option<uuid::Uuid>,
+}
+
+impl RecoveryCodeInsertable {
+    pub fn new(user_id: Uuid, code_hash: String) -> Self {
+        RecoveryCodeInsertable {
+            user_id,
+            code_hash,
+            used_at: None,
+            is_deleted: false,
+            created_at: Utc::now(),
+            updated_at: None,
+            deleted_at: None,
+            created_by_id: Some(user_id),
+            updated_by_id: None,
+            deleted_by_id: None,
+        }
+    }
+}
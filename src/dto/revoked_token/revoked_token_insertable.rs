@@ -7,7 +7,7 @@ use crate::schema::revoked_tokens;
 #[derive(Deserialize, Serialize, Debug, Insertable)]
 #[diesel(table_name = revoked_tokens)]
 pub struct RevokedTokenInsertable {
-    pub token: String,
+    pub token_hash: String,
     pub is_deleted: bool,
     pub created_at: DateTime<Utc>,
     pub updated_at: Option<DateTime<Utc>>,
@@ -15,12 +15,13 @@ pub struct RevokedTokenInsertable {
     pub created_by_id: Option<uuid::Uuid>,
     pub updated_by_id: Option<uuid::Uuid>,
     pub deleted_by_id: Option<uuid::Uuid>,
+    pub expires_at: DateTime<Utc>,
 }
 
 impl RevokedTokenInsertable {
-    pub fn new(token: String) -> Self {
+    pub fn new(token_hash: String, expires_at: DateTime<Utc>) -> Self {
         RevokedTokenInsertable {
-            token,
+            token_hash,
             is_deleted: false,
             created_at: Utc::now(),
             updated_at: None,
@@ -28,6 +29,7 @@ impl RevokedTokenInsertable {
             created_by_id: None,
             updated_by_id: None,
             deleted_by_id: None,
+            expires_at,
         }
     }
 }
@@ -0,0 +1,7 @@
+pub mod secret_input;
+pub mod secret_version_input;
+pub mod secret_insertable;
+pub mod secret_version_insertable;
+pub mod secret_output;
+pub mod secret_value_output;
+pub mod secret_version_output;
\ No newline at end of file
@@ -0,0 +1,11 @@
+use serde::{Deserialize, Serialize};
+use rocket_okapi::okapi::schemars;
+use schemars::JsonSchema;
+
+
+#[derive(Deserialize, Serialize, Debug, JsonSchema, Clone)]
+pub struct SecretInput {
+    pub name: String,
+    pub value: String,
+    pub clusters: Vec<String>
+}
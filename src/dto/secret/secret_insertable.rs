@@ -0,0 +1,38 @@
+use chrono::{DateTime, Utc};
+use diesel::prelude::Insertable;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::schema::secrets;
+
+#[derive(Deserialize, Serialize, Debug, Insertable)]
+#[diesel(table_name = secrets)]
+pub struct SecretInsertable {
+    pub application_id: i32,
+    pub name: String,
+    pub current_version: i32,
+    pub is_deleted: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: Option<DateTime<Utc>>,
+    pub deleted_at: Option<DateTime<Utc>>,
+    pub created_by_id: Option<uuid::Uuid>,
+    pub updated_by_id: Option<uuid::Uuid>,
+    pub deleted_by_id: Option<uuid::Uuid>,
+}
+
+impl SecretInsertable {
+    pub fn new(name: String, application_id: i32, user_from_id: Uuid) -> Self {
+        SecretInsertable {
+            application_id,
+            name,
+            current_version: 1,
+            is_deleted: false,
+            created_at: Utc::now(),
+            updated_at: None,
+            deleted_at: None,
+            created_by_id: Some(user_from_id),
+            updated_by_id: None,
+            deleted_by_id: None,
+        }
+    }
+}
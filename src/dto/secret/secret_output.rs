@@ -0,0 +1,26 @@
+use rocket_okapi::okapi::schemars;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::models::secret::Secret;
+
+#[derive(Deserialize, Serialize, Clone, Debug, JsonSchema)]
+pub struct SecretOutput {
+    pub id: String,
+    pub name: String,
+    pub current_version: i32,
+    pub created_at: String,
+    pub updated_at: Option<String>,
+}
+
+impl SecretOutput {
+    pub fn new(secret: Secret) -> Self {
+        SecretOutput {
+            id: secret.id.to_string(),
+            name: secret.name,
+            current_version: secret.current_version,
+            created_at: secret.created_at.to_string(),
+            updated_at: secret.updated_at.map(|updated_at| updated_at.to_string()),
+        }
+    }
+}
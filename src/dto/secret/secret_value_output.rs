@@ -0,0 +1,26 @@
+use rocket_okapi::okapi::schemars;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::models::{secret::Secret, secret_version::SecretVersion};
+
+#[derive(Deserialize, Serialize, Clone, Debug, JsonSchema)]
+pub struct SecretValueOutput {
+    pub id: String,
+    pub name: String,
+    pub version: i32,
+    pub value: String,
+    pub sum: String,
+}
+
+impl SecretValueOutput {
+    pub fn new(secret: Secret, secret_version: SecretVersion, value: String) -> Self {
+        SecretValueOutput {
+            id: secret.id.to_string(),
+            name: secret.name,
+            version: secret_version.version,
+            value,
+            sum: secret_version.sum,
+        }
+    }
+}
@@ -0,0 +1,40 @@
+use chrono::{DateTime, Utc};
+use diesel::prelude::Insertable;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::schema::secret_versions;
+
+#[derive(Deserialize, Serialize, Debug, Insertable)]
+#[diesel(table_name = secret_versions)]
+pub struct SecretVersionInsertable {
+    pub secret_id: Uuid,
+    pub version: i32,
+    pub iv: String,
+    pub sum: String,
+    pub is_deleted: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: Option<DateTime<Utc>>,
+    pub deleted_at: Option<DateTime<Utc>>,
+    pub created_by_id: Option<uuid::Uuid>,
+    pub updated_by_id: Option<uuid::Uuid>,
+    pub deleted_by_id: Option<uuid::Uuid>,
+}
+
+impl SecretVersionInsertable {
+    pub fn new(secret_id: Uuid, version: i32, iv: String, sum: String, user_from_id: Uuid) -> Self {
+        SecretVersionInsertable {
+            secret_id,
+            version,
+            iv,
+            sum,
+            is_deleted: false,
+            created_at: Utc::now(),
+            updated_at: None,
+            deleted_at: None,
+            created_by_id: Some(user_from_id),
+            updated_by_id: None,
+            deleted_by_id: None,
+        }
+    }
+}
@@ -0,0 +1,24 @@
+use rocket_okapi::okapi::schemars;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::models::secret_version::SecretVersion;
+
+#[derive(Deserialize, Serialize, Clone, Debug, JsonSchema)]
+pub struct SecretVersionOutput {
+    pub version: i32,
+    pub sum: String,
+    pub created_at: String,
+    pub created_by_id: Option<String>,
+}
+
+impl SecretVersionOutput {
+    pub fn new(secret_version: SecretVersion) -> Self {
+        SecretVersionOutput {
+            version: secret_version.version,
+            sum: secret_version.sum,
+            created_at: secret_version.created_at.to_string(),
+            created_by_id: secret_version.created_by_id.map(|id| id.to_string()),
+        }
+    }
+}
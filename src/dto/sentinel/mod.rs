@@ -1,3 +1,9 @@
 pub mod sentinel_input;
 pub mod sentinel_output;
-pub mod sentinel_insertable;
\ No newline at end of file
+pub mod sentinel_insertable;
+pub mod sentinel_import_input;
+pub mod sentinel_jwk;
+pub mod sentinel_hmac_input;
+pub mod sentinel_hmac_verify_input;
+pub mod sentinel_hmac_output;
+pub mod sentinel_hmac_verify_output;
\ No newline at end of file
@@ -0,0 +1,12 @@
+use serde::{Deserialize, Serialize};
+use rocket_okapi::okapi::schemars;
+use schemars::JsonSchema;
+
+
+#[derive(Deserialize, Serialize, Debug, JsonSchema, Clone)]
+pub struct SentinelHmacInput {
+    /// `HS256` or `HS512`
+    pub algorithm: String,
+    /// the base64 encoded payload
+    pub payload: String,
+}
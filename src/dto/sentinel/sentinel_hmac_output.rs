@@ -0,0 +1,23 @@
+use base64::{engine::general_purpose, Engine as _};
+use rocket_okapi::okapi::schemars;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{enums::mac_algorithm::MacAlgorithm, models::sentinel::Sentinel};
+
+#[derive(Deserialize, Serialize, Clone, Debug, JsonSchema)]
+pub struct SentinelHmacOutput {
+    pub id: String,
+    pub algorithm: String,
+    pub mac: String,
+}
+
+impl SentinelHmacOutput {
+    pub fn new(sentinel: Sentinel, algorithm: MacAlgorithm, mac: Vec<u8>) -> Self {
+        SentinelHmacOutput {
+            id: sentinel.id.to_string(),
+            algorithm: algorithm.as_str().to_string(),
+            mac: general_purpose::STANDARD.encode(mac),
+        }
+    }
+}
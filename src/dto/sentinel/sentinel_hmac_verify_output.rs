@@ -0,0 +1,22 @@
+use rocket_okapi::okapi::schemars;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{enums::mac_algorithm::MacAlgorithm, models::sentinel::Sentinel};
+
+#[derive(Deserialize, Serialize, Clone, Debug, JsonSchema)]
+pub struct SentinelHmacVerifyOutput {
+    pub id: String,
+    pub algorithm: String,
+    pub valid: bool,
+}
+
+impl SentinelHmacVerifyOutput {
+    pub fn new(sentinel: Sentinel, algorithm: MacAlgorithm, valid: bool) -> Self {
+        SentinelHmacVerifyOutput {
+            id: sentinel.id.to_string(),
+            algorithm: algorithm.as_str().to_string(),
+            valid,
+        }
+    }
+}
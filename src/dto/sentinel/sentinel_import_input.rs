@@ -0,0 +1,15 @@
+use serde::{Deserialize, Serialize};
+use rocket_okapi::okapi::schemars;
+use schemars::JsonSchema;
+
+
+#[derive(Deserialize, Serialize, Debug, JsonSchema, Clone)]
+pub struct SentinelImportInput {
+    pub import_key_id: String,
+    pub wrapped_key: String,
+    pub encapsulated_key: Option<String>,
+    pub nonce: Option<String>,
+    pub clusters: Vec<String>,
+    /// `encryption` (default) or `mac`
+    pub purpose: Option<String>,
+}
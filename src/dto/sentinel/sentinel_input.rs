@@ -5,5 +5,7 @@ use schemars::JsonSchema;
 
 #[derive(Deserialize, Serialize, Debug, JsonSchema, Clone)]
 pub struct SentinelInput {
-    pub clusters: Vec<String>
+    pub clusters: Vec<String>,
+    /// `encryption` (default) or `mac`
+    pub purpose: Option<String>,
 }
@@ -12,6 +12,7 @@ pub struct SentinelInsertable {
     pub iv: String,
     pub sum: String,
     pub key_size: i32,
+    pub purpose: String,
     pub is_deleted: bool,
     pub created_at: DateTime<Utc>,
     pub updated_at: Option<DateTime<Utc>>,
@@ -27,13 +28,15 @@ impl SentinelInsertable {
         sum: String,
         application_id: i32,
         user_from_id: Uuid,
-        key_size: i32
+        key_size: i32,
+        purpose: String,
     ) -> Self {
         SentinelInsertable {
             application_id,
             iv,
             sum,
             key_size,
+            purpose,
             is_deleted: false,
             created_at: Utc::now(),
             updated_at: None,
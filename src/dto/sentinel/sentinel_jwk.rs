@@ -0,0 +1,31 @@
+use rocket_okapi::okapi::schemars;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// an AES key as a RFC 7517 symmetric JWK, importable with WebCrypto
+#[derive(Deserialize, Serialize, Clone, Debug, JsonSchema)]
+pub struct SentinelJwk {
+    pub kty: String,
+    pub k: String,
+    pub alg: String,
+    pub kid: String,
+    pub ext: bool,
+    pub key_ops: Vec<String>,
+}
+
+impl SentinelJwk {
+    pub fn new(kid: String, k: String, key_size: i32) -> Self {
+        let alg = match key_size {
+            256 => String::from("A256GCM"),
+            _ => String::from("A128GCM"),
+        };
+        SentinelJwk {
+            kty: String::from("oct"),
+            k,
+            alg,
+            kid,
+            ext: true,
+            key_ops: vec![String::from("encrypt"), String::from("decrypt")],
+        }
+    }
+}
@@ -2,7 +2,11 @@ use rocket_okapi::okapi::schemars;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use crate::models::sentinel::Sentinel;
+use crate::{
+    dto::sentinel::sentinel_jwk::SentinelJwk,
+    enums::{key_format::KeyFormat, sentinel_purpose::SentinelPurpose},
+    models::sentinel::Sentinel, utils::key_encoding::KeyEncoding,
+};
 
 #[derive(Deserialize, Serialize, Clone, Debug, JsonSchema)]
 
@@ -11,19 +15,68 @@ pub struct SentinelOutput {
     pub key_size: String,
     pub cipher: String,
     pub sum: String,
+    pub purpose: String,
+    pub format: String,
+    /// set while a break-glass read is not acknowledged by an admin
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub break_glass_at: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub jwk: Option<SentinelJwk>,
 }
 
 impl SentinelOutput {
+    /// the key of a MAC sentinel is never part of the output
     pub fn new(sentinel: Sentinel, cipher: String) -> Self {
-        let key_size = match sentinel.key_size {
-            256 => format!("AES-256"),
-            _ => format!("AES-128")
+        let is_mac = sentinel.purpose == SentinelPurpose::Mac.as_str();
+        let key_size = match (is_mac, sentinel.key_size) {
+            (true, size) => format!("HMAC-{}", size),
+            (false, 256) => String::from("AES-256"),
+            (false, _) => String::from("AES-128"),
+        };
+        let cipher = match is_mac {
+            true => String::new(),
+            false => cipher,
         };
         SentinelOutput {
             id: sentinel.id.to_string(),
             cipher,
             key_size,
             sum: sentinel.sum,
+            purpose: sentinel.purpose,
+            format: KeyFormat::Hex.as_str().to_string(),
+            break_glass_at: sentinel.break_glass_at.map(|date| date.to_string()),
+            jwk: None,
+        }
+    }
+
+    /// the output with the key (`cipher`) in the requested export format,
+    /// `key_encryption_key` is the hex KEK used by the `wrapped` format
+    pub fn formatted(
+        sentinel: Sentinel,
+        cipher: String,
+        format: &KeyFormat,
+        key_encryption_key: Option<String>,
+    ) -> Result<Self, &'static str> {
+        let mut output = SentinelOutput::new(sentinel.clone(), cipher.clone());
+        output.format = format.as_str().to_string();
+        match format {
+            KeyFormat::Hex => {}
+            KeyFormat::RawBase64 => output.cipher = KeyEncoding::to_base64(&cipher)?,
+            KeyFormat::Jwk => {
+                let k = KeyEncoding::to_base64_url(&cipher)?;
+                output.jwk = Some(SentinelJwk::new(
+                    sentinel.id.to_string(),
+                    k.clone(),
+                    sentinel.key_size,
+                ));
+                output.cipher = k;
+            }
+            KeyFormat::Wrapped => match key_encryption_key {
+                None => return Err("The wrapped format requires a key-encryption key"),
+                Some(kek) => output.cipher = KeyEncoding::wrap(&cipher, &kek)?,
+            },
+            KeyFormat::Pem => return Err("The pem format is only available for public keys"),
         }
+        Ok(output)
     }
 }
@@ -0,0 +1,5 @@
+pub mod service_account_input;
+pub mod service_account_insertable;
+pub mod service_account_output;
+pub mod service_account_credentials_input;
+pub mod service_account_signing_key_input;
\ No newline at end of file
@@ -0,0 +1,9 @@
+use serde::{Deserialize, Serialize};
+use rocket_okapi::okapi::schemars;
+use schemars::JsonSchema;
+
+
+#[derive(Deserialize, Serialize, Debug, JsonSchema, Clone)]
+pub struct ServiceAccountCredentialsInput {
+    pub public_key: Option<String>,
+}
@@ -0,0 +1,52 @@
+use chrono::{DateTime, Utc};
+use diesel::prelude::Insertable;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::schema::service_accounts;
+
+#[derive(Deserialize, Serialize, Debug, Insertable)]
+#[diesel(table_name = service_accounts)]
+pub struct ServiceAccountInsertable {
+    pub user_id: Uuid,
+    pub application_id: i32,
+    pub name: String,
+    pub client_id: String,
+    pub client_secret_hash: Option<String>,
+    pub public_key: Option<String>,
+    pub is_deleted: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: Option<DateTime<Utc>>,
+    pub deleted_at: Option<DateTime<Utc>>,
+    pub created_by_id: Option<uuid::Uuid>,
+    pub updated_by_id: Option<uuid::Uuid>,
+    pub deleted_by_id: Option<uuid::Uuid>,
+}
+
+impl ServiceAccountInsertable {
+    /// `user_id` is set once the user of the account is inserted
+    pub fn new(
+        application_id: i32,
+        name: String,
+        client_id: String,
+        client_secret_hash: Option<String>,
+        public_key: Option<String>,
+        user_from_id: Uuid,
+    ) -> Self {
+        ServiceAccountInsertable {
+            user_id: Uuid::nil(),
+            application_id,
+            name,
+            client_id,
+            client_secret_hash,
+            public_key,
+            is_deleted: false,
+            created_at: Utc::now(),
+            updated_at: None,
+            deleted_at: None,
+            created_by_id: Some(user_from_id),
+            updated_by_id: None,
+            deleted_by_id: None,
+        }
+    }
+}
@@ -0,0 +1,43 @@
+use rocket_okapi::okapi::schemars;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::models::service_account::ServiceAccount;
+
+#[derive(Deserialize, Serialize, Clone, Debug, JsonSchema)]
+pub struct ServiceAccountOutput {
+    pub id: String,
+    /// the identity to add to the clusters
+    pub user_id: String,
+    pub name: String,
+    pub client_id: String,
+    /// `client_secret` or `private_key_jwt`
+    pub auth_method: String,
+    /// only returned when the secret is created
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub client_secret: Option<String>,
+    /// whether the account can sign its requests (HTTP message signatures)
+    pub signs_requests: bool,
+    pub last_used_at: Option<String>,
+    pub created_at: String,
+}
+
+impl ServiceAccountOutput {
+    pub fn new(service_account: ServiceAccount, client_secret: Option<String>) -> Self {
+        let auth_method = match service_account.public_key {
+            Some(_) => "private_key_jwt",
+            None => "client_secret",
+        };
+        ServiceAccountOutput {
+            id: service_account.id.to_string(),
+            user_id: service_account.user_id.to_string(),
+            name: service_account.name,
+            client_id: service_account.client_id,
+            auth_method: auth_method.to_string(),
+            client_secret,
+            signs_requests: service_account.signing_key.is_some(),
+            last_used_at: service_account.last_used_at.map(|date| date.to_string()),
+            created_at: service_account.created_at.to_string(),
+        }
+    }
+}
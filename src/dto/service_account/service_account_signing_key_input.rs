@@ -0,0 +1,10 @@
+use serde::{Deserialize, Serialize};
+use rocket_okapi::okapi::schemars;
+use schemars::JsonSchema;
+
+
+#[derive(Deserialize, Serialize, Debug, JsonSchema, Clone)]
+pub struct ServiceAccountSigningKeyInput {
+    /// the PEM public key checking the signatures, none to stop accepting signed requests
+    pub signing_key: Option<String>,
+}
@@ -0,0 +1,2 @@
+pub mod session_insertable;
+pub mod session_output;
\ No newline at end of file
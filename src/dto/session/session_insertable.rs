@@ -0,0 +1,51 @@
+use chrono::{DateTime, Utc};
+use diesel::prelude::Insertable;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::schema::sessions;
+
+#[derive(Deserialize, Serialize, Debug, Insertable)]
+#[diesel(table_name = sessions)]
+pub struct SessionInsertable {
+    pub user_id: Uuid,
+    pub application_id: i32,
+    pub connexion_id: Option<i32>,
+    pub refresh_jti: Uuid,
+    pub expires_at: DateTime<Utc>,
+    pub revoked_at: Option<DateTime<Utc>>,
+    pub is_deleted: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: Option<DateTime<Utc>>,
+    pub deleted_at: Option<DateTime<Utc>>,
+    pub created_by_id: Option<uuid::Uuid>,
+    pub updated_by_id: Option<uuid::Uuid>,
+    pub deleted_by_id: Option<uuid::Uuid>,
+    pub last_used_at: DateTime<Utc>,
+}
+
+impl SessionInsertable {
+    pub fn new(
+        user_id: Uuid,
+        application_id: i32,
+        connexion_id: Option<i32>,
+        expires_at: DateTime<Utc>,
+    ) -> Self {
+        SessionInsertable {
+            user_id,
+            application_id,
+            connexion_id,
+            refresh_jti: Uuid::new_v4(),
+            expires_at,
+            revoked_at: None,
+            is_deleted: false,
+            created_at: Utc::now(),
+            updated_at: None,
+            deleted_at: None,
+            created_by_id: Some(user_id),
+            updated_by_id: None,
+            deleted_by_id: None,
+            last_used_at: Utc::now(),
+        }
+    }
+}
@@ -0,0 +1,53 @@
+use rocket_okapi::okapi::schemars;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+use woothee::{parser::Parser, woothee::VALUE_UNKNOWN};
+
+use crate::models::{connexion::Connexion, session::Session};
+
+#[derive(Deserialize, Serialize, Clone, Debug, JsonSchema)]
+pub struct SessionOutput {
+    pub id: String,
+    /// the session of the token listing the sessions
+    pub current: bool,
+    pub ip: Option<String>,
+    pub fingerprint: Option<String>,
+    pub user_agent: Option<String>,
+    /// `pc`, `smartphone`, `mobilephone`, `appliance` or `crawler`
+    pub device: Option<String>,
+    pub os: Option<String>,
+    pub os_version: Option<String>,
+    pub browser: Option<String>,
+    pub browser_version: Option<String>,
+    pub created_at: String,
+    /// the latest refresh of the session
+    pub last_used_at: String,
+    pub expires_at: String,
+}
+
+impl SessionOutput {
+    pub fn new(session: Session, connexion: Option<Connexion>, current: Option<Uuid>) -> Self {
+        let known = |value: &str| match value {
+            "" | VALUE_UNKNOWN => None,
+            value => Some(value.to_string()),
+        };
+        let user_agent = connexion.as_ref().map(|connexion| connexion.user_agent.clone());
+        let parsed = user_agent.as_deref().and_then(|user_agent| Parser::new().parse(user_agent));
+        SessionOutput {
+            id: session.id.to_string(),
+            current: current == Some(session.id),
+            ip: connexion.as_ref().map(|connexion| connexion.ip.clone()),
+            fingerprint: connexion.as_ref().map(|connexion| connexion.fingerprint.clone()),
+            device: parsed.as_ref().and_then(|result| known(result.category)),
+            os: parsed.as_ref().and_then(|result| known(result.os)),
+            os_version: parsed.as_ref().and_then(|result| known(&result.os_version)),
+            browser: parsed.as_ref().and_then(|result| known(result.name)),
+            browser_version: parsed.as_ref().and_then(|result| known(result.version)),
+            user_agent,
+            created_at: session.created_at.to_string(),
+            last_used_at: session.last_used_at.to_string(),
+            expires_at: session.expires_at.to_string(),
+        }
+    }
+}
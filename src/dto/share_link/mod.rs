@@ -0,0 +1,4 @@
+pub mod share_link_input;
+pub mod share_link_insertable;
+pub mod share_link_output;
+pub mod share_link_redeem_output;
\ No newline at end of file
@@ -0,0 +1,11 @@
+use serde::{Deserialize, Serialize};
+use rocket_okapi::okapi::schemars;
+use schemars::JsonSchema;
+
+
+#[derive(Deserialize, Serialize, Debug, JsonSchema, Clone)]
+pub struct ShareLinkInput {
+    pub expires_in: Option<i64>,
+    pub max_uses: Option<i32>,
+    pub recipient_public_key: Option<String>,
+}
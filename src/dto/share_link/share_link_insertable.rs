@@ -0,0 +1,51 @@
+use chrono::{DateTime, Utc};
+use diesel::prelude::Insertable;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::schema::share_links;
+
+#[derive(Deserialize, Serialize, Debug, Insertable)]
+#[diesel(table_name = share_links)]
+pub struct ShareLinkInsertable {
+    pub sentinel_id: Uuid,
+    pub token_hash: String,
+    pub max_uses: i32,
+    pub use_count: i32,
+    pub expires_at: DateTime<Utc>,
+    pub recipient_public_key: Option<String>,
+    pub is_deleted: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: Option<DateTime<Utc>>,
+    pub deleted_at: Option<DateTime<Utc>>,
+    pub created_by_id: Option<uuid::Uuid>,
+    pub updated_by_id: Option<uuid::Uuid>,
+    pub deleted_by_id: Option<uuid::Uuid>,
+}
+
+impl ShareLinkInsertable {
+    pub fn new(
+        sentinel_id: Uuid,
+        token_hash: String,
+        max_uses: i32,
+        expires_at: DateTime<Utc>,
+        recipient_public_key: Option<String>,
+        user_from_id: Uuid,
+    ) -> Self {
+        ShareLinkInsertable {
+            sentinel_id,
+            token_hash,
+            max_uses,
+            use_count: 0,
+            expires_at,
+            recipient_public_key,
+            is_deleted: false,
+            created_at: Utc::now(),
+            updated_at: None,
+            deleted_at: None,
+            created_by_id: Some(user_from_id),
+            updated_by_id: None,
+            deleted_by_id: None,
+        }
+    }
+}
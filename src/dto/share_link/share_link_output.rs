@@ -0,0 +1,29 @@
+use rocket_okapi::okapi::schemars;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::models::share_link::ShareLink;
+
+#[derive(Deserialize, Serialize, Clone, Debug, JsonSchema)]
+pub struct ShareLinkOutput {
+    pub id: String,
+    pub sentinel_id: String,
+    /// the token is only returned at the creation of the link
+    pub token: String,
+    pub max_uses: i32,
+    pub expires_at: String,
+    pub created_at: String,
+}
+
+impl ShareLinkOutput {
+    pub fn new(share_link: ShareLink, token: String) -> Self {
+        ShareLinkOutput {
+            id: share_link.id.to_string(),
+            sentinel_id: share_link.sentinel_id.to_string(),
+            token,
+            max_uses: share_link.max_uses,
+            expires_at: share_link.expires_at.to_string(),
+            created_at: share_link.created_at.to_string(),
+        }
+    }
+}
@@ -0,0 +1,54 @@
+use rocket_okapi::okapi::schemars;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::models::{sentinel::Sentinel, share_link::ShareLink};
+
+/// the key of a redeemed link, in clear (`cipher`) or encapsulated to the recipient
+/// ML-KEM-768 public key bound to the link
+#[derive(Deserialize, Serialize, Clone, Debug, JsonSchema)]
+pub struct ShareLinkRedeemOutput {
+    pub sentinel_id: String,
+    pub key_size: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cipher: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub encapsulated_key: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nonce: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub wrapped_key: Option<String>,
+    pub remaining_uses: i32,
+}
+
+impl ShareLinkRedeemOutput {
+    /// `wrapped` is the (encapsulated key, nonce, wrapped key) of a link bound to a recipient
+    pub fn new(
+        share_link: &ShareLink,
+        sentinel: &Sentinel,
+        cipher: String,
+        wrapped: Option<(Vec<u8>, Vec<u8>, Vec<u8>)>,
+    ) -> Self {
+        let remaining_uses = (share_link.max_uses - share_link.use_count).max(0);
+        match wrapped {
+            None => ShareLinkRedeemOutput {
+                sentinel_id: sentinel.id.to_string(),
+                key_size: sentinel.key_size.to_string(),
+                cipher: Some(cipher),
+                encapsulated_key: None,
+                nonce: None,
+                wrapped_key: None,
+                remaining_uses,
+            },
+            Some((encapsulated_key, nonce, wrapped_key)) => ShareLinkRedeemOutput {
+                sentinel_id: sentinel.id.to_string(),
+                key_size: sentinel.key_size.to_string(),
+                cipher: None,
+                encapsulated_key: Some(hex::encode(encapsulated_key)),
+                nonce: Some(hex::encode(nonce)),
+                wrapped_key: Some(hex::encode(wrapped_key)),
+                remaining_uses,
+            },
+        }
+    }
+}
@@ -0,0 +1 @@
+pub mod signing_key_insertable;
\ No newline at end of file
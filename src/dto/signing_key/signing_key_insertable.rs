@@ -0,0 +1,51 @@
+use chrono::{DateTime, Utc};
+use diesel::prelude::Insertable;
+use serde::{Deserialize, Serialize};
+
+use crate::schema::signing_keys;
+
+#[derive(Deserialize, Serialize, Debug, Insertable)]
+#[diesel(table_name = signing_keys)]
+pub struct SigningKeyInsertable {
+    pub kid: String,
+    pub algorithm: String,
+    pub public_key: String,
+    pub private_key: String,
+    pub iv: String,
+    pub activates_at: DateTime<Utc>,
+    pub retired_at: Option<DateTime<Utc>>,
+    pub is_deleted: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: Option<DateTime<Utc>>,
+    pub deleted_at: Option<DateTime<Utc>>,
+    pub created_by_id: Option<uuid::Uuid>,
+    pub updated_by_id: Option<uuid::Uuid>,
+    pub deleted_by_id: Option<uuid::Uuid>,
+}
+
+impl SigningKeyInsertable {
+    pub fn new(
+        kid: String,
+        public_key: String,
+        private_key: String,
+        iv: String,
+        activates_at: DateTime<Utc>,
+    ) -> Self {
+        SigningKeyInsertable {
+            kid,
+            algorithm: String::from("RS256"),
+            public_key,
+            private_key,
+            iv,
+            activates_at,
+            retired_at: None,
+            is_deleted: false,
+            created_at: Utc::now(),
+            updated_at: None,
+            deleted_at: None,
+            created_by_id: None,
+            updated_by_id: None,
+            deleted_by_id: None,
+        }
+    }
+}
@@ -7,4 +7,7 @@ pub mod user_reset_input;
 pub mod user_input;
 pub mod user_password_input;
 pub mod user_totp_code;
-pub mod user_2fa_activate_input;
\ No newline at end of file
+pub mod user_2fa_activate_input;pub mod user_break_glass_input;
+
+pub mod user_recovery_codes;
+pub mod user_restricted_ip_input;
\ No newline at end of file
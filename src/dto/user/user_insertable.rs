@@ -189,4 +189,45 @@ impl UserInsertable {
             refresh_token: None,
         }
     }
+
+    /// the user behind a service account, it has no password nor email and logs in with
+    /// the client credentials of its account
+    pub fn new_service_account(name: &str, client_id: &str, app_id: i32, user_from_id: Uuid) -> Self {
+        let (public, secret) = PQKyber::generate_key_pair();
+        let pq_sk = hex::encode(secret);
+        let pq_pk = hex::encode(public);
+        let iv = Crypto::generate_unique_iv();
+        let pq_sk_encrypted = PQKyber::encrypt_key(pq_sk, iv.clone());
+        let pq_pk_encrypted = PQKyber::encrypt_key(pq_pk, iv.clone());
+        UserInsertable {
+            email: String::new(),
+            password: None,
+            firstname: name.to_string(),
+            lastname: String::new(),
+            login: client_id.to_string(),
+            validation_tries: 0,
+            twofa_code: generate_base32_key(160),
+            is_2fa_activated: false,
+            full_text_search: format!("{} {}", client_id, name),
+            is_deleted: false,
+            is_validated: true,
+            validation_code: None,
+            created_at: Utc::now(),
+            updated_at: None,
+            roles: vec![
+                String::from("ROLE_USER"),
+                String::from("ROLE_SERVICE_ACCOUNT"),
+            ],
+            restricted_ip: None,
+            application: Some(app_id),
+            kyber_secret_key: pq_sk_encrypted,
+            kyber_public_key: pq_pk_encrypted,
+            iv,
+            deleted_at: None,
+            created_by_id: Some(user_from_id),
+            updated_by_id: None,
+            deleted_by_id: None,
+            refresh_token: None,
+        }
+    }
 }
@@ -13,6 +13,8 @@ pub struct UserOutput {
     pub lastname: String,
     pub login: String,
     pub roles: Vec<Option<String>>,
+    /// the IP addresses or CIDR ranges the user connects from, every IP when empty
+    pub restricted_ip: Vec<String>,
     pub created_at: String,
 }
 
@@ -25,6 +27,7 @@ impl UserOutput {
             firstname: user.firstname,
             lastname: user.lastname,
             roles: user.roles,
+            restricted_ip: user.restricted_ip.into_iter().flatten().collect(),
             created_at: user.created_at.to_string(),
         }
     }
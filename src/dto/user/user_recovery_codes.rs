@@ -0,0 +1,15 @@
+use serde::{Deserialize, Serialize};
+use rocket_okapi::okapi::schemars;
+use schemars::JsonSchema;
+
+/// the recovery codes of a user, shown once when 2FA is activated
+#[derive(Deserialize, Serialize, Debug, Clone, JsonSchema)]
+pub struct UserRecoveryCodes {
+    pub recovery_codes: Vec<String>,
+}
+
+impl UserRecoveryCodes {
+    pub fn new(recovery_codes: Vec<String>) -> Self {
+        UserRecoveryCodes { recovery_codes }
+    }
+}
@@ -0,0 +1,12 @@
+use serde::{Deserialize, Serialize};
+use rocket_okapi::okapi::schemars;
+use schemars::JsonSchema;
+
+use crate::utils::validator::vec_ip;
+
+#[derive(Deserialize, Serialize, Debug, JsonSchema, Clone)]
+pub struct UserRestrictedIpInput {
+    /// the IP addresses or CIDR ranges the user connects from, an empty list allows every IP
+    #[serde(deserialize_with = "vec_ip")]
+    pub restricted_ip: Vec<String>,
+}
@@ -0,0 +1,8 @@
+pub mod webauthn_assertion_input;
+pub mod webauthn_assertion_options;
+pub mod webauthn_assertion_start_input;
+pub mod webauthn_credential_insertable;
+pub mod webauthn_credential_output;
+pub mod webauthn_login_input;
+pub mod webauthn_registration_input;
+pub mod webauthn_registration_options;
\ No newline at end of file
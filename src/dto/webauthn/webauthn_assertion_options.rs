@@ -0,0 +1,38 @@
+use rocket_okapi::okapi::schemars;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    dto::webauthn::webauthn_registration_options::WebauthnCredentialDescriptor,
+    models::webauthn_credential::WebauthnCredential,
+    utils::webauthn::{Webauthn, CEREMONY_TIMEOUT},
+};
+
+/// the `publicKey` options of `navigator.credentials.get()`, the binary values are url safe base64
+#[derive(Deserialize, Serialize, Clone, Debug, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct WebauthnAssertionOptions {
+    pub challenge: String,
+    pub rp_id: String,
+    /// in milliseconds
+    pub timeout: u64,
+    /// empty for a passwordless login, the authenticator offers its passkeys
+    pub allow_credentials: Vec<WebauthnCredentialDescriptor>,
+    pub user_verification: String,
+}
+
+impl WebauthnAssertionOptions {
+    pub fn new(challenge: String, credentials: &[WebauthnCredential]) -> Self {
+        WebauthnAssertionOptions {
+            challenge,
+            rp_id: Webauthn::rp_id(),
+            timeout: CEREMONY_TIMEOUT * 1000,
+            allow_credentials: credentials.iter().map(WebauthnCredentialDescriptor::new).collect(),
+            // a passwordless login requires it, a second factor accepts a user only present
+            user_verification: match credentials.is_empty() {
+                true => "required".to_string(),
+                false => "preferred".to_string(),
+            },
+        }
+    }
+}
@@ -0,0 +1,10 @@
+use serde::{Deserialize, Serialize};
+use rocket_okapi::okapi::schemars;
+use schemars::JsonSchema;
+
+
+#[derive(Deserialize, Serialize, Debug, JsonSchema, Clone)]
+pub struct WebauthnAssertionStartInput {
+    pub application_id: i32,
+    pub login: Option<String>,
+}
@@ -0,0 +1,53 @@
+use chrono::{DateTime, Utc};
+use diesel::prelude::Insertable;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::schema::webauthn_credentials;
+
+#[derive(Deserialize, Serialize, Debug, Insertable)]
+#[diesel(table_name = webauthn_credentials)]
+pub struct WebauthnCredentialInsertable {
+    pub user_id: Uuid,
+    pub name: String,
+    pub credential_id: String,
+    pub public_key: String,
+    pub algorithm: i32,
+    pub sign_count: i64,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub is_deleted: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: Option<DateTime<Utc>>,
+    pub deleted_at: Option<DateTime<Utc>>,
+    pub created_by_id: Option<uuid::Uuid>,
+    pub updated_by_id: Option<uuid::Uuid>,
+    pub deleted_by_id: Option<uuid::Uuid>,
+}
+
+impl WebauthnCredentialInsertable {
+    pub fn new(
+        user_id: Uuid,
+        name: String,
+        credential_id: String,
+        public_key: String,
+        algorithm: i32,
+        sign_count: i64,
+    ) -> Self {
+        WebauthnCredentialInsertable {
+            user_id,
+            name,
+            credential_id,
+            public_key,
+            algorithm,
+            sign_count,
+            last_used_at: None,
+            is_deleted: false,
+            created_at: Utc::now(),
+            updated_at: None,
+            deleted_at: None,
+            created_by_id: Some(user_id),
+            updated_by_id: None,
+            deleted_by_id: None,
+        }
+    }
+}
@@ -0,0 +1,37 @@
+use rocket_okapi::okapi::schemars;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    models::webauthn_credential::WebauthnCredential,
+    utils::webauthn::{COSE_EDDSA, COSE_ES256},
+};
+
+#[derive(Deserialize, Serialize, Clone, Debug, JsonSchema)]
+pub struct WebauthnCredentialOutput {
+    pub id: String,
+    pub name: String,
+    pub credential_id: String,
+    /// `ES256`, `EdDSA` or `RS256`
+    pub algorithm: String,
+    pub last_used_at: Option<String>,
+    pub created_at: String,
+}
+
+impl WebauthnCredentialOutput {
+    pub fn new(credential: WebauthnCredential) -> Self {
+        let algorithm = match credential.algorithm {
+            COSE_ES256 => "ES256",
+            COSE_EDDSA => "EdDSA",
+            _ => "RS256",
+        };
+        WebauthnCredentialOutput {
+            id: credential.id.to_string(),
+            name: credential.name,
+            credential_id: credential.credential_id,
+            algorithm: algorithm.to_string(),
+            last_used_at: credential.last_used_at.map(|date| date.to_string()),
+            created_at: credential.created_at.to_string(),
+        }
+    }
+}
@@ -0,0 +1,12 @@
+use serde::{Deserialize, Serialize};
+use rocket_okapi::okapi::schemars;
+use schemars::JsonSchema;
+
+use crate::dto::webauthn::webauthn_assertion_input::WebauthnAssertionInput;
+
+#[derive(Deserialize, Serialize, Debug, JsonSchema, Clone)]
+pub struct WebauthnLoginInput {
+    pub application_id: i32,
+    pub fingerprint: String,
+    pub assertion: WebauthnAssertionInput,
+}
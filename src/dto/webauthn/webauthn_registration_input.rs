@@ -0,0 +1,12 @@
+use serde::{Deserialize, Serialize};
+use rocket_okapi::okapi::schemars;
+use schemars::JsonSchema;
+
+
+#[derive(Deserialize, Serialize, Debug, JsonSchema, Clone)]
+pub struct WebauthnRegistrationInput {
+    pub name: Option<String>,
+    pub id: String,
+    pub client_data_json: String,
+    pub attestation_object: String,
+}
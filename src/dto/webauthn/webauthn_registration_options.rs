@@ -0,0 +1,100 @@
+use rocket_okapi::okapi::schemars;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    models::{user::User, webauthn_credential::WebauthnCredential},
+    utils::webauthn::{Webauthn, CEREMONY_TIMEOUT, COSE_EDDSA, COSE_ES256, COSE_RS256},
+};
+
+/// the `publicKey` options of `navigator.credentials.create()`, the binary values are url safe base64
+#[derive(Deserialize, Serialize, Clone, Debug, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct WebauthnRegistrationOptions {
+    pub challenge: String,
+    pub rp: WebauthnRelyingParty,
+    pub user: WebauthnUser,
+    pub pub_key_cred_params: Vec<WebauthnCredentialParameter>,
+    /// in milliseconds
+    pub timeout: u64,
+    pub attestation: String,
+    /// the passkeys the user already registered
+    pub exclude_credentials: Vec<WebauthnCredentialDescriptor>,
+    pub authenticator_selection: WebauthnAuthenticatorSelection,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, JsonSchema)]
+pub struct WebauthnRelyingParty {
+    pub id: String,
+    pub name: String,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct WebauthnUser {
+    /// the bytes of the uuid of the user, returned as the `userHandle` of the assertions
+    pub id: String,
+    pub name: String,
+    pub display_name: String,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, JsonSchema)]
+pub struct WebauthnCredentialParameter {
+    #[serde(rename = "type")]
+    pub credential_type: String,
+    pub alg: i32,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, JsonSchema)]
+pub struct WebauthnCredentialDescriptor {
+    #[serde(rename = "type")]
+    pub credential_type: String,
+    pub id: String,
+}
+
+#[derive(Deserialize, Serialize, Clone, Debug, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct WebauthnAuthenticatorSelection {
+    pub resident_key: String,
+    pub user_verification: String,
+}
+
+impl WebauthnCredentialDescriptor {
+    pub fn new(credential: &WebauthnCredential) -> Self {
+        WebauthnCredentialDescriptor {
+            credential_type: "public-key".to_string(),
+            id: credential.credential_id.clone(),
+        }
+    }
+}
+
+impl WebauthnRegistrationOptions {
+    pub fn new(challenge: String, user: &User, credentials: &[WebauthnCredential]) -> Self {
+        WebauthnRegistrationOptions {
+            challenge,
+            rp: WebauthnRelyingParty {
+                id: Webauthn::rp_id(),
+                name: Webauthn::rp_name(),
+            },
+            user: WebauthnUser {
+                id: Webauthn::encode_base64(user.id.as_bytes()),
+                name: user.login.clone(),
+                display_name: format!("{} {}", user.firstname, user.lastname).trim().to_string(),
+            },
+            pub_key_cred_params: [COSE_ES256, COSE_EDDSA, COSE_RS256]
+                .into_iter()
+                .map(|alg| WebauthnCredentialParameter {
+                    credential_type: "public-key".to_string(),
+                    alg,
+                })
+                .collect(),
+            timeout: CEREMONY_TIMEOUT * 1000,
+            attestation: "none".to_string(),
+            exclude_credentials: credentials.iter().map(WebauthnCredentialDescriptor::new).collect(),
+            authenticator_selection: WebauthnAuthenticatorSelection {
+                resident_key: "preferred".to_string(),
+                user_verification: "preferred".to_string(),
+            },
+        }
+    }
+}
@@ -0,0 +1 @@
+pub mod x_secret_cluster_insertable;
\ No newline at end of file
@@ -0,0 +1,30 @@
+/// the life cycle of a dual control access request,
+/// an expired request keeps its last status
+#[derive(PartialEq)]
+pub enum AccessRequestStatus {
+    Pending,
+    Approved,
+    Rejected,
+    Consumed,
+}
+
+impl AccessRequestStatus {
+    pub fn from_str(status: &str) -> Option<Self> {
+        match status {
+            "pending" => Some(AccessRequestStatus::Pending),
+            "approved" => Some(AccessRequestStatus::Approved),
+            "rejected" => Some(AccessRequestStatus::Rejected),
+            "consumed" => Some(AccessRequestStatus::Consumed),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AccessRequestStatus::Pending => "pending",
+            AccessRequestStatus::Approved => "approved",
+            AccessRequestStatus::Rejected => "rejected",
+            AccessRequestStatus::Consumed => "consumed",
+        }
+    }
+}
@@ -0,0 +1,30 @@
+/// the flows guessing a secret, each counts its failed attempts apart
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum AttemptScope {
+    Login,
+    SecondFactor,
+    Validation,
+    PasswordReset,
+}
+
+impl AttemptScope {
+    pub const ALL: [AttemptScope; 4] = [
+        AttemptScope::Login,
+        AttemptScope::SecondFactor,
+        AttemptScope::Validation,
+        AttemptScope::PasswordReset,
+    ];
+
+    pub fn from_str(scope: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|attempt_scope| attempt_scope.as_str() == scope)
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AttemptScope::Login => "login",
+            AttemptScope::SecondFactor => "2fa",
+            AttemptScope::Validation => "validation",
+            AttemptScope::PasswordReset => "password_reset",
+        }
+    }
+}
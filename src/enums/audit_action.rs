@@ -0,0 +1,37 @@
+/// the actions recorded in the audit log
+#[derive(PartialEq, Clone, Copy)]
+pub enum AuditAction {
+    AccountLocked,
+    AccountUnlocked,
+    BreakGlassRead,
+    FlagAcknowledged,
+    IpDenied,
+    IpRestrictionUpdated,
+    RecoveryCodeUsed,
+    RefreshTokenReused,
+    RoleGranted,
+    RoleRevoked,
+    SessionRevoked,
+    ShareLinkCreated,
+    ShareLinkRedeemed,
+}
+
+impl AuditAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuditAction::AccountLocked => "account_locked",
+            AuditAction::AccountUnlocked => "account_unlocked",
+            AuditAction::BreakGlassRead => "break_glass_read",
+            AuditAction::FlagAcknowledged => "break_glass_acknowledged",
+            AuditAction::IpDenied => "ip_denied",
+            AuditAction::IpRestrictionUpdated => "ip_restriction_updated",
+            AuditAction::RecoveryCodeUsed => "recovery_code_used",
+            AuditAction::RefreshTokenReused => "refresh_token_reused",
+            AuditAction::RoleGranted => "break_glass_role_granted",
+            AuditAction::RoleRevoked => "break_glass_role_revoked",
+            AuditAction::SessionRevoked => "session_revoked",
+            AuditAction::ShareLinkCreated => "share_link_created",
+            AuditAction::ShareLinkRedeemed => "share_link_redeemed",
+        }
+    }
+}
@@ -0,0 +1,25 @@
+#[derive(PartialEq, Clone, Copy)]
+pub enum AuditSeverity {
+    Low,
+    Medium,
+    High,
+}
+
+impl AuditSeverity {
+    pub fn from_str(severity: &str) -> Option<Self> {
+        match severity {
+            "low" => Some(AuditSeverity::Low),
+            "medium" => Some(AuditSeverity::Medium),
+            "high" => Some(AuditSeverity::High),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuditSeverity::Low => "low",
+            AuditSeverity::Medium => "medium",
+            AuditSeverity::High => "high",
+        }
+    }
+}
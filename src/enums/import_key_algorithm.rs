@@ -0,0 +1,21 @@
+pub enum ImportKeyAlgorithm {
+    RsaOaep256,
+    MlKem768,
+}
+
+impl ImportKeyAlgorithm {
+    pub fn from_str(algorithm: &str) -> Option<Self> {
+        match algorithm {
+            "RSA-OAEP-256" => Some(ImportKeyAlgorithm::RsaOaep256),
+            "ML-KEM-768" => Some(ImportKeyAlgorithm::MlKem768),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ImportKeyAlgorithm::RsaOaep256 => "RSA-OAEP-256",
+            ImportKeyAlgorithm::MlKem768 => "ML-KEM-768",
+        }
+    }
+}
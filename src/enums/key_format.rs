@@ -0,0 +1,30 @@
+pub enum KeyFormat {
+    Hex,
+    Jwk,
+    RawBase64,
+    Wrapped,
+    Pem,
+}
+
+impl KeyFormat {
+    pub fn from_str(format: &str) -> Option<Self> {
+        match format {
+            "hex" => Some(KeyFormat::Hex),
+            "jwk" => Some(KeyFormat::Jwk),
+            "raw-base64" => Some(KeyFormat::RawBase64),
+            "wrapped" => Some(KeyFormat::Wrapped),
+            "pem" => Some(KeyFormat::Pem),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            KeyFormat::Hex => "hex",
+            KeyFormat::Jwk => "jwk",
+            KeyFormat::RawBase64 => "raw-base64",
+            KeyFormat::Wrapped => "wrapped",
+            KeyFormat::Pem => "pem",
+        }
+    }
+}
@@ -0,0 +1,29 @@
+/// the operations a key policy can allow on a sentinel
+#[derive(PartialEq, Clone, Copy)]
+pub enum KeyOperation {
+    Read,
+    Encrypt,
+    Decrypt,
+    Delete,
+}
+
+impl KeyOperation {
+    pub fn from_str(operation: &str) -> Option<Self> {
+        match operation {
+            "read" => Some(KeyOperation::Read),
+            "encrypt" => Some(KeyOperation::Encrypt),
+            "decrypt" => Some(KeyOperation::Decrypt),
+            "delete" => Some(KeyOperation::Delete),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            KeyOperation::Read => "read",
+            KeyOperation::Encrypt => "encrypt",
+            KeyOperation::Decrypt => "decrypt",
+            KeyOperation::Delete => "delete",
+        }
+    }
+}
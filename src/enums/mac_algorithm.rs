@@ -0,0 +1,30 @@
+use openssl::hash::MessageDigest;
+
+pub enum MacAlgorithm {
+    HS256,
+    HS512,
+}
+
+impl MacAlgorithm {
+    pub fn from_str(algorithm: &str) -> Option<Self> {
+        match algorithm {
+            "HS256" => Some(MacAlgorithm::HS256),
+            "HS512" => Some(MacAlgorithm::HS512),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MacAlgorithm::HS256 => "HS256",
+            MacAlgorithm::HS512 => "HS512",
+        }
+    }
+
+    pub fn digest(&self) -> MessageDigest {
+        match self {
+            MacAlgorithm::HS256 => MessageDigest::sha256(),
+            MacAlgorithm::HS512 => MessageDigest::sha512(),
+        }
+    }
+}
@@ -1 +1,12 @@
-pub mod roles;
\ No newline at end of file
+pub mod roles;
+pub mod import_key_algorithm;
+pub mod key_format;
+pub mod sentinel_purpose;
+pub mod mac_algorithm;
+pub mod key_operation;
+pub mod access_request_status;
+pub mod audit_severity;
+pub mod audit_action;
+pub mod attempt_scope;
+pub mod token_binding;
+pub mod signature_algorithm;
\ No newline at end of file
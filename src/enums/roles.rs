@@ -1,6 +1,10 @@
+/// the roles of the users, named after their `ROLE_` strings
+#[allow(clippy::upper_case_acronyms)]
 pub enum Role {
     ADMIN,
     USER,
     SUPERADMIN,
-    VALIDATION
+    VALIDATION,
+    BREAKGLASS,
+    SERVICEACCOUNT
 }
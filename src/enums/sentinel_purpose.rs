@@ -0,0 +1,23 @@
+/// what a sentinel key is used for, a key is never used for both
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SentinelPurpose {
+    Encryption,
+    Mac,
+}
+
+impl SentinelPurpose {
+    pub fn from_str(purpose: &str) -> Option<Self> {
+        match purpose {
+            "encryption" => Some(SentinelPurpose::Encryption),
+            "mac" => Some(SentinelPurpose::Mac),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SentinelPurpose::Encryption => "encryption",
+            SentinelPurpose::Mac => "mac",
+        }
+    }
+}
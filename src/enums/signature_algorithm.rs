@@ -0,0 +1,72 @@
+use openssl::{
+    hash::MessageDigest,
+    nid::Nid,
+    pkey::{Id, PKey, Public},
+};
+
+/// the algorithms of the HTTP message signatures (RFC 9421 section 3.3) the API checks
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum SignatureAlgorithm {
+    EcdsaP256Sha256,
+    EcdsaP384Sha384,
+    Ed25519,
+    RsaPssSha512,
+    RsaV15Sha256,
+}
+
+impl SignatureAlgorithm {
+    pub const ALL: [SignatureAlgorithm; 5] = [
+        SignatureAlgorithm::EcdsaP256Sha256,
+        SignatureAlgorithm::EcdsaP384Sha384,
+        SignatureAlgorithm::Ed25519,
+        SignatureAlgorithm::RsaPssSha512,
+        SignatureAlgorithm::RsaV15Sha256,
+    ];
+
+    pub fn from_str(algorithm: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|a| a.as_str() == algorithm)
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SignatureAlgorithm::EcdsaP256Sha256 => "ecdsa-p256-sha256",
+            SignatureAlgorithm::EcdsaP384Sha384 => "ecdsa-p384-sha384",
+            SignatureAlgorithm::Ed25519 => "ed25519",
+            SignatureAlgorithm::RsaPssSha512 => "rsa-pss-sha512",
+            SignatureAlgorithm::RsaV15Sha256 => "rsa-v1_5-sha256",
+        }
+    }
+
+    /// none for Ed25519, it signs the message itself
+    pub fn digest(&self) -> Option<MessageDigest> {
+        match self {
+            SignatureAlgorithm::EcdsaP256Sha256 | SignatureAlgorithm::RsaV15Sha256 => {
+                Some(MessageDigest::sha256())
+            }
+            SignatureAlgorithm::EcdsaP384Sha384 => Some(MessageDigest::sha384()),
+            SignatureAlgorithm::RsaPssSha512 => Some(MessageDigest::sha512()),
+            SignatureAlgorithm::Ed25519 => None,
+        }
+    }
+
+    /// whether the algorithm signs with a key of this type and curve
+    pub fn fits(&self, key: &PKey<Public>) -> bool {
+        let curve = key
+            .ec_key()
+            .ok()
+            .and_then(|ec_key| ec_key.group().curve_name());
+        match self {
+            SignatureAlgorithm::EcdsaP256Sha256 => curve == Some(Nid::X9_62_PRIME256V1),
+            SignatureAlgorithm::EcdsaP384Sha384 => curve == Some(Nid::SECP384R1),
+            SignatureAlgorithm::Ed25519 => key.id() == Id::ED25519,
+            SignatureAlgorithm::RsaPssSha512 | SignatureAlgorithm::RsaV15Sha256 => {
+                key.id() == Id::RSA
+            }
+        }
+    }
+
+    /// the algorithm of a key when the signature does not name it, RSA-PSS for a RSA key
+    pub fn of_key(key: &PKey<Public>) -> Option<Self> {
+        Self::ALL.into_iter().find(|algorithm| algorithm.fits(key))
+    }
+}
@@ -0,0 +1,28 @@
+use std::env;
+
+/// how the access tokens of the users are bound to their client, set by `TOKEN_BINDING`
+#[derive(PartialEq, Clone, Copy, Debug)]
+pub enum TokenBinding {
+    /// the tokens are bound to a key pair of the client, each request carries a DPoP proof (RFC 9449)
+    Dpop,
+    /// the tokens are bound to the `X-FINGERPRINT` and `User-Agent` headers, each request carries a new `X-NONCE`
+    Legacy,
+}
+
+impl TokenBinding {
+    pub fn from_str(binding: &str) -> Option<Self> {
+        match binding {
+            "dpop" => Some(TokenBinding::Dpop),
+            "legacy" => Some(TokenBinding::Legacy),
+            _ => None,
+        }
+    }
+
+    /// the binding of the tokens issued now, DPoP unless `TOKEN_BINDING` is `legacy`
+    pub fn current() -> Self {
+        env::var("TOKEN_BINDING")
+            .ok()
+            .and_then(|binding| Self::from_str(binding.trim()))
+            .unwrap_or(TokenBinding::Dpop)
+    }
+}
@@ -0,0 +1,39 @@
+use base64::{engine::general_purpose, Engine as _};
+use rocket::{
+    request::{self, Outcome},
+    Request,
+};
+
+use crate::core::errors::ErrorObject;
+
+/// ### ClientAuthorization
+///
+/// the client credentials of the `client_secret_basic` authentication, the HTTP Basic
+/// `Authorization` header of the token endpoint. The client ids and secrets generated
+/// by the API are url safe so they are used as sent
+#[derive(Debug, Default)]
+pub struct ClientAuthorization {
+    pub client_id: Option<String>,
+    pub client_secret: Option<String>,
+}
+
+#[rocket::async_trait]
+impl<'r> rocket::request::FromRequest<'r> for ClientAuthorization {
+    type Error = ErrorObject;
+
+    async fn from_request(req: &'r Request<'_>) -> request::Outcome<Self, Self::Error> {
+        let credentials = req
+            .headers()
+            .get_one("Authorization")
+            .and_then(|header| header.strip_prefix("Basic "))
+            .and_then(|encoded| general_purpose::STANDARD.decode(encoded.trim()).ok())
+            .and_then(|decoded| String::from_utf8(decoded).ok());
+        match credentials.as_deref().and_then(|credentials| credentials.split_once(':')) {
+            None => Outcome::Success(ClientAuthorization::default()),
+            Some((client_id, client_secret)) => Outcome::Success(ClientAuthorization {
+                client_id: Some(client_id.to_string()),
+                client_secret: Some(client_secret.to_string()),
+            }),
+        }
+    }
+}
@@ -0,0 +1,100 @@
+use std::{env, net::IpAddr};
+
+use rocket::{
+    request::{self, Outcome},
+    Request,
+};
+use rocket_okapi::{gen::OpenApiGenerator, request::OpenApiFromRequest, request::RequestHeaderInput};
+
+use crate::{core::errors::ErrorObject, utils::cidr::Cidr};
+
+/// ### ClientIp
+///
+/// the IP of the client. Behind the proxies of `TRUSTED_PROXIES` it is the nearest address of
+/// `X-Forwarded-For` not added by one of them, the addresses a client sends itself are ignored
+#[derive(Debug, Clone, Copy)]
+pub struct ClientIp {
+    ip: IpAddr,
+}
+
+impl ClientIp {
+    pub fn new(ip: IpAddr) -> Self {
+        Self { ip }
+    }
+
+    pub fn ip(&self) -> IpAddr {
+        self.ip
+    }
+
+    pub fn from_request(req: &Request<'_>) -> Option<Self> {
+        let peer = req.remote()?.ip();
+        let forwarded_for: Vec<&str> = req.headers().get("X-Forwarded-For").collect();
+        Some(Self::new(Self::resolve(
+            peer,
+            &forwarded_for.join(","),
+            &Self::trusted_proxies(),
+        )))
+    }
+
+    /// walk `X-Forwarded-For` back from the peer while the hops are trusted proxies
+    pub fn resolve(peer: IpAddr, forwarded_for: &str, trusted_proxies: &[Cidr]) -> IpAddr {
+        let is_trusted = |ip: &IpAddr| trusted_proxies.iter().any(|cidr| cidr.contains(ip));
+        let mut client = peer;
+        for hop in forwarded_for.rsplit(',') {
+            if !is_trusted(&client) {
+                break;
+            }
+            match Self::parse_hop(hop) {
+                None => break,
+                Some(ip) => client = ip,
+            }
+        }
+        client.to_canonical()
+    }
+
+    /// the proxies in front of the API, `TRUSTED_PROXIES` comma separated addresses or CIDR ranges
+    pub fn trusted_proxies() -> Vec<Cidr> {
+        env::var("TRUSTED_PROXIES")
+            .unwrap_or_default()
+            .split(',')
+            .filter(|cidr| !cidr.trim().is_empty())
+            .filter_map(|cidr| Cidr::parse(cidr).ok())
+            .collect()
+    }
+
+    /// an address of `X-Forwarded-For`, some proxies add the port (`10.0.0.1:443`, `[::1]:443`)
+    fn parse_hop(hop: &str) -> Option<IpAddr> {
+        let hop = hop.trim();
+        hop.parse::<IpAddr>()
+            .ok()
+            .or_else(|| hop.parse::<std::net::SocketAddr>().ok().map(|addr| addr.ip()))
+    }
+}
+
+#[rocket::async_trait]
+impl<'r> rocket::request::FromRequest<'r> for ClientIp {
+    type Error = ErrorObject;
+
+    async fn from_request(req: &'r Request<'_>) -> request::Outcome<Self, Self::Error> {
+        match ClientIp::from_request(req) {
+            Some(client_ip) => Outcome::Success(client_ip),
+            None => Outcome::Error((
+                rocket::http::Status::BadRequest,
+                ErrorObject {
+                    code: 400,
+                    message: String::from("No client address"),
+                },
+            )),
+        }
+    }
+}
+
+impl<'a> OpenApiFromRequest<'a> for ClientIp {
+    fn from_request_input(
+        _gen: &mut OpenApiGenerator,
+        _name: String,
+        _required: bool,
+    ) -> rocket_okapi::Result<RequestHeaderInput> {
+        Ok(RequestHeaderInput::None)
+    }
+}
@@ -0,0 +1,70 @@
+use rocket::{
+    data::{self, Data, FromData, Limits},
+    http::Status,
+    serde::json::Json,
+    Request,
+};
+use rocket_okapi::{
+    gen::OpenApiGenerator, okapi::openapi3::RequestBody, okapi::schemars::JsonSchema,
+    request::OpenApiFromData,
+};
+use serde::de::DeserializeOwned;
+
+use crate::core::errors::ErrorObject;
+use crate::guards::signed_security::SignedSecurity;
+use crate::utils::message_signature::{MessageSignature, CONTENT_DIGEST_HEADER};
+
+/// ### DigestedJson
+///
+/// a JSON body checked against its `Content-Digest` (RFC 9530) when there is one, a signed
+/// request must send it since its signature only covers the digest of the body
+#[derive(Debug)]
+pub struct DigestedJson<T>(pub T);
+
+impl<T> DigestedJson<T> {
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+#[rocket::async_trait]
+impl<'r, T: DeserializeOwned> FromData<'r> for DigestedJson<T> {
+    type Error = ErrorObject;
+
+    async fn from_data(req: &'r Request<'_>, data: Data<'r>) -> data::Outcome<'r, Self> {
+        let error = |status: Status, message: &str| {
+            data::Outcome::Error((
+                status,
+                ErrorObject {
+                    code: status.code,
+                    message: message.to_string(),
+                },
+            ))
+        };
+        let limit = req.limits().get("json").unwrap_or(Limits::JSON);
+        let body = match data.open(limit).into_bytes().await {
+            Ok(body) if body.is_complete() => body.into_inner(),
+            Ok(_) => return error(Status::PayloadTooLarge, "The body is too large"),
+            Err(_) => return error(Status::BadRequest, "Unable to read the body"),
+        };
+        match req.headers().get_one(CONTENT_DIGEST_HEADER) {
+            None if SignedSecurity::is_signed(req) => {
+                return error(Status::BadRequest, "A signed request must send its Content-Digest")
+            }
+            Some(digest) if !MessageSignature::digest_matches(digest, &body) => {
+                return error(Status::BadRequest, "The body does not match its Content-Digest")
+            }
+            _ => {}
+        }
+        match serde_json::from_slice::<T>(&body) {
+            Ok(value) => data::Outcome::Success(DigestedJson(value)),
+            Err(_) => error(Status::UnprocessableEntity, "Bad JSON body"),
+        }
+    }
+}
+
+impl<'r, T: JsonSchema + DeserializeOwned> OpenApiFromData<'r> for DigestedJson<T> {
+    fn request_body(gen: &mut OpenApiGenerator) -> rocket_okapi::Result<RequestBody> {
+        Json::<T>::request_body(gen)
+    }
+}
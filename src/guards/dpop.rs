@@ -0,0 +1,90 @@
+use rocket::{
+    request::{self, Outcome},
+    Request,
+};
+use rocket_okapi::{
+    gen::OpenApiGenerator,
+    okapi::openapi3::{Object, Parameter, ParameterValue},
+    request::{OpenApiFromRequest, RequestHeaderInput},
+};
+
+use crate::core::errors::ErrorObject;
+use crate::utils::dpop::{Dpop, DPOP_HEADER};
+
+/// ### DpopHeader
+///
+/// the DPoP proofs sent in the `DPoP` header, with the method and the URI
+/// of the request they must be issued for
+#[derive(Debug, Clone)]
+pub struct DpopHeader {
+    proofs: Vec<String>,
+    pub htm: String,
+    pub htu: String,
+}
+
+impl DpopHeader {
+    pub fn from_request(req: &Request<'_>) -> Self {
+        Self {
+            proofs: req
+                .headers()
+                .get(DPOP_HEADER)
+                .map(|proof| proof.trim().to_string())
+                .collect(),
+            htm: req.method().as_str().to_string(),
+            htu: Dpop::htu(req.uri().path().as_str()),
+        }
+    }
+
+    /// check the proof when there is one, the thumbprint of its key is returned
+    pub fn check(&self, access_token: Option<&str>) -> Result<Option<String>, &'static str> {
+        match self.proofs.as_slice() {
+            [] => Ok(None),
+            [proof] => Dpop::check(proof, &self.htm, &self.htu, access_token).map(Some),
+            _ => Err("Only one DPoP proof is allowed"),
+        }
+    }
+
+    /// check the proof, it is required
+    pub fn require(&self, access_token: Option<&str>) -> Result<String, &'static str> {
+        self.check(access_token)?.ok_or("A DPoP proof is required")
+    }
+}
+
+#[rocket::async_trait]
+impl<'r> rocket::request::FromRequest<'r> for DpopHeader {
+    type Error = ErrorObject;
+
+    async fn from_request(req: &'r Request<'_>) -> request::Outcome<Self, Self::Error> {
+        Outcome::Success(DpopHeader::from_request(req))
+    }
+}
+
+impl<'a> OpenApiFromRequest<'a> for DpopHeader {
+    fn from_request_input(
+        gen: &mut OpenApiGenerator,
+        _name: String,
+        _required: bool,
+    ) -> rocket_okapi::Result<RequestHeaderInput> {
+        let schema = gen.json_schema::<String>();
+        Ok(RequestHeaderInput::Parameter(Parameter {
+            name: DPOP_HEADER.to_owned(),
+            location: "header".to_owned(),
+            description: Some(
+                "DPoP proof (RFC 9449) binding the tokens to the key of the client, required unless `TOKEN_BINDING` is `legacy`"
+                    .to_owned(),
+            ),
+            required: false,
+            deprecated: false,
+            allow_empty_value: false,
+            value: ParameterValue::Schema {
+                style: None,
+                explode: None,
+                allow_reserved: false,
+                schema,
+                example: None,
+                examples: None,
+            },
+            extensions: Object::default(),
+        }))
+    }
+}
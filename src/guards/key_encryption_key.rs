@@ -0,0 +1,66 @@
+use rocket::{
+    request::{self, Outcome},
+    Request,
+};
+use rocket_okapi::{
+    gen::OpenApiGenerator,
+    okapi::openapi3::{Object, Parameter, ParameterValue},
+    request::{OpenApiFromRequest, RequestHeaderInput},
+};
+
+use crate::core::errors::ErrorObject;
+
+const KEY_ENCRYPTION_KEY_HEADER: &str = "X-Key-Encryption-Key";
+
+/// ### KeyEncryptionKey
+///
+/// the hex AES key sent in the `X-Key-Encryption-Key` header
+/// to wrap an exported key (`wrapped` format), it is never stored
+#[derive(Debug)]
+pub struct KeyEncryptionKey {
+    pub key: Option<String>,
+}
+
+#[rocket::async_trait]
+impl<'r> rocket::request::FromRequest<'r> for KeyEncryptionKey {
+    type Error = ErrorObject;
+
+    async fn from_request(req: &'r Request<'_>) -> request::Outcome<Self, Self::Error> {
+        let key = req
+            .headers()
+            .get_one(KEY_ENCRYPTION_KEY_HEADER)
+            .map(|key| key.trim().to_string());
+
+        Outcome::Success(KeyEncryptionKey { key })
+    }
+}
+
+impl<'a> OpenApiFromRequest<'a> for KeyEncryptionKey {
+    fn from_request_input(
+        gen: &mut OpenApiGenerator,
+        _name: String,
+        _required: bool,
+    ) -> rocket_okapi::Result<RequestHeaderInput> {
+        let schema = gen.json_schema::<String>();
+        Ok(RequestHeaderInput::Parameter(Parameter {
+            name: KEY_ENCRYPTION_KEY_HEADER.to_owned(),
+            location: "header".to_owned(),
+            description: Some(
+                "Hex AES-128/192/256 key-encryption key, required by the `wrapped` format"
+                    .to_owned(),
+            ),
+            required: false,
+            deprecated: false,
+            allow_empty_value: false,
+            value: ParameterValue::Schema {
+                style: None,
+                explode: None,
+                allow_reserved: false,
+                schema,
+                example: None,
+                examples: None,
+            },
+            extensions: Object::default(),
+        }))
+    }
+}
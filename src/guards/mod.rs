@@ -1,2 +1,10 @@
 pub mod user_agent;
-pub mod security;
\ No newline at end of file
+pub mod security;
+pub mod key_encryption_key;
+pub mod scoped_security;
+pub mod client_authorization;
+pub mod client_ip;
+pub mod dpop;
+pub mod signed_security;
+pub mod digested_json;
+pub mod open_id_security;
\ No newline at end of file
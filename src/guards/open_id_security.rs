@@ -0,0 +1,46 @@
+use rocket::{request::Outcome, Request};
+use rocket_okapi::request::OpenApiFromRequest;
+use rocket_okapi::{gen::OpenApiGenerator, request::RequestHeaderInput};
+
+use crate::core::errors::ErrorObject;
+use crate::guards::security::Security;
+
+/// ### OpenIdSecurity
+///
+/// the authentication of the OpenID Connect endpoints, the only endpoints accepting the
+/// access tokens issued to a third party OAuth client. The endpoint must check the
+/// `oauth_scope` of these tokens
+#[derive(Debug, Clone)]
+pub struct OpenIdSecurity {
+    pub security: Security,
+}
+
+#[rocket::async_trait]
+impl<'r> rocket::request::FromRequest<'r> for OpenIdSecurity {
+    type Error = ErrorObject;
+
+    async fn from_request(req: &'r Request<'_>) -> Outcome<Self, ErrorObject> {
+        match Security::authenticate(req) {
+            Outcome::Success((security, None)) => Outcome::Success(OpenIdSecurity { security }),
+            Outcome::Success((_, Some(_))) => Outcome::Error((
+                rocket::http::Status::Forbidden,
+                ErrorObject {
+                    code: 403,
+                    message: String::from("This token is restricted to the key operations of its scope"),
+                },
+            )),
+            Outcome::Error(e) => Outcome::Error(e),
+            Outcome::Forward(status) => Outcome::Forward(status),
+        }
+    }
+}
+
+impl<'a> OpenApiFromRequest<'a> for OpenIdSecurity {
+    fn from_request_input(
+        gen: &mut OpenApiGenerator,
+        name: String,
+        required: bool,
+    ) -> rocket_okapi::Result<RequestHeaderInput> {
+        Security::from_request_input(gen, name, required)
+    }
+}
@@ -0,0 +1,63 @@
+use rocket::{request::Outcome, Request};
+use rocket_okapi::request::OpenApiFromRequest;
+use rocket_okapi::{gen::OpenApiGenerator, request::RequestHeaderInput};
+
+use crate::core::errors::ErrorObject;
+use crate::enums::roles::Role;
+use crate::guards::{security::Security, signed_security::SignedSecurity};
+use crate::models::user::User;
+use crate::utils::jwt::TokenScope;
+
+/// ### ScopedSecurity
+///
+/// the authentication of the key operation endpoints, it also accepts the capability
+/// tokens and the requests signed by a service account. The endpoint must check the `scope`
+/// against the sentinel it works on, `None` is a full session token or a signed request
+#[derive(Debug, Clone)]
+pub struct ScopedSecurity {
+    pub user: User,
+    pub scope: Option<TokenScope>,
+    /// the request is signed instead of carrying a token
+    pub is_signed: bool,
+}
+
+impl ScopedSecurity {
+    pub fn check_roles(&self, require_role: Role) -> bool {
+        Security::has_role(&self.user, require_role)
+    }
+
+    /// a full session token or a capability token of the user, never a token of an OAuth client
+    pub fn from_token(security: Security, scope: Option<TokenScope>) -> Outcome<Self, ErrorObject> {
+        Security::check_first_party(security).map(|security| ScopedSecurity {
+            user: security.user,
+            scope,
+            is_signed: false,
+        })
+    }
+}
+
+#[rocket::async_trait]
+impl<'r> rocket::request::FromRequest<'r> for ScopedSecurity {
+    type Error = ErrorObject;
+
+    async fn from_request(req: &'r Request<'_>) -> Outcome<Self, ErrorObject> {
+        if !req.headers().contains("Authorization") && SignedSecurity::is_signed(req) {
+            return SignedSecurity::authenticate(req).map(|signed| ScopedSecurity {
+                user: signed.user,
+                scope: None,
+                is_signed: true,
+            });
+        }
+        Security::authenticate(req).and_then(|(security, scope)| Self::from_token(security, scope))
+    }
+}
+
+impl<'a> OpenApiFromRequest<'a> for ScopedSecurity {
+    fn from_request_input(
+        gen: &mut OpenApiGenerator,
+        name: String,
+        required: bool,
+    ) -> rocket_okapi::Result<RequestHeaderInput> {
+        Security::from_request_input(gen, name, required)
+    }
+}
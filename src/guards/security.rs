@@ -2,12 +2,25 @@ use std::env;
 
 use crate::core::errors::ErrorObject;
 use crate::db::connect::{establish_connection_pool, DbPool};
-use crate::enums::roles::Role;
+use crate::enums::{roles::Role, token_binding::TokenBinding};
+use crate::guards::{client_ip::ClientIp, dpop::DpopHeader};
 use crate::models::user::User;
 use crate::redis::RedisClient;
-use crate::{repositories::user::UserRepository, utils::jwt::Jwt};
+use crate::services::ip_restriction::IpRestrictionService;
+use crate::{
+    repositories::{
+        oauth_client::OauthClientRepository, service_account::ServiceAccountRepository,
+        user::UserRepository,
+    },
+    utils::{
+        dpop::Confirmation,
+        jwt::{Jwt, TokenScope},
+        key_ring::KeyRing,
+        open_id::Authentication,
+    },
+};
 use dotenv::dotenv;
-use jsonwebtoken::{decode, Algorithm, DecodingKey, Validation};
+use jsonwebtoken::{Algorithm, Validation};
 use redis::Commands;
 use rocket::{request::Outcome, Request};
 use rocket_okapi::request::OpenApiFromRequest;
@@ -17,38 +30,73 @@ use rocket_okapi::{
     request::RequestHeaderInput,
 };
 use sha2::{Digest, Sha256};
+use uuid::Uuid;
 
 #[derive(Debug, Clone)]
 pub struct Security {
     pub user: User,
+    /// the device the access token is bound to
+    pub device_id: Option<String>,
+    /// the OAuth client the access token was issued to
+    pub oauth_client_id: Option<String>,
+    /// the scope granted to the OAuth client
+    pub oauth_scope: Option<String>,
+    /// when and how the user authenticated the session
+    pub authentication: Authentication,
+    /// the session of the access token, none for the machines and the tokens issued before the sessions
+    pub session_id: Option<Uuid>,
+    /// the thumbprint of the DPoP key the access token is bound to
+    pub dpop_jkt: Option<String>,
 }
 
 impl Security {
     pub fn new(user: &User) -> Self {
-        Self { user: user.clone() }
+        Self {
+            user: user.clone(),
+            device_id: None,
+            oauth_client_id: None,
+            oauth_scope: None,
+            authentication: Authentication::default(),
+            session_id: None,
+            dpop_jkt: None,
+        }
     }
 
     pub fn check_roles(&self, require_role: Role) -> bool {
+        Self::has_role(&self.user, require_role)
+    }
+
+    pub fn has_role(user: &User, require_role: Role) -> bool {
         match require_role {
-            Role::ADMIN => self.user.roles.iter().any(|x| match x {
+            Role::ADMIN => user.roles.iter().any(|x| match x {
                 None => false,
                 Some(x) => x.contains("ROLE_ADMIN"),
             }),
-            Role::USER => self.user.roles.iter().any(|x| match x {
+            Role::USER => user.roles.iter().any(|x| match x {
                 None => false,
                 Some(x) => x.contains("ROLE_USER"),
             }),
-            Role::SUPERADMIN => self.user.roles.iter().any(|x| match x {
+            Role::SUPERADMIN => user.roles.iter().any(|x| match x {
                 None => false,
                 Some(x) => x.contains("ROLE_SUPER_ADMIN"),
             }),
-            Role::VALIDATION => self.user.roles.iter().any(|x| match x {
+            Role::VALIDATION => user.roles.iter().any(|x| match x {
                 None => false,
                 Some(x) => x.contains("ROLE_VALIDATION"),
             }),
+            Role::BREAKGLASS => user.roles.iter().any(|x| match x {
+                None => false,
+                Some(x) => x.contains("ROLE_BREAK_GLASS"),
+            }),
+            Role::SERVICEACCOUNT => user.roles.iter().any(|x| match x {
+                None => false,
+                Some(x) => x.contains("ROLE_SERVICE_ACCOUNT"),
+            }),
         }
     }
 
+    /// the binding of `TOKEN_BINDING=legacy`, the token is bound to the hash of `X-FINGERPRINT` and `User-Agent`
+    /// and each request carries a new `X-NONCE`
     pub fn check_headers(
         user: &User,
         device_id: Option<String>,
@@ -135,13 +183,10 @@ impl Security {
             }
         }
     }
-}
 
-#[rocket::async_trait]
-impl<'r> rocket::request::FromRequest<'r> for Security {
-    type Error = ErrorObject;
-
-    async fn from_request(req: &'r Request<'_>) -> Outcome<Self, ErrorObject> {
+    /// check the bearer token of the request, the scope of a capability token is returned
+    /// for the guards accepting them
+    pub fn authenticate(req: &Request<'_>) -> Outcome<(Self, Option<TokenScope>), ErrorObject> {
         dotenv().ok();
         let pool: DbPool = establish_connection_pool();
         let user_repository = UserRepository::new(&pool);
@@ -154,40 +199,176 @@ impl<'r> rocket::request::FromRequest<'r> for Security {
                 message: String::from("No token found"),
             },
         ));
-        let jwt = match key {
+        let (is_dpop, jwt) = match key {
             None => return outcome_error,
-            Some(key) => key.replace("Bearer ", ""),
+            Some(key) => match key.strip_prefix("DPoP ") {
+                Some(jwt) => (true, jwt.trim().to_string()),
+                None => (false, key.replace("Bearer ", "")),
+            },
         };
-        match decode::<Jwt>(
-            jwt.as_ref(),
-            &DecodingKey::from_rsa_pem(include_bytes!("../../ssh_keys/public.key")).unwrap(),
-            &Validation::new(Algorithm::RS256),
-        ) {
+        match KeyRing::decode::<Jwt>(jwt.as_ref(), &Validation::new(Algorithm::RS256)) {
             Ok(token) => {
-                if token.claims.is_refresh {
+                if token.claims.is_refresh || token.claims.is_revoked() {
                     return outcome_error;
                 }
+                let dpop_jkt = match Self::check_dpop(req, &jwt, is_dpop, token.claims.cnf.as_ref()) {
+                    Err(message) => {
+                        return Outcome::Error((
+                            rocket::http::Status::Forbidden,
+                            ErrorObject {
+                                code: 403,
+                                message: String::from(message),
+                            },
+                        ))
+                    }
+                    Ok(dpop_jkt) => dpop_jkt,
+                };
+                let authentication = token.claims.authentication();
+                let session_id = token.claims.sid;
                 match user_repository
                     .get_by_login_and_app(&token.claims.login, token.claims.application_id)
                 {
                     None => outcome_error,
+                    Some(user) if token.claims.is_service_account => {
+                        // a machine has no device, the account must still exist
+                        match ServiceAccountRepository::new(&pool).get_by_user_id(&user.id) {
+                            None => outcome_error,
+                            Some(_) => Outcome::Success((Self::new(&user), token.claims.scope)),
+                        }
+                    }
+                    Some(user) if token.claims.oauth_client_id.is_some() => {
+                        // a third party client has no device, it must still be registered
+                        let client_id = token.claims.oauth_client_id.unwrap_or_default();
+                        match OauthClientRepository::new(&pool).get_by_client_id(&client_id) {
+                            Some(client) if user.application == Some(client.application_id) => {
+                                let mut security = Self::new(&user.validation());
+                                security.oauth_client_id = Some(client.client_id);
+                                security.oauth_scope = token.claims.oauth_scope;
+                                Outcome::Success((security, token.claims.scope))
+                            }
+                            _ => outcome_error,
+                        }
+                    }
                     Some(user) => {
                         let mode = env::var("MODE").unwrap_or_else(|_| format!("prod"));
                         let user = user.validation();
-                        match mode.as_str() {
-                            "dev" => {
-                                Outcome::Success(Self::new(&user))
+                        match (mode.as_str(), TokenBinding::current()) {
+                            _ if dpop_jkt.is_some() => Outcome::Success(Self::new(&user)),
+                            ("dev", _) => Outcome::Success(Self::new(&user)),
+                            (_, TokenBinding::Legacy) => {
+                                Security::check_headers(&user, token.claims.device_id.clone(), req)
                             }
-                            _ => Security::check_headers(&user, token.claims.device_id, req),
+                            (_, TokenBinding::Dpop) => Outcome::Error((
+                                rocket::http::Status::Forbidden,
+                                ErrorObject {
+                                    code: 403,
+                                    message: String::from("This token is not bound to a DPoP key"),
+                                },
+                            )),
                         }
+                        .map(|mut security| {
+                            security.device_id = token.claims.device_id;
+                            (security, token.claims.scope)
+                        })
                     }
                 }
+                .map(|(mut security, scope)| {
+                    security.authentication = authentication;
+                    security.session_id = session_id;
+                    security.dpop_jkt = dpop_jkt;
+                    (security, scope)
+                })
+                .and_then(|(security, scope)| Self::check_ip(&pool, security, scope, req))
             }
             Err(_e) => outcome_error,
         }
     }
 }
 
+impl Security {
+    /// a token bound to a DPoP key comes with the `DPoP` scheme and a proof of the key for the request,
+    /// the thumbprint of the key is returned
+    fn check_dpop(
+        req: &Request<'_>,
+        access_token: &str,
+        is_dpop: bool,
+        cnf: Option<&Confirmation>,
+    ) -> Result<Option<String>, &'static str> {
+        match cnf {
+            None if is_dpop => Err("This token is not bound to a DPoP key"),
+            None => Ok(None),
+            Some(_) if !is_dpop => Err("This token must be sent with the DPoP scheme"),
+            Some(cnf) => match DpopHeader::from_request(req).require(Some(access_token))? {
+                jkt if jkt == cnf.jkt => Ok(Some(jkt)),
+                _ => Err("The DPoP proof was signed by another key"),
+            },
+        }
+    }
+
+    /// an access token issued to a third party OAuth client only reaches the OpenID Connect
+    /// endpoints, it does not carry the roles of its user on the rest of the API
+    pub fn check_first_party(security: Security) -> Outcome<Self, ErrorObject> {
+        match security.oauth_client_id {
+            None => Outcome::Success(security),
+            Some(_) => Outcome::Error((
+                rocket::http::Status::Forbidden,
+                ErrorObject {
+                    code: 403,
+                    message: String::from(
+                        "This token was issued to an OAuth client, it only reaches the OpenID Connect endpoints",
+                    ),
+                },
+            )),
+        }
+    }
+
+    /// the client IP must be allowed for the user and their application
+    pub fn check_ip(
+        pool: &DbPool,
+        security: Security,
+        scope: Option<TokenScope>,
+        req: &Request<'_>,
+    ) -> Outcome<(Self, Option<TokenScope>), ErrorObject> {
+        let denied = |status: rocket::http::Status, message: &str| {
+            Outcome::Error((
+                status,
+                ErrorObject {
+                    code: status.code,
+                    message: message.to_string(),
+                },
+            ))
+        };
+        let client_ip = match ClientIp::from_request(req) {
+            None => return denied(rocket::http::Status::Forbidden, "IP not allowed"),
+            Some(client_ip) => client_ip,
+        };
+        match IpRestrictionService::new(pool).check(&security.user, &client_ip.ip()) {
+            Ok(()) => Outcome::Success((security, scope)),
+            Err((status, msg)) => denied(status, msg.unwrap_or_default()),
+        }
+    }
+}
+
+#[rocket::async_trait]
+impl<'r> rocket::request::FromRequest<'r> for Security {
+    type Error = ErrorObject;
+
+    async fn from_request(req: &'r Request<'_>) -> Outcome<Self, ErrorObject> {
+        match Security::authenticate(req) {
+            Outcome::Success((security, None)) => Security::check_first_party(security),
+            Outcome::Success((_, Some(_))) => Outcome::Error((
+                rocket::http::Status::Forbidden,
+                ErrorObject {
+                    code: 403,
+                    message: String::from("This token is restricted to the key operations of its scope"),
+                },
+            )),
+            Outcome::Error(e) => Outcome::Error(e),
+            Outcome::Forward(status) => Outcome::Forward(status),
+        }
+    }
+}
+
 impl<'a> OpenApiFromRequest<'a> for Security {
     fn from_request_input(
         _gen: &mut OpenApiGenerator,
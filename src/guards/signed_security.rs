@@ -0,0 +1,211 @@
+use chrono::{DateTime, Utc};
+use dotenv::dotenv;
+use rocket::{http::Status, request::Outcome, Request};
+use rocket_okapi::{
+    gen::OpenApiGenerator,
+    okapi::openapi3::{Object, SecurityRequirement, SecurityScheme, SecuritySchemeData},
+    request::{OpenApiFromRequest, RequestHeaderInput},
+};
+
+use crate::core::errors::ErrorObject;
+use crate::db::connect::establish_connection_pool;
+use crate::guards::security::Security;
+use crate::models::user::User;
+use crate::repositories::{service_account::ServiceAccountRepository, user::UserRepository};
+use crate::utils::message_signature::{
+    MessageSignature, SignatureInput, CONTENT_DIGEST_HEADER, SIGNATURE_HEADER,
+    SIGNATURE_INPUT_HEADER,
+};
+
+/// the components every signature covers
+const REQUIRED_COMPONENTS: [&str; 3] = ["@method", "@path", "date"];
+
+/// ### SignedSecurity
+///
+/// a request of a service account signed with its signing key (RFC 9421 HTTP message signatures),
+/// in place of a bearer token. The `keyid` of the signature is the `client_id` of the account,
+/// `user` is its identity, checked by `Security::has_role` like a token
+#[derive(Debug, Clone)]
+pub struct SignedSecurity {
+    pub user: User,
+}
+
+impl SignedSecurity {
+    /// whether the request carries a signature
+    pub fn is_signed(req: &Request<'_>) -> bool {
+        req.headers().contains(SIGNATURE_INPUT_HEADER)
+    }
+
+    /// the values of a header joined as one field, none when it is absent
+    fn field(req: &Request<'_>, name: &str) -> Option<String> {
+        let values: Vec<&str> = req.headers().get(name).map(str::trim).collect();
+        match values.is_empty() {
+            true => None,
+            false => Some(values.join(", ")),
+        }
+    }
+
+    fn has_body(req: &Request<'_>) -> bool {
+        req.headers().contains("Transfer-Encoding")
+            || req
+                .headers()
+                .get_one("Content-Length")
+                .is_some_and(|length| length.trim() != "0")
+    }
+
+    /// the value of a covered component of the request
+    fn component(req: &Request<'_>, component: &str) -> Option<String> {
+        match component {
+            "@method" => Some(req.method().as_str().to_string()),
+            "@path" => Some(req.uri().path().as_str().to_string()),
+            "@query" => Some(format!(
+                "?{}",
+                req.uri().query().map(|query| query.as_str()).unwrap_or_default()
+            )),
+            "@authority" => req.host().map(|host| host.to_string().to_lowercase()),
+            name if name.starts_with('@') => None,
+            name => Self::field(req, name),
+        }
+    }
+
+    /// the signature covers the method, the path, the date, the query and the content digest of the request
+    fn check_components(req: &Request<'_>, input: &SignatureInput) -> Result<(), &'static str> {
+        let covers = |component: &str| input.components.iter().any(|c| c == component);
+        if !REQUIRED_COMPONENTS.iter().all(|component| covers(component)) {
+            return Err("The signature must cover @method, @path and date");
+        }
+        if req.uri().query().is_some() && !covers("@query") {
+            return Err("The signature must cover @query");
+        }
+        if Self::has_body(req) && !covers("content-digest") {
+            return Err("The signature must cover content-digest");
+        }
+        Ok(())
+    }
+
+    /// the `Date` and the `created` of the signature are recent, its `expires` is not reached
+    fn check_age(req: &Request<'_>, input: &SignatureInput, now: i64) -> Result<(), &'static str> {
+        let max_age = MessageSignature::max_age();
+        let recent = |timestamp: i64| (timestamp - now).abs() <= max_age;
+        let date = req
+            .headers()
+            .get_one("Date")
+            .and_then(|date| DateTime::parse_from_rfc2822(date.trim()).ok())
+            .ok_or("Bad Date")?;
+        if !recent(date.timestamp()) || input.created.is_some_and(|created| !recent(created)) {
+            return Err("The signature has expired");
+        }
+        if input.expires.is_some_and(|expires| expires < now) {
+            return Err("The signature has expired");
+        }
+        Ok(())
+    }
+
+    /// check the signature of the request with the signing key of its service account,
+    /// a signature is accepted once
+    pub fn authenticate(req: &Request<'_>) -> Outcome<Self, ErrorObject> {
+        dotenv().ok();
+        let denied = |message: &str| {
+            Outcome::Error((
+                Status::Forbidden,
+                ErrorObject {
+                    code: 403,
+                    message: message.to_string(),
+                },
+            ))
+        };
+        let (input_field, signature_field) = match (
+            Self::field(req, SIGNATURE_INPUT_HEADER),
+            Self::field(req, SIGNATURE_HEADER),
+        ) {
+            (Some(input_field), Some(signature_field)) => (input_field, signature_field),
+            _ => return denied("No signature found"),
+        };
+        let input = match MessageSignature::parse_input(&input_field) {
+            Ok(inputs) if inputs.len() == 1 => inputs[0].clone(),
+            Ok(_) => return denied("Only one signature is allowed"),
+            Err(e) => return denied(e),
+        };
+        let signature = match MessageSignature::parse_signature(&signature_field, &input.label) {
+            Err(e) => return denied(e),
+            Ok(signature) => signature,
+        };
+        if let Err(e) = Self::check_components(req, &input)
+            .and_then(|_| Self::check_age(req, &input, Utc::now().timestamp()))
+        {
+            return denied(e);
+        }
+        let pool = establish_connection_pool();
+        let service_account_repository = ServiceAccountRepository::new(&pool);
+        let service_account = match input
+            .keyid
+            .as_deref()
+            .and_then(|keyid| service_account_repository.get_by_client_id(keyid))
+        {
+            None => return denied("Unknown signing key"),
+            Some(service_account) => service_account,
+        };
+        let key = match service_account
+            .signing_key
+            .as_deref()
+            .and_then(MessageSignature::public_key)
+        {
+            None => return denied("Unknown signing key"),
+            Some(key) => key,
+        };
+        let verified = MessageSignature::signature_base(&input, |component| {
+            Self::component(req, component)
+        })
+        .and_then(|base| {
+            MessageSignature::verify(&key, input.alg.as_deref(), base.as_bytes(), &signature)
+        });
+        if let Err(e) = verified {
+            return denied(e);
+        }
+        if !MessageSignature::claim(&service_account.client_id, &signature) {
+            return denied("The signature was already used");
+        }
+        let user = match UserRepository::new(&pool).get_by_id(&service_account.user_id) {
+            None => return denied("Unknown signing key"),
+            Some(user) => user,
+        };
+        service_account_repository.touch(&service_account.id);
+        Security::check_ip(&pool, Security::new(&user), None, req).map(|_| SignedSecurity { user })
+    }
+}
+
+#[rocket::async_trait]
+impl<'r> rocket::request::FromRequest<'r> for SignedSecurity {
+    type Error = ErrorObject;
+
+    async fn from_request(req: &'r Request<'_>) -> Outcome<Self, ErrorObject> {
+        SignedSecurity::authenticate(req)
+    }
+}
+
+impl<'a> OpenApiFromRequest<'a> for SignedSecurity {
+    fn from_request_input(
+        _gen: &mut OpenApiGenerator,
+        _name: String,
+        _required: bool,
+    ) -> rocket_okapi::Result<RequestHeaderInput> {
+        let security_scheme = SecurityScheme {
+            description: Some(format!(
+                "HTTP message signature (RFC 9421) of a service account, with the {} and {} headers",
+                SIGNATURE_INPUT_HEADER, CONTENT_DIGEST_HEADER
+            )),
+            data: SecuritySchemeData::ApiKey {
+                name: SIGNATURE_HEADER.to_owned(),
+                location: "header".to_owned(),
+            },
+            extensions: Object::default(),
+        };
+        let mut security_req = SecurityRequirement::new();
+        security_req.insert("HttpSignature".to_owned(), Vec::new());
+        Ok(RequestHeaderInput::Security(
+            "HttpSignature".to_owned(),
+            security_scheme,
+            security_req,
+        ))
+    }
+}
@@ -93,7 +93,7 @@ async fn rocket() -> _ {
     };
     let args: Vec<String> = env::args().collect();
     match args.len() > 1 {
-        true => CoreCli::exec(args, pool).await,
+        true => CoreCli::exec(args, pool, nodes_config).await,
         false => CoreApi::launch(pool, nodes_config),
     }
 }
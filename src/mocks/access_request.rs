@@ -0,0 +1,185 @@
+use std::cell::RefCell;
+
+use chrono::Utc;
+use diesel::result::{DatabaseErrorKind, Error};
+use uuid::Uuid;
+
+use crate::{
+    db::connect::DbPool,
+    dto::access_request::access_request_insertable::AccessRequestInsertable,
+    enums::access_request_status::AccessRequestStatus,
+    models::{access_request::AccessRequest, user::User},
+    traits::access_request::{AccessRequestContract, AccessRequestContractWithoutPool},
+};
+
+/// a user sharing no cluster with the keys of the requests
+pub const OUTSIDER: Uuid = Uuid::from_u128(1);
+
+/// the access requests and their approvals kept in memory,
+/// an approver approves a request once like with the unique index of the table
+pub struct AccessRequestMocks {
+    clusters_requirement: i32,
+    access_requests: RefCell<Vec<AccessRequest>>,
+    approvals: RefCell<Vec<(Uuid, Uuid)>>,
+}
+
+impl AccessRequestMocks {
+    /// the keys are in a cluster requiring `clusters_requirement` approvals
+    pub fn with_clusters_requirement(clusters_requirement: i32) -> Self {
+        Self {
+            clusters_requirement,
+            access_requests: RefCell::new(vec![]),
+            approvals: RefCell::new(vec![]),
+        }
+    }
+}
+
+impl AccessRequestContractWithoutPool for AccessRequestMocks {
+    fn new_without_pool() -> Self {
+        Self::with_clusters_requirement(0)
+    }
+}
+
+impl AccessRequestContract for AccessRequestMocks {
+    fn new(_pool: &DbPool) -> Self
+    where
+        Self: Sized,
+    {
+        todo!()
+    }
+
+    fn get_sentinel_clusters_requirement(&self, _sentinel_uuid: &Uuid) -> i32 {
+        self.clusters_requirement
+    }
+
+    fn get_anonymous_sentinel_clusters_requirement(&self, _anonymous_sentinel_uuid: &Uuid) -> i32 {
+        self.clusters_requirement
+    }
+
+    fn create_access_request(&self, insertable: AccessRequestInsertable) -> AccessRequest {
+        let access_request = AccessRequest {
+            id: Uuid::new_v4(),
+            sentinel_id: insertable.sentinel_id,
+            anonymous_sentinel_id: insertable.anonymous_sentinel_id,
+            requester_id: insertable.requester_id,
+            required_approvals: insertable.required_approvals,
+            status: insertable.status,
+            expires_at: insertable.expires_at,
+            is_deleted: false,
+            created_at: insertable.created_at,
+            updated_at: None,
+            deleted_at: None,
+            created_by_id: insertable.created_by_id,
+            updated_by_id: None,
+            deleted_by_id: None,
+        };
+        self.access_requests.borrow_mut().push(access_request.clone());
+        access_request
+    }
+
+    fn get_by_id(&self, access_request_uuid: &Uuid) -> Option<AccessRequest> {
+        self.access_requests
+            .borrow()
+            .iter()
+            .find(|access_request| access_request.id == *access_request_uuid)
+            .cloned()
+    }
+
+    fn find_open(
+        &self,
+        sentinel_uuid: Option<Uuid>,
+        anonymous_sentinel_uuid: Option<Uuid>,
+        requester_uuid: &Uuid,
+        status: AccessRequestStatus,
+    ) -> Option<AccessRequest> {
+        self.access_requests
+            .borrow()
+            .iter()
+            .rev()
+            .find(|access_request| {
+                access_request.requester_id == *requester_uuid
+                    && access_request.status == status.as_str()
+                    && !access_request.is_expired()
+                    && match sentinel_uuid {
+                        Some(sentinel_uuid) => access_request.sentinel_id == Some(sentinel_uuid),
+                        None => access_request.anonymous_sentinel_id == anonymous_sentinel_uuid,
+                    }
+            })
+            .cloned()
+    }
+
+    fn get_for_user(&self, user_from: &User) -> Vec<AccessRequest> {
+        self.access_requests
+            .borrow()
+            .iter()
+            .filter(|access_request| {
+                access_request.requester_id == user_from.id || user_from.id != OUTSIDER
+            })
+            .cloned()
+            .collect()
+    }
+
+    fn is_cluster_member(&self, _access_request: &AccessRequest, user_uuid: &Uuid) -> bool {
+        *user_uuid != OUTSIDER
+    }
+
+    fn count_approvals(&self, access_request_uuid: &Uuid) -> i64 {
+        self.approvals
+            .borrow()
+            .iter()
+            .filter(|(access_request_id, _)| access_request_id == access_request_uuid)
+            .count() as i64
+    }
+
+    fn approve(&self, access_request_uuid: &Uuid, approver: &User) -> Result<AccessRequest, Error> {
+        let mut access_requests = self.access_requests.borrow_mut();
+        let access_request = access_requests
+            .iter_mut()
+            .find(|access_request| {
+                access_request.id == *access_request_uuid
+                    && access_request.status == AccessRequestStatus::Pending.as_str()
+                    && !access_request.is_expired()
+            })
+            .ok_or(Error::NotFound)?;
+        if self
+            .approvals
+            .borrow()
+            .contains(&(access_request.id, approver.id))
+        {
+            return Err(Error::DatabaseError(
+                DatabaseErrorKind::UniqueViolation,
+                Box::new(String::from("access_request_approvals_unique")),
+            ));
+        }
+        self.approvals
+            .borrow_mut()
+            .push((access_request.id, approver.id));
+        let approvals = self.count_approvals(&access_request.id);
+        access_request.status = access_request.status_after(approvals).as_str().to_string();
+        access_request.updated_at = Some(Utc::now());
+        access_request.updated_by_id = Some(approver.id);
+        Ok(access_request.clone())
+    }
+
+    fn transition(
+        &self,
+        access_request_uuid: &Uuid,
+        from: AccessRequestStatus,
+        to: AccessRequestStatus,
+        user_from: &User,
+    ) -> bool {
+        match self.access_requests.borrow_mut().iter_mut().find(|access_request| {
+            access_request.id == *access_request_uuid
+                && access_request.status == from.as_str()
+                && !access_request.is_expired()
+        }) {
+            None => false,
+            Some(access_request) => {
+                access_request.status = to.as_str().to_string();
+                access_request.updated_at = Some(Utc::now());
+                access_request.updated_by_id = Some(user_from.id);
+                true
+            }
+        }
+    }
+}
@@ -34,6 +34,7 @@ impl ApplicationContract for ApplicationMocks {
             created_by_id: None,
             updated_by_id: None,
             deleted_by_id: None,
+            allowed_ips: vec![],
         }
     }
 
@@ -55,6 +56,7 @@ impl ApplicationContract for ApplicationMocks {
                     created_by_id: None,
                     updated_by_id: None,
                     deleted_by_id: None,
+                    allowed_ips: vec![],
                 };
                 Some(app)
             }
@@ -113,6 +115,7 @@ impl ApplicationContract for ApplicationMocks {
                     created_by_id: None,
                     updated_by_id: Some(user_from.id),
                     deleted_by_id: None,
+                    allowed_ips: vec![],
                 };
                 Ok(app)
             }
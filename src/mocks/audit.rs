@@ -0,0 +1,110 @@
+use std::{cell::RefCell, rc::Rc};
+
+use chrono::Utc;
+use uuid::Uuid;
+
+use crate::{
+    db::connect::DbPool,
+    dto::audit_log::{audit_log_insertable::AuditLogInsertable, break_glass_alert::BreakGlassAlert},
+    models::{audit_log::AuditLog, user::User},
+    traits::audit::AuditContract,
+};
+
+/// the audit records and the alerts kept in memory, a clone shares them
+/// so a test can read what the service wrote
+#[derive(Clone)]
+pub struct AuditMocks {
+    fail_writes: bool,
+    admins: Vec<User>,
+    audit_logs: Rc<RefCell<Vec<AuditLog>>>,
+    alerts: Rc<RefCell<Vec<BreakGlassAlert>>>,
+}
+
+impl AuditMocks {
+    /// the admins of the application alerted by a break-glass read
+    pub fn with_admins(admins: Vec<User>) -> Self {
+        Self {
+            fail_writes: false,
+            admins,
+            audit_logs: Rc::new(RefCell::new(vec![])),
+            alerts: Rc::new(RefCell::new(vec![])),
+        }
+    }
+
+    /// a store refusing every audit record
+    pub fn failing(admins: Vec<User>) -> Self {
+        Self {
+            fail_writes: true,
+            ..Self::with_admins(admins)
+        }
+    }
+
+    pub fn audit_logs(&self) -> Vec<AuditLog> {
+        self.audit_logs.borrow().clone()
+    }
+
+    pub fn alerts(&self) -> Vec<BreakGlassAlert> {
+        self.alerts.borrow().clone()
+    }
+}
+
+impl AuditContract for AuditMocks {
+    fn new(_pool: &DbPool) -> Self
+    where
+        Self: Sized,
+    {
+        todo!()
+    }
+
+    fn create_audit_log(
+        &self,
+        insertable: AuditLogInsertable,
+    ) -> Result<AuditLog, diesel::result::Error> {
+        if self.fail_writes {
+            return Err(diesel::result::Error::RollbackTransaction);
+        }
+        let audit_log = AuditLog {
+            id: Uuid::new_v4(),
+            application_id: insertable.application_id,
+            user_id: insertable.user_id,
+            action: insertable.action,
+            severity: insertable.severity,
+            target_type: insertable.target_type,
+            target_id: insertable.target_id,
+            justification: insertable.justification,
+            ip: insertable.ip,
+            created_at: Utc::now(),
+        };
+        self.audit_logs.borrow_mut().push(audit_log.clone());
+        Ok(audit_log)
+    }
+
+    fn get_by_application(
+        &self,
+        application_id: i32,
+        severity: Option<&str>,
+        limit: i64,
+    ) -> Vec<AuditLog> {
+        self.audit_logs
+            .borrow()
+            .iter()
+            .rev()
+            .filter(|audit_log| audit_log.application_id == Some(application_id))
+            .filter(|audit_log| severity.is_none_or(|severity| audit_log.severity == severity))
+            .take(limit as usize)
+            .cloned()
+            .collect()
+    }
+
+    fn get_admins_by_app(&self, application_id: i32) -> Vec<User> {
+        self.admins
+            .iter()
+            .filter(|admin| admin.application == Some(application_id))
+            .cloned()
+            .collect()
+    }
+
+    fn send_break_glass_alerts(&self, alerts: Vec<BreakGlassAlert>) {
+        self.alerts.borrow_mut().extend(alerts);
+    }
+}
@@ -1,2 +1,8 @@
 pub mod application;
-pub mod connexion;
\ No newline at end of file
+pub mod connexion;
+#[cfg(test)]
+pub mod secret;
+#[cfg(test)]
+pub mod access_request;
+#[cfg(test)]
+pub mod audit;
\ No newline at end of file
@@ -0,0 +1,157 @@
+use chrono::Utc;
+use diesel::result::{DatabaseErrorKind, Error};
+use uuid::Uuid;
+
+use crate::{
+    db::connect::DbPool,
+    dto::secret::secret_insertable::SecretInsertable,
+    models::{secret::Secret, secret_version::SecretVersion, user::User},
+    traits::secret::{SecretContract, SecretContractWithoutPool},
+};
+
+/// a secret created by another user of the application
+pub const OTHER_USER_SECRET: Uuid = Uuid::from_u128(1);
+
+pub struct SecretMocks;
+
+impl SecretMocks {
+    fn secret(id: Uuid, name: String, created_by_id: Uuid) -> Secret {
+        Secret {
+            id,
+            application_id: 1,
+            name,
+            current_version: 3,
+            is_deleted: false,
+            created_at: Utc::now(),
+            updated_at: None,
+            deleted_at: None,
+            created_by_id: Some(created_by_id),
+            updated_by_id: None,
+            deleted_by_id: None,
+        }
+    }
+
+    fn secret_version(secret: &Secret, version: i32) -> SecretVersion {
+        SecretVersion {
+            id: Uuid::new_v4(),
+            secret_id: secret.id,
+            version,
+            iv: String::from("iv"),
+            sum: String::from("sum"),
+            is_deleted: false,
+            created_at: Utc::now(),
+            updated_at: None,
+            deleted_at: None,
+            created_by_id: secret.created_by_id,
+            updated_by_id: None,
+            deleted_by_id: None,
+        }
+    }
+}
+
+impl SecretContractWithoutPool for SecretMocks {
+    fn new_without_pool() -> Self {
+        Self
+    }
+}
+
+impl SecretContract for SecretMocks {
+    fn new(_pool: &DbPool) -> Self
+    where
+        Self: Sized,
+    {
+        todo!()
+    }
+
+    fn create_secret(
+        &self,
+        insertable: SecretInsertable,
+        _iv: String,
+        _sum: String,
+    ) -> Result<(Secret, SecretVersion), Error> {
+        match insertable.name.as_str() {
+            // created by a concurrent request
+            "taken" => Err(Error::DatabaseError(
+                DatabaseErrorKind::UniqueViolation,
+                Box::new(String::from("secrets_name_key")),
+            )),
+            _ => {
+                let mut secret = Self::secret(
+                    Uuid::new_v4(),
+                    insertable.name,
+                    insertable.created_by_id.unwrap_or_default(),
+                );
+                secret.current_version = 1;
+                let secret_version = Self::secret_version(&secret, 1);
+                Ok((secret, secret_version))
+            }
+        }
+    }
+
+    fn add_version(
+        &self,
+        secret_uuid: &Uuid,
+        _iv: String,
+        _sum: String,
+        user_from: &User,
+    ) -> Result<(Secret, SecretVersion), Error> {
+        let mut secret = Self::secret(*secret_uuid, String::from("test"), user_from.id);
+        secret.current_version += 1;
+        let secret_version = Self::secret_version(&secret, secret.current_version);
+        Ok((secret, secret_version))
+    }
+
+    fn get_by_name(&self, secret_name: &str, _application_id: i32) -> Option<Secret> {
+        match secret_name {
+            "existing" => Some(Self::secret(
+                Uuid::new_v4(),
+                String::from(secret_name),
+                Uuid::new_v4(),
+            )),
+            _ => None,
+        }
+    }
+
+    fn get_secret_by_id(&self, secret_uuid: &Uuid, user_from: &User) -> Option<Secret> {
+        match *secret_uuid {
+            uuid if uuid.is_nil() => None,
+            OTHER_USER_SECRET => Some(Self::secret(
+                OTHER_USER_SECRET,
+                String::from("test"),
+                Uuid::new_v4(),
+            )),
+            uuid => Some(Self::secret(uuid, String::from("test"), user_from.id)),
+        }
+    }
+
+    fn get_version(&self, secret: &Secret, version: i32) -> Option<SecretVersion> {
+        match version > 0 && version <= secret.current_version {
+            true => Some(Self::secret_version(secret, version)),
+            false => None,
+        }
+    }
+
+    fn get_versions(&self, secret: &Secret) -> Vec<SecretVersion> {
+        (1..=secret.current_version)
+            .rev()
+            .map(|version| Self::secret_version(secret, version))
+            .collect()
+    }
+
+    fn delete_secret(
+        &self,
+        secret: &Secret,
+        _user_from: &User,
+    ) -> Result<Vec<SecretVersion>, Error> {
+        Ok(self.get_versions(secret))
+    }
+
+    fn add_to_user_cluster(
+        &self,
+        _cluster_uuid: &Uuid,
+        _secret: &Secret,
+        _user_from: &User,
+    ) -> bool {
+        true
+    }
+}
@@ -0,0 +1,41 @@
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+use uuid::{self, Uuid};
+
+use crate::enums::access_request_status::AccessRequestStatus;
+
+#[derive(Debug, PartialEq, Queryable, Selectable, Identifiable, Insertable, Clone, Serialize, Deserialize)]
+#[diesel(table_name = crate::schema::access_requests)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct AccessRequest {
+    pub id: Uuid,
+    pub sentinel_id: Option<Uuid>,
+    pub anonymous_sentinel_id: Option<Uuid>,
+    pub requester_id: Uuid,
+    pub required_approvals: i32,
+    pub status: String,
+    pub expires_at: DateTime<Utc>,
+    pub is_deleted: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: Option<DateTime<Utc>>,
+    pub deleted_at: Option<DateTime<Utc>>,
+    pub created_by_id: Option<uuid::Uuid>,
+    pub updated_by_id: Option<uuid::Uuid>,
+    pub deleted_by_id: Option<uuid::Uuid>,
+}
+
+impl AccessRequest {
+    pub fn is_expired(&self) -> bool {
+        self.expires_at <= Utc::now()
+    }
+
+    /// the status of a pending request once it has `approvals` approvals,
+    /// the last required approval releases the key
+    pub fn status_after(&self, approvals: i64) -> AccessRequestStatus {
+        match approvals >= self.required_approvals as i64 {
+            true => AccessRequestStatus::Approved,
+            false => AccessRequestStatus::Pending,
+        }
+    }
+}
@@ -0,0 +1,17 @@
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use super::access_request::AccessRequest;
+
+#[derive(Debug, PartialEq, Queryable, Selectable, Identifiable, Associations, Clone, Serialize, Deserialize)]
+#[diesel(belongs_to(AccessRequest))]
+#[diesel(table_name = crate::schema::access_request_approvals)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct AccessRequestApproval {
+    pub id: i32,
+    pub access_request_id: Uuid,
+    pub approver_id: Uuid,
+    pub created_at: DateTime<Utc>,
+}
@@ -1,11 +1,12 @@
 use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
 use chrono::{DateTime, Utc};
 use uuid::{self, Uuid};
 
 use crate::utils::crypto::Crypto;
 
 
-#[derive(Debug, PartialEq, Queryable, Selectable, Clone )]
+#[derive(Debug, PartialEq, Queryable, Selectable, Insertable, Clone, Serialize, Deserialize)]
 #[diesel(table_name = crate::schema::anonymous_sentinels)]
 #[diesel(check_for_backend(diesel::pg::Pg))]
 pub struct AnonymousSentinel {
@@ -22,6 +23,14 @@ pub struct AnonymousSentinel {
     pub updated_by_id: Option<uuid::Uuid>,
     pub deleted_by_id: Option<uuid::Uuid>,
     pub key_size: i32,
+    /// number of approvals needed to release the secret key, 0 when the key is not under dual control
+    #[serde(default)]
+    pub required_approvals: i32,
+    /// set when the secret key was read through break-glass, until an admin acknowledges it
+    #[serde(default)]
+    pub break_glass_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub break_glass_by_id: Option<uuid::Uuid>,
 }
 
 impl AnonymousSentinel {
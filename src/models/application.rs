@@ -1,9 +1,10 @@
 use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
 use chrono::{DateTime, Utc};
 use uuid;
 
 
-#[derive(Debug, PartialEq, Queryable, Selectable )]
+#[derive(Debug, PartialEq, Queryable, Selectable, Insertable, Clone, Serialize, Deserialize)]
 #[diesel(table_name = crate::schema::applications)]
 #[diesel(check_for_backend(diesel::pg::Pg))]
 pub struct Application {
@@ -19,5 +20,8 @@ pub struct Application {
     pub deleted_at: Option<DateTime<Utc>>,
     pub created_by_id: Option<uuid::Uuid>,
     pub updated_by_id: Option<uuid::Uuid>,
-    pub deleted_by_id: Option<uuid::Uuid>
+    pub deleted_by_id: Option<uuid::Uuid>,
+    /// the IP ranges the users of the application connect from, every IP when empty
+    #[serde(default)]
+    pub allowed_ips: Vec<String>,
 }
\ No newline at end of file
@@ -0,0 +1,21 @@
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+/// an append only record of a sensitive action
+#[derive(Debug, PartialEq, Queryable, Selectable, Insertable, Clone, Serialize, Deserialize)]
+#[diesel(table_name = crate::schema::audit_logs)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct AuditLog {
+    pub id: Uuid,
+    pub application_id: Option<i32>,
+    pub user_id: Option<Uuid>,
+    pub action: String,
+    pub severity: String,
+    pub target_type: Option<String>,
+    pub target_id: Option<Uuid>,
+    pub justification: Option<String>,
+    pub ip: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
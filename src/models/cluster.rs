@@ -1,9 +1,10 @@
 use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
 use chrono::{DateTime, Utc};
 use uuid::{self, Uuid};
 
 
-#[derive(Debug, PartialEq, Queryable, Selectable, Identifiable, Clone )]
+#[derive(Debug, PartialEq, Queryable, Selectable, Insertable, Identifiable, Clone, Serialize, Deserialize)]
 #[diesel(table_name = crate::schema::clusters)]
 #[diesel(check_for_backend(diesel::pg::Pg))]
 pub struct Cluster {
@@ -17,5 +18,8 @@ pub struct Cluster {
     pub deleted_at: Option<DateTime<Utc>>,
     pub created_by_id: Option<uuid::Uuid>,
     pub updated_by_id: Option<uuid::Uuid>,
-    pub deleted_by_id: Option<uuid::Uuid>
+    pub deleted_by_id: Option<uuid::Uuid>,
+    /// number of approvals needed to release the keys of the cluster, 0 when the cluster is not under dual control
+    #[serde(default)]
+    pub required_approvals: i32,
 }
\ No newline at end of file
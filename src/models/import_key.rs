@@ -0,0 +1,36 @@
+use diesel::prelude::*;
+use chrono::{DateTime, Utc};
+use uuid::{self, Uuid};
+
+use crate::utils::crypto::Crypto;
+
+
+#[derive(Debug, PartialEq, Queryable, Selectable, Clone )]
+#[diesel(table_name = crate::schema::import_keys)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct ImportKey {
+    pub id: Uuid,
+    pub application_id: i32,
+    pub algorithm: String,
+    pub iv: String,
+    pub sum: String,
+    pub public_key: String,
+    pub expires_at: DateTime<Utc>,
+    pub is_deleted: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: Option<DateTime<Utc>>,
+    pub deleted_at: Option<DateTime<Utc>>,
+    pub created_by_id: Option<uuid::Uuid>,
+    pub updated_by_id: Option<uuid::Uuid>,
+    pub deleted_by_id: Option<uuid::Uuid>,
+}
+
+impl ImportKey {
+    pub fn check(&self, encrypted_private_key: String) -> Result<Self, &'static str> {
+        let sum = Crypto::key_sum(&encrypted_private_key);
+        match sum == self.sum {
+            false => Err("Not valid import key integrity"),
+            true => Ok(self.clone())
+        }
+    }
+}
@@ -0,0 +1,82 @@
+use std::net::IpAddr;
+
+use chrono::{DateTime, Datelike, Timelike, Utc};
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use uuid::{self, Uuid};
+
+use crate::enums::key_operation::KeyOperation;
+use crate::utils::cidr::Cidr;
+
+#[derive(Debug, PartialEq, Queryable, Selectable, Insertable, Clone, Serialize, Deserialize)]
+#[diesel(table_name = crate::schema::key_policies)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct KeyPolicy {
+    pub id: Uuid,
+    pub sentinel_id: Option<Uuid>,
+    pub anonymous_sentinel_id: Option<Uuid>,
+    pub operations: Vec<Option<String>>,
+    pub allowed_cidrs: Vec<Option<String>>,
+    pub allowed_days: Vec<Option<i32>>,
+    pub hour_start: Option<i32>,
+    pub hour_end: Option<i32>,
+    pub max_reads: Option<i32>,
+    pub read_count: i32,
+    pub is_deleted: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: Option<DateTime<Utc>>,
+    pub deleted_at: Option<DateTime<Utc>>,
+    pub created_by_id: Option<uuid::Uuid>,
+    pub updated_by_id: Option<uuid::Uuid>,
+    pub deleted_by_id: Option<uuid::Uuid>,
+}
+
+impl KeyPolicy {
+    /// check an operation coming from `ip` at `now` (UTC) against the policy,
+    /// an empty list of ranges or days means no restriction
+    pub fn check(
+        &self,
+        operation: KeyOperation,
+        ip: &IpAddr,
+        now: DateTime<Utc>,
+    ) -> Result<(), &'static str> {
+        if !self
+            .operations
+            .iter()
+            .flatten()
+            .any(|allowed| allowed == operation.as_str())
+        {
+            return Err("Operation not allowed by the key policy");
+        }
+        if !self.allowed_cidrs.is_empty()
+            && !self
+                .allowed_cidrs
+                .iter()
+                .flatten()
+                .any(|cidr| Cidr::parse(cidr).map(|cidr| cidr.contains(ip)).unwrap_or(false))
+        {
+            return Err("IP address not allowed by the key policy");
+        }
+        let weekday = now.weekday().number_from_monday() as i32;
+        if !self.allowed_days.is_empty() && !self.allowed_days.iter().flatten().any(|day| *day == weekday) {
+            return Err("Access not allowed on this day by the key policy");
+        }
+        if let (Some(start), Some(end)) = (self.hour_start, self.hour_end) {
+            let hour = now.hour() as i32;
+            let inside = match start < end {
+                true => hour >= start && hour < end,
+                // the window wraps around midnight
+                false => hour >= start || hour < end,
+            };
+            if !inside {
+                return Err("Access not allowed at this hour by the key policy");
+            }
+        }
+        if let (KeyOperation::Read, Some(max_reads)) = (operation, self.max_reads) {
+            if self.read_count >= max_reads {
+                return Err("The maximum number of reads of the key is reached");
+            }
+        }
+        Ok(())
+    }
+}
@@ -7,4 +7,20 @@ pub mod sentinel;
 pub mod x_sentinel_cluster;
 pub mod x_user_cluster;
 pub mod anonymous_sentinel;
-pub mod x_anonymous_sentinel_cluster;
\ No newline at end of file
+pub mod x_anonymous_sentinel_cluster;
+pub mod import_key;
+pub mod secret;
+pub mod secret_version;
+pub mod x_secret_cluster;
+pub mod key_policy;
+pub mod access_request;
+pub mod access_request_approval;
+pub mod audit_log;
+pub mod share_link;
+pub mod service_account;
+pub mod oauth_client;
+pub mod oauth_code;
+pub mod signing_key;
+pub mod session;
+pub mod webauthn_credential;
+pub mod recovery_code;
\ No newline at end of file
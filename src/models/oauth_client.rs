@@ -0,0 +1,35 @@
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+use uuid::{self, Uuid};
+
+/// a third party application allowed to request authorization codes,
+/// public clients have no secret and rely on PKCE alone
+#[derive(Debug, PartialEq, Queryable, Selectable, Insertable, Clone, Serialize, Deserialize)]
+#[diesel(table_name = crate::schema::oauth_clients)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct OauthClient {
+    pub id: Uuid,
+    pub application_id: i32,
+    pub name: String,
+    pub client_id: String,
+    /// the SHA-256 of the client secret
+    pub client_secret_hash: Option<String>,
+    pub redirect_uris: Vec<Option<String>>,
+    pub is_deleted: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: Option<DateTime<Utc>>,
+    pub deleted_at: Option<DateTime<Utc>>,
+    pub created_by_id: Option<uuid::Uuid>,
+    pub updated_by_id: Option<uuid::Uuid>,
+    pub deleted_by_id: Option<uuid::Uuid>,
+}
+
+impl OauthClient {
+    /// redirect uris are compared as exact strings
+    pub fn has_redirect_uri(&self, redirect_uri: &str) -> bool {
+        self.redirect_uris
+            .iter()
+            .any(|uri| uri.as_deref() == Some(redirect_uri))
+    }
+}
@@ -0,0 +1,33 @@
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+use uuid::{self, Uuid};
+
+/// a single use authorization code, only its hash is stored
+#[derive(Debug, PartialEq, Queryable, Selectable, Insertable, Clone, Serialize, Deserialize)]
+#[diesel(table_name = crate::schema::oauth_codes)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct OauthCode {
+    pub id: Uuid,
+    pub oauth_client_id: Uuid,
+    pub user_id: Uuid,
+    pub code_hash: String,
+    pub redirect_uri: String,
+    /// the S256 PKCE challenge
+    pub code_challenge: String,
+    pub scope: Option<String>,
+    pub expires_at: DateTime<Utc>,
+    pub used_at: Option<DateTime<Utc>>,
+    pub is_deleted: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: Option<DateTime<Utc>>,
+    pub deleted_at: Option<DateTime<Utc>>,
+    pub created_by_id: Option<uuid::Uuid>,
+    pub updated_by_id: Option<uuid::Uuid>,
+    pub deleted_by_id: Option<uuid::Uuid>,
+    /// the OpenID Connect nonce of the client, returned in the ID token
+    pub nonce: Option<String>,
+    /// when and how the user authenticated the session authorizing the client
+    pub auth_time: DateTime<Utc>,
+    pub amr: Vec<Option<String>>,
+}
@@ -1,17 +1,21 @@
 use chrono::{DateTime, Utc};
 use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, PartialEq, Queryable, Selectable, Clone)]
+#[derive(Debug, PartialEq, Queryable, Selectable, Insertable, Clone, Serialize, Deserialize)]
 #[diesel(table_name = crate::schema::revoked_tokens)]
 #[diesel(check_for_backend(diesel::pg::Pg))]
 pub struct RevokedToken {
     pub id: i32,
-    pub token: String,
+    /// the SHA-256 of the refresh token
+    pub token_hash: String,
     pub is_deleted: bool,
     pub created_at: DateTime<Utc>,
     pub updated_at: Option<DateTime<Utc>>,
     pub deleted_at: Option<DateTime<Utc>>,
     pub created_by_id: Option<uuid::Uuid>,
     pub updated_by_id: Option<uuid::Uuid>,
-    pub deleted_by_id: Option<uuid::Uuid>
+    pub deleted_by_id: Option<uuid::Uuid>,
+    /// the expiration of the token, the record is purged after it
+    pub expires_at: DateTime<Utc>,
 }
@@ -0,0 +1,21 @@
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+use uuid::{self, Uuid};
+
+#[derive(Debug, PartialEq, Queryable, Selectable, Identifiable, Insertable, Clone, Serialize, Deserialize)]
+#[diesel(table_name = crate::schema::secrets)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct Secret {
+    pub id: Uuid,
+    pub application_id: i32,
+    pub name: String,
+    pub current_version: i32,
+    pub is_deleted: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: Option<DateTime<Utc>>,
+    pub deleted_at: Option<DateTime<Utc>>,
+    pub created_by_id: Option<uuid::Uuid>,
+    pub updated_by_id: Option<uuid::Uuid>,
+    pub deleted_by_id: Option<uuid::Uuid>,
+}
@@ -0,0 +1,37 @@
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+use uuid::{self, Uuid};
+
+use super::secret::Secret;
+use crate::utils::crypto::Crypto;
+
+/// one value of a secret, its encrypted value is split on the nodes under the version id
+#[derive(Debug, PartialEq, Queryable, Selectable, Identifiable, Insertable, Associations, Clone, Serialize, Deserialize)]
+#[diesel(table_name = crate::schema::secret_versions)]
+#[diesel(belongs_to(Secret, foreign_key = secret_id))]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct SecretVersion {
+    pub id: Uuid,
+    pub secret_id: Uuid,
+    pub version: i32,
+    pub iv: String,
+    pub sum: String,
+    pub is_deleted: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: Option<DateTime<Utc>>,
+    pub deleted_at: Option<DateTime<Utc>>,
+    pub created_by_id: Option<uuid::Uuid>,
+    pub updated_by_id: Option<uuid::Uuid>,
+    pub deleted_by_id: Option<uuid::Uuid>,
+}
+
+impl SecretVersion {
+    pub fn check(&self, encrypted_value: String) -> Result<Self, &'static str> {
+        let sum = Crypto::key_sum(&encrypted_value);
+        match sum == self.sum {
+            false => Err("Not valid secret integrity"),
+            true => Ok(self.clone())
+        }
+    }
+}
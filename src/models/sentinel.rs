@@ -1,11 +1,12 @@
 use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
 use chrono::{DateTime, Utc};
 use uuid::{self, Uuid};
 
 use crate::utils::crypto::Crypto;
 
 
-#[derive(Debug, PartialEq, Queryable, Selectable, Clone )]
+#[derive(Debug, PartialEq, Queryable, Selectable, Insertable, Clone, Serialize, Deserialize)]
 #[diesel(table_name = crate::schema::sentinels)]
 #[diesel(check_for_backend(diesel::pg::Pg))]
 pub struct Sentinel {
@@ -21,6 +22,21 @@ pub struct Sentinel {
     pub updated_by_id: Option<uuid::Uuid>,
     pub deleted_by_id: Option<uuid::Uuid>,
     pub key_size: i32,
+    #[serde(default = "default_purpose")]
+    pub purpose: String,
+    /// number of approvals needed to release the key, 0 when the key is not under dual control
+    #[serde(default)]
+    pub required_approvals: i32,
+    /// set when the key was read through break-glass, until an admin acknowledges it
+    #[serde(default)]
+    pub break_glass_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub break_glass_by_id: Option<uuid::Uuid>,
+}
+
+/// the sentinels of the backups made before the purpose existed are encryption keys
+fn default_purpose() -> String {
+    String::from("encryption")
 }
 
 impl Sentinel {
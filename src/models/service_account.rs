@@ -0,0 +1,31 @@
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+use uuid::{self, Uuid};
+
+/// the credentials of a machine identity, the identity itself is the `user_id` user
+/// so it gets the cluster memberships of any user
+#[derive(Debug, PartialEq, Queryable, Selectable, Insertable, Clone, Serialize, Deserialize)]
+#[diesel(table_name = crate::schema::service_accounts)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct ServiceAccount {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub application_id: i32,
+    pub name: String,
+    pub client_id: String,
+    /// the SHA-256 of the client secret
+    pub client_secret_hash: Option<String>,
+    /// the PEM public key checking the `private_key_jwt` client assertions
+    pub public_key: Option<String>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub is_deleted: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: Option<DateTime<Utc>>,
+    pub deleted_at: Option<DateTime<Utc>>,
+    pub created_by_id: Option<uuid::Uuid>,
+    pub updated_by_id: Option<uuid::Uuid>,
+    pub deleted_by_id: Option<uuid::Uuid>,
+    /// the PEM public key checking the HTTP message signatures of the requests
+    pub signing_key: Option<String>,
+}
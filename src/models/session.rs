@@ -0,0 +1,31 @@
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+use uuid::{self, Uuid};
+
+/// a login of a user and the family of refresh tokens issued from it. Only the refresh token
+/// whose `jti` is `refresh_jti` is accepted, presenting an older one revokes the whole family
+#[derive(Debug, PartialEq, Queryable, Selectable, Insertable, Clone, Serialize, Deserialize)]
+#[diesel(table_name = crate::schema::sessions)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct Session {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub application_id: i32,
+    /// the connexion opening the session
+    pub connexion_id: Option<i32>,
+    /// the `jti` of the latest refresh token of the session
+    pub refresh_jti: Uuid,
+    /// the expiration of the latest refresh token
+    pub expires_at: DateTime<Utc>,
+    pub revoked_at: Option<DateTime<Utc>>,
+    pub is_deleted: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: Option<DateTime<Utc>>,
+    pub deleted_at: Option<DateTime<Utc>>,
+    pub created_by_id: Option<uuid::Uuid>,
+    pub updated_by_id: Option<uuid::Uuid>,
+    pub deleted_by_id: Option<uuid::Uuid>,
+    /// the latest refresh of the session
+    pub last_used_at: DateTime<Utc>,
+}
@@ -0,0 +1,30 @@
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+use uuid::{self, Uuid};
+
+/// a passkey of a user, a WebAuthn public key credential
+#[derive(Debug, PartialEq, Queryable, Selectable, Insertable, Clone, Serialize, Deserialize)]
+#[diesel(table_name = crate::schema::webauthn_credentials)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct WebauthnCredential {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub name: String,
+    /// the credential id chosen by the authenticator, url safe base64
+    pub credential_id: String,
+    /// the PEM of the public key
+    pub public_key: String,
+    /// the COSE algorithm of the key: `-7` ES256, `-8` EdDSA or `-257` RS256
+    pub algorithm: i32,
+    /// the signature counter of the authenticator, a counter going back reveals a cloned authenticator
+    pub sign_count: i64,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub is_deleted: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: Option<DateTime<Utc>>,
+    pub deleted_at: Option<DateTime<Utc>>,
+    pub created_by_id: Option<uuid::Uuid>,
+    pub updated_by_id: Option<uuid::Uuid>,
+    pub deleted_by_id: Option<uuid::Uuid>,
+}
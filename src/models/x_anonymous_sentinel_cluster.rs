@@ -1,4 +1,5 @@
 use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
 use chrono::{DateTime, Utc};
 use uuid::{self, Uuid};
 
@@ -6,7 +7,7 @@ use super::anonymous_sentinel::AnonymousSentinel;
 use crate::models::cluster::Cluster;
 
 
-#[derive(Identifiable, Debug, Queryable, Selectable, Associations, PartialEq)]
+#[derive(Identifiable, Debug, Queryable, Selectable, Insertable, Associations, PartialEq, Serialize, Deserialize)]
 #[diesel(table_name = crate::schema::x_anonymous_sentinel_cluster)]
 #[diesel(belongs_to(AnonymousSentinel, foreign_key = anonymous_sentinel_id))]
 #[diesel(belongs_to(Cluster, foreign_key = cluster_id))]
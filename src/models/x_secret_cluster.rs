@@ -0,0 +1,25 @@
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+use uuid::{self, Uuid};
+
+use super::secret::Secret;
+use crate::models::cluster::Cluster;
+
+
+#[derive(Identifiable, Debug, Queryable, Selectable, Insertable, Associations, PartialEq, Serialize, Deserialize)]
+#[diesel(table_name = crate::schema::x_secret_cluster)]
+#[diesel(belongs_to(Secret, foreign_key = secret_id))]
+#[diesel(belongs_to(Cluster, foreign_key = cluster_id))]
+pub struct XSecretCluster {
+    pub id: i32,
+    pub secret_id: Uuid,
+    pub cluster_id: Uuid,
+    pub is_deleted: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: Option<DateTime<Utc>>,
+    pub deleted_at: Option<DateTime<Utc>>,
+    pub created_by_id: Option<uuid::Uuid>,
+    pub updated_by_id: Option<uuid::Uuid>,
+    pub deleted_by_id: Option<uuid::Uuid>
+}
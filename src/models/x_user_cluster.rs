@@ -1,12 +1,13 @@
 use chrono::{DateTime, Utc};
 use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
 use crate::models::cluster::Cluster;
 use crate::models::user::User;
 use crate::schema::x_user_cluster;
 
-#[derive(Identifiable, Debug, Queryable, Selectable, Associations, PartialEq)]
+#[derive(Identifiable, Debug, Queryable, Selectable, Insertable, Associations, PartialEq, Serialize, Deserialize)]
 #[diesel(belongs_to(User, foreign_key = user_id))]
 #[diesel(belongs_to(Cluster, foreign_key = cluster_id))]
 #[diesel(table_name = x_user_cluster)]
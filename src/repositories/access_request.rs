@@ -0,0 +1,251 @@
+use crate::db::connect::DbPool;
+use crate::dto::access_request::access_request_approval_insertable::AccessRequestApprovalInsertable;
+use crate::dto::access_request::access_request_insertable::AccessRequestInsertable;
+use crate::enums::access_request_status::AccessRequestStatus;
+use crate::models::access_request::AccessRequest;
+use crate::models::user::User;
+use crate::schema::{
+    access_request_approvals, access_requests, clusters, x_anonymous_sentinel_cluster,
+    x_sentinel_cluster, x_user_cluster,
+};
+use crate::traits::access_request::AccessRequestContract;
+use chrono::Utc;
+use diesel::dsl::exists;
+use diesel::prelude::*;
+use uuid::Uuid;
+
+pub struct AccessRequestRepository {
+    pool: DbPool,
+}
+
+impl AccessRequestContract for AccessRequestRepository {
+    fn new(pool: &DbPool) -> Self {
+        Self { pool: pool.clone() }
+    }
+
+    /// the highest number of approvals required by the clusters of a sentinel
+    fn get_sentinel_clusters_requirement(&self, sentinel_uuid: &Uuid) -> i32 {
+        let mut conn = self.pool.get().expect("Fail to get a db connexion");
+        x_sentinel_cluster::table
+            .inner_join(clusters::table)
+            .filter(x_sentinel_cluster::sentinel_id.eq(sentinel_uuid))
+            .filter(x_sentinel_cluster::is_deleted.eq(false))
+            .filter(clusters::is_deleted.eq(false))
+            .select(diesel::dsl::max(clusters::required_approvals))
+            .first::<Option<i32>>(&mut conn)
+            .ok()
+            .flatten()
+            .unwrap_or(0)
+    }
+
+    /// the highest number of approvals required by the clusters of an anonymous sentinel
+    fn get_anonymous_sentinel_clusters_requirement(&self, anonymous_sentinel_uuid: &Uuid) -> i32 {
+        let mut conn = self.pool.get().expect("Fail to get a db connexion");
+        x_anonymous_sentinel_cluster::table
+            .inner_join(clusters::table)
+            .filter(x_anonymous_sentinel_cluster::anonymous_sentinel_id.eq(anonymous_sentinel_uuid))
+            .filter(x_anonymous_sentinel_cluster::is_deleted.eq(false))
+            .filter(clusters::is_deleted.eq(false))
+            .select(diesel::dsl::max(clusters::required_approvals))
+            .first::<Option<i32>>(&mut conn)
+            .ok()
+            .flatten()
+            .unwrap_or(0)
+    }
+
+    fn create_access_request(&self, insertable: AccessRequestInsertable) -> AccessRequest {
+        let mut conn = self.pool.get().expect("Fail to get a db connexion");
+        diesel::insert_into(access_requests::table)
+            .values(&insertable)
+            .returning(AccessRequest::as_returning())
+            .get_result(&mut conn)
+            .expect("failed to insert access request")
+    }
+
+    fn get_by_id(&self, access_request_uuid: &Uuid) -> Option<AccessRequest> {
+        let mut conn = self.pool.get().expect("Fail to get a db connexion");
+        access_requests::table
+            .find(access_request_uuid)
+            .filter(access_requests::is_deleted.eq(false))
+            .select(AccessRequest::as_select())
+            .first(&mut conn)
+            .optional()
+            .ok()
+            .flatten()
+    }
+
+    /// the unexpired request of a user on a key with the given status
+    fn find_open(
+        &self,
+        sentinel_uuid: Option<Uuid>,
+        anonymous_sentinel_uuid: Option<Uuid>,
+        requester_uuid: &Uuid,
+        status: AccessRequestStatus,
+    ) -> Option<AccessRequest> {
+        let mut conn = self.pool.get().expect("Fail to get a db connexion");
+        let mut query = access_requests::table
+            .filter(access_requests::requester_id.eq(requester_uuid))
+            .filter(access_requests::status.eq(status.as_str()))
+            .filter(access_requests::expires_at.gt(Utc::now()))
+            .filter(access_requests::is_deleted.eq(false))
+            .into_boxed();
+        query = match (sentinel_uuid, anonymous_sentinel_uuid) {
+            (Some(sentinel_uuid), _) => query.filter(access_requests::sentinel_id.eq(sentinel_uuid)),
+            (None, anonymous_sentinel_uuid) => query.filter(
+                access_requests::anonymous_sentinel_id.eq(anonymous_sentinel_uuid),
+            ),
+        };
+        query
+            .order(access_requests::created_at.desc())
+            .select(AccessRequest::as_select())
+            .first(&mut conn)
+            .optional()
+            .ok()
+            .flatten()
+    }
+
+    /// the requests of the user and the pending requests on the keys of the user clusters
+    fn get_for_user(&self, user_from: &User) -> Vec<AccessRequest> {
+        let mut conn = self.pool.get().expect("Fail to get a db connexion");
+        let user_sentinels = x_sentinel_cluster::table
+            .inner_join(
+                x_user_cluster::table.on(x_user_cluster::cluster_id
+                    .eq(x_sentinel_cluster::cluster_id)
+                    .and(x_user_cluster::is_deleted.eq(false))),
+            )
+            .filter(x_user_cluster::user_id.eq(user_from.id))
+            .filter(x_sentinel_cluster::is_deleted.eq(false))
+            .select(x_sentinel_cluster::sentinel_id.nullable());
+        let user_anonymous_sentinels = x_anonymous_sentinel_cluster::table
+            .inner_join(
+                x_user_cluster::table.on(x_user_cluster::cluster_id
+                    .eq(x_anonymous_sentinel_cluster::cluster_id)
+                    .and(x_user_cluster::is_deleted.eq(false))),
+            )
+            .filter(x_user_cluster::user_id.eq(user_from.id))
+            .filter(x_anonymous_sentinel_cluster::is_deleted.eq(false))
+            .select(x_anonymous_sentinel_cluster::anonymous_sentinel_id.nullable());
+        access_requests::table
+            .filter(access_requests::is_deleted.eq(false))
+            .filter(
+                access_requests::requester_id.eq(user_from.id).or(access_requests::status
+                    .eq(AccessRequestStatus::Pending.as_str())
+                    .and(access_requests::expires_at.gt(Utc::now()))
+                    .and(
+                        access_requests::sentinel_id
+                            .eq_any(user_sentinels)
+                            .or(access_requests::anonymous_sentinel_id
+                                .eq_any(user_anonymous_sentinels)),
+                    )),
+            )
+            .order(access_requests::created_at.desc())
+            .select(AccessRequest::as_select())
+            .load(&mut conn)
+            .unwrap_or_default()
+    }
+
+    /// an approver must share a cluster with the key of the request
+    fn is_cluster_member(&self, access_request: &AccessRequest, user_uuid: &Uuid) -> bool {
+        let mut conn = self.pool.get().expect("Fail to get a db connexion");
+        let res = match (access_request.sentinel_id, access_request.anonymous_sentinel_id) {
+            (Some(sentinel_uuid), _) => diesel::select(exists(
+                x_sentinel_cluster::table
+                    .inner_join(
+                        x_user_cluster::table.on(x_user_cluster::cluster_id
+                            .eq(x_sentinel_cluster::cluster_id)
+                            .and(x_user_cluster::is_deleted.eq(false))),
+                    )
+                    .filter(x_sentinel_cluster::sentinel_id.eq(sentinel_uuid))
+                    .filter(x_sentinel_cluster::is_deleted.eq(false))
+                    .filter(x_user_cluster::user_id.eq(user_uuid)),
+            ))
+            .get_result::<bool>(&mut conn),
+            (None, Some(anonymous_sentinel_uuid)) => diesel::select(exists(
+                x_anonymous_sentinel_cluster::table
+                    .inner_join(
+                        x_user_cluster::table.on(x_user_cluster::cluster_id
+                            .eq(x_anonymous_sentinel_cluster::cluster_id)
+                            .and(x_user_cluster::is_deleted.eq(false))),
+                    )
+                    .filter(x_anonymous_sentinel_cluster::anonymous_sentinel_id.eq(anonymous_sentinel_uuid))
+                    .filter(x_anonymous_sentinel_cluster::is_deleted.eq(false))
+                    .filter(x_user_cluster::user_id.eq(user_uuid)),
+            ))
+            .get_result::<bool>(&mut conn),
+            (None, None) => Ok(false),
+        };
+        res.unwrap_or(false)
+    }
+
+    fn count_approvals(&self, access_request_uuid: &Uuid) -> i64 {
+        let mut conn = self.pool.get().expect("Fail to get a db connexion");
+        access_request_approvals::table
+            .filter(access_request_approvals::access_request_id.eq(access_request_uuid))
+            .count()
+            .get_result(&mut conn)
+            .unwrap_or(0)
+    }
+
+    /// record an approval, the request is locked so the approvals are counted once
+    /// and it becomes `approved` with the last required approval
+    fn approve(
+        &self,
+        access_request_uuid: &Uuid,
+        approver: &User,
+    ) -> Result<AccessRequest, diesel::result::Error> {
+        let mut conn = self.pool.get().expect("Fail to get a db connexion");
+        conn.transaction(|conn| {
+            let access_request = access_requests::table
+                .find(access_request_uuid)
+                .filter(access_requests::status.eq(AccessRequestStatus::Pending.as_str()))
+                .filter(access_requests::expires_at.gt(Utc::now()))
+                .for_update()
+                .select(AccessRequest::as_select())
+                .first(conn)?;
+            diesel::insert_into(access_request_approvals::table)
+                .values(&AccessRequestApprovalInsertable::new(
+                    access_request.id,
+                    approver.id,
+                ))
+                .execute(conn)?;
+            let approvals: i64 = access_request_approvals::table
+                .filter(access_request_approvals::access_request_id.eq(access_request.id))
+                .count()
+                .get_result(conn)?;
+            diesel::update(access_requests::table.find(access_request.id))
+                .set((
+                    access_requests::status.eq(access_request.status_after(approvals).as_str()),
+                    access_requests::updated_at.eq(Some(Utc::now())),
+                    access_requests::updated_by_id.eq(approver.id),
+                ))
+                .returning(AccessRequest::as_returning())
+                .get_result(conn)
+        })
+    }
+
+    /// move a request from one status to another, fails when the request
+    /// is expired or was moved by a concurrent call
+    fn transition(
+        &self,
+        access_request_uuid: &Uuid,
+        from: AccessRequestStatus,
+        to: AccessRequestStatus,
+        user_from: &User,
+    ) -> bool {
+        let mut conn = self.pool.get().expect("Fail to get a db connexion");
+        diesel::update(
+            access_requests::table
+                .find(access_request_uuid)
+                .filter(access_requests::status.eq(from.as_str()))
+                .filter(access_requests::expires_at.gt(Utc::now())),
+        )
+        .set((
+            access_requests::status.eq(to.as_str()),
+            access_requests::updated_at.eq(Some(Utc::now())),
+            access_requests::updated_by_id.eq(user_from.id),
+        ))
+        .execute(&mut conn)
+        .map(|updated| updated == 1)
+        .unwrap_or(false)
+    }
+}
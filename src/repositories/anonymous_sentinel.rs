@@ -26,8 +26,9 @@ impl AnonymousSentinelRepository {
             .expect("failed to insert application")
     }
 
+    /// the admins get any anonymous sentinel of their application, the other users the anonymous
+    /// sentinels they created or that are part of their clusters
     pub fn get_anonymous_sentinel_by_id(&self, sentinel_uuid: &Uuid, user_from: &User) -> Option<AnonymousSentinel> {
-        let mut conn = self.pool.get().expect("Fail to get a db connexion");
         match user_from.clone().roles.into_iter().find(|role| {
             if role.is_some() {
                 let role = role.clone().unwrap();
@@ -35,71 +36,78 @@ impl AnonymousSentinelRepository {
             }
             false
         }) {
-            None => {
-                match anonymous_sentinels::table
-                    .left_join(
-                        x_anonymous_sentinel_cluster::table.on(x_anonymous_sentinel_cluster::anonymous_sentinel_id
-                            .eq(anonymous_sentinels::id)
-                            .and(x_anonymous_sentinel_cluster::is_deleted.eq(false))),
-                    )
-                    .left_join(
-                        clusters::table.on(clusters::id
-                            .eq(x_anonymous_sentinel_cluster::cluster_id)
-                            .and(clusters::is_deleted.eq(false))),
-                    )
-                    .left_join(
-                        x_user_cluster::table.on(x_user_cluster::cluster_id
-                            .eq(clusters::id)
-                            .and(x_user_cluster::is_deleted.eq(false))),
-                    )
-                    .left_join(
-                        users::table.on(users::id
-                            .eq(x_user_cluster::user_id)
-                            .and(users::is_deleted.eq(false))),
-                    )
-                    .filter(
-                        anonymous_sentinels::id
-                            .eq(sentinel_uuid)
-                            .and(anonymous_sentinels::is_deleted.eq(false)),
-                    )
-                    .filter(
-                        users::id
-                            .eq(user_from.id)
-                            .or(anonymous_sentinels::created_by_id.eq(user_from.id)),
-                    )
-                    .select(anonymous_sentinels::all_columns)
-                    .first::<AnonymousSentinel>(&mut conn)
-                    .optional()
-                {
-                    Err(_) => None,
-                    Ok(res) => {
-        
-                        res
-                    },
-                }
-            },
-            Some(user) => {
-                match anonymous_sentinels::table
-                .filter(
-                    anonymous_sentinels::id
-                        .eq(sentinel_uuid)
-                        .and(anonymous_sentinels::is_deleted.eq(false))
-                        .and(anonymous_sentinels::application_id.eq(&user_from.application.unwrap())),
-                )
-                .select(anonymous_sentinels::all_columns)
-                .first::<AnonymousSentinel>(&mut conn)
-                .optional()
-            {
-                Err(_) => None,
-                Ok(res) => {
-    
-                    res
-                },
-            }
-            }
+            None => self.get_member_anonymous_sentinel_by_id(sentinel_uuid, user_from),
+            Some(_) => self.get_application_anonymous_sentinel_by_id(
+                sentinel_uuid,
+                user_from.application.unwrap(),
+            ),
         }
-        
+    }
 
+    /// an anonymous sentinel the user created or that is part of their clusters, whatever their roles
+    pub fn get_member_anonymous_sentinel_by_id(
+        &self,
+        sentinel_uuid: &Uuid,
+        user_from: &User,
+    ) -> Option<AnonymousSentinel> {
+        let mut conn = self.pool.get().expect("Fail to get a db connexion");
+        anonymous_sentinels::table
+            .left_join(
+                x_anonymous_sentinel_cluster::table.on(x_anonymous_sentinel_cluster::anonymous_sentinel_id
+                    .eq(anonymous_sentinels::id)
+                    .and(x_anonymous_sentinel_cluster::is_deleted.eq(false))),
+            )
+            .left_join(
+                clusters::table.on(clusters::id
+                    .eq(x_anonymous_sentinel_cluster::cluster_id)
+                    .and(clusters::is_deleted.eq(false))),
+            )
+            .left_join(
+                x_user_cluster::table.on(x_user_cluster::cluster_id
+                    .eq(clusters::id)
+                    .and(x_user_cluster::is_deleted.eq(false))),
+            )
+            .left_join(
+                users::table.on(users::id
+                    .eq(x_user_cluster::user_id)
+                    .and(users::is_deleted.eq(false))),
+            )
+            .filter(
+                anonymous_sentinels::id
+                    .eq(sentinel_uuid)
+                    .and(anonymous_sentinels::is_deleted.eq(false)),
+            )
+            .filter(
+                users::id
+                    .eq(user_from.id)
+                    .or(anonymous_sentinels::created_by_id.eq(user_from.id)),
+            )
+            .select(anonymous_sentinels::all_columns)
+            .first::<AnonymousSentinel>(&mut conn)
+            .optional()
+            .ok()
+            .flatten()
+    }
+
+    /// any anonymous sentinel of the application, without membership check
+    pub fn get_application_anonymous_sentinel_by_id(
+        &self,
+        sentinel_uuid: &Uuid,
+        app_id: i32,
+    ) -> Option<AnonymousSentinel> {
+        let mut conn = self.pool.get().expect("Fail to get a db connexion");
+        anonymous_sentinels::table
+            .filter(
+                anonymous_sentinels::id
+                    .eq(sentinel_uuid)
+                    .and(anonymous_sentinels::is_deleted.eq(false))
+                    .and(anonymous_sentinels::application_id.eq(app_id)),
+            )
+            .select(anonymous_sentinels::all_columns)
+            .first::<AnonymousSentinel>(&mut conn)
+            .optional()
+            .ok()
+            .flatten()
     }
 
     pub fn get_public_anonymous_sentinel_by_id(&self, sentinel_uuid: &Uuid) -> Option<AnonymousSentinel> {
@@ -180,4 +188,62 @@ impl AnonymousSentinelRepository {
         }
     }
 
+
+    /// set the number of approvals needed to release the key
+    pub fn set_required_approvals(
+        &self,
+        sentinel_uuid: &Uuid,
+        approvals: i32,
+        user_from: &User,
+    ) -> Result<AnonymousSentinel, diesel::result::Error> {
+        let mut conn = self.pool.get().expect("Fail to get a db connexion");
+        diesel::update(
+            anonymous_sentinels::table
+                .filter(anonymous_sentinels::id.eq(sentinel_uuid).and(is_deleted.eq(false))),
+        )
+        .set((
+            anonymous_sentinels::required_approvals.eq(approvals),
+            anonymous_sentinels::updated_at.eq(Some(Utc::now())),
+            anonymous_sentinels::updated_by_id.eq(user_from.id),
+        ))
+        .returning(AnonymousSentinel::as_returning())
+        .get_result(&mut conn)
+    }
+
+    /// flag the anonymous sentinel as read through break-glass
+    pub fn mark_break_glass(
+        &self,
+        sentinel_uuid: &Uuid,
+        user_from: &User,
+    ) -> Result<usize, diesel::result::Error> {
+        let mut conn = self.pool.get().expect("Fail to get a db connexion");
+        diesel::update(anonymous_sentinels::table.find(sentinel_uuid))
+            .set((
+                anonymous_sentinels::break_glass_at.eq(Some(Utc::now())),
+                anonymous_sentinels::break_glass_by_id.eq(Some(user_from.id)),
+            ))
+            .execute(&mut conn)
+    }
+
+    /// clear the break-glass flag of an anonymous sentinel of the application, `0` when it was not flagged
+    pub fn acknowledge_break_glass(
+        &self,
+        sentinel_uuid: &Uuid,
+        user_from: &User,
+    ) -> Result<usize, diesel::result::Error> {
+        let mut conn = self.pool.get().expect("Fail to get a db connexion");
+        diesel::update(
+            anonymous_sentinels::table.filter(
+                anonymous_sentinels::id
+                    .eq(sentinel_uuid)
+                    .and(application_id.eq(user_from.application.unwrap()))
+                    .and(anonymous_sentinels::break_glass_at.is_not_null()),
+            ),
+        )
+        .set((
+            anonymous_sentinels::break_glass_at.eq(None::<chrono::DateTime<Utc>>),
+            anonymous_sentinels::break_glass_by_id.eq(None::<Uuid>),
+        ))
+        .execute(&mut conn)
+    }
 }
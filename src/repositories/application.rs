@@ -19,6 +19,7 @@ use crate::{
 struct ApplicationChangeset {
     name: Option<String>,
     contact_email: Option<String>,
+    allowed_ips: Option<Vec<String>>,
     updated_at: Option<DateTime<Utc>>,
     updated_by_id: Option<Uuid>,
 }
@@ -139,6 +140,7 @@ impl ApplicationContract for ApplicationRepository {
         let changes = ApplicationChangeset {
             name: input.name.clone(),
             contact_email: input.contact_email.clone(),
+            allowed_ips: input.allowed_ips.clone(),
             updated_at: Some(chrono::Utc::now()),
             updated_by_id: Some(user_from.id),
         };
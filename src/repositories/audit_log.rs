@@ -0,0 +1,74 @@
+use crate::db::connect::DbPool;
+use crate::dto::audit_log::audit_log_insertable::AuditLogInsertable;
+use crate::dto::audit_log::break_glass_alert::BreakGlassAlert;
+use crate::models::audit_log::AuditLog;
+use crate::models::user::User;
+use crate::repositories::user::UserRepository;
+use crate::schema::audit_logs;
+use crate::services::mail::MailService;
+use crate::traits::audit::AuditContract;
+use diesel::prelude::*;
+use rocket::tokio::spawn;
+
+pub struct AuditLogRepository {
+    pool: DbPool,
+}
+
+impl AuditContract for AuditLogRepository {
+    fn new(pool: &DbPool) -> Self {
+        Self { pool: pool.clone() }
+    }
+
+    fn create_audit_log(
+        &self,
+        insertable: AuditLogInsertable,
+    ) -> Result<AuditLog, diesel::result::Error> {
+        let mut conn = self.pool.get().expect("Fail to get a db connexion");
+        diesel::insert_into(audit_logs::table)
+            .values(&insertable)
+            .returning(AuditLog::as_returning())
+            .get_result(&mut conn)
+    }
+
+    /// the latest records of an application, newest first
+    fn get_by_application(
+        &self,
+        application_id: i32,
+        severity: Option<&str>,
+        limit: i64,
+    ) -> Vec<AuditLog> {
+        let mut conn = self.pool.get().expect("Fail to get a db connexion");
+        let mut query = audit_logs::table
+            .filter(audit_logs::application_id.eq(application_id))
+            .into_boxed();
+        if let Some(severity) = severity {
+            query = query.filter(audit_logs::severity.eq(severity));
+        }
+        query
+            .order(audit_logs::created_at.desc())
+            .limit(limit)
+            .select(AuditLog::as_select())
+            .load(&mut conn)
+            .unwrap_or_default()
+    }
+
+    fn get_admins_by_app(&self, application_id: i32) -> Vec<User> {
+        UserRepository::new(&self.pool).get_admins_by_app(application_id)
+    }
+
+    fn send_break_glass_alerts(&self, alerts: Vec<BreakGlassAlert>) {
+        spawn(async move {
+            for alert in alerts {
+                MailService::send_break_glass_alert(
+                    &alert.email,
+                    &alert.login,
+                    &alert.requester,
+                    &alert.key,
+                    &alert.justification,
+                    &alert.ip,
+                )
+                .await;
+            }
+        });
+    }
+}
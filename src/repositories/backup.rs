@@ -0,0 +1,265 @@
+use crate::db::connect::DbPool;
+use crate::dto::backup::backup_content::BackupContent;
+use crate::models::anonymous_sentinel::AnonymousSentinel;
+use crate::models::application::Application;
+use crate::models::audit_log::AuditLog;
+use crate::models::cluster::Cluster;
+use crate::models::key_policy::KeyPolicy;
+use crate::models::oauth_client::OauthClient;
+use crate::models::recovery_code::RecoveryCode;
+use crate::models::revoked_token::RevokedToken;
+use crate::models::secret::Secret;
+use crate::models::secret_version::SecretVersion;
+use crate::models::sentinel::Sentinel;
+use crate::models::service_account::ServiceAccount;
+use crate::models::signing_key::SigningKey;
+use crate::models::user::User;
+use crate::models::webauthn_credential::WebauthnCredential;
+use crate::models::x_anonymous_sentinel_cluster::XAnonymousSentinelCluster;
+use crate::models::x_secret_cluster::XSecretCluster;
+use crate::models::x_sentinel_cluster::XSentinelCluster;
+use crate::models::x_user_cluster::XUserCluster;
+use crate::schema::{
+    anonymous_sentinels, applications, audit_logs, clusters, key_policies, oauth_clients,
+    recovery_codes, revoked_tokens, secret_versions, secrets, sentinels, service_accounts,
+    signing_keys, users, webauthn_credentials,
+    x_anonymous_sentinel_cluster, x_secret_cluster, x_sentinel_cluster, x_user_cluster,
+};
+use diesel::prelude::*;
+
+/// rows inserted per statement, keeps every insert below the postgres bind parameters limit
+const RESTORE_CHUNK_SIZE: usize = 500;
+
+pub struct BackupRepository {
+    pool: DbPool,
+}
+
+impl BackupRepository {
+    pub fn new(pool: &DbPool) -> Self {
+        Self { pool: pool.clone() }
+    }
+
+    /// a restore is only allowed on a fresh instance
+    pub fn is_empty(&self) -> bool {
+        let mut conn = self.pool.get().expect("Fail to get a db connexion");
+        let nb_applications: i64 = applications::table
+            .count()
+            .get_result(&mut conn)
+            .expect("failed to count applications");
+        let nb_users: i64 = users::table
+            .count()
+            .get_result(&mut conn)
+            .expect("failed to count users");
+        nb_applications == 0 && nb_users == 0
+    }
+
+    /// ### Dump
+    ///
+    /// read every table of the backup in a single repeatable read snapshot, so the rows
+    /// reference each other even when the instance keeps running. The signing keys travel with
+    /// the revoked tokens, a token revoked before the backup stays revoked after the restore.
+    /// The short lived rows are left out on purpose, they expire before an archive is restored
+    /// or only make sense on the instance that wrote them:
+    /// `sessions`, `connexions`, `oauth_codes`, `import_keys`,
+    /// `share_links`, `access_requests` and `access_request_approvals`
+    pub fn dump(&self) -> Result<BackupContent, diesel::result::Error> {
+        let mut conn = self.pool.get().expect("Fail to get a db connexion");
+        conn.build_transaction()
+            .repeatable_read()
+            .read_only()
+            .run(|conn| {
+                Ok(BackupContent {
+                    applications: applications::table
+                        .select(Application::as_select())
+                        .load(conn)?,
+                    users: users::table
+                        .select(User::as_select())
+                        .load(conn)?,
+                    clusters: clusters::table
+                        .select(Cluster::as_select())
+                        .load(conn)?,
+                    sentinels: sentinels::table
+                        .select(Sentinel::as_select())
+                        .load(conn)?,
+                    anonymous_sentinels: anonymous_sentinels::table
+                        .select(AnonymousSentinel::as_select())
+                        .load(conn)?,
+                    x_user_cluster: x_user_cluster::table
+                        .select(XUserCluster::as_select())
+                        .load(conn)?,
+                    x_sentinel_cluster: x_sentinel_cluster::table
+                        .select(XSentinelCluster::as_select())
+                        .load(conn)?,
+                    x_anonymous_sentinel_cluster: x_anonymous_sentinel_cluster::table
+                        .select(XAnonymousSentinelCluster::as_select())
+                        .load(conn)?,
+                    secrets: secrets::table
+                        .select(Secret::as_select())
+                        .load(conn)?,
+                    secret_versions: secret_versions::table
+                        .select(SecretVersion::as_select())
+                        .load(conn)?,
+                    x_secret_cluster: x_secret_cluster::table
+                        .select(XSecretCluster::as_select())
+                        .load(conn)?,
+                    key_policies: key_policies::table
+                        .select(KeyPolicy::as_select())
+                        .load(conn)?,
+                    service_accounts: service_accounts::table
+                        .select(ServiceAccount::as_select())
+                        .load(conn)?,
+                    oauth_clients: oauth_clients::table
+                        .select(OauthClient::as_select())
+                        .load(conn)?,
+                    webauthn_credentials: webauthn_credentials::table
+                        .select(WebauthnCredential::as_select())
+                        .load(conn)?,
+                    recovery_codes: recovery_codes::table
+                        .select(RecoveryCode::as_select())
+                        .load(conn)?,
+                    signing_keys: signing_keys::table
+                        .select(SigningKey::as_select())
+                        .load(conn)?,
+                    revoked_tokens: revoked_tokens::table
+                        .select(RevokedToken::as_select())
+                        .load(conn)?,
+                    audit_logs: audit_logs::table
+                        .select(AuditLog::as_select())
+                        .load(conn)?,
+                    fragments: vec![],
+                })
+            })
+    }
+
+    /// insert every row of the backup in a single transaction
+    ///
+    /// applications and users reference each other, so the applications are inserted
+    /// without their audit users first and updated once the users exist
+    pub fn restore(&self, content: &BackupContent) -> Result<(), diesel::result::Error> {
+        let mut conn = self.pool.get().expect("Fail to get a db connexion");
+        conn.transaction(|conn| {
+            let orphan_applications: Vec<Application> = content
+                .applications
+                .iter()
+                .map(|application| Application {
+                    created_by_id: None,
+                    updated_by_id: None,
+                    deleted_by_id: None,
+                    ..application.clone()
+                })
+                .collect();
+            for chunk in orphan_applications.chunks(RESTORE_CHUNK_SIZE) {
+                diesel::insert_into(applications::table)
+                    .values(chunk)
+                    .execute(conn)?;
+            }
+            for chunk in content.users.chunks(RESTORE_CHUNK_SIZE) {
+                diesel::insert_into(users::table).values(chunk).execute(conn)?;
+            }
+            for application in content.applications.iter() {
+                diesel::update(applications::table.find(application.id))
+                    .set((
+                        applications::created_by_id.eq(application.created_by_id),
+                        applications::updated_by_id.eq(application.updated_by_id),
+                        applications::deleted_by_id.eq(application.deleted_by_id),
+                    ))
+                    .execute(conn)?;
+            }
+            for chunk in content.service_accounts.chunks(RESTORE_CHUNK_SIZE) {
+                diesel::insert_into(service_accounts::table)
+                    .values(chunk)
+                    .execute(conn)?;
+            }
+            for chunk in content.oauth_clients.chunks(RESTORE_CHUNK_SIZE) {
+                diesel::insert_into(oauth_clients::table)
+                    .values(chunk)
+                    .execute(conn)?;
+            }
+            for chunk in content.webauthn_credentials.chunks(RESTORE_CHUNK_SIZE) {
+                diesel::insert_into(webauthn_credentials::table)
+                    .values(chunk)
+                    .execute(conn)?;
+            }
+            for chunk in content.recovery_codes.chunks(RESTORE_CHUNK_SIZE) {
+                diesel::insert_into(recovery_codes::table)
+                    .values(chunk)
+                    .execute(conn)?;
+            }
+            for chunk in content.clusters.chunks(RESTORE_CHUNK_SIZE) {
+                diesel::insert_into(clusters::table).values(chunk).execute(conn)?;
+            }
+            for chunk in content.sentinels.chunks(RESTORE_CHUNK_SIZE) {
+                diesel::insert_into(sentinels::table).values(chunk).execute(conn)?;
+            }
+            for chunk in content.anonymous_sentinels.chunks(RESTORE_CHUNK_SIZE) {
+                diesel::insert_into(anonymous_sentinels::table)
+                    .values(chunk)
+                    .execute(conn)?;
+            }
+            for chunk in content.x_user_cluster.chunks(RESTORE_CHUNK_SIZE) {
+                diesel::insert_into(x_user_cluster::table)
+                    .values(chunk)
+                    .execute(conn)?;
+            }
+            for chunk in content.x_sentinel_cluster.chunks(RESTORE_CHUNK_SIZE) {
+                diesel::insert_into(x_sentinel_cluster::table)
+                    .values(chunk)
+                    .execute(conn)?;
+            }
+            for chunk in content.x_anonymous_sentinel_cluster.chunks(RESTORE_CHUNK_SIZE) {
+                diesel::insert_into(x_anonymous_sentinel_cluster::table)
+                    .values(chunk)
+                    .execute(conn)?;
+            }
+            for chunk in content.secrets.chunks(RESTORE_CHUNK_SIZE) {
+                diesel::insert_into(secrets::table).values(chunk).execute(conn)?;
+            }
+            for chunk in content.secret_versions.chunks(RESTORE_CHUNK_SIZE) {
+                diesel::insert_into(secret_versions::table)
+                    .values(chunk)
+                    .execute(conn)?;
+            }
+            for chunk in content.x_secret_cluster.chunks(RESTORE_CHUNK_SIZE) {
+                diesel::insert_into(x_secret_cluster::table)
+                    .values(chunk)
+                    .execute(conn)?;
+            }
+            for chunk in content.key_policies.chunks(RESTORE_CHUNK_SIZE) {
+                diesel::insert_into(key_policies::table)
+                    .values(chunk)
+                    .execute(conn)?;
+            }
+            for chunk in content.signing_keys.chunks(RESTORE_CHUNK_SIZE) {
+                diesel::insert_into(signing_keys::table)
+                    .values(chunk)
+                    .execute(conn)?;
+            }
+            for chunk in content.revoked_tokens.chunks(RESTORE_CHUNK_SIZE) {
+                diesel::insert_into(revoked_tokens::table)
+                    .values(chunk)
+                    .execute(conn)?;
+            }
+            for chunk in content.audit_logs.chunks(RESTORE_CHUNK_SIZE) {
+                diesel::insert_into(audit_logs::table)
+                    .values(chunk)
+                    .execute(conn)?;
+            }
+            // serial ids were inserted explicitly, move the sequences after them
+            for table in [
+                "applications",
+                "x_user_cluster",
+                "x_sentinel_cluster",
+                "x_anonymous_sentinel_cluster",
+                "x_secret_cluster",
+                "revoked_tokens",
+            ] {
+                diesel::sql_query(format!(
+                    "SELECT setval(pg_get_serial_sequence('{0}', 'id'), COALESCE((SELECT MAX(id) FROM {0}), 0) + 1, false)",
+                    table
+                ))
+                .execute(conn)?;
+            }
+            Ok(())
+        })
+    }
+}
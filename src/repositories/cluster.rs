@@ -4,18 +4,21 @@ use uuid::Uuid;
 
 use crate::dto::cluster::cluster_insertable::ClusterInsertable;
 use crate::dto::x_anonymous_sentinel_cluster::x_anonymous_sentinel_cluster_insertable::XAnonymousSentinelClusterInsertable;
+use crate::dto::x_secret_cluster::x_secret_cluster_insertable::XSecretClusterInsertable;
 use crate::dto::x_sentinel_cluster::x_sentinel_cluster_insertable::XSentinelClusterInsertable;
 use crate::dto::x_user_cluster::x_user_cluster_insertable::XUserClusterInsertable;
 use crate::models::anonymous_sentinel::AnonymousSentinel;
 use crate::models::cluster::Cluster;
+use crate::models::secret::Secret;
 use crate::models::sentinel::Sentinel;
 use crate::models::user::User;
 use crate::models::x_anonymous_sentinel_cluster::XAnonymousSentinelCluster;
+use crate::models::x_secret_cluster::XSecretCluster;
 use crate::models::x_sentinel_cluster::XSentinelCluster;
 use crate::models::x_user_cluster::XUserCluster;
 use crate::schema::clusters::dsl::*;
 use crate::schema::x_user_cluster::{cluster_id, user_id};
-use crate::schema::{anonymous_sentinels, sentinels, users, x_anonymous_sentinel_cluster, x_sentinel_cluster, x_user_cluster};
+use crate::schema::{anonymous_sentinels, secrets, sentinels, users, x_anonymous_sentinel_cluster, x_secret_cluster, x_sentinel_cluster, x_user_cluster};
 use crate::{db::connect::DbPool, schema::clusters};
 
 use super::anonymous_sentinel;
@@ -143,6 +146,28 @@ impl ClusterRepository {
         .execute(&mut conn)
     }
 
+    /// set the number of approvals needed to release the keys of the cluster
+    pub fn set_required_approvals(
+        &self,
+        cluster_uuid: &Uuid,
+        approvals: i32,
+        user_from: &User,
+    ) -> Result<Cluster, diesel::result::Error> {
+        let mut conn = self.pool.get().expect("Fail to get a db connexion");
+        diesel::update(
+            clusters::table
+                .find(cluster_uuid)
+                .filter(is_deleted.eq(false)),
+        )
+        .set((
+            clusters::required_approvals.eq(approvals),
+            updated_at.eq(Some(Utc::now())),
+            updated_by_id.eq(user_from.id),
+        ))
+        .returning(Cluster::as_returning())
+        .get_result(&mut conn)
+    }
+
     /// check if a user is part of a cluster
     pub fn get_user_cluster_sentinels(
         &self,
@@ -185,6 +210,32 @@ impl ClusterRepository {
         users.len() > 0
     }
 
+    /// the active clusters of a sentinel
+    pub fn get_sentinel_cluster_ids(&self, sentinel_uuid: &Uuid) -> Vec<Uuid> {
+        let mut conn = self.pool.get().expect("Fail to get a db connexion");
+        x_sentinel_cluster::table
+            .inner_join(clusters::table)
+            .filter(x_sentinel_cluster::sentinel_id.eq(sentinel_uuid))
+            .filter(x_sentinel_cluster::is_deleted.eq(false))
+            .filter(clusters::is_deleted.eq(false))
+            .select(x_sentinel_cluster::cluster_id)
+            .load::<Uuid>(&mut conn)
+            .unwrap_or_default()
+    }
+
+    /// the active clusters of an anonymous sentinel
+    pub fn get_anonymous_sentinel_cluster_ids(&self, anonymous_sentinel_uuid: &Uuid) -> Vec<Uuid> {
+        let mut conn = self.pool.get().expect("Fail to get a db connexion");
+        x_anonymous_sentinel_cluster::table
+            .inner_join(clusters::table)
+            .filter(x_anonymous_sentinel_cluster::anonymous_sentinel_id.eq(anonymous_sentinel_uuid))
+            .filter(x_anonymous_sentinel_cluster::is_deleted.eq(false))
+            .filter(clusters::is_deleted.eq(false))
+            .select(x_anonymous_sentinel_cluster::cluster_id)
+            .load::<Uuid>(&mut conn)
+            .unwrap_or_default()
+    }
+
     pub fn add_sentinel_to_cluster(
         &self,
         insertable: XSentinelClusterInsertable,
@@ -256,6 +307,55 @@ impl ClusterRepository {
         .execute(&mut conn)
     }
 
+    /// check if a secret is part of a cluster
+    pub fn get_cluster_secrets(&self, cluster: &Cluster, secret_to_test_id: &Uuid) -> bool {
+        let mut conn = self.pool.get().expect("Fail to get a db connexion");
+        let secrets = XSecretCluster::belonging_to(&cluster)
+            .inner_join(secrets::table.on(x_secret_cluster::secret_id.eq(secrets::id)))
+            .filter(
+                x_secret_cluster::is_deleted
+                    .eq(false)
+                    .and(secrets::id.eq(secret_to_test_id))
+                    .and(secrets::is_deleted.eq(false)),
+            )
+            .select(secrets::id)
+            .load::<Uuid>(&mut conn)
+            .unwrap_or_default();
+        !secrets.is_empty()
+    }
+
+    pub fn add_secret_to_cluster(&self, insertable: XSecretClusterInsertable) -> XSecretCluster {
+        let mut conn = self.pool.get().expect("Fail to get a db connexion");
+        diesel::insert_into(x_secret_cluster::table)
+            .values(&insertable)
+            .returning(XSecretCluster::as_returning())
+            .get_result(&mut conn)
+            .expect("failed to insert secret cluster")
+    }
+
+    pub fn remove_secret_from_cluster(
+        &self,
+        secret: &Secret,
+        cluster: &Cluster,
+        user_from: &User,
+    ) -> Result<usize, diesel::result::Error> {
+        let mut conn = self.pool.get().expect("Fail to get a db connexion");
+        diesel::update(
+            x_secret_cluster::table.filter(
+                x_secret_cluster::secret_id
+                    .eq(secret.id)
+                    .and(x_secret_cluster::cluster_id.eq(cluster.id))
+                    .and(x_secret_cluster::is_deleted.eq(false)),
+            ),
+        )
+        .set((
+            x_secret_cluster::is_deleted.eq(true),
+            x_secret_cluster::deleted_at.eq(Some(Utc::now())),
+            x_secret_cluster::deleted_by_id.eq(user_from.id),
+        ))
+        .execute(&mut conn)
+    }
+
     pub fn get_cluster_users(
         &self,
         cluster_uuid: &Uuid,
@@ -0,0 +1,66 @@
+use crate::db::connect::DbPool;
+use crate::dto::import_key::import_key_insertable::ImportKeyInsertable;
+use crate::models::import_key::ImportKey;
+use crate::schema::import_keys::{self};
+use chrono::Utc;
+use diesel::prelude::*;
+use uuid::Uuid;
+
+pub struct ImportKeyRepository {
+    pool: DbPool,
+}
+
+impl ImportKeyRepository {
+    pub fn new(pool: &DbPool) -> Self {
+        Self { pool: pool.clone() }
+    }
+
+    pub fn create_import_key(&self, insertable: ImportKeyInsertable) -> ImportKey {
+        let mut conn = self.pool.get().expect("Fail to get a db connexion");
+        diesel::insert_into(import_keys::table)
+            .values(&insertable)
+            .returning(ImportKey::as_returning())
+            .get_result(&mut conn)
+            .expect("failed to insert import key")
+    }
+
+    /// the most recent living import key of an application for an algorithm
+    pub fn get_active_by_application(
+        &self,
+        application_id: i32,
+        algorithm: &str,
+    ) -> Option<ImportKey> {
+        let mut conn = self.pool.get().expect("Fail to get a db connexion");
+        import_keys::table
+            .filter(
+                import_keys::application_id
+                    .eq(application_id)
+                    .and(import_keys::algorithm.eq(algorithm))
+                    .and(import_keys::is_deleted.eq(false))
+                    .and(import_keys::expires_at.gt(Utc::now())),
+            )
+            .order(import_keys::created_at.desc())
+            .select(import_keys::all_columns)
+            .first::<ImportKey>(&mut conn)
+            .optional()
+            .ok()
+            .flatten()
+    }
+
+    pub fn get_active_by_id(&self, import_key_uuid: &Uuid, application_id: i32) -> Option<ImportKey> {
+        let mut conn = self.pool.get().expect("Fail to get a db connexion");
+        import_keys::table
+            .filter(
+                import_keys::id
+                    .eq(import_key_uuid)
+                    .and(import_keys::application_id.eq(application_id))
+                    .and(import_keys::is_deleted.eq(false))
+                    .and(import_keys::expires_at.gt(Utc::now())),
+            )
+            .select(import_keys::all_columns)
+            .first::<ImportKey>(&mut conn)
+            .optional()
+            .ok()
+            .flatten()
+    }
+}
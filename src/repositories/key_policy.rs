@@ -0,0 +1,115 @@
+use crate::db::connect::DbPool;
+use crate::dto::key_policy::key_policy_insertable::KeyPolicyInsertable;
+use crate::models::key_policy::KeyPolicy;
+use crate::models::user::User;
+use crate::schema::key_policies;
+use chrono::Utc;
+use diesel::prelude::*;
+use uuid::Uuid;
+
+pub struct KeyPolicyRepository {
+    pool: DbPool,
+}
+
+impl KeyPolicyRepository {
+    pub fn new(pool: &DbPool) -> Self {
+        Self { pool: pool.clone() }
+    }
+
+    pub fn get_by_sentinel_id(&self, sentinel_uuid: &Uuid) -> Option<KeyPolicy> {
+        let mut conn = self.pool.get().expect("Fail to get a db connexion");
+        key_policies::table
+            .filter(key_policies::sentinel_id.eq(sentinel_uuid))
+            .filter(key_policies::is_deleted.eq(false))
+            .select(KeyPolicy::as_select())
+            .first(&mut conn)
+            .optional()
+            .ok()
+            .flatten()
+    }
+
+    pub fn get_by_anonymous_sentinel_id(&self, anonymous_sentinel_uuid: &Uuid) -> Option<KeyPolicy> {
+        let mut conn = self.pool.get().expect("Fail to get a db connexion");
+        key_policies::table
+            .filter(key_policies::anonymous_sentinel_id.eq(anonymous_sentinel_uuid))
+            .filter(key_policies::is_deleted.eq(false))
+            .select(KeyPolicy::as_select())
+            .first(&mut conn)
+            .optional()
+            .ok()
+            .flatten()
+    }
+
+    /// replace the active policy of the key, the reads already done are kept
+    /// so a new policy can not be used to reset the counter
+    pub fn set_policy(
+        &self,
+        mut insertable: KeyPolicyInsertable,
+        user_from: &User,
+    ) -> Result<KeyPolicy, diesel::result::Error> {
+        let mut conn = self.pool.get().expect("Fail to get a db connexion");
+        conn.transaction(|conn| {
+            let previous: Vec<i32> = diesel::update(
+                key_policies::table
+                    .filter(
+                        key_policies::sentinel_id
+                            .eq(insertable.sentinel_id)
+                            .or(key_policies::anonymous_sentinel_id
+                                .eq(insertable.anonymous_sentinel_id)),
+                    )
+                    .filter(key_policies::is_deleted.eq(false)),
+            )
+            .set((
+                key_policies::is_deleted.eq(true),
+                key_policies::deleted_at.eq(Some(Utc::now())),
+                key_policies::deleted_by_id.eq(user_from.id),
+            ))
+            .returning(key_policies::read_count)
+            .get_results(conn)?;
+            insertable.read_count = previous.into_iter().max().unwrap_or(0);
+            diesel::insert_into(key_policies::table)
+                .values(&insertable)
+                .returning(KeyPolicy::as_returning())
+                .get_result(conn)
+        })
+    }
+
+    pub fn delete_policy(
+        &self,
+        policy_uuid: &Uuid,
+        user_from: &User,
+    ) -> Result<usize, diesel::result::Error> {
+        let mut conn = self.pool.get().expect("Fail to get a db connexion");
+        diesel::update(
+            key_policies::table
+                .filter(key_policies::id.eq(policy_uuid))
+                .filter(key_policies::is_deleted.eq(false)),
+        )
+        .set((
+            key_policies::is_deleted.eq(true),
+            key_policies::deleted_at.eq(Some(Utc::now())),
+            key_policies::deleted_by_id.eq(user_from.id),
+        ))
+        .execute(&mut conn)
+    }
+
+    /// count a read, fails when the maximum number of reads is reached,
+    /// the check and the increment are a single statement so concurrent reads can not overshoot
+    pub fn consume_read(&self, policy_uuid: &Uuid) -> bool {
+        let mut conn = self.pool.get().expect("Fail to get a db connexion");
+        diesel::update(
+            key_policies::table
+                .filter(key_policies::id.eq(policy_uuid))
+                .filter(key_policies::is_deleted.eq(false))
+                .filter(
+                    key_policies::max_reads
+                        .is_null()
+                        .or(key_policies::read_count.nullable().lt(key_policies::max_reads)),
+                ),
+        )
+        .set(key_policies::read_count.eq(key_policies::read_count + 1))
+        .execute(&mut conn)
+        .map(|updated| updated == 1)
+        .unwrap_or(false)
+    }
+}
@@ -4,4 +4,17 @@ pub mod connexion;
 pub mod revoked_token;
 pub mod cluster;
 pub mod sentinel;
-pub mod anonymous_sentinel;
\ No newline at end of file
+pub mod anonymous_sentinel;
+pub mod backup;
+pub mod import_key;
+pub mod secret;
+pub mod key_policy;
+pub mod access_request;
+pub mod audit_log;
+pub mod share_link;
+pub mod service_account;
+pub mod oauth_client;
+pub mod signing_key;
+pub mod session;
+pub mod webauthn_credential;
+pub mod recovery_code;
\ No newline at end of file
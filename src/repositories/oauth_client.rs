@@ -0,0 +1,127 @@
+use crate::db::connect::DbPool;
+use crate::dto::oauth::oauth_client_insertable::OauthClientInsertable;
+use crate::dto::oauth::oauth_code_insertable::OauthCodeInsertable;
+use crate::models::oauth_client::OauthClient;
+use crate::models::oauth_code::OauthCode;
+use crate::models::user::User;
+use crate::schema::{oauth_clients, oauth_codes};
+use chrono::Utc;
+use diesel::prelude::*;
+use uuid::Uuid;
+
+pub struct OauthClientRepository {
+    pool: DbPool,
+}
+
+impl OauthClientRepository {
+    pub fn new(pool: &DbPool) -> Self {
+        Self { pool: pool.clone() }
+    }
+
+    pub fn create_oauth_client(&self, insertable: OauthClientInsertable) -> Result<OauthClient, diesel::result::Error> {
+        let mut conn = self.pool.get().expect("Fail to get a db connexion");
+        diesel::insert_into(oauth_clients::table)
+            .values(&insertable)
+            .returning(OauthClient::as_returning())
+            .get_result(&mut conn)
+    }
+
+    pub fn get_by_client_id(&self, client_id: &str) -> Option<OauthClient> {
+        let mut conn = self.pool.get().expect("Fail to get a db connexion");
+        oauth_clients::table
+            .filter(oauth_clients::client_id.eq(client_id))
+            .filter(oauth_clients::is_deleted.eq(false))
+            .select(OauthClient::as_select())
+            .first(&mut conn)
+            .optional()
+            .ok()
+            .flatten()
+    }
+
+    pub fn get_by_id_and_app(&self, oauth_client_uuid: &Uuid, app_id: i32) -> Option<OauthClient> {
+        let mut conn = self.pool.get().expect("Fail to get a db connexion");
+        oauth_clients::table
+            .find(oauth_client_uuid)
+            .filter(oauth_clients::application_id.eq(app_id))
+            .filter(oauth_clients::is_deleted.eq(false))
+            .select(OauthClient::as_select())
+            .first(&mut conn)
+            .optional()
+            .ok()
+            .flatten()
+    }
+
+    pub fn get_by_application(&self, app_id: i32) -> Vec<OauthClient> {
+        let mut conn = self.pool.get().expect("Fail to get a db connexion");
+        oauth_clients::table
+            .filter(oauth_clients::application_id.eq(app_id))
+            .filter(oauth_clients::is_deleted.eq(false))
+            .order(oauth_clients::created_at.desc())
+            .select(OauthClient::as_select())
+            .load(&mut conn)
+            .unwrap_or_default()
+    }
+
+    pub fn update_secret(
+        &self,
+        oauth_client_uuid: &Uuid,
+        client_secret_hash: Option<String>,
+        user_from: &User,
+    ) -> Result<OauthClient, diesel::result::Error> {
+        let mut conn = self.pool.get().expect("Fail to get a db connexion");
+        diesel::update(
+            oauth_clients::table
+                .find(oauth_client_uuid)
+                .filter(oauth_clients::is_deleted.eq(false)),
+        )
+        .set((
+            oauth_clients::client_secret_hash.eq(client_secret_hash),
+            oauth_clients::updated_at.eq(Some(Utc::now())),
+            oauth_clients::updated_by_id.eq(user_from.id),
+        ))
+        .returning(OauthClient::as_returning())
+        .get_result(&mut conn)
+    }
+
+    pub fn delete(&self, oauth_client_uuid: &Uuid, user_from: &User) -> Result<usize, diesel::result::Error> {
+        let mut conn = self.pool.get().expect("Fail to get a db connexion");
+        diesel::update(
+            oauth_clients::table
+                .find(oauth_client_uuid)
+                .filter(oauth_clients::is_deleted.eq(false)),
+        )
+        .set((
+            oauth_clients::is_deleted.eq(true),
+            oauth_clients::deleted_at.eq(Some(Utc::now())),
+            oauth_clients::deleted_by_id.eq(user_from.id),
+        ))
+        .execute(&mut conn)
+    }
+
+    pub fn create_code(&self, insertable: OauthCodeInsertable) -> Result<OauthCode, diesel::result::Error> {
+        let mut conn = self.pool.get().expect("Fail to get a db connexion");
+        diesel::insert_into(oauth_codes::table)
+            .values(&insertable)
+            .returning(OauthCode::as_returning())
+            .get_result(&mut conn)
+    }
+
+    /// mark a live code as used, the check and the update are a single statement
+    /// so a code can only be exchanged once
+    pub fn consume_code(&self, code_hash: &str) -> Option<OauthCode> {
+        let mut conn = self.pool.get().expect("Fail to get a db connexion");
+        diesel::update(
+            oauth_codes::table
+                .filter(oauth_codes::code_hash.eq(code_hash))
+                .filter(oauth_codes::is_deleted.eq(false))
+                .filter(oauth_codes::used_at.is_null())
+                .filter(oauth_codes::expires_at.gt(Utc::now())),
+        )
+        .set(oauth_codes::used_at.eq(Some(Utc::now())))
+        .returning(OauthCode::as_returning())
+        .get_result(&mut conn)
+        .optional()
+        .ok()
+        .flatten()
+    }
+}
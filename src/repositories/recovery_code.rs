@@ -0,0 +1,73 @@
+use crate::db::connect::DbPool;
+use crate::dto::recovery_code::recovery_code_insertable::RecoveryCodeInsertable;
+use crate::schema::recovery_codes;
+use chrono::Utc;
+use diesel::prelude::*;
+use uuid::Uuid;
+
+pub struct RecoveryCodeRepository {
+    pool: DbPool,
+}
+
+impl RecoveryCodeRepository {
+    pub fn new(pool: &DbPool) -> Self {
+        Self { pool: pool.clone() }
+    }
+
+    /// replace the codes of a user, the previous ones stop working
+    pub fn replace_codes(
+        &self,
+        user_uuid: &Uuid,
+        code_hashes: Vec<String>,
+    ) -> Result<usize, diesel::result::Error> {
+        let mut conn = self.pool.get().expect("Fail to get a db connexion");
+        conn.transaction(|conn| {
+            Self::delete_codes(conn, user_uuid)?;
+            let insertables: Vec<RecoveryCodeInsertable> = code_hashes
+                .into_iter()
+                .map(|code_hash| RecoveryCodeInsertable::new(*user_uuid, code_hash))
+                .collect();
+            diesel::insert_into(recovery_codes::table)
+                .values(&insertables)
+                .execute(conn)
+        })
+    }
+
+    /// use an unused code once, two concurrent logins cannot both use it
+    pub fn consume(&self, user_uuid: &Uuid, code_hash: &str) -> bool {
+        let mut conn = self.pool.get().expect("Fail to get a db connexion");
+        diesel::update(
+            recovery_codes::table
+                .filter(recovery_codes::user_id.eq(user_uuid))
+                .filter(recovery_codes::code_hash.eq(code_hash))
+                .filter(recovery_codes::used_at.is_null())
+                .filter(recovery_codes::is_deleted.eq(false)),
+        )
+        .set((
+            recovery_codes::used_at.eq(Some(Utc::now())),
+            recovery_codes::updated_at.eq(Some(Utc::now())),
+            recovery_codes::updated_by_id.eq(Some(*user_uuid)),
+        ))
+        .execute(&mut conn)
+        .is_ok_and(|updated| updated > 0)
+    }
+
+    pub fn delete_by_user(&self, user_uuid: &Uuid) -> Result<usize, diesel::result::Error> {
+        let mut conn = self.pool.get().expect("Fail to get a db connexion");
+        Self::delete_codes(&mut conn, user_uuid)
+    }
+
+    fn delete_codes(conn: &mut PgConnection, user_uuid: &Uuid) -> Result<usize, diesel::result::Error> {
+        diesel::update(
+            recovery_codes::table
+                .filter(recovery_codes::user_id.eq(user_uuid))
+                .filter(recovery_codes::is_deleted.eq(false)),
+        )
+        .set((
+            recovery_codes::is_deleted.eq(true),
+            recovery_codes::deleted_at.eq(Some(Utc::now())),
+            recovery_codes::deleted_by_id.eq(Some(*user_uuid)),
+        ))
+        .execute(conn)
+    }
+}
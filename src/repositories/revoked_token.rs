@@ -1,3 +1,4 @@
+use chrono::Utc;
 use diesel::prelude::*;
 
 use crate::dto::connexion::connexion_insertable::ConnexionInsertable;
@@ -18,24 +19,28 @@ impl RevokedTokenContract for RevokedTokenRepository {
         Self { pool: pool.clone() }
     }
 
-    fn create_revoked_token(&self, insertable: RevokedTokenInsertable) -> RevokedToken {
+    fn create_revoked_token(&self, insertable: RevokedTokenInsertable) -> Result<RevokedToken, diesel::result::Error> {
         let mut conn = self.pool.get().expect("Fail to get a db connexion");
         diesel::insert_into(revoked_tokens::table)
             .values(&insertable)
             .returning(RevokedToken::as_returning())
             .get_result(&mut conn)
-            .expect("failed to insert application")
     }
 
-    fn get_by_token(&self, test_token: &String) -> Option<RevokedToken> {
+    fn get_by_token_hash(&self, test_token_hash: &str) -> Option<RevokedToken> {
         let mut conn = self.pool.get().expect("Fail to get a db connexion");
-        match revoked_tokens
-            .filter(token.eq(test_token).and(is_deleted.eq(false)))
+        revoked_tokens
+            .filter(token_hash.eq(test_token_hash).and(is_deleted.eq(false)))
             .first::<RevokedToken>(&mut conn)
             .optional()
-        {
-            Err(_) => None,
-            Ok(app) => app,
-        }
+            .ok()
+            .flatten()
     }
-}
+
+    fn purge_expired(&self) -> usize {
+        let mut conn = self.pool.get().expect("Fail to get a db connexion");
+        diesel::delete(revoked_tokens.filter(expires_at.lt(Utc::now())))
+            .execute(&mut conn)
+            .unwrap_or(0)
+    }
+}
\ No newline at end of file
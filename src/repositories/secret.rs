@@ -0,0 +1,232 @@
+use crate::db::connect::DbPool;
+use crate::dto::secret::secret_insertable::SecretInsertable;
+use crate::dto::secret::secret_version_insertable::SecretVersionInsertable;
+use crate::dto::x_secret_cluster::x_secret_cluster_insertable::XSecretClusterInsertable;
+use crate::models::secret::Secret;
+use crate::models::secret_version::SecretVersion;
+use crate::models::user::User;
+use crate::repositories::cluster::ClusterRepository;
+use crate::schema::{clusters, secret_versions, secrets, users, x_secret_cluster, x_user_cluster};
+use crate::traits::secret::SecretContract;
+use chrono::Utc;
+use diesel::prelude::*;
+use uuid::Uuid;
+
+pub struct SecretRepository {
+    pool: DbPool,
+}
+
+impl SecretContract for SecretRepository {
+    fn new(pool: &DbPool) -> Self {
+        Self { pool: pool.clone() }
+    }
+
+    /// insert the secret and its first version in a single transaction
+    fn create_secret(
+        &self,
+        insertable: SecretInsertable,
+        iv: String,
+        sum: String,
+    ) -> Result<(Secret, SecretVersion), diesel::result::Error> {
+        let mut conn = self.pool.get().expect("Fail to get a db connexion");
+        conn.transaction(|conn| {
+            let user_from_id = insertable.created_by_id.unwrap_or_default();
+            let secret = diesel::insert_into(secrets::table)
+                .values(&insertable)
+                .returning(Secret::as_returning())
+                .get_result(conn)?;
+            let secret_version = diesel::insert_into(secret_versions::table)
+                .values(&SecretVersionInsertable::new(
+                    secret.id,
+                    secret.current_version,
+                    iv,
+                    sum,
+                    user_from_id,
+                ))
+                .returning(SecretVersion::as_returning())
+                .get_result(conn)?;
+            Ok((secret, secret_version))
+        })
+    }
+
+    /// insert the next version of a secret, the secret row is locked
+    /// so two concurrent writers can not get the same version number
+    fn add_version(
+        &self,
+        secret_uuid: &Uuid,
+        iv: String,
+        sum: String,
+        user_from: &User,
+    ) -> Result<(Secret, SecretVersion), diesel::result::Error> {
+        let mut conn = self.pool.get().expect("Fail to get a db connexion");
+        conn.transaction(|conn| {
+            let secret = secrets::table
+                .find(secret_uuid)
+                .filter(secrets::is_deleted.eq(false))
+                .for_update()
+                .select(Secret::as_select())
+                .first(conn)?;
+            let secret_version = diesel::insert_into(secret_versions::table)
+                .values(&SecretVersionInsertable::new(
+                    secret.id,
+                    secret.current_version + 1,
+                    iv,
+                    sum,
+                    user_from.id,
+                ))
+                .returning(SecretVersion::as_returning())
+                .get_result(conn)?;
+            let secret = diesel::update(secrets::table.find(secret.id))
+                .set((
+                    secrets::current_version.eq(secret_version.version),
+                    secrets::updated_at.eq(Some(Utc::now())),
+                    secrets::updated_by_id.eq(user_from.id),
+                ))
+                .returning(Secret::as_returning())
+                .get_result(conn)?;
+            Ok((secret, secret_version))
+        })
+    }
+
+    fn get_by_name(&self, secret_name: &str, application_id: i32) -> Option<Secret> {
+        let mut conn = self.pool.get().expect("Fail to get a db connexion");
+        secrets::table
+            .filter(
+                secrets::name
+                    .eq(secret_name)
+                    .and(secrets::application_id.eq(application_id))
+                    .and(secrets::is_deleted.eq(false)),
+            )
+            .select(Secret::as_select())
+            .first(&mut conn)
+            .optional()
+            .ok()
+            .flatten()
+    }
+
+    /// get a secret the user can read: an admin reads every secret of the application,
+    /// a user the secrets they created or shared with one of his clusters
+    fn get_secret_by_id(&self, secret_uuid: &Uuid, user_from: &User) -> Option<Secret> {
+        let mut conn = self.pool.get().expect("Fail to get a db connexion");
+        let is_admin = user_from
+            .roles
+            .iter()
+            .any(|role| role.as_deref() == Some("ROLE_ADMIN"));
+        let query = secrets::table
+            .filter(
+                secrets::id
+                    .eq(secret_uuid)
+                    .and(secrets::is_deleted.eq(false))
+                    .and(secrets::application_id.eq(user_from.application.unwrap())),
+            )
+            .into_boxed();
+        let query = match is_admin {
+            true => query,
+            false => query.filter(
+                secrets::created_by_id.eq(user_from.id).or(diesel::dsl::exists(
+                    x_secret_cluster::table
+                        .inner_join(
+                            clusters::table.on(clusters::id
+                                .eq(x_secret_cluster::cluster_id)
+                                .and(clusters::is_deleted.eq(false))),
+                        )
+                        .inner_join(
+                            x_user_cluster::table.on(x_user_cluster::cluster_id
+                                .eq(clusters::id)
+                                .and(x_user_cluster::is_deleted.eq(false))),
+                        )
+                        .inner_join(
+                            users::table.on(users::id
+                                .eq(x_user_cluster::user_id)
+                                .and(users::is_deleted.eq(false))),
+                        )
+                        .filter(
+                            x_secret_cluster::secret_id
+                                .eq(secrets::id)
+                                .and(x_secret_cluster::is_deleted.eq(false))
+                                .and(users::id.eq(user_from.id)),
+                        ),
+                )),
+            ),
+        };
+        query
+            .select(Secret::as_select())
+            .first(&mut conn)
+            .optional()
+            .ok()
+            .flatten()
+    }
+
+    fn get_version(&self, secret: &Secret, version: i32) -> Option<SecretVersion> {
+        let mut conn = self.pool.get().expect("Fail to get a db connexion");
+        SecretVersion::belonging_to(secret)
+            .filter(
+                secret_versions::version
+                    .eq(version)
+                    .and(secret_versions::is_deleted.eq(false)),
+            )
+            .select(SecretVersion::as_select())
+            .first(&mut conn)
+            .optional()
+            .ok()
+            .flatten()
+    }
+
+    fn get_versions(&self, secret: &Secret) -> Vec<SecretVersion> {
+        let mut conn = self.pool.get().expect("Fail to get a db connexion");
+        SecretVersion::belonging_to(secret)
+            .filter(secret_versions::is_deleted.eq(false))
+            .order(secret_versions::version.desc())
+            .select(SecretVersion::as_select())
+            .load(&mut conn)
+            .unwrap_or_default()
+    }
+
+    /// soft delete the secret and all its versions
+    ///
+    /// # Returns
+    ///
+    /// The deleted versions, their fragments have to be removed from the nodes.
+    fn delete_secret(
+        &self,
+        secret: &Secret,
+        user_from: &User,
+    ) -> Result<Vec<SecretVersion>, diesel::result::Error> {
+        let mut conn = self.pool.get().expect("Fail to get a db connexion");
+        conn.transaction(|conn| {
+            diesel::update(secrets::table.find(secret.id).filter(secrets::is_deleted.eq(false)))
+                .set((
+                    secrets::is_deleted.eq(true),
+                    secrets::deleted_at.eq(Some(Utc::now())),
+                    secrets::deleted_by_id.eq(user_from.id),
+                ))
+                .execute(conn)?;
+            diesel::update(
+                secret_versions::table.filter(
+                    secret_versions::secret_id
+                        .eq(secret.id)
+                        .and(secret_versions::is_deleted.eq(false)),
+                ),
+            )
+            .set((
+                secret_versions::is_deleted.eq(true),
+                secret_versions::deleted_at.eq(Some(Utc::now())),
+                secret_versions::deleted_by_id.eq(user_from.id),
+            ))
+            .returning(SecretVersion::as_returning())
+            .get_results(conn)
+        })
+    }
+
+    fn add_to_user_cluster(&self, cluster_uuid: &Uuid, secret: &Secret, user_from: &User) -> bool {
+        let cluster_repository = ClusterRepository::new(&self.pool);
+        match cluster_repository.get_user_cluster_by_id(cluster_uuid, user_from) {
+            None => false,
+            Some(cluster) => {
+                let insertable = XSecretClusterInsertable::new(cluster.id, secret.id, user_from.id);
+                cluster_repository.add_secret_to_cluster(insertable);
+                true
+            }
+        }
+    }
+}
@@ -33,8 +33,9 @@ impl SentinelRepository {
             .expect("failed to insert application")
     }
 
+    /// the admins get any sentinel of their application, the other users the sentinels
+    /// they created or that are part of their clusters
     pub fn get_sentinel_by_id(&self, sentinel_uuid: &Uuid, user_from: &User) -> Option<Sentinel> {
-        let mut conn = self.pool.get().expect("Fail to get a db connexion");
         match user_from.clone().roles.into_iter().find(|role| {
             if role.is_some() {
                 let role = role.clone().unwrap();
@@ -42,73 +43,112 @@ impl SentinelRepository {
             }
             false
         }) {
-                None => {
-                    match sentinels::table
-                    .left_join(
-                        x_sentinel_cluster::table.on(x_sentinel_cluster::sentinel_id
-                            .eq(sentinels::id)
-                            .and(x_sentinel_cluster::is_deleted.eq(false))),
-                    )
-                    .left_join(
-                        clusters::table.on(clusters::id
-                            .eq(x_sentinel_cluster::cluster_id)
-                            .and(clusters::is_deleted.eq(false))),
-                    )
-                    .left_join(
-                        x_user_cluster::table.on(x_user_cluster::cluster_id
-                            .eq(clusters::id)
-                            .and(x_user_cluster::is_deleted.eq(false))),
-                    )
-                    .left_join(
-                        users::table.on(users::id
-                            .eq(x_user_cluster::user_id)
-                            .and(users::is_deleted.eq(false))),
-                    )
-                    .filter(
-                        sentinels::id
-                            .eq(sentinel_uuid)
-                            .and(sentinels::is_deleted.eq(false))
-                            .and(sentinels::application_id.eq(&user_from.application.unwrap())),
-                    )
-                    .filter(
-                        users::id
-                            .eq(user_from.id)
-                            .or(sentinels::created_by_id.eq(user_from.id)),
-                    )
-                    .select(sentinels::all_columns)
-                    .first::<Sentinel>(&mut conn)
-                    .optional()
-                {
-                    Err(_) => None,
-                    Ok(res) => {
-        
-                        res
-                    },
-                }
-                },
-                Some(user) => {
-                    match sentinels::table
-                    .filter(
-                        sentinels::id
-                            .eq(sentinel_uuid)
-                            .and(sentinels::is_deleted.eq(false))
-                            .and(sentinels::application_id.eq(&user_from.application.unwrap())),
-                    )
-                    .select(sentinels::all_columns)
-                    .first::<Sentinel>(&mut conn)
-                    .optional()
-                {
-                    Err(_) => None,
-                    Ok(res) => {
-        
-                        res
-                    },
-                }
-                }
+            None => self.get_member_sentinel_by_id(sentinel_uuid, user_from),
+            Some(_) => {
+                self.get_application_sentinel_by_id(sentinel_uuid, user_from.application.unwrap())
+            }
         }
+    }
+
+    /// a sentinel the user created or that is part of their clusters, whatever their roles
+    pub fn get_member_sentinel_by_id(
+        &self,
+        sentinel_uuid: &Uuid,
+        user_from: &User,
+    ) -> Option<Sentinel> {
+        let mut conn = self.pool.get().expect("Fail to get a db connexion");
+        sentinels::table
+            .left_join(
+                x_sentinel_cluster::table.on(x_sentinel_cluster::sentinel_id
+                    .eq(sentinels::id)
+                    .and(x_sentinel_cluster::is_deleted.eq(false))),
+            )
+            .left_join(
+                clusters::table.on(clusters::id
+                    .eq(x_sentinel_cluster::cluster_id)
+                    .and(clusters::is_deleted.eq(false))),
+            )
+            .left_join(
+                x_user_cluster::table.on(x_user_cluster::cluster_id
+                    .eq(clusters::id)
+                    .and(x_user_cluster::is_deleted.eq(false))),
+            )
+            .left_join(
+                users::table.on(users::id
+                    .eq(x_user_cluster::user_id)
+                    .and(users::is_deleted.eq(false))),
+            )
+            .filter(
+                sentinels::id
+                    .eq(sentinel_uuid)
+                    .and(sentinels::is_deleted.eq(false))
+                    .and(sentinels::application_id.eq(&user_from.application.unwrap())),
+            )
+            .filter(
+                users::id
+                    .eq(user_from.id)
+                    .or(sentinels::created_by_id.eq(user_from.id)),
+            )
+            .select(sentinels::all_columns)
+            .first::<Sentinel>(&mut conn)
+            .optional()
+            .ok()
+            .flatten()
+    }
+
+    /// any sentinel of the application, without membership check
+    pub fn get_application_sentinel_by_id(
+        &self,
+        sentinel_uuid: &Uuid,
+        app_id: i32,
+    ) -> Option<Sentinel> {
+        let mut conn = self.pool.get().expect("Fail to get a db connexion");
+        sentinels::table
+            .filter(
+                sentinels::id
+                    .eq(sentinel_uuid)
+                    .and(sentinels::is_deleted.eq(false))
+                    .and(sentinels::application_id.eq(app_id)),
+            )
+            .select(sentinels::all_columns)
+            .first::<Sentinel>(&mut conn)
+            .optional()
+            .ok()
+            .flatten()
+    }
 
-        
-        
+    /// get a sentinel by id, deleted or not, without any membership check
+    pub fn find_by_id(&self, sentinel_uuid: &Uuid) -> Option<Sentinel> {
+        let mut conn = self.pool.get().expect("Fail to get a db connexion");
+        sentinels::table
+            .filter(sentinels::id.eq(sentinel_uuid))
+            .select(sentinels::all_columns)
+            .first::<Sentinel>(&mut conn)
+            .optional()
+            .ok()
+            .flatten()
+    }
+
+    /// insert a sentinel with its original id, or revive it if it was deleted
+    pub fn restore_sentinel(&self, sentinel: Sentinel) -> Sentinel {
+        let mut conn = self.pool.get().expect("Fail to get a db connexion");
+        diesel::insert_into(sentinels::table)
+            .values(&sentinel)
+            .on_conflict(sentinels::id)
+            .do_update()
+            .set((
+                iv.eq(&sentinel.iv),
+                sum.eq(&sentinel.sum),
+                key_size.eq(sentinel.key_size),
+                purpose.eq(&sentinel.purpose),
+                is_deleted.eq(false),
+                updated_at.eq(Some(Utc::now())),
+                deleted_at.eq(None::<chrono::DateTime<Utc>>),
+                deleted_by_id.eq(None::<Uuid>),
+            ))
+            .returning(Sentinel::as_returning())
+            .get_result(&mut conn)
+            .expect("failed to restore sentinel")
     }
 
     pub fn count_sentinels(&self) -> Option<i64> {
@@ -170,4 +210,61 @@ impl SentinelRepository {
         ))
         .execute(&mut conn)
     }
+
+    /// set the number of approvals needed to release the key
+    pub fn set_required_approvals(
+        &self,
+        sentinel_uuid: &Uuid,
+        approvals: i32,
+        user_from: &User,
+    ) -> Result<Sentinel, diesel::result::Error> {
+        let mut conn = self.pool.get().expect("Fail to get a db connexion");
+        diesel::update(
+            sentinels::table.filter(sentinels::id.eq(sentinel_uuid).and(is_deleted.eq(false))),
+        )
+        .set((
+            required_approvals.eq(approvals),
+            updated_at.eq(Some(Utc::now())),
+            updated_by_id.eq(user_from.id),
+        ))
+        .returning(Sentinel::as_returning())
+        .get_result(&mut conn)
+    }
+
+    /// flag the sentinel as read through break-glass
+    pub fn mark_break_glass(
+        &self,
+        sentinel_uuid: &Uuid,
+        user_from: &User,
+    ) -> Result<usize, diesel::result::Error> {
+        let mut conn = self.pool.get().expect("Fail to get a db connexion");
+        diesel::update(sentinels::table.find(sentinel_uuid))
+            .set((
+                break_glass_at.eq(Some(Utc::now())),
+                break_glass_by_id.eq(Some(user_from.id)),
+            ))
+            .execute(&mut conn)
+    }
+
+    /// clear the break-glass flag of a sentinel of the application, `0` when it was not flagged
+    pub fn acknowledge_break_glass(
+        &self,
+        sentinel_uuid: &Uuid,
+        user_from: &User,
+    ) -> Result<usize, diesel::result::Error> {
+        let mut conn = self.pool.get().expect("Fail to get a db connexion");
+        diesel::update(
+            sentinels::table.filter(
+                sentinels::id
+                    .eq(sentinel_uuid)
+                    .and(application_id.eq(user_from.application.unwrap()))
+                    .and(break_glass_at.is_not_null()),
+            ),
+        )
+        .set((
+            break_glass_at.eq(None::<chrono::DateTime<Utc>>),
+            break_glass_by_id.eq(None::<Uuid>),
+        ))
+        .execute(&mut conn)
+    }
 }
@@ -0,0 +1,171 @@
+use crate::db::connect::DbPool;
+use crate::dto::service_account::service_account_insertable::ServiceAccountInsertable;
+use crate::dto::user::user_insertable::UserInsertable;
+use crate::models::service_account::ServiceAccount;
+use crate::models::user::User;
+use crate::schema::{service_accounts, users, x_user_cluster};
+use chrono::Utc;
+use diesel::prelude::*;
+use uuid::Uuid;
+
+pub struct ServiceAccountRepository {
+    pool: DbPool,
+}
+
+impl ServiceAccountRepository {
+    pub fn new(pool: &DbPool) -> Self {
+        Self { pool: pool.clone() }
+    }
+
+    /// insert the user of the account and its credentials together
+    pub fn create_service_account(
+        &self,
+        user_insertable: UserInsertable,
+        mut insertable: ServiceAccountInsertable,
+    ) -> Result<ServiceAccount, diesel::result::Error> {
+        let mut conn = self.pool.get().expect("Fail to get a db connexion");
+        conn.transaction(|conn| {
+            let user: User = diesel::insert_into(users::table)
+                .values(&user_insertable)
+                .returning(User::as_returning())
+                .get_result(conn)?;
+            insertable.user_id = user.id;
+            diesel::insert_into(service_accounts::table)
+                .values(&insertable)
+                .returning(ServiceAccount::as_returning())
+                .get_result(conn)
+        })
+    }
+
+    pub fn get_by_client_id(&self, client_id: &str) -> Option<ServiceAccount> {
+        let mut conn = self.pool.get().expect("Fail to get a db connexion");
+        service_accounts::table
+            .filter(service_accounts::client_id.eq(client_id))
+            .filter(service_accounts::is_deleted.eq(false))
+            .select(ServiceAccount::as_select())
+            .first(&mut conn)
+            .optional()
+            .ok()
+            .flatten()
+    }
+
+    pub fn get_by_user_id(&self, user_uuid: &Uuid) -> Option<ServiceAccount> {
+        let mut conn = self.pool.get().expect("Fail to get a db connexion");
+        service_accounts::table
+            .filter(service_accounts::user_id.eq(user_uuid))
+            .filter(service_accounts::is_deleted.eq(false))
+            .select(ServiceAccount::as_select())
+            .first(&mut conn)
+            .optional()
+            .ok()
+            .flatten()
+    }
+
+    pub fn get_by_id_and_app(&self, service_account_uuid: &Uuid, app_id: i32) -> Option<ServiceAccount> {
+        let mut conn = self.pool.get().expect("Fail to get a db connexion");
+        service_accounts::table
+            .find(service_account_uuid)
+            .filter(service_accounts::application_id.eq(app_id))
+            .filter(service_accounts::is_deleted.eq(false))
+            .select(ServiceAccount::as_select())
+            .first(&mut conn)
+            .optional()
+            .ok()
+            .flatten()
+    }
+
+    pub fn get_by_application(&self, app_id: i32) -> Vec<ServiceAccount> {
+        let mut conn = self.pool.get().expect("Fail to get a db connexion");
+        service_accounts::table
+            .filter(service_accounts::application_id.eq(app_id))
+            .filter(service_accounts::is_deleted.eq(false))
+            .order(service_accounts::created_at.desc())
+            .select(ServiceAccount::as_select())
+            .load(&mut conn)
+            .unwrap_or_default()
+    }
+
+    pub fn update_credentials(
+        &self,
+        service_account_uuid: &Uuid,
+        client_secret_hash: Option<String>,
+        public_key: Option<String>,
+        user_from: &User,
+    ) -> Result<ServiceAccount, diesel::result::Error> {
+        let mut conn = self.pool.get().expect("Fail to get a db connexion");
+        diesel::update(
+            service_accounts::table
+                .find(service_account_uuid)
+                .filter(service_accounts::is_deleted.eq(false)),
+        )
+        .set((
+            service_accounts::client_secret_hash.eq(client_secret_hash),
+            service_accounts::public_key.eq(public_key),
+            service_accounts::updated_at.eq(Some(Utc::now())),
+            service_accounts::updated_by_id.eq(user_from.id),
+        ))
+        .returning(ServiceAccount::as_returning())
+        .get_result(&mut conn)
+    }
+
+    pub fn update_signing_key(
+        &self,
+        service_account_uuid: &Uuid,
+        signing_key: Option<String>,
+        user_from: &User,
+    ) -> Result<ServiceAccount, diesel::result::Error> {
+        let mut conn = self.pool.get().expect("Fail to get a db connexion");
+        diesel::update(
+            service_accounts::table
+                .find(service_account_uuid)
+                .filter(service_accounts::is_deleted.eq(false)),
+        )
+        .set((
+            service_accounts::signing_key.eq(signing_key),
+            service_accounts::updated_at.eq(Some(Utc::now())),
+            service_accounts::updated_by_id.eq(user_from.id),
+        ))
+        .returning(ServiceAccount::as_returning())
+        .get_result(&mut conn)
+    }
+
+    pub fn touch(&self, service_account_uuid: &Uuid) {
+        let mut conn = self.pool.get().expect("Fail to get a db connexion");
+        let _ = diesel::update(service_accounts::table.find(service_account_uuid))
+            .set(service_accounts::last_used_at.eq(Some(Utc::now())))
+            .execute(&mut conn);
+    }
+
+    /// delete the account, its user and the cluster memberships of the user
+    pub fn delete(&self, service_account: &ServiceAccount, user_from: &User) -> Result<(), diesel::result::Error> {
+        let mut conn = self.pool.get().expect("Fail to get a db connexion");
+        conn.transaction(|conn| {
+            diesel::update(service_accounts::table.find(service_account.id))
+                .set((
+                    service_accounts::is_deleted.eq(true),
+                    service_accounts::deleted_at.eq(Some(Utc::now())),
+                    service_accounts::deleted_by_id.eq(user_from.id),
+                ))
+                .execute(conn)?;
+            diesel::update(users::table.find(service_account.user_id))
+                .set((
+                    users::is_deleted.eq(true),
+                    users::deleted_at.eq(Some(Utc::now())),
+                    users::deleted_by_id.eq(user_from.id),
+                ))
+                .execute(conn)?;
+            diesel::update(
+                x_user_cluster::table
+                    .filter(x_user_cluster::user_id.eq(service_account.user_id))
+                    .filter(x_user_cluster::is_deleted.eq(false)),
+            )
+            .set((
+                x_user_cluster::is_deleted.eq(true),
+                x_user_cluster::deleted_at.eq(Some(Utc::now())),
+                x_user_cluster::deleted_by_id.eq(user_from.id),
+            ))
+            .execute(conn)?;
+            Ok(())
+        })
+    }
+}
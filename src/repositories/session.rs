@@ -0,0 +1,99 @@
+use crate::db::connect::DbPool;
+use crate::dto::session::session_insertable::SessionInsertable;
+use crate::models::{connexion::Connexion, session::Session};
+use crate::schema::{connexions, sessions};
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use uuid::Uuid;
+
+pub struct SessionRepository {
+    pool: DbPool,
+}
+
+impl SessionRepository {
+    pub fn new(pool: &DbPool) -> Self {
+        Self { pool: pool.clone() }
+    }
+
+    pub fn create_session(&self, insertable: SessionInsertable) -> Result<Session, diesel::result::Error> {
+        let mut conn = self.pool.get().expect("Fail to get a db connexion");
+        diesel::insert_into(sessions::table)
+            .values(&insertable)
+            .returning(Session::as_returning())
+            .get_result(&mut conn)
+    }
+
+    /// a session neither revoked nor expired
+    pub fn get_active(&self, session_id: &Uuid) -> Option<Session> {
+        let mut conn = self.pool.get().expect("Fail to get a db connexion");
+        sessions::table
+            .filter(sessions::id.eq(session_id))
+            .filter(sessions::is_deleted.eq(false))
+            .filter(sessions::revoked_at.is_null())
+            .filter(sessions::expires_at.gt(Utc::now()))
+            .select(Session::as_select())
+            .first(&mut conn)
+            .optional()
+            .ok()
+            .flatten()
+    }
+
+    /// the active sessions of a user with the connexion opening them, the latest used first
+    pub fn get_active_by_user(&self, session_user_id: &Uuid) -> Vec<(Session, Option<Connexion>)> {
+        let mut conn = self.pool.get().expect("Fail to get a db connexion");
+        sessions::table
+            .left_join(connexions::table)
+            .filter(sessions::user_id.eq(session_user_id))
+            .filter(sessions::is_deleted.eq(false))
+            .filter(sessions::revoked_at.is_null())
+            .filter(sessions::expires_at.gt(Utc::now()))
+            .order(sessions::last_used_at.desc())
+            .select((Session::as_select(), Option::<Connexion>::as_select()))
+            .load(&mut conn)
+            .unwrap_or_default()
+    }
+
+    /// replace the refresh token of the session, `None` when `old_jti` is no longer
+    /// the latest refresh token, another refresh already used it
+    pub fn rotate(
+        &self,
+        session_id: &Uuid,
+        old_jti: &Uuid,
+        new_jti: &Uuid,
+        new_expires_at: DateTime<Utc>,
+    ) -> Option<Session> {
+        let mut conn = self.pool.get().expect("Fail to get a db connexion");
+        diesel::update(
+            sessions::table
+                .filter(sessions::id.eq(session_id))
+                .filter(sessions::refresh_jti.eq(old_jti))
+                .filter(sessions::revoked_at.is_null()),
+        )
+        .set((
+            sessions::refresh_jti.eq(new_jti),
+            sessions::expires_at.eq(new_expires_at),
+            sessions::last_used_at.eq(Utc::now()),
+            sessions::updated_at.eq(Some(Utc::now())),
+        ))
+        .returning(Session::as_returning())
+        .get_result(&mut conn)
+        .optional()
+        .ok()
+        .flatten()
+    }
+
+    pub fn revoke(&self, session_id: &Uuid, revoked_by: Option<Uuid>) -> Result<usize, diesel::result::Error> {
+        let mut conn = self.pool.get().expect("Fail to get a db connexion");
+        diesel::update(
+            sessions::table
+                .filter(sessions::id.eq(session_id))
+                .filter(sessions::revoked_at.is_null()),
+        )
+        .set((
+            sessions::revoked_at.eq(Some(Utc::now())),
+            sessions::updated_at.eq(Some(Utc::now())),
+            sessions::updated_by_id.eq(revoked_by),
+        ))
+        .execute(&mut conn)
+    }
+}
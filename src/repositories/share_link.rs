@@ -0,0 +1,76 @@
+use crate::db::connect::DbPool;
+use crate::dto::share_link::share_link_insertable::ShareLinkInsertable;
+use crate::models::share_link::ShareLink;
+use crate::models::user::User;
+use crate::schema::share_links;
+use chrono::Utc;
+use diesel::prelude::*;
+use uuid::Uuid;
+
+pub struct ShareLinkRepository {
+    pool: DbPool,
+}
+
+impl ShareLinkRepository {
+    pub fn new(pool: &DbPool) -> Self {
+        Self { pool: pool.clone() }
+    }
+
+    pub fn create_share_link(&self, insertable: ShareLinkInsertable) -> Result<ShareLink, diesel::result::Error> {
+        let mut conn = self.pool.get().expect("Fail to get a db connexion");
+        diesel::insert_into(share_links::table)
+            .values(&insertable)
+            .returning(ShareLink::as_returning())
+            .get_result(&mut conn)
+    }
+
+    pub fn get_by_id(&self, share_link_uuid: &Uuid, sentinel_uuid: &Uuid) -> Option<ShareLink> {
+        let mut conn = self.pool.get().expect("Fail to get a db connexion");
+        share_links::table
+            .find(share_link_uuid)
+            .filter(share_links::sentinel_id.eq(sentinel_uuid))
+            .filter(share_links::is_deleted.eq(false))
+            .select(ShareLink::as_select())
+            .first(&mut conn)
+            .optional()
+            .ok()
+            .flatten()
+    }
+
+    /// count one use of a live link, the check and the increment are a single statement
+    /// so two concurrent redemptions can not both take the last use
+    pub fn redeem(&self, token_hash: &str) -> Option<ShareLink> {
+        let mut conn = self.pool.get().expect("Fail to get a db connexion");
+        diesel::update(
+            share_links::table
+                .filter(share_links::token_hash.eq(token_hash))
+                .filter(share_links::is_deleted.eq(false))
+                .filter(share_links::expires_at.gt(Utc::now()))
+                .filter(share_links::use_count.lt(share_links::max_uses)),
+        )
+        .set((
+            share_links::use_count.eq(share_links::use_count + 1),
+            share_links::updated_at.eq(Some(Utc::now())),
+        ))
+        .returning(ShareLink::as_returning())
+        .get_result(&mut conn)
+        .optional()
+        .ok()
+        .flatten()
+    }
+
+    pub fn revoke(&self, share_link_uuid: &Uuid, user_from: &User) -> Result<usize, diesel::result::Error> {
+        let mut conn = self.pool.get().expect("Fail to get a db connexion");
+        diesel::update(
+            share_links::table
+                .find(share_link_uuid)
+                .filter(share_links::is_deleted.eq(false)),
+        )
+        .set((
+            share_links::is_deleted.eq(true),
+            share_links::deleted_at.eq(Some(Utc::now())),
+            share_links::deleted_by_id.eq(user_from.id),
+        ))
+        .execute(&mut conn)
+    }
+}
@@ -0,0 +1,52 @@
+use crate::db::connect::DbPool;
+use crate::dto::signing_key::signing_key_insertable::SigningKeyInsertable;
+use crate::models::signing_key::SigningKey;
+use crate::schema::signing_keys;
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+
+pub struct SigningKeyRepository {
+    pool: DbPool,
+}
+
+impl SigningKeyRepository {
+    pub fn new(pool: &DbPool) -> Self {
+        Self { pool: pool.clone() }
+    }
+
+    pub fn create_signing_key(&self, insertable: SigningKeyInsertable) -> Result<SigningKey, diesel::result::Error> {
+        let mut conn = self.pool.get().expect("Fail to get a db connexion");
+        diesel::insert_into(signing_keys::table)
+            .values(&insertable)
+            .returning(SigningKey::as_returning())
+            .get_result(&mut conn)
+    }
+
+    /// the keys still trusted, the latest activation first
+    pub fn get_trusted(&self) -> Vec<SigningKey> {
+        let mut conn = self.pool.get().expect("Fail to get a db connexion");
+        signing_keys::table
+            .filter(signing_keys::is_deleted.eq(false))
+            .filter(signing_keys::retired_at.is_null())
+            .order(signing_keys::activates_at.desc())
+            .select(SigningKey::as_select())
+            .load(&mut conn)
+            .unwrap_or_default()
+    }
+
+    /// retire the keys activated before `activated_before`
+    pub fn retire_before(&self, activated_before: DateTime<Utc>) -> Result<usize, diesel::result::Error> {
+        let mut conn = self.pool.get().expect("Fail to get a db connexion");
+        diesel::update(
+            signing_keys::table
+                .filter(signing_keys::is_deleted.eq(false))
+                .filter(signing_keys::retired_at.is_null())
+                .filter(signing_keys::activates_at.lt(activated_before)),
+        )
+        .set((
+            signing_keys::retired_at.eq(Some(Utc::now())),
+            signing_keys::updated_at.eq(Some(Utc::now())),
+        ))
+        .execute(&mut conn)
+    }
+}
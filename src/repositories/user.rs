@@ -1,6 +1,6 @@
 use crate::schema::users::dsl::*;
 use crate::utils::password::PasswordUtils;
-use chrono::{Duration, Utc};
+use chrono::{DateTime, Duration, Utc};
 use diesel::prelude::*;
 use diesel::{ExpressionMethods, RunQueryDsl, SelectableHelper};
 use uuid::Uuid;
@@ -152,10 +152,26 @@ impl UserRepository {
             .execute(&mut conn)
     }
 
+    /// the reset code is used, it can not be used again
+    pub fn end_password_reset(&self, user_id: &Uuid) -> Result<usize, diesel::result::Error> {
+        let mut conn = self.pool.get().expect("Fail to get a db connexion");
+        diesel::update(users::table.find(user_id).filter(is_deleted.eq(false)))
+            .set((
+                validation_tries.eq(0),
+                forget_code_delay.eq(None::<DateTime<Utc>>),
+                updated_at.eq(Some(Utc::now())),
+            ))
+            .execute(&mut conn)
+    }
+
     pub fn validate_account(&self, user_id: &Uuid) -> Result<User, diesel::result::Error> {
         let mut conn = self.pool.get().expect("Fail to get a db connexion");
         let _ = diesel::update(users::table.find(user_id).filter(is_deleted.eq(false)))
-            .set((is_validated.eq(true), updated_at.eq(Some(Utc::now()))))
+            .set((
+                is_validated.eq(true),
+                validation_tries.eq(0),
+                updated_at.eq(Some(Utc::now())),
+            ))
             .execute(&mut conn);
         let user = self.get_by_id(user_id).unwrap();
         Ok(user)
@@ -205,4 +221,51 @@ impl UserRepository {
             .execute(&mut conn);
         self.get_by_id(user_id).unwrap()
     }
+
+    /// the admins of an application, used to send the security alerts
+    pub fn get_admins_by_app(&self, app_id: i32) -> Vec<User> {
+        let mut conn = self.pool.get().expect("Fail to get a db connexion");
+        users
+            .filter(
+                application
+                    .eq(app_id)
+                    .and(is_deleted.eq(false))
+                    .and(roles.contains(vec![Some(String::from("ROLE_ADMIN"))])),
+            )
+            .load::<User>(&mut conn)
+            .unwrap_or_default()
+    }
+
+    pub fn update_roles(
+        &self,
+        user_id: &Uuid,
+        new_roles: Vec<Option<String>>,
+        user_from_id: &Uuid,
+    ) -> Result<User, diesel::result::Error> {
+        let mut conn = self.pool.get().expect("Fail to get a db connexion");
+        diesel::update(users::table.find(user_id).filter(is_deleted.eq(false)))
+            .set((
+                roles.eq(new_roles),
+                updated_at.eq(Some(Utc::now())),
+                updated_by_id.eq(Some(user_from_id)),
+            ))
+            .get_result(&mut conn)
+    }
+
+    pub fn update_restricted_ip(
+        &self,
+        user_id: &Uuid,
+        new_restricted_ip: Vec<String>,
+        user_from_id: &Uuid,
+    ) -> Result<User, diesel::result::Error> {
+        let mut conn = self.pool.get().expect("Fail to get a db connexion");
+        let new_restricted_ip: Vec<Option<String>> = new_restricted_ip.into_iter().map(Some).collect();
+        diesel::update(users::table.find(user_id).filter(is_deleted.eq(false)))
+            .set((
+                restricted_ip.eq(new_restricted_ip),
+                updated_at.eq(Some(Utc::now())),
+                updated_by_id.eq(Some(user_from_id)),
+            ))
+            .get_result(&mut conn)
+    }
 }
@@ -0,0 +1,77 @@
+use crate::db::connect::DbPool;
+use crate::dto::webauthn::webauthn_credential_insertable::WebauthnCredentialInsertable;
+use crate::models::{user::User, webauthn_credential::WebauthnCredential};
+use crate::schema::webauthn_credentials;
+use chrono::Utc;
+use diesel::prelude::*;
+use uuid::Uuid;
+
+pub struct WebauthnCredentialRepository {
+    pool: DbPool,
+}
+
+impl WebauthnCredentialRepository {
+    pub fn new(pool: &DbPool) -> Self {
+        Self { pool: pool.clone() }
+    }
+
+    pub fn create_credential(
+        &self,
+        insertable: WebauthnCredentialInsertable,
+    ) -> Result<WebauthnCredential, diesel::result::Error> {
+        let mut conn = self.pool.get().expect("Fail to get a db connexion");
+        diesel::insert_into(webauthn_credentials::table)
+            .values(&insertable)
+            .returning(WebauthnCredential::as_returning())
+            .get_result(&mut conn)
+    }
+
+    pub fn get_by_credential_id(&self, test_credential_id: &str) -> Option<WebauthnCredential> {
+        let mut conn = self.pool.get().expect("Fail to get a db connexion");
+        webauthn_credentials::table
+            .filter(webauthn_credentials::credential_id.eq(test_credential_id))
+            .filter(webauthn_credentials::is_deleted.eq(false))
+            .select(WebauthnCredential::as_select())
+            .first(&mut conn)
+            .optional()
+            .ok()
+            .flatten()
+    }
+
+    pub fn get_by_user(&self, test_user_id: &Uuid) -> Vec<WebauthnCredential> {
+        let mut conn = self.pool.get().expect("Fail to get a db connexion");
+        webauthn_credentials::table
+            .filter(webauthn_credentials::user_id.eq(test_user_id))
+            .filter(webauthn_credentials::is_deleted.eq(false))
+            .order(webauthn_credentials::created_at.asc())
+            .select(WebauthnCredential::as_select())
+            .load(&mut conn)
+            .unwrap_or_default()
+    }
+
+    /// record a use of the credential with the new signature counter of its authenticator
+    pub fn update_usage(
+        &self,
+        credential_uuid: &Uuid,
+        new_sign_count: i64,
+    ) -> Result<usize, diesel::result::Error> {
+        let mut conn = self.pool.get().expect("Fail to get a db connexion");
+        diesel::update(webauthn_credentials::table.find(credential_uuid))
+            .set((
+                webauthn_credentials::sign_count.eq(new_sign_count),
+                webauthn_credentials::last_used_at.eq(Some(Utc::now())),
+            ))
+            .execute(&mut conn)
+    }
+
+    pub fn delete(&self, credential: &WebauthnCredential, user_from: &User) -> Result<usize, diesel::result::Error> {
+        let mut conn = self.pool.get().expect("Fail to get a db connexion");
+        diesel::update(webauthn_credentials::table.find(credential.id))
+            .set((
+                webauthn_credentials::is_deleted.eq(true),
+                webauthn_credentials::deleted_at.eq(Some(Utc::now())),
+                webauthn_credentials::deleted_by_id.eq(user_from.id),
+            ))
+            .execute(&mut conn)
+    }
+}
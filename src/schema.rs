@@ -1,5 +1,34 @@
 // @generated automatically by Diesel CLI.
 
+diesel::table! {
+    access_request_approvals (id) {
+        id -> Int4,
+        access_request_id -> Uuid,
+        approver_id -> Uuid,
+        created_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    access_requests (id) {
+        id -> Uuid,
+        sentinel_id -> Nullable<Uuid>,
+        anonymous_sentinel_id -> Nullable<Uuid>,
+        requester_id -> Uuid,
+        required_approvals -> Int4,
+        #[max_length = 50]
+        status -> Varchar,
+        expires_at -> Timestamptz,
+        is_deleted -> Bool,
+        created_at -> Timestamptz,
+        updated_at -> Nullable<Timestamptz>,
+        deleted_at -> Nullable<Timestamptz>,
+        created_by_id -> Nullable<Uuid>,
+        updated_by_id -> Nullable<Uuid>,
+        deleted_by_id -> Nullable<Uuid>,
+    }
+}
+
 diesel::table! {
     anonymous_sentinels (id) {
         id -> Uuid,
@@ -16,6 +45,9 @@ diesel::table! {
         updated_by_id -> Nullable<Uuid>,
         deleted_by_id -> Nullable<Uuid>,
         key_size -> Int4,
+        required_approvals -> Int4,
+        break_glass_at -> Nullable<Timestamptz>,
+        break_glass_by_id -> Nullable<Uuid>,
     }
 }
 
@@ -34,6 +66,26 @@ diesel::table! {
         created_by_id -> Nullable<Uuid>,
         updated_by_id -> Nullable<Uuid>,
         deleted_by_id -> Nullable<Uuid>,
+        allowed_ips -> Array<Text>,
+    }
+}
+
+diesel::table! {
+    audit_logs (id) {
+        id -> Uuid,
+        application_id -> Nullable<Int4>,
+        user_id -> Nullable<Uuid>,
+        #[max_length = 100]
+        action -> Varchar,
+        #[max_length = 20]
+        severity -> Varchar,
+        #[max_length = 50]
+        target_type -> Nullable<Varchar>,
+        target_id -> Nullable<Uuid>,
+        justification -> Nullable<Text>,
+        #[max_length = 100]
+        ip -> Nullable<Varchar>,
+        created_at -> Timestamptz,
     }
 }
 
@@ -51,6 +103,7 @@ diesel::table! {
         created_by_id -> Nullable<Uuid>,
         updated_by_id -> Nullable<Uuid>,
         deleted_by_id -> Nullable<Uuid>,
+        required_approvals -> Int4,
     }
 }
 
@@ -71,10 +124,154 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    import_keys (id) {
+        id -> Uuid,
+        application_id -> Int4,
+        #[max_length = 50]
+        algorithm -> Varchar,
+        #[max_length = 255]
+        iv -> Varchar,
+        sum -> Text,
+        public_key -> Text,
+        expires_at -> Timestamptz,
+        is_deleted -> Bool,
+        created_at -> Timestamptz,
+        updated_at -> Nullable<Timestamptz>,
+        deleted_at -> Nullable<Timestamptz>,
+        created_by_id -> Nullable<Uuid>,
+        updated_by_id -> Nullable<Uuid>,
+        deleted_by_id -> Nullable<Uuid>,
+    }
+}
+
+diesel::table! {
+    key_policies (id) {
+        id -> Uuid,
+        sentinel_id -> Nullable<Uuid>,
+        anonymous_sentinel_id -> Nullable<Uuid>,
+        operations -> Array<Nullable<Text>>,
+        allowed_cidrs -> Array<Nullable<Text>>,
+        allowed_days -> Array<Nullable<Int4>>,
+        hour_start -> Nullable<Int4>,
+        hour_end -> Nullable<Int4>,
+        max_reads -> Nullable<Int4>,
+        read_count -> Int4,
+        is_deleted -> Bool,
+        created_at -> Timestamptz,
+        updated_at -> Nullable<Timestamptz>,
+        deleted_at -> Nullable<Timestamptz>,
+        created_by_id -> Nullable<Uuid>,
+        updated_by_id -> Nullable<Uuid>,
+        deleted_by_id -> Nullable<Uuid>,
+    }
+}
+
+diesel::table! {
+    oauth_clients (id) {
+        id -> Uuid,
+        application_id -> Int4,
+        #[max_length = 100]
+        name -> Varchar,
+        #[max_length = 64]
+        client_id -> Varchar,
+        #[max_length = 64]
+        client_secret_hash -> Nullable<Varchar>,
+        redirect_uris -> Array<Nullable<Text>>,
+        is_deleted -> Bool,
+        created_at -> Timestamptz,
+        updated_at -> Nullable<Timestamptz>,
+        deleted_at -> Nullable<Timestamptz>,
+        created_by_id -> Nullable<Uuid>,
+        updated_by_id -> Nullable<Uuid>,
+        deleted_by_id -> Nullable<Uuid>,
+    }
+}
+
+diesel::table! {
+    oauth_codes (id) {
+        id -> Uuid,
+        oauth_client_id -> Uuid,
+        user_id -> Uuid,
+        #[max_length = 64]
+        code_hash -> Varchar,
+        redirect_uri -> Text,
+        #[max_length = 128]
+        code_challenge -> Varchar,
+        scope -> Nullable<Text>,
+        expires_at -> Timestamptz,
+        used_at -> Nullable<Timestamptz>,
+        is_deleted -> Bool,
+        created_at -> Timestamptz,
+        updated_at -> Nullable<Timestamptz>,
+        deleted_at -> Nullable<Timestamptz>,
+        created_by_id -> Nullable<Uuid>,
+        updated_by_id -> Nullable<Uuid>,
+        deleted_by_id -> Nullable<Uuid>,
+        nonce -> Nullable<Text>,
+        auth_time -> Timestamptz,
+        amr -> Array<Nullable<Text>>,
+    }
+}
+
+diesel::table! {
+    recovery_codes (id) {
+        id -> Uuid,
+        user_id -> Uuid,
+        #[max_length = 64]
+        code_hash -> Varchar,
+        used_at -> Nullable<Timestamptz>,
+        is_deleted -> Bool,
+        created_at -> Timestamptz,
+        updated_at -> Nullable<Timestamptz>,
+        deleted_at -> Nullable<Timestamptz>,
+        created_by_id -> Nullable<Uuid>,
+        updated_by_id -> Nullable<Uuid>,
+        deleted_by_id -> Nullable<Uuid>,
+    }
+}
+
 diesel::table! {
     revoked_tokens (id) {
         id -> Int4,
-        token -> Text,
+        #[max_length = 64]
+        token_hash -> Varchar,
+        is_deleted -> Bool,
+        created_at -> Timestamptz,
+        updated_at -> Nullable<Timestamptz>,
+        deleted_at -> Nullable<Timestamptz>,
+        created_by_id -> Nullable<Uuid>,
+        updated_by_id -> Nullable<Uuid>,
+        deleted_by_id -> Nullable<Uuid>,
+        expires_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    secret_versions (id) {
+        id -> Uuid,
+        secret_id -> Uuid,
+        version -> Int4,
+        #[max_length = 255]
+        iv -> Varchar,
+        sum -> Text,
+        is_deleted -> Bool,
+        created_at -> Timestamptz,
+        updated_at -> Nullable<Timestamptz>,
+        deleted_at -> Nullable<Timestamptz>,
+        created_by_id -> Nullable<Uuid>,
+        updated_by_id -> Nullable<Uuid>,
+        deleted_by_id -> Nullable<Uuid>,
+    }
+}
+
+diesel::table! {
+    secrets (id) {
+        id -> Uuid,
+        application_id -> Int4,
+        #[max_length = 255]
+        name -> Varchar,
+        current_version -> Int4,
         is_deleted -> Bool,
         created_at -> Timestamptz,
         updated_at -> Nullable<Timestamptz>,
@@ -100,6 +297,98 @@ diesel::table! {
         updated_by_id -> Nullable<Uuid>,
         deleted_by_id -> Nullable<Uuid>,
         key_size -> Int4,
+        #[max_length = 50]
+        purpose -> Varchar,
+        required_approvals -> Int4,
+        break_glass_at -> Nullable<Timestamptz>,
+        break_glass_by_id -> Nullable<Uuid>,
+    }
+}
+
+diesel::table! {
+    service_accounts (id) {
+        id -> Uuid,
+        user_id -> Uuid,
+        application_id -> Int4,
+        #[max_length = 100]
+        name -> Varchar,
+        #[max_length = 64]
+        client_id -> Varchar,
+        #[max_length = 64]
+        client_secret_hash -> Nullable<Varchar>,
+        public_key -> Nullable<Text>,
+        last_used_at -> Nullable<Timestamptz>,
+        is_deleted -> Bool,
+        created_at -> Timestamptz,
+        updated_at -> Nullable<Timestamptz>,
+        deleted_at -> Nullable<Timestamptz>,
+        created_by_id -> Nullable<Uuid>,
+        updated_by_id -> Nullable<Uuid>,
+        deleted_by_id -> Nullable<Uuid>,
+        signing_key -> Nullable<Text>,
+    }
+}
+
+diesel::table! {
+    sessions (id) {
+        id -> Uuid,
+        user_id -> Uuid,
+        application_id -> Int4,
+        connexion_id -> Nullable<Int4>,
+        refresh_jti -> Uuid,
+        expires_at -> Timestamptz,
+        revoked_at -> Nullable<Timestamptz>,
+        is_deleted -> Bool,
+        created_at -> Timestamptz,
+        updated_at -> Nullable<Timestamptz>,
+        deleted_at -> Nullable<Timestamptz>,
+        created_by_id -> Nullable<Uuid>,
+        updated_by_id -> Nullable<Uuid>,
+        deleted_by_id -> Nullable<Uuid>,
+        last_used_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    share_links (id) {
+        id -> Uuid,
+        sentinel_id -> Uuid,
+        #[max_length = 64]
+        token_hash -> Varchar,
+        max_uses -> Int4,
+        use_count -> Int4,
+        expires_at -> Timestamptz,
+        recipient_public_key -> Nullable<Text>,
+        is_deleted -> Bool,
+        created_at -> Timestamptz,
+        updated_at -> Nullable<Timestamptz>,
+        deleted_at -> Nullable<Timestamptz>,
+        created_by_id -> Nullable<Uuid>,
+        updated_by_id -> Nullable<Uuid>,
+        deleted_by_id -> Nullable<Uuid>,
+    }
+}
+
+diesel::table! {
+    signing_keys (id) {
+        id -> Uuid,
+        #[max_length = 64]
+        kid -> Varchar,
+        #[max_length = 10]
+        algorithm -> Varchar,
+        public_key -> Text,
+        private_key -> Text,
+        #[max_length = 32]
+        iv -> Varchar,
+        activates_at -> Timestamptz,
+        retired_at -> Nullable<Timestamptz>,
+        is_deleted -> Bool,
+        created_at -> Timestamptz,
+        updated_at -> Nullable<Timestamptz>,
+        deleted_at -> Nullable<Timestamptz>,
+        created_by_id -> Nullable<Uuid>,
+        updated_by_id -> Nullable<Uuid>,
+        deleted_by_id -> Nullable<Uuid>,
     }
 }
 
@@ -135,6 +424,27 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    webauthn_credentials (id) {
+        id -> Uuid,
+        user_id -> Uuid,
+        #[max_length = 100]
+        name -> Varchar,
+        credential_id -> Text,
+        public_key -> Text,
+        algorithm -> Int4,
+        sign_count -> Int8,
+        last_used_at -> Nullable<Timestamptz>,
+        is_deleted -> Bool,
+        created_at -> Timestamptz,
+        updated_at -> Nullable<Timestamptz>,
+        deleted_at -> Nullable<Timestamptz>,
+        created_by_id -> Nullable<Uuid>,
+        updated_by_id -> Nullable<Uuid>,
+        deleted_by_id -> Nullable<Uuid>,
+    }
+}
+
 diesel::table! {
     x_anonymous_sentinel_cluster (id) {
         id -> Int4,
@@ -150,6 +460,21 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    x_secret_cluster (id) {
+        id -> Int4,
+        secret_id -> Uuid,
+        cluster_id -> Uuid,
+        is_deleted -> Bool,
+        created_at -> Timestamptz,
+        updated_at -> Nullable<Timestamptz>,
+        deleted_at -> Nullable<Timestamptz>,
+        created_by_id -> Nullable<Uuid>,
+        updated_by_id -> Nullable<Uuid>,
+        deleted_by_id -> Nullable<Uuid>,
+    }
+}
+
 diesel::table! {
     x_sentinel_cluster (id) {
         id -> Int4,
@@ -180,24 +505,64 @@ diesel::table! {
     }
 }
 
+diesel::joinable!(access_request_approvals -> access_requests (access_request_id));
+diesel::joinable!(access_request_approvals -> users (approver_id));
+diesel::joinable!(access_requests -> anonymous_sentinels (anonymous_sentinel_id));
+diesel::joinable!(access_requests -> sentinels (sentinel_id));
 diesel::joinable!(anonymous_sentinels -> applications (application_id));
+diesel::joinable!(audit_logs -> applications (application_id));
+diesel::joinable!(audit_logs -> users (user_id));
 diesel::joinable!(clusters -> applications (application_id));
+diesel::joinable!(import_keys -> applications (application_id));
+diesel::joinable!(key_policies -> anonymous_sentinels (anonymous_sentinel_id));
+diesel::joinable!(key_policies -> sentinels (sentinel_id));
+diesel::joinable!(oauth_clients -> applications (application_id));
+diesel::joinable!(oauth_codes -> oauth_clients (oauth_client_id));
+diesel::joinable!(oauth_codes -> users (user_id));
+diesel::joinable!(recovery_codes -> users (user_id));
+diesel::joinable!(secret_versions -> secrets (secret_id));
+diesel::joinable!(secrets -> applications (application_id));
 diesel::joinable!(sentinels -> applications (application_id));
+diesel::joinable!(sessions -> applications (application_id));
+diesel::joinable!(sessions -> connexions (connexion_id));
+diesel::joinable!(sessions -> users (user_id));
+diesel::joinable!(service_accounts -> applications (application_id));
+diesel::joinable!(service_accounts -> users (user_id));
+diesel::joinable!(share_links -> sentinels (sentinel_id));
+diesel::joinable!(webauthn_credentials -> users (user_id));
 diesel::joinable!(x_anonymous_sentinel_cluster -> anonymous_sentinels (anonymous_sentinel_id));
 diesel::joinable!(x_anonymous_sentinel_cluster -> clusters (cluster_id));
+diesel::joinable!(x_secret_cluster -> clusters (cluster_id));
+diesel::joinable!(x_secret_cluster -> secrets (secret_id));
 diesel::joinable!(x_sentinel_cluster -> clusters (cluster_id));
 diesel::joinable!(x_sentinel_cluster -> sentinels (sentinel_id));
 diesel::joinable!(x_user_cluster -> clusters (cluster_id));
 
 diesel::allow_tables_to_appear_in_same_query!(
+    access_request_approvals,
+    access_requests,
     anonymous_sentinels,
     applications,
+    audit_logs,
     clusters,
     connexions,
+    import_keys,
+    key_policies,
+    oauth_clients,
+    oauth_codes,
+    recovery_codes,
     revoked_tokens,
+    secret_versions,
+    secrets,
     sentinels,
+    service_accounts,
+    sessions,
+    share_links,
+    signing_keys,
     users,
+    webauthn_credentials,
     x_anonymous_sentinel_cluster,
+    x_secret_cluster,
     x_sentinel_cluster,
     x_user_cluster,
 );
@@ -0,0 +1,255 @@
+use std::env;
+
+use chrono::Utc;
+use rocket::http::Status;
+use uuid::Uuid;
+
+use crate::{
+    dto::access_request::access_request_insertable::AccessRequestInsertable,
+    enums::access_request_status::AccessRequestStatus,
+    models::{
+        access_request::AccessRequest, anonymous_sentinel::AnonymousSentinel, sentinel::Sentinel,
+        user::User,
+    },
+    traits::access_request::AccessRequestContract,
+};
+
+type AccessRequestError = (Status, Option<&'static str>);
+
+pub struct AccessRequestService<T> {
+    access_request_repository: T,
+}
+
+impl<T: AccessRequestContract> AccessRequestService<T> {
+    pub fn new(access_request_repository: T) -> Self {
+        Self {
+            access_request_repository,
+        }
+    }
+
+    /// ### Gate sentinel
+    ///
+    /// the approved request that releases a dual control sentinel to the user,
+    /// `None` when the sentinel is not under dual control.
+    /// Without an approved request a pending one is opened and `202 Accepted` is returned
+    pub fn gate_sentinel(
+        &self,
+        sentinel: &Sentinel,
+        user_from: &User,
+    ) -> Result<Option<AccessRequest>, AccessRequestError> {
+        let required_approvals = self.sentinel_required_approvals(sentinel);
+        self.gate(Some(sentinel.id), None, required_approvals, user_from)
+    }
+
+    /// the approvals needed to release a sentinel, set on the key or on one of its clusters
+    pub fn sentinel_required_approvals(&self, sentinel: &Sentinel) -> i32 {
+        sentinel.required_approvals.max(
+            self.access_request_repository
+                .get_sentinel_clusters_requirement(&sentinel.id),
+        )
+    }
+
+    /// ### Gate anonymous sentinel
+    ///
+    /// same as `gate_sentinel` for the secret key of an anonymous sentinel
+    pub fn gate_anonymous_sentinel(
+        &self,
+        anonymous_sentinel: &AnonymousSentinel,
+        user_from: &User,
+    ) -> Result<Option<AccessRequest>, AccessRequestError> {
+        let required_approvals = anonymous_sentinel.required_approvals.max(
+            self.access_request_repository
+                .get_anonymous_sentinel_clusters_requirement(&anonymous_sentinel.id),
+        );
+        self.gate(None, Some(anonymous_sentinel.id), required_approvals, user_from)
+    }
+
+    /// an approved request releases the key once
+    pub fn consume(
+        &self,
+        access_request: Option<AccessRequest>,
+        user_from: &User,
+    ) -> Result<(), AccessRequestError> {
+        match access_request {
+            None => Ok(()),
+            Some(access_request) => match self.access_request_repository.transition(
+                &access_request.id,
+                AccessRequestStatus::Approved,
+                AccessRequestStatus::Consumed,
+                user_from,
+            ) {
+                true => Ok(()),
+                false => Err((
+                    Status::Forbidden,
+                    Some("The access request was already used or is expired"),
+                )),
+            },
+        }
+    }
+
+    /// ### Release sentinel
+    ///
+    /// run `release` on a sentinel the dual control lets through, the approved request is
+    /// consumed once `release` succeeds. Every read of the key material of a sentinel goes through it
+    pub fn release_sentinel<R>(
+        &self,
+        sentinel: Sentinel,
+        user_from: &User,
+        release: impl FnOnce(Sentinel) -> Result<R, AccessRequestError>,
+    ) -> Result<R, AccessRequestError> {
+        let approved = self.gate_sentinel(&sentinel, user_from)?;
+        let released = release(sentinel)?;
+        self.consume(approved, user_from)?;
+        Ok(released)
+    }
+
+    pub fn get_all(&self, user_from: &User) -> Vec<(AccessRequest, i64)> {
+        self.access_request_repository
+            .get_for_user(user_from)
+            .into_iter()
+            .map(|access_request| {
+                let approvals = self
+                    .access_request_repository
+                    .count_approvals(&access_request.id);
+                (access_request, approvals)
+            })
+            .collect()
+    }
+
+    /// a request is visible by its requester and by the members of the clusters of its key
+    pub fn get_by_id(
+        &self,
+        access_request_uuid: Uuid,
+        user_from: &User,
+    ) -> Result<(AccessRequest, i64), AccessRequestError> {
+        match self.access_request_repository.get_by_id(&access_request_uuid) {
+            Some(access_request)
+                if access_request.requester_id == user_from.id
+                    || self
+                        .access_request_repository
+                        .is_cluster_member(&access_request, &user_from.id) =>
+            {
+                let approvals = self
+                    .access_request_repository
+                    .count_approvals(&access_request.id);
+                Ok((access_request, approvals))
+            }
+            _ => Err((Status::NotFound, None)),
+        }
+    }
+
+    /// ### Approve
+    ///
+    /// approve a pending request, the requester can not approve their own request
+    pub fn approve(
+        &self,
+        access_request_uuid: Uuid,
+        user_from: &User,
+    ) -> Result<(AccessRequest, i64), AccessRequestError> {
+        let access_request = self.get_pending_for_approver(access_request_uuid, user_from)?;
+        match self
+            .access_request_repository
+            .approve(&access_request.id, user_from)
+        {
+            Err(diesel::result::Error::DatabaseError(
+                diesel::result::DatabaseErrorKind::UniqueViolation,
+                _,
+            )) => Err((Status::Conflict, Some("The request is already approved by this user"))),
+            Err(_) => Err((Status::Conflict, Some("The request is not pending anymore"))),
+            Ok(access_request) => {
+                let approvals = self
+                    .access_request_repository
+                    .count_approvals(&access_request.id);
+                Ok((access_request, approvals))
+            }
+        }
+    }
+
+    /// ### Reject
+    ///
+    /// reject a pending request, a single rejection closes it
+    pub fn reject(
+        &self,
+        access_request_uuid: Uuid,
+        user_from: &User,
+    ) -> Result<(AccessRequest, i64), AccessRequestError> {
+        let access_request = self.get_pending_for_approver(access_request_uuid, user_from)?;
+        match self.access_request_repository.transition(
+            &access_request.id,
+            AccessRequestStatus::Pending,
+            AccessRequestStatus::Rejected,
+            user_from,
+        ) {
+            false => Err((Status::Conflict, Some("The request is not pending anymore"))),
+            true => self.get_by_id(access_request.id, user_from),
+        }
+    }
+
+    fn gate(
+        &self,
+        sentinel_uuid: Option<Uuid>,
+        anonymous_sentinel_uuid: Option<Uuid>,
+        required_approvals: i32,
+        user_from: &User,
+    ) -> Result<Option<AccessRequest>, AccessRequestError> {
+        if required_approvals < 1 {
+            return Ok(None);
+        }
+        if let Some(approved) = self.access_request_repository.find_open(
+            sentinel_uuid,
+            anonymous_sentinel_uuid,
+            &user_from.id,
+            AccessRequestStatus::Approved,
+        ) {
+            return Ok(Some(approved));
+        }
+        if self
+            .access_request_repository
+            .find_open(
+                sentinel_uuid,
+                anonymous_sentinel_uuid,
+                &user_from.id,
+                AccessRequestStatus::Pending,
+            )
+            .is_none()
+        {
+            let ttl = env::var("ACCESS_REQUEST_TTL")
+                .unwrap_or_else(|_| "60".to_string())
+                .parse::<i64>()
+                .unwrap_or(60);
+            self.access_request_repository
+                .create_access_request(AccessRequestInsertable::new(
+                    sentinel_uuid,
+                    anonymous_sentinel_uuid,
+                    user_from.id,
+                    required_approvals,
+                    Utc::now() + chrono::Duration::minutes(ttl),
+                ));
+        }
+        Err((
+            Status::Accepted,
+            Some("The key is under dual control, the access request is waiting for approvals"),
+        ))
+    }
+
+    fn get_pending_for_approver(
+        &self,
+        access_request_uuid: Uuid,
+        user_from: &User,
+    ) -> Result<AccessRequest, AccessRequestError> {
+        let (access_request, _) = self.get_by_id(access_request_uuid, user_from)?;
+        if access_request.requester_id == user_from.id {
+            return Err((
+                Status::Forbidden,
+                Some("The requester can not approve their own request"),
+            ));
+        }
+        if access_request.is_expired()
+            || AccessRequestStatus::from_str(&access_request.status)
+                != Some(AccessRequestStatus::Pending)
+        {
+            return Err((Status::Conflict, Some("The request is not pending anymore")));
+        }
+        Ok(access_request)
+    }
+}
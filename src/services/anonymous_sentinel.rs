@@ -1,4 +1,7 @@
 use mail_send::mail_auth::hickory_resolver::proto::rr::dnssec::Private;
+use std::net::IpAddr;
+
+use chrono::Utc;
 use rocket::http::Status;
 use uuid::Uuid;
 
@@ -10,22 +13,37 @@ use crate::{
             anonymous_sentinel_insertable::AnonymousSentinelInsertable,
             anonymous_sentinel_public_input::AnonymousSentinelPublicInput,
         },
+        access_request::dual_control_input::DualControlInput,
+        audit_log::audit_log_insertable::AuditLogInsertable,
+        key_policy::key_policy_input::KeyPolicyInput,
         sentinel::sentinel_input::SentinelInput,
         x_anonymous_sentinel_cluster::x_anonymous_sentinel_cluster_insertable::XAnonymousSentinelClusterInsertable,
     },
-    models::{anonymous_sentinel::AnonymousSentinel, user::User},
-    repositories::{anonymous_sentinel::AnonymousSentinelRepository, cluster::ClusterRepository},
-    traits::application::ApplicationContract,
-    utils::{crypto::Crypto, pq_kyber::PQKyber},
+    enums::{
+        audit_action::AuditAction, audit_severity::AuditSeverity, key_operation::KeyOperation,
+    },
+    models::{anonymous_sentinel::AnonymousSentinel, key_policy::KeyPolicy, user::User},
+    repositories::{
+        access_request::AccessRequestRepository, anonymous_sentinel::AnonymousSentinelRepository,
+        cluster::ClusterRepository,
+    },
+    traits::{access_request::AccessRequestContract, application::ApplicationContract},
+    utils::{crypto::Crypto, jwt::TokenScope, pq_kyber::PQKyber},
     LICENSE_VALID,
 };
 
+use super::access_request::AccessRequestService;
+use super::audit::AuditService;
 use super::fragments::FragmentsService;
+use super::key_policy::KeyPolicyService;
 
 pub struct AnonymousSentinelService<T> {
     nodes_config: NodesConfig,
     anonymous_sentinel_repository: AnonymousSentinelRepository,
     cluster_repository: ClusterRepository,
+    key_policy_service: KeyPolicyService,
+    access_request_service: AccessRequestService<AccessRequestRepository>,
+    audit_service: AuditService,
     application_repository: T,
 }
 
@@ -35,6 +53,9 @@ impl<T: ApplicationContract> AnonymousSentinelService<T> {
             nodes_config: nodes_config.clone(),
             anonymous_sentinel_repository: AnonymousSentinelRepository::new(&pool),
             cluster_repository: ClusterRepository::new(&pool),
+            key_policy_service: KeyPolicyService::new(pool),
+            access_request_service: AccessRequestService::new(AccessRequestContract::new(pool)),
+            audit_service: AuditService::new(pool),
             application_repository,
         }
     }
@@ -183,33 +204,160 @@ impl<T: ApplicationContract> AnonymousSentinelService<T> {
         }
     }
 
+    /// a capability token only works on the anonymous sentinels of its scope, named directly or
+    /// through one of their clusters, and for its operations. `None` is a full session
+    pub fn check_scope(
+        &self,
+        scope: Option<&TokenScope>,
+        sentinel_uuid: &Uuid,
+        operation: KeyOperation,
+    ) -> Result<(), (Status, Option<&'static str>)> {
+        match scope {
+            None => Ok(()),
+            Some(scope) => match scope.allows(
+                sentinel_uuid,
+                &self
+                    .cluster_repository
+                    .get_anonymous_sentinel_cluster_ids(sentinel_uuid),
+                operation,
+            ) {
+                true => Ok(()),
+                false => Err((
+                    Status::Forbidden,
+                    Some("The token scope does not allow this operation on this key"),
+                )),
+            },
+        }
+    }
+
+    /// only the creator and the members of the clusters of the anonymous sentinel can read it,
+    /// the admins go through break-glass. The dual control and the key policy are checked before the fragments are fetched
     pub fn get_by_id(
         &self,
         sentinel_uuid: Uuid,
         user_from: User,
+        ip: &IpAddr,
     ) -> Result<(AnonymousSentinel, String), (Status, Option<&str>)> {
         match self
             .anonymous_sentinel_repository
-            .get_anonymous_sentinel_by_id(&sentinel_uuid, &user_from)
+            .get_member_anonymous_sentinel_by_id(&sentinel_uuid, &user_from)
+        {
+            None => Err((Status::NotFound, None)),
+            Some(anonymous_sentinel) => self.release_key(anonymous_sentinel, &user_from, ip, None),
+        }
+    }
+
+    /// ### Break glass
+    ///
+    /// emergency read of any anonymous sentinel of the application with a justification.
+    /// The read is recorded as a high severity audit record, alerted to every admin and
+    /// flags the anonymous sentinel until an admin acknowledges it
+    pub fn break_glass(
+        &self,
+        sentinel_uuid: Uuid,
+        justification: &str,
+        user_from: User,
+        ip: &IpAddr,
+    ) -> Result<(AnonymousSentinel, String), (Status, Option<&'static str>)> {
+        AuditService::check_break_glass_role(&user_from)?;
+        let justification = AuditService::check_justification(justification)?;
+        match self
+            .anonymous_sentinel_repository
+            .get_application_anonymous_sentinel_by_id(&sentinel_uuid, user_from.application.unwrap())
         {
             None => Err((Status::NotFound, None)),
             Some(anonymous_sentinel) => {
-                let fragments = FragmentsService::get_fragments_from_nodes(
-                    anonymous_sentinel.clone().id.to_string(),
-                    &self.nodes_config,
-                );
-                match FragmentsService::reconstruct_encrypted_key(fragments) {
-                    None => Err((Status::NotFound, None)),
-                    Some(encrypted_key) => match anonymous_sentinel.check(encrypted_key.clone()) {
-                        Err(e) => Err((Status::NotAcceptable, Some(e))),
-                        Ok(valid_sentinel) => Ok((
-                            valid_sentinel.clone(),
-                            PQKyber::decrypt_key(encrypted_key, valid_sentinel.iv),
-                        )),
-                    },
+                self.release_key(anonymous_sentinel, &user_from, ip, Some(&justification))
+            }
+        }
+    }
+
+    /// ### Acknowledge break glass
+    ///
+    /// clear the break-glass flag of an anonymous sentinel
+    pub fn acknowledge_break_glass(
+        &self,
+        sentinel_uuid: Uuid,
+        user_from: User,
+        ip: &IpAddr,
+    ) -> Result<(), (Status, Option<&'static str>)> {
+        match self
+            .anonymous_sentinel_repository
+            .acknowledge_break_glass(&sentinel_uuid, &user_from)
+        {
+            Ok(1) => self
+                .audit_service
+                .record(AuditLogInsertable::new(
+                    &user_from,
+                    AuditAction::FlagAcknowledged,
+                    AuditSeverity::Medium,
+                    Some(("anonymous_sentinel", sentinel_uuid)),
+                    None,
+                    Some(ip),
+                ))
+                .map(|_| ()),
+            _ => Err((Status::NotFound, Some("The key is not flagged as break-glass accessed"))),
+        }
+    }
+
+    /// the checks done before a secret key leaves the service. A break-glass attempt is
+    /// audited before the dual control and the key policy, the key is flagged once they let it through
+    fn release_key(
+        &self,
+        mut anonymous_sentinel: AnonymousSentinel,
+        user_from: &User,
+        ip: &IpAddr,
+        break_glass_justification: Option<&str>,
+    ) -> Result<(AnonymousSentinel, String), (Status, Option<&'static str>)> {
+        if let Some(justification) = break_glass_justification {
+            self.audit_service.break_glass(
+                user_from,
+                ("anonymous_sentinel", anonymous_sentinel.id),
+                justification,
+                ip,
+            )?;
+        }
+        let approved = self
+            .access_request_service
+            .gate_anonymous_sentinel(&anonymous_sentinel, user_from)?;
+        self.key_policy_service.enforce(
+            self.key_policy_service
+                .get_for_anonymous_sentinel(&anonymous_sentinel.id),
+            KeyOperation::Read,
+            ip,
+        )?;
+        if break_glass_justification.is_some() {
+            match self
+                .anonymous_sentinel_repository
+                .mark_break_glass(&anonymous_sentinel.id, user_from)
+            {
+                Ok(1) => {
+                    anonymous_sentinel.break_glass_at = Some(Utc::now());
+                    anonymous_sentinel.break_glass_by_id = Some(user_from.id);
+                }
+                _ => {
+                    return Err((
+                        Status::InternalServerError,
+                        Some("Unable to flag the key as break-glass accessed"),
+                    ))
                 }
             }
         }
+        self.access_request_service.consume(approved, user_from)?;
+        let fragments = FragmentsService::get_fragments_from_nodes(
+            anonymous_sentinel.clone().id.to_string(),
+            &self.nodes_config,
+        );
+        match FragmentsService::reconstruct_encrypted_key(fragments) {
+            None => Err((Status::NotFound, None)),
+            Some(encrypted_key) => match anonymous_sentinel.check(encrypted_key.clone()) {
+                Err(e) => Err((Status::NotAcceptable, Some(e))),
+                Ok(valid_sentinel) => Ok((
+                    valid_sentinel.clone(),
+                    PQKyber::decrypt_key(encrypted_key, valid_sentinel.iv),
+                )),
+            },
+        }
     }
 
     pub fn delete_one(
@@ -217,7 +365,13 @@ impl<T: ApplicationContract> AnonymousSentinelService<T> {
         sentinel_uuid: Uuid,
         user_from: User,
         is_admin: bool,
+        ip: &IpAddr,
     ) -> Result<(), (Status, Option<&str>)> {
+        self.key_policy_service.enforce(
+            self.key_policy_service.get_for_anonymous_sentinel(&sentinel_uuid),
+            KeyOperation::Delete,
+            ip,
+        )?;
         match is_admin {
             true => match self
                 .anonymous_sentinel_repository
@@ -243,4 +397,109 @@ impl<T: ApplicationContract> AnonymousSentinelService<T> {
             .decrement_keys(&user_from.application.unwrap());
         Ok(())
     }
+
+    /// ### Get policy
+    ///
+    /// the active usage policy of an anonymous sentinel
+    pub fn get_policy(
+        &self,
+        sentinel_uuid: Uuid,
+        user_from: User,
+    ) -> Result<KeyPolicy, (Status, Option<&'static str>)> {
+        match self
+            .anonymous_sentinel_repository
+            .get_anonymous_sentinel_by_id(&sentinel_uuid, &user_from)
+        {
+            None => Err((Status::NotFound, None)),
+            Some(anonymous_sentinel) => self
+                .key_policy_service
+                .get_for_anonymous_sentinel(&anonymous_sentinel.id)
+                .ok_or((Status::NotFound, None)),
+        }
+    }
+
+    /// ### Set policy
+    ///
+    /// replace the usage policy of an anonymous sentinel, only its creator or an admin can change it
+    /// and only an admin can relax it
+    pub fn set_policy(
+        &self,
+        sentinel_uuid: Uuid,
+        input: KeyPolicyInput,
+        user_from: User,
+        is_admin: bool,
+    ) -> Result<KeyPolicy, (Status, Option<&'static str>)> {
+        let anonymous_sentinel = self.get_owned_anonymous_sentinel(sentinel_uuid, &user_from, is_admin)?;
+        self.key_policy_service
+            .set(None, Some(anonymous_sentinel.id), input, &user_from, is_admin)
+    }
+
+    /// ### Delete policy
+    ///
+    /// remove the usage policy of an anonymous sentinel, only an admin can remove it
+    pub fn delete_policy(
+        &self,
+        sentinel_uuid: Uuid,
+        user_from: User,
+        is_admin: bool,
+    ) -> Result<(), (Status, Option<&'static str>)> {
+        let anonymous_sentinel = self.get_owned_anonymous_sentinel(sentinel_uuid, &user_from, is_admin)?;
+        self.key_policy_service.delete(
+            self.key_policy_service
+                .get_for_anonymous_sentinel(&anonymous_sentinel.id),
+            &user_from,
+            is_admin,
+        )
+    }
+
+    /// ### Set dual control
+    ///
+    /// number of approvals needed to release the secret key, only an admin can lower it
+    pub fn set_dual_control(
+        &self,
+        sentinel_uuid: Uuid,
+        input: DualControlInput,
+        user_from: User,
+        is_admin: bool,
+    ) -> Result<AnonymousSentinel, (Status, Option<&'static str>)> {
+        let anonymous_sentinel = self.get_owned_anonymous_sentinel(sentinel_uuid, &user_from, is_admin)?;
+        if input.required_approvals < 0 {
+            return Err((
+                Status::BadRequest,
+                Some("The number of approvals can not be negative"),
+            ));
+        }
+        if !is_admin && input.required_approvals < anonymous_sentinel.required_approvals {
+            return Err((
+                Status::Forbidden,
+                Some("Only an admin can lower the dual control of a key"),
+            ));
+        }
+        self.anonymous_sentinel_repository
+            .set_required_approvals(&anonymous_sentinel.id, input.required_approvals, &user_from)
+            .map_err(|_| (Status::NotFound, None))
+    }
+
+    fn get_owned_anonymous_sentinel(
+        &self,
+        sentinel_uuid: Uuid,
+        user_from: &User,
+        is_admin: bool,
+    ) -> Result<AnonymousSentinel, (Status, Option<&'static str>)> {
+        match self
+            .anonymous_sentinel_repository
+            .get_anonymous_sentinel_by_id(&sentinel_uuid, user_from)
+        {
+            None => Err((Status::NotFound, None)),
+            Some(anonymous_sentinel)
+                if is_admin || anonymous_sentinel.created_by_id == Some(user_from.id) =>
+            {
+                Ok(anonymous_sentinel)
+            }
+            Some(_) => Err((
+                Status::Forbidden,
+                Some("Only the creator of the key can change its settings"),
+            )),
+        }
+    }
 }
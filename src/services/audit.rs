@@ -0,0 +1,129 @@
+use std::net::IpAddr;
+
+use rocket::http::Status;
+use uuid::Uuid;
+
+use crate::{
+    db::connect::DbPool,
+    dto::audit_log::{audit_log_insertable::AuditLogInsertable, break_glass_alert::BreakGlassAlert},
+    enums::{audit_action::AuditAction, audit_severity::AuditSeverity, roles::Role},
+    guards::security::Security,
+    models::{audit_log::AuditLog, user::User},
+    repositories::audit_log::AuditLogRepository,
+    traits::audit::AuditContract,
+};
+
+type AuditError = (Status, Option<&'static str>);
+
+/// number of records returned by the audit log listing
+const AUDIT_LOGS_LIMIT: i64 = 500;
+const JUSTIFICATION_MIN_LENGTH: usize = 10;
+const JUSTIFICATION_MAX_LENGTH: usize = 2000;
+
+pub struct AuditService<T = AuditLogRepository> {
+    audit_log_repository: T,
+}
+
+impl AuditService {
+    pub fn new(pool: &DbPool) -> Self {
+        Self::with_repository(AuditContract::new(pool))
+    }
+
+    /// only a user with `ROLE_BREAK_GLASS` can break the glass
+    pub fn check_break_glass_role(user_from: &User) -> Result<(), AuditError> {
+        match Security::has_role(user_from, Role::BREAKGLASS) {
+            true => Ok(()),
+            false => Err((Status::Forbidden, Some("The break-glass role is required"))),
+        }
+    }
+
+    /// the trimmed justification of a break-glass access
+    pub fn check_justification(justification: &str) -> Result<String, AuditError> {
+        let justification = justification.trim();
+        match justification.chars().count() {
+            n if n < JUSTIFICATION_MIN_LENGTH => Err((
+                Status::BadRequest,
+                Some("A justification of at least 10 characters is required"),
+            )),
+            n if n > JUSTIFICATION_MAX_LENGTH => Err((
+                Status::BadRequest,
+                Some("The justification can not be longer than 2000 characters"),
+            )),
+            _ => Ok(justification.to_string()),
+        }
+    }
+}
+
+impl<T: AuditContract> AuditService<T> {
+    pub fn with_repository(audit_log_repository: T) -> Self {
+        Self {
+            audit_log_repository,
+        }
+    }
+
+    /// a failed write is an error, the audited action must not go on without its record
+    pub fn record(&self, insertable: AuditLogInsertable) -> Result<AuditLog, AuditError> {
+        self.audit_log_repository
+            .create_audit_log(insertable)
+            .map_err(|_| {
+                (
+                    Status::InternalServerError,
+                    Some("Unable to write the audit record"),
+                )
+            })
+    }
+
+    pub fn get_all(
+        &self,
+        user_from: &User,
+        severity: Option<String>,
+    ) -> Result<Vec<AuditLog>, AuditError> {
+        let severity = match severity {
+            None => None,
+            Some(severity) => match AuditSeverity::from_str(&severity) {
+                None => {
+                    return Err((
+                        Status::BadRequest,
+                        Some("The severity must be `low`, `medium` or `high`"),
+                    ))
+                }
+                Some(severity) => Some(severity),
+            },
+        };
+        Ok(self.audit_log_repository.get_by_application(
+            user_from.application.unwrap(),
+            severity.map(|severity| severity.as_str()),
+            AUDIT_LOGS_LIMIT,
+        ))
+    }
+
+    /// ### Break glass
+    ///
+    /// write the high severity record of a break-glass attempt, then alert every admin of the application.
+    /// It is written before the key is gated so a refused attempt leaves a trail too
+    pub fn break_glass(
+        &self,
+        user_from: &User,
+        target: (&str, Uuid),
+        justification: &str,
+        ip: &IpAddr,
+    ) -> Result<(), AuditError> {
+        AuditService::check_break_glass_role(user_from)?;
+        self.record(AuditLogInsertable::new(
+            user_from,
+            AuditAction::BreakGlassRead,
+            AuditSeverity::High,
+            Some(target),
+            Some(justification.to_string()),
+            Some(ip),
+        ))?;
+        let alerts = self
+            .audit_log_repository
+            .get_admins_by_app(user_from.application.unwrap())
+            .iter()
+            .map(|admin| BreakGlassAlert::new(admin, user_from, target, justification, ip))
+            .collect();
+        self.audit_log_repository.send_break_glass_alerts(alerts);
+        Ok(())
+    }
+}
@@ -1,4 +1,7 @@
-use jsonwebtoken::{decode, Algorithm, DecodingKey, Validation};
+use std::{env, net::IpAddr};
+
+use chrono::{DateTime, Utc};
+use jsonwebtoken::{Algorithm, Validation};
 use rocket::{http::Status, response::status::Custom, serde::json::Json};
 use sha2::{Digest, Sha256};
 
@@ -6,24 +9,58 @@ use crate::{
     core::errors::{CustomError, ErrorObject},
     db::connect::DbPool,
     dto::{
-        auth::auth_output::AuthOutput,
+        auth::{
+            auth_output::AuthOutput, scoped_token_input::ScopedTokenInput,
+            scoped_token_output::ScopedTokenOutput,
+        },
+        audit_log::audit_log_insertable::AuditLogInsertable,
         revoked_token::revoked_token_insertable::RevokedTokenInsertable,
+        session::session_insertable::SessionInsertable,
+        webauthn::webauthn_assertion_input::WebauthnAssertionInput,
+    },
+    enums::{
+        attempt_scope::AttemptScope, audit_action::AuditAction, audit_severity::AuditSeverity,
+        token_binding::TokenBinding,
+    },
+    guards::dpop::DpopHeader,
+    models::{application::Application, connexion::Connexion, session::Session, user::User},
+    repositories::{
+        service_account::ServiceAccountRepository, session::SessionRepository,
+        user::UserRepository,
     },
-    models::{application::Application, revoked_token::RevokedToken, user::User},
-    repositories::{revoked_token::RevokedTokenRepository, user::UserRepository},
     traits::{
         application::ApplicationContract, connexion::ConnexionContract,
         revoked_token::RevokedTokenContract,
     },
-    utils::{jwt::Jwt, open_id::OpenId},
+    utils::{
+        code::hash_token,
+        jwt::{Jwt, ScopedClaims, SessionClaims, TokenScope},
+        key_ring::KeyRing,
+        open_id::{Authentication, OpenId},
+    },
 };
+use uuid::Uuid;
 
-use super::{connexion::ConnexionService, user::UserService};
+use super::{
+    audit::AuditService, connexion::ConnexionService, ip_restriction::IpRestrictionService,
+    lockout::LockoutService,
+    session::SessionService, user::UserService, webauthn::WebauthnService,
+};
+
+/// validity of a scoped token in seconds when none is asked
+const SCOPED_TOKEN_DEFAULT_DURATION: i64 = 900;
 
 pub struct AuthService<T, D, C> {
     user_service: UserService<D, C>,
     user_repository: UserRepository,
+    service_account_repository: ServiceAccountRepository,
     revoked_repository: T,
+    session_repository: SessionRepository,
+    session_service: SessionService,
+    webauthn_service: WebauthnService,
+    audit_service: AuditService,
+    lockout_service: LockoutService,
+    ip_restriction_service: IpRestrictionService,
     connexion_service: ConnexionService<C>,
 }
 
@@ -37,7 +74,14 @@ impl<T: RevokedTokenContract, D: ApplicationContract, C: ConnexionContract> Auth
         Self {
             user_service,
             user_repository: UserRepository::new(pool),
+            service_account_repository: ServiceAccountRepository::new(pool),
             revoked_repository,
+            session_repository: SessionRepository::new(pool),
+            session_service: SessionService::new(pool),
+            webauthn_service: WebauthnService::new(pool),
+            audit_service: AuditService::new(pool),
+            lockout_service: LockoutService::new(pool),
+            ip_restriction_service: IpRestrictionService::new(pool),
             connexion_service,
         }
     }
@@ -49,12 +93,14 @@ impl<T: RevokedTokenContract, D: ApplicationContract, C: ConnexionContract> Auth
         }
     }
 
-    /// check the provided credentials
+    /// check the provided credentials, an account or an IP failing too often is refused a while.
+    /// The IP must be allowed for the user and their application
     pub async fn check_creds(
         &self,
         login: &String,
         password: &String,
         app: &Application,
+        ip: &IpAddr,
     ) -> Result<User, CustomError> {
         let custom_error = Custom(
             Status::Unauthorized,
@@ -63,22 +109,43 @@ impl<T: RevokedTokenContract, D: ApplicationContract, C: ConnexionContract> Auth
                 401,
             )),
         );
-        match self.user_repository.get_by_login_and_app(login, app.id) {
-            None => Err(custom_error),
-            Some(user) => match user.clone().password.is_some()
-                && Self::verify_password(password, &user.clone().password.unwrap())
+        if let Err((status, msg)) = self.lockout_service.check(AttemptScope::Login, app.id, login, ip) {
+            return Err(ErrorObject::create(status, msg));
+        }
+        let user = self.user_repository.get_by_login_and_app(login, app.id);
+        match &user {
+            Some(user)
+                if user.password.is_some()
+                    && Self::verify_password(password, user.password.as_ref().unwrap()) =>
             {
-                false => Err(custom_error),
-                true => Ok(user),
-            },
+                self.lockout_service.succeed(AttemptScope::Login, app.id, login);
+                self.ip_restriction_service
+                    .check(user, ip)
+                    .map_err(|(status, msg)| ErrorObject::create(status, msg))?;
+                Ok(user.clone())
+            }
+            _ => {
+                self.lockout_service
+                    .fail(AttemptScope::Login, app.id, login, ip, user.as_ref());
+                Err(custom_error)
+            }
         }
     }
 
+    /// ### Check refresh token
+    ///
+    /// rotate the session of the refresh token, the new refresh token replaces it. A refresh token bound to a
+    /// DPoP key comes with a proof of the key `jkt`.
+    /// A refresh token already replaced is reused: it was stolen or its thief already
+    /// refreshed, the whole session is revoked. The refresh tokens issued before the sessions
+    /// are accepted once and open a session
     pub fn check_refresh_token(
         &self,
         refresh_token: &String,
         app: &Application,
-    ) -> Result<User, CustomError> {
+        ip: &IpAddr,
+        jkt: Option<&String>,
+    ) -> Result<(User, Authentication, Session), CustomError> {
         let custom_error = Custom(
             Status::Unauthorized,
             Json(ErrorObject::new(
@@ -86,90 +153,313 @@ impl<T: RevokedTokenContract, D: ApplicationContract, C: ConnexionContract> Auth
                 401,
             )),
         );
-        let revoked_token = self.revoked_repository.get_by_token(refresh_token);
-        match revoked_token {
-            Some(_) => Err(custom_error),
-            None => match decode::<Jwt>(
-                refresh_token.as_ref(),
-                &DecodingKey::from_rsa_pem(include_bytes!("../../ssh_keys/public.key")).unwrap(),
-                &Validation::new(Algorithm::RS256),
-            ) {
-                Err(_) => Err(custom_error),
-                Ok(token) => {
-                    if !token.claims.is_refresh {
-                        return Err(custom_error);
-                    };
-                    match self
-                        .user_repository
-                        .get_by_login_and_app(&token.claims.login, app.id)
-                    {
-                        None => Err(custom_error),
-                        Some(user) => Ok(user),
-                    }
-                }
-            },
+        let claims = match KeyRing::decode::<Jwt>(
+            refresh_token.as_ref(),
+            &Validation::new(Algorithm::RS256),
+        ) {
+            Err(_) => return Err(custom_error),
+            Ok(token) => token.claims,
+        };
+        if !claims.is_refresh || claims.is_revoked() {
+            return Err(custom_error);
+        }
+        // a refresh token bound to a DPoP key is refreshed by its key only
+        if claims.cnf.as_ref().is_some_and(|cnf| Some(&cnf.jkt) != jkt) {
+            return Err(custom_error);
+        }
+        let user = match self
+            .user_repository
+            .get_by_login_and_app(&claims.login, app.id)
+        {
+            Some(user) if user.id == claims.id => user,
+            _ => return Err(custom_error),
+        };
+        self.ip_restriction_service
+            .check(&user, ip)
+            .map_err(|(status, msg)| ErrorObject::create(status, msg))?;
+        let session = match (claims.sid, claims.jti) {
+            (Some(sid), Some(jti)) => self.rotate_session(&sid, &jti, &user, ip),
+            _ => self.adopt_refresh_token(refresh_token, &claims, &user, app),
+        };
+        match session {
+            None => Err(custom_error),
+            Some(session) => Ok((user, claims.authentication(), session)),
+        }
+    }
+
+    /// ### Open session
+    ///
+    /// the session of a login, its first refresh token is issued by `generate_creds`
+    pub fn open_session(
+        &self,
+        user: &User,
+        app: &Application,
+        connexion: Option<&Connexion>,
+    ) -> Result<Session, CustomError> {
+        self.session_repository
+            .create_session(SessionInsertable::new(
+                user.id,
+                app.id,
+                connexion.map(|connexion| connexion.id),
+                Jwt::refresh_expiration(),
+            ))
+            .map_err(|_| {
+                ErrorObject::create(Status::InternalServerError, Some("Unable to open the session"))
+            })
+    }
+
+    fn rotate_session(&self, sid: &Uuid, jti: &Uuid, user: &User, ip: &IpAddr) -> Option<Session> {
+        let session = self
+            .session_repository
+            .get_active(sid)
+            .filter(|session| session.user_id == user.id)?;
+        let rotated = self.session_repository.rotate(
+            sid,
+            jti,
+            &Uuid::new_v4(),
+            Jwt::refresh_expiration(),
+        );
+        if rotated.is_none() {
+            self.revoke_reused_session(&session, user, ip);
         }
+        rotated
+    }
+
+    /// revoke the session and its access tokens, a failure still refuses the refresh
+    fn revoke_reused_session(&self, session: &Session, user: &User, ip: &IpAddr) {
+        let _ = self.session_service.end(session, None);
+        let _ = self.audit_service.record(AuditLogInsertable::new(
+            user,
+            AuditAction::RefreshTokenReused,
+            AuditSeverity::High,
+            Some(("session", session.id)),
+            None,
+            Some(ip),
+        ));
     }
 
-    pub fn revoke_token(&self, token: &String) -> RevokedToken {
-        let insertable = RevokedTokenInsertable::new(token.clone());
-        self.revoked_repository.create_revoked_token(insertable)
+    /// a refresh token without session is revoked by its hash until it expires
+    fn adopt_refresh_token(
+        &self,
+        refresh_token: &str,
+        claims: &Jwt,
+        user: &User,
+        app: &Application,
+    ) -> Option<Session> {
+        let expires_at = DateTime::<Utc>::from_timestamp(claims.exp, 0)?;
+        self.revoked_repository.purge_expired();
+        self.revoked_repository
+            .create_revoked_token(RevokedTokenInsertable::new(
+                hash_token(refresh_token),
+                expires_at,
+            ))
+            .ok()?;
+        self.open_session(user, app, None).ok()
+    }
+
+    /// ### Bind client
+    ///
+    /// check the DPoP proof of a login or a refresh, the thumbprint of its key binds the tokens.
+    /// It is required unless `TOKEN_BINDING` is `legacy`
+    pub fn bind_client(
+        &self,
+        dpop: &DpopHeader,
+    ) -> Result<Option<String>, (Status, Option<&'static str>)> {
+        let jkt = dpop.check(None).map_err(|e| (Status::BadRequest, Some(e)))?;
+        match (&jkt, TokenBinding::current()) {
+            (None, TokenBinding::Dpop) => Err((Status::BadRequest, Some("A DPoP proof is required"))),
+            _ => Ok(jkt),
+        }
     }
 
+    /// the access and refresh tokens of `session`, bound to the DPoP key `jkt` when there is one
     pub async fn generate_creds(
         &self,
         user: &User,
         application: &Application,
         fingerprint: &String,
         user_agent: &String,
+        claims: &SessionClaims<'_>,
     ) -> AuthOutput {
         let validation_check_user = user.validation();
         let to_hash = &format!("{}{}", fingerprint, user_agent);
         let mut hasher = Sha256::new();
         hasher.update(to_hash);
         let device_sha = format!("{:x}", hasher.finalize());
-        let jwt =
-            Jwt::create_jwt(&validation_check_user, false, application, Some(device_sha)).await;
-        let refresh = Jwt::create_jwt(&validation_check_user, true, application, None).await;
-        let open_id = OpenId::create(validation_check_user.clone()).await;
-        AuthOutput::new(jwt, refresh, open_id).await
+        let jwt = Jwt::create_jwt(
+            &validation_check_user,
+            false,
+            application,
+            Some(device_sha),
+            claims,
+        )
+        .await;
+        let refresh = Jwt::create_jwt(
+            &validation_check_user,
+            true,
+            application,
+            None,
+            claims,
+        )
+        .await;
+        let open_id = OpenId::create(
+            &validation_check_user,
+            application.id.to_string(),
+            claims.authentication,
+            None,
+            None,
+        )
+        .await;
+        let mut output = AuthOutput::new(jwt, refresh, open_id).await;
+        if claims.jkt.is_some() {
+            output.token_type = String::from("DPoP");
+        }
+        output
+    }
+
+    /// ### Mint scoped token
+    ///
+    /// a short lived access token limited to some sentinels and operations,
+    /// bound to the device and DPoP key of the session minting it and revoked with it
+    pub async fn mint_scoped_token(
+        &self,
+        user: &User,
+        application: &Application,
+        device_id: Option<String>,
+        dpop_jkt: Option<String>,
+        session_id: Option<Uuid>,
+        input: ScopedTokenInput,
+    ) -> Result<ScopedTokenOutput, (Status, Option<&'static str>)> {
+        let max_duration = env::var("SCOPED_TOKEN_MAX_DURATION")
+            .unwrap_or_else(|_| "3600".to_string())
+            .parse::<i64>()
+            .unwrap_or(3600);
+        let duration = input.expires_in.unwrap_or(SCOPED_TOKEN_DEFAULT_DURATION.min(max_duration));
+        if duration < 1 || duration > max_duration {
+            return Err((
+                Status::BadRequest,
+                Some("The validity of the token is out of the allowed range"),
+            ));
+        }
+        let scope = TokenScope::parse(
+            input.sentinels.unwrap_or_default(),
+            input.clusters.unwrap_or_default(),
+            input.operations,
+        )
+        .map_err(|e| (Status::BadRequest, Some(e)))?;
+        let (token, expires_at) = Jwt::create_scoped_jwt(
+            &user.validation(),
+            application,
+            ScopedClaims {
+                device_sha: device_id,
+                jkt: dpop_jkt,
+                session_id,
+                is_service_account: self
+                    .service_account_repository
+                    .get_by_user_id(&user.id)
+                    .is_some(),
+                scope: scope.clone(),
+                duration,
+            },
+        )
+        .await;
+        Ok(ScopedTokenOutput::new(token, expires_at, scope))
     }
 
+    /// ### Check second factor
+    ///
+    /// from an unfamiliar ip or device a user with 2FA gives their TOTP code. A passkey assertion
+    /// replaces the code, it is required from the users with a passkey and without TOTP
     pub async fn check_otp(
         &self,
         user: &User,
         code_2fa: Option<String>,
+        webauthn: Option<WebauthnAssertionInput>,
         ip: &String,
         fingerprint: &String,
         user_agent: &String,
-    ) -> Result<(), (Status, Option<&str>)> {
-        if user.is_2fa_activated {
-            match self
-                .user_service
-                .check_otp(
-                    user.clone(),
-                    code_2fa,
-                    ip.clone(),
-                    fingerprint.clone(),
-                    user_agent.clone(),
-                )
-                .await
+    ) -> Result<Connexion, (Status, Option<&str>)> {
+        match webauthn {
+            Some(assertion) => {
+                let application_id = user.application.ok_or((Status::Unauthorized, None))?;
+                self.webauthn_service
+                    .authenticate(application_id, &assertion, Some(user), false)?;
+                self.user_service
+                    .check_new_connexion(
+                        user.clone(),
+                        ip.clone(),
+                        fingerprint.clone(),
+                        user_agent.clone(),
+                    )
+                    .await;
+            }
+            None if user.is_2fa_activated => {
+                match self
+                    .user_service
+                    .check_otp(
+                        user.clone(),
+                        code_2fa,
+                        ip.clone(),
+                        fingerprint.clone(),
+                        user_agent.clone(),
+                    )
+                    .await
+                {
+                    Err(status) if status == Status::TooManyRequests => {
+                        return Err((status, Some("Too many attempts, try again later")))
+                    }
+                    Err(status) => return Err((status, Some("Missing TOTP code"))),
+                    Ok(_) => {}
+                }
+            }
+            None if self.webauthn_service.has_credentials(user)
+                && !self.user_service.is_familiar_connexion(user, ip, fingerprint) =>
             {
-                Err(status) => return Err((status, Some("Missing TOTP code"))),
-                Ok(_) => {}
+                return Err((Status::BadRequest, Some("Missing passkey assertion")))
+            }
+            None => {
+                self.user_service
+                    .check_new_connexion(
+                        user.clone(),
+                        ip.clone(),
+                        fingerprint.clone(),
+                        user_agent.clone(),
+                    )
+                    .await;
             }
-        } else {
-            self.user_service
-                .check_new_connexion(
-                    user.clone(),
-                    ip.clone(),
-                    fingerprint.clone(),
-                    user_agent.clone(),
-                )
-                .await;
         }
-        self.connexion_service
-            .create_connexion(ip, user_agent, fingerprint, user);
-        Ok(())
+        Ok(self
+            .connexion_service
+            .create_connexion(ip, user_agent, fingerprint, user))
+    }
+
+    /// ### Check passkey
+    ///
+    /// a passwordless login, the passkey must have verified the user
+    pub async fn check_passkey(
+        &self,
+        app: &Application,
+        assertion: &WebauthnAssertionInput,
+        ip: &String,
+        fingerprint: &String,
+        user_agent: &String,
+    ) -> Result<(User, Connexion), (Status, Option<&'static str>)> {
+        let (user, _) = self
+            .webauthn_service
+            .authenticate(app.id, assertion, None, true)?;
+        let addr = ip.parse::<IpAddr>().map_err(|_| (Status::BadRequest, None))?;
+        self.ip_restriction_service.check(&user, &addr)?;
+        self.user_service
+            .check_new_connexion(
+                user.clone(),
+                ip.clone(),
+                fingerprint.clone(),
+                user_agent.clone(),
+            )
+            .await;
+        let connexion = self
+            .connexion_service
+            .create_connexion(ip, user_agent, fingerprint, &user);
+        Ok((user, connexion))
     }
 }
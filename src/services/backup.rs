@@ -0,0 +1,210 @@
+use std::env;
+
+use aes_gcm::aead::OsRng;
+use aes_gcm::{
+    aead::{Aead, KeyInit},
+    Aes256Gcm, Key, Nonce,
+};
+use base64::{engine::general_purpose, Engine as _};
+use chrono::Utc;
+use rand_core::RngCore;
+
+use crate::{
+    core::nodes_config::NodesConfig,
+    db::connect::DbPool,
+    dto::backup::{
+        backup_archive::{BackupArchive, BACKUP_ARCHIVE_VERSION},
+        backup_content::BackupContent,
+        backup_key_fragments::BackupKeyFragments,
+    },
+    repositories::backup::BackupRepository,
+    utils::{backup_signing_keys::BackupSigningKeys, crypto::Crypto},
+};
+
+use super::fragments::FragmentsService;
+
+pub struct BackupService {
+    repository: BackupRepository,
+    nodes_config: NodesConfig,
+}
+
+impl BackupService {
+    pub fn new(pool: &DbPool, nodes_config: &NodesConfig) -> Self {
+        BackupService {
+            repository: BackupRepository::new(pool),
+            nodes_config: nodes_config.clone(),
+        }
+    }
+
+    /// ### Create archive
+    ///
+    /// dump the postgres metadata, collect the fragments of every living key
+    /// then encrypt the whole content with the `BACKUP_KEY` and sign it
+    /// with the key of `BACKUP_SIGNING_KEY_PATH`
+    pub fn create_archive(&self) -> Result<BackupArchive, String> {
+        let backup_key = Self::get_backup_key()?;
+        let signing_keys = BackupSigningKeys::load()?;
+        let mut content = self
+            .repository
+            .dump()
+            .map_err(|e| format!("Failed to dump the metadata: {}", e))?;
+
+        let key_ids = content.live_key_ids();
+        for key_id in key_ids {
+            let fragments =
+                FragmentsService::get_fragments_from_nodes(key_id.to_string(), &self.nodes_config);
+            if FragmentsService::reconstruct_encrypted_key(fragments.clone()).is_none() {
+                return Err(format!("Not enough fragments to rebuild the key {}", key_id));
+            }
+            content
+                .fragments
+                .push(BackupKeyFragments::new(key_id, fragments));
+        }
+
+        Self::seal(&content, &backup_key, &signing_keys)
+    }
+
+    /// ### Restore archive
+    ///
+    /// check the archive signature, decrypt it, check every living key has its fragments
+    /// and can be rebuilt with the current master key, insert the metadata and deal fresh fragments
+    /// to the configured nodes
+    pub fn restore_archive(&self, archive: BackupArchive) -> Result<BackupContent, String> {
+        let backup_key = Self::get_backup_key()?;
+        let content = Self::open(&archive, &backup_key, &BackupSigningKeys::load()?)?;
+        if !self.repository.is_empty() {
+            return Err(String::from(
+                "The restore must be done on a fresh instance (applications or users already exist)",
+            ));
+        }
+
+        // every key is rebuilt and checked before anything is written
+        content.check_fragments()?;
+        let mut encrypted_keys = vec![];
+        for key_fragments in content.fragments.iter() {
+            let encrypted_key =
+                match FragmentsService::reconstruct_encrypted_key(key_fragments.fragments.clone()) {
+                    None => {
+                        return Err(format!(
+                            "Unable to rebuild the key {}",
+                            key_fragments.key_id
+                        ))
+                    }
+                    Some(encrypted_key) => encrypted_key,
+                };
+            // the sum is computed on the encrypted key, the decryption checks the master key
+            let iv = match content
+                .sentinels
+                .iter()
+                .find(|sentinel| sentinel.id == key_fragments.key_id)
+            {
+                Some(sentinel) => sentinel
+                    .check(encrypted_key.clone())
+                    .ok()
+                    .map(|sentinel| sentinel.iv),
+                None => match content
+                    .anonymous_sentinels
+                    .iter()
+                    .find(|anonymous_sentinel| anonymous_sentinel.id == key_fragments.key_id)
+                {
+                    Some(anonymous_sentinel) => anonymous_sentinel
+                        .check(encrypted_key.clone())
+                        .ok()
+                        .map(|anonymous_sentinel| anonymous_sentinel.iv),
+                    None => content
+                        .secret_versions
+                        .iter()
+                        .find(|secret_version| secret_version.id == key_fragments.key_id)
+                        .and_then(|secret_version| secret_version.check(encrypted_key.clone()).ok())
+                        .map(|secret_version| secret_version.iv),
+                },
+            };
+            let checked = match iv {
+                None => false,
+                Some(iv) => !Crypto::decrypt(encrypted_key.clone(), iv).is_empty(),
+            };
+            if !checked {
+                return Err(format!(
+                    "Integrity check failed for the key {}, is the master key the same?",
+                    key_fragments.key_id
+                ));
+            }
+            encrypted_keys.push((key_fragments.key_id, encrypted_key));
+        }
+
+        self.repository
+            .restore(&content)
+            .map_err(|e| format!("Failed to restore the metadata: {}", e))?;
+
+        for (key_id, encrypted_key) in encrypted_keys {
+            let fragments = FragmentsService::generate_fragments(encrypted_key);
+            FragmentsService::save_fragments_to_nodes(
+                fragments,
+                key_id.to_string(),
+                &self.nodes_config,
+            );
+        }
+        Ok(content)
+    }
+
+    /// ### Seal
+    ///
+    /// encrypt the content with `backup_key` (AES-256-GCM) and sign the archive
+    pub fn seal(
+        content: &BackupContent,
+        backup_key: &[u8],
+        signing_keys: &BackupSigningKeys,
+    ) -> Result<BackupArchive, String> {
+        let clear = serde_json::to_vec(content).map_err(|e| e.to_string())?;
+        let mut nonce = [0u8; 12];
+        OsRng.fill_bytes(&mut nonce);
+        let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(backup_key));
+        let encrypted = cipher
+            .encrypt(Nonce::from_slice(&nonce), clear.as_slice())
+            .map_err(|_| String::from("Failed to encrypt the backup"))?;
+
+        let mut archive = BackupArchive::new(
+            signing_keys.signing_key_id()?,
+            Utc::now(),
+            hex::encode(nonce),
+            general_purpose::STANDARD.encode(encrypted),
+        );
+        archive.signature = signing_keys.sign(&archive.signed_data())?;
+        Ok(archive)
+    }
+
+    /// ### Open
+    ///
+    /// check the signature of the archive with the key it records then decrypt it
+    pub fn open(
+        archive: &BackupArchive,
+        backup_key: &[u8],
+        signing_keys: &BackupSigningKeys,
+    ) -> Result<BackupContent, String> {
+        if archive.version != BACKUP_ARCHIVE_VERSION {
+            return Err(format!("Unsupported backup version {}", archive.version));
+        }
+        signing_keys.verify(&archive.key_id, &archive.signed_data(), &archive.signature)?;
+
+        let nonce = match hex::decode(&archive.nonce) {
+            Ok(nonce) if nonce.len() == 12 => nonce,
+            _ => return Err(String::from("Bad backup nonce")),
+        };
+        let encrypted = general_purpose::STANDARD
+            .decode(&archive.payload)
+            .map_err(|_| String::from("Bad backup payload"))?;
+        let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(backup_key));
+        let clear = cipher
+            .decrypt(Nonce::from_slice(&nonce), encrypted.as_slice())
+            .map_err(|_| String::from("Failed to decrypt the backup, check the BACKUP_KEY"))?;
+        serde_json::from_slice(&clear).map_err(|e| e.to_string())
+    }
+
+    fn get_backup_key() -> Result<Vec<u8>, String> {
+        let backup_key = env::var("BACKUP_KEY").map_err(|_| String::from("BACKUP_KEY is not set"))?;
+        match hex::decode(backup_key) {
+            Ok(key) if key.len() == 32 => Ok(key),
+            _ => Err(String::from("BACKUP_KEY must be a 32 bytes hex string")),
+        }
+    }
+}
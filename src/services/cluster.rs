@@ -4,24 +4,29 @@ use uuid::Uuid;
 use crate::{
     db::connect::DbPool,
     dto::{
+        access_request::dual_control_input::DualControlInput,
         cluster::{
             cluster_anonymous_sentinels_input::ClusterAnonymousSentinelsInput,
             cluster_input::ClusterInput, cluster_insertable::ClusterInsertable,
             cluster_memberships_input::ClusterMembershipsInput,
+            cluster_secrets_input::ClusterSecretsInput,
             cluster_sentinels_input::ClusterSentinelsInput,
         },
         x_anonymous_sentinel_cluster::x_anonymous_sentinel_cluster_insertable::XAnonymousSentinelClusterInsertable,
+        x_secret_cluster::x_secret_cluster_insertable::XSecretClusterInsertable,
         x_sentinel_cluster::x_sentinel_cluster_insertable::XSentinelClusterInsertable,
         x_user_cluster::x_user_cluster_insertable::XUserClusterInsertable,
     },
     enums::roles::Role,
     guards::security::Security,
-    models::{cluster::Cluster, user::User},
+    models::{cluster::Cluster, secret::Secret, user::User},
     repositories::{
         anonymous_sentinel::AnonymousSentinelRepository, cluster::ClusterRepository,
-        sentinel::SentinelRepository, user::UserRepository,
+        secret::SecretRepository, sentinel::SentinelRepository, user::UserRepository,
+    },
+    traits::{
+        application::ApplicationContract, connexion::ConnexionContract, secret::SecretContract,
     },
-    traits::{application::ApplicationContract, connexion::ConnexionContract},
 };
 
 pub struct ClusterService<T, D> {
@@ -32,6 +37,7 @@ pub struct ClusterService<T, D> {
     cluster_repository: ClusterRepository,
     sentinel_repository: SentinelRepository,
     anonymous_sentinel_repository: AnonymousSentinelRepository,
+    secret_repository: SecretRepository,
 }
 
 impl<T: ApplicationContract, D: ConnexionContract> ClusterService<T, D> {
@@ -44,6 +50,7 @@ impl<T: ApplicationContract, D: ConnexionContract> ClusterService<T, D> {
             cluster_repository: ClusterRepository::new(&pool),
             sentinel_repository: SentinelRepository::new(&pool),
             anonymous_sentinel_repository: AnonymousSentinelRepository::new(&pool),
+            secret_repository: SecretRepository::new(pool),
         }
     }
 
@@ -191,6 +198,45 @@ impl<T: ApplicationContract, D: ConnexionContract> ClusterService<T, D> {
         }
     }
 
+    /// ### Set dual control
+    ///
+    /// number of approvals needed to release the keys of the cluster,
+    /// only an admin can lower it
+    pub fn set_dual_control(
+        &self,
+        cluster_uuid: Uuid,
+        input: DualControlInput,
+        authorised: Security,
+    ) -> Result<Cluster, (Status, Option<&str>)> {
+        let user_from = authorised.user.clone();
+        let is_admin = authorised.check_roles(Role::ADMIN);
+        let cluster = match self
+            .cluster_repository
+            .get_by_id_and_application(&cluster_uuid, &user_from.application.unwrap())
+        {
+            None => return Err((Status::NotFound, None)),
+            Some(cluster) => cluster,
+        };
+        if !is_admin && cluster.created_by_id != Some(user_from.id) {
+            return Err((Status::Forbidden, None));
+        }
+        if input.required_approvals < 0 {
+            return Err((
+                Status::BadRequest,
+                Some("The number of approvals can not be negative"),
+            ));
+        }
+        if !is_admin && input.required_approvals < cluster.required_approvals {
+            return Err((
+                Status::Forbidden,
+                Some("Only an admin can lower the dual control of a key"),
+            ));
+        }
+        self.cluster_repository
+            .set_required_approvals(&cluster_uuid, input.required_approvals, &user_from)
+            .map_err(|_| (Status::NotFound, None))
+    }
+
     pub fn add_sentinels(
         &self,
         cluster_uuid: Uuid,
@@ -230,9 +276,10 @@ impl<T: ApplicationContract, D: ConnexionContract> ClusterService<T, D> {
                 Ok(uuid) => uuid,
             };
 
+            // an admin only shares the keys they can read, the others go through break-glass
             let sentinel = match self
                 .sentinel_repository
-                .get_sentinel_by_id(&sentinel_uuid, &user_from)
+                .get_member_sentinel_by_id(&sentinel_uuid, &user_from)
             {
                 None => continue,
                 Some(sentinel) => {
@@ -306,7 +353,7 @@ impl<T: ApplicationContract, D: ConnexionContract> ClusterService<T, D> {
 
             let anonymous_sentinel = match self
                 .anonymous_sentinel_repository
-                .get_anonymous_sentinel_by_id(&anonymous_sentinel_uuid, &user_from)
+                .get_member_anonymous_sentinel_by_id(&anonymous_sentinel_uuid, &user_from)
             {
                 None => continue,
                 Some(sentinel) => {
@@ -336,6 +383,82 @@ impl<T: ApplicationContract, D: ConnexionContract> ClusterService<T, D> {
         }
     }
 
+    pub fn add_secrets(
+        &self,
+        cluster_uuid: Uuid,
+        input: ClusterSecretsInput,
+        authorised: Security,
+    ) -> Result<Cluster, (Status, Option<&str>)> {
+        let user_from = authorised.user.clone();
+        let cluster = match self
+            .cluster_repository
+            .get_by_id_and_application(&cluster_uuid, &user_from.application.unwrap())
+        {
+            None => return Err((Status::NotFound, None)),
+            Some(cluster) => cluster,
+        };
+        match authorised.check_roles(Role::ADMIN) || cluster.created_by_id == Some(user_from.id) {
+            false => Err((Status::Forbidden, None)),
+            true => {
+                let is_admin = authorised.check_roles(Role::ADMIN);
+                for secret_id in input.secrets {
+                    let secret = match self.get_owned_secret(&secret_id, &user_from, is_admin) {
+                        None => continue,
+                        Some(secret) => secret,
+                    };
+                    if !self.cluster_repository.get_cluster_secrets(&cluster, &secret.id) {
+                        let insertable =
+                            XSecretClusterInsertable::new(cluster.id, secret.id, user_from.id);
+                        let _ = self.cluster_repository.add_secret_to_cluster(insertable);
+                    }
+                }
+                Ok(cluster)
+            }
+        }
+    }
+
+    pub fn remove_secrets(
+        &self,
+        cluster_uuid: Uuid,
+        input: ClusterSecretsInput,
+        authorised: Security,
+    ) -> Result<Cluster, (Status, Option<&str>)> {
+        let user_from = authorised.user.clone();
+        let cluster = match self
+            .cluster_repository
+            .get_by_id_and_application(&cluster_uuid, &user_from.application.unwrap())
+        {
+            None => return Err((Status::NotFound, None)),
+            Some(cluster) => cluster,
+        };
+        match authorised.check_roles(Role::ADMIN) || cluster.created_by_id == Some(user_from.id) {
+            false => Err((Status::Forbidden, None)),
+            true => {
+                let is_admin = authorised.check_roles(Role::ADMIN);
+                for secret_id in input.secrets {
+                    if let Some(secret) = self.get_owned_secret(&secret_id, &user_from, is_admin) {
+                        let _ = self
+                            .cluster_repository
+                            .remove_secret_from_cluster(&secret, &cluster, &user_from);
+                    }
+                }
+                Ok(cluster)
+            }
+        }
+    }
+
+    /// a secret can be shared by an admin or by the user who created it
+    fn get_owned_secret(&self, secret_id: &str, user_from: &User, is_admin: bool) -> Option<Secret> {
+        let secret_uuid = Uuid::parse_str(secret_id).ok()?;
+        let secret = self
+            .secret_repository
+            .get_secret_by_id(&secret_uuid, user_from)?;
+        match is_admin || secret.created_by_id == Some(user_from.id) {
+            false => None,
+            true => Some(secret),
+        }
+    }
+
     pub fn remove_sentinels(
         &self,
         cluster_uuid: Uuid,
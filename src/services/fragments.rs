@@ -27,15 +27,49 @@ impl FragmentsService {
             .unwrap_or_else(|_| format!("3"))
             .parse::<usize>()
             .unwrap();
-        let secret = encrypted_key.as_bytes();
-        let sharks = Sharks(trigger);
-        let fragment_generator = sharks.dealer(secret);
-        fragment_generator
-            .take(number_of_fragments)
-            .map(|fragment| hex::encode(Vec::from(&fragment as &Share)))
+        Self::split_secret(encrypted_key.as_bytes(), trigger, number_of_fragments)
+            .into_iter()
+            .map(hex::encode)
+            .collect()
+    }
+
+    /// Splits a secret into `number_of_shares` raw shares, any `threshold` of them rebuild it.
+    ///
+    /// # Arguments
+    ///
+    /// * `secret` - The bytes to split.
+    /// * `threshold` - The number of shares needed to recover the secret.
+    /// * `number_of_shares` - The number of shares to deal.
+    ///
+    /// # Returns
+    ///
+    /// A list of shares, the first byte of each share is its x coordinate.
+    pub fn split_secret(secret: &[u8], threshold: u8, number_of_shares: usize) -> Vec<Vec<u8>> {
+        let sharks = Sharks(threshold);
+        sharks
+            .dealer(secret)
+            .take(number_of_shares)
+            .map(|fragment| Vec::from(&fragment as &Share))
             .collect()
     }
 
+    /// Recovers a secret from raw shares dealt by `split_secret`.
+    ///
+    /// # Returns
+    ///
+    /// The secret bytes, or `None` if the shares are invalid or not enough.
+    pub fn recover_secret(shares: Vec<Vec<u8>>, threshold: u8) -> Option<Vec<u8>> {
+        let sharks = Sharks(threshold);
+        let shares: Vec<Share> = shares
+            .iter()
+            .filter_map(|bytes| Share::try_from(bytes.as_slice()).ok())
+            .collect();
+        if shares.len() < threshold as usize {
+            return None;
+        }
+        sharks.recover(&shares).ok()
+    }
+
     /// Reconstructs the encrypted key from fragments using Shamir's Secret Sharing scheme.
     ///
     /// # Arguments
@@ -51,24 +85,12 @@ impl FragmentsService {
             .unwrap_or_else(|_| "2".to_string())
             .parse::<u8>()
             .expect("Invalid threshold value");
-        let sharks = Sharks(threshold);
-        let shares: Vec<Share> = fragments
+        let shares: Vec<Vec<u8>> = fragments
             .into_iter()
-            .filter_map(|fragment| {
-                hex::decode(fragment)
-                    .ok()
-                    .and_then(|bytes| Share::try_from(bytes.as_slice()).ok())
-            })
+            .filter_map(|fragment| hex::decode(fragment).ok())
             .collect();
-        if shares.len() < threshold as usize {
-            return None;
-        }
-        let res = sharks
-            .recover(&shares)
-            .ok()
-            .map(|secret| String::from_utf8(secret).expect("Failed to convert secret to String"));
-
-        res
+        Self::recover_secret(shares, threshold)
+            .map(|secret| String::from_utf8(secret).expect("Failed to convert secret to String"))
     }
 
     pub fn save_fragments_to_nodes(
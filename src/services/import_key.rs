@@ -0,0 +1,163 @@
+use std::env;
+
+use chrono::Utc;
+use rocket::http::Status;
+use uuid::Uuid;
+
+use crate::{
+    core::nodes_config::NodesConfig,
+    db::connect::DbPool,
+    dto::{
+        import_key::import_key_insertable::ImportKeyInsertable,
+        sentinel::sentinel_import_input::SentinelImportInput,
+    },
+    enums::import_key_algorithm::ImportKeyAlgorithm,
+    models::{import_key::ImportKey, user::User},
+    repositories::import_key::ImportKeyRepository,
+    services::fragments::FragmentsService,
+    utils::{crypto::Crypto, key_import::KeyImport},
+};
+
+pub struct ImportKeyService {
+    nodes_config: NodesConfig,
+    import_key_repository: ImportKeyRepository,
+}
+
+impl ImportKeyService {
+    pub fn new(pool: &DbPool, nodes_config: &NodesConfig) -> Self {
+        Self {
+            nodes_config: nodes_config.clone(),
+            import_key_repository: ImportKeyRepository::new(pool),
+        }
+    }
+
+    /// ### Get or create
+    ///
+    /// the living import key of the user application for the algorithm,
+    /// a new key pair is generated when none is left, its private key
+    /// is kept like a sentinel key: encrypted with the master key and split on the nodes
+    pub fn get_or_create(
+        &self,
+        algorithm: &str,
+        user_from: &User,
+    ) -> Result<ImportKey, (Status, Option<&str>)> {
+        let algorithm = match ImportKeyAlgorithm::from_str(algorithm) {
+            None => return Err((Status::BadRequest, Some("Unknown import key algorithm"))),
+            Some(algorithm) => algorithm,
+        };
+        let application_id = user_from.application.unwrap();
+        if let Some(import_key) = self
+            .import_key_repository
+            .get_active_by_application(application_id, algorithm.as_str())
+        {
+            return Ok(import_key);
+        }
+        let (public, private) = match algorithm {
+            ImportKeyAlgorithm::RsaOaep256 => KeyImport::generate_rsa_key_pair(),
+            ImportKeyAlgorithm::MlKem768 => KeyImport::generate_ml_kem_768_key_pair(),
+        };
+        let duration = env::var("IMPORT_KEY_DURATION")
+            .unwrap_or_else(|_| "30".to_string())
+            .parse::<i64>()
+            .unwrap_or(30);
+        let expires_at = Utc::now() + chrono::Duration::days(duration);
+
+        let iv = Crypto::generate_unique_iv();
+        let encrypted = Crypto::encrypt(private, iv.clone());
+        let fragments = FragmentsService::generate_fragments(encrypted.clone());
+        let sum = Crypto::key_sum(&encrypted);
+        let insertable = ImportKeyInsertable::new(
+            algorithm.as_str().to_string(),
+            iv,
+            sum,
+            public,
+            expires_at,
+            application_id,
+            user_from.id,
+        );
+        let import_key = self.import_key_repository.create_import_key(insertable);
+        FragmentsService::save_fragments_to_nodes(
+            fragments,
+            import_key.id.to_string(),
+            &self.nodes_config,
+        );
+        Ok(import_key)
+    }
+
+    /// ### Unwrap
+    ///
+    /// decrypt the customer key sent with an import key of the user application
+    ///
+    /// # Returns
+    ///
+    /// the clear key bytes
+    pub fn unwrap(
+        &self,
+        input: &SentinelImportInput,
+        user_from: &User,
+    ) -> Result<Vec<u8>, (Status, Option<&str>)> {
+        let import_key_uuid = match Uuid::parse_str(&input.import_key_id) {
+            Err(_) => return Err((Status::BadRequest, Some("Bad import key uuid"))),
+            Ok(uuid) => uuid,
+        };
+        let import_key = match self
+            .import_key_repository
+            .get_active_by_id(&import_key_uuid, user_from.application.unwrap())
+        {
+            None => return Err((Status::NotFound, Some("Import key not found or expired"))),
+            Some(import_key) => import_key,
+        };
+        let fragments = FragmentsService::get_fragments_from_nodes(
+            import_key.id.to_string(),
+            &self.nodes_config,
+        );
+        let encrypted_private_key = match FragmentsService::reconstruct_encrypted_key(fragments) {
+            None => return Err((Status::NotFound, None)),
+            Some(encrypted_private_key) => encrypted_private_key,
+        };
+        let import_key = match import_key.check(encrypted_private_key.clone()) {
+            Err(e) => return Err((Status::NotAcceptable, Some(e))),
+            Ok(import_key) => import_key,
+        };
+        let private_key = Crypto::decrypt(encrypted_private_key, import_key.iv.clone());
+
+        let wrapped_key = match hex::decode(&input.wrapped_key) {
+            Err(_) => return Err((Status::BadRequest, Some("Bad wrapped key"))),
+            Ok(wrapped_key) => wrapped_key,
+        };
+        let key = match ImportKeyAlgorithm::from_str(&import_key.algorithm) {
+            Some(ImportKeyAlgorithm::RsaOaep256) => {
+                KeyImport::unwrap_rsa_oaep(&private_key, &wrapped_key)
+            }
+            Some(ImportKeyAlgorithm::MlKem768) => {
+                let encapsulated_key = input
+                    .encapsulated_key
+                    .as_ref()
+                    .and_then(|encapsulated_key| hex::decode(encapsulated_key).ok());
+                let nonce = input
+                    .nonce
+                    .as_ref()
+                    .and_then(|nonce| hex::decode(nonce).ok());
+                match (encapsulated_key, nonce) {
+                    (Some(encapsulated_key), Some(nonce)) => KeyImport::unwrap_ml_kem_768(
+                        &private_key,
+                        &encapsulated_key,
+                        &nonce,
+                        &wrapped_key,
+                    ),
+                    _ => {
+                        return Err((
+                            Status::BadRequest,
+                            Some("encapsulated_key and nonce are required with ML-KEM-768"),
+                        ))
+                    }
+                }
+            }
+            None => None,
+        };
+        match key {
+            None => Err((Status::BadRequest, Some("Unable to unwrap the key"))),
+            Some(key) => Ok(key),
+        }
+    }
+}
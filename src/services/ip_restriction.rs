@@ -0,0 +1,63 @@
+use std::net::IpAddr;
+
+use rocket::http::Status;
+
+use crate::{
+    db::connect::DbPool,
+    dto::audit_log::audit_log_insertable::AuditLogInsertable,
+    enums::{audit_action::AuditAction, audit_severity::AuditSeverity},
+    models::user::User,
+    repositories::application::ApplicationRepository,
+    services::audit::AuditService,
+    traits::application::ApplicationContract,
+    utils::cidr::Cidr,
+};
+
+type IpRestrictionError = (Status, Option<&'static str>);
+
+pub struct IpRestrictionService {
+    application_repository: ApplicationRepository,
+    audit_service: AuditService,
+}
+
+impl IpRestrictionService {
+    pub fn new(pool: &DbPool) -> Self {
+        Self {
+            application_repository: ApplicationContract::new(pool),
+            audit_service: AuditService::new(pool),
+        }
+    }
+
+    /// ### Check
+    ///
+    /// the IP must be in the restricted IPs of the user and in the allowlist of their
+    /// application, an empty list allows every IP. A denial is audited
+    pub fn check(&self, user: &User, ip: &IpAddr) -> Result<(), IpRestrictionError> {
+        let restricted_ip: Vec<String> = user.restricted_ip.iter().flatten().cloned().collect();
+        let allowed_ips = user
+            .application
+            .and_then(|app_id| self.application_repository.get_by_id(app_id))
+            .map(|application| application.allowed_ips)
+            .unwrap_or_default();
+        if Self::allows(&restricted_ip, ip) && Self::allows(&allowed_ips, ip) {
+            return Ok(());
+        }
+        let _ = self.audit_service.record(AuditLogInsertable::new(
+            user,
+            AuditAction::IpDenied,
+            AuditSeverity::High,
+            Some(("user", user.id)),
+            None,
+            Some(ip),
+        ));
+        Err((Status::Forbidden, Some("IP not allowed")))
+    }
+
+    /// an IP in one of the ranges, an empty list allows every IP and a malformed range matches none
+    pub fn allows(ranges: &[String], ip: &IpAddr) -> bool {
+        ranges.is_empty()
+            || ranges
+                .iter()
+                .any(|range| Cidr::parse(range).is_ok_and(|cidr| cidr.contains(ip)))
+    }
+}
@@ -0,0 +1,259 @@
+use std::net::IpAddr;
+
+use chrono::Utc;
+use rocket::http::Status;
+use uuid::Uuid;
+
+use crate::{
+    db::connect::DbPool,
+    dto::key_policy::{
+        key_policy_input::KeyPolicyInput, key_policy_insertable::KeyPolicyInsertable,
+    },
+    enums::key_operation::KeyOperation,
+    models::{key_policy::KeyPolicy, user::User},
+    repositories::key_policy::KeyPolicyRepository,
+    utils::cidr::Cidr,
+};
+
+type PolicyError = (Status, Option<&'static str>);
+
+pub struct KeyPolicyService {
+    key_policy_repository: KeyPolicyRepository,
+}
+
+impl KeyPolicyService {
+    pub fn new(pool: &DbPool) -> Self {
+        Self {
+            key_policy_repository: KeyPolicyRepository::new(pool),
+        }
+    }
+
+    pub fn get_for_sentinel(&self, sentinel_uuid: &Uuid) -> Option<KeyPolicy> {
+        self.key_policy_repository.get_by_sentinel_id(sentinel_uuid)
+    }
+
+    pub fn get_for_anonymous_sentinel(&self, anonymous_sentinel_uuid: &Uuid) -> Option<KeyPolicy> {
+        self.key_policy_repository
+            .get_by_anonymous_sentinel_id(anonymous_sentinel_uuid)
+    }
+
+    /// ### Set
+    ///
+    /// validate the policy and replace the active policy of a sentinel or of an anonymous sentinel,
+    /// only an admin can relax it
+    pub fn set(
+        &self,
+        sentinel_uuid: Option<Uuid>,
+        anonymous_sentinel_uuid: Option<Uuid>,
+        input: KeyPolicyInput,
+        user_from: &User,
+        is_admin: bool,
+    ) -> Result<KeyPolicy, PolicyError> {
+        let input = Self::validate(input)?;
+        let current = match (sentinel_uuid, anonymous_sentinel_uuid) {
+            (Some(sentinel_uuid), _) => self.get_for_sentinel(&sentinel_uuid),
+            (None, Some(anonymous_sentinel_uuid)) => {
+                self.get_for_anonymous_sentinel(&anonymous_sentinel_uuid)
+            }
+            (None, None) => None,
+        };
+        Self::check_change(current.as_ref(), Some(&input), is_admin)?;
+        let insertable =
+            KeyPolicyInsertable::new(sentinel_uuid, anonymous_sentinel_uuid, input, user_from.id);
+        self.key_policy_repository
+            .set_policy(insertable, user_from)
+            .map_err(|_| (Status::InternalServerError, None))
+    }
+
+    /// ### Delete
+    ///
+    /// remove the active policy of a key, only an admin can remove it
+    pub fn delete(
+        &self,
+        policy: Option<KeyPolicy>,
+        user_from: &User,
+        is_admin: bool,
+    ) -> Result<(), PolicyError> {
+        let policy = policy.ok_or((Status::NotFound, None))?;
+        Self::check_change(Some(&policy), None, is_admin)?;
+        match self.key_policy_repository.delete_policy(&policy.id, user_from) {
+            Ok(1) => Ok(()),
+            _ => Err((Status::NotFound, None)),
+        }
+    }
+
+    /// ### Check change
+    ///
+    /// a policy is replaced by `input`, removed when there is none. Anyone allowed to change the policy
+    /// can make it stricter, only an admin can remove it or lift one of its restrictions
+    pub fn check_change(
+        current: Option<&KeyPolicy>,
+        input: Option<&KeyPolicyInput>,
+        is_admin: bool,
+    ) -> Result<(), PolicyError> {
+        match (current, input) {
+            _ if is_admin => Ok(()),
+            (None, _) => Ok(()),
+            (Some(_), None) => Err((
+                Status::Forbidden,
+                Some("Only an admin can remove the policy of a key"),
+            )),
+            (Some(current), Some(input)) if Self::relaxes(current, input) => Err((
+                Status::Forbidden,
+                Some("Only an admin can relax the policy of a key"),
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    /// whether a validated policy allows something the current policy refuses
+    fn relaxes(current: &KeyPolicy, input: &KeyPolicyInput) -> bool {
+        let operations = input.operations.clone().unwrap_or_default();
+        if operations
+            .iter()
+            .any(|operation| !current.operations.contains(&Some(operation.clone())))
+        {
+            return true;
+        }
+        let current_cidrs: Vec<Cidr> = current
+            .allowed_cidrs
+            .iter()
+            .flatten()
+            .filter_map(|cidr| Cidr::parse(cidr).ok())
+            .collect();
+        let cidrs = input.allowed_cidrs.clone().unwrap_or_default();
+        if !current_cidrs.is_empty()
+            && (cidrs.is_empty()
+                || cidrs
+                    .iter()
+                    .filter_map(|cidr| Cidr::parse(cidr).ok())
+                    .any(|cidr| !current_cidrs.iter().any(|current| current.covers(&cidr))))
+        {
+            return true;
+        }
+        let days = input.allowed_days.clone().unwrap_or_default();
+        if !current.allowed_days.is_empty()
+            && (days.is_empty()
+                || days
+                    .iter()
+                    .any(|day| !current.allowed_days.contains(&Some(*day))))
+        {
+            return true;
+        }
+        let inside = |start: i32, end: i32, hour: i32| match start < end {
+            true => hour >= start && hour < end,
+            false => hour >= start || hour < end,
+        };
+        if let (Some(start), Some(end)) = (current.hour_start, current.hour_end) {
+            match (input.hour_start, input.hour_end) {
+                (Some(new_start), Some(new_end)) => {
+                    if (0..24)
+                        .any(|hour| inside(new_start, new_end, hour) && !inside(start, end, hour))
+                    {
+                        return true;
+                    }
+                }
+                _ => return true,
+            }
+        }
+        match (current.max_reads, input.max_reads) {
+            (Some(_), None) => true,
+            (Some(max_reads), Some(new_max_reads)) => new_max_reads > max_reads,
+            _ => false,
+        }
+    }
+
+    /// ### Enforce
+    ///
+    /// check an operation against the policy of a key, a key without policy is not restricted.
+    /// A granted read is counted against `max_reads`
+    pub fn enforce(
+        &self,
+        policy: Option<KeyPolicy>,
+        operation: KeyOperation,
+        ip: &IpAddr,
+    ) -> Result<(), PolicyError> {
+        let policy = match policy {
+            None => return Ok(()),
+            Some(policy) => policy,
+        };
+        if let Err(e) = policy.check(operation, ip, Utc::now()) {
+            return Err((Status::Forbidden, Some(e)));
+        }
+        if operation == KeyOperation::Read
+            && policy.max_reads.is_some()
+            && !self.key_policy_repository.consume_read(&policy.id)
+        {
+            return Err((
+                Status::Forbidden,
+                Some("The maximum number of reads of the key is reached"),
+            ));
+        }
+        Ok(())
+    }
+
+    fn validate(input: KeyPolicyInput) -> Result<KeyPolicyInput, PolicyError> {
+        let operations = match input.operations {
+            None => [
+                KeyOperation::Read,
+                KeyOperation::Encrypt,
+                KeyOperation::Decrypt,
+                KeyOperation::Delete,
+            ]
+            .iter()
+            .map(|operation| operation.as_str().to_string())
+            .collect(),
+            Some(operations) => {
+                let mut validated: Vec<String> = Vec::new();
+                for operation in operations {
+                    match KeyOperation::from_str(&operation) {
+                        None => {
+                            return Err((
+                                Status::BadRequest,
+                                Some("The operations must be `read`, `encrypt`, `decrypt` or `delete`"),
+                            ))
+                        }
+                        Some(operation) => {
+                            let operation = operation.as_str().to_string();
+                            if !validated.contains(&operation) {
+                                validated.push(operation);
+                            }
+                        }
+                    }
+                }
+                validated
+            }
+        };
+        let allowed_cidrs = input.allowed_cidrs.unwrap_or_default();
+        if allowed_cidrs.iter().any(|cidr| Cidr::parse(cidr).is_err()) {
+            return Err((Status::BadRequest, Some("Bad CIDR range")));
+        }
+        let allowed_days = input.allowed_days.unwrap_or_default();
+        if allowed_days.iter().any(|day| !(1..=7).contains(day)) {
+            return Err((
+                Status::BadRequest,
+                Some("The allowed days must be between 1 (monday) and 7 (sunday)"),
+            ));
+        }
+        match (input.hour_start, input.hour_end) {
+            (None, None) => {}
+            (Some(start), Some(end))
+                if (0..=23).contains(&start) && (0..=24).contains(&end) && start != end => {}
+            _ => {
+                return Err((
+                    Status::BadRequest,
+                    Some("The hour window needs a start (0-23) and a different end (0-24)"),
+                ))
+            }
+        }
+        if input.max_reads.is_some_and(|max_reads| max_reads < 1) {
+            return Err((Status::BadRequest, Some("The maximum number of reads must be positive")));
+        }
+        Ok(KeyPolicyInput {
+            operations: Some(operations),
+            allowed_cidrs: Some(allowed_cidrs),
+            allowed_days: Some(allowed_days),
+            ..input
+        })
+    }
+}
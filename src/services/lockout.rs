@@ -0,0 +1,110 @@
+use std::net::IpAddr;
+
+use chrono::{Duration, Utc};
+use rocket::http::Status;
+use uuid::Uuid;
+
+use crate::{
+    db::connect::DbPool,
+    dto::{
+        audit_log::audit_log_insertable::AuditLogInsertable,
+        lockout::locked_account_output::LockedAccountOutput,
+    },
+    enums::{attempt_scope::AttemptScope, audit_action::AuditAction, audit_severity::AuditSeverity},
+    models::user::User,
+    repositories::user::UserRepository,
+    services::audit::AuditService,
+    utils::rate_limit::RateLimit,
+};
+
+type LockoutError = (Status, Option<&'static str>);
+
+pub struct LockoutService {
+    user_repository: UserRepository,
+    audit_service: AuditService,
+}
+
+impl LockoutService {
+    pub fn new(pool: &DbPool) -> Self {
+        Self {
+            user_repository: UserRepository::new(pool),
+            audit_service: AuditService::new(pool),
+        }
+    }
+
+    /// ### Check
+    ///
+    /// refuse an attempt of a locked account or of a throttled IP
+    pub fn check(
+        &self,
+        scope: AttemptScope,
+        app_id: i32,
+        login: &str,
+        ip: &IpAddr,
+    ) -> Result<(), LockoutError> {
+        match RateLimit::retry_after(scope, app_id, login, ip) {
+            None => Ok(()),
+            Some(_) => Err((Status::TooManyRequests, Some("Too many attempts, try again later"))),
+        }
+    }
+
+    /// ### Fail
+    ///
+    /// count a failed attempt, the lock of a known user is audited
+    pub fn fail(&self, scope: AttemptScope, app_id: i32, login: &str, ip: &IpAddr, user: Option<&User>) {
+        let locked = RateLimit::record_failure(scope, app_id, login, ip);
+        if let (Some(_), Some(user)) = (locked, user) {
+            let _ = self.audit_service.record(AuditLogInsertable::new(
+                user,
+                AuditAction::AccountLocked,
+                AuditSeverity::High,
+                Some(("user", user.id)),
+                Some(scope.as_str().to_string()),
+                Some(ip),
+            ));
+        }
+    }
+
+    pub fn succeed(&self, scope: AttemptScope, app_id: i32, login: &str) {
+        RateLimit::reset(scope, app_id, login);
+    }
+
+    /// ### Get locked
+    ///
+    /// the locked users of the application of the admin
+    pub fn get_locked(&self, user_from: &User) -> Result<Vec<LockedAccountOutput>, LockoutError> {
+        let app_id = user_from.application.ok_or((Status::Unauthorized, None))?;
+        Ok(RateLimit::get_locked(app_id)
+            .into_iter()
+            .filter_map(|(scope, login, retry_after)| {
+                let user = self.user_repository.get_by_login_and_app(&login, app_id)?;
+                Some(LockedAccountOutput::new(
+                    &user,
+                    scope,
+                    Utc::now() + Duration::seconds(retry_after as i64),
+                ))
+            })
+            .collect())
+    }
+
+    /// ### Unlock
+    ///
+    /// lift the locks of a user of the application of the admin, audited
+    pub fn unlock(&self, user_uuid: &Uuid, user_from: &User, ip: &IpAddr) -> Result<(), LockoutError> {
+        let user_to = user_from
+            .application
+            .and_then(|app_id| self.user_repository.get_by_id_and_app(user_uuid, app_id))
+            .ok_or((Status::NotFound, Some("User not found")))?;
+        RateLimit::unlock(user_to.application.unwrap_or_default(), &user_to.login)
+            .map_err(|_| (Status::ServiceUnavailable, None))?;
+        self.audit_service.record(AuditLogInsertable::new(
+            user_from,
+            AuditAction::AccountUnlocked,
+            AuditSeverity::Medium,
+            Some(("user", user_to.id)),
+            None,
+            Some(ip),
+        ))?;
+        Ok(())
+    }
+}
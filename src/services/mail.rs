@@ -129,4 +129,42 @@ impl MailService {
 
         Self::send_email(message).await;
     }
+
+    /// alert an admin of a break-glass read, the user input is escaped before it is put in the template
+    pub async fn send_break_glass_alert(
+        email: &str,
+        login: &str,
+        requester: &str,
+        key: &str,
+        justification: &str,
+        ip: &str,
+    ) {
+        let date = Utc::now().format("%Y-%m-%d %H:%M:%S").to_string();
+        let template = fs::read_to_string("templates/break_glass.html")
+            .unwrap()
+            .replace("{{var:Date:\"\"}}", &date)
+            .replace("{{var:Login:\"\"}}", login)
+            .replace("{{var:Requester:\"\"}}", &Self::escape_html(requester))
+            .replace("{{var:Key:\"\"}}", key)
+            .replace("{{var:Ip:\"\"}}", ip)
+            .replace("{{var:Justification:\"\"}}", &Self::escape_html(justification));
+        let smtp_from_name = env::var("SMTP_FROM_NAME").unwrap();
+        let smtp_from = env::var("SMTP_FROM").unwrap();
+        let message = MessageBuilder::new()
+            .from((smtp_from_name.as_str(), smtp_from.as_str()))
+            .to(vec![(login, email)])
+            .subject("Hb Cyber_core: Break-Glass Access to a Key")
+            .html_body(template);
+
+        Self::send_email(message).await;
+    }
+
+    fn escape_html(value: &str) -> String {
+        value
+            .replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+            .replace('"', "&quot;")
+            .replace('\'', "&#39;")
+    }
 }
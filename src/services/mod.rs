@@ -12,4 +12,18 @@ pub mod fragments;
 pub mod sentinel_log;
 pub mod anonymous_sentinel;
 pub mod licence;
-pub mod system;
\ No newline at end of file
+pub mod system;
+pub mod backup;
+pub mod recovery_kit;
+pub mod import_key;
+pub mod secret;
+pub mod key_policy;
+pub mod access_request;
+pub mod audit;pub mod service_account;
+
+pub mod signing_key;
+pub mod token;
+pub mod session;
+pub mod webauthn;
+pub mod lockout;
+pub mod ip_restriction;
\ No newline at end of file
@@ -1,93 +1,371 @@
+use std::env;
+
+use chrono::{Duration, Utc};
+use rand::Rng;
 use rocket::http::Status;
 use uuid::Uuid;
 
 use crate::{
     db::connect::DbPool,
-    dto::{auth::auth_output::AuthOutput, oauth::oauth_token_input::OauthTokenInput},
-    models::user::User,
-    repositories::user::UserRepository,
-    traits::{
-        application::ApplicationContract, connexion::ConnexionContract,
-        revoked_token::RevokedTokenContract,
+    dto::oauth::{
+        oauth_access_token_output::OauthAccessTokenOutput,
+        oauth_authorize_output::OauthAuthorizeOutput, oauth_client_input::OauthClientInput,
+        oauth_client_insertable::OauthClientInsertable,
+        oauth_code_insertable::{OauthCodeGrant, OauthCodeInsertable}, oauth_token_input::OauthTokenInput,
+    },
+    models::{oauth_client::OauthClient, user::User},
+    repositories::{
+        application::ApplicationRepository, oauth_client::OauthClientRepository,
+        user::UserRepository,
+    },
+    traits::application::ApplicationContract,
+    utils::{
+        code::{generate_token, hash_token},
+        jwt::Jwt,
+        oauth::{Oauth, CODE_CHALLENGE_METHOD},
+        open_id::{Authentication, OpenId},
     },
-    utils::oauth::Oauth,
 };
 
-use super::auth::AuthService;
+/// the status, the RFC 6749 error code and its description
+pub type OauthServiceError = (Status, &'static str, Option<&'static str>);
+
+type OauthClientError = (Status, Option<&'static str>);
 
-pub struct OauthService<T, D, C> {
+/// lifetime of an authorization code in seconds, it is exchanged right after the redirect
+const AUTHORIZATION_CODE_TTL: i64 = 60;
+
+const MAX_REDIRECT_URIS: usize = 10;
+
+pub struct OauthService {
+    oauth_client_repository: OauthClientRepository,
     user_repository: UserRepository,
-    application_repository: D,
-    auth_service: AuthService<T, D, C>,
+    application_repository: ApplicationRepository,
 }
 
-impl<T: RevokedTokenContract, D: ApplicationContract, C: ConnexionContract> OauthService<T, D, C> {
-    pub fn new(
-        pool: &DbPool,
-        application_repository: D,
-        auth_service: AuthService<T, D, C>,
-    ) -> Self {
+impl OauthService {
+    pub fn new(pool: &DbPool) -> Self {
         Self {
+            oauth_client_repository: OauthClientRepository::new(pool),
             user_repository: UserRepository::new(pool),
-            application_repository,
-            auth_service,
+            application_repository: ApplicationContract::new(pool),
+        }
+    }
+
+    /// ### Create client
+    ///
+    /// register a client of the application of the admin, a confidential client
+    /// gets a generated secret, returned once
+    pub fn create_client(
+        &self,
+        input: OauthClientInput,
+        user_from: &User,
+    ) -> Result<(OauthClient, Option<String>), OauthClientError> {
+        let name = input.name.trim();
+        if name.is_empty() || name.chars().count() > 100 {
+            return Err((Status::BadRequest, Some("Bad client name")));
+        }
+        if input.redirect_uris.is_empty() || input.redirect_uris.len() > MAX_REDIRECT_URIS {
+            return Err((
+                Status::BadRequest,
+                Some("A client needs between 1 and 10 redirect uris"),
+            ));
+        }
+        if !input.redirect_uris.iter().all(|uri| Oauth::is_redirect_uri(uri)) {
+            return Err((
+                Status::BadRequest,
+                Some("The redirect uris must be absolute https, loopback http or private-use scheme uris without fragment"),
+            ));
+        }
+        let app_id = user_from.application.ok_or((Status::Forbidden, None))?;
+        let secret = match input.is_public.unwrap_or(false) {
+            true => None,
+            false => Some(generate_token()),
+        };
+        let client_id = hex::encode(rand::thread_rng().gen::<[u8; 16]>());
+        let insertable = OauthClientInsertable::new(
+            app_id,
+            name.to_string(),
+            client_id,
+            secret.as_deref().map(hash_token),
+            input.redirect_uris,
+            user_from.id,
+        );
+        match self.oauth_client_repository.create_oauth_client(insertable) {
+            Ok(oauth_client) => Ok((oauth_client, secret)),
+            Err(_) => Err((Status::InternalServerError, None)),
         }
     }
 
+    pub fn get_clients(&self, user_from: &User) -> Vec<OauthClient> {
+        match user_from.application {
+            None => Vec::new(),
+            Some(app_id) => self.oauth_client_repository.get_by_application(app_id),
+        }
+    }
+
+    /// ### Rotate secret
+    ///
+    /// replace the secret of a confidential client, the previous one stops working at once
+    pub fn rotate_secret(
+        &self,
+        oauth_client_uuid: &Uuid,
+        user_from: &User,
+    ) -> Result<(OauthClient, Option<String>), OauthClientError> {
+        let oauth_client = self.get_client(oauth_client_uuid, user_from)?;
+        if oauth_client.client_secret_hash.is_none() {
+            return Err((Status::BadRequest, Some("A public client has no secret")));
+        }
+        let secret = generate_token();
+        match self.oauth_client_repository.update_secret(
+            &oauth_client.id,
+            Some(hash_token(&secret)),
+            user_from,
+        ) {
+            Ok(oauth_client) => Ok((oauth_client, Some(secret))),
+            Err(_) => Err((Status::InternalServerError, None)),
+        }
+    }
+
+    pub fn delete_client(
+        &self,
+        oauth_client_uuid: &Uuid,
+        user_from: &User,
+    ) -> Result<(), OauthClientError> {
+        let oauth_client = self.get_client(oauth_client_uuid, user_from)?;
+        match self.oauth_client_repository.delete(&oauth_client.id, user_from) {
+            Ok(_) => Ok(()),
+            Err(_) => Err((Status::InternalServerError, None)),
+        }
+    }
+
+    /// ### Authorize
+    ///
+    /// issue a single use code to a client of the application of the user.
+    /// The errors found once the client and its redirect uri are validated
+    /// come with the callback of the client carrying them
+    #[allow(clippy::too_many_arguments)]
     pub fn authorize(
         &self,
-        user_from: User,
-        user_id: Uuid,
-        state: String,
-    ) -> Result<String, (Status, Option<&str>)> {
-        let user = match self.user_repository.get_by_id(&user_id) {
-            None => return Err((Status::NotFound, Some("User not found"))),
-            Some(user) => user,
+        user_from: &User,
+        response_type: &str,
+        client_id: &str,
+        redirect_uri: Option<String>,
+        state: Option<String>,
+        code_challenge: Option<String>,
+        code_challenge_method: Option<String>,
+        scope: Option<String>,
+        nonce: Option<String>,
+        authentication: &Authentication,
+    ) -> Result<OauthAuthorizeOutput, (OauthServiceError, Option<String>)> {
+        let oauth_client = match self.oauth_client_repository.get_by_client_id(client_id) {
+            Some(oauth_client) if user_from.application == Some(oauth_client.application_id) => {
+                oauth_client
+            }
+            _ => {
+                return Err((
+                    (Status::BadRequest, "invalid_request", Some("Unknown client_id")),
+                    None,
+                ))
+            }
         };
-        match user_from.application == user.application {
-            false => Err((Status::Forbidden, None)),
-            true => {
-                let authorize_code = Oauth::create(user, state);
-                Ok(authorize_code)
+        let redirect_uri = match redirect_uri {
+            Some(redirect_uri) if oauth_client.has_redirect_uri(&redirect_uri) => redirect_uri,
+            None if oauth_client.redirect_uris.len() == 1 => {
+                oauth_client.redirect_uris[0].clone().unwrap_or_default()
             }
+            _ => {
+                return Err((
+                    (
+                        Status::BadRequest,
+                        "invalid_request",
+                        Some("The redirect_uri is not registered for this client"),
+                    ),
+                    None,
+                ))
+            }
+        };
+
+        let redirect_error = |error: OauthServiceError| {
+            let mut params = vec![("error", error.1)];
+            if let Some(description) = error.2 {
+                params.push(("error_description", description));
+            }
+            if let Some(state) = state.as_deref() {
+                params.push(("state", state));
+            }
+            Err((error, Some(Oauth::redirect_location(&redirect_uri, &params))))
+        };
+        if response_type != "code" {
+            return redirect_error((Status::BadRequest, "unsupported_response_type", None));
+        }
+        let code_challenge = match code_challenge {
+            Some(code_challenge) if Oauth::is_code_challenge(&code_challenge) => code_challenge,
+            _ => {
+                return redirect_error((
+                    Status::BadRequest,
+                    "invalid_request",
+                    Some("A S256 code_challenge is required"),
+                ))
+            }
+        };
+        if code_challenge_method.as_deref() != Some(CODE_CHALLENGE_METHOD) {
+            return redirect_error((
+                Status::BadRequest,
+                "invalid_request",
+                Some("The code_challenge_method must be S256"),
+            ));
+        }
+        if scope.as_ref().is_some_and(|scope| scope.len() > 1000) {
+            return redirect_error((Status::BadRequest, "invalid_scope", None));
+        }
+        if nonce.as_ref().is_some_and(|nonce| nonce.len() > 255) {
+            return redirect_error((Status::BadRequest, "invalid_request", Some("The nonce is too long")));
+        }
+
+        let code = generate_token();
+        let insertable = OauthCodeInsertable::new(
+            oauth_client.id,
+            user_from.id,
+            hash_token(&code),
+            OauthCodeGrant {
+                redirect_uri: redirect_uri.clone(),
+                code_challenge,
+                scope,
+                nonce,
+            },
+            authentication,
+            Utc::now() + Duration::seconds(AUTHORIZATION_CODE_TTL),
+        );
+        if self.oauth_client_repository.create_code(insertable).is_err() {
+            return redirect_error((Status::InternalServerError, "server_error", None));
+        }
+        let mut params = vec![("code", code.as_str())];
+        if let Some(state) = state.as_deref() {
+            params.push(("state", state));
         }
+        let location = Oauth::redirect_location(&redirect_uri, &params);
+        Ok(OauthAuthorizeOutput::new(code, state, location))
     }
 
-    pub async fn check(
+    /// ### Exchange code
+    ///
+    /// the authorization code grant: a code is consumed by its first exchange,
+    /// even when the exchange fails
+    pub async fn exchange_code(
         &self,
         input: OauthTokenInput,
-        user_agent: String,
-        ip: String,
-    ) -> Result<AuthOutput, (Status, Option<&str>)> {
-        match Oauth::check(input.authorization_code, input.state).await {
-            Err(e) => Err(e),
-            Ok(oauth) => {
-                let user = match self.user_repository.get_by_id(&oauth.id) {
-                    None => return Err((Status::NotFound, None)),
-                    Some(user) => user,
-                };
-                let application = match self
-                    .application_repository
-                    .get_by_id(user.application.unwrap().clone())
-                {
-                    None => return Err((Status::NotFound, None)),
-                    Some(application) => application,
-                };
-                match self
-                    .auth_service
-                    .check_otp(&user, input.code_2fa, &ip, &input.fingerprint, &user_agent)
-                    .await
-                {
-                    Err((status, msg)) => Err((status, msg)),
-                    Ok(()) => {
-                        let creds = self
-                            .auth_service
-                            .generate_creds(&user, &application, &input.fingerprint, &user_agent)
-                            .await;
-                        Ok(creds)
-                    }
+    ) -> Result<OauthAccessTokenOutput, OauthServiceError> {
+        let invalid_grant = (Status::BadRequest, "invalid_grant", None);
+        let oauth_client = self.authenticate_client(input.client_id, input.client_secret)?;
+        let (code, redirect_uri, code_verifier) =
+            match (input.code, input.redirect_uri, input.code_verifier) {
+                (Some(code), Some(redirect_uri), Some(code_verifier)) => {
+                    (code, redirect_uri, code_verifier)
                 }
+                _ => {
+                    return Err((
+                        Status::BadRequest,
+                        "invalid_request",
+                        Some("code, redirect_uri and code_verifier are required"),
+                    ))
+                }
+            };
+
+        let oauth_code = self
+            .oauth_client_repository
+            .consume_code(&hash_token(&code))
+            .ok_or(invalid_grant)?;
+        if oauth_code.oauth_client_id != oauth_client.id
+            || oauth_code.redirect_uri != redirect_uri
+            || !Oauth::verify_code_challenge(&code_verifier, &oauth_code.code_challenge)
+        {
+            return Err(invalid_grant);
+        }
+        let user = self
+            .user_repository
+            .get_by_id(&oauth_code.user_id)
+            .ok_or(invalid_grant)?;
+        let application = self
+            .application_repository
+            .get_by_id(oauth_client.application_id)
+            .ok_or(invalid_grant)?;
+
+        let authentication = Authentication {
+            auth_time: oauth_code.auth_time.timestamp(),
+            amr: oauth_code.amr.into_iter().flatten().collect(),
+        };
+        let duration = Self::token_duration();
+        let access_token = Jwt::create_oauth_jwt(
+            &user,
+            &application,
+            &oauth_client.client_id,
+            oauth_code.scope.clone(),
+            &authentication,
+            duration,
+        )
+        .await;
+        let mut output = OauthAccessTokenOutput::new(access_token, duration, oauth_code.scope.clone());
+        if Oauth::has_scope(oauth_code.scope.as_deref(), "openid") {
+            output.id_token = Some(
+                OpenId::create(
+                    &user,
+                    oauth_client.client_id,
+                    &authentication,
+                    oauth_code.nonce,
+                    oauth_code.scope.as_deref(),
+                )
+                .await,
+            );
+        }
+        Ok(output)
+    }
+
+    /// ### Authenticate client
+    ///
+    /// a confidential client with its secret, a public client with its client id alone
+    pub fn authenticate_client(
+        &self,
+        client_id: Option<String>,
+        client_secret: Option<String>,
+    ) -> Result<OauthClient, OauthServiceError> {
+        let invalid_client = (Status::Unauthorized, "invalid_client", None);
+        let client_id = client_id.ok_or((
+            Status::BadRequest,
+            "invalid_request",
+            Some("Missing client_id"),
+        ))?;
+        let oauth_client = self
+            .oauth_client_repository
+            .get_by_client_id(&client_id)
+            .ok_or(invalid_client)?;
+        match (&oauth_client.client_secret_hash, client_secret) {
+            (None, None) => Ok(oauth_client),
+            (Some(secret_hash), Some(secret))
+                if openssl::memcmp::eq(hash_token(&secret).as_bytes(), secret_hash.as_bytes()) =>
+            {
+                Ok(oauth_client)
             }
+            _ => Err(invalid_client),
         }
     }
+
+    fn get_client(
+        &self,
+        oauth_client_uuid: &Uuid,
+        user_from: &User,
+    ) -> Result<OauthClient, OauthClientError> {
+        let app_id = user_from.application.ok_or((Status::Forbidden, None))?;
+        self.oauth_client_repository
+            .get_by_id_and_app(oauth_client_uuid, app_id)
+            .ok_or((Status::NotFound, Some("Client not found")))
+    }
+
+    /// lifetime of the access tokens issued to the clients, `OAUTH_TOKEN_DURATION` in seconds
+    fn token_duration() -> i64 {
+        env::var("OAUTH_TOKEN_DURATION")
+            .unwrap_or_else(|_| "900".to_string())
+            .parse::<i64>()
+            .unwrap_or(900)
+    }
 }
@@ -1,8 +1,16 @@
 use rocket::http::Status;
 
 use crate::{
-    db::connect::DbPool, models::user::User, repositories::user::UserRepository,
-    utils::open_id::OpenId,
+    db::connect::DbPool,
+    dto::oidc::{
+        jwks_output::{JwkOutput, JwksOutput},
+        openid_configuration_output::OpenidConfigurationOutput,
+        userinfo_output::UserinfoOutput,
+    },
+    guards::security::Security,
+    models::user::User,
+    repositories::user::UserRepository,
+    utils::{key_ring::KeyRing, oauth::Oauth, open_id::OpenId},
 };
 
 pub struct OidcService {
@@ -33,4 +41,35 @@ impl OidcService {
             Some(user) => Ok(user),
         }
     }
+
+    pub fn configuration(&self) -> OpenidConfigurationOutput {
+        OpenidConfigurationOutput::new(OpenId::issuer())
+    }
+
+    /// every trusted key, the keys waiting for their activation included
+    pub fn jwks(&self) -> JwksOutput {
+        JwksOutput {
+            keys: KeyRing::keys()
+                .into_iter()
+                .map(|key| JwkOutput::rsa(key.kid, key.n, key.e))
+                .collect(),
+        }
+    }
+
+    /// ### Userinfo
+    ///
+    /// the claims of the user of an access token, an OAuth client needs the `openid` scope
+    /// and only gets the claims allowed by its scope
+    pub fn userinfo(&self, authorised: &Security) -> Result<UserinfoOutput, (Status, &'static str)> {
+        match &authorised.oauth_client_id {
+            None => Ok(UserinfoOutput::new(&authorised.user, None)),
+            Some(_) => match Oauth::has_scope(authorised.oauth_scope.as_deref(), "openid") {
+                false => Err((Status::Forbidden, "insufficient_scope")),
+                true => Ok(UserinfoOutput::new(
+                    &authorised.user,
+                    authorised.oauth_scope.as_deref(),
+                )),
+            },
+        }
+    }
 }
@@ -0,0 +1,185 @@
+use chrono::Utc;
+use uuid::Uuid;
+
+use crate::{
+    core::nodes_config::NodesConfig,
+    db::connect::DbPool,
+    enums::sentinel_purpose::SentinelPurpose,
+    models::sentinel::Sentinel,
+    repositories::sentinel::SentinelRepository,
+    services::fragments::FragmentsService,
+    traits::application::ApplicationContract,
+    utils::{crypto::Crypto, recovery_share::RecoveryShare},
+};
+
+pub struct RecoveryKitService<T> {
+    nodes_config: NodesConfig,
+    sentinel_repository: SentinelRepository,
+    application_repository: T,
+}
+
+impl<T: ApplicationContract> RecoveryKitService<T> {
+    pub fn new(pool: &DbPool, application_repository: T, nodes_config: &NodesConfig) -> Self {
+        Self {
+            nodes_config: nodes_config.clone(),
+            sentinel_repository: SentinelRepository::new(pool),
+            application_repository,
+        }
+    }
+
+    /// ### Export
+    ///
+    /// rebuild the sentinel key from the nodes and split the clear key
+    /// into `number_of_shares` paper shares, any `threshold` of them rebuild it
+    /// without the nodes nor the master key
+    pub fn export(
+        &self,
+        sentinel_uuid: Uuid,
+        threshold: u8,
+        number_of_shares: u8,
+    ) -> Result<Vec<RecoveryShare>, String> {
+        if threshold < 2 || threshold > number_of_shares {
+            return Err(String::from(
+                "The threshold must be at least 2 and lower or equal to the number of shares",
+            ));
+        }
+        let sentinel = match self.sentinel_repository.find_by_id(&sentinel_uuid) {
+            Some(sentinel) if !sentinel.is_deleted => sentinel,
+            _ => return Err(format!("Sentinel {} not found", sentinel_uuid)),
+        };
+        if sentinel.purpose == SentinelPurpose::Mac.as_str() {
+            return Err(String::from("MAC keys can not be exported"));
+        }
+        let fragments =
+            FragmentsService::get_fragments_from_nodes(sentinel.id.to_string(), &self.nodes_config);
+        let encrypted_key = match FragmentsService::reconstruct_encrypted_key(fragments) {
+            None => return Err(String::from("Unable to rebuild the key from the nodes")),
+            Some(encrypted_key) => encrypted_key,
+        };
+        let sentinel = sentinel
+            .check(encrypted_key.clone())
+            .map_err(|e| e.to_string())?;
+        let key = match hex::decode(Crypto::decrypt(encrypted_key, sentinel.iv.clone())) {
+            Ok(key) if !key.is_empty() => key,
+            _ => return Err(String::from("Unable to decrypt the key")),
+        };
+        let shares = FragmentsService::split_secret(&key, threshold, number_of_shares as usize)
+            .into_iter()
+            .map(|share| RecoveryShare {
+                sentinel_id: sentinel.id,
+                application_id: sentinel.application_id,
+                key_size: sentinel.key_size as u16,
+                threshold,
+                number_of_shares,
+                purpose: SentinelPurpose::from_str(&sentinel.purpose)
+                    .unwrap_or(SentinelPurpose::Encryption),
+                share,
+            })
+            .collect();
+        Ok(shares)
+    }
+
+    /// ### Import
+    ///
+    /// rebuild the clear key from the custodian shares and re-create the sentinel
+    /// under its original id, the key then follows the same path as a new sentinel:
+    /// encryption with the master key, fragments dealt to the nodes and sum
+    pub fn import(&self, encoded_shares: Vec<String>) -> Result<Sentinel, String> {
+        let shares = encoded_shares
+            .iter()
+            .map(|encoded| RecoveryShare::decode(encoded))
+            .collect::<Result<Vec<RecoveryShare>, &str>>()
+            .map_err(|e| e.to_string())?;
+        let first = match shares.first() {
+            None => return Err(String::from("No share given")),
+            Some(first) => first.clone(),
+        };
+        if shares.iter().any(|share| {
+            share.sentinel_id != first.sentinel_id
+                || share.application_id != first.application_id
+                || share.key_size != first.key_size
+                || share.threshold != first.threshold
+                || share.purpose != first.purpose
+        }) {
+            return Err(String::from("The shares do not belong to the same recovery kit"));
+        }
+        if shares.len() < first.threshold as usize {
+            return Err(format!("{} shares are needed", first.threshold));
+        }
+        let key = match FragmentsService::recover_secret(
+            shares.into_iter().map(|share| share.share).collect(),
+            first.threshold,
+        ) {
+            Some(key) if key.len() * 8 == first.key_size as usize => key,
+            _ => return Err(String::from("Unable to rebuild the key from the shares")),
+        };
+
+        let existing = self.sentinel_repository.find_by_id(&first.sentinel_id);
+        if let Some(existing) = existing.clone() {
+            if !existing.is_deleted {
+                return Err(format!("Sentinel {} still exists", existing.id));
+            }
+        }
+        if self
+            .application_repository
+            .get_by_id(first.application_id)
+            .is_none()
+        {
+            return Err(format!("Application {} not found", first.application_id));
+        }
+
+        let iv = Crypto::generate_unique_iv();
+        let encrypted = Crypto::encrypt(hex::encode(key), iv.clone());
+        let fragments = FragmentsService::generate_fragments(encrypted.clone());
+        let sum = Crypto::key_sum(&encrypted);
+        let sentinel = self.sentinel_repository.restore_sentinel(Self::restored_sentinel(
+            &first,
+            existing.as_ref(),
+            iv,
+            sum,
+        ));
+        FragmentsService::save_fragments_to_nodes(
+            fragments,
+            sentinel.id.to_string(),
+            &self.nodes_config,
+        );
+        let _ = self
+            .application_repository
+            .increment_keys(&sentinel.application_id);
+        Ok(sentinel)
+    }
+
+    /// the sentinel row re-created from a kit, a sentinel deleted since keeps its purpose,
+    /// its dual control and its creation
+    pub fn restored_sentinel(
+        share: &RecoveryShare,
+        existing: Option<&Sentinel>,
+        iv: String,
+        sum: String,
+    ) -> Sentinel {
+        Sentinel {
+            id: share.sentinel_id,
+            application_id: share.application_id,
+            iv,
+            sum,
+            is_deleted: false,
+            created_at: existing
+                .map(|existing| existing.created_at)
+                .unwrap_or(Utc::now()),
+            updated_at: None,
+            deleted_at: None,
+            created_by_id: existing.and_then(|existing| existing.created_by_id),
+            updated_by_id: None,
+            deleted_by_id: None,
+            key_size: share.key_size as i32,
+            purpose: existing
+                .map(|existing| existing.purpose.clone())
+                .unwrap_or(share.purpose.as_str().to_string()),
+            required_approvals: existing
+                .map(|existing| existing.required_approvals)
+                .unwrap_or(0),
+            break_glass_at: existing.and_then(|existing| existing.break_glass_at),
+            break_glass_by_id: existing.and_then(|existing| existing.break_glass_by_id),
+        }
+    }
+}
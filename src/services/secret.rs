@@ -0,0 +1,231 @@
+use diesel::result::{DatabaseErrorKind, Error};
+use rocket::http::Status;
+use uuid::Uuid;
+
+use crate::{
+    core::nodes_config::NodesConfig,
+    dto::secret::{
+        secret_input::SecretInput, secret_insertable::SecretInsertable,
+        secret_version_input::SecretVersionInput,
+    },
+    models::{secret::Secret, secret_version::SecretVersion, user::User},
+    services::fragments::FragmentsService,
+    traits::secret::SecretContract,
+    utils::crypto::Crypto,
+};
+
+/// the largest value accepted for a secret (64 KiB)
+const SECRET_MAX_SIZE: usize = 64 * 1024;
+
+pub struct SecretService<T> {
+    nodes_config: NodesConfig,
+    secret_repository: T,
+}
+
+impl<T: SecretContract> SecretService<T> {
+    pub fn new(secret_repository: T, nodes_config: &NodesConfig) -> Self {
+        Self {
+            nodes_config: nodes_config.clone(),
+            secret_repository,
+        }
+    }
+
+    /// ### Create
+    ///
+    /// store the first version of a named secret and attach it to the clusters of the user
+    pub fn create(
+        &self,
+        input: SecretInput,
+        user_from: User,
+    ) -> Result<Secret, (Status, Option<&str>)> {
+        let name = input.name.trim().to_string();
+        if name.is_empty() || name.len() > 255 {
+            return Err((
+                Status::BadRequest,
+                Some("The secret name must be between 1 and 255 characters"),
+            ));
+        }
+        Self::check_value(&input.value)?;
+        let application_id = user_from.application.unwrap();
+        if self
+            .secret_repository
+            .get_by_name(&name, application_id)
+            .is_some()
+        {
+            return Err((Status::Conflict, Some("A secret with this name already exists")));
+        }
+
+        let (iv, sum, encrypted) = Self::encrypt_value(&input.value);
+        let insertable = SecretInsertable::new(name, application_id, user_from.id);
+        let (secret, secret_version) = match self
+            .secret_repository
+            .create_secret(insertable, iv, sum)
+        {
+            Err(Error::DatabaseError(DatabaseErrorKind::UniqueViolation, _)) => {
+                return Err((Status::Conflict, Some("A secret with this name already exists")))
+            }
+            Err(_) => return Err((Status::InternalServerError, None)),
+            Ok(res) => res,
+        };
+        FragmentsService::save_fragments_to_nodes(
+            FragmentsService::generate_fragments(encrypted),
+            secret_version.id.to_string(),
+            &self.nodes_config,
+        );
+
+        for cluster_id in input.clusters {
+            let cluster_uuid = match Uuid::parse_str(&cluster_id) {
+                Err(_) => continue,
+                Ok(uuid) => uuid,
+            };
+            self.secret_repository
+                .add_to_user_cluster(&cluster_uuid, &secret, &user_from);
+        }
+        Ok(secret)
+    }
+
+    /// ### Add version
+    ///
+    /// store a new value for the secret, the previous versions stay readable
+    pub fn add_version(
+        &self,
+        secret_uuid: Uuid,
+        input: SecretVersionInput,
+        user_from: User,
+    ) -> Result<Secret, (Status, Option<&str>)> {
+        Self::check_value(&input.value)?;
+        let secret = match self
+            .secret_repository
+            .get_secret_by_id(&secret_uuid, &user_from)
+        {
+            None => return Err((Status::NotFound, None)),
+            Some(secret) => secret,
+        };
+        let (iv, sum, encrypted) = Self::encrypt_value(&input.value);
+        let (secret, secret_version) =
+            match self
+                .secret_repository
+                .add_version(&secret.id, iv, sum, &user_from)
+            {
+                Err(Error::NotFound) => return Err((Status::NotFound, None)),
+                Err(_) => return Err((Status::InternalServerError, None)),
+                Ok(res) => res,
+            };
+        FragmentsService::save_fragments_to_nodes(
+            FragmentsService::generate_fragments(encrypted),
+            secret_version.id.to_string(),
+            &self.nodes_config,
+        );
+        Ok(secret)
+    }
+
+    /// ### Get by id
+    ///
+    /// rebuild a version of the secret (the current one by default) from the nodes
+    pub fn get_by_id(
+        &self,
+        secret_uuid: Uuid,
+        version: Option<i32>,
+        user_from: User,
+    ) -> Result<(Secret, SecretVersion, String), (Status, Option<&str>)> {
+        let secret = match self
+            .secret_repository
+            .get_secret_by_id(&secret_uuid, &user_from)
+        {
+            None => return Err((Status::NotFound, None)),
+            Some(secret) => secret,
+        };
+        let secret_version = match self
+            .secret_repository
+            .get_version(&secret, version.unwrap_or(secret.current_version))
+        {
+            None => return Err((Status::NotFound, Some("Secret version not found"))),
+            Some(secret_version) => secret_version,
+        };
+        let fragments = FragmentsService::get_fragments_from_nodes(
+            secret_version.id.to_string(),
+            &self.nodes_config,
+        );
+        let (valid_version, value) = Self::reveal(&secret_version, fragments)?;
+        Ok((secret, valid_version, value))
+    }
+
+    pub fn get_versions(
+        &self,
+        secret_uuid: Uuid,
+        user_from: User,
+    ) -> Result<Vec<SecretVersion>, (Status, Option<&str>)> {
+        match self
+            .secret_repository
+            .get_secret_by_id(&secret_uuid, &user_from)
+        {
+            None => Err((Status::NotFound, None)),
+            Some(secret) => Ok(self.secret_repository.get_versions(&secret)),
+        }
+    }
+
+    /// ### Delete one
+    ///
+    /// an admin deletes any secret of the application, a user only the secrets
+    /// they created, the fragments of every version are removed from the nodes
+    pub fn delete_one(
+        &self,
+        secret_uuid: Uuid,
+        user_from: User,
+        is_admin: bool,
+    ) -> Result<(), (Status, Option<&str>)> {
+        let secret = match self
+            .secret_repository
+            .get_secret_by_id(&secret_uuid, &user_from)
+        {
+            Some(secret) if is_admin || secret.created_by_id == Some(user_from.id) => secret,
+            _ => return Err((Status::NotFound, None)),
+        };
+        let versions = match self.secret_repository.delete_secret(&secret, &user_from) {
+            Err(_) => return Err((Status::InternalServerError, None)),
+            Ok(versions) => versions,
+        };
+        for secret_version in versions {
+            FragmentsService::delete_fragments_from_nodes(
+                secret_version.id.to_string(),
+                &self.nodes_config,
+            );
+        }
+        Ok(())
+    }
+
+    fn check_value(value: &str) -> Result<(), (Status, Option<&'static str>)> {
+        match value.is_empty() || value.len() > SECRET_MAX_SIZE {
+            true => Err((
+                Status::BadRequest,
+                Some("The secret value must be between 1 byte and 64 KiB"),
+            )),
+            false => Ok(()),
+        }
+    }
+
+    /// rebuild the encrypted value of a version from its fragments, check its sum and decrypt it
+    pub fn reveal(
+        secret_version: &SecretVersion,
+        fragments: Vec<String>,
+    ) -> Result<(SecretVersion, String), (Status, Option<&'static str>)> {
+        match FragmentsService::reconstruct_encrypted_key(fragments) {
+            None => Err((Status::NotFound, None)),
+            Some(encrypted) => match secret_version.check(encrypted.clone()) {
+                Err(e) => Err((Status::NotAcceptable, Some(e))),
+                Ok(valid_version) => {
+                    let value = Crypto::decrypt(encrypted, valid_version.iv.clone());
+                    Ok((valid_version, value))
+                }
+            },
+        }
+    }
+
+    /// encrypt the value with the master key, the sum is computed on the encrypted value
+    pub fn encrypt_value(value: &str) -> (String, String, String) {
+        let iv = Crypto::generate_unique_iv();
+        let encrypted = Crypto::encrypt(value.to_string(), iv.clone());
+        let sum = Crypto::key_sum(&encrypted);
+        (iv, sum, encrypted)
+    }
+}
@@ -1,3 +1,8 @@
+use std::env;
+use std::net::IpAddr;
+
+use base64::{engine::general_purpose, Engine as _};
+use chrono::Utc;
 use rocket::http::Status;
 use uuid::Uuid;
 
@@ -5,33 +10,80 @@ use crate::{
     core::nodes_config::NodesConfig,
     db::connect::DbPool,
     dto::{
-        sentinel::{sentinel_input::SentinelInput, sentinel_insertable::SentinelInsertable},
+        sentinel::{
+            sentinel_hmac_input::SentinelHmacInput,
+            sentinel_hmac_verify_input::SentinelHmacVerifyInput,
+            sentinel_import_input::SentinelImportInput, sentinel_input::SentinelInput,
+            sentinel_insertable::SentinelInsertable,
+        },
+        access_request::dual_control_input::DualControlInput,
+        audit_log::audit_log_insertable::AuditLogInsertable,
+        key_policy::key_policy_input::KeyPolicyInput,
+        share_link::{
+            share_link_input::ShareLinkInput, share_link_insertable::ShareLinkInsertable,
+            share_link_redeem_output::ShareLinkRedeemOutput,
+        },
         x_sentinel_cluster::x_sentinel_cluster_insertable::XSentinelClusterInsertable,
     },
-    models::{sentinel::Sentinel, user::User},
+    enums::{
+        audit_action::AuditAction, audit_severity::AuditSeverity, key_operation::KeyOperation, mac_algorithm::MacAlgorithm,
+        sentinel_purpose::SentinelPurpose,
+    },
+    models::{key_policy::KeyPolicy, sentinel::Sentinel, share_link::ShareLink, user::User},
     repositories::{
-        application::ApplicationRepository, cluster::ClusterRepository,
-        sentinel::SentinelRepository,
+        access_request::AccessRequestRepository, application::ApplicationRepository,
+        cluster::ClusterRepository, sentinel::SentinelRepository,
+        share_link::ShareLinkRepository,
+    },
+    services::{
+        access_request::AccessRequestService,
+        audit::AuditService,
+        fragments::{self, FragmentsService},
+        import_key::ImportKeyService,
+        key_policy::KeyPolicyService,
+    },
+    traits::{access_request::AccessRequestContract, application::ApplicationContract},
+    utils::{
+        code::{generate_token, hash_token},
+        crypto::Crypto,
+        jwt::TokenScope,
+        key_import::KeyImport,
+        mac::Mac,
     },
-    services::fragments::{self, FragmentsService},
-    traits::application::ApplicationContract,
-    utils::crypto::Crypto,
     LICENSE_VALID,
 };
 
+type KeyError = (Status, Option<&'static str>);
+/// the sentinel, the algorithm and the result of a MAC operation
+type MacResult<T> = Result<(Sentinel, MacAlgorithm, T), KeyError>;
+
 pub struct SentinelService<T> {
     nodes_config: NodesConfig,
     sentinel_repository: SentinelRepository,
     cluster_repository: ClusterRepository,
+    import_key_service: ImportKeyService,
+    key_policy_service: KeyPolicyService,
+    access_request_service: AccessRequestService<AccessRequestRepository>,
+    audit_service: AuditService,
+    share_link_repository: ShareLinkRepository,
     application_repository: T,
 }
 
+/// the default validity of a share link in minutes and the most uses it can have
+const SHARE_LINK_DEFAULT_TTL: i64 = 60;
+const SHARE_LINK_MAX_USES: i32 = 100;
+
 impl<T: ApplicationContract> SentinelService<T> {
     pub fn new(pool: &DbPool, application_repository: T, nodes_config: &NodesConfig) -> Self {
         Self {
             nodes_config: nodes_config.clone(),
             sentinel_repository: SentinelRepository::new(&pool),
             cluster_repository: ClusterRepository::new(&pool),
+            import_key_service: ImportKeyService::new(pool, nodes_config),
+            key_policy_service: KeyPolicyService::new(pool),
+            access_request_service: AccessRequestService::new(AccessRequestContract::new(pool)),
+            audit_service: AuditService::new(pool),
+            share_link_repository: ShareLinkRepository::new(pool),
             application_repository,
         }
     }
@@ -54,6 +106,52 @@ impl<T: ApplicationContract> SentinelService<T> {
             false => Crypto::generate_aes_128_key(),
         };
 
+        let purpose = Self::parse_purpose(input.purpose)?;
+        self.store_key(key, key_size, purpose, input.clusters, user_from)
+    }
+
+    /// ### Import
+    ///
+    /// create a sentinel from a customer key wrapped with one of the published import keys
+    pub fn import(
+        &self,
+        input: SentinelImportInput,
+        user_from: User,
+    ) -> Result<(Sentinel, String), (Status, Option<&str>)> {
+        let purpose = Self::parse_purpose(input.purpose.clone())?;
+        let key = self.import_key_service.unwrap(&input, &user_from)?;
+        let key_size = match KeyImport::key_size(&key) {
+            Some(key_size) => key_size,
+            None => {
+                return Err((
+                    Status::BadRequest,
+                    Some("Only AES-128 and AES-256 keys can be imported"),
+                ))
+            }
+        };
+        let is_entreprise = match LICENSE_VALID.lock().unwrap().clone() {
+            None => false,
+            Some(license) => license.mode == "Entreprise",
+        };
+        if key_size == 256 && !is_entreprise {
+            return Err((
+                Status::Forbidden,
+                Some("AES-256 keys require an Entreprise license"),
+            ));
+        }
+        self.store_key(hex::encode(key), key_size, purpose, input.clusters, user_from)
+    }
+
+    /// encrypt the key with the master key, deal its fragments to the nodes,
+    /// save its sum and attach it to the clusters of the user
+    fn store_key(
+        &self,
+        key: String,
+        key_size: i32,
+        purpose: SentinelPurpose,
+        clusters: Vec<String>,
+        user_from: User,
+    ) -> Result<(Sentinel, String), (Status, Option<&str>)> {
         let iv = Crypto::generate_unique_iv();
         let encrypted = Crypto::encrypt(key.clone(), iv.clone());
         let fragments = FragmentsService::generate_fragments(encrypted.clone());
@@ -64,6 +162,7 @@ impl<T: ApplicationContract> SentinelService<T> {
             user_from.application.unwrap(),
             user_from.id,
             key_size,
+            purpose.as_str().to_string(),
         );
         let sentinel = self.sentinel_repository.create_sentinel(insertable);
         FragmentsService::save_fragments_to_nodes(
@@ -71,7 +170,6 @@ impl<T: ApplicationContract> SentinelService<T> {
             sentinel.id.to_string(),
             &self.nodes_config,
         );
-        let clusters = input.clusters;
         for cluster_id in clusters {
             let cluster_uuid = match Uuid::parse_str(&cluster_id) {
                 Err(_) => continue,
@@ -98,32 +196,580 @@ impl<T: ApplicationContract> SentinelService<T> {
         Ok((sentinel, key))
     }
 
+    /// ### Check scope
+    ///
+    /// a capability token only works on the sentinels of its scope, named directly or
+    /// through one of their clusters, and for its operations. `None` is a full session
+    pub fn check_scope(
+        &self,
+        scope: Option<&TokenScope>,
+        sentinel_uuid: &Uuid,
+        operation: KeyOperation,
+    ) -> Result<(), KeyError> {
+        match scope {
+            None => Ok(()),
+            Some(scope) => match scope.allows(
+                sentinel_uuid,
+                &self.cluster_repository.get_sentinel_cluster_ids(sentinel_uuid),
+                operation,
+            ) {
+                true => Ok(()),
+                false => Err((
+                    Status::Forbidden,
+                    Some("The token scope does not allow this operation on this key"),
+                )),
+            },
+        }
+    }
+
+    /// ### Get by id
+    ///
+    /// rebuild the key of an encryption sentinel, the MAC keys are never exported.
+    /// Only the creator and the members of the clusters of the sentinel can read it, the admins
+    /// go through break-glass. The dual control and the key policy are checked before the fragments are fetched
     pub fn get_by_id(
         &self,
         sentinel_uuid: Uuid,
         user_from: User,
+        ip: &IpAddr,
     ) -> Result<(Sentinel, String), (Status, Option<&str>)> {
+        match self
+            .sentinel_repository
+            .get_member_sentinel_by_id(&sentinel_uuid, &user_from)
+        {
+            None => Err((Status::NotFound, None)),
+            Some(sentinel) => self.release_key(sentinel, &user_from, ip, None),
+        }
+    }
+
+    /// ### Break glass
+    ///
+    /// emergency read of any sentinel of the application with a justification.
+    /// The read is recorded as a high severity audit record, alerted to every admin and
+    /// flags the sentinel until an admin acknowledges it
+    pub fn break_glass(
+        &self,
+        sentinel_uuid: Uuid,
+        justification: &str,
+        user_from: User,
+        ip: &IpAddr,
+    ) -> Result<(Sentinel, String), KeyError> {
+        AuditService::check_break_glass_role(&user_from)?;
+        let justification = AuditService::check_justification(justification)?;
+        match self
+            .sentinel_repository
+            .get_application_sentinel_by_id(&sentinel_uuid, user_from.application.unwrap())
+        {
+            None => Err((Status::NotFound, None)),
+            Some(sentinel) => self.release_key(sentinel, &user_from, ip, Some(&justification)),
+        }
+    }
+
+    /// ### Acknowledge break glass
+    ///
+    /// clear the break-glass flag of a sentinel
+    pub fn acknowledge_break_glass(
+        &self,
+        sentinel_uuid: Uuid,
+        user_from: User,
+        ip: &IpAddr,
+    ) -> Result<(), KeyError> {
+        match self
+            .sentinel_repository
+            .acknowledge_break_glass(&sentinel_uuid, &user_from)
+        {
+            Ok(1) => self
+                .audit_service
+                .record(AuditLogInsertable::new(
+                    &user_from,
+                    AuditAction::FlagAcknowledged,
+                    AuditSeverity::Medium,
+                    Some(("sentinel", sentinel_uuid)),
+                    None,
+                    Some(ip),
+                ))
+                .map(|_| ()),
+            _ => Err((Status::NotFound, Some("The key is not flagged as break-glass accessed"))),
+        }
+    }
+
+    /// the checks done before an encryption key leaves the service. A break-glass attempt is
+    /// audited before the dual control and the key policy, the key is flagged once they let it through
+    fn release_key(
+        &self,
+        sentinel: Sentinel,
+        user_from: &User,
+        ip: &IpAddr,
+        break_glass_justification: Option<&str>,
+    ) -> Result<(Sentinel, String), KeyError> {
+        if let Some(justification) = break_glass_justification {
+            self.audit_service
+                .break_glass(user_from, ("sentinel", sentinel.id), justification, ip)?;
+        }
+        if sentinel.purpose == SentinelPurpose::Mac.as_str() {
+            return Err((Status::Forbidden, Some("MAC keys can not be exported")));
+        }
+        self.access_request_service
+            .release_sentinel(sentinel, user_from, |mut sentinel| {
+                self.key_policy_service.enforce(
+                    self.key_policy_service.get_for_sentinel(&sentinel.id),
+                    KeyOperation::Read,
+                    ip,
+                )?;
+                if break_glass_justification.is_some() {
+                    match self
+                        .sentinel_repository
+                        .mark_break_glass(&sentinel.id, user_from)
+                    {
+                        Ok(1) => {
+                            sentinel.break_glass_at = Some(Utc::now());
+                            sentinel.break_glass_by_id = Some(user_from.id);
+                        }
+                        _ => {
+                            return Err((
+                                Status::InternalServerError,
+                                Some("Unable to flag the key as break-glass accessed"),
+                            ))
+                        }
+                    }
+                }
+                self.rebuild_key(sentinel)
+            })
+    }
+
+    /// ### Hmac
+    ///
+    /// compute the HMAC of a payload with the key of a MAC sentinel
+    pub fn hmac(
+        &self,
+        sentinel_uuid: Uuid,
+        input: SentinelHmacInput,
+        user_from: User,
+        ip: &IpAddr,
+    ) -> MacResult<Vec<u8>> {
+        let (algorithm, payload) = Self::parse_hmac_input(&input.algorithm, &input.payload)?;
+        let (sentinel, key) = self.get_raw_key(
+            sentinel_uuid,
+            user_from,
+            SentinelPurpose::Mac,
+            KeyOperation::Encrypt,
+            ip,
+        )?;
+        match Mac::sign(&algorithm, &key, &payload) {
+            Err(e) => Err((Status::InternalServerError, Some(e))),
+            Ok(mac) => Ok((sentinel, algorithm, mac)),
+        }
+    }
+
+    /// ### Hmac verify
+    ///
+    /// check the HMAC of a payload with the key of a MAC sentinel
+    pub fn hmac_verify(
+        &self,
+        sentinel_uuid: Uuid,
+        input: SentinelHmacVerifyInput,
+        user_from: User,
+        ip: &IpAddr,
+    ) -> MacResult<bool> {
+        let (algorithm, payload) = Self::parse_hmac_input(&input.algorithm, &input.payload)?;
+        let mac = match general_purpose::STANDARD.decode(&input.mac) {
+            Err(_) => return Err((Status::BadRequest, Some("The mac must be base64 encoded"))),
+            Ok(mac) => mac,
+        };
+        let (sentinel, key) = self.get_raw_key(
+            sentinel_uuid,
+            user_from,
+            SentinelPurpose::Mac,
+            KeyOperation::Decrypt,
+            ip,
+        )?;
+        match Mac::verify(&algorithm, &key, &payload, &mac) {
+            Err(e) => Err((Status::InternalServerError, Some(e))),
+            Ok(valid) => Ok((sentinel, algorithm, valid)),
+        }
+    }
+
+    /// ### Stream key
+    ///
+    /// rebuild the raw key of an encryption sentinel for the streaming encryption,
+    /// the key stays on the server. `operation` is `encrypt` or `decrypt`
+    pub fn stream_key(
+        &self,
+        sentinel_uuid: Uuid,
+        user_from: User,
+        operation: KeyOperation,
+        ip: &IpAddr,
+    ) -> Result<(Sentinel, Vec<u8>), KeyError> {
+        self.get_raw_key(
+            sentinel_uuid,
+            user_from,
+            SentinelPurpose::Encryption,
+            operation,
+            ip,
+        )
+    }
+
+    fn get_raw_key(
+        &self,
+        sentinel_uuid: Uuid,
+        user_from: User,
+        purpose: SentinelPurpose,
+        operation: KeyOperation,
+        ip: &IpAddr,
+    ) -> Result<(Sentinel, Vec<u8>), KeyError> {
+        let sentinel = match self
+            .sentinel_repository
+            .get_member_sentinel_by_id(&sentinel_uuid, &user_from)
+        {
+            None => return Err((Status::NotFound, None)),
+            Some(sentinel) => sentinel,
+        };
+        if sentinel.purpose != purpose.as_str() {
+            return Err((
+                Status::Forbidden,
+                match purpose {
+                    SentinelPurpose::Mac => Some("This sentinel is not a MAC key"),
+                    SentinelPurpose::Encryption => Some("This sentinel is not an encryption key"),
+                },
+            ));
+        }
+        let (sentinel, key) =
+            self.access_request_service
+                .release_sentinel(sentinel, &user_from, |sentinel| {
+                    self.key_policy_service.enforce(
+                        self.key_policy_service.get_for_sentinel(&sentinel.id),
+                        operation,
+                        ip,
+                    )?;
+                    self.rebuild_key(sentinel)
+                })?;
+        match hex::decode(key) {
+            Ok(key) if !key.is_empty() => Ok((sentinel, key)),
+            _ => Err((Status::NotAcceptable, Some("Unable to decrypt the key"))),
+        }
+    }
+
+    /// rebuild the encrypted key from the nodes, check its sum and decrypt it
+    fn rebuild_key(
+        &self,
+        sentinel: Sentinel,
+    ) -> Result<(Sentinel, String), (Status, Option<&'static str>)> {
+        let fragments =
+            FragmentsService::get_fragments_from_nodes(sentinel.id.to_string(), &self.nodes_config);
+        match FragmentsService::reconstruct_encrypted_key(fragments) {
+            None => Err((Status::NotFound, None)),
+            Some(encrypted_key) => match sentinel.check(encrypted_key.clone()) {
+                Err(e) => Err((Status::NotAcceptable, Some(e))),
+                Ok(valid_sentinel) => {
+                    let key = Crypto::decrypt(encrypted_key, valid_sentinel.iv.clone());
+                    Ok((valid_sentinel, key))
+                }
+            },
+        }
+    }
+
+    /// ### Get policy
+    ///
+    /// the active usage policy of a sentinel
+    pub fn get_policy(&self, sentinel_uuid: Uuid, user_from: User) -> Result<KeyPolicy, KeyError> {
         match self
             .sentinel_repository
             .get_sentinel_by_id(&sentinel_uuid, &user_from)
         {
             None => Err((Status::NotFound, None)),
-            Some(sentinel) => {
-                let fragments = FragmentsService::get_fragments_from_nodes(
-                    sentinel.clone().id.to_string(),
-                    &self.nodes_config,
-                );
-                match FragmentsService::reconstruct_encrypted_key(fragments) {
-                    None => Err((Status::NotFound, None)),
-                    Some(encrypted_key) => match sentinel.check(encrypted_key.clone()) {
-                        Err(e) => Err((Status::NotAcceptable, Some(e))),
-                        Ok(valid_sentinel) => Ok((
-                            valid_sentinel.clone(),
-                            Crypto::decrypt(encrypted_key, valid_sentinel.iv),
-                        )),
-                    },
+            Some(sentinel) => self
+                .key_policy_service
+                .get_for_sentinel(&sentinel.id)
+                .ok_or((Status::NotFound, None)),
+        }
+    }
+
+    /// ### Set policy
+    ///
+    /// replace the usage policy of a sentinel, only its creator or an admin can change it
+    /// and only an admin can relax it
+    pub fn set_policy(
+        &self,
+        sentinel_uuid: Uuid,
+        input: KeyPolicyInput,
+        user_from: User,
+        is_admin: bool,
+    ) -> Result<KeyPolicy, KeyError> {
+        let sentinel = self.get_owned_sentinel(sentinel_uuid, &user_from, is_admin)?;
+        self.key_policy_service
+            .set(Some(sentinel.id), None, input, &user_from, is_admin)
+    }
+
+    /// ### Delete policy
+    ///
+    /// remove the usage policy of a sentinel, only an admin can remove it
+    pub fn delete_policy(
+        &self,
+        sentinel_uuid: Uuid,
+        user_from: User,
+        is_admin: bool,
+    ) -> Result<(), KeyError> {
+        let sentinel = self.get_owned_sentinel(sentinel_uuid, &user_from, is_admin)?;
+        self.key_policy_service.delete(
+            self.key_policy_service.get_for_sentinel(&sentinel.id),
+            &user_from,
+            is_admin,
+        )
+    }
+
+    /// ### Set dual control
+    ///
+    /// number of approvals needed to release the key, only an admin can lower it
+    pub fn set_dual_control(
+        &self,
+        sentinel_uuid: Uuid,
+        input: DualControlInput,
+        user_from: User,
+        is_admin: bool,
+    ) -> Result<Sentinel, KeyError> {
+        let sentinel = self.get_owned_sentinel(sentinel_uuid, &user_from, is_admin)?;
+        if input.required_approvals < 0 {
+            return Err((
+                Status::BadRequest,
+                Some("The number of approvals can not be negative"),
+            ));
+        }
+        if !is_admin && input.required_approvals < sentinel.required_approvals {
+            return Err((
+                Status::Forbidden,
+                Some("Only an admin can lower the dual control of a key"),
+            ));
+        }
+        self.sentinel_repository
+            .set_required_approvals(&sentinel.id, input.required_approvals, &user_from)
+            .map_err(|_| (Status::NotFound, None))
+    }
+
+    /// ### Create share link
+    ///
+    /// a link redeeming the key of an encryption sentinel without authentication,
+    /// `max_uses` times before it expires. Only the members of the sentinel can share it
+    /// and a key under dual control can not be shared.
+    /// The token is returned once, only its hash is stored
+    pub fn create_share_link(
+        &self,
+        sentinel_uuid: Uuid,
+        input: ShareLinkInput,
+        user_from: User,
+        ip: &IpAddr,
+    ) -> Result<(ShareLink, String), KeyError> {
+        let (expires_in, max_uses, recipient_public_key) = Self::validate_share_link(input)?;
+        let sentinel = match self
+            .sentinel_repository
+            .get_member_sentinel_by_id(&sentinel_uuid, &user_from)
+        {
+            None => return Err((Status::NotFound, None)),
+            Some(sentinel) => sentinel,
+        };
+        self.check_shareable(&sentinel)?;
+        let token = generate_token();
+        let share_link = self
+            .share_link_repository
+            .create_share_link(ShareLinkInsertable::new(
+                sentinel.id,
+                hash_token(&token),
+                max_uses,
+                Utc::now() + chrono::Duration::minutes(expires_in),
+                recipient_public_key,
+                user_from.id,
+            ))
+            .map_err(|_| (Status::InternalServerError, Some("Unable to create the share link")))?;
+        self.audit_service.record(AuditLogInsertable::new(
+            &user_from,
+            AuditAction::ShareLinkCreated,
+            AuditSeverity::Low,
+            Some(("sentinel", sentinel.id)),
+            None,
+            Some(ip),
+        ))?;
+        Ok((share_link, token))
+    }
+
+    /// ### Redeem share link
+    ///
+    /// release the key of a share link, a use is counted before the checks so a refused
+    /// redemption still burns it. The dual control and the key policy are checked again
+    /// with the address of the redeemer
+    pub fn redeem_share_link(
+        &self,
+        token: &str,
+        ip: &IpAddr,
+    ) -> Result<ShareLinkRedeemOutput, KeyError> {
+        let share_link = match self.share_link_repository.redeem(&hash_token(token)) {
+            None => {
+                return Err((
+                    Status::NotFound,
+                    Some("The share link is invalid, expired or already used"),
+                ))
+            }
+            Some(share_link) => share_link,
+        };
+        let sentinel = match self.sentinel_repository.find_by_id(&share_link.sentinel_id) {
+            Some(sentinel) if !sentinel.is_deleted => sentinel,
+            _ => return Err((Status::NotFound, None)),
+        };
+        self.check_shareable(&sentinel)?;
+        self.key_policy_service.enforce(
+            self.key_policy_service.get_for_sentinel(&sentinel.id),
+            KeyOperation::Read,
+            ip,
+        )?;
+        self.audit_service.record(AuditLogInsertable::anonymous(
+            sentinel.application_id,
+            AuditAction::ShareLinkRedeemed,
+            AuditSeverity::Medium,
+            ("sentinel", sentinel.id),
+            ip,
+        ))?;
+        let (sentinel, key) = self.rebuild_key(sentinel)?;
+        let wrapped = match &share_link.recipient_public_key {
+            None => None,
+            Some(public_key) => match hex::decode(&key)
+                .ok()
+                .and_then(|key| KeyImport::wrap_ml_kem_768(public_key, &key))
+            {
+                None => {
+                    return Err((
+                        Status::InternalServerError,
+                        Some("Unable to encapsulate the key"),
+                    ))
                 }
+                wrapped => wrapped,
+            },
+        };
+        Ok(ShareLinkRedeemOutput::new(&share_link, &sentinel, key, wrapped))
+    }
+
+    /// ### Revoke share link
+    ///
+    /// invalidate a share link before it expires, only its creator or an admin can revoke it
+    pub fn revoke_share_link(
+        &self,
+        sentinel_uuid: Uuid,
+        share_link_uuid: Uuid,
+        user_from: User,
+        is_admin: bool,
+    ) -> Result<(), KeyError> {
+        if self
+            .sentinel_repository
+            .get_sentinel_by_id(&sentinel_uuid, &user_from)
+            .is_none()
+        {
+            return Err((Status::NotFound, None));
+        }
+        match self
+            .share_link_repository
+            .get_by_id(&share_link_uuid, &sentinel_uuid)
+        {
+            None => Err((Status::NotFound, None)),
+            Some(share_link) if is_admin || share_link.created_by_id == Some(user_from.id) => self
+                .share_link_repository
+                .revoke(&share_link.id, &user_from)
+                .map(|_| ())
+                .map_err(|_| (Status::NotFound, None)),
+            Some(_) => Err((
+                Status::Forbidden,
+                Some("Only the creator of the link can revoke it"),
+            )),
+        }
+    }
+
+    /// the MAC keys and the keys under dual control are never released through a link
+    fn check_shareable(&self, sentinel: &Sentinel) -> Result<(), KeyError> {
+        if sentinel.purpose == SentinelPurpose::Mac.as_str() {
+            return Err((Status::Forbidden, Some("MAC keys can not be exported")));
+        }
+        if self
+            .access_request_service
+            .sentinel_required_approvals(sentinel)
+            > 0
+        {
+            return Err((
+                Status::Forbidden,
+                Some("A key under dual control can not be shared"),
+            ));
+        }
+        Ok(())
+    }
+
+    /// the validity in minutes, the number of uses and the recipient key of a link
+    fn validate_share_link(
+        input: ShareLinkInput,
+    ) -> Result<(i64, i32, Option<String>), KeyError> {
+        let max_ttl = env::var("SHARE_LINK_MAX_TTL")
+            .unwrap_or_else(|_| "10080".to_string())
+            .parse::<i64>()
+            .unwrap_or(10080);
+        let expires_in = input.expires_in.unwrap_or(SHARE_LINK_DEFAULT_TTL);
+        if expires_in < 1 || expires_in > max_ttl {
+            return Err((
+                Status::BadRequest,
+                Some("The validity of the link is out of the allowed range"),
+            ));
+        }
+        let max_uses = input.max_uses.unwrap_or(1);
+        if !(1..=SHARE_LINK_MAX_USES).contains(&max_uses) {
+            return Err((
+                Status::BadRequest,
+                Some("The number of uses must be between 1 and 100"),
+            ));
+        }
+        match input.recipient_public_key {
+            Some(public_key) if !KeyImport::is_ml_kem_768_public_key(&public_key) => Err((
+                Status::BadRequest,
+                Some("The recipient key must be a hex ML-KEM-768 public key"),
+            )),
+            recipient_public_key => Ok((expires_in, max_uses, recipient_public_key)),
+        }
+    }
+
+    fn get_owned_sentinel(
+        &self,
+        sentinel_uuid: Uuid,
+        user_from: &User,
+        is_admin: bool,
+    ) -> Result<Sentinel, KeyError> {
+        match self
+            .sentinel_repository
+            .get_sentinel_by_id(&sentinel_uuid, user_from)
+        {
+            None => Err((Status::NotFound, None)),
+            Some(sentinel) if is_admin || sentinel.created_by_id == Some(user_from.id) => {
+                Ok(sentinel)
             }
+            Some(_) => Err((
+                Status::Forbidden,
+                Some("Only the creator of the key can change its settings"),
+            )),
+        }
+    }
+
+    fn parse_purpose(purpose: Option<String>) -> Result<SentinelPurpose, (Status, Option<&'static str>)> {
+        match purpose {
+            None => Ok(SentinelPurpose::Encryption),
+            Some(purpose) => SentinelPurpose::from_str(&purpose).ok_or((
+                Status::BadRequest,
+                Some("The purpose must be `encryption` or `mac`"),
+            )),
+        }
+    }
+
+    fn parse_hmac_input(
+        algorithm: &str,
+        payload: &str,
+    ) -> Result<(MacAlgorithm, Vec<u8>), KeyError> {
+        let algorithm = match MacAlgorithm::from_str(algorithm) {
+            None => return Err((Status::BadRequest, Some("The algorithm must be HS256 or HS512"))),
+            Some(algorithm) => algorithm,
+        };
+        match general_purpose::STANDARD.decode(payload) {
+            Err(_) => Err((Status::BadRequest, Some("The payload must be base64 encoded"))),
+            Ok(payload) => Ok((algorithm, payload)),
         }
     }
 
@@ -132,7 +778,13 @@ impl<T: ApplicationContract> SentinelService<T> {
         sentinel_uuid: Uuid,
         user_from: User,
         is_admin: bool,
+        ip: &IpAddr,
     ) -> Result<(), (Status, Option<&str>)> {
+        self.key_policy_service.enforce(
+            self.key_policy_service.get_for_sentinel(&sentinel_uuid),
+            KeyOperation::Delete,
+            ip,
+        )?;
         match is_admin {
             true => match self
                 .sentinel_repository
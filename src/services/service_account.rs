@@ -0,0 +1,291 @@
+use std::env;
+
+use chrono::Utc;
+use rand::Rng;
+use rocket::http::Status;
+use uuid::Uuid;
+
+use crate::{
+    db::connect::DbPool,
+    dto::{
+        oauth::oauth_access_token_output::OauthAccessTokenOutput,
+        service_account::{
+            service_account_credentials_input::ServiceAccountCredentialsInput,
+            service_account_input::ServiceAccountInput,
+            service_account_insertable::ServiceAccountInsertable,
+            service_account_signing_key_input::ServiceAccountSigningKeyInput,
+        },
+        user::user_insertable::UserInsertable,
+    },
+    models::{service_account::ServiceAccount, user::User},
+    redis::RedisClient,
+    repositories::{
+        application::ApplicationRepository, service_account::ServiceAccountRepository,
+        user::UserRepository,
+    },
+    traits::application::ApplicationContract,
+    services::oauth::OauthServiceError,
+    utils::{
+        client_assertion::{ClientAssertion, CLIENT_ASSERTION_TYPE},
+        code::{generate_token, hash_token},
+        jwt::Jwt,
+        message_signature::MessageSignature,
+    },
+};
+
+type ServiceAccountError = (Status, Option<&'static str>);
+
+pub struct ServiceAccountService {
+    service_account_repository: ServiceAccountRepository,
+    user_repository: UserRepository,
+    application_repository: ApplicationRepository,
+}
+
+impl ServiceAccountService {
+    pub fn new(pool: &DbPool) -> Self {
+        Self {
+            service_account_repository: ServiceAccountRepository::new(pool),
+            user_repository: UserRepository::new(pool),
+            application_repository: ApplicationContract::new(pool),
+        }
+    }
+
+    /// ### Create
+    ///
+    /// a service account of the application of the admin, authenticated by the given
+    /// public key or by a generated secret, returned once
+    pub fn create(
+        &self,
+        input: ServiceAccountInput,
+        user_from: &User,
+    ) -> Result<(ServiceAccount, Option<String>), ServiceAccountError> {
+        let name = input.name.trim();
+        if name.is_empty() || name.chars().count() > 100 {
+            return Err((Status::BadRequest, Some("Bad service account name")));
+        }
+        let app_id = user_from.application.ok_or((Status::Forbidden, None))?;
+        let (secret, public_key) = Self::credentials(input.public_key)?;
+        let client_id = format!(
+            "sa_{}",
+            hex::encode(rand::thread_rng().gen::<[u8; 16]>())
+        );
+        let user_insertable =
+            UserInsertable::new_service_account(name, &client_id, app_id, user_from.id);
+        let insertable = ServiceAccountInsertable::new(
+            app_id,
+            name.to_string(),
+            client_id,
+            secret.as_deref().map(hash_token),
+            public_key,
+            user_from.id,
+        );
+        match self
+            .service_account_repository
+            .create_service_account(user_insertable, insertable)
+        {
+            Ok(service_account) => Ok((service_account, secret)),
+            Err(_) => Err((Status::InternalServerError, None)),
+        }
+    }
+
+    pub fn get_all(&self, user_from: &User) -> Vec<ServiceAccount> {
+        match user_from.application {
+            None => Vec::new(),
+            Some(app_id) => self.service_account_repository.get_by_application(app_id),
+        }
+    }
+
+    /// ### Rotate credentials
+    ///
+    /// replace the credentials of an account, the previous secret or key stops working at once
+    pub fn rotate_credentials(
+        &self,
+        service_account_uuid: &Uuid,
+        input: ServiceAccountCredentialsInput,
+        user_from: &User,
+    ) -> Result<(ServiceAccount, Option<String>), ServiceAccountError> {
+        let service_account = self.get_by_id(service_account_uuid, user_from)?;
+        let (secret, public_key) = Self::credentials(input.public_key)?;
+        match self.service_account_repository.update_credentials(
+            &service_account.id,
+            secret.as_deref().map(hash_token),
+            public_key,
+            user_from,
+        ) {
+            Ok(service_account) => Ok((service_account, secret)),
+            Err(_) => Err((Status::InternalServerError, None)),
+        }
+    }
+
+    /// ### Set signing key
+    ///
+    /// the public key checking the signed requests of an account, none stops accepting them
+    pub fn set_signing_key(
+        &self,
+        service_account_uuid: &Uuid,
+        input: ServiceAccountSigningKeyInput,
+        user_from: &User,
+    ) -> Result<ServiceAccount, ServiceAccountError> {
+        let service_account = self.get_by_id(service_account_uuid, user_from)?;
+        if input
+            .signing_key
+            .as_deref()
+            .is_some_and(|signing_key| MessageSignature::public_key(signing_key).is_none())
+        {
+            return Err((Status::BadRequest, Some("Bad signing key")));
+        }
+        self.service_account_repository
+            .update_signing_key(&service_account.id, input.signing_key, user_from)
+            .map_err(|_| (Status::InternalServerError, None))
+    }
+
+    pub fn delete(
+        &self,
+        service_account_uuid: &Uuid,
+        user_from: &User,
+    ) -> Result<(), ServiceAccountError> {
+        let service_account = self.get_by_id(service_account_uuid, user_from)?;
+        self.service_account_repository
+            .delete(&service_account, user_from)
+            .map_err(|_| (Status::InternalServerError, None))
+    }
+
+    /// ### Client credentials
+    ///
+    /// authenticate a service account with its secret or a `private_key_jwt` assertion
+    /// and issue an access token, an assertion is accepted once
+    pub async fn client_credentials(
+        &self,
+        client_id: Option<String>,
+        client_secret: Option<String>,
+        client_assertion_type: Option<String>,
+        client_assertion: Option<String>,
+    ) -> Result<OauthAccessTokenOutput, OauthServiceError> {
+        let invalid_client = (Status::Unauthorized, "invalid_client", None);
+        let service_account = self.authenticate(
+            client_id,
+            client_secret,
+            client_assertion_type,
+            client_assertion,
+        )?;
+        let user = self
+            .user_repository
+            .get_by_id(&service_account.user_id)
+            .ok_or(invalid_client)?;
+        let application = self
+            .application_repository
+            .get_by_id(service_account.application_id)
+            .ok_or(invalid_client)?;
+        self.service_account_repository.touch(&service_account.id);
+
+        let duration = Self::token_duration();
+        let access_token = Jwt::create_service_account_jwt(&user, &application, duration).await;
+        Ok(OauthAccessTokenOutput::new(access_token, duration, None))
+    }
+
+    /// ### Authenticate
+    ///
+    /// a service account with its secret or a `private_key_jwt` assertion, an assertion is accepted once
+    pub fn authenticate(
+        &self,
+        client_id: Option<String>,
+        client_secret: Option<String>,
+        client_assertion_type: Option<String>,
+        client_assertion: Option<String>,
+    ) -> Result<ServiceAccount, OauthServiceError> {
+        let invalid_client = (Status::Unauthorized, "invalid_client", None);
+        let client_id = client_id.ok_or((
+            Status::BadRequest,
+            "invalid_request",
+            Some("Missing client_id"),
+        ))?;
+        let service_account = self
+            .service_account_repository
+            .get_by_client_id(&client_id)
+            .ok_or(invalid_client)?;
+
+        match (
+            client_secret,
+            client_assertion_type.as_deref(),
+            client_assertion,
+            &service_account.client_secret_hash,
+            &service_account.public_key,
+        ) {
+            (Some(secret), None, None, Some(secret_hash), _) => {
+                let hash = hash_token(&secret);
+                if !openssl::memcmp::eq(hash.as_bytes(), secret_hash.as_bytes()) {
+                    return Err(invalid_client);
+                }
+            }
+            (None, Some(CLIENT_ASSERTION_TYPE), Some(assertion), _, Some(public_key)) => {
+                let assertion = ClientAssertion::verify(
+                    &assertion,
+                    public_key,
+                    &service_account.client_id,
+                    &ClientAssertion::audience(),
+                )
+                .map_err(|_| invalid_client)?;
+                Self::consume_assertion(&service_account.client_id, &assertion)?;
+            }
+            _ => return Err(invalid_client),
+        }
+        Ok(service_account)
+    }
+
+    fn get_by_id(
+        &self,
+        service_account_uuid: &Uuid,
+        user_from: &User,
+    ) -> Result<ServiceAccount, ServiceAccountError> {
+        let app_id = user_from.application.ok_or((Status::Forbidden, None))?;
+        self.service_account_repository
+            .get_by_id_and_app(service_account_uuid, app_id)
+            .ok_or((Status::NotFound, Some("Service account not found")))
+    }
+
+    /// a public key replaces the secret, without one a new secret is generated
+    fn credentials(
+        public_key: Option<String>,
+    ) -> Result<(Option<String>, Option<String>), ServiceAccountError> {
+        match public_key {
+            Some(public_key) => match ClientAssertion::is_public_key(&public_key) {
+                true => Ok((None, Some(public_key))),
+                false => Err((Status::BadRequest, Some("Bad public key"))),
+            },
+            None => Ok((Some(generate_token()), None)),
+        }
+    }
+
+    /// remember the `jti` of an assertion until it expires so it cannot be replayed
+    fn consume_assertion(
+        client_id: &str,
+        assertion: &ClientAssertion,
+    ) -> Result<(), OauthServiceError> {
+        let ttl = (assertion.exp - Utc::now().timestamp()).max(1);
+        let mut conn = RedisClient::get_connection();
+        let stored: Option<String> = redis::cmd("SET")
+            .arg(format!("client_assertion:{}:{}", client_id, assertion.jti))
+            .arg(1)
+            .arg("NX")
+            .arg("EX")
+            .arg(ttl)
+            .query(&mut conn)
+            .map_err(|_| (Status::InternalServerError, "server_error", None))?;
+        match stored {
+            Some(_) => Ok(()),
+            None => Err((
+                Status::Unauthorized,
+                "invalid_client",
+                Some("The client assertion was already used"),
+            )),
+        }
+    }
+
+    /// lifetime of the service account tokens, `SERVICE_ACCOUNT_TOKEN_DURATION` in seconds
+    fn token_duration() -> i64 {
+        env::var("SERVICE_ACCOUNT_TOKEN_DURATION")
+            .unwrap_or_else(|_| "900".to_string())
+            .parse::<i64>()
+            .unwrap_or(900)
+    }
+}
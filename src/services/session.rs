@@ -0,0 +1,96 @@
+use std::net::IpAddr;
+
+use rocket::http::Status;
+use uuid::Uuid;
+
+use crate::{
+    db::connect::DbPool,
+    dto::audit_log::audit_log_insertable::AuditLogInsertable,
+    enums::{audit_action::AuditAction, audit_severity::AuditSeverity},
+    models::{connexion::Connexion, session::Session, user::User},
+    repositories::{session::SessionRepository, user::UserRepository},
+    services::audit::AuditService,
+    utils::token_deny_list::TokenDenyList,
+};
+
+type SessionError = (Status, Option<&'static str>);
+
+pub struct SessionService {
+    session_repository: SessionRepository,
+    user_repository: UserRepository,
+    audit_service: AuditService,
+}
+
+impl SessionService {
+    pub fn new(pool: &DbPool) -> Self {
+        Self {
+            session_repository: SessionRepository::new(pool),
+            user_repository: UserRepository::new(pool),
+            audit_service: AuditService::new(pool),
+        }
+    }
+
+    /// ### Get all
+    ///
+    /// the active sessions of a user with the connexion opening them. An admin lists
+    /// the sessions of the users of their application
+    pub fn get_all(
+        &self,
+        user_uuid: &Uuid,
+        user_from: &User,
+    ) -> Result<Vec<(Session, Option<Connexion>)>, SessionError> {
+        let user_to = self.get_user(user_uuid, user_from)?;
+        Ok(self.session_repository.get_active_by_user(&user_to.id))
+    }
+
+    /// ### Revoke
+    ///
+    /// end a session of a user, its access and refresh tokens are refused at once.
+    /// The revocation of the session of another user is audited
+    pub fn revoke(
+        &self,
+        session_uuid: &Uuid,
+        user_uuid: &Uuid,
+        user_from: &User,
+        ip: &IpAddr,
+    ) -> Result<(), SessionError> {
+        let user_to = self.get_user(user_uuid, user_from)?;
+        let session = match self.session_repository.get_active(session_uuid) {
+            Some(session) if session.user_id == user_to.id => session,
+            _ => return Err((Status::NotFound, Some("Session not found"))),
+        };
+        if user_to.id != user_from.id {
+            self.audit_service.record(AuditLogInsertable::new(
+                user_from,
+                AuditAction::SessionRevoked,
+                AuditSeverity::Medium,
+                Some(("session", session.id)),
+                None,
+                Some(ip),
+            ))?;
+        }
+        self.end(&session, Some(user_from.id))
+    }
+
+    /// ### End
+    ///
+    /// revoke a session and deny its tokens until the latest refresh token expires
+    pub fn end(&self, session: &Session, revoked_by: Option<Uuid>) -> Result<(), SessionError> {
+        self.session_repository
+            .revoke(&session.id, revoked_by)
+            .map_err(|_| (Status::InternalServerError, None))?;
+        TokenDenyList::revoke_session(&session.id, session.expires_at.timestamp())
+            .map_err(|_| (Status::ServiceUnavailable, None))
+    }
+
+    /// the user themselves, or a user of the application of the admin
+    fn get_user(&self, user_uuid: &Uuid, user_from: &User) -> Result<User, SessionError> {
+        if user_uuid == &user_from.id {
+            return Ok(user_from.clone());
+        }
+        user_from
+            .application
+            .and_then(|app_id| self.user_repository.get_by_id_and_app(user_uuid, app_id))
+            .ok_or((Status::NotFound, Some("User not found")))
+    }
+}
@@ -0,0 +1,198 @@
+use std::{env, fs};
+
+use chrono::{Duration, Utc};
+use openssl::rsa::Rsa;
+
+use crate::{
+    db::connect::DbPool,
+    dto::signing_key::signing_key_insertable::SigningKeyInsertable,
+    models::signing_key::SigningKey,
+    redis::RedisClient,
+    repositories::signing_key::SigningKeyRepository,
+    utils::{
+        crypto::Crypto,
+        jwt::Jwt,
+        key_ring::{KeyRing, RingKey},
+    },
+};
+
+type SigningKeyError = &'static str;
+
+pub struct SigningKeyService {
+    signing_key_repository: SigningKeyRepository,
+}
+
+impl SigningKeyService {
+    pub fn new(pool: &DbPool) -> Self {
+        Self {
+            signing_key_repository: SigningKeyRepository::new(pool),
+        }
+    }
+
+    /// ### Load
+    ///
+    /// install the trusted keys in the key ring. The private keys are only decrypted
+    /// for the key signing now and the keys published to replace it.
+    /// A first launch imports the key of `JWT_PRIVATE_KEY_PATH` when it exists,
+    /// so the tokens already issued stay valid, or generates one
+    pub fn load(&self) -> Result<(), SigningKeyError> {
+        let mut keys = self.signing_key_repository.get_trusted();
+        if keys.is_empty() {
+            keys = self.bootstrap()?;
+        }
+        let now = Utc::now();
+        let signing_from = keys
+            .iter()
+            .find(|key| key.activates_at <= now)
+            .map(|key| key.activates_at);
+        let installed = KeyRing::keys();
+        let mut ring = Vec::new();
+        for key in keys {
+            let can_sign = signing_from.is_none_or(|from| key.activates_at >= from);
+            let ring_key = match installed
+                .iter()
+                .find(|ring_key| ring_key.kid == key.kid && ring_key.can_sign() == can_sign)
+            {
+                Some(ring_key) => ring_key.clone(),
+                None if can_sign => {
+                    let private_key = Crypto::decrypt(key.private_key, key.iv);
+                    RingKey::new(&key.public_key, Some(&private_key), key.activates_at)?
+                }
+                None => RingKey::new(&key.public_key, None, key.activates_at)?,
+            };
+            ring.push(ring_key);
+        }
+        KeyRing::install(ring);
+        Ok(())
+    }
+
+    /// ### Rotate
+    ///
+    /// generate a key, published at once through the JWKS and signing
+    /// once the `SIGNING_KEY_PUBLICATION_DELAY` is over
+    pub fn rotate(&self) -> Result<SigningKey, SigningKeyError> {
+        let (public_key, private_key) = KeyRing::generate();
+        let activates_at = Utc::now() + Duration::seconds(Self::publication_delay());
+        let signing_key = self.store(public_key, private_key, activates_at)?;
+        self.load()?;
+        Ok(signing_key)
+    }
+
+    /// ### Scheduled rotation
+    ///
+    /// publish the next key when the latest one reaches `SIGNING_KEY_ROTATION_DAYS`,
+    /// then retire the keys replaced for longer than `SIGNING_KEY_RETIREMENT_DELAY`.
+    /// A single node runs it at a time, every node reloads its key ring
+    pub fn rotate_if_due(&self) -> Result<(), SigningKeyError> {
+        if Self::lock_rotation() {
+            let keys = self.signing_key_repository.get_trusted();
+            let now = Utc::now();
+            let rotation_days = Self::rotation_days();
+            if let Some(latest) = keys.first() {
+                let due_at = latest.activates_at + Duration::days(rotation_days)
+                    - Duration::seconds(Self::publication_delay());
+                if rotation_days > 0 && due_at <= now {
+                    self.rotate()?;
+                }
+            }
+            if let Some(signing_key) = keys.iter().find(|key| key.activates_at <= now) {
+                if signing_key.activates_at + Duration::seconds(Self::retirement_delay()) <= now {
+                    self.signing_key_repository
+                        .retire_before(signing_key.activates_at)
+                        .map_err(|_| "Unable to retire the signing keys")?;
+                }
+            }
+        }
+        self.load()
+    }
+
+    fn bootstrap(&self) -> Result<Vec<SigningKey>, SigningKeyError> {
+        let path = env::var("JWT_PRIVATE_KEY_PATH")
+            .unwrap_or_else(|_| "ssh_keys/private.key".to_string());
+        let (public_key, private_key) = match fs::read_to_string(path) {
+            Err(_) => KeyRing::generate(),
+            Ok(private_key) => {
+                let rsa = Rsa::private_key_from_pem(private_key.as_bytes())
+                    .map_err(|_| "Bad private key in JWT_PRIVATE_KEY_PATH")?;
+                let public_key = rsa
+                    .public_key_to_pem()
+                    .map_err(|_| "Bad private key in JWT_PRIVATE_KEY_PATH")?;
+                (String::from_utf8_lossy(&public_key).to_string(), private_key)
+            }
+        };
+        match self.store(public_key, private_key, Utc::now()) {
+            Ok(signing_key) => Ok(vec![signing_key]),
+            // another node stored its key first
+            Err(error) => match self.signing_key_repository.get_trusted() {
+                keys if keys.is_empty() => Err(error),
+                keys => Ok(keys),
+            },
+        }
+    }
+
+    fn store(
+        &self,
+        public_key: String,
+        private_key: String,
+        activates_at: chrono::DateTime<Utc>,
+    ) -> Result<SigningKey, SigningKeyError> {
+        let ring_key = RingKey::new(&public_key, Some(&private_key), activates_at)?;
+        let iv = Crypto::generate_unique_iv();
+        let encrypted = Crypto::encrypt(private_key, iv.clone());
+        self.signing_key_repository
+            .create_signing_key(SigningKeyInsertable::new(
+                ring_key.kid,
+                public_key,
+                encrypted,
+                iv,
+                activates_at,
+            ))
+            .map_err(|_| "Unable to store the signing key")
+    }
+
+    /// hold the rotation for a minute, `false` when another node holds it
+    fn lock_rotation() -> bool {
+        let mut conn = RedisClient::get_connection();
+        let locked: Option<String> = redis::cmd("SET")
+            .arg("signing_key_rotation")
+            .arg(1)
+            .arg("NX")
+            .arg("EX")
+            .arg(60)
+            .query(&mut conn)
+            .unwrap_or(None);
+        locked.is_some()
+    }
+
+    /// age in days of a key when the next one is published, `0` disables the scheduled rotation
+    fn rotation_days() -> i64 {
+        env::var("SIGNING_KEY_ROTATION_DAYS")
+            .unwrap_or_else(|_| "90".to_string())
+            .parse::<i64>()
+            .unwrap_or(90)
+    }
+
+    /// seconds a new key is published before it signs, longer than the caches of the JWKS
+    fn publication_delay() -> i64 {
+        env::var("SIGNING_KEY_PUBLICATION_DELAY")
+            .unwrap_or_else(|_| "86400".to_string())
+            .parse::<i64>()
+            .unwrap_or(86400)
+    }
+
+    /// seconds a replaced key still checks tokens, never shorter than the refresh tokens live
+    fn retirement_delay() -> i64 {
+        Self::clamp_retirement_delay(
+            env::var("SIGNING_KEY_RETIREMENT_DELAY")
+                .ok()
+                .and_then(|delay| delay.parse::<i64>().ok()),
+            Jwt::refresh_duration(),
+        )
+    }
+
+    /// a key retired before the refresh tokens it signed expire would log their users out
+    pub fn clamp_retirement_delay(configured: Option<i64>, refresh_duration: Duration) -> i64 {
+        let refresh_delay = refresh_duration.num_seconds();
+        configured.unwrap_or(refresh_delay).max(refresh_delay)
+    }
+}
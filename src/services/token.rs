@@ -0,0 +1,221 @@
+use chrono::{DateTime, Utc};
+use jsonwebtoken::{Algorithm, Validation};
+use rocket::http::Status;
+
+use crate::{
+    db::connect::DbPool,
+    dto::{
+        oauth::oauth_introspection_output::OauthIntrospectionOutput,
+        revoked_token::revoked_token_insertable::RevokedTokenInsertable,
+    },
+    models::{oauth_client::OauthClient, service_account::ServiceAccount, user::User},
+    repositories::{
+        oauth_client::OauthClientRepository, revoked_token::RevokedTokenRepository,
+        service_account::ServiceAccountRepository, session::SessionRepository,
+        user::UserRepository,
+    },
+    services::{
+        oauth::{OauthService, OauthServiceError},
+        service_account::ServiceAccountService,
+        session::SessionService,
+    },
+    traits::revoked_token::RevokedTokenContract,
+    utils::{
+        code::hash_token, jwt::Jwt, key_ring::KeyRing, open_id::OpenId,
+        token_deny_list::TokenDenyList,
+    },
+};
+
+/// ### TokenCaller
+///
+/// who asks for the introspection or the revocation of a token
+pub enum TokenCaller {
+    /// a registered client, a public client can only revoke its tokens
+    OauthClient(Box<OauthClient>),
+    ServiceAccount(Box<ServiceAccount>),
+    /// a user of the first party application revoking their own tokens
+    User(Box<User>),
+}
+
+impl TokenCaller {
+    fn application_id(&self) -> Option<i32> {
+        match self {
+            TokenCaller::OauthClient(oauth_client) => Some(oauth_client.application_id),
+            TokenCaller::ServiceAccount(service_account) => Some(service_account.application_id),
+            TokenCaller::User(user) => user.application,
+        }
+    }
+}
+
+pub struct TokenService {
+    pool: DbPool,
+    oauth_client_repository: OauthClientRepository,
+    service_account_repository: ServiceAccountRepository,
+    user_repository: UserRepository,
+    revoked_token_repository: RevokedTokenRepository,
+    session_repository: SessionRepository,
+}
+
+impl TokenService {
+    pub fn new(pool: &DbPool) -> Self {
+        Self {
+            pool: pool.clone(),
+            oauth_client_repository: OauthClientRepository::new(pool),
+            service_account_repository: ServiceAccountRepository::new(pool),
+            user_repository: UserRepository::new(pool),
+            revoked_token_repository: RevokedTokenContract::new(pool),
+            session_repository: SessionRepository::new(pool),
+        }
+    }
+
+    /// ### Authenticate caller
+    ///
+    /// a service account, its client id starts with `sa_`, or a registered client
+    pub fn authenticate_caller(
+        &self,
+        client_id: Option<String>,
+        client_secret: Option<String>,
+        client_assertion_type: Option<String>,
+        client_assertion: Option<String>,
+    ) -> Result<TokenCaller, OauthServiceError> {
+        match client_id.as_deref().is_some_and(|id| id.starts_with("sa_")) {
+            true => ServiceAccountService::new(&self.pool)
+                .authenticate(client_id, client_secret, client_assertion_type, client_assertion)
+                .map(Box::new)
+                .map(TokenCaller::ServiceAccount),
+            false if client_assertion.is_some() => {
+                Err((Status::Unauthorized, "invalid_client", None))
+            }
+            false => OauthService::new(&self.pool)
+                .authenticate_client(client_id, client_secret)
+                .map(Box::new)
+                .map(TokenCaller::OauthClient),
+        }
+    }
+
+    /// ### Introspect
+    ///
+    /// the state of a token of the application of the caller, for the resource servers.
+    /// A public client can not introspect
+    pub fn introspect(
+        &self,
+        caller: &TokenCaller,
+        token: &str,
+    ) -> Result<OauthIntrospectionOutput, OauthServiceError> {
+        match caller {
+            TokenCaller::OauthClient(oauth_client) if oauth_client.client_secret_hash.is_none() => {
+                return Err((Status::Unauthorized, "invalid_client", None))
+            }
+            TokenCaller::User(_) => return Err((Status::Unauthorized, "invalid_client", None)),
+            _ => {}
+        }
+        let claims = match self.decode(token) {
+            Some(claims) if Some(claims.application_id) == caller.application_id() => claims,
+            _ => return Ok(OauthIntrospectionOutput::inactive()),
+        };
+        if !self.is_active(&claims, token) {
+            return Ok(OauthIntrospectionOutput::inactive());
+        }
+        Ok(OauthIntrospectionOutput::new(&claims, OpenId::issuer()))
+    }
+
+    /// ### Revoke
+    ///
+    /// deny a token until it expires, a refresh token ends its session. A client revokes the tokens
+    /// issued to it, a service account and a user their own tokens. An invalid or expired token
+    /// needs no revocation and is accepted
+    pub fn revoke(&self, caller: &TokenCaller, token: &str) -> Result<(), OauthServiceError> {
+        let claims = match self.decode(token) {
+            None => return Ok(()),
+            Some(claims) => claims,
+        };
+        let is_owner = Some(claims.application_id) == caller.application_id()
+            && match caller {
+                TokenCaller::OauthClient(oauth_client) => {
+                    claims.oauth_client_id.as_ref() == Some(&oauth_client.client_id)
+                }
+                TokenCaller::ServiceAccount(service_account) => {
+                    claims.is_service_account && claims.id == service_account.user_id
+                }
+                TokenCaller::User(user) => claims.id == user.id,
+            };
+        if !is_owner {
+            return Err((
+                Status::BadRequest,
+                "unauthorized_client",
+                Some("The token was not issued to this client"),
+            ));
+        }
+        if let Some(jti) = claims.jti {
+            TokenDenyList::revoke(&jti, claims.exp)
+                .map_err(|_| (Status::ServiceUnavailable, "temporarily_unavailable", None))?;
+        }
+        match (claims.is_refresh, claims.sid) {
+            // the refresh token of a session ends the session and its access tokens
+            (true, Some(sid)) => {
+                if let Some(session) = self.session_repository.get_active(&sid) {
+                    SessionService::new(&self.pool)
+                        .end(&session, Some(claims.id))
+                        .map_err(|_| (Status::ServiceUnavailable, "temporarily_unavailable", None))?;
+                }
+            }
+            (true, None) => {
+                let expires_at = DateTime::<Utc>::from_timestamp(claims.exp, 0).unwrap_or_default();
+                self.revoked_token_repository.purge_expired();
+                // already revoked when the insertion fails
+                let _ = self
+                    .revoked_token_repository
+                    .create_revoked_token(RevokedTokenInsertable::new(hash_token(token), expires_at));
+            }
+            (false, _) => {}
+        }
+        Ok(())
+    }
+
+    /// the claims of a token signed by the key ring and not expired
+    fn decode(&self, token: &str) -> Option<Jwt> {
+        KeyRing::decode::<Jwt>(token, &Validation::new(Algorithm::RS256))
+            .ok()
+            .map(|token| token.claims)
+    }
+
+    /// a token not revoked whose user, and client or service account, still exist
+    fn is_active(&self, claims: &Jwt, token: &str) -> bool {
+        if claims.is_revoked() {
+            return false;
+        }
+        let is_refresh_revoked = match (claims.is_refresh, claims.sid) {
+            (false, _) => false,
+            // only the latest refresh token of the session is accepted
+            (true, Some(sid)) => self
+                .session_repository
+                .get_active(&sid)
+                .is_none_or(|session| Some(session.refresh_jti) != claims.jti),
+            (true, None) => self
+                .revoked_token_repository
+                .get_by_token_hash(&hash_token(token))
+                .is_some(),
+        };
+        if is_refresh_revoked {
+            return false;
+        }
+        let user = match self
+            .user_repository
+            .get_by_login_and_app(&claims.login, claims.application_id)
+        {
+            Some(user) if user.id == claims.id => user,
+            _ => return false,
+        };
+        match (&claims.oauth_client_id, claims.is_service_account) {
+            (Some(client_id), _) => self
+                .oauth_client_repository
+                .get_by_client_id(client_id)
+                .is_some_and(|oauth_client| user.application == Some(oauth_client.application_id)),
+            (None, true) => self
+                .service_account_repository
+                .get_by_user_id(&user.id)
+                .is_some(),
+            (None, false) => true,
+        }
+    }
+}
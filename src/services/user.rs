@@ -1,21 +1,28 @@
+use std::net::IpAddr;
+
 use chrono::Utc;
 use rocket::http::Status;
 use uuid::Uuid;
 
 use crate::{
-    db::connect::DbPool, dto::user::{
+    db::connect::DbPool, dto::audit_log::audit_log_insertable::AuditLogInsertable, dto::user::{
         user_input::UserInput, user_insertable::UserInsertable, user_public_input::UserPublicInput,
-        user_totp_code::UserTotpCode,
-    }, models::user::User, repositories::{
-        application::ApplicationRepository, user::UserRepository,
-    }, traits::{application::ApplicationContract, connexion::ConnexionContract}, utils::{code, crypto::Crypto, password::PasswordUtils, pq_kyber::PQKyber}, LICENSE_VALID
+        user_recovery_codes::UserRecoveryCodes, user_totp_code::UserTotpCode,
+    }, enums::{attempt_scope::AttemptScope, audit_action::AuditAction, audit_severity::AuditSeverity}, models::user::User, repositories::{
+        application::ApplicationRepository, recovery_code::RecoveryCodeRepository,
+        user::UserRepository,
+    }, traits::{application::ApplicationContract, connexion::ConnexionContract}, utils::{code, crypto::Crypto, password::PasswordUtils, pq_kyber::PQKyber, used_otp_steps::UsedOtpSteps}, LICENSE_VALID
 };
 
+use super::audit::AuditService;
+use super::lockout::LockoutService;
 use super::mail::MailService;
 
 pub struct UserService<T, D> {
     pool: DbPool,
     user_repository: UserRepository,
+    recovery_code_repository: RecoveryCodeRepository,
+    lockout_service: LockoutService,
     application_repository: T,
     connexion_repository: D,
 }
@@ -35,6 +42,8 @@ impl<T: ApplicationContract, D: ConnexionContract> UserService<T, D> {
         Self {
             pool: pool.clone(),
             user_repository: UserRepository::new(&pool),
+            recovery_code_repository: RecoveryCodeRepository::new(&pool),
+            lockout_service: LockoutService::new(&pool),
             application_repository,
             connexion_repository,
         }
@@ -110,38 +119,45 @@ impl<T: ApplicationContract, D: ConnexionContract> UserService<T, D> {
         }
     }
 
+    /// validate the account with the emailed code, a code failing three times is replaced by a
+    /// new one and the failures count toward the lock of the account
     pub async fn validate_user(
         &self,
         user: &User,
         validation_code: &String,
+        ip: &IpAddr,
     ) -> Result<User, Status> {
-        match user.is_validated {
-            true => Ok(user.clone()),
-            false => match PasswordUtils::compare_hash(
-                validation_code.clone(),
-                user.clone().validation_code.unwrap(),
-            ) {
-                false => {
-                    if user.validation_tries >= 2 {
-                        let new_code = code::generate_reset_code();
-                        let _ = self
-                            .user_repository
-                            .update_validation_code(&new_code, &user.id);
-                        MailService::send_validation_code(&user.email, &user.login, &new_code)
-                            .await;
-                        return Err(Status::TooManyRequests);
-                    };
-                    let _ = self
-                        .user_repository
-                        .update_validation_tries(&(user.validation_tries + 1), &user.id);
-                    Err(Status::Unauthorized)
-                }
-                true => match self.user_repository.validate_account(&user.id) {
-                    Err(_) => Err(Status::BadRequest),
-                    Ok(user) => Ok(user),
-                },
-            },
+        if user.is_validated {
+            return Ok(user.clone());
+        }
+        let app_id = user.application.unwrap_or_default();
+        self.lockout_service
+            .check(AttemptScope::Validation, app_id, &user.login, ip)
+            .map_err(|(status, _)| status)?;
+        let valid = user.validation_code.as_ref().is_some_and(|hash| {
+            PasswordUtils::compare_hash(code::normalize_reset_code(validation_code), hash.clone())
+        });
+        if !valid {
+            self.lockout_service
+                .fail(AttemptScope::Validation, app_id, &user.login, ip, Some(user));
+            if user.validation_tries >= 2 {
+                let new_code = code::generate_reset_code();
+                let _ = self
+                    .user_repository
+                    .update_validation_code(&new_code, &user.id);
+                MailService::send_validation_code(&user.email, &user.login, &new_code).await;
+                return Err(Status::TooManyRequests);
+            };
+            let _ = self
+                .user_repository
+                .update_validation_tries(&(user.validation_tries + 1), &user.id);
+            return Err(Status::Unauthorized);
         }
+        self.lockout_service
+            .succeed(AttemptScope::Validation, app_id, &user.login);
+        self.user_repository
+            .validate_account(&user.id)
+            .map_err(|_| Status::BadRequest)
     }
 
     pub async fn send_reset_code(&self, login: &String, app_id: i32) {
@@ -154,52 +170,58 @@ impl<T: ApplicationContract, D: ConnexionContract> UserService<T, D> {
         MailService::send_forget_code(&user.email, &user.login, &new_code).await;
     }
 
+    /// reset the password with the emailed code, usable once. A code failing three times must be
+    /// asked again and the failures count toward the lock of the account
     pub fn reset_user_code(
         &self,
         login: &String,
         app_id: i32,
         validation_code: String,
         password: String,
+        ip: &IpAddr,
     ) -> Result<User, Status> {
-        let user_repository = UserRepository::new(&self.pool);
-        let user = match user_repository.get_by_login_and_app(login, app_id) {
-            None => return Err(Status::Unauthorized),
+        self.lockout_service
+            .check(AttemptScope::PasswordReset, app_id, login, ip)
+            .map_err(|(status, _)| status)?;
+        let user = match self.user_repository.get_by_login_and_app(login, app_id) {
+            None => {
+                self.lockout_service
+                    .fail(AttemptScope::PasswordReset, app_id, login, ip, None);
+                return Err(Status::Unauthorized);
+            }
             Some(user) => user,
         };
-        let u = user.clone();
         if user.validation_tries >= 2 {
             return Err(Status::TooManyRequests);
         }
-        let user_code = match user.validation_code {
-            None => {
-                let _ =
-                    user_repository.update_validation_tries(&(user.validation_tries + 1), &user.id);
-                return Err(Status::NotFound);
-            }
-            Some(user_code) => user_code,
+        let failure = |status: Status| {
+            self.lockout_service
+                .fail(AttemptScope::PasswordReset, app_id, login, ip, Some(&user));
+            let _ = self
+                .user_repository
+                .update_validation_tries(&(user.validation_tries + 1), &user.id);
+            Err(status)
         };
-        match PasswordUtils::compare_hash(validation_code, user_code) {
-            false => {
-                let _ =
-                    user_repository.update_validation_tries(&(user.validation_tries + 1), &user.id);
-                return Err(Status::Unauthorized);
-            }
-            true => {}
+        let user_code = match &user.validation_code {
+            None => return failure(Status::NotFound),
+            Some(user_code) => user_code.clone(),
         };
+        if !PasswordUtils::compare_hash(code::normalize_reset_code(&validation_code), user_code) {
+            return failure(Status::Unauthorized);
+        }
         let delay = match user.forget_code_delay {
-            None => {
-                let _ =
-                    user_repository.update_validation_tries(&(user.validation_tries + 1), &user.id);
-                return Err(Status::Unauthorized);
-            }
+            None => return failure(Status::Unauthorized),
             Some(delay) => delay,
         };
         match delay > Utc::now() {
             false => Err(Status::RequestTimeout),
             true => {
+                self.lockout_service
+                    .succeed(AttemptScope::PasswordReset, app_id, login);
                 let hash_password = PasswordUtils::hash_password(password);
-                let _ = user_repository.update_user_password(&user.id, hash_password);
-                Ok(u)
+                let _ = self.user_repository.update_user_password(&user.id, hash_password);
+                let _ = self.user_repository.end_password_reset(&user.id);
+                Ok(user)
             }
         }
     }
@@ -284,6 +306,83 @@ impl<T: ApplicationContract, D: ConnexionContract> UserService<T, D> {
         Ok(())
     }
 
+    /// ### Set break glass
+    ///
+    /// grant or revoke the break-glass role of a user of the admin's application, the change is audited
+    pub fn set_break_glass(
+        &self,
+        user_uuid: Uuid,
+        enabled: bool,
+        user_from: User,
+        ip: &IpAddr,
+    ) -> Result<User, Status> {
+        let user_to = match self
+            .user_repository
+            .get_by_id_and_app(&user_uuid, user_from.application.unwrap())
+        {
+            None => return Err(Status::NotFound),
+            Some(user_to) => user_to,
+        };
+        let mut roles: Vec<Option<String>> = user_to
+            .roles
+            .into_iter()
+            .filter(|role| role.as_deref() != Some("ROLE_BREAK_GLASS"))
+            .collect();
+        if enabled {
+            roles.push(Some(String::from("ROLE_BREAK_GLASS")));
+        }
+        let user_to = self
+            .user_repository
+            .update_roles(&user_uuid, roles, &user_from.id)
+            .map_err(|_| Status::NotFound)?;
+        AuditService::new(&self.pool)
+            .record(AuditLogInsertable::new(
+                &user_from,
+                match enabled {
+                    true => AuditAction::RoleGranted,
+                    false => AuditAction::RoleRevoked,
+                },
+                AuditSeverity::Medium,
+                Some(("user", user_uuid)),
+                None,
+                Some(ip),
+            ))
+            .map_err(|(status, _)| status)?;
+        Ok(user_to)
+    }
+
+    /// restrict a user of the application of the admin to some IPs, audited
+    pub fn set_restricted_ip(
+        &self,
+        user_uuid: Uuid,
+        restricted_ip: Vec<String>,
+        user_from: User,
+        ip: &IpAddr,
+    ) -> Result<User, Status> {
+        if self
+            .user_repository
+            .get_by_id_and_app(&user_uuid, user_from.application.unwrap())
+            .is_none()
+        {
+            return Err(Status::NotFound);
+        }
+        let user_to = self
+            .user_repository
+            .update_restricted_ip(&user_uuid, restricted_ip, &user_from.id)
+            .map_err(|_| Status::NotFound)?;
+        AuditService::new(&self.pool)
+            .record(AuditLogInsertable::new(
+                &user_from,
+                AuditAction::IpRestrictionUpdated,
+                AuditSeverity::Medium,
+                Some(("user", user_uuid)),
+                None,
+                Some(ip),
+            ))
+            .map_err(|(status, _)| status)?;
+        Ok(user_to)
+    }
+
     pub fn get_totp_code(&self, user: &User) -> Result<UserTotpCode, Status> {
         let application = self
             .application_repository
@@ -294,20 +393,88 @@ impl<T: ApplicationContract, D: ConnexionContract> UserService<T, D> {
         Ok(UserTotpCode::new(user, application))
     }
 
+    /// activate 2FA with a code of the new secret, the recovery codes are given once. 2FA is
+    /// deactivated with a TOTP code or a recovery code
     pub fn activate_2fa(
         &self,
         code: String,
         secret_key: String,
         user: User,
         activate: bool,
-    ) -> Result<(), Status> {
-        let code = code.parse::<u32>().unwrap();
-        match code::check_otp(code, secret_key) {
-            false => Err(Status::BadRequest),
+        ip: &IpAddr,
+    ) -> Result<UserRecoveryCodes, Status> {
+        match activate {
             true => {
-                let _ = self.user_repository.activate_2fa(&user.id, activate);
+                let totp = code.parse::<u32>().map_err(|_| Status::BadRequest)?;
+                let step = code::check_otp(totp, &secret_key).ok_or(Status::BadRequest)?;
+                if !UsedOtpSteps::claim(&user.id, step, code::totp_window()) {
+                    return Err(Status::BadRequest);
+                }
+                let recovery_codes: Vec<String> = (0..code::RECOVERY_CODE_COUNT)
+                    .map(|_| code::generate_recovery_code())
+                    .collect();
+                let code_hashes = recovery_codes
+                    .iter()
+                    .map(|recovery_code| {
+                        code::hash_token(&code::normalize_recovery_code(recovery_code))
+                    })
+                    .collect();
+                self.recovery_code_repository
+                    .replace_codes(&user.id, code_hashes)
+                    .map_err(|_| Status::InternalServerError)?;
+                let _ = self.user_repository.activate_2fa(&user.id, true);
+                Ok(UserRecoveryCodes::new(recovery_codes))
+            }
+            false => {
+                self.check_second_factor(&user, &code, ip)
+                    .map_err(|status| match status == Status::TooManyRequests {
+                        true => status,
+                        false => Status::BadRequest,
+                    })?;
+                let _ = self.user_repository.activate_2fa(&user.id, false);
+                let _ = self.recovery_code_repository.delete_by_user(&user.id);
+                Ok(UserRecoveryCodes::new(vec![]))
+            }
+        }
+    }
+
+    /// a TOTP code not used yet in the window around now, or an unused recovery code. The
+    /// failures count toward the lock of the account
+    pub fn check_second_factor(&self, user: &User, code: &str, ip: &IpAddr) -> Result<(), Status> {
+        let app_id = user.application.unwrap_or_default();
+        self.lockout_service
+            .check(AttemptScope::SecondFactor, app_id, &user.login, ip)
+            .map_err(|(status, _)| status)?;
+        let valid = match code.parse::<u32>() {
+            Ok(totp) if code.len() == 6 => code::check_otp(totp, &user.twofa_code)
+                .is_some_and(|step| UsedOtpSteps::claim(&user.id, step, code::totp_window())),
+            _ => {
+                let code_hash = code::hash_token(&code::normalize_recovery_code(code));
+                let used = self.recovery_code_repository.consume(&user.id, &code_hash);
+                if used {
+                    let _ = AuditService::new(&self.pool).record(AuditLogInsertable::new(
+                        user,
+                        AuditAction::RecoveryCodeUsed,
+                        AuditSeverity::High,
+                        Some(("user", user.id)),
+                        None,
+                        Some(ip),
+                    ));
+                }
+                used
+            }
+        };
+        match valid {
+            true => {
+                self.lockout_service
+                    .succeed(AttemptScope::SecondFactor, app_id, &user.login);
                 Ok(())
             }
+            false => {
+                self.lockout_service
+                    .fail(AttemptScope::SecondFactor, app_id, &user.login, ip, Some(user));
+                Err(Status::Unauthorized)
+            }
         }
     }
 
@@ -319,29 +486,20 @@ impl<T: ApplicationContract, D: ConnexionContract> UserService<T, D> {
         fingerprint: String,
         user_agent: String,
     ) -> Result<(), Status> {
-        let ip_connexion = self.connexion_repository.get_user_ip_connexion(&user, &ip);
-        let fingerprint_connexion = self
-            .connexion_repository
-            .get_user_fingerprint_connexion(&user, &fingerprint);
-        if ip_connexion.is_none() || fingerprint_connexion.is_none() {
+        if !self.is_familiar_connexion(&user, &ip, &fingerprint) {
             match otp {
                 None => return Err(Status::BadRequest),
-                Some(totp) => {
-                    let totp = match totp.parse::<u32>() {
-                        Err(_) => 0,
-                        Ok(totp) => totp,
-                    };
-                    if code::check_otp(totp, user.twofa_code) {
-                        MailService::send_unfamiliar_connexion(
-                            &user.email,
-                            &user.login,
-                            &ip,
-                            &user_agent,
-                        )
-                        .await;
-                        return Ok(());
-                    };
-                    return Err(Status::Unauthorized);
+                Some(otp) => {
+                    let addr = ip.parse::<IpAddr>().map_err(|_| Status::BadRequest)?;
+                    self.check_second_factor(&user, &otp, &addr)?;
+                    MailService::send_unfamiliar_connexion(
+                        &user.email,
+                        &user.login,
+                        &ip,
+                        &user_agent,
+                    )
+                    .await;
+                    return Ok(());
                 }
             }
         }
@@ -355,13 +513,20 @@ impl<T: ApplicationContract, D: ConnexionContract> UserService<T, D> {
         fingerprint: String,
         user_agent: String,
     ) {
-        let ip_connexion = self.connexion_repository.get_user_ip_connexion(&user, &ip);
-        let fingerprint_connexion = self
-            .connexion_repository
-            .get_user_fingerprint_connexion(&user, &fingerprint);
-        if ip_connexion.is_none() || fingerprint_connexion.is_none() {
+        if !self.is_familiar_connexion(&user, &ip, &fingerprint) {
             MailService::send_unfamiliar_connexion(&user.email, &user.login, &ip, &user_agent)
                 .await;
         }
     }
+
+    /// the user already logged in from this ip and this device
+    pub fn is_familiar_connexion(&self, user: &User, ip: &String, fingerprint: &String) -> bool {
+        self.connexion_repository
+            .get_user_ip_connexion(user, ip)
+            .is_some()
+            && self
+                .connexion_repository
+                .get_user_fingerprint_connexion(user, fingerprint)
+                .is_some()
+    }
 }
@@ -0,0 +1,226 @@
+use rocket::http::Status;
+use uuid::Uuid;
+
+use crate::{
+    db::connect::DbPool,
+    dto::webauthn::{
+        webauthn_assertion_input::WebauthnAssertionInput,
+        webauthn_assertion_options::WebauthnAssertionOptions,
+        webauthn_assertion_start_input::WebauthnAssertionStartInput,
+        webauthn_credential_insertable::WebauthnCredentialInsertable,
+        webauthn_registration_input::WebauthnRegistrationInput,
+        webauthn_registration_options::WebauthnRegistrationOptions,
+    },
+    models::{user::User, webauthn_credential::WebauthnCredential},
+    redis::RedisClient,
+    repositories::{user::UserRepository, webauthn_credential::WebauthnCredentialRepository},
+    utils::{
+        code::generate_token,
+        webauthn::{AuthenticatorData, Webauthn, CEREMONY_TIMEOUT},
+    },
+};
+
+type WebauthnError = (Status, Option<&'static str>);
+
+pub struct WebauthnService {
+    webauthn_credential_repository: WebauthnCredentialRepository,
+    user_repository: UserRepository,
+}
+
+impl WebauthnService {
+    pub fn new(pool: &DbPool) -> Self {
+        Self {
+            webauthn_credential_repository: WebauthnCredentialRepository::new(pool),
+            user_repository: UserRepository::new(pool),
+        }
+    }
+
+    /// ### Registration options
+    ///
+    /// start the registration of a passkey, the challenge replaces the one of a registration in progress
+    pub fn registration_options(&self, user: &User) -> Result<WebauthnRegistrationOptions, WebauthnError> {
+        let challenge = generate_token();
+        Self::store_challenge(&Self::registration_key(&user.id), &challenge)?;
+        Ok(WebauthnRegistrationOptions::new(
+            challenge,
+            user,
+            &self.webauthn_credential_repository.get_by_user(&user.id),
+        ))
+    }
+
+    /// ### Register
+    ///
+    /// store the passkey created by the authenticator for the challenge of the registration
+    pub fn register(
+        &self,
+        user: &User,
+        input: WebauthnRegistrationInput,
+    ) -> Result<WebauthnCredential, WebauthnError> {
+        let name = input.name.unwrap_or_else(|| "Passkey".to_string());
+        let name = name.trim();
+        if name.is_empty() || name.chars().count() > 100 {
+            return Err((Status::BadRequest, Some("Bad passkey name")));
+        }
+        let challenge = Self::take_challenge(&Self::registration_key(&user.id))
+            .ok_or((Status::BadRequest, Some("No passkey registration in progress")))?;
+        let client_data_json = Self::decode(&input.client_data_json)?;
+        let client_data = Webauthn::client_data(&client_data_json, "webauthn.create")
+            .map_err(|e| (Status::BadRequest, Some(e)))?;
+        if client_data.challenge != challenge {
+            return Err((Status::BadRequest, Some("Bad challenge")));
+        }
+        let authenticator_data = Webauthn::attestation(&Self::decode(&input.attestation_object)?)
+            .map_err(|e| (Status::BadRequest, Some(e)))?;
+        Webauthn::check_authenticator_data(&authenticator_data, false)
+            .map_err(|e| (Status::BadRequest, Some(e)))?;
+        let credential = authenticator_data
+            .credential
+            .ok_or((Status::BadRequest, Some("No credential in the attestation")))?;
+        let credential_id = Webauthn::encode_base64(&credential.credential_id);
+        if credential_id != input.id.trim_end_matches('=') {
+            return Err((Status::BadRequest, Some("Bad credential id")));
+        }
+        if self
+            .webauthn_credential_repository
+            .get_by_credential_id(&credential_id)
+            .is_some()
+        {
+            return Err((Status::Conflict, Some("Passkey already registered")));
+        }
+        self.webauthn_credential_repository
+            .create_credential(WebauthnCredentialInsertable::new(
+                user.id,
+                name.to_string(),
+                credential_id,
+                credential.public_key,
+                credential.algorithm,
+                authenticator_data.sign_count as i64,
+            ))
+            .map_err(|_| (Status::InternalServerError, None))
+    }
+
+    pub fn get_all(&self, user: &User) -> Vec<WebauthnCredential> {
+        self.webauthn_credential_repository.get_by_user(&user.id)
+    }
+
+    pub fn delete(&self, credential_uuid: &Uuid, user: &User) -> Result<(), WebauthnError> {
+        let credential = self
+            .get_all(user)
+            .into_iter()
+            .find(|credential| &credential.id == credential_uuid)
+            .ok_or((Status::NotFound, Some("Passkey not found")))?;
+        self.webauthn_credential_repository
+            .delete(&credential, user)
+            .map(|_| ())
+            .map_err(|_| (Status::InternalServerError, None))
+    }
+
+    /// ### Assertion options
+    ///
+    /// start a login with a passkey. With a login the passkeys of the user are allowed, as a second factor,
+    /// without one the authenticator offers its passkeys for a passwordless login
+    pub fn assertion_options(
+        &self,
+        input: WebauthnAssertionStartInput,
+    ) -> Result<WebauthnAssertionOptions, WebauthnError> {
+        let credentials = input
+            .login
+            .and_then(|login| self.user_repository.get_by_login_and_app(&login, input.application_id))
+            .map(|user| self.webauthn_credential_repository.get_by_user(&user.id))
+            .unwrap_or_default();
+        let challenge = generate_token();
+        Self::store_challenge(&Self::assertion_key(&challenge), &input.application_id.to_string())?;
+        Ok(WebauthnAssertionOptions::new(challenge, &credentials))
+    }
+
+    /// ### Authenticate
+    ///
+    /// check an assertion signed for a challenge of the application, by a passkey of `user` when
+    /// it is a second factor. A signature counter going back reveals a cloned authenticator
+    pub fn authenticate(
+        &self,
+        application_id: i32,
+        input: &WebauthnAssertionInput,
+        user: Option<&User>,
+        require_user_verification: bool,
+    ) -> Result<(User, WebauthnCredential), WebauthnError> {
+        let unauthorized = |e: &'static str| (Status::Unauthorized, Some(e));
+        let client_data_json = Self::decode(&input.client_data_json)?;
+        let client_data =
+            Webauthn::client_data(&client_data_json, "webauthn.get").map_err(unauthorized)?;
+        if Self::take_challenge(&Self::assertion_key(&client_data.challenge))
+            != Some(application_id.to_string())
+        {
+            return Err(unauthorized("Bad challenge"));
+        }
+        let credential = self
+            .webauthn_credential_repository
+            .get_by_credential_id(input.id.trim_end_matches('='))
+            .ok_or(unauthorized("Unknown passkey"))?;
+        let user_to = self
+            .user_repository
+            .get_by_id_and_app(&credential.user_id, application_id)
+            .filter(|user_to| user.is_none_or(|user| user.id == user_to.id))
+            .ok_or(unauthorized("Unknown passkey"))?;
+        if let Some(user_handle) = input.user_handle.as_deref().filter(|handle| !handle.is_empty()) {
+            if Self::decode(user_handle)? != user_to.id.as_bytes() {
+                return Err(unauthorized("Bad user handle"));
+            }
+        }
+        let authenticator_data_bytes = Self::decode(&input.authenticator_data)?;
+        let authenticator_data =
+            AuthenticatorData::parse(&authenticator_data_bytes).map_err(unauthorized)?;
+        Webauthn::check_authenticator_data(&authenticator_data, require_user_verification)
+            .map_err(unauthorized)?;
+        if !Webauthn::verify_signature(
+            &credential.public_key,
+            credential.algorithm,
+            &authenticator_data_bytes,
+            &client_data_json,
+            &Self::decode(&input.signature)?,
+        ) {
+            return Err(unauthorized("Bad passkey signature"));
+        }
+        let sign_count = authenticator_data.sign_count as i64;
+        if (sign_count != 0 || credential.sign_count != 0) && sign_count <= credential.sign_count {
+            return Err(unauthorized("Passkey signature counter went back"));
+        }
+        self.webauthn_credential_repository
+            .update_usage(&credential.id, sign_count)
+            .map_err(|_| (Status::InternalServerError, None))?;
+        Ok((user_to, credential))
+    }
+
+    pub fn has_credentials(&self, user: &User) -> bool {
+        !self.get_all(user).is_empty()
+    }
+
+    fn decode(value: &str) -> Result<Vec<u8>, WebauthnError> {
+        Webauthn::decode_base64(value).map_err(|e| (Status::BadRequest, Some(e)))
+    }
+
+    fn registration_key(user_uuid: &Uuid) -> String {
+        format!("webauthn_registration:{}", user_uuid)
+    }
+
+    fn assertion_key(challenge: &str) -> String {
+        format!("webauthn_assertion:{}", challenge)
+    }
+
+    fn store_challenge(key: &str, value: &str) -> Result<(), WebauthnError> {
+        let mut conn = RedisClient::get_connection();
+        redis::cmd("SET")
+            .arg(key)
+            .arg(value)
+            .arg("EX")
+            .arg(CEREMONY_TIMEOUT)
+            .query::<()>(&mut conn)
+            .map_err(|_| (Status::ServiceUnavailable, None))
+    }
+
+    /// a challenge is used once
+    fn take_challenge(key: &str) -> Option<String> {
+        let mut conn = RedisClient::get_connection();
+        redis::cmd("GETDEL").arg(key).query(&mut conn).ok().flatten()
+    }
+}
@@ -0,0 +1,334 @@
+#[cfg(test)]
+mod access_request_tests {
+    use chrono::{Duration, Utc};
+    use uuid::Uuid;
+
+    use crate::{
+        dto::access_request::access_request_output::AccessRequestOutput,
+        enums::access_request_status::AccessRequestStatus,
+        mocks::access_request::{AccessRequestMocks, OUTSIDER},
+        models::{access_request::AccessRequest, sentinel::Sentinel, user::User},
+        services::access_request::AccessRequestService,
+        traits::access_request::AccessRequestContractWithoutPool,
+    };
+
+    use rocket::{http::Status, tokio};
+
+    const WAITING: (Status, Option<&str>) = (
+        Status::Accepted,
+        Some("The key is under dual control, the access request is waiting for approvals"),
+    );
+
+    fn access_request_service() -> AccessRequestService<AccessRequestMocks> {
+        let access_request_repository: AccessRequestMocks =
+            AccessRequestContractWithoutPool::new_without_pool();
+        AccessRequestService::new(access_request_repository)
+    }
+
+    fn sentinel(required_approvals: i32) -> Sentinel {
+        Sentinel {
+            id: Uuid::new_v4(),
+            application_id: 1,
+            iv: String::from("iv"),
+            sum: String::from("sum"),
+            is_deleted: false,
+            created_at: Utc::now(),
+            updated_at: None,
+            deleted_at: None,
+            created_by_id: None,
+            updated_by_id: None,
+            deleted_by_id: None,
+            key_size: 256,
+            purpose: String::from("encryption"),
+            required_approvals,
+            break_glass_at: None,
+            break_glass_by_id: None,
+        }
+    }
+
+    fn user(id: Uuid) -> User {
+        User {
+            id,
+            email: String::from("test@test.com"),
+            firstname: String::from("test"),
+            lastname: String::from("test"),
+            twofa_code: String::from("123"),
+            is_2fa_activated: false,
+            login: String::from("test"),
+            roles: vec![Some(String::from("ROLE_USER"))],
+            password: Some(String::from("test")),
+            full_text_search: String::from("test"),
+            kyber_secret_key: String::from("test"),
+            kyber_public_key: String::from("test"),
+            iv: String::from("test"),
+            is_deleted: false,
+            created_at: Utc::now(),
+            updated_at: None,
+            deleted_at: None,
+            created_by_id: None,
+            updated_by_id: None,
+            deleted_by_id: None,
+            refresh_token: None,
+            application: Some(1),
+            restricted_ip: vec![],
+            is_validated: true,
+            validation_code: None,
+            validation_tries: 0,
+            forget_code_delay: None,
+        }
+    }
+
+    fn access_request(status: AccessRequestStatus, expires_in: i64) -> AccessRequest {
+        AccessRequest {
+            id: Uuid::new_v4(),
+            sentinel_id: Some(Uuid::new_v4()),
+            anonymous_sentinel_id: None,
+            requester_id: Uuid::new_v4(),
+            required_approvals: 2,
+            status: status.as_str().to_string(),
+            expires_at: Utc::now() + Duration::minutes(expires_in),
+            is_deleted: false,
+            created_at: Utc::now(),
+            updated_at: None,
+            deleted_at: None,
+            created_by_id: None,
+            updated_by_id: None,
+            deleted_by_id: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn output_status() {
+        let output = AccessRequestOutput::new(access_request(AccessRequestStatus::Pending, 30), 1);
+        assert_eq!(output.status, "pending");
+        assert_eq!(output.approvals, 1);
+        let output = AccessRequestOutput::new(access_request(AccessRequestStatus::Approved, -1), 2);
+        assert_eq!(output.status, "expired");
+        let output = AccessRequestOutput::new(access_request(AccessRequestStatus::Consumed, -1), 2);
+        assert_eq!(output.status, "consumed");
+        assert!(AccessRequestStatus::from_str("rejected") == Some(AccessRequestStatus::Rejected));
+    }
+
+    #[tokio::test]
+    async fn gate_waits_for_approvals() {
+        let access_request_service = access_request_service();
+        let requester = user(Uuid::new_v4());
+        let free = sentinel(0);
+        assert_eq!(access_request_service.gate_sentinel(&free, &requester), Ok(None));
+
+        let guarded = sentinel(2);
+        assert_eq!(
+            access_request_service.gate_sentinel(&guarded, &requester).unwrap_err(),
+            WAITING
+        );
+        // a second read waits on the same request
+        assert_eq!(
+            access_request_service.gate_sentinel(&guarded, &requester).unwrap_err(),
+            WAITING
+        );
+        let access_requests = access_request_service.get_all(&requester);
+        assert_eq!(access_requests.len(), 1);
+        let (access_request, approvals) = &access_requests[0];
+        assert_eq!(access_request.sentinel_id, Some(guarded.id));
+        assert_eq!(access_request.required_approvals, 2);
+        assert_eq!(access_request.status, "pending");
+        assert_eq!(*approvals, 0);
+    }
+
+    #[tokio::test]
+    async fn gate_uses_the_clusters_requirement() {
+        let access_request_service =
+            AccessRequestService::new(AccessRequestMocks::with_clusters_requirement(3));
+        let requester = user(Uuid::new_v4());
+        let free = sentinel(0);
+        assert_eq!(access_request_service.sentinel_required_approvals(&free), 3);
+        assert_eq!(
+            access_request_service.gate_sentinel(&free, &requester).unwrap_err(),
+            WAITING
+        );
+        let (access_request, _) = access_request_service.get_all(&requester).remove(0);
+        assert_eq!(access_request.required_approvals, 3);
+    }
+
+    #[tokio::test]
+    async fn requester_can_not_approve_their_own_request() {
+        let access_request_service = access_request_service();
+        let requester = user(Uuid::new_v4());
+        let _ = access_request_service.gate_sentinel(&sentinel(1), &requester);
+        let (access_request, _) = access_request_service.get_all(&requester).remove(0);
+
+        assert_eq!(
+            access_request_service
+                .approve(access_request.id, &requester)
+                .unwrap_err(),
+            (
+                Status::Forbidden,
+                Some("The requester can not approve their own request")
+            )
+        );
+        assert_eq!(
+            access_request_service
+                .approve(access_request.id, &user(OUTSIDER))
+                .unwrap_err(),
+            (Status::NotFound, None)
+        );
+        let (access_request, approvals) = access_request_service
+            .get_by_id(access_request.id, &requester)
+            .unwrap();
+        assert_eq!(access_request.status, "pending");
+        assert_eq!(approvals, 0);
+    }
+
+    #[tokio::test]
+    async fn an_approver_counts_once() {
+        let access_request_service = access_request_service();
+        let requester = user(Uuid::new_v4());
+        let approver = user(Uuid::new_v4());
+        let _ = access_request_service.gate_sentinel(&sentinel(2), &requester);
+        let (access_request, _) = access_request_service.get_all(&requester).remove(0);
+
+        let (approved, approvals) = access_request_service
+            .approve(access_request.id, &approver)
+            .unwrap();
+        assert_eq!(approved.status, "pending");
+        assert_eq!(approvals, 1);
+        assert_eq!(
+            access_request_service
+                .approve(access_request.id, &approver)
+                .unwrap_err(),
+            (
+                Status::Conflict,
+                Some("The request is already approved by this user")
+            )
+        );
+        let (access_request, approvals) = access_request_service
+            .get_by_id(access_request.id, &requester)
+            .unwrap();
+        assert_eq!(access_request.status, "pending");
+        assert_eq!(approvals, 1);
+    }
+
+    #[tokio::test]
+    async fn released_after_the_required_approvals() {
+        let access_request_service = access_request_service();
+        let requester = user(Uuid::new_v4());
+        let guarded = sentinel(3);
+        let _ = access_request_service.gate_sentinel(&guarded, &requester);
+        let (access_request, _) = access_request_service.get_all(&requester).remove(0);
+
+        for expected_approvals in 1..=2 {
+            let (pending, approvals) = access_request_service
+                .approve(access_request.id, &user(Uuid::new_v4()))
+                .unwrap();
+            assert_eq!(pending.status, "pending");
+            assert_eq!(approvals, expected_approvals);
+            assert_eq!(
+                access_request_service.gate_sentinel(&guarded, &requester).unwrap_err(),
+                WAITING
+            );
+        }
+        let (approved, approvals) = access_request_service
+            .approve(access_request.id, &user(Uuid::new_v4()))
+            .unwrap();
+        assert_eq!(approved.status, "approved");
+        assert_eq!(approvals, 3);
+        assert_eq!(
+            access_request_service
+                .approve(access_request.id, &user(Uuid::new_v4()))
+                .unwrap_err(),
+            (Status::Conflict, Some("The request is not pending anymore"))
+        );
+
+        // the approved request releases the key once
+        let released = access_request_service
+            .gate_sentinel(&guarded, &requester)
+            .unwrap();
+        assert_eq!(released.as_ref().map(|released| released.id), Some(access_request.id));
+        assert!(access_request_service.consume(released.clone(), &requester).is_ok());
+        assert_eq!(
+            access_request_service.consume(released, &requester).unwrap_err(),
+            (
+                Status::Forbidden,
+                Some("The access request was already used or is expired")
+            )
+        );
+        assert_eq!(
+            access_request_service.gate_sentinel(&guarded, &requester).unwrap_err(),
+            WAITING
+        );
+    }
+
+    #[tokio::test]
+    async fn a_rejection_closes_the_request() {
+        let access_request_service = access_request_service();
+        let requester = user(Uuid::new_v4());
+        let approver = user(Uuid::new_v4());
+        let _ = access_request_service.gate_sentinel(&sentinel(2), &requester);
+        let (access_request, _) = access_request_service.get_all(&requester).remove(0);
+
+        let (rejected, _) = access_request_service
+            .reject(access_request.id, &approver)
+            .unwrap();
+        assert_eq!(rejected.status, "rejected");
+        assert_eq!(
+            access_request_service
+                .approve(access_request.id, &user(Uuid::new_v4()))
+                .unwrap_err(),
+            (Status::Conflict, Some("The request is not pending anymore"))
+        );
+    }
+
+    #[tokio::test]
+    async fn status_after_approvals() {
+        let access_request = access_request(AccessRequestStatus::Pending, 30);
+        assert!(access_request.status_after(1) == AccessRequestStatus::Pending);
+        assert!(access_request.status_after(2) == AccessRequestStatus::Approved);
+        assert!(access_request.status_after(3) == AccessRequestStatus::Approved);
+    }
+
+    #[tokio::test]
+    async fn release_waits_for_the_approved_request() {
+        let access_request_service = access_request_service();
+        let requester = user(Uuid::new_v4());
+        let guarded = sentinel(1);
+
+        // the HMAC and the streaming reads are gated like the export of the key
+        let mut reads = 0;
+        assert_eq!(
+            access_request_service
+                .release_sentinel(guarded.clone(), &requester, |_| {
+                    reads += 1;
+                    Ok(())
+                })
+                .unwrap_err(),
+            WAITING
+        );
+        assert_eq!(reads, 0);
+
+        let (access_request, _) = access_request_service.get_all(&requester).remove(0);
+        let _ = access_request_service.approve(access_request.id, &user(Uuid::new_v4()));
+        // a failed read keeps the approval
+        assert_eq!(
+            access_request_service
+                .release_sentinel(guarded.clone(), &requester, |_| {
+                    Err::<(), _>((Status::Forbidden, None))
+                })
+                .unwrap_err(),
+            (Status::Forbidden, None)
+        );
+        let released = access_request_service
+            .release_sentinel(guarded.clone(), &requester, |sentinel| Ok(sentinel.id))
+            .unwrap();
+        assert_eq!(released, guarded.id);
+        let (access_request, _) = access_request_service
+            .get_by_id(access_request.id, &requester)
+            .unwrap();
+        assert_eq!(access_request.status, "consumed");
+        assert_eq!(
+            access_request_service
+                .release_sentinel(guarded, &requester, |_| Ok(()))
+                .unwrap_err(),
+            WAITING
+        );
+    }
+}
@@ -140,6 +140,7 @@ mod application_tests {
             id: 123,
             name: Some(String::from("Updated App")),
             contact_email: Some(String::from("updated@example.com")),
+            allowed_ips: None,
         };
 
         let app = application_service.update_application(input, user).unwrap();
@@ -186,6 +187,7 @@ mod application_tests {
             id: 999,  // ID non existant
             name: Some(String::from("Updated App")),
             contact_email: Some(String::from("updated@example.com")),
+            allowed_ips: None,
         };
 
         let result = application_service.update_application(input, user);
@@ -0,0 +1,169 @@
+#[cfg(test)]
+mod audit_tests {
+    use std::net::IpAddr;
+
+    use chrono::Utc;
+    use rocket::http::Status;
+    use uuid::Uuid;
+
+    use crate::{mocks::audit::AuditMocks, models::user::User, services::audit::AuditService};
+
+    use rocket::tokio;
+
+    fn user(login: &str, role: &str, application: i32) -> User {
+        User {
+            id: Uuid::new_v4(),
+            email: format!("{}@test.com", login),
+            firstname: String::from("test"),
+            lastname: String::from("test"),
+            twofa_code: String::from("123"),
+            is_2fa_activated: false,
+            login: String::from(login),
+            roles: vec![Some(String::from("ROLE_USER")), Some(String::from(role))],
+            password: Some(String::from("test")),
+            full_text_search: String::from("test"),
+            kyber_secret_key: String::from("test"),
+            kyber_public_key: String::from("test"),
+            iv: String::from("test"),
+            is_deleted: false,
+            created_at: Utc::now(),
+            updated_at: None,
+            deleted_at: None,
+            created_by_id: None,
+            updated_by_id: None,
+            deleted_by_id: None,
+            refresh_token: None,
+            application: Some(application),
+            restricted_ip: vec![],
+            is_validated: true,
+            validation_code: None,
+            validation_tries: 0,
+            forget_code_delay: None,
+        }
+    }
+
+    fn admins() -> Vec<User> {
+        vec![
+            user("alice", "ROLE_ADMIN", 1),
+            user("bob", "ROLE_ADMIN", 1),
+            // the admin of another application is not alerted
+            user("carol", "ROLE_ADMIN", 2),
+        ]
+    }
+
+    fn ip() -> IpAddr {
+        "10.1.2.3".parse().unwrap()
+    }
+
+    #[tokio::test]
+    async fn break_glass_justification() {
+        assert_eq!(
+            AuditService::check_justification("  Incident 4521, payroll outage  ").unwrap(),
+            "Incident 4521, payroll outage"
+        );
+        let (status, _) = AuditService::check_justification("   urgent   ").unwrap_err();
+        assert_eq!(status, Status::BadRequest);
+        assert!(AuditService::check_justification(&"a".repeat(2001)).is_err());
+    }
+
+    #[tokio::test]
+    async fn break_glass_writes_the_record_and_alerts_the_admins() {
+        let audit_mocks = AuditMocks::with_admins(admins());
+        let audit_service = AuditService::with_repository(audit_mocks.clone());
+        let responder = user("dave", "ROLE_BREAK_GLASS", 1);
+        let sentinel_id = Uuid::new_v4();
+
+        audit_service
+            .break_glass(
+                &responder,
+                ("sentinel", sentinel_id),
+                "Incident 4521, payroll outage",
+                &ip(),
+            )
+            .unwrap();
+
+        let audit_logs = audit_mocks.audit_logs();
+        assert_eq!(audit_logs.len(), 1);
+        let audit_log = &audit_logs[0];
+        assert_eq!(audit_log.action, "break_glass_read");
+        assert_eq!(audit_log.severity, "high");
+        assert_eq!(audit_log.application_id, Some(1));
+        assert_eq!(audit_log.user_id, Some(responder.id));
+        assert_eq!(audit_log.target_type.as_deref(), Some("sentinel"));
+        assert_eq!(audit_log.target_id, Some(sentinel_id));
+        assert_eq!(
+            audit_log.justification.as_deref(),
+            Some("Incident 4521, payroll outage")
+        );
+        assert_eq!(audit_log.ip.as_deref(), Some("10.1.2.3"));
+        assert_eq!(
+            audit_service
+                .get_all(&responder, Some(String::from("high")))
+                .unwrap(),
+            audit_logs
+        );
+
+        let alerts = audit_mocks.alerts();
+        assert_eq!(
+            alerts
+                .iter()
+                .map(|alert| alert.email.as_str())
+                .collect::<Vec<&str>>(),
+            vec!["alice@test.com", "bob@test.com"]
+        );
+        for alert in alerts {
+            assert_eq!(alert.requester, "dave");
+            assert_eq!(alert.key, format!("sentinel {}", sentinel_id));
+            assert_eq!(alert.justification, "Incident 4521, payroll outage");
+            assert_eq!(alert.ip, "10.1.2.3");
+        }
+    }
+
+    #[tokio::test]
+    async fn break_glass_requires_the_role() {
+        let audit_mocks = AuditMocks::with_admins(admins());
+        let audit_service = AuditService::with_repository(audit_mocks.clone());
+        // an admin does not hold the break-glass role
+        for user_from in [user("eve", "ROLE_USER", 1), user("alice", "ROLE_ADMIN", 1)] {
+            assert_eq!(
+                AuditService::check_break_glass_role(&user_from).unwrap_err(),
+                (Status::Forbidden, Some("The break-glass role is required"))
+            );
+            assert_eq!(
+                audit_service
+                    .break_glass(
+                        &user_from,
+                        ("anonymous_sentinel", Uuid::new_v4()),
+                        "Incident 4521, payroll outage",
+                        &ip(),
+                    )
+                    .unwrap_err(),
+                (Status::Forbidden, Some("The break-glass role is required"))
+            );
+        }
+        assert!(audit_mocks.audit_logs().is_empty());
+        assert!(audit_mocks.alerts().is_empty());
+        assert!(AuditService::check_break_glass_role(&user("dave", "ROLE_BREAK_GLASS", 1)).is_ok());
+    }
+
+    #[tokio::test]
+    async fn break_glass_stops_without_its_record() {
+        let audit_mocks = AuditMocks::failing(admins());
+        let audit_service = AuditService::with_repository(audit_mocks.clone());
+        assert_eq!(
+            audit_service
+                .break_glass(
+                    &user("dave", "ROLE_BREAK_GLASS", 1),
+                    ("sentinel", Uuid::new_v4()),
+                    "Incident 4521, payroll outage",
+                    &ip(),
+                )
+                .unwrap_err(),
+            (
+                Status::InternalServerError,
+                Some("Unable to write the audit record")
+            )
+        );
+        assert!(audit_mocks.alerts().is_empty());
+    }
+}
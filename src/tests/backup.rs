@@ -0,0 +1,207 @@
+#[cfg(test)]
+mod backup_tests {
+    use crate::{
+        dto::backup::{
+            backup_archive::BackupArchive, backup_content::BackupContent,
+            backup_key_fragments::BackupKeyFragments,
+        },
+        models::sentinel::Sentinel,
+        services::backup::BackupService,
+        utils::backup_signing_keys::BackupSigningKeys,
+    };
+
+    use base64::{engine::general_purpose, Engine as _};
+    use openssl::{
+        pkey::{PKey, Private, Public},
+        rsa::Rsa,
+    };
+    use chrono::Utc;
+    use rocket::tokio;
+    use uuid::Uuid;
+
+    const BACKUP_KEY: [u8; 32] = [7u8; 32];
+
+    fn generate_key() -> PKey<Private> {
+        PKey::from_rsa(Rsa::generate(2048).unwrap()).unwrap()
+    }
+
+    fn public_key(key: &PKey<Private>) -> PKey<Public> {
+        PKey::public_key_from_pem(&key.public_key_to_pem().unwrap()).unwrap()
+    }
+
+    fn content() -> BackupContent {
+        BackupContent {
+            fragments: vec![BackupKeyFragments::new(
+                Uuid::new_v4(),
+                vec![String::from("1-abcd"), String::from("2-ef01")],
+            )],
+            ..Default::default()
+        }
+    }
+
+    fn sentinel(is_deleted: bool) -> Sentinel {
+        Sentinel {
+            id: Uuid::new_v4(),
+            application_id: 1,
+            iv: String::from("iv"),
+            sum: String::from("sum"),
+            is_deleted,
+            created_at: Utc::now(),
+            updated_at: None,
+            deleted_at: None,
+            created_by_id: None,
+            updated_by_id: None,
+            deleted_by_id: None,
+            key_size: 256,
+            purpose: String::from("encryption"),
+            required_approvals: 0,
+            break_glass_at: None,
+            break_glass_by_id: None,
+        }
+    }
+
+    fn same(a: &BackupContent, b: &BackupContent) -> bool {
+        serde_json::to_value(a).unwrap() == serde_json::to_value(b).unwrap()
+    }
+
+    #[tokio::test]
+    async fn archive_round_trip() {
+        let keys = BackupSigningKeys::new(Some(generate_key()), vec![]).unwrap();
+        let content = content();
+        let archive = BackupService::seal(&content, &BACKUP_KEY, &keys).unwrap();
+        assert_eq!(archive.key_id, keys.signing_key_id().unwrap());
+
+        // the archive is written and read as JSON by the commands
+        let archive: BackupArchive =
+            serde_json::from_str(&serde_json::to_string(&archive).unwrap()).unwrap();
+        let restored = BackupService::open(&archive, &BACKUP_KEY, &keys).unwrap();
+        assert!(same(&content, &restored));
+
+        assert_eq!(
+            BackupService::open(&archive, &[8u8; 32], &keys).unwrap_err(),
+            "Failed to decrypt the backup, check the BACKUP_KEY"
+        );
+    }
+
+    #[tokio::test]
+    async fn archive_of_a_former_key() {
+        let former_key = generate_key();
+        let former_keys = BackupSigningKeys::new(Some(former_key.clone()), vec![]).unwrap();
+        let archive = BackupService::seal(&content(), &BACKUP_KEY, &former_keys).unwrap();
+
+        let keys = BackupSigningKeys::new(Some(generate_key()), vec![]).unwrap();
+        assert!(BackupService::open(&archive, &BACKUP_KEY, &keys)
+            .unwrap_err()
+            .starts_with("Unknown backup signing key"));
+
+        // a restore only needs the public keys
+        let keys = BackupSigningKeys::new(Some(generate_key()), vec![public_key(&former_key)]).unwrap();
+        assert!(BackupService::open(&archive, &BACKUP_KEY, &keys).is_ok());
+        let keys = BackupSigningKeys::new(None, vec![public_key(&former_key)]).unwrap();
+        assert!(BackupService::open(&archive, &BACKUP_KEY, &keys).is_ok());
+        assert_eq!(
+            BackupService::seal(&content(), &BACKUP_KEY, &keys).unwrap_err(),
+            "BACKUP_SIGNING_KEY_PATH is not set"
+        );
+    }
+
+    #[tokio::test]
+    async fn tampered_archive() {
+        let keys = BackupSigningKeys::new(Some(generate_key()), vec![]).unwrap();
+        let archive = BackupService::seal(&content(), &BACKUP_KEY, &keys).unwrap();
+
+        let mut payload = general_purpose::STANDARD.decode(&archive.payload).unwrap();
+        payload[0] ^= 1;
+        let tampered_payload = BackupArchive {
+            payload: general_purpose::STANDARD.encode(payload),
+            ..archive.clone()
+        };
+        let tampered_date = BackupArchive {
+            created_at: archive.created_at - chrono::Duration::days(1),
+            ..archive.clone()
+        };
+        let other_key = BackupArchive {
+            key_id: String::from("unknown"),
+            ..archive.clone()
+        };
+        assert_eq!(
+            BackupService::open(&other_key, &BACKUP_KEY, &keys).unwrap_err(),
+            "Unknown backup signing key unknown"
+        );
+        for tampered in [tampered_payload, tampered_date] {
+            assert_eq!(
+                BackupService::open(&tampered, &BACKUP_KEY, &keys).unwrap_err(),
+                "The backup signature is not valid"
+            );
+        }
+
+        for version in [1, 9] {
+            let unsupported = BackupArchive {
+                version,
+                ..archive.clone()
+            };
+            assert_eq!(
+                BackupService::open(&unsupported, &BACKUP_KEY, &keys).unwrap_err(),
+                format!("Unsupported backup version {}", version)
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn wrong_signature() {
+        let keys = BackupSigningKeys::new(Some(generate_key()), vec![]).unwrap();
+        let archive = BackupService::seal(&content(), &BACKUP_KEY, &keys).unwrap();
+
+        // signed by another key while recording the trusted one
+        let other_keys = BackupSigningKeys::new(Some(generate_key()), vec![]).unwrap();
+        let forged = BackupArchive {
+            signature: other_keys.sign(&archive.signed_data()).unwrap(),
+            ..archive.clone()
+        };
+        assert_eq!(
+            BackupService::open(&forged, &BACKUP_KEY, &keys).unwrap_err(),
+            "The backup signature is not valid"
+        );
+        let garbage = BackupArchive {
+            signature: String::from("not base64!"),
+            ..archive
+        };
+        assert_eq!(
+            BackupService::open(&garbage, &BACKUP_KEY, &keys).unwrap_err(),
+            "Bad backup signature"
+        );
+    }
+
+    #[tokio::test]
+    async fn archive_without_signing_key() {
+        // the archives written before they recorded their signing key are not read anymore
+        let keys = BackupSigningKeys::new(Some(generate_key()), vec![]).unwrap();
+        let archive = BackupService::seal(&content(), &BACKUP_KEY, &keys).unwrap();
+        let mut json = serde_json::to_value(&archive).unwrap();
+        json["version"] = serde_json::Value::from(1);
+        json.as_object_mut().unwrap().remove("key_id");
+        assert!(serde_json::from_value::<BackupArchive>(json).is_err());
+    }
+
+    #[tokio::test]
+    async fn every_live_key_has_its_fragments() {
+        let live = sentinel(false);
+        let deleted = sentinel(true);
+        let mut content = BackupContent {
+            sentinels: vec![live.clone(), deleted.clone()],
+            ..Default::default()
+        };
+        assert_eq!(content.live_key_ids(), vec![live.id]);
+        assert_eq!(
+            content.check_fragments().unwrap_err(),
+            format!("The backup has no fragments for the key {}", live.id)
+        );
+
+        // the deleted keys are not restored, they need no fragments
+        content.fragments.push(BackupKeyFragments::new(
+            live.id,
+            vec![String::from("1-abcd"), String::from("2-ef01")],
+        ));
+        assert!(content.check_fragments().is_ok());
+    }
+}
@@ -0,0 +1,145 @@
+#[cfg(test)]
+mod dpop_tests {
+    use crate::utils::{dpop::Dpop, key_ring::KeyRing};
+
+    use base64::{engine::general_purpose, Engine};
+    use jsonwebtoken::{jwk::Jwk, Algorithm, EncodingKey, Header};
+    use openssl::{
+        bn::{BigNum, BigNumContext},
+        ec::{EcGroup, EcKey},
+        nid::Nid,
+        pkey::{PKey, Private},
+    };
+    use rocket::tokio;
+    use serde_json::{json, Value};
+
+    const HTU: &str = "https://api.example.com/sentinels";
+    const NOW: i64 = 1_700_000_000;
+
+    fn generate_key() -> PKey<Private> {
+        let group = EcGroup::from_curve_name(Nid::X9_62_PRIME256V1).unwrap();
+        PKey::from_ec_key(EcKey::generate(&group).unwrap()).unwrap()
+    }
+
+    fn public_jwk(key: &PKey<Private>) -> Value {
+        let ec_key = key.ec_key().unwrap();
+        let mut x = BigNum::new().unwrap();
+        let mut y = BigNum::new().unwrap();
+        ec_key
+            .public_key()
+            .affine_coordinates(ec_key.group(), &mut x, &mut y, &mut BigNumContext::new().unwrap())
+            .unwrap();
+        let encode = |n: &BigNum| general_purpose::URL_SAFE_NO_PAD.encode(n.to_vec_padded(32).unwrap());
+        json!({ "kty": "EC", "crv": "P-256", "x": encode(&x), "y": encode(&y) })
+    }
+
+    /// a proof signed by `key` with `jwk` in its header
+    fn proof(key: &PKey<Private>, jwk: Value, typ: &str, claims: Value) -> String {
+        let mut header = Header::new(Algorithm::ES256);
+        header.typ = Some(typ.to_string());
+        header.jwk = Some(serde_json::from_value::<Jwk>(jwk).unwrap());
+        let encoding_key =
+            EncodingKey::from_ec_pem(&key.private_key_to_pem_pkcs8().unwrap()).unwrap();
+        jsonwebtoken::encode(&header, &claims, &encoding_key).unwrap()
+    }
+
+    fn claims(access_token: Option<&str>) -> Value {
+        let mut claims = json!({ "jti": "e1j3V_bKic8-LAEB", "htm": "GET", "htu": HTU, "iat": NOW });
+        if let Some(access_token) = access_token {
+            claims["ath"] = json!(Dpop::access_token_hash(access_token));
+        }
+        claims
+    }
+
+    #[tokio::test]
+    async fn proof_is_bound_to_its_key() {
+        let key = generate_key();
+        let jwk = public_jwk(&key);
+        let proof = proof(&key, jwk.clone(), "dpop+jwt", claims(None));
+        let verified = Dpop::verify_at(&proof, "GET", HTU, None, NOW).unwrap();
+        assert_eq!(verified.jkt, Dpop::thumbprint(&jwk).unwrap());
+        assert_eq!(verified.claims.jti, "e1j3V_bKic8-LAEB");
+
+        // the query and the fragment are not compared
+        let with_query = format!("{}?page=2", HTU);
+        assert!(Dpop::verify_at(&proof, "GET", &with_query, None, NOW).is_ok());
+        assert!(Dpop::verify_at(&proof, "POST", HTU, None, NOW).is_err());
+        assert!(Dpop::verify_at(&proof, "GET", "https://api.example.com/users", None, NOW).is_err());
+    }
+
+    #[tokio::test]
+    async fn proof_expires() {
+        let key = generate_key();
+        let proof = proof(&key, public_jwk(&key), "dpop+jwt", claims(None));
+        assert!(Dpop::verify_at(&proof, "GET", HTU, None, NOW + 60).is_ok());
+        assert_eq!(
+            Dpop::verify_at(&proof, "GET", HTU, None, NOW + Dpop::proof_lifetime() + 1).unwrap_err(),
+            "The DPoP proof has expired"
+        );
+        assert_eq!(
+            Dpop::verify_at(&proof, "GET", HTU, None, NOW - 3600).unwrap_err(),
+            "The DPoP proof has expired"
+        );
+    }
+
+    #[tokio::test]
+    async fn proof_carries_the_access_token_hash() {
+        let key = generate_key();
+        let jwk = public_jwk(&key);
+        let bound = proof(&key, jwk.clone(), "dpop+jwt", claims(Some("access.token")));
+        assert!(Dpop::verify_at(&bound, "GET", HTU, Some("access.token"), NOW).is_ok());
+        assert!(Dpop::verify_at(&bound, "GET", HTU, Some("other.token"), NOW).is_err());
+
+        let unbound = proof(&key, jwk, "dpop+jwt", claims(None));
+        assert_eq!(
+            Dpop::verify_at(&unbound, "GET", HTU, Some("access.token"), NOW).unwrap_err(),
+            "The DPoP proof was issued for another access token"
+        );
+    }
+
+    #[tokio::test]
+    async fn proof_refuses_bad_headers() {
+        let key = generate_key();
+        let jwk = public_jwk(&key);
+
+        let untyped = proof(&key, jwk.clone(), "JWT", claims(None));
+        assert_eq!(
+            Dpop::verify_at(&untyped, "GET", HTU, None, NOW).unwrap_err(),
+            "The DPoP proof must be typed dpop+jwt"
+        );
+
+        // signed by another key than the one of its header
+        let forged = proof(&generate_key(), jwk.clone(), "dpop+jwt", claims(None));
+        assert_eq!(
+            Dpop::verify_at(&forged, "GET", HTU, None, NOW).unwrap_err(),
+            "Invalid DPoP proof"
+        );
+
+        // a key of another curve than the algorithm
+        let mut p384 = jwk;
+        p384["crv"] = json!("P-384");
+        let mismatch = proof(&key, p384, "dpop+jwt", claims(None));
+        assert_eq!(
+            Dpop::verify_at(&mismatch, "GET", HTU, None, NOW).unwrap_err(),
+            "The DPoP algorithm does not match the key"
+        );
+
+        assert!(Dpop::verify_at("not.a.proof", "GET", HTU, None, NOW).is_err());
+    }
+
+    #[tokio::test]
+    async fn thumbprint_of_the_keys() {
+        // RFC 7638 section 3.1
+        let n = "0vx7agoebGcQSuuPiLJXZptN9nndrQmbXEps2aiAFbWhM78LhWx4cbbfAAtVT86zwu1RK7aPFFxuhDR1L6tSoc_BJECPebWKRXjBZCiFV4n3oknjhMstn64tZ_2W-5JsGY4Hc5n9yBXArwl93lqt7_RN5w6Cf0h4QyQ5v-65YGjQR0_FDW2QvzqY368QQMicAtaSqzs8KJZgnYb9c7d0zgdAZHzu6qMQvRL5hajrn1n91CbOpbISD08qNLyrdkt-bFTWhAI4vMQFh6WeZu0fM4lFd2NcRwr3XPksINHaQ-G_xBniIqbw0Ls1jF44-csFCur-kEgU8awapJzKnqDKgw";
+        let jwk = json!({ "kty": "RSA", "n": n, "e": "AQAB", "alg": "RS256", "kid": "2011-04-29" });
+        assert_eq!(
+            Dpop::thumbprint(&jwk).unwrap(),
+            "NzbLsXh8uDCcd-6MNwXF4W_7noWXFZAfHkxZsRGC9Xs"
+        );
+        assert_eq!(Dpop::thumbprint(&jwk).unwrap(), KeyRing::thumbprint(n, "AQAB"));
+        assert_eq!(
+            Dpop::thumbprint(&json!({ "kty": "oct", "k": "c2VjcmV0" })).unwrap_err(),
+            "Unsupported DPoP key type"
+        );
+    }
+}
@@ -0,0 +1,71 @@
+#[cfg(test)]
+mod ip_restriction_tests {
+    use std::net::IpAddr;
+
+    use crate::{
+        dto::user::user_restricted_ip_input::UserRestrictedIpInput,
+        guards::client_ip::ClientIp, services::ip_restriction::IpRestrictionService,
+        utils::cidr::Cidr,
+    };
+    use rocket::tokio;
+
+    fn ip(ip: &str) -> IpAddr {
+        ip.parse().unwrap()
+    }
+
+    fn ranges(ranges: &[&str]) -> Vec<String> {
+        ranges.iter().map(|range| range.to_string()).collect()
+    }
+
+    #[tokio::test]
+    async fn forwarded_for_only_from_trusted_proxies() {
+        let trusted = vec![Cidr::parse("10.0.0.0/8").unwrap(), Cidr::parse("fd00::/8").unwrap()];
+        // a client sending its own header directly
+        assert_eq!(ClientIp::resolve(ip("203.0.113.7"), "1.2.3.4", &trusted), ip("203.0.113.7"));
+        // behind a trusted proxy
+        assert_eq!(ClientIp::resolve(ip("10.0.0.2"), "203.0.113.7", &trusted), ip("203.0.113.7"));
+        // the client forged the first hop, the last untrusted hop is the client
+        assert_eq!(
+            ClientIp::resolve(ip("10.0.0.2"), "1.2.3.4, 203.0.113.7, 10.0.0.9", &trusted),
+            ip("203.0.113.7")
+        );
+        // IPv6 proxies and ports
+        assert_eq!(
+            ClientIp::resolve(ip("fd00::1"), "[2001:db8::7]:443", &trusted),
+            ip("2001:db8::7")
+        );
+        assert_eq!(ClientIp::resolve(ip("10.0.0.2"), "", &trusted), ip("10.0.0.2"));
+        // a malformed hop stops the walk
+        assert_eq!(ClientIp::resolve(ip("10.0.0.2"), "1.2.3.4, nope", &trusted), ip("10.0.0.2"));
+        // no trusted proxy
+        assert_eq!(ClientIp::resolve(ip("10.0.0.2"), "1.2.3.4", &[]), ip("10.0.0.2"));
+        // an IPv4-mapped peer is its IPv4 address
+        assert_eq!(ClientIp::resolve(ip("::ffff:10.0.0.2"), "", &[]), ip("10.0.0.2"));
+    }
+
+    #[tokio::test]
+    async fn allowlists() {
+        assert!(IpRestrictionService::allows(&[], &ip("203.0.113.7")));
+        let allowed = ranges(&["192.168.1.10", "10.0.0.0/8", "2001:db8::/32"]);
+        assert!(IpRestrictionService::allows(&allowed, &ip("192.168.1.10")));
+        assert!(!IpRestrictionService::allows(&allowed, &ip("192.168.1.11")));
+        assert!(IpRestrictionService::allows(&allowed, &ip("10.20.30.40")));
+        assert!(IpRestrictionService::allows(&allowed, &ip("::ffff:10.1.1.1")));
+        assert!(IpRestrictionService::allows(&allowed, &ip("2001:db8:1::1")));
+        assert!(!IpRestrictionService::allows(&allowed, &ip("2001:db9::1")));
+        // a list of malformed ranges allows nothing
+        assert!(!IpRestrictionService::allows(&ranges(&["nope"]), &ip("10.0.0.1")));
+    }
+
+    #[tokio::test]
+    async fn restricted_ip_input_accepts_ranges_and_ipv6() {
+        let input: UserRestrictedIpInput =
+            serde_json::from_str(r#"{"restricted_ip": [" 10.0.0.0/8", "2001:db8::1", "::/0"]}"#)
+                .unwrap();
+        assert_eq!(input.restricted_ip, ranges(&["10.0.0.0/8", "2001:db8::1", "::/0"]));
+        for invalid in ["300.0.0.1", "10.0.0.0/33", "2001:db8::/129", "host.example"] {
+            let json = format!(r#"{{"restricted_ip": ["{}"]}}"#, invalid);
+            assert!(serde_json::from_str::<UserRestrictedIpInput>(&json).is_err());
+        }
+    }
+}
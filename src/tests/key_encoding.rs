@@ -0,0 +1,48 @@
+#[cfg(test)]
+mod key_encoding_tests {
+    use crate::{enums::key_format::KeyFormat, utils::key_encoding::KeyEncoding};
+
+    use base64::{engine::general_purpose, Engine as _};
+    use rocket::tokio;
+
+    #[tokio::test]
+    async fn wrap_rfc_3394_vector() {
+        // RFC 3394 4.1: 128 bits of key data with a 128-bit KEK
+        let wrapped = KeyEncoding::wrap(
+            "00112233445566778899AABBCCDDEEFF",
+            "000102030405060708090A0B0C0D0E0F",
+        )
+        .unwrap();
+        assert_eq!(
+            hex::encode_upper(general_purpose::STANDARD.decode(wrapped).unwrap()),
+            "1FA68B0A8112B447AEF34BD8FB5A7B829D3E862371D2CFE5"
+        );
+        assert!(KeyEncoding::wrap("00112233445566778899AABBCCDDEEFF", "0011").is_err());
+    }
+
+    #[tokio::test]
+    async fn export_format_checks() {
+        assert!(matches!(KeyEncoding::export_format(None, &None), Ok(KeyFormat::Hex)));
+        assert!(KeyEncoding::export_format(Some("der"), &None).is_err());
+        assert!(KeyEncoding::export_format(Some("wrapped"), &None).is_err());
+        let kek = Some(String::from("000102030405060708090A0B0C0D0E0F"));
+        assert!(matches!(
+            KeyEncoding::export_format(Some("wrapped"), &kek),
+            Ok(KeyFormat::Wrapped)
+        ));
+    }
+
+    #[tokio::test]
+    async fn ml_kem_public_pem() {
+        let pem = KeyEncoding::ml_kem_public_pem(&hex::encode([1u8; 800]), 512).unwrap();
+        let body: String = pem.lines().filter(|line| !line.starts_with("-----")).collect();
+        let der = general_purpose::STANDARD.decode(body).unwrap();
+        // SEQUENCE (long form), AlgorithmIdentifier with the ML-KEM-512 OID, BIT STRING
+        assert_eq!(
+            hex::encode(&der[..22]),
+            "30820332300b06096086480165030404010382032100"
+        );
+        assert_eq!(der.len(), 4 + 13 + 4 + 1 + 800);
+        assert_eq!(&der[der.len() - 800..], &[1u8; 800]);
+    }
+}
@@ -0,0 +1,97 @@
+#[cfg(test)]
+mod key_import_tests {
+    use crate::utils::key_import::KeyImport;
+
+    use openssl::{md::Md, pkey::PKey, pkey_ctx::PkeyCtx, rsa::Padding};
+    use rocket::tokio;
+
+    // the customer side of RSA-OAEP-256, with the published PEM public key
+    fn wrap_rsa_oaep(public_key: &str, key: &[u8]) -> Vec<u8> {
+        let public_key = PKey::public_key_from_pem(public_key.as_bytes()).unwrap();
+        let mut ctx = PkeyCtx::new(&public_key).unwrap();
+        ctx.encrypt_init().unwrap();
+        ctx.set_rsa_padding(Padding::PKCS1_OAEP).unwrap();
+        ctx.set_rsa_oaep_md(Md::sha256()).unwrap();
+        ctx.set_rsa_mgf1_md(Md::sha256()).unwrap();
+        let mut wrapped_key = vec![];
+        ctx.encrypt_to_vec(key, &mut wrapped_key).unwrap();
+        wrapped_key
+    }
+
+    #[tokio::test]
+    async fn rsa_oaep_round_trip() {
+        let (public_key, private_key) = KeyImport::generate_rsa_key_pair();
+        for key in [vec![1u8; 16], vec![2u8; 32]] {
+            let wrapped_key = wrap_rsa_oaep(&public_key, &key);
+            assert_eq!(KeyImport::unwrap_rsa_oaep(&private_key, &wrapped_key), Some(key));
+        }
+    }
+
+    #[tokio::test]
+    async fn rsa_oaep_wrapped_for_another_key() {
+        let (_, private_key) = KeyImport::generate_rsa_key_pair();
+        let (other_public_key, _) = KeyImport::generate_rsa_key_pair();
+        let wrapped_key = wrap_rsa_oaep(&other_public_key, &[3u8; 32]);
+        assert_eq!(KeyImport::unwrap_rsa_oaep(&private_key, &wrapped_key), None);
+
+        let (public_key, _) = KeyImport::generate_rsa_key_pair();
+        let mut tampered = wrap_rsa_oaep(&public_key, &[3u8; 32]);
+        tampered[0] ^= 1;
+        assert_eq!(KeyImport::unwrap_rsa_oaep(&private_key, &tampered), None);
+        assert_eq!(KeyImport::unwrap_rsa_oaep("not a key", &wrapped_key), None);
+    }
+
+    #[tokio::test]
+    async fn ml_kem_768_round_trip() {
+        let (public_key, private_key) = KeyImport::generate_ml_kem_768_key_pair();
+        assert!(KeyImport::is_ml_kem_768_public_key(&public_key));
+        assert!(!KeyImport::is_ml_kem_768_public_key(&private_key));
+        for key in [vec![1u8; 16], vec![2u8; 32]] {
+            let (encapsulated_key, nonce, wrapped_key) =
+                KeyImport::wrap_ml_kem_768(&public_key, &key).unwrap();
+            assert_eq!(
+                KeyImport::unwrap_ml_kem_768(&private_key, &encapsulated_key, &nonce, &wrapped_key),
+                Some(key)
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn ml_kem_768_wrapped_for_another_key() {
+        let (_, private_key) = KeyImport::generate_ml_kem_768_key_pair();
+        let (other_public_key, _) = KeyImport::generate_ml_kem_768_key_pair();
+        let (encapsulated_key, nonce, wrapped_key) =
+            KeyImport::wrap_ml_kem_768(&other_public_key, &[3u8; 32]).unwrap();
+        assert_eq!(
+            KeyImport::unwrap_ml_kem_768(&private_key, &encapsulated_key, &nonce, &wrapped_key),
+            None
+        );
+
+        let (public_key, private_key) = KeyImport::generate_ml_kem_768_key_pair();
+        let (encapsulated_key, nonce, wrapped_key) =
+            KeyImport::wrap_ml_kem_768(&public_key, &[3u8; 32]).unwrap();
+        let mut tampered = wrapped_key.clone();
+        tampered[0] ^= 1;
+        assert_eq!(
+            KeyImport::unwrap_ml_kem_768(&private_key, &encapsulated_key, &nonce, &tampered),
+            None
+        );
+        assert_eq!(
+            KeyImport::unwrap_ml_kem_768(&private_key, &encapsulated_key, &nonce[..8], &wrapped_key),
+            None
+        );
+        assert_eq!(
+            KeyImport::unwrap_ml_kem_768(&private_key, &encapsulated_key[1..], &nonce, &wrapped_key),
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn only_aes_128_and_256_keys() {
+        assert_eq!(KeyImport::key_size(&[0u8; 16]), Some(128));
+        assert_eq!(KeyImport::key_size(&[0u8; 32]), Some(256));
+        for size in [0, 1, 15, 17, 24, 31, 33, 64] {
+            assert_eq!(KeyImport::key_size(&vec![0u8; size]), None);
+        }
+    }
+}
@@ -0,0 +1,189 @@
+#[cfg(test)]
+mod key_policy_tests {
+    use std::net::IpAddr;
+
+    use chrono::{TimeZone, Utc};
+    use uuid::Uuid;
+
+    use crate::{
+        dto::key_policy::key_policy_input::KeyPolicyInput, enums::key_operation::KeyOperation,
+        models::key_policy::KeyPolicy, services::key_policy::KeyPolicyService, utils::cidr::Cidr,
+    };
+
+    use rocket::{http::Status, tokio};
+
+    fn ip(address: &str) -> IpAddr {
+        address.parse().unwrap()
+    }
+
+    #[tokio::test]
+    async fn cidr_ranges() {
+        let range = Cidr::parse("10.20.0.0/14").unwrap();
+        assert!(range.contains(&ip("10.23.255.1")));
+        assert!(!range.contains(&ip("10.24.0.1")));
+        assert!(range.contains(&ip("::ffff:10.21.0.1")));
+        let range = Cidr::parse("2001:db8::/32").unwrap();
+        assert!(range.contains(&ip("2001:db8:ffff::1")));
+        assert!(!range.contains(&ip("2001:db9::1")));
+        assert!(!range.contains(&ip("10.20.0.1")));
+        assert!(Cidr::parse("192.168.1.7").unwrap().contains(&ip("192.168.1.7")));
+        assert!(Cidr::parse("0.0.0.0/0").unwrap().contains(&ip("8.8.8.8")));
+        assert!(Cidr::parse("10.0.0.0/33").is_err());
+        assert!(Cidr::parse("payroll").is_err());
+    }
+
+    #[tokio::test]
+    async fn policy_check() {
+        let policy = KeyPolicy {
+            id: Uuid::new_v4(),
+            sentinel_id: Some(Uuid::new_v4()),
+            anonymous_sentinel_id: None,
+            operations: vec![Some("read".to_string())],
+            allowed_cidrs: vec![Some("10.0.0.0/8".to_string())],
+            // monday to friday
+            allowed_days: (1..=5).map(Some).collect(),
+            hour_start: Some(22),
+            hour_end: Some(6),
+            max_reads: Some(2),
+            read_count: 1,
+            is_deleted: false,
+            created_at: Utc::now(),
+            updated_at: None,
+            deleted_at: None,
+            created_by_id: None,
+            updated_by_id: None,
+            deleted_by_id: None,
+        };
+        // 2026-10-19 is a monday
+        let monday_night = Utc.with_ymd_and_hms(2026, 10, 19, 23, 30, 0).unwrap();
+        let monday_noon = Utc.with_ymd_and_hms(2026, 10, 19, 12, 0, 0).unwrap();
+        let sunday_night = Utc.with_ymd_and_hms(2026, 10, 18, 23, 30, 0).unwrap();
+        let inside = ip("10.1.2.3");
+
+        assert!(policy.check(KeyOperation::Read, &inside, monday_night).is_ok());
+        assert!(policy.check(KeyOperation::Delete, &inside, monday_night).is_err());
+        assert!(policy.check(KeyOperation::Read, &ip("192.168.0.1"), monday_night).is_err());
+        assert!(policy.check(KeyOperation::Read, &inside, monday_noon).is_err());
+        assert!(policy.check(KeyOperation::Read, &inside, sunday_night).is_err());
+        let exhausted = KeyPolicy { read_count: 2, ..policy };
+        assert!(exhausted.check(KeyOperation::Read, &inside, monday_night).is_err());
+    }
+
+    fn current_policy() -> KeyPolicy {
+        KeyPolicy {
+            id: Uuid::new_v4(),
+            sentinel_id: Some(Uuid::new_v4()),
+            anonymous_sentinel_id: None,
+            operations: vec![Some("read".to_string()), Some("encrypt".to_string())],
+            allowed_cidrs: vec![Some("10.0.0.0/8".to_string())],
+            allowed_days: (1..=5).map(Some).collect(),
+            hour_start: Some(22),
+            hour_end: Some(6),
+            max_reads: Some(10),
+            read_count: 3,
+            is_deleted: false,
+            created_at: Utc::now(),
+            updated_at: None,
+            deleted_at: None,
+            created_by_id: None,
+            updated_by_id: None,
+            deleted_by_id: None,
+        }
+    }
+
+    /// the same restrictions as `current_policy`, as a validated input
+    fn same_input() -> KeyPolicyInput {
+        KeyPolicyInput {
+            operations: Some(vec!["read".to_string(), "encrypt".to_string()]),
+            allowed_cidrs: Some(vec!["10.0.0.0/8".to_string()]),
+            allowed_days: Some((1..=5).collect()),
+            hour_start: Some(22),
+            hour_end: Some(6),
+            max_reads: Some(10),
+        }
+    }
+
+    #[tokio::test]
+    async fn stricter_policy_by_the_creator() {
+        let current = current_policy();
+        assert!(KeyPolicyService::check_change(Some(&current), Some(&same_input()), false).is_ok());
+        let stricter = KeyPolicyInput {
+            operations: Some(vec!["read".to_string()]),
+            allowed_cidrs: Some(vec!["10.20.0.0/16".to_string()]),
+            allowed_days: Some(vec![1, 2]),
+            hour_start: Some(23),
+            hour_end: Some(2),
+            max_reads: Some(5),
+        };
+        assert!(KeyPolicyService::check_change(Some(&current), Some(&stricter), false).is_ok());
+        // a key without policy takes any policy
+        assert!(KeyPolicyService::check_change(None, Some(&same_input()), false).is_ok());
+    }
+
+    #[tokio::test]
+    async fn relaxed_policy_refused_to_the_creator() {
+        let current = current_policy();
+        let relaxed_inputs = [
+            KeyPolicyInput {
+                operations: Some(vec!["read".to_string(), "delete".to_string()]),
+                ..same_input()
+            },
+            KeyPolicyInput {
+                allowed_cidrs: Some(vec![]),
+                ..same_input()
+            },
+            KeyPolicyInput {
+                allowed_cidrs: Some(vec!["0.0.0.0/0".to_string()]),
+                ..same_input()
+            },
+            KeyPolicyInput {
+                allowed_cidrs: Some(vec!["10.0.0.0/8".to_string(), "192.168.0.0/16".to_string()]),
+                ..same_input()
+            },
+            KeyPolicyInput {
+                allowed_days: Some(vec![]),
+                ..same_input()
+            },
+            KeyPolicyInput {
+                allowed_days: Some(vec![6]),
+                ..same_input()
+            },
+            KeyPolicyInput {
+                hour_start: None,
+                hour_end: None,
+                ..same_input()
+            },
+            KeyPolicyInput {
+                hour_start: Some(21),
+                ..same_input()
+            },
+            KeyPolicyInput {
+                max_reads: None,
+                ..same_input()
+            },
+            KeyPolicyInput {
+                max_reads: Some(11),
+                ..same_input()
+            },
+        ];
+        for relaxed in relaxed_inputs.iter() {
+            assert_eq!(
+                KeyPolicyService::check_change(Some(&current), Some(relaxed), false),
+                Err((Status::Forbidden, Some("Only an admin can relax the policy of a key"))),
+                "{:?}",
+                relaxed
+            );
+            assert!(KeyPolicyService::check_change(Some(&current), Some(relaxed), true).is_ok());
+        }
+    }
+
+    #[tokio::test]
+    async fn policy_deletion_refused_to_the_creator() {
+        let current = current_policy();
+        assert_eq!(
+            KeyPolicyService::check_change(Some(&current), None, false),
+            Err((Status::Forbidden, Some("Only an admin can remove the policy of a key")))
+        );
+        assert!(KeyPolicyService::check_change(Some(&current), None, true).is_ok());
+    }
+}
@@ -0,0 +1,100 @@
+#[cfg(test)]
+pub mod key_ring_tests {
+    use crate::utils::key_ring::{KeyRing, RingKey};
+
+    use chrono::{Duration, Utc};
+    use jsonwebtoken::{Algorithm, EncodingKey, Header, Validation};
+    use once_cell::sync::Lazy;
+    use rocket::tokio::{self, sync::Mutex};
+    use serde::{Deserialize, Serialize};
+
+    /// the key ring is shared by the whole process, the tests installing keys hold it
+    pub static KEY_RING_LOCK: Lazy<Mutex<()>> = Lazy::new(|| Mutex::new(()));
+
+    #[derive(Serialize, Deserialize, Debug, PartialEq)]
+    struct Claims {
+        sub: String,
+        exp: i64,
+    }
+
+    fn claims() -> Claims {
+        Claims {
+            sub: format!("test"),
+            exp: (Utc::now() + Duration::minutes(5)).timestamp(),
+        }
+    }
+
+    fn ring_key(activates_in: Duration) -> (RingKey, String) {
+        let (public_key, private_key) = KeyRing::generate();
+        let ring_key =
+            RingKey::new(&public_key, Some(&private_key), Utc::now() + activates_in).unwrap();
+        (ring_key, private_key)
+    }
+
+    /// install a single active key
+    pub fn install_key() {
+        KeyRing::install(vec![ring_key(Duration::zero()).0]);
+    }
+
+    #[tokio::test]
+    async fn rotation_keeps_issued_tokens_valid() {
+        let _lock = KEY_RING_LOCK.lock().await;
+        let validation = Validation::new(Algorithm::RS256);
+        let (current, _) = ring_key(-Duration::days(1));
+        let (next, _) = ring_key(Duration::days(1));
+        KeyRing::install(vec![current.clone(), next.clone()]);
+
+        // the next key is published but the current one signs until its activation
+        let token = KeyRing::encode(&claims());
+        let header = jsonwebtoken::decode_header(&token).unwrap();
+        assert_eq!(header.kid, Some(current.kid.clone()));
+        assert_eq!(KeyRing::keys().len(), 2);
+
+        let (newest, _) = ring_key(Duration::zero());
+        KeyRing::install(vec![current.clone(), newest.clone()]);
+        let rotated = KeyRing::encode(&claims());
+        let header = jsonwebtoken::decode_header(&rotated).unwrap();
+        assert_eq!(header.kid, Some(newest.kid.clone()));
+        assert!(KeyRing::decode::<Claims>(&token, &validation).is_ok());
+        assert!(KeyRing::decode::<Claims>(&rotated, &validation).is_ok());
+
+        // once retired the key no longer checks its tokens
+        KeyRing::install(vec![newest]);
+        assert!(KeyRing::decode::<Claims>(&token, &validation).is_err());
+        assert!(KeyRing::decode::<Claims>(&rotated, &validation).is_ok());
+    }
+
+    #[tokio::test]
+    async fn check_tokens_issued_without_kid() {
+        let _lock = KEY_RING_LOCK.lock().await;
+        let validation = Validation::new(Algorithm::RS256);
+        let (legacy, legacy_private_key) = ring_key(-Duration::days(1));
+        let (current, _) = ring_key(Duration::zero());
+        KeyRing::install(vec![legacy, current]);
+
+        let key = EncodingKey::from_rsa_pem(legacy_private_key.as_bytes()).unwrap();
+        let token =
+            jsonwebtoken::encode(&Header::new(Algorithm::RS256), &claims(), &key).unwrap();
+        assert_eq!(
+            KeyRing::decode::<Claims>(&token, &validation).unwrap().claims.sub,
+            "test"
+        );
+
+        let (unknown, unknown_private_key) = ring_key(Duration::zero());
+        let key = EncodingKey::from_rsa_pem(unknown_private_key.as_bytes()).unwrap();
+        let mut header = Header::new(Algorithm::RS256);
+        header.kid = Some(unknown.kid);
+        let token = jsonwebtoken::encode(&header, &claims(), &key).unwrap();
+        assert!(KeyRing::decode::<Claims>(&token, &validation).is_err());
+    }
+
+    #[tokio::test]
+    async fn thumbprint_of_the_public_key() {
+        // RFC 7638 section 3.1
+        let n = "0vx7agoebGcQSuuPiLJXZptN9nndrQmbXEps2aiAFbWhM78LhWx4cbbfAAtVT86zwu1RK7aPFFxuhDR1L6tSoc_BJECPebWKRXjBZCiFV4n3oknjhMstn64tZ_2W-5JsGY4Hc5n9yBXArwl93lqt7_RN5w6Cf0h4QyQ5v-65YGjQR0_FDW2QvzqY368QQMicAtaSqzs8KJZgnYb9c7d0zgdAZHzu6qMQvRL5hajrn1n91CbOpbISD08qNLyrdkt-bFTWhAI4vMQFh6WeZu0fM4lFd2NcRwr3XPksINHaQ-G_xBniIqbw0Ls1jF44-csFCur-kEgU8awapJzKnqDKgw";
+        assert_eq!(
+            KeyRing::thumbprint(n, "AQAB"),
+            "NzbLsXh8uDCcd-6MNwXF4W_7noWXFZAfHkxZsRGC9Xs"
+        );
+    }
+}
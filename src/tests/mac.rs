@@ -0,0 +1,19 @@
+#[cfg(test)]
+mod mac_tests {
+    use crate::{enums::mac_algorithm::MacAlgorithm, utils::mac::Mac};
+
+    use rocket::tokio;
+
+    #[tokio::test]
+    async fn hmac_rfc_4231_vector() {
+        // RFC 4231 test case 2
+        let mac = Mac::sign(&MacAlgorithm::HS256, b"Jefe", b"what do ya want for nothing?").unwrap();
+        assert_eq!(
+            hex::encode(&mac),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+        assert!(Mac::verify(&MacAlgorithm::HS256, b"Jefe", b"what do ya want for nothing?", &mac).unwrap());
+        assert!(!Mac::verify(&MacAlgorithm::HS256, b"Jefe", b"what do ya want for nothing!", &mac).unwrap());
+        assert!(!Mac::verify(&MacAlgorithm::HS256, b"Jefe", b"what do ya want for nothing?", &mac[..16]).unwrap());
+    }
+}
@@ -0,0 +1,183 @@
+#[cfg(test)]
+mod message_signature_tests {
+    use crate::enums::signature_algorithm::SignatureAlgorithm;
+    use crate::utils::message_signature::MessageSignature;
+
+    use base64::{engine::general_purpose, Engine};
+    use openssl::{
+        ec::{EcGroup, EcKey},
+        ecdsa::EcdsaSig,
+        hash::MessageDigest,
+        nid::Nid,
+        pkey::{PKey, Private},
+        sign::Signer,
+    };
+    use rocket::tokio;
+
+    // RFC 9421 appendix B.1.4
+    const ED25519_KEY: &str = "-----BEGIN PUBLIC KEY-----
+MCowBQYDK2VwAyEAJrQLj5P/89iXES9+vFgrIy29clF9CC/oPPsw3c5D0bs=
+-----END PUBLIC KEY-----";
+
+    fn request_component(component: &str) -> Option<String> {
+        match component {
+            "date" => Some(format!("Tue, 20 Apr 2021 02:07:55 GMT")),
+            "@method" => Some(format!("POST")),
+            "@path" => Some(format!("/foo")),
+            "@authority" => Some(format!("example.com")),
+            "content-type" => Some(format!("application/json")),
+            "content-length" => Some(format!("18")),
+            _ => None,
+        }
+    }
+
+    fn generate_key() -> PKey<Private> {
+        let group = EcGroup::from_curve_name(Nid::X9_62_PRIME256V1).unwrap();
+        PKey::from_ec_key(EcKey::generate(&group).unwrap()).unwrap()
+    }
+
+    /// an `ecdsa-p256-sha256` signature, the concatenation of r and s
+    fn sign(key: &PKey<Private>, base: &str) -> Vec<u8> {
+        let mut signer = Signer::new(MessageDigest::sha256(), key).unwrap();
+        let der = signer.sign_oneshot_to_vec(base.as_bytes()).unwrap();
+        let signature = EcdsaSig::from_der(&der).unwrap();
+        let mut raw = signature.r().to_vec_padded(32).unwrap();
+        raw.extend(signature.s().to_vec_padded(32).unwrap());
+        raw
+    }
+
+    #[tokio::test]
+    async fn parse_signature_input() {
+        let inputs = MessageSignature::parse_input(
+            r#"sig1=("@method" "@path" "date" "content-digest");created=1618884473;keyid="sa_01, 02";alg="ecdsa-p256-sha256""#,
+        )
+        .unwrap();
+        assert_eq!(inputs.len(), 1);
+        let input = &inputs[0];
+        assert_eq!(input.label, "sig1");
+        assert_eq!(input.components, vec!["@method", "@path", "date", "content-digest"]);
+        assert_eq!(input.created, Some(1618884473));
+        assert_eq!(input.keyid.as_deref(), Some("sa_01, 02"));
+        assert_eq!(input.alg.as_deref(), Some("ecdsa-p256-sha256"));
+        assert!(input.params.starts_with(r#"("@method" "@path""#));
+
+        assert!(MessageSignature::parse_input(r#"sig1="@method""#).is_err());
+        assert!(MessageSignature::parse_input(r#"sig1=(@method)"#).is_err());
+        assert_eq!(
+            MessageSignature::parse_signature("sig1=:AQID:, sig2=:BA==:", "sig2").unwrap(),
+            vec![4]
+        );
+        assert!(MessageSignature::parse_signature("sig1=:AQID:", "sig2").is_err());
+    }
+
+    #[tokio::test]
+    async fn verify_rfc_ed25519_signature() {
+        // RFC 9421 appendix B.2.6
+        let input = MessageSignature::parse_input(
+            r#"sig-b26=("date" "@method" "@path" "@authority" "content-type" "content-length");created=1618884473;keyid="test-key-ed25519""#,
+        )
+        .unwrap()
+        .remove(0);
+        let base = MessageSignature::signature_base(&input, request_component).unwrap();
+        assert_eq!(
+            base,
+            "\"date\": Tue, 20 Apr 2021 02:07:55 GMT\n\"@method\": POST\n\"@path\": /foo\n\"@authority\": example.com\n\"content-type\": application/json\n\"content-length\": 18\n\"@signature-params\": (\"date\" \"@method\" \"@path\" \"@authority\" \"content-type\" \"content-length\");created=1618884473;keyid=\"test-key-ed25519\""
+        );
+        let signature = MessageSignature::parse_signature(
+            "sig-b26=:wqcAqbmYJ2ji2glfAMaRy4gruYYnx2nEFN2HN6jrnDnQCK1u02Gb04v9EDgwUPiu4A0w6vuQv5lIp5WPpBKRCw==:",
+            "sig-b26",
+        )
+        .unwrap();
+        let key = MessageSignature::public_key(ED25519_KEY).unwrap();
+        assert!(MessageSignature::verify(&key, None, base.as_bytes(), &signature).is_ok());
+        assert!(MessageSignature::verify(&key, Some("ed25519"), base.as_bytes(), &signature).is_ok());
+        assert_eq!(
+            MessageSignature::verify(&key, Some("rsa-pss-sha512"), base.as_bytes(), &signature)
+                .unwrap_err(),
+            "The signature algorithm does not match the key"
+        );
+        let tampered = base.replace("/foo", "/bar");
+        assert!(MessageSignature::verify(&key, None, tampered.as_bytes(), &signature).is_err());
+    }
+
+    #[tokio::test]
+    async fn verify_ecdsa_signature() {
+        let key = generate_key();
+        let public_key =
+            MessageSignature::public_key(&String::from_utf8(key.public_key_to_pem().unwrap()).unwrap())
+                .unwrap();
+        assert_eq!(
+            SignatureAlgorithm::of_key(&public_key),
+            Some(SignatureAlgorithm::EcdsaP256Sha256)
+        );
+        let input = MessageSignature::parse_input(
+            r#"sig1=("@method" "@path" "date");created=1618884473;keyid="sa_1""#,
+        )
+        .unwrap()
+        .remove(0);
+        let base = MessageSignature::signature_base(&input, request_component).unwrap();
+        let signature = sign(&key, &base);
+        assert!(MessageSignature::verify(&public_key, None, base.as_bytes(), &signature).is_ok());
+        assert!(MessageSignature::verify(
+            &public_key,
+            Some("ecdsa-p256-sha256"),
+            base.as_bytes(),
+            &signature
+        )
+        .is_ok());
+        let other = sign(&generate_key(), &base);
+        assert!(MessageSignature::verify(&public_key, None, base.as_bytes(), &other).is_err());
+        assert!(MessageSignature::verify(&public_key, None, base.as_bytes(), &signature[..63]).is_err());
+    }
+
+    #[tokio::test]
+    async fn signature_base_refuses_bad_components() {
+        let missing = MessageSignature::parse_input(r#"sig1=("@method" "x-missing")"#)
+            .unwrap()
+            .remove(0);
+        assert_eq!(
+            MessageSignature::signature_base(&missing, request_component).unwrap_err(),
+            "A covered component is missing"
+        );
+        let twice = MessageSignature::parse_input(r#"sig1=("@method" "@method")"#)
+            .unwrap()
+            .remove(0);
+        assert_eq!(
+            MessageSignature::signature_base(&twice, request_component).unwrap_err(),
+            "A component is covered twice"
+        );
+    }
+
+    #[tokio::test]
+    async fn content_digest_matches_the_body() {
+        // RFC 9530 appendix D.1
+        let body = br#"{"hello": "world"}"#;
+        let sha_256 = "sha-256=:X48E9qOokqqrvdts8nOJRJN3OWDUoyWxBf7kbu9DBPE=:";
+        assert!(MessageSignature::digest_matches(sha_256, body));
+        assert!(!MessageSignature::digest_matches(sha_256, br#"{"hello": "there"}"#));
+
+        let sha_512 = format!(
+            "sha-512=:{}:",
+            general_purpose::STANDARD.encode(<sha2::Sha512 as sha2::Digest>::digest(body))
+        );
+        assert!(MessageSignature::digest_matches(&format!("{}, {}", sha_256, sha_512), body));
+        // every known digest must match, one at least
+        assert!(!MessageSignature::digest_matches(
+            &format!("{}, sha-512=:AAAA:", sha_256),
+            body
+        ));
+        assert!(!MessageSignature::digest_matches("md5=:AAAA:", body));
+        assert!(!MessageSignature::digest_matches("sha-256=X48E", body));
+    }
+
+    #[tokio::test]
+    async fn signing_keys() {
+        assert!(MessageSignature::public_key(ED25519_KEY).is_some());
+        assert!(MessageSignature::public_key("not a key").is_none());
+        // a P-521 key has no signature algorithm
+        let group = EcGroup::from_curve_name(Nid::SECP521R1).unwrap();
+        let key = PKey::from_ec_key(EcKey::generate(&group).unwrap()).unwrap();
+        let pem = String::from_utf8(key.public_key_to_pem().unwrap()).unwrap();
+        assert!(MessageSignature::public_key(&pem).is_none());
+    }
+}
@@ -1,2 +1,27 @@
 pub mod application;
 pub mod connexion;
+pub mod recovery_share;
+pub mod key_encoding;
+pub mod mac;
+pub mod stream_cipher;
+pub mod key_policy;
+pub mod access_request;
+pub mod audit;
+pub mod share_link;
+pub mod scoped_token;
+pub mod service_account;
+pub mod oauth;
+pub mod open_id;
+pub mod key_ring;
+pub mod token;
+pub mod session;
+pub mod webauthn;
+pub mod totp;
+pub mod rate_limit;
+pub mod ip_restriction;
+pub mod dpop;
+pub mod message_signature;
+pub mod backup;
+pub mod key_import;
+pub mod secret;
+pub mod signing_key;
\ No newline at end of file
@@ -0,0 +1,133 @@
+#[cfg(test)]
+mod oauth_tests {
+    use chrono::Utc;
+    use uuid::Uuid;
+
+    use crate::{
+        enums::roles::Role,
+        guards::{scoped_security::ScopedSecurity, security::Security},
+        models::user::User,
+        utils::{jwt::TokenScope, oauth::Oauth},
+    };
+
+    use rocket::{http::Status, request::Outcome, tokio};
+
+    fn user() -> User {
+        User {
+            id: Uuid::new_v4(),
+            email: String::from("test@test.com"),
+            firstname: String::from("test"),
+            lastname: String::from("test"),
+            twofa_code: String::from("123"),
+            is_2fa_activated: false,
+            login: String::from("test"),
+            roles: vec![Some(String::from("ROLE_USER")), Some(String::from("ROLE_ADMIN"))],
+            password: Some(String::from("test")),
+            full_text_search: String::from("test"),
+            kyber_secret_key: String::from("test"),
+            kyber_public_key: String::from("test"),
+            iv: String::from("test"),
+            is_deleted: false,
+            created_at: Utc::now(),
+            updated_at: None,
+            deleted_at: None,
+            created_by_id: None,
+            updated_by_id: None,
+            deleted_by_id: None,
+            refresh_token: None,
+            application: Some(1),
+            restricted_ip: vec![],
+            is_validated: true,
+            validation_code: None,
+            validation_tries: 0,
+            forget_code_delay: None,
+        }
+    }
+
+    // RFC 7636 appendix B
+    const VERIFIER: &str = "dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk";
+    const CHALLENGE: &str = "E9Melhoa2OwvFrEMTJguCHaoeK1t8URWbuGJSstw-cM";
+
+    #[tokio::test]
+    async fn verify_pkce_challenge() {
+        assert_eq!(Oauth::code_challenge(VERIFIER), CHALLENGE);
+        assert!(Oauth::is_code_challenge(CHALLENGE));
+        assert!(Oauth::verify_code_challenge(VERIFIER, CHALLENGE));
+        assert!(!Oauth::verify_code_challenge(
+            "dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXK",
+            CHALLENGE
+        ));
+        assert!(!Oauth::is_code_challenge(&VERIFIER[..42]));
+        assert!(!Oauth::verify_code_challenge("short", &Oauth::code_challenge("short")));
+        assert!(!Oauth::is_code_verifier(&"a".repeat(129)));
+        assert!(!Oauth::is_code_verifier(&format!("{}+", &VERIFIER[..43])));
+    }
+
+    #[tokio::test]
+    async fn validate_redirect_uris() {
+        assert!(Oauth::is_redirect_uri("https://app.example.com/callback"));
+        assert!(Oauth::is_redirect_uri("http://127.0.0.1:8080/callback"));
+        assert!(Oauth::is_redirect_uri("http://localhost/callback"));
+        assert!(Oauth::is_redirect_uri("com.example.app:/callback"));
+        assert!(!Oauth::is_redirect_uri("http://app.example.com/callback"));
+        assert!(!Oauth::is_redirect_uri("https://app.example.com/callback#token"));
+        assert!(!Oauth::is_redirect_uri("javascript:alert(1)"));
+        assert!(!Oauth::is_redirect_uri("/callback"));
+    }
+
+    #[tokio::test]
+    async fn build_redirect_location() {
+        let location = Oauth::redirect_location(
+            "https://app.example.com/callback?tenant=1",
+            &[("code", "abc"), ("state", "a b&c")],
+        );
+        assert_eq!(
+            location,
+            "https://app.example.com/callback?tenant=1&code=abc&state=a+b%26c"
+        );
+    }
+
+    #[tokio::test]
+    async fn oauth_client_token_refused_on_the_api() {
+        // the token an OAuth client got with the `openid` scope, for an admin
+        let mut security = Security::new(&user());
+        security.oauth_client_id = Some(String::from("client"));
+        security.oauth_scope = Some(String::from("openid profile"));
+
+        // `GET /sentinels/<id>` is guarded by `ScopedSecurity`
+        match ScopedSecurity::from_token(security.clone(), None) {
+            Outcome::Error((status, error)) => {
+                assert_eq!(status, Status::Forbidden);
+                assert_eq!(error.code, 403);
+            }
+            _ => panic!("an OAuth client token reached a key endpoint"),
+        }
+        let scope = TokenScope {
+            sentinels: vec![Uuid::new_v4()],
+            clusters: vec![],
+            operations: vec![String::from("read")],
+        };
+        assert!(ScopedSecurity::from_token(security.clone(), Some(scope)).is_error());
+        assert!(Security::check_first_party(security).is_error());
+
+        // the tokens of the user keep working
+        let security = Security::new(&user());
+        assert!(Security::check_first_party(security.clone()).is_success());
+        match ScopedSecurity::from_token(security, None) {
+            Outcome::Success(scoped) => assert!(scoped.scope.is_none()),
+            _ => panic!("a session token was refused"),
+        }
+    }
+
+    #[tokio::test]
+    async fn service_account_role() {
+        // a service account can not authorize a client on behalf of a user
+        let mut service_account = user();
+        service_account.roles = vec![
+            Some(String::from("ROLE_USER")),
+            Some(String::from("ROLE_SERVICE_ACCOUNT")),
+        ];
+        assert!(Security::has_role(&service_account, Role::SERVICEACCOUNT));
+        assert!(!Security::has_role(&user(), Role::SERVICEACCOUNT));
+    }
+}
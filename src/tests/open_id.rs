@@ -0,0 +1,117 @@
+#[cfg(test)]
+mod open_id_tests {
+    use crate::{
+        dto::oidc::openid_configuration_output::OpenidConfigurationOutput,
+        models::user::User,
+        tests::key_ring::key_ring_tests::{install_key, KEY_RING_LOCK},
+        utils::{
+            key_ring::KeyRing,
+            open_id::{Authentication, OpenId, UserClaims},
+        },
+    };
+
+    use chrono::Utc;
+    use rocket::tokio;
+    use uuid::Uuid;
+
+    fn user() -> User {
+        User {
+            id: Uuid::new_v4(),
+            email: format!("ada@test.com"),
+            firstname: format!("Ada"),
+            lastname: format!("Lovelace"),
+            twofa_code: format!("123"),
+            is_2fa_activated: true,
+            login: format!("ada"),
+            roles: vec![Some(format!("ROLE_USER"))],
+            password: Some(format!("test")),
+            full_text_search: format!("test"),
+            kyber_secret_key: format!("test"),
+            kyber_public_key: format!("test"),
+            iv: format!("test"),
+            is_deleted: false,
+            created_at: Utc::now(),
+            updated_at: None,
+            deleted_at: None,
+            created_by_id: None,
+            updated_by_id: None,
+            deleted_by_id: None,
+            refresh_token: None,
+            application: Some(123),
+            restricted_ip: vec![],
+            is_validated: true,
+            validation_code: None,
+            validation_tries: 0,
+            forget_code_delay: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn filter_claims_by_scope() {
+        let user = user();
+
+        let claims = UserClaims::new(&user, Some("openid"));
+        assert!(claims.name.is_none());
+        assert!(claims.email.is_none());
+
+        let claims = UserClaims::new(&user, Some("openid email"));
+        assert!(claims.preferred_username.is_none());
+        assert_eq!(claims.email.as_deref(), Some("ada@test.com"));
+        assert_eq!(claims.email_verified, Some(true));
+
+        let claims = UserClaims::new(&user, None);
+        assert_eq!(claims.name.as_deref(), Some("Ada Lovelace"));
+        assert_eq!(claims.preferred_username.as_deref(), Some("ada"));
+        assert_eq!(claims.email.as_deref(), Some("ada@test.com"));
+    }
+
+    #[tokio::test]
+    async fn password_authentication_methods() {
+        let mut user = user();
+        assert_eq!(Authentication::password(&user).amr, vec!["pwd", "otp", "mfa"]);
+        user.is_2fa_activated = false;
+        assert_eq!(Authentication::password(&user).amr, vec!["pwd"]);
+    }
+
+    #[tokio::test]
+    async fn create_and_check_id_token() {
+        let _lock = KEY_RING_LOCK.lock().await;
+        install_key();
+        let user = user();
+        let authentication = Authentication::password(&user);
+        let token = OpenId::create(
+            &user,
+            format!("client"),
+            &authentication,
+            Some(format!("n-0S6_WzA2Mj")),
+            Some("openid profile"),
+        )
+        .await;
+
+        let header = jsonwebtoken::decode_header(&token).unwrap();
+        assert_eq!(header.kid, KeyRing::signing_kid());
+
+        let open_id = OpenId::check(token).unwrap();
+        assert_eq!(open_id.sub, user.id);
+        assert_eq!(open_id.aud, "client");
+        assert_eq!(open_id.iss, OpenId::issuer());
+        assert_eq!(open_id.nonce.as_deref(), Some("n-0S6_WzA2Mj"));
+        assert_eq!(open_id.auth_time, authentication.auth_time);
+        assert_eq!(open_id.claims.given_name.as_deref(), Some("Ada"));
+        assert!(open_id.claims.email.is_none());
+    }
+
+    #[tokio::test]
+    async fn discovery_endpoints_share_the_issuer() {
+        let configuration = OpenidConfigurationOutput::new(format!("https://api.example.com"));
+        assert_eq!(
+            configuration.jwks_uri,
+            "https://api.example.com/jwks.json"
+        );
+        assert_eq!(
+            configuration.token_endpoint,
+            "https://api.example.com/oauth/token"
+        );
+        assert_eq!(configuration.code_challenge_methods_supported, vec!["S256"]);
+    }
+}
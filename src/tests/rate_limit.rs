@@ -0,0 +1,57 @@
+#[cfg(test)]
+mod rate_limit_tests {
+    use crate::{
+        enums::attempt_scope::AttemptScope,
+        utils::{code, rate_limit::RateLimit},
+    };
+    use rocket::tokio;
+
+    #[tokio::test]
+    async fn lockout_doubles_up_to_the_max() {
+        assert_eq!(RateLimit::lockout_duration(1, 60, 86400), 60);
+        assert_eq!(RateLimit::lockout_duration(2, 60, 86400), 120);
+        assert_eq!(RateLimit::lockout_duration(5, 60, 86400), 960);
+        assert_eq!(RateLimit::lockout_duration(20, 60, 86400), 86400);
+        assert_eq!(RateLimit::lockout_duration(200, 60, 86400), 86400);
+    }
+
+    #[tokio::test]
+    async fn lock_keys_of_an_application() {
+        let key = RateLimit::lock_key(AttemptScope::SecondFactor, 12, "jo:doe");
+        assert_eq!(key, "lockout:12:2fa:jo:doe");
+        assert_eq!(
+            RateLimit::parse_lock_key(&key, 12),
+            Some((AttemptScope::SecondFactor, "jo:doe".to_string()))
+        );
+        // another application, or a login lock of application 1 for the application 12
+        assert_eq!(RateLimit::parse_lock_key(&key, 1), None);
+        assert_eq!(RateLimit::parse_lock_key("lockout:1:login:12:x", 12), None);
+        assert_eq!(RateLimit::parse_lock_key("lockout:12:unknown:jo", 12), None);
+    }
+
+    #[tokio::test]
+    async fn scopes_count_apart() {
+        for scope in AttemptScope::ALL {
+            assert_eq!(AttemptScope::from_str(scope.as_str()), Some(scope));
+        }
+        assert_ne!(
+            RateLimit::failures_key(AttemptScope::Login, 1, "jo"),
+            RateLimit::failures_key(AttemptScope::PasswordReset, 1, "jo")
+        );
+        assert_ne!(
+            RateLimit::failures_key(AttemptScope::Login, 1, "jo"),
+            RateLimit::failures_key(AttemptScope::Login, 2, "jo")
+        );
+    }
+
+    #[tokio::test]
+    async fn reset_codes_are_hard_to_guess() {
+        let reset_code = code::generate_reset_code();
+        assert_eq!(reset_code.len(), 8);
+        assert!(reset_code
+            .chars()
+            .all(|c| c.is_ascii_uppercase() || c.is_ascii_digit()));
+        assert!(!reset_code.contains(['0', 'O', '1', 'I']));
+        assert_eq!(code::normalize_reset_code(" ab2cd3ef "), "AB2CD3EF");
+    }
+}
@@ -0,0 +1,152 @@
+#[cfg(test)]
+mod recovery_share_tests {
+    use crate::{
+        enums::sentinel_purpose::SentinelPurpose,
+        mocks::application::ApplicationMocks,
+        models::sentinel::Sentinel,
+        services::{fragments::FragmentsService, recovery_kit::RecoveryKitService},
+        utils::recovery_share::RecoveryShare,
+    };
+
+    use chrono::{Duration, Utc};
+    use rocket::tokio;
+    use sha2::{Digest, Sha256};
+    use uuid::Uuid;
+
+    fn build_kit(key: &[u8], threshold: u8, number_of_shares: u8) -> Vec<RecoveryShare> {
+        build_kit_for(key, threshold, number_of_shares, SentinelPurpose::Encryption)
+    }
+
+    fn build_kit_for(
+        key: &[u8],
+        threshold: u8,
+        number_of_shares: u8,
+        purpose: SentinelPurpose,
+    ) -> Vec<RecoveryShare> {
+        let sentinel_id = Uuid::new_v4();
+        FragmentsService::split_secret(key, threshold, number_of_shares as usize)
+            .into_iter()
+            .map(|share| RecoveryShare {
+                sentinel_id,
+                application_id: 42,
+                key_size: (key.len() * 8) as u16,
+                threshold,
+                number_of_shares,
+                purpose,
+                share,
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn encode_decode_success() {
+        let kit = build_kit(&[7u8; 32], 3, 5);
+        for share in kit.iter() {
+            assert_eq!(RecoveryShare::decode(&share.encode()).unwrap(), share.clone());
+            assert_eq!(RecoveryShare::decode(&share.to_text()).unwrap(), share.clone());
+            let typed = share.encode().to_lowercase();
+            assert_eq!(RecoveryShare::decode(&typed).unwrap(), share.clone());
+        }
+    }
+
+    #[tokio::test]
+    async fn decode_typo_detected() {
+        let kit = build_kit(&[7u8; 16], 2, 3);
+        let mut encoded: Vec<char> = kit[0].encode().chars().collect();
+        encoded[30] = match encoded[30] {
+            'A' => 'B',
+            _ => 'A',
+        };
+        let encoded: String = encoded.into_iter().collect();
+        assert_eq!(
+            RecoveryShare::decode(&encoded),
+            Err("The share checksum does not match, check for a typo")
+        );
+    }
+
+    #[tokio::test]
+    async fn recover_with_threshold_shares() {
+        let key: Vec<u8> = (0..32).collect();
+        let kit = build_kit(&key, 3, 5);
+        let shares = vec![kit[4].share.clone(), kit[0].share.clone(), kit[2].share.clone()];
+        assert_eq!(FragmentsService::recover_secret(shares, 3), Some(key));
+        let shares = vec![kit[1].share.clone(), kit[3].share.clone()];
+        assert_eq!(FragmentsService::recover_secret(shares, 3), None);
+    }
+
+    #[tokio::test]
+    async fn purpose_is_carried() {
+        let kit = build_kit_for(&[7u8; 32], 2, 3, SentinelPurpose::Mac);
+        let decoded = RecoveryShare::decode(&kit[0].encode()).unwrap();
+        assert_eq!(decoded.purpose, SentinelPurpose::Mac);
+        assert!(kit[0].to_text().contains("purpose: mac"));
+    }
+
+    #[tokio::test]
+    async fn decode_version_1_share() {
+        // the shares printed before the purpose was carried hold encryption keys
+        let sentinel_id = Uuid::new_v4();
+        let mut bytes = vec![1u8];
+        bytes.extend_from_slice(sentinel_id.as_bytes());
+        bytes.extend_from_slice(&42i32.to_be_bytes());
+        bytes.extend_from_slice(&256u16.to_be_bytes());
+        bytes.extend_from_slice(&[2, 3]);
+        bytes.extend_from_slice(&[1, 9, 9, 9]);
+        let checksum = Sha256::digest(&bytes)[..4].to_vec();
+        bytes.extend_from_slice(&checksum);
+        let encoded = base32::encode(base32::Alphabet::Rfc4648 { padding: false }, &bytes);
+
+        let share = RecoveryShare::decode(&encoded).unwrap();
+        assert_eq!(share.sentinel_id, sentinel_id);
+        assert_eq!(share.application_id, 42);
+        assert_eq!(share.threshold, 2);
+        assert_eq!(share.purpose, SentinelPurpose::Encryption);
+        assert_eq!(share.share, vec![1, 9, 9, 9]);
+    }
+
+    #[tokio::test]
+    async fn reimport_keeps_the_mac_purpose() {
+        let kit = build_kit(&[7u8; 32], 2, 3);
+        let deleted = Sentinel {
+            id: kit[0].sentinel_id,
+            application_id: 42,
+            iv: String::from("old iv"),
+            sum: String::from("old sum"),
+            is_deleted: true,
+            created_at: Utc::now() - Duration::days(30),
+            updated_at: None,
+            deleted_at: Some(Utc::now()),
+            created_by_id: Some(Uuid::new_v4()),
+            updated_by_id: None,
+            deleted_by_id: None,
+            key_size: 256,
+            purpose: SentinelPurpose::Mac.as_str().to_string(),
+            required_approvals: 2,
+            break_glass_at: None,
+            break_glass_by_id: None,
+        };
+
+        // a kit carrying another purpose does not turn the MAC key into an encryption key
+        let restored = RecoveryKitService::<ApplicationMocks>::restored_sentinel(
+            &kit[0],
+            Some(&deleted),
+            String::from("iv"),
+            String::from("sum"),
+        );
+        assert_eq!(restored.purpose, "mac");
+        assert_eq!(restored.required_approvals, 2);
+        assert_eq!(restored.created_at, deleted.created_at);
+        assert_eq!(restored.created_by_id, deleted.created_by_id);
+        assert!(!restored.is_deleted);
+
+        let mac_kit = build_kit_for(&[7u8; 32], 2, 3, SentinelPurpose::Mac);
+        let fresh = RecoveryKitService::<ApplicationMocks>::restored_sentinel(
+            &mac_kit[0],
+            None,
+            String::from("iv"),
+            String::from("sum"),
+        );
+        assert_eq!(fresh.purpose, "mac");
+        assert_eq!(fresh.required_approvals, 0);
+    }
+}
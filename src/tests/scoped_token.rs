@@ -0,0 +1,45 @@
+#[cfg(test)]
+mod scoped_token_tests {
+    use uuid::Uuid;
+
+    use crate::{enums::key_operation::KeyOperation, utils::jwt::TokenScope};
+
+    use rocket::tokio;
+
+    #[tokio::test]
+    async fn parse_scope() {
+        let sentinel = Uuid::new_v4();
+        let scope = TokenScope::parse(
+            vec![sentinel.to_string()],
+            vec![],
+            vec!["read".to_string(), "encrypt".to_string(), "read".to_string()],
+        )
+        .unwrap();
+        assert_eq!(scope.sentinels, vec![sentinel]);
+        assert_eq!(scope.operations, vec!["read", "encrypt"]);
+        assert!(TokenScope::parse(vec![], vec![], vec!["read".to_string()]).is_err());
+        assert!(TokenScope::parse(vec![sentinel.to_string()], vec![], vec![]).is_err());
+        assert!(
+            TokenScope::parse(vec![sentinel.to_string()], vec![], vec!["write".to_string()]).is_err()
+        );
+        assert!(TokenScope::parse(vec!["payroll".to_string()], vec![], vec!["read".to_string()]).is_err());
+    }
+
+    #[tokio::test]
+    async fn scope_allows_only_its_keys_and_operations() {
+        let sentinel = Uuid::new_v4();
+        let cluster = Uuid::new_v4();
+        let other = Uuid::new_v4();
+        let scope = TokenScope::parse(
+            vec![sentinel.to_string()],
+            vec![cluster.to_string()],
+            vec!["read".to_string()],
+        )
+        .unwrap();
+        assert!(scope.allows(&sentinel, &[], KeyOperation::Read));
+        assert!(!scope.allows(&sentinel, &[], KeyOperation::Delete));
+        assert!(scope.allows(&other, &[Uuid::new_v4(), cluster], KeyOperation::Read));
+        assert!(!scope.allows(&other, &[Uuid::new_v4()], KeyOperation::Read));
+        assert!(!scope.allows(&other, &[], KeyOperation::Read));
+    }
+}
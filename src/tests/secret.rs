@@ -0,0 +1,239 @@
+#[cfg(test)]
+mod secret_tests {
+    use std::env;
+
+    use crate::{
+        core::nodes_config::NodesConfig,
+        dto::secret::{secret_input::SecretInput, secret_version_input::SecretVersionInput},
+        mocks::secret::{SecretMocks, OTHER_USER_SECRET},
+        models::{secret_version::SecretVersion, user::User},
+        services::{fragments::FragmentsService, secret::SecretService},
+        traits::secret::SecretContractWithoutPool,
+    };
+
+    use chrono::Utc;
+    use rocket::{http::Status, tokio};
+    use uuid::Uuid;
+
+    // the fragments can not be saved without nodes, only the reads and deletions reach them
+    fn secret_service() -> SecretService<SecretMocks> {
+        let secret_repository: SecretMocks = SecretContractWithoutPool::new_without_pool();
+        SecretService::new(secret_repository, &NodesConfig { nodes: vec![] })
+    }
+
+    fn set_master_key() {
+        env::set_var("HSM_MODE", "0");
+        env::set_var("ENCRYPTION_KEY", hex::encode([9u8; 32]));
+    }
+
+    fn user() -> User {
+        User {
+            id: Uuid::new_v4(),
+            email: String::from("test@test.com"),
+            firstname: String::from("test"),
+            lastname: String::from("test"),
+            twofa_code: String::from("123"),
+            is_2fa_activated: false,
+            login: String::from("test"),
+            roles: vec![Some(String::from("ROLE_USER"))],
+            password: Some(String::from("test")),
+            full_text_search: String::from("test"),
+            kyber_secret_key: String::from("test"),
+            kyber_public_key: String::from("test"),
+            iv: String::from("test"),
+            is_deleted: false,
+            created_at: Utc::now(),
+            updated_at: None,
+            deleted_at: None,
+            created_by_id: None,
+            updated_by_id: None,
+            deleted_by_id: None,
+            refresh_token: None,
+            application: Some(1),
+            restricted_ip: vec![],
+            is_validated: true,
+            validation_code: None,
+            validation_tries: 0,
+            forget_code_delay: None,
+        }
+    }
+
+    fn input(name: &str, value: &str) -> SecretInput {
+        SecretInput {
+            name: String::from(name),
+            value: String::from(value),
+            clusters: vec![],
+        }
+    }
+
+    #[tokio::test]
+    async fn create_refuses_bad_names() {
+        let secret_service = secret_service();
+        for name in [String::new(), String::from("   "), "a".repeat(256)] {
+            assert_eq!(
+                secret_service.create(input(&name, "s3cr3t"), user()).unwrap_err(),
+                (
+                    Status::BadRequest,
+                    Some("The secret name must be between 1 and 255 characters")
+                )
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn create_refuses_bad_values() {
+        let secret_service = secret_service();
+        for value in [String::new(), "a".repeat(64 * 1024 + 1)] {
+            assert_eq!(
+                secret_service.create(input("db_password", &value), user()).unwrap_err(),
+                (
+                    Status::BadRequest,
+                    Some("The secret value must be between 1 byte and 64 KiB")
+                )
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn create_refuses_an_existing_name() {
+        set_master_key();
+        let secret_service = secret_service();
+        // already stored, or stored by a concurrent request
+        for name in ["existing", " existing ", "taken"] {
+            assert_eq!(
+                secret_service.create(input(name, "s3cr3t"), user()).unwrap_err(),
+                (Status::Conflict, Some("A secret with this name already exists"))
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn add_version_checks() {
+        let secret_service = secret_service();
+        let version = SecretVersionInput {
+            value: String::from("n3w"),
+        };
+        assert_eq!(
+            secret_service
+                .add_version(Uuid::nil(), version, user())
+                .unwrap_err(),
+            (Status::NotFound, None)
+        );
+        let empty = SecretVersionInput {
+            value: String::new(),
+        };
+        assert_eq!(
+            secret_service
+                .add_version(Uuid::new_v4(), empty, user())
+                .unwrap_err()
+                .0,
+            Status::BadRequest
+        );
+    }
+
+    #[tokio::test]
+    async fn get_by_id_checks() {
+        let secret_service = secret_service();
+        assert_eq!(
+            secret_service.get_by_id(Uuid::nil(), None, user()).unwrap_err(),
+            (Status::NotFound, None)
+        );
+        assert_eq!(
+            secret_service
+                .get_by_id(Uuid::new_v4(), Some(4), user())
+                .unwrap_err(),
+            (Status::NotFound, Some("Secret version not found"))
+        );
+        // the version exists but its fragments are on no node
+        assert_eq!(
+            secret_service
+                .get_by_id(Uuid::new_v4(), Some(2), user())
+                .unwrap_err(),
+            (Status::NotFound, None)
+        );
+    }
+
+    #[tokio::test]
+    async fn get_versions_newest_first() {
+        let secret_service = secret_service();
+        let versions = secret_service.get_versions(Uuid::new_v4(), user()).unwrap();
+        assert_eq!(
+            versions.iter().map(|version| version.version).collect::<Vec<i32>>(),
+            vec![3, 2, 1]
+        );
+        assert_eq!(
+            secret_service.get_versions(Uuid::nil(), user()).unwrap_err(),
+            (Status::NotFound, None)
+        );
+    }
+
+    #[tokio::test]
+    async fn delete_own_secret_or_as_admin() {
+        let secret_service = secret_service();
+        assert!(secret_service.delete_one(Uuid::new_v4(), user(), false).is_ok());
+        assert_eq!(
+            secret_service
+                .delete_one(OTHER_USER_SECRET, user(), false)
+                .unwrap_err(),
+            (Status::NotFound, None)
+        );
+        assert!(secret_service.delete_one(OTHER_USER_SECRET, user(), true).is_ok());
+        assert_eq!(
+            secret_service.delete_one(Uuid::nil(), user(), true).unwrap_err(),
+            (Status::NotFound, None)
+        );
+    }
+
+    #[tokio::test]
+    async fn value_round_trip_through_the_fragments() {
+        set_master_key();
+        let value = "-----BEGIN CERTIFICATE-----\nMIIB\n-----END CERTIFICATE-----";
+        let (iv, sum, encrypted) = SecretService::<SecretMocks>::encrypt_value(value);
+        assert_ne!(encrypted, value);
+        let secret_version = SecretVersion {
+            id: Uuid::new_v4(),
+            secret_id: Uuid::new_v4(),
+            version: 1,
+            iv,
+            sum,
+            is_deleted: false,
+            created_at: Utc::now(),
+            updated_at: None,
+            deleted_at: None,
+            created_by_id: None,
+            updated_by_id: None,
+            deleted_by_id: None,
+        };
+        let fragments = FragmentsService::split_secret(encrypted.as_bytes(), 2, 3)
+            .into_iter()
+            .map(hex::encode)
+            .collect::<Vec<String>>();
+
+        // any two nodes rebuild the value
+        for skipped in 0..3 {
+            let available = fragments
+                .iter()
+                .enumerate()
+                .filter(|(index, _)| *index != skipped)
+                .map(|(_, fragment)| fragment.clone())
+                .collect();
+            let (_, revealed) =
+                SecretService::<SecretMocks>::reveal(&secret_version, available).unwrap();
+            assert_eq!(revealed, value);
+        }
+        assert_eq!(
+            SecretService::<SecretMocks>::reveal(&secret_version, fragments[..1].to_vec())
+                .unwrap_err(),
+            (Status::NotFound, None)
+        );
+
+        let tampered = SecretVersion {
+            sum: String::from("0000"),
+            ..secret_version
+        };
+        assert_eq!(
+            SecretService::<SecretMocks>::reveal(&tampered, fragments).unwrap_err(),
+            (Status::NotAcceptable, Some("Not valid secret integrity"))
+        );
+    }
+}
@@ -0,0 +1,66 @@
+#[cfg(test)]
+mod service_account_tests {
+    use chrono::Utc;
+    use jsonwebtoken::{encode, Algorithm, EncodingKey, Header};
+    use openssl::rsa::Rsa;
+    use serde_json::json;
+
+    use crate::utils::client_assertion::ClientAssertion;
+
+    use rocket::tokio;
+
+    const AUDIENCE: &str = "https://lagertha.example/oauth/token";
+
+    fn key_pair() -> (Vec<u8>, String) {
+        let rsa = Rsa::generate(2048).unwrap();
+        let private_key = rsa.private_key_to_pem().unwrap();
+        let public_key = String::from_utf8(rsa.public_key_to_pem().unwrap()).unwrap();
+        (private_key, public_key)
+    }
+
+    fn sign(private_key: &[u8], claims: serde_json::Value) -> String {
+        let key = EncodingKey::from_rsa_pem(private_key).unwrap();
+        encode(&Header::new(Algorithm::RS256), &claims, &key).unwrap()
+    }
+
+    fn claims(client_id: &str, audience: &str, lifetime: i64) -> serde_json::Value {
+        json!({
+            "iss": client_id,
+            "sub": client_id,
+            "aud": audience,
+            "exp": Utc::now().timestamp() + lifetime,
+            "jti": "1f0c8a6e",
+        })
+    }
+
+    #[tokio::test]
+    async fn verify_client_assertion() {
+        let (private_key, public_key) = key_pair();
+        let assertion = sign(&private_key, claims("sa_client", AUDIENCE, 60));
+        let verified = ClientAssertion::verify(&assertion, &public_key, "sa_client", AUDIENCE).unwrap();
+        assert_eq!(verified.jti, "1f0c8a6e");
+        assert!(ClientAssertion::is_public_key(&public_key));
+        assert!(!ClientAssertion::is_public_key("not a key"));
+    }
+
+    #[tokio::test]
+    async fn reject_bad_client_assertion() {
+        let (private_key, public_key) = key_pair();
+        let (_, other_public_key) = key_pair();
+
+        let wrong_audience = sign(&private_key, claims("sa_client", "https://other/oauth/token", 60));
+        assert!(ClientAssertion::verify(&wrong_audience, &public_key, "sa_client", AUDIENCE).is_err());
+
+        let wrong_issuer = sign(&private_key, claims("sa_other", AUDIENCE, 60));
+        assert!(ClientAssertion::verify(&wrong_issuer, &public_key, "sa_client", AUDIENCE).is_err());
+
+        let too_long = sign(&private_key, claims("sa_client", AUDIENCE, 3600));
+        assert!(ClientAssertion::verify(&too_long, &public_key, "sa_client", AUDIENCE).is_err());
+
+        let expired = sign(&private_key, claims("sa_client", AUDIENCE, -3600));
+        assert!(ClientAssertion::verify(&expired, &public_key, "sa_client", AUDIENCE).is_err());
+
+        let valid = sign(&private_key, claims("sa_client", AUDIENCE, 60));
+        assert!(ClientAssertion::verify(&valid, &other_public_key, "sa_client", AUDIENCE).is_err());
+    }
+}
@@ -0,0 +1,122 @@
+#[cfg(test)]
+mod session_tests {
+    use crate::{
+        dto::session::{session_insertable::SessionInsertable, session_output::SessionOutput},
+        models::{connexion::Connexion, session::Session},
+        utils::{jwt::Jwt, token_deny_list::TokenDenyList},
+    };
+
+    use chrono::Utc;
+    use rocket::tokio;
+    use uuid::Uuid;
+
+    fn session(user_id: Uuid) -> Session {
+        Session {
+            id: Uuid::new_v4(),
+            user_id,
+            application_id: 1,
+            connexion_id: Some(2),
+            refresh_jti: Uuid::new_v4(),
+            expires_at: Utc::now(),
+            revoked_at: None,
+            is_deleted: false,
+            created_at: Utc::now(),
+            updated_at: None,
+            deleted_at: None,
+            created_by_id: Some(user_id),
+            updated_by_id: None,
+            deleted_by_id: None,
+            last_used_at: Utc::now(),
+        }
+    }
+
+    fn connexion(user_id: Uuid, user_agent: &str) -> Connexion {
+        Connexion {
+            id: 2,
+            user_id,
+            ip: format!("10.0.0.1"),
+            user_agent: user_agent.to_string(),
+            fingerprint: format!("fingerprint"),
+            is_deleted: false,
+            created_at: Utc::now(),
+            updated_at: None,
+            deleted_at: None,
+            created_by_id: None,
+            updated_by_id: None,
+            deleted_by_id: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn session_device_is_parsed_from_the_user_agent() {
+        let user_id = Uuid::new_v4();
+        let session = session(user_id);
+        let current = Some(session.id);
+        let output = SessionOutput::new(
+            session,
+            Some(connexion(
+                user_id,
+                "Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36",
+            )),
+            current,
+        );
+        assert!(output.current);
+        assert_eq!(output.ip.as_deref(), Some("10.0.0.1"));
+        assert_eq!(output.device.as_deref(), Some("pc"));
+        assert_eq!(output.browser.as_deref(), Some("Chrome"));
+        assert_eq!(output.browser_version.as_deref(), Some("120.0.0.0"));
+        assert_eq!(output.os.as_deref(), Some("Windows 10"));
+    }
+
+    #[tokio::test]
+    async fn session_without_connexion_has_no_device() {
+        let output = SessionOutput::new(session(Uuid::new_v4()), None, None);
+        assert!(!output.current);
+        assert!(output.ip.is_none());
+        assert!(output.device.is_none());
+        let user_id = Uuid::new_v4();
+        let output = SessionOutput::new(session(user_id), Some(connexion(user_id, "curl")), None);
+        assert!(output.browser.is_none());
+        assert!(output.os.is_none());
+    }
+
+    #[tokio::test]
+    async fn sessions_start_with_their_own_refresh_jti() {
+        let user_id = Uuid::new_v4();
+        let expires_at = Utc::now();
+        let first = SessionInsertable::new(user_id, 1, Some(2), expires_at);
+        let second = SessionInsertable::new(user_id, 1, None, expires_at);
+        assert_ne!(first.refresh_jti, second.refresh_jti);
+        assert_eq!(first.created_by_id, Some(user_id));
+        assert!(first.revoked_at.is_none());
+    }
+
+    #[tokio::test]
+    async fn tokens_before_sessions_have_no_sid() {
+        let claims: Jwt = serde_json::from_value(serde_json::json!({
+            "id": Uuid::new_v4(),
+            "login": "ada",
+            "application_id": 1,
+            "application_name": "test",
+            "is_refresh": true,
+            "is_2fa_activate": false,
+            "iat": 1000,
+            "exp": 1900,
+            "roles": ["ROLE_USER"],
+            "firstname": "Ada",
+            "lastname": "Lovelace",
+            "email": "ada@test.com",
+            "device_id": null
+        }))
+        .unwrap();
+        assert!(claims.sid.is_none());
+        assert!(claims.jti.is_none());
+    }
+
+    #[tokio::test]
+    async fn session_and_token_deny_keys_differ() {
+        let id = Uuid::new_v4();
+        assert_eq!(TokenDenyList::session_key(&id), format!("revoked_sid:{}", id));
+        assert_ne!(TokenDenyList::session_key(&id), TokenDenyList::key(&id));
+    }
+}
@@ -0,0 +1,43 @@
+#[cfg(test)]
+mod share_link_tests {
+    use crate::utils::{
+        code::{generate_token, hash_token},
+        key_import::KeyImport,
+    };
+
+    use rocket::tokio;
+
+    #[tokio::test]
+    async fn tokens_are_random_and_hashed() {
+        let token = generate_token();
+        assert_eq!(token.len(), 43);
+        assert!(token.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_'));
+        assert_ne!(token, generate_token());
+        let hash = hash_token(&token);
+        assert_eq!(hash.len(), 64);
+        assert_eq!(hash, hash_token(&token));
+        assert_ne!(hash, hash_token(&generate_token()));
+    }
+
+    #[tokio::test]
+    async fn ml_kem_768_wrap_roundtrip() {
+        let (public_key, private_key) = KeyImport::generate_ml_kem_768_key_pair();
+        assert!(KeyImport::is_ml_kem_768_public_key(&public_key));
+        assert!(!KeyImport::is_ml_kem_768_public_key(&private_key));
+        assert!(!KeyImport::is_ml_kem_768_public_key("payroll"));
+        let key = [7u8; 32];
+        let (encapsulated_key, nonce, wrapped_key) =
+            KeyImport::wrap_ml_kem_768(&public_key, &key).unwrap();
+        assert_eq!(
+            KeyImport::unwrap_ml_kem_768(&private_key, &encapsulated_key, &nonce, &wrapped_key),
+            Some(key.to_vec())
+        );
+        let (other_public_key, _) = KeyImport::generate_ml_kem_768_key_pair();
+        let (encapsulated_key, nonce, wrapped_key) =
+            KeyImport::wrap_ml_kem_768(&other_public_key, &key).unwrap();
+        assert_eq!(
+            KeyImport::unwrap_ml_kem_768(&private_key, &encapsulated_key, &nonce, &wrapped_key),
+            None
+        );
+    }
+}
@@ -0,0 +1,30 @@
+#[cfg(test)]
+mod signing_key_tests {
+    use crate::services::signing_key::SigningKeyService;
+
+    use chrono::Duration;
+    use rocket::tokio;
+
+    #[tokio::test]
+    async fn retirement_outlives_the_refresh_tokens() {
+        let refresh_duration = Duration::days(45);
+        let refresh_delay = refresh_duration.num_seconds();
+        // unset, the former 30 days default would retire keys still signing living refresh tokens
+        assert_eq!(
+            SigningKeyService::clamp_retirement_delay(None, refresh_duration),
+            refresh_delay
+        );
+        assert_eq!(
+            SigningKeyService::clamp_retirement_delay(Some(2592000), refresh_duration),
+            refresh_delay
+        );
+        assert_eq!(
+            SigningKeyService::clamp_retirement_delay(Some(0), refresh_duration),
+            refresh_delay
+        );
+        assert_eq!(
+            SigningKeyService::clamp_retirement_delay(Some(refresh_delay + 60), refresh_duration),
+            refresh_delay + 60
+        );
+    }
+}
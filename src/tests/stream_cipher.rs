@@ -0,0 +1,49 @@
+#[cfg(test)]
+mod stream_cipher_tests {
+    use crate::utils::stream_cipher::{StreamCipherReader, STREAM_SEGMENT_SIZE};
+
+    use rocket::tokio::{self, io::AsyncReadExt};
+
+    async fn encrypt(key: &[u8], plaintext: &[u8]) -> Vec<u8> {
+        let mut output = Vec::new();
+        StreamCipherReader::encrypt(plaintext, key, u64::MAX)
+            .unwrap()
+            .read_to_end(&mut output)
+            .await
+            .unwrap();
+        output
+    }
+
+    async fn decrypt(key: &[u8], ciphertext: &[u8]) -> std::io::Result<Vec<u8>> {
+        let mut output = Vec::new();
+        StreamCipherReader::decrypt(ciphertext, key, u64::MAX)
+            .unwrap()
+            .read_to_end(&mut output)
+            .await?;
+        Ok(output)
+    }
+
+    #[tokio::test]
+    async fn stream_roundtrip_and_tampering() {
+        let key = [7u8; 32];
+        for size in [0, 1, STREAM_SEGMENT_SIZE, 2 * STREAM_SEGMENT_SIZE + 17] {
+            let plaintext: Vec<u8> = (0..size).map(|i| i as u8).collect();
+            let ciphertext = encrypt(&key, &plaintext).await;
+            assert_eq!(decrypt(&key, &ciphertext).await.unwrap(), plaintext);
+        }
+
+        let plaintext = vec![42u8; 2 * STREAM_SEGMENT_SIZE + 17];
+        let ciphertext = encrypt(&key, &plaintext).await;
+        // wrong key
+        assert!(decrypt(&[8u8; 32], &ciphertext).await.is_err());
+        // flipped bit
+        let mut tampered = ciphertext.clone();
+        tampered[ciphertext.len() / 2] ^= 1;
+        assert!(decrypt(&key, &tampered).await.is_err());
+        // truncated on a segment boundary
+        let truncated = &ciphertext[..ciphertext.len() - 17 - 16];
+        assert!(decrypt(&key, truncated).await.is_err());
+        // truncated inside the header
+        assert!(decrypt(&key, &ciphertext[..10]).await.is_err());
+    }
+}
@@ -0,0 +1,72 @@
+#[cfg(test)]
+mod token_tests {
+    use crate::{
+        dto::oauth::oauth_introspection_output::OauthIntrospectionOutput, utils::jwt::Jwt,
+    };
+
+    use rocket::tokio;
+    use uuid::Uuid;
+
+    fn claims() -> Jwt {
+        Jwt {
+            id: Uuid::new_v4(),
+            login: format!("ada"),
+            application_id: 123,
+            application_name: format!("test"),
+            is_refresh: false,
+            is_2fa_activate: false,
+            iat: 1000,
+            exp: 1900,
+            roles: vec![Some(format!("ROLE_USER"))],
+            firstname: format!("Ada"),
+            lastname: format!("Lovelace"),
+            email: format!("ada@test.com"),
+            device_id: None,
+            scope: None,
+            is_service_account: false,
+            oauth_client_id: Some(format!("client")),
+            oauth_scope: Some(format!("openid profile")),
+            auth_time: 1000,
+            amr: vec![format!("pwd")],
+            jti: Some(Uuid::new_v4()),
+            sid: None,
+            cnf: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn inactive_token_has_no_claims() {
+        let output = serde_json::to_value(OauthIntrospectionOutput::inactive()).unwrap();
+        assert_eq!(output, serde_json::json!({ "active": false }));
+    }
+
+    #[tokio::test]
+    async fn introspect_access_token() {
+        let claims = claims();
+        let output = OauthIntrospectionOutput::new(&claims, format!("https://api.example.com"));
+        assert!(output.active);
+        assert_eq!(output.token_type.as_deref(), Some("Bearer"));
+        assert_eq!(output.client_id.as_deref(), Some("client"));
+        assert_eq!(output.scope.as_deref(), Some("openid profile"));
+        assert_eq!(output.sub, Some(claims.id.to_string()));
+        assert_eq!(output.jti, claims.jti.map(|jti| jti.to_string()));
+        assert_eq!(output.exp, Some(1900));
+    }
+
+    #[tokio::test]
+    async fn introspect_refresh_token() {
+        let mut claims = claims();
+        claims.is_refresh = true;
+        claims.oauth_client_id = None;
+        let output = OauthIntrospectionOutput::new(&claims, format!("https://api.example.com"));
+        assert_eq!(output.token_type.as_deref(), Some("refresh_token"));
+        assert!(output.client_id.is_none());
+    }
+
+    #[tokio::test]
+    async fn tokens_without_jti_are_not_in_the_deny_list() {
+        let mut claims = claims();
+        claims.jti = None;
+        assert!(!claims.is_revoked());
+    }
+}
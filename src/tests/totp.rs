@@ -0,0 +1,48 @@
+#[cfg(test)]
+mod totp_tests {
+    use crate::utils::code;
+    use rocket::tokio;
+
+    /// the SHA-1 secret of RFC 6238, `12345678901234567890` in base32
+    const SECRET: &str = "GEZDGNBVGY3TQOJQGEZDGNBVGY3TQOJQ";
+
+    #[tokio::test]
+    async fn rfc_6238_vector() {
+        // 94287082 at T = 59, its 6 last digits
+        assert_eq!(code::check_otp_at(287082, SECRET, 0, 59), Some(1));
+        assert_eq!(code::check_otp_at(287081, SECRET, 0, 59), None);
+    }
+
+    #[tokio::test]
+    async fn drift_window() {
+        let timestamp = 59 + 2 * code::TOTP_PERIOD;
+        // the code of the step 1 is two steps late
+        assert_eq!(code::check_otp_at(287082, SECRET, 1, timestamp), None);
+        assert_eq!(code::check_otp_at(287082, SECRET, 2, timestamp), Some(1));
+        // and a step early from before
+        assert_eq!(code::check_otp_at(287082, SECRET, 1, 0), Some(1));
+        assert_eq!(code::check_otp_at(287082, SECRET, 0, 0), None);
+    }
+
+    #[tokio::test]
+    async fn malformed_secret_matches_nothing() {
+        assert_eq!(code::check_otp_at(287082, "not base32 !", 1, 59), None);
+        assert_eq!(code::check_otp(0, "1"), None);
+    }
+
+    #[tokio::test]
+    async fn recovery_codes() {
+        let recovery_code = code::generate_recovery_code();
+        assert_eq!(recovery_code.len(), 19);
+        assert_eq!(recovery_code.split('-').count(), 4);
+        assert_ne!(recovery_code, code::generate_recovery_code());
+
+        // typed in lower case, with spaces in place of the dashes
+        let typed = recovery_code.to_lowercase().replace('-', " ");
+        assert_eq!(
+            code::hash_token(&code::normalize_recovery_code(&typed)),
+            code::hash_token(&code::normalize_recovery_code(&recovery_code))
+        );
+        assert_eq!(code::normalize_recovery_code(&recovery_code).len(), 16);
+    }
+}
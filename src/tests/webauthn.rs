@@ -0,0 +1,237 @@
+#[cfg(test)]
+mod webauthn_tests {
+    use crate::utils::{
+        cbor::Cbor,
+        webauthn::{AuthenticatorData, Webauthn, COSE_ES256},
+    };
+
+    use openssl::{
+        bn::{BigNum, BigNumContext},
+        ec::{EcGroup, EcKey},
+        hash::MessageDigest,
+        nid::Nid,
+        pkey::{PKey, Private},
+        sign::Signer,
+    };
+    use rocket::tokio;
+    use sha2::{Digest, Sha256};
+    use std::env;
+
+    const RP_ID: &str = "example.com";
+    const ORIGIN: &str = "https://app.example.com";
+
+    fn settings() {
+        env::set_var("WEBAUTHN_RP_ID", RP_ID);
+        env::set_var("WEBAUTHN_ORIGINS", format!("https://other.example.com, {}/", ORIGIN));
+    }
+
+    fn generate_key() -> PKey<Private> {
+        let group = EcGroup::from_curve_name(Nid::X9_62_PRIME256V1).unwrap();
+        PKey::from_ec_key(EcKey::generate(&group).unwrap()).unwrap()
+    }
+
+    /// the COSE key of an ES256 key, `{1: 2, 3: -7, -1: 1, -2: x, -3: y}`
+    fn cose_key(key: &PKey<Private>) -> Vec<u8> {
+        let ec_key = key.ec_key().unwrap();
+        let mut x = BigNum::new().unwrap();
+        let mut y = BigNum::new().unwrap();
+        ec_key
+            .public_key()
+            .affine_coordinates(ec_key.group(), &mut x, &mut y, &mut BigNumContext::new().unwrap())
+            .unwrap();
+        let mut cose = vec![0xa5, 0x01, 0x02, 0x03, 0x26, 0x20, 0x01, 0x21, 0x58, 0x20];
+        cose.extend(x.to_vec_padded(32).unwrap());
+        cose.extend([0x22, 0x58, 0x20]);
+        cose.extend(y.to_vec_padded(32).unwrap());
+        cose
+    }
+
+    fn authenticator_data(flags: u8, sign_count: u32, credential: Option<(&[u8], &[u8])>) -> Vec<u8> {
+        let mut data = Sha256::digest(RP_ID.as_bytes()).to_vec();
+        data.push(flags);
+        data.extend(sign_count.to_be_bytes());
+        if let Some((credential_id, cose_key)) = credential {
+            data.extend([0u8; 16]);
+            data.extend((credential_id.len() as u16).to_be_bytes());
+            data.extend(credential_id);
+            data.extend(cose_key);
+        }
+        data
+    }
+
+    /// `{"fmt": "none", "attStmt": {}, "authData": authenticator_data}`
+    fn attestation_object(authenticator_data: &[u8]) -> Vec<u8> {
+        let mut object = vec![0xa3, 0x63];
+        object.extend(b"fmt");
+        object.push(0x64);
+        object.extend(b"none");
+        object.push(0x67);
+        object.extend(b"attStmt");
+        object.extend([0xa0, 0x68]);
+        object.extend(b"authData");
+        object.push(0x59);
+        object.extend((authenticator_data.len() as u16).to_be_bytes());
+        object.extend(authenticator_data);
+        object
+    }
+
+    fn sign(key: &PKey<Private>, authenticator_data: &[u8], client_data_json: &[u8]) -> Vec<u8> {
+        let mut signer = Signer::new(MessageDigest::sha256(), key).unwrap();
+        signer.update(authenticator_data).unwrap();
+        signer.update(&Sha256::digest(client_data_json)).unwrap();
+        signer.sign_to_vec().unwrap()
+    }
+
+    #[tokio::test]
+    async fn cbor_decode() {
+        // {1: -7, "a": h'0102', "b": [true, null]}
+        let bytes = [
+            0xa3, 0x01, 0x26, 0x61, 0x61, 0x42, 0x01, 0x02, 0x61, 0x62, 0x82, 0xf5, 0xf6,
+        ];
+        let (value, length) = Cbor::decode(&bytes).unwrap();
+        assert_eq!(length, bytes.len());
+        assert_eq!(value.get_int(1).and_then(Cbor::as_int), Some(-7));
+        assert_eq!(value.get_text("a").and_then(Cbor::as_bytes), Some(&[1u8, 2][..]));
+        assert_eq!(
+            value.get_text("b"),
+            Some(&Cbor::Array(vec![Cbor::Bool(true), Cbor::Null]))
+        );
+    }
+
+    #[tokio::test]
+    async fn cbor_refuses_bad_items() {
+        // truncated byte string
+        assert!(Cbor::decode(&[0x43, 0x01]).is_err());
+        // indefinite length
+        assert!(Cbor::decode(&[0x9f, 0x01, 0xff]).is_err());
+        // nested too deep
+        assert!(Cbor::decode(&[0x81; 64]).is_err());
+        // a length larger than the input
+        assert!(Cbor::decode(&[0x5b, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff]).is_err());
+    }
+
+    #[tokio::test]
+    async fn base64_with_or_without_padding() {
+        let encoded = Webauthn::encode_base64(&[0xfb, 0xff]);
+        assert_eq!(encoded, "-_8");
+        assert_eq!(Webauthn::decode_base64("-_8=").unwrap(), vec![0xfb, 0xff]);
+        assert!(Webauthn::decode_base64("+/8").is_err());
+    }
+
+    #[tokio::test]
+    async fn client_data_checks_ceremony_and_origin() {
+        settings();
+        let client_data = format!(
+            r#"{{"type":"webauthn.get","challenge":"abc","origin":"{}"}}"#,
+            ORIGIN
+        );
+        let parsed = Webauthn::client_data(client_data.as_bytes(), "webauthn.get").unwrap();
+        assert_eq!(parsed.challenge, "abc");
+        assert!(Webauthn::client_data(client_data.as_bytes(), "webauthn.create").is_err());
+
+        let other = r#"{"type":"webauthn.get","challenge":"abc","origin":"https://evil.com"}"#;
+        assert_eq!(
+            Webauthn::client_data(other.as_bytes(), "webauthn.get").unwrap_err(),
+            "Origin not allowed"
+        );
+    }
+
+    #[tokio::test]
+    async fn registration_then_assertion() {
+        settings();
+        let key = generate_key();
+        let credential_id = [7u8; 16];
+        let cose_key = cose_key(&key);
+
+        // registration, user present and verified with an attested credential
+        let registration = authenticator_data(0x45, 0, Some((&credential_id, &cose_key)));
+        let attested = Webauthn::attestation(&attestation_object(&registration)).unwrap();
+        Webauthn::check_authenticator_data(&attested, true).unwrap();
+        let credential = attested.credential.unwrap();
+        assert_eq!(credential.credential_id, credential_id.to_vec());
+        assert_eq!(credential.algorithm, COSE_ES256);
+
+        // assertion signed by the registered key
+        let assertion = authenticator_data(0x05, 1, None);
+        let client_data_json = format!(
+            r#"{{"type":"webauthn.get","challenge":"abc","origin":"{}"}}"#,
+            ORIGIN
+        );
+        let signature = sign(&key, &assertion, client_data_json.as_bytes());
+        let parsed = AuthenticatorData::parse(&assertion).unwrap();
+        assert_eq!(parsed.sign_count, 1);
+        assert!(parsed.credential.is_none());
+        Webauthn::check_authenticator_data(&parsed, true).unwrap();
+        assert!(Webauthn::verify_signature(
+            &credential.public_key,
+            credential.algorithm,
+            &assertion,
+            client_data_json.as_bytes(),
+            &signature,
+        ));
+
+        // the signature does not cover other client data, nor another key
+        assert!(!Webauthn::verify_signature(
+            &credential.public_key,
+            credential.algorithm,
+            &assertion,
+            b"{}",
+            &signature,
+        ));
+        let other = sign(&generate_key(), &assertion, client_data_json.as_bytes());
+        assert!(!Webauthn::verify_signature(
+            &credential.public_key,
+            credential.algorithm,
+            &assertion,
+            client_data_json.as_bytes(),
+            &other,
+        ));
+    }
+
+    #[tokio::test]
+    async fn authenticator_data_checks() {
+        settings();
+        // user present but not verified
+        let present = AuthenticatorData::parse(&authenticator_data(0x01, 0, None)).unwrap();
+        assert!(Webauthn::check_authenticator_data(&present, false).is_ok());
+        assert_eq!(
+            Webauthn::check_authenticator_data(&present, true).unwrap_err(),
+            "User not verified"
+        );
+
+        let absent = AuthenticatorData::parse(&authenticator_data(0x00, 0, None)).unwrap();
+        assert_eq!(
+            Webauthn::check_authenticator_data(&absent, false).unwrap_err(),
+            "User not present"
+        );
+
+        let mut other_party = authenticator_data(0x05, 0, None);
+        other_party[0] ^= 0xff;
+        let other_party = AuthenticatorData::parse(&other_party).unwrap();
+        assert_eq!(
+            Webauthn::check_authenticator_data(&other_party, false).unwrap_err(),
+            "Bad relying party"
+        );
+
+        assert!(AuthenticatorData::parse(&[0u8; 36]).is_err());
+        // an attested credential flag without the credential
+        assert!(AuthenticatorData::parse(&authenticator_data(0x45, 0, None)).is_err());
+    }
+
+    #[tokio::test]
+    async fn cose_key_refuses_unsupported_keys() {
+        // an ES256 key on P-384
+        let (cose, _) = Cbor::decode(&[0xa3, 0x01, 0x02, 0x03, 0x26, 0x20, 0x02]).unwrap();
+        assert_eq!(
+            Webauthn::cose_key_to_pem(&cose).unwrap_err(),
+            "Unsupported COSE algorithm"
+        );
+        // a point off the curve
+        let mut cose = vec![0xa5, 0x01, 0x02, 0x03, 0x26, 0x20, 0x01, 0x21, 0x58, 0x20];
+        cose.extend([1u8; 32]);
+        cose.extend([0x22, 0x58, 0x20]);
+        cose.extend([2u8; 32]);
+        let (cose, _) = Cbor::decode(&cose).unwrap();
+        assert!(Webauthn::cose_key_to_pem(&cose).is_err());
+    }
+}
@@ -0,0 +1,58 @@
+use uuid::Uuid;
+
+use crate::{
+    db::connect::DbPool,
+    dto::access_request::access_request_insertable::AccessRequestInsertable,
+    enums::access_request_status::AccessRequestStatus,
+    models::{access_request::AccessRequest, user::User},
+};
+
+pub trait AccessRequestContract {
+    /// create a new instance
+    fn new(pool: &DbPool) -> Self
+    where
+        Self: Sized;
+
+    fn get_sentinel_clusters_requirement(&self, sentinel_uuid: &Uuid) -> i32;
+
+    fn get_anonymous_sentinel_clusters_requirement(&self, anonymous_sentinel_uuid: &Uuid) -> i32;
+
+    fn create_access_request(&self, insertable: AccessRequestInsertable) -> AccessRequest;
+
+    fn get_by_id(&self, access_request_uuid: &Uuid) -> Option<AccessRequest>;
+
+    fn find_open(
+        &self,
+        sentinel_uuid: Option<Uuid>,
+        anonymous_sentinel_uuid: Option<Uuid>,
+        requester_uuid: &Uuid,
+        status: AccessRequestStatus,
+    ) -> Option<AccessRequest>;
+
+    fn get_for_user(&self, user_from: &User) -> Vec<AccessRequest>;
+
+    fn is_cluster_member(&self, access_request: &AccessRequest, user_uuid: &Uuid) -> bool;
+
+    fn count_approvals(&self, access_request_uuid: &Uuid) -> i64;
+
+    fn approve(
+        &self,
+        access_request_uuid: &Uuid,
+        approver: &User,
+    ) -> Result<AccessRequest, diesel::result::Error>;
+
+    fn transition(
+        &self,
+        access_request_uuid: &Uuid,
+        from: AccessRequestStatus,
+        to: AccessRequestStatus,
+        user_from: &User,
+    ) -> bool;
+}
+
+#[cfg(test)]
+pub trait AccessRequestContractWithoutPool: AccessRequestContract {
+    fn new_without_pool() -> Self
+    where
+        Self: Sized;
+}
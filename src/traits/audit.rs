@@ -0,0 +1,29 @@
+use crate::{
+    db::connect::DbPool,
+    dto::audit_log::{audit_log_insertable::AuditLogInsertable, break_glass_alert::BreakGlassAlert},
+    models::{audit_log::AuditLog, user::User},
+};
+
+pub trait AuditContract {
+    /// create a new instance
+    fn new(pool: &DbPool) -> Self
+    where
+        Self: Sized;
+
+    fn create_audit_log(
+        &self,
+        insertable: AuditLogInsertable,
+    ) -> Result<AuditLog, diesel::result::Error>;
+
+    fn get_by_application(
+        &self,
+        application_id: i32,
+        severity: Option<&str>,
+        limit: i64,
+    ) -> Vec<AuditLog>;
+
+    fn get_admins_by_app(&self, application_id: i32) -> Vec<User>;
+
+    /// the alerts are mailed in the background, the audited action does not wait for them
+    fn send_break_glass_alerts(&self, alerts: Vec<BreakGlassAlert>);
+}
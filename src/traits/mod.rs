@@ -1,3 +1,6 @@
 pub mod revoked_token;
 pub mod application;
-pub mod connexion;
\ No newline at end of file
+pub mod connexion;
+pub mod secret;
+pub mod access_request;
+pub mod audit;
\ No newline at end of file
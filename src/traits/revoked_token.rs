@@ -4,8 +4,12 @@ pub trait RevokedTokenContract {
     /// create a new instance
     fn new(pool: &DbPool) -> Self where Self: Sized;
 
-    fn create_revoked_token(&self, insertable: RevokedTokenInsertable) -> RevokedToken;
+    /// fails when the token is already revoked
+    fn create_revoked_token(&self, insertable: RevokedTokenInsertable) -> Result<RevokedToken, diesel::result::Error>;
 
-    fn get_by_token(&self, test_token: &String) -> Option<RevokedToken>;
+    fn get_by_token_hash(&self, token_hash: &str) -> Option<RevokedToken>;
+
+    /// remove the records of the expired tokens
+    fn purge_expired(&self) -> usize;
 
 }
\ No newline at end of file
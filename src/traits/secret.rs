@@ -0,0 +1,53 @@
+use uuid::Uuid;
+
+use crate::{
+    db::connect::DbPool,
+    dto::secret::secret_insertable::SecretInsertable,
+    models::{secret::Secret, secret_version::SecretVersion, user::User},
+};
+
+pub trait SecretContract {
+    /// create a new instance
+    fn new(pool: &DbPool) -> Self
+    where
+        Self: Sized;
+
+    fn create_secret(
+        &self,
+        insertable: SecretInsertable,
+        iv: String,
+        sum: String,
+    ) -> Result<(Secret, SecretVersion), diesel::result::Error>;
+
+    fn add_version(
+        &self,
+        secret_uuid: &Uuid,
+        iv: String,
+        sum: String,
+        user_from: &User,
+    ) -> Result<(Secret, SecretVersion), diesel::result::Error>;
+
+    fn get_by_name(&self, secret_name: &str, application_id: i32) -> Option<Secret>;
+
+    fn get_secret_by_id(&self, secret_uuid: &Uuid, user_from: &User) -> Option<Secret>;
+
+    fn get_version(&self, secret: &Secret, version: i32) -> Option<SecretVersion>;
+
+    fn get_versions(&self, secret: &Secret) -> Vec<SecretVersion>;
+
+    fn delete_secret(
+        &self,
+        secret: &Secret,
+        user_from: &User,
+    ) -> Result<Vec<SecretVersion>, diesel::result::Error>;
+
+    /// share the secret with a cluster created by the user
+    fn add_to_user_cluster(&self, cluster_uuid: &Uuid, secret: &Secret, user_from: &User) -> bool;
+}
+
+#[cfg(test)]
+pub trait SecretContractWithoutPool: SecretContract {
+    fn new_without_pool() -> Self
+    where
+        Self: Sized;
+}
@@ -0,0 +1,130 @@
+use std::{env, fs};
+
+use base64::{engine::general_purpose, Engine as _};
+use openssl::{
+    hash::MessageDigest,
+    pkey::{PKey, Private, Public},
+    sign::{Signer, Verifier},
+};
+use sha2::{Digest, Sha256};
+
+/// ### BackupSigningKeys
+///
+/// the key signing the backup archives and the keys checking them, loaded at runtime so the signing
+/// key is not in the binary. An archive records the id of its key, the former keys of
+/// `BACKUP_VERIFY_KEY_PATHS` keep checking the archives taken before a change of key
+pub struct BackupSigningKeys {
+    signing_key: Option<(String, PKey<Private>)>,
+    verifying_keys: Vec<(String, PKey<Public>)>,
+}
+
+impl BackupSigningKeys {
+    /// the keys of `verifying_keys` and the public part of `signing_key` check the archives
+    pub fn new(
+        signing_key: Option<PKey<Private>>,
+        verifying_keys: Vec<PKey<Public>>,
+    ) -> Result<Self, String> {
+        let mut keys = Self {
+            signing_key: None,
+            verifying_keys: vec![],
+        };
+        if let Some(signing_key) = signing_key {
+            let public_key = signing_key
+                .public_key_to_pem()
+                .and_then(|pem| PKey::public_key_from_pem(&pem))
+                .map_err(|_| String::from("Unable to read the backup signing key"))?;
+            let key_id = Self::key_id(&public_key)?;
+            keys.verifying_keys.push((key_id.clone(), public_key));
+            keys.signing_key = Some((key_id, signing_key));
+        }
+        for public_key in verifying_keys {
+            let key_id = Self::key_id(&public_key)?;
+            if !keys.verifying_keys.iter().any(|(id, _)| *id == key_id) {
+                keys.verifying_keys.push((key_id, public_key));
+            }
+        }
+        Ok(keys)
+    }
+
+    /// the PEM private key of `BACKUP_SIGNING_KEY_PATH`, the archives are only checked without it,
+    /// and the comma separated PEM public keys of `BACKUP_VERIFY_KEY_PATHS`
+    pub fn load() -> Result<Self, String> {
+        let signing_key = match env::var("BACKUP_SIGNING_KEY_PATH") {
+            Ok(path) if !path.trim().is_empty() => Some(
+                fs::read(path.trim())
+                    .ok()
+                    .and_then(|pem| PKey::private_key_from_pem(&pem).ok())
+                    .ok_or(String::from("Unable to load the BACKUP_SIGNING_KEY_PATH key"))?,
+            ),
+            _ => None,
+        };
+        let mut verifying_keys = vec![];
+        for path in env::var("BACKUP_VERIFY_KEY_PATHS")
+            .unwrap_or_default()
+            .split(',')
+            .map(str::trim)
+            .filter(|path| !path.is_empty())
+        {
+            let public_key = fs::read(path)
+                .ok()
+                .and_then(|pem| PKey::public_key_from_pem(&pem).ok())
+                .ok_or(format!("Unable to load the backup verification key {}", path))?;
+            verifying_keys.push(public_key);
+        }
+        Self::new(signing_key, verifying_keys)
+    }
+
+    /// the SHA-256 of the DER public key, url safe base64
+    pub fn key_id(public_key: &PKey<Public>) -> Result<String, String> {
+        let der = public_key
+            .public_key_to_der()
+            .map_err(|_| String::from("Unable to read the backup key"))?;
+        Ok(general_purpose::URL_SAFE_NO_PAD.encode(Sha256::digest(der)))
+    }
+
+    /// the id of the key signing the archives
+    pub fn signing_key_id(&self) -> Result<String, String> {
+        self.signing_key
+            .as_ref()
+            .map(|(key_id, _)| key_id.clone())
+            .ok_or(String::from("BACKUP_SIGNING_KEY_PATH is not set"))
+    }
+
+    /// the SHA-256 signature of `data` (base64)
+    pub fn sign(&self, data: &str) -> Result<String, String> {
+        let (_, private_key) = self
+            .signing_key
+            .as_ref()
+            .ok_or(String::from("BACKUP_SIGNING_KEY_PATH is not set"))?;
+        let mut signer = Signer::new(MessageDigest::sha256(), private_key)
+            .map_err(|_| String::from("Unable to create signer"))?;
+        signer
+            .update(data.as_bytes())
+            .map_err(|_| String::from("Unable to sign the backup"))?;
+        let signature = signer
+            .sign_to_vec()
+            .map_err(|_| String::from("Unable to sign the backup"))?;
+        Ok(general_purpose::STANDARD.encode(signature))
+    }
+
+    /// check the signature with the key of `key_id`
+    pub fn verify(&self, key_id: &str, data: &str, signature: &str) -> Result<(), String> {
+        let signature = general_purpose::STANDARD
+            .decode(signature)
+            .map_err(|_| String::from("Bad backup signature"))?;
+        let public_key = match self.verifying_keys.iter().find(|(id, _)| id == key_id) {
+            None => return Err(format!("Unknown backup signing key {}", key_id)),
+            Some((_, public_key)) => public_key,
+        };
+        let verified = Verifier::new(MessageDigest::sha256(), public_key)
+            .and_then(|mut verifier| {
+                verifier.update(data.as_bytes())?;
+                verifier.verify(&signature)
+            })
+            .unwrap_or(false);
+        match verified {
+            true => Ok(()),
+            false => Err(String::from("The backup signature is not valid")),
+        }
+    }
+}
@@ -0,0 +1,135 @@
+/// the deepest nesting accepted, the WebAuthn structures are a few levels deep
+const MAX_DEPTH: usize = 16;
+
+/// ### Cbor
+///
+/// a CBOR (RFC 8949) value, only the definite length items WebAuthn uses are decoded
+#[derive(Debug, Clone, PartialEq)]
+pub enum Cbor {
+    Unsigned(u64),
+    Negative(i64),
+    Bytes(Vec<u8>),
+    Text(String),
+    Array(Vec<Cbor>),
+    Map(Vec<(Cbor, Cbor)>),
+    Bool(bool),
+    Null,
+}
+
+impl Cbor {
+    /// decode the first item of `bytes`, with the number of bytes it takes
+    pub fn decode(bytes: &[u8]) -> Result<(Cbor, usize), &'static str> {
+        let mut offset = 0;
+        let value = Self::decode_item(bytes, &mut offset, 0)?;
+        Ok((value, offset))
+    }
+
+    /// the value of an integer or text key of a map
+    pub fn get(&self, key: &Cbor) -> Option<&Cbor> {
+        match self {
+            Cbor::Map(entries) => entries.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    pub fn get_int(&self, key: i64) -> Option<&Cbor> {
+        self.get(&Self::int(key))
+    }
+
+    pub fn get_text(&self, key: &str) -> Option<&Cbor> {
+        self.get(&Cbor::Text(key.to_string()))
+    }
+
+    pub fn int(value: i64) -> Cbor {
+        match value {
+            value if value < 0 => Cbor::Negative(value),
+            value => Cbor::Unsigned(value as u64),
+        }
+    }
+
+    pub fn as_int(&self) -> Option<i64> {
+        match self {
+            Cbor::Unsigned(value) => i64::try_from(*value).ok(),
+            Cbor::Negative(value) => Some(*value),
+            _ => None,
+        }
+    }
+
+    pub fn as_bytes(&self) -> Option<&[u8]> {
+        match self {
+            Cbor::Bytes(bytes) => Some(bytes),
+            _ => None,
+        }
+    }
+
+    fn decode_item(bytes: &[u8], offset: &mut usize, depth: usize) -> Result<Cbor, &'static str> {
+        if depth > MAX_DEPTH {
+            return Err("CBOR nested too deep");
+        }
+        let initial = *bytes.get(*offset).ok_or("Truncated CBOR")?;
+        *offset += 1;
+        let major = initial >> 5;
+        let info = initial & 0x1f;
+        if major == 7 {
+            return match info {
+                20 => Ok(Cbor::Bool(false)),
+                21 => Ok(Cbor::Bool(true)),
+                22 => Ok(Cbor::Null),
+                _ => Err("Unsupported CBOR simple value"),
+            };
+        }
+        let argument = Self::argument(bytes, offset, info)?;
+        match major {
+            0 => Ok(Cbor::Unsigned(argument)),
+            1 => Ok(Cbor::Negative(
+                -1 - i64::try_from(argument).map_err(|_| "CBOR integer out of range")?,
+            )),
+            2 => Ok(Cbor::Bytes(Self::take(bytes, offset, argument)?.to_vec())),
+            3 => String::from_utf8(Self::take(bytes, offset, argument)?.to_vec())
+                .map(Cbor::Text)
+                .map_err(|_| "Bad CBOR text"),
+            4 => {
+                let mut items = Vec::new();
+                for _ in 0..argument {
+                    items.push(Self::decode_item(bytes, offset, depth + 1)?);
+                }
+                Ok(Cbor::Array(items))
+            }
+            5 => {
+                let mut entries = Vec::new();
+                for _ in 0..argument {
+                    let key = Self::decode_item(bytes, offset, depth + 1)?;
+                    let value = Self::decode_item(bytes, offset, depth + 1)?;
+                    entries.push((key, value));
+                }
+                Ok(Cbor::Map(entries))
+            }
+            // a tag is followed by the tagged item, the tag is dropped
+            6 => Self::decode_item(bytes, offset, depth + 1),
+            _ => Err("Unsupported CBOR item"),
+        }
+    }
+
+    /// the argument of an item, its value or its length. The indefinite lengths are refused
+    fn argument(bytes: &[u8], offset: &mut usize, info: u8) -> Result<u64, &'static str> {
+        let size = match info {
+            0..=23 => return Ok(info as u64),
+            24 => 1,
+            25 => 2,
+            26 => 4,
+            27 => 8,
+            _ => return Err("Unsupported CBOR length"),
+        };
+        Ok(Self::take(bytes, offset, size)?
+            .iter()
+            .fold(0u64, |value, byte| (value << 8) | *byte as u64))
+    }
+
+    fn take<'a>(bytes: &'a [u8], offset: &mut usize, length: u64) -> Result<&'a [u8], &'static str> {
+        let length = usize::try_from(length).map_err(|_| "Truncated CBOR")?;
+        let end = offset.checked_add(length).ok_or("Truncated CBOR")?;
+        let slice = bytes.get(*offset..end).ok_or("Truncated CBOR")?;
+        *offset = end;
+        Ok(slice)
+    }
+}
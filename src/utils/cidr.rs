@@ -0,0 +1,63 @@
+use std::net::IpAddr;
+
+/// ### Cidr
+///
+/// IPv4 / IPv6 network ranges (`10.0.0.0/8`, `2001:db8::/32`),
+/// a bare address is a range of one address
+pub struct Cidr {
+    network: IpAddr,
+    prefix: u8,
+}
+
+impl Cidr {
+    pub fn parse(cidr: &str) -> Result<Self, &'static str> {
+        let (address, prefix) = match cidr.trim().split_once('/') {
+            None => (cidr.trim(), None),
+            Some((address, prefix)) => (address, Some(prefix)),
+        };
+        let network: IpAddr = address.parse().map_err(|_| "Bad CIDR range")?;
+        let max_prefix = match network {
+            IpAddr::V4(_) => 32,
+            IpAddr::V6(_) => 128,
+        };
+        let prefix = match prefix {
+            None => max_prefix,
+            Some(prefix) => prefix.parse::<u8>().map_err(|_| "Bad CIDR range")?,
+        };
+        if prefix > max_prefix {
+            return Err("Bad CIDR range");
+        }
+        Ok(Self { network, prefix })
+    }
+
+    /// an IPv4-mapped IPv6 address (`::ffff:10.0.0.1`) matches the IPv4 ranges
+    pub fn contains(&self, ip: &IpAddr) -> bool {
+        match (self.network, ip.to_canonical()) {
+            (IpAddr::V4(network), IpAddr::V4(ip)) => {
+                Self::matches(&network.octets(), &ip.octets(), self.prefix)
+            }
+            (IpAddr::V6(network), IpAddr::V6(ip)) => {
+                Self::matches(&network.octets(), &ip.octets(), self.prefix)
+            }
+            _ => false,
+        }
+    }
+
+    /// whether every address of `other` is in the range
+    pub fn covers(&self, other: &Cidr) -> bool {
+        other.prefix >= self.prefix && self.contains(&other.network)
+    }
+
+    fn matches(network: &[u8], ip: &[u8], prefix: u8) -> bool {
+        let full_bytes = (prefix / 8) as usize;
+        let remaining_bits = prefix % 8;
+        if network[..full_bytes] != ip[..full_bytes] {
+            return false;
+        }
+        if remaining_bits == 0 {
+            return true;
+        }
+        let mask = 0xffu8 << (8 - remaining_bits);
+        network[full_bytes] & mask == ip[full_bytes] & mask
+    }
+}
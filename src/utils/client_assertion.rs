@@ -0,0 +1,80 @@
+use chrono::Utc;
+use jsonwebtoken::{decode, decode_header, Algorithm, DecodingKey, Validation};
+use serde::{Deserialize, Serialize};
+
+use crate::utils::open_id::OpenId;
+
+/// the `client_assertion_type` of the `private_key_jwt` client authentication (RFC 7523)
+pub const CLIENT_ASSERTION_TYPE: &str = "urn:ietf:params:oauth:client-assertion-type:jwt-bearer";
+
+/// the longest an assertion can be valid, in seconds
+const MAX_ASSERTION_LIFETIME: i64 = 300;
+
+/// ### ClientAssertion
+///
+/// the JWT a service account signs with its private key to authenticate
+/// on the token endpoint, `iss` and `sub` are its client id
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ClientAssertion {
+    pub iss: String,
+    pub sub: String,
+    pub exp: i64,
+    pub jti: String,
+}
+
+impl ClientAssertion {
+    /// the audience the assertions must be issued for, the token endpoint
+    pub fn audience() -> String {
+        format!("{}/oauth/token", OpenId::issuer())
+    }
+
+    /// the decoding key of a PEM public key, RSA, EC or Ed25519
+    pub fn decoding_key(public_key: &str, algorithm: Algorithm) -> Option<DecodingKey> {
+        let pem = public_key.as_bytes();
+        match algorithm {
+            Algorithm::RS256
+            | Algorithm::RS384
+            | Algorithm::RS512
+            | Algorithm::PS256
+            | Algorithm::PS384
+            | Algorithm::PS512 => DecodingKey::from_rsa_pem(pem).ok(),
+            Algorithm::ES256 | Algorithm::ES384 => DecodingKey::from_ec_pem(pem).ok(),
+            Algorithm::EdDSA => DecodingKey::from_ed_pem(pem).ok(),
+            _ => None,
+        }
+    }
+
+    /// check that a PEM public key can verify assertions
+    pub fn is_public_key(public_key: &str) -> bool {
+        [Algorithm::RS256, Algorithm::ES256, Algorithm::EdDSA]
+            .into_iter()
+            .any(|algorithm| Self::decoding_key(public_key, algorithm).is_some())
+    }
+
+    /// check the signature, the issuer, the subject, the audience and the lifetime of an assertion
+    pub fn verify(
+        assertion: &str,
+        public_key: &str,
+        client_id: &str,
+        audience: &str,
+    ) -> Result<Self, &'static str> {
+        let header = decode_header(assertion).map_err(|_| "Invalid client assertion")?;
+        let key = Self::decoding_key(public_key, header.alg)
+            .ok_or("The client assertion algorithm does not match the key")?;
+        let mut validation = Validation::new(header.alg);
+        validation.set_audience(&[audience]);
+        validation.set_issuer(&[client_id]);
+        validation.sub = Some(client_id.to_string());
+        validation.set_required_spec_claims(&["exp", "aud", "iss", "sub"]);
+        let assertion = decode::<Self>(assertion, &key, &validation)
+            .map_err(|_| "Invalid client assertion")?
+            .claims;
+        if assertion.exp > Utc::now().timestamp() + MAX_ASSERTION_LIFETIME {
+            return Err("The client assertion lives too long");
+        }
+        if assertion.jti.is_empty() {
+            return Err("The client assertion has no jti");
+        }
+        Ok(assertion)
+    }
+}
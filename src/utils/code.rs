@@ -1,8 +1,11 @@
 extern crate std;
 use base32::Alphabet;
+use base64::{engine::general_purpose, Engine as _};
 use otp_rs::TOTP;
 use rand::Rng;
+use sha2::{Digest, Sha256};
 extern crate chrono;
+use std::env;
 use std::time::{SystemTime, UNIX_EPOCH};
 
 pub fn _generate_code() -> String {
@@ -19,10 +22,11 @@ pub fn _generate_code() -> String {
     password
 }
 
+/// a validation or reset code of 8 characters, about 40 bits, without the look-alike `0`, `O`, `1` and `I`
 pub fn generate_reset_code() -> String {
-    const CHARSET: &[u8] = b"0123456789";
+    const CHARSET: &[u8] = b"ABCDEFGHJKLMNPQRSTUVWXYZ23456789";
     let mut rng = rand::thread_rng();
-    let password: String = (0..6)
+    let password: String = (0..8)
         .map(|_| {
             let idx = rng.gen_range(0..CHARSET.len());
             CHARSET[idx] as char
@@ -31,6 +35,11 @@ pub fn generate_reset_code() -> String {
     password
 }
 
+/// a validation or reset code as typed by a user
+pub fn normalize_reset_code(input: &str) -> String {
+    input.trim().to_uppercase()
+}
+
 pub fn generate_base32_key(length_in_bits: usize) -> String {
     let length_in_bytes = (length_in_bits + 7) / 8;
     let length_in_base32_chars = (length_in_bits + 4) / 5;
@@ -42,12 +51,68 @@ pub fn generate_base32_key(length_in_bits: usize) -> String {
     truncated_base32_key.to_string()
 }
 
-pub fn check_otp(input: u32, secret_key: String) -> bool {
-    let otp = TOTP::from_base32(&secret_key).unwrap();
+/// the seconds a TOTP code lasts
+pub const TOTP_PERIOD: u64 = 30;
+
+/// the recovery codes given when 2FA is activated
+pub const RECOVERY_CODE_COUNT: usize = 10;
+
+/// the steps accepted before and after the current one for the clocks drifting, `TOTP_WINDOW`
+pub fn totp_window() -> u64 {
+    env::var("TOTP_WINDOW")
+        .ok()
+        .and_then(|window| window.parse::<u64>().ok())
+        .unwrap_or(1)
+        .min(10)
+}
+
+/// the step the code was generated for, a malformed secret matches nothing
+pub fn check_otp(input: u32, secret_key: &str) -> Option<u64> {
     let timestamp = SystemTime::now()
         .duration_since(UNIX_EPOCH)
         .unwrap()
         .as_secs();
-    let code = otp.generate(30, timestamp).unwrap();
-    input == code
+    check_otp_at(input, secret_key, totp_window(), timestamp)
+}
+
+pub fn check_otp_at(input: u32, secret_key: &str, window: u64, timestamp: u64) -> Option<u64> {
+    // the base32 decoding of the TOTP crate panics on a malformed secret
+    let secret = base32::decode(Alphabet::Rfc4648 { padding: false }, secret_key)?;
+    let otp = TOTP::from_bytes(&secret);
+    let current = timestamp / TOTP_PERIOD;
+    (current.saturating_sub(window)..=current + window)
+        .find(|step| otp.generate(TOTP_PERIOD, step * TOTP_PERIOD).ok() == Some(input))
+}
+
+/// a recovery code of 80 random bits, `XXXX-XXXX-XXXX-XXXX` in base32
+pub fn generate_recovery_code() -> String {
+    let key = generate_base32_key(80);
+    key.as_bytes()
+        .chunks(4)
+        .map(|group| String::from_utf8_lossy(group).to_string())
+        .collect::<Vec<String>>()
+        .join("-")
+}
+
+/// a recovery code as typed by a user, without its dashes and spaces and in upper case
+pub fn normalize_recovery_code(input: &str) -> String {
+    input
+        .chars()
+        .filter(|c| *c != '-' && !c.is_whitespace())
+        .collect::<String>()
+        .to_uppercase()
+}
+
+/// an opaque url safe token of 256 random bits
+pub fn generate_token() -> String {
+    let mut rng = rand::thread_rng();
+    let random_bytes: Vec<u8> = (0..32).map(|_| rng.gen()).collect();
+    general_purpose::URL_SAFE_NO_PAD.encode(random_bytes)
+}
+
+/// the SHA-256 of a token, hex encoded, the tokens are only stored hashed
+pub fn hash_token(token: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(token.as_bytes());
+    hex::encode(hasher.finalize())
 }
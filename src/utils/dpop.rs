@@ -0,0 +1,217 @@
+use std::env;
+
+use base64::{engine::general_purpose, Engine};
+use chrono::Utc;
+use jsonwebtoken::{decode, Algorithm, DecodingKey, Validation};
+use rocket_okapi::okapi::schemars;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+use crate::redis::RedisClient;
+use crate::utils::open_id::OpenId;
+
+/// the header carrying the proof of the requests
+pub const DPOP_HEADER: &str = "DPoP";
+
+/// the `typ` of the proofs
+pub const DPOP_TYPE: &str = "dpop+jwt";
+
+/// seconds a proof is accepted after its `iat` when `DPOP_PROOF_LIFETIME` is not set
+const DEFAULT_PROOF_LIFETIME: i64 = 300;
+
+/// seconds a proof can be issued ahead of the clock of the API
+const CLOCK_SKEW: i64 = 30;
+
+/// ### Confirmation
+///
+/// the `cnf` claim of a token bound to a DPoP key, `jkt` is the RFC 7638 thumbprint of its public key
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, JsonSchema)]
+pub struct Confirmation {
+    pub jkt: String,
+}
+
+/// the claims of a proof, `ath` is the hash of the access token it comes with
+#[derive(Serialize, Deserialize, Debug)]
+pub struct DpopClaims {
+    pub jti: String,
+    pub htm: String,
+    pub htu: String,
+    pub iat: i64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ath: Option<String>,
+}
+
+/// ### DpopProof
+///
+/// a verified proof, signed by the key of thumbprint `jkt`
+#[derive(Debug)]
+pub struct DpopProof {
+    pub jkt: String,
+    pub claims: DpopClaims,
+}
+
+/// ### Dpop
+///
+/// the proofs of possession of RFC 9449, the JWT a client signs with its private key
+/// for each request, the public key in its header
+pub struct Dpop;
+
+impl Dpop {
+    /// seconds a proof is accepted after its `iat`
+    pub fn proof_lifetime() -> i64 {
+        env::var("DPOP_PROOF_LIFETIME")
+            .ok()
+            .and_then(|lifetime| lifetime.trim().parse::<i64>().ok())
+            .filter(|lifetime| *lifetime > 0)
+            .unwrap_or(DEFAULT_PROOF_LIFETIME)
+    }
+
+    /// the URI a proof must be issued for, the path of the request on `API_URL`
+    pub fn htu(path: &str) -> String {
+        format!("{}{}", OpenId::issuer(), path)
+    }
+
+    /// the `ath` of a proof sent with `access_token`
+    pub fn access_token_hash(access_token: &str) -> String {
+        general_purpose::URL_SAFE_NO_PAD.encode(Sha256::digest(access_token.as_bytes()))
+    }
+
+    /// the RFC 7638 thumbprint of a public JWK, its required members in lexicographic order
+    pub fn thumbprint(jwk: &Value) -> Result<String, &'static str> {
+        let member = |name: &str| {
+            jwk.get(name)
+                .and_then(Value::as_str)
+                .ok_or("The DPoP key is incomplete")
+        };
+        let canonical = match member("kty")? {
+            "EC" => format!(
+                r#"{{"crv":"{}","kty":"EC","x":"{}","y":"{}"}}"#,
+                member("crv")?,
+                member("x")?,
+                member("y")?
+            ),
+            "RSA" => format!(
+                r#"{{"e":"{}","kty":"RSA","n":"{}"}}"#,
+                member("e")?,
+                member("n")?
+            ),
+            _ => return Err("Unsupported DPoP key type"),
+        };
+        Ok(general_purpose::URL_SAFE_NO_PAD.encode(Sha256::digest(canonical.as_bytes())))
+    }
+
+    /// the key checking the signature of a proof, its curve must be the one of the algorithm
+    fn decoding_key(jwk: &Value, algorithm: Algorithm) -> Result<DecodingKey, &'static str> {
+        let member = |name: &str| jwk.get(name).and_then(Value::as_str).unwrap_or_default();
+        if jwk.get("d").is_some() {
+            return Err("The DPoP key must be public");
+        }
+        let key = match (algorithm, member("kty"), member("crv")) {
+            (Algorithm::ES256, "EC", "P-256") | (Algorithm::ES384, "EC", "P-384") => {
+                DecodingKey::from_ec_components(member("x"), member("y"))
+            }
+            (Algorithm::RS256 | Algorithm::RS384 | Algorithm::RS512, "RSA", _)
+            | (Algorithm::PS256 | Algorithm::PS384 | Algorithm::PS512, "RSA", _) => {
+                DecodingKey::from_rsa_components(member("n"), member("e"))
+            }
+            _ => return Err("The DPoP algorithm does not match the key"),
+        };
+        key.map_err(|_| "Invalid DPoP key")
+    }
+
+    /// the proofs are compared to the request without their query and fragment
+    fn same_uri(htu: &str, expected: &str) -> bool {
+        let strip = |uri: &str| {
+            uri.split(['?', '#'])
+                .next()
+                .unwrap_or_default()
+                .trim_end_matches('/')
+                .to_string()
+        };
+        strip(htu) == strip(expected)
+    }
+
+    /// check the header, the signature, the method, the URI and the age of a proof at `now`.
+    /// Sent with an access token, the proof must carry its hash
+    pub fn verify_at(
+        proof: &str,
+        htm: &str,
+        htu: &str,
+        access_token: Option<&str>,
+        now: i64,
+    ) -> Result<DpopProof, &'static str> {
+        let invalid = "Invalid DPoP proof";
+        let header = proof
+            .split('.')
+            .next()
+            .and_then(|header| general_purpose::URL_SAFE_NO_PAD.decode(header).ok())
+            .and_then(|header| serde_json::from_slice::<Value>(&header).ok())
+            .ok_or(invalid)?;
+        if header.get("typ").and_then(Value::as_str) != Some(DPOP_TYPE) {
+            return Err("The DPoP proof must be typed dpop+jwt");
+        }
+        let algorithm = header
+            .get("alg")
+            .cloned()
+            .and_then(|alg| serde_json::from_value::<Algorithm>(alg).ok())
+            .ok_or(invalid)?;
+        let jwk = header.get("jwk").ok_or("The DPoP proof has no key")?;
+        let key = Self::decoding_key(jwk, algorithm)?;
+        let jkt = Self::thumbprint(jwk)?;
+        let mut validation = Validation::new(algorithm);
+        validation.validate_exp = false;
+        validation.validate_aud = false;
+        validation.set_required_spec_claims::<&str>(&[]);
+        let claims = decode::<DpopClaims>(proof, &key, &validation)
+            .map_err(|_| invalid)?
+            .claims;
+        if claims.jti.is_empty() || claims.jti.len() > 256 {
+            return Err(invalid);
+        }
+        if claims.htm != htm || !Self::same_uri(&claims.htu, htu) {
+            return Err("The DPoP proof was issued for another request");
+        }
+        if claims.iat > now + CLOCK_SKEW || claims.iat < now - Self::proof_lifetime() {
+            return Err("The DPoP proof has expired");
+        }
+        if let Some(access_token) = access_token {
+            if claims.ath.as_deref() != Some(Self::access_token_hash(access_token).as_str()) {
+                return Err("The DPoP proof was issued for another access token");
+            }
+        }
+        Ok(DpopProof { jkt, claims })
+    }
+
+    pub fn key(jkt: &str, jti: &str) -> String {
+        format!("dpop_jti:{}:{}", jkt, jti)
+    }
+
+    /// mark a proof used until it expires, false when it already was. A failure of Redis counts as used
+    pub fn claim(proof: &DpopProof) -> bool {
+        let mut conn = RedisClient::get_connection();
+        redis::cmd("SET")
+            .arg(Self::key(&proof.jkt, &proof.claims.jti))
+            .arg(1)
+            .arg("NX")
+            .arg("EX")
+            .arg(Self::proof_lifetime() + CLOCK_SKEW)
+            .query::<Option<String>>(&mut conn)
+            .is_ok_and(|reply| reply.is_some())
+    }
+
+    /// verify a proof now and refuse its replays, the thumbprint of its key is returned
+    pub fn check(
+        proof: &str,
+        htm: &str,
+        htu: &str,
+        access_token: Option<&str>,
+    ) -> Result<String, &'static str> {
+        let proof = Self::verify_at(proof, htm, htu, access_token, Utc::now().timestamp())?;
+        match Self::claim(&proof) {
+            false => Err("The DPoP proof was already used"),
+            true => Ok(proof.jkt),
+        }
+    }
+}
@@ -1,7 +1,11 @@
-use crate::models::{application::Application, user::User};
-use chrono::Utc;
+use crate::enums::key_operation::KeyOperation;
+use crate::models::{application::Application, session::Session, user::User};
+use crate::utils::dpop::Confirmation;
+use crate::utils::key_ring::KeyRing;
+use crate::utils::open_id::Authentication;
+use crate::utils::token_deny_list::TokenDenyList;
+use chrono::{DateTime, Utc};
 use dotenv::dotenv;
-use jsonwebtoken::{encode, Algorithm, EncodingKey, Header};
 use serde::{Deserialize, Serialize};
 use std::env;
 use uuid::Uuid;
@@ -21,6 +25,119 @@ pub struct Jwt {
     pub lastname: String,
     pub email: String,
     pub device_id: Option<String>,
+    /// set on the capability tokens, they are limited to the key operations of their scope
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scope: Option<TokenScope>,
+    /// set on the tokens of the service accounts, they are not bound to a device
+    #[serde(default)]
+    pub is_service_account: bool,
+    /// set on the tokens issued to an OAuth client, they are not bound to a device
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub oauth_client_id: Option<String>,
+    /// the scope granted to the OAuth client
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub oauth_scope: Option<String>,
+    /// when the user authenticated the session, kept across refreshes
+    #[serde(default)]
+    pub auth_time: i64,
+    /// how the user authenticated the session
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub amr: Vec<String>,
+    /// the id of the token, its key in the deny-list once revoked
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub jti: Option<Uuid>,
+    /// the session of the login, shared by the tokens issued from it and its refreshes
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sid: Option<Uuid>,
+    /// set on the tokens bound to a DPoP key, each request proves the possession of its private key
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cnf: Option<Confirmation>,
+}
+
+/// ### TokenScope
+///
+/// the sentinels, directly or through their clusters, and the operations
+/// a capability token is restricted to
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct TokenScope {
+    #[serde(default)]
+    pub sentinels: Vec<Uuid>,
+    #[serde(default)]
+    pub clusters: Vec<Uuid>,
+    pub operations: Vec<String>,
+}
+
+impl TokenScope {
+    /// a scope names at least one sentinel or cluster and one known operation
+    pub fn parse(
+        sentinels: Vec<String>,
+        clusters: Vec<String>,
+        operations: Vec<String>,
+    ) -> Result<Self, &'static str> {
+        let parse_ids = |ids: Vec<String>| {
+            ids.iter()
+                .map(|id| Uuid::parse_str(id.trim()))
+                .collect::<Result<Vec<Uuid>, _>>()
+        };
+        let sentinels = parse_ids(sentinels).map_err(|_| "Bad Sentinel uuid")?;
+        let clusters = parse_ids(clusters).map_err(|_| "Bad Cluster uuid")?;
+        if sentinels.is_empty() && clusters.is_empty() {
+            return Err("The scope must name at least one sentinel or cluster");
+        }
+        if operations.is_empty() {
+            return Err("The scope must allow at least one operation");
+        }
+        let mut scope_operations: Vec<String> = vec![];
+        for operation in operations {
+            match KeyOperation::from_str(&operation) {
+                None => return Err("The operations must be read, encrypt, decrypt or delete"),
+                Some(operation) if !scope_operations.iter().any(|o| o == operation.as_str()) => {
+                    scope_operations.push(operation.as_str().to_string())
+                }
+                Some(_) => {}
+            }
+        }
+        Ok(TokenScope {
+            sentinels,
+            clusters,
+            operations: scope_operations,
+        })
+    }
+
+    /// `sentinel_clusters` are the clusters the sentinel belongs to
+    pub fn allows(
+        &self,
+        sentinel_uuid: &Uuid,
+        sentinel_clusters: &[Uuid],
+        operation: KeyOperation,
+    ) -> bool {
+        self.operations.iter().any(|o| o == operation.as_str())
+            && (self.sentinels.contains(sentinel_uuid)
+                || sentinel_clusters.iter().any(|c| self.clusters.contains(c)))
+    }
+}
+
+/// ### SessionClaims
+///
+/// the claims shared by the access and the refresh tokens of a session
+pub struct SessionClaims<'a> {
+    /// the thumbprint of the DPoP key the tokens are bound to
+    pub jkt: Option<&'a String>,
+    pub authentication: &'a Authentication,
+    pub session: &'a Session,
+}
+
+/// ### ScopedClaims
+///
+/// the claims of a capability token, inherited from the session or the service account minting it
+pub struct ScopedClaims {
+    pub device_sha: Option<String>,
+    pub jkt: Option<String>,
+    pub session_id: Option<Uuid>,
+    pub is_service_account: bool,
+    pub scope: TokenScope,
+    /// the validity of the token in seconds
+    pub duration: i64,
 }
 
 impl Jwt {
@@ -33,15 +150,7 @@ impl Jwt {
         dotenv().ok();
         let now = Utc::now().timestamp();
         let expiration = match is_refresh {
-            true => Utc::now()
-                .checked_add_signed(chrono::Duration::days(
-                    env::var("JWT_REFRESH_TOKEN_DURATION")
-                        .unwrap()
-                        .parse::<i64>()
-                        .unwrap(),
-                ))
-                .expect("valid timestamp")
-                .timestamp(),
+            true => Self::refresh_expiration().timestamp(),
             _ => Utc::now()
                 .checked_add_signed(chrono::Duration::seconds(
                     env::var("JWT_TOKEN_DURATION")
@@ -67,17 +176,118 @@ impl Jwt {
             lastname: data.lastname.to_string(),
             email: data.email.to_string(),
             device_id: device_sha,
+            scope: None,
+            is_service_account: false,
+            oauth_client_id: None,
+            oauth_scope: None,
+            auth_time: now,
+            amr: vec![],
+            jti: Some(Uuid::new_v4()),
+            sid: None,
+            cnf: None,
         }
     }
 
+    /// the lifetime of a refresh token, `JWT_REFRESH_TOKEN_DURATION` days
+    pub fn refresh_duration() -> chrono::Duration {
+        dotenv().ok();
+        chrono::Duration::days(
+            env::var("JWT_REFRESH_TOKEN_DURATION")
+                .unwrap()
+                .parse::<i64>()
+                .unwrap(),
+        )
+    }
+
+    /// the expiration of a refresh token issued now
+    pub fn refresh_expiration() -> DateTime<Utc> {
+        Utc::now()
+            .checked_add_signed(Self::refresh_duration())
+            .expect("valid timestamp")
+    }
+
+    /// a token of the session of `claims`, the refresh token is the latest one of the session.
+    /// Both are bound to the DPoP key of thumbprint `jkt`
     pub async fn create_jwt(
         data: &User,
         is_refresh: bool,
         application: &Application,
         device_sha: Option<String>,
+        claims: &SessionClaims<'_>,
     ) -> String {
-        let data = Jwt::new(data, application, is_refresh, device_sha).await;
-        let key = EncodingKey::from_rsa_pem(include_bytes!("../../ssh_keys/private.key")).unwrap();
-        encode(&Header::new(Algorithm::RS256), &data, &key).unwrap()
+        let mut data = Jwt::new(data, application, is_refresh, device_sha).await;
+        data.cnf = claims.jkt.map(|jkt| Confirmation { jkt: jkt.clone() });
+        data.auth_time = claims.authentication.auth_time;
+        data.amr = claims.authentication.amr.clone();
+        data.sid = Some(claims.session.id);
+        if is_refresh {
+            data.jti = Some(claims.session.refresh_jti);
+            data.exp = claims.session.expires_at.timestamp();
+        }
+        KeyRing::encode(&data)
+    }
+
+    /// a short lived access token restricted to `scope`, bound to the same device, DPoP key and session
+    /// as the session minting it or to the same service account
+    pub async fn create_scoped_jwt(
+        data: &User,
+        application: &Application,
+        claims: ScopedClaims,
+    ) -> (String, i64) {
+        let mut data = Jwt::new(data, application, false, claims.device_sha).await;
+        data.exp = data.iat + claims.duration;
+        data.scope = Some(claims.scope);
+        data.cnf = claims.jkt.map(|jkt| Confirmation { jkt });
+        data.is_service_account = claims.is_service_account;
+        data.sid = claims.session_id;
+        (KeyRing::encode(&data), data.exp)
+    }
+
+    /// the access token of a service account, obtained with the client credentials grant
+    pub async fn create_service_account_jwt(
+        data: &User,
+        application: &Application,
+        duration: i64,
+    ) -> String {
+        let mut data = Jwt::new(data, application, false, None).await;
+        data.exp = data.iat + duration;
+        data.is_service_account = true;
+        KeyRing::encode(&data)
+    }
+
+    /// the access token of a user issued to an OAuth client with the authorization code grant
+    pub async fn create_oauth_jwt(
+        data: &User,
+        application: &Application,
+        oauth_client_id: &str,
+        oauth_scope: Option<String>,
+        authentication: &Authentication,
+        duration: i64,
+    ) -> String {
+        let mut data = Jwt::new(data, application, false, None).await;
+        data.exp = data.iat + duration;
+        data.oauth_client_id = Some(oauth_client_id.to_string());
+        data.oauth_scope = oauth_scope;
+        data.auth_time = authentication.auth_time;
+        data.amr = authentication.amr.clone();
+        KeyRing::encode(&data)
+    }
+
+    /// a revoked token or a token of a revoked session, the tokens issued before the deny-list
+    /// have no `jti` and are only revoked when they are refresh tokens, by the list of revoked refresh tokens
+    pub fn is_revoked(&self) -> bool {
+        self.jti
+            .is_some_and(|jti| TokenDenyList::is_revoked(&jti, self.sid.as_ref()))
+    }
+
+    /// the authentication of the session, the tokens issued before `auth_time` fall back to their issue time
+    pub fn authentication(&self) -> Authentication {
+        Authentication {
+            auth_time: match self.auth_time {
+                0 => self.iat,
+                auth_time => auth_time,
+            },
+            amr: self.amr.clone(),
+        }
     }
 }
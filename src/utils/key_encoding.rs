@@ -0,0 +1,136 @@
+use base64::{engine::general_purpose, Engine as _};
+use openssl::aes::{wrap_key, AesKey};
+
+use crate::enums::key_format::KeyFormat;
+
+/// DER encoded OIDs of the ML-KEM parameter sets (NIST, 2.16.840.1.101.3.4.4.x)
+const OID_ML_KEM_512: [u8; 9] = [0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x04, 0x01];
+const OID_ML_KEM_768: [u8; 9] = [0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x04, 0x02];
+const OID_ML_KEM_1024: [u8; 9] = [0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x04, 0x03];
+
+/// ### KeyEncoding
+///
+/// the standard encodings offered when a key is exported
+/// - raw base64
+/// - RFC 3394 AES key wrap under a caller supplied key-encryption key
+/// - PEM / SPKI for the ML-KEM public keys
+pub struct KeyEncoding;
+
+impl KeyEncoding {
+    /// Parses the requested export format of a key (`hex` when none is given)
+    /// and checks the key-encryption key needed by the `wrapped` format,
+    /// before the key is rebuilt from the nodes.
+    pub fn export_format(
+        format: Option<&str>,
+        key_encryption_key: &Option<String>,
+    ) -> Result<KeyFormat, &'static str> {
+        let format = match format {
+            None => KeyFormat::Hex,
+            Some(format) => KeyFormat::from_str(format).ok_or("Unknown key format")?,
+        };
+        if let KeyFormat::Wrapped = format {
+            match key_encryption_key {
+                None => return Err("The wrapped format requires a key-encryption key"),
+                Some(kek) => {
+                    Self::decode_key_encryption_key(kek)?;
+                }
+            }
+        }
+        Ok(format)
+    }
+
+    /// Encodes a hex key as standard base64.
+    pub fn to_base64(key: &str) -> Result<String, &'static str> {
+        let bytes = hex::decode(key).map_err(|_| "Bad key encoding")?;
+        Ok(general_purpose::STANDARD.encode(bytes))
+    }
+
+    /// Encodes a hex key as base64url without padding, as used in the JWK.
+    pub fn to_base64_url(key: &str) -> Result<String, &'static str> {
+        let bytes = hex::decode(key).map_err(|_| "Bad key encoding")?;
+        Ok(general_purpose::URL_SAFE_NO_PAD.encode(bytes))
+    }
+
+    /// Wraps a hex key with the RFC 3394 AES key wrap.
+    ///
+    /// # Arguments
+    ///
+    /// * `key` - The hex key to wrap, its length must be a multiple of 8 bytes.
+    /// * `key_encryption_key` - The hex AES-128, AES-192 or AES-256 key-encryption key.
+    ///
+    /// # Returns
+    ///
+    /// The wrapped key encoded in base64.
+    pub fn wrap(key: &str, key_encryption_key: &str) -> Result<String, &'static str> {
+        let key = hex::decode(key).map_err(|_| "Bad key encoding")?;
+        let kek = Self::decode_key_encryption_key(key_encryption_key)?;
+        if key.len() < 16 || key.len() % 8 != 0 {
+            return Err("The key can not be wrapped with RFC 3394");
+        }
+        let aes_key = AesKey::new_encrypt(&kek).map_err(|_| "Bad key-encryption key")?;
+        let mut wrapped = vec![0u8; key.len() + 8];
+        wrap_key(&aes_key, None, &mut wrapped, &key).map_err(|_| "Failed to wrap the key")?;
+        Ok(general_purpose::STANDARD.encode(wrapped))
+    }
+
+    /// Decodes a hex key-encryption key, it must be an AES-128, AES-192 or AES-256 key.
+    pub fn decode_key_encryption_key(key_encryption_key: &str) -> Result<Vec<u8>, &'static str> {
+        match hex::decode(key_encryption_key) {
+            Ok(kek) if [16, 24, 32].contains(&kek.len()) => Ok(kek),
+            _ => Err("The key-encryption key must be a hex AES-128, AES-192 or AES-256 key"),
+        }
+    }
+
+    /// Encodes an ML-KEM public key as a PEM SubjectPublicKeyInfo.
+    ///
+    /// # Arguments
+    ///
+    /// * `public_key` - The hex encapsulation key.
+    /// * `parameter_set` - The ML-KEM parameter set (512, 768 or 1024).
+    pub fn ml_kem_public_pem(public_key: &str, parameter_set: i32) -> Result<String, &'static str> {
+        let oid = match parameter_set {
+            512 => OID_ML_KEM_512,
+            768 => OID_ML_KEM_768,
+            1024 => OID_ML_KEM_1024,
+            _ => return Err("Unknown ML-KEM parameter set"),
+        };
+        let public_key = hex::decode(public_key).map_err(|_| "Bad key encoding")?;
+        let algorithm = Self::der(0x30, &Self::der(0x06, &oid));
+        let mut bit_string = vec![0u8];
+        bit_string.extend_from_slice(&public_key);
+        let mut spki = algorithm;
+        spki.extend(Self::der(0x03, &bit_string));
+        let spki = Self::der(0x30, &spki);
+
+        let encoded = general_purpose::STANDARD.encode(spki);
+        let lines: Vec<&str> = encoded
+            .as_bytes()
+            .chunks(64)
+            .map(|line| std::str::from_utf8(line).unwrap_or_default())
+            .collect();
+        Ok(format!(
+            "-----BEGIN PUBLIC KEY-----\n{}\n-----END PUBLIC KEY-----\n",
+            lines.join("\n")
+        ))
+    }
+
+    /// a DER tag-length-value
+    fn der(tag: u8, content: &[u8]) -> Vec<u8> {
+        let mut res = vec![tag];
+        let len = content.len();
+        match len < 0x80 {
+            true => res.push(len as u8),
+            false => {
+                let bytes: Vec<u8> = len
+                    .to_be_bytes()
+                    .into_iter()
+                    .skip_while(|byte| *byte == 0)
+                    .collect();
+                res.push(0x80 | bytes.len() as u8);
+                res.extend(bytes);
+            }
+        }
+        res.extend_from_slice(content);
+        res
+    }
+}
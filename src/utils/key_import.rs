@@ -0,0 +1,139 @@
+use aes_gcm::{
+    aead::{Aead, AeadCore, KeyInit, OsRng},
+    Aes256Gcm, Key, Nonce,
+};
+use ml_kem::{Ciphertext, Decapsulate, Encapsulate, EncodedSizeUser, KemCore, MlKem768};
+use openssl::{md::Md, pkey::PKey, pkey_ctx::PkeyCtx, rsa::Padding, rsa::Rsa};
+
+/// ### KeyImport
+///
+/// the import key pairs published by the server and the unwrapping
+/// of the customer keys sent with them
+/// - `RSA-OAEP-256`: the key is encrypted with RSA-OAEP (SHA-256, MGF1 SHA-256)
+/// - `ML-KEM-768`: the customer encapsulates a shared secret with the public key
+///   and encrypts the key with AES-256-GCM under this shared secret
+pub struct KeyImport;
+
+const RSA_IMPORT_KEY_BITS: u32 = 3072;
+
+impl KeyImport {
+    /// Generates an RSA import key pair.
+    ///
+    /// # Returns
+    ///
+    /// The (public, private) keys PEM encoded.
+    pub fn generate_rsa_key_pair() -> (String, String) {
+        let rsa = Rsa::generate(RSA_IMPORT_KEY_BITS).expect("Failed to generate RSA key");
+        let public = rsa
+            .public_key_to_pem()
+            .expect("Failed to encode RSA public key");
+        let private = rsa
+            .private_key_to_pem()
+            .expect("Failed to encode RSA private key");
+        (
+            String::from_utf8_lossy(&public).to_string(),
+            String::from_utf8_lossy(&private).to_string(),
+        )
+    }
+
+    /// Generates an ML-KEM-768 import key pair.
+    ///
+    /// # Returns
+    ///
+    /// The (encapsulation, decapsulation) keys hex encoded.
+    pub fn generate_ml_kem_768_key_pair() -> (String, String) {
+        let mut rng = rand::thread_rng();
+        let (dk, ek) = MlKem768::generate(&mut rng);
+        (
+            hex::encode(ek.as_bytes().as_slice()),
+            hex::encode(dk.as_bytes().as_slice()),
+        )
+    }
+
+    /// Decrypts a key wrapped with RSA-OAEP-256.
+    ///
+    /// # Returns
+    ///
+    /// The clear key, or `None` if the unwrapping fails.
+    pub fn unwrap_rsa_oaep(private_key: &str, wrapped_key: &[u8]) -> Option<Vec<u8>> {
+        let private_key = PKey::private_key_from_pem(private_key.as_bytes()).ok()?;
+        let mut ctx = PkeyCtx::new(&private_key).ok()?;
+        ctx.decrypt_init().ok()?;
+        ctx.set_rsa_padding(Padding::PKCS1_OAEP).ok()?;
+        ctx.set_rsa_oaep_md(Md::sha256()).ok()?;
+        ctx.set_rsa_mgf1_md(Md::sha256()).ok()?;
+        let mut key = vec![];
+        ctx.decrypt_to_vec(wrapped_key, &mut key).ok()?;
+        Some(key)
+    }
+
+    /// Decapsulates the shared secret with the ML-KEM-768 private key
+    /// and decrypts the key with AES-256-GCM.
+    ///
+    /// # Returns
+    ///
+    /// The clear key, or `None` if the unwrapping fails.
+    pub fn unwrap_ml_kem_768(
+        private_key: &str,
+        encapsulated_key: &[u8],
+        nonce: &[u8],
+        wrapped_key: &[u8],
+    ) -> Option<Vec<u8>> {
+        let private_key = hex::decode(private_key).ok()?;
+        let encoded =
+            ml_kem::Encoded::<<MlKem768 as KemCore>::DecapsulationKey>::try_from(private_key.as_slice())
+                .ok()?;
+        let dk = <MlKem768 as KemCore>::DecapsulationKey::from_bytes(&encoded);
+        let ciphertext = Ciphertext::<MlKem768>::try_from(encapsulated_key).ok()?;
+        let shared_secret = dk.decapsulate(&ciphertext).ok()?;
+        if nonce.len() != 12 {
+            return None;
+        }
+        let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(shared_secret.as_slice()));
+        cipher.decrypt(Nonce::from_slice(nonce), wrapped_key).ok()
+    }
+
+    /// Encapsulates a shared secret to the ML-KEM-768 public key
+    /// and encrypts the key with AES-256-GCM, the inverse of `unwrap_ml_kem_768`.
+    ///
+    /// # Returns
+    ///
+    /// The (encapsulated key, nonce, wrapped key), or `None` if the public key is invalid.
+    pub fn wrap_ml_kem_768(public_key: &str, key: &[u8]) -> Option<(Vec<u8>, Vec<u8>, Vec<u8>)> {
+        let public_key = hex::decode(public_key).ok()?;
+        let encoded =
+            ml_kem::Encoded::<<MlKem768 as KemCore>::EncapsulationKey>::try_from(public_key.as_slice())
+                .ok()?;
+        let ek = <MlKem768 as KemCore>::EncapsulationKey::from_bytes(&encoded);
+        let mut rng = rand::thread_rng();
+        let (ciphertext, shared_secret) = ek.encapsulate(&mut rng).ok()?;
+        let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(shared_secret.as_slice()));
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let wrapped_key = cipher.encrypt(&nonce, key).ok()?;
+        Some((ciphertext.as_slice().to_vec(), nonce.to_vec(), wrapped_key))
+    }
+
+    /// The size in bits of an imported AES key.
+    ///
+    /// # Returns
+    ///
+    /// `128` or `256`, or `None` for the other key sizes.
+    pub fn key_size(key: &[u8]) -> Option<i32> {
+        match key.len() {
+            16 => Some(128),
+            32 => Some(256),
+            _ => None,
+        }
+    }
+
+    /// Checks that a hex string is an ML-KEM-768 public key.
+    pub fn is_ml_kem_768_public_key(public_key: &str) -> bool {
+        hex::decode(public_key)
+            .ok()
+            .map(|public_key| {
+                ml_kem::Encoded::<<MlKem768 as KemCore>::EncapsulationKey>::try_from(public_key.as_slice())
+                    .is_ok()
+            })
+            .unwrap_or(false)
+    }
+}
@@ -0,0 +1,154 @@
+use std::{cmp::Reverse, sync::RwLock};
+
+use base64::{engine::general_purpose, Engine as _};
+use chrono::{DateTime, Utc};
+use jsonwebtoken::{
+    decode, decode_header, encode, errors::ErrorKind, Algorithm, DecodingKey, EncodingKey,
+    Header, TokenData, Validation,
+};
+use once_cell::sync::Lazy;
+use openssl::rsa::Rsa;
+use serde::{de::DeserializeOwned, Serialize};
+use sha2::{Digest, Sha256};
+
+/// size in bits of the generated RSA keys
+const KEY_SIZE: u32 = 2048;
+
+/// the keys trusted by this node, the latest activation first
+static KEY_RING: Lazy<RwLock<Vec<RingKey>>> = Lazy::new(|| RwLock::new(Vec::new()));
+
+/// ### RingKey
+///
+/// a trusted key, only the keys which sign now or will sign later keep their private part
+#[derive(Clone)]
+pub struct RingKey {
+    pub kid: String,
+    /// the modulus, url safe base64
+    pub n: String,
+    /// the exponent, url safe base64
+    pub e: String,
+    pub activates_at: DateTime<Utc>,
+    decoding_key: DecodingKey,
+    encoding_key: Option<EncodingKey>,
+}
+
+impl RingKey {
+    pub fn new(
+        public_key: &str,
+        private_key: Option<&str>,
+        activates_at: DateTime<Utc>,
+    ) -> Result<Self, &'static str> {
+        let rsa = Rsa::public_key_from_pem(public_key.as_bytes()).map_err(|_| "Bad public key")?;
+        let n = general_purpose::URL_SAFE_NO_PAD.encode(rsa.n().to_vec());
+        let e = general_purpose::URL_SAFE_NO_PAD.encode(rsa.e().to_vec());
+        let decoding_key =
+            DecodingKey::from_rsa_pem(public_key.as_bytes()).map_err(|_| "Bad public key")?;
+        let encoding_key = match private_key {
+            None => None,
+            Some(private_key) => Some(
+                EncodingKey::from_rsa_pem(private_key.as_bytes())
+                    .map_err(|_| "Bad private key")?,
+            ),
+        };
+        Ok(RingKey {
+            kid: KeyRing::thumbprint(&n, &e),
+            n,
+            e,
+            activates_at,
+            decoding_key,
+            encoding_key,
+        })
+    }
+
+    pub fn can_sign(&self) -> bool {
+        self.encoding_key.is_some()
+    }
+}
+
+/// ### KeyRing
+///
+/// the RS256 keys signing and checking the tokens. The tokens carry the `kid` of their key,
+/// every trusted key checks tokens so a rotation does not invalidate the tokens already issued
+pub struct KeyRing;
+
+impl KeyRing {
+    /// replace the keys of the ring
+    pub fn install(mut keys: Vec<RingKey>) {
+        keys.sort_by_key(|key| Reverse(key.activates_at));
+        *KEY_RING.write().unwrap() = keys;
+    }
+
+    pub fn keys() -> Vec<RingKey> {
+        KEY_RING.read().unwrap().clone()
+    }
+
+    /// the `kid` of the key signing now, the latest one activated
+    pub fn signing_kid() -> Option<String> {
+        let now = Utc::now();
+        KEY_RING
+            .read()
+            .unwrap()
+            .iter()
+            .find(|key| key.activates_at <= now)
+            .map(|key| key.kid.clone())
+    }
+
+    /// sign the claims with the key signing now
+    ///
+    /// # Panics
+    ///
+    /// Panics if the ring has no active key with its private part, the ring is loaded at launch
+    pub fn encode<T: Serialize>(claims: &T) -> String {
+        let now = Utc::now();
+        let ring = KEY_RING.read().unwrap();
+        let key = ring
+            .iter()
+            .find(|key| key.activates_at <= now)
+            .expect("the key ring has no active signing key");
+        let encoding_key = key
+            .encoding_key
+            .as_ref()
+            .expect("the active signing key has no private key");
+        let mut header = Header::new(Algorithm::RS256);
+        header.kid = Some(key.kid.clone());
+        encode(&header, claims, encoding_key).unwrap()
+    }
+
+    /// check a token with the key named by its `kid`, the tokens issued before the key ring
+    /// carry no `kid` and are checked against every trusted key
+    pub fn decode<T: DeserializeOwned>(
+        token: &str,
+        validation: &Validation,
+    ) -> Result<TokenData<T>, jsonwebtoken::errors::Error> {
+        let header = decode_header(token)?;
+        let ring = KEY_RING.read().unwrap();
+        let mut result = Err(ErrorKind::InvalidSignature.into());
+        for key in ring
+            .iter()
+            .filter(|key| header.kid.as_ref().is_none_or(|kid| kid == &key.kid))
+        {
+            result = decode::<T>(token, &key.decoding_key, validation);
+            if !matches!(&result, Err(error) if *error.kind() == ErrorKind::InvalidSignature) {
+                break;
+            }
+        }
+        result
+    }
+
+    /// the RFC 7638 thumbprint of a RSA public key
+    pub fn thumbprint(n: &str, e: &str) -> String {
+        let jwk = format!(r#"{{"e":"{}","kty":"RSA","n":"{}"}}"#, e, n);
+        general_purpose::URL_SAFE_NO_PAD.encode(Sha256::digest(jwk.as_bytes()))
+    }
+
+    /// a new RSA key pair, the PEM of the public and of the private key
+    pub fn generate() -> (String, String) {
+        let rsa = Rsa::generate(KEY_SIZE).expect("Unable to generate a RSA key");
+        let public_key = rsa.public_key_to_pem().expect("Unable to export the public key");
+        let private_key = rsa.private_key_to_pem().expect("Unable to export the private key");
+        (
+            String::from_utf8(public_key).unwrap(),
+            String::from_utf8(private_key).unwrap(),
+        )
+    }
+}
@@ -0,0 +1,30 @@
+use openssl::{memcmp, pkey::PKey, sign::Signer};
+
+use crate::enums::mac_algorithm::MacAlgorithm;
+
+/// ### Mac
+///
+/// HMAC-SHA256 / HMAC-SHA512 computed with a sentinel key,
+/// the key never leaves the server
+pub struct Mac;
+
+impl Mac {
+    /// Computes the HMAC of a payload.
+    pub fn sign(algorithm: &MacAlgorithm, key: &[u8], payload: &[u8]) -> Result<Vec<u8>, &'static str> {
+        let pkey = PKey::hmac(key).map_err(|_| "Bad MAC key")?;
+        let mut signer = Signer::new(algorithm.digest(), &pkey).map_err(|_| "Unable to create signer")?;
+        signer.update(payload).map_err(|_| "Unable to compute the MAC")?;
+        signer.sign_to_vec().map_err(|_| "Unable to compute the MAC")
+    }
+
+    /// Checks the HMAC of a payload in constant time.
+    pub fn verify(
+        algorithm: &MacAlgorithm,
+        key: &[u8],
+        payload: &[u8],
+        mac: &[u8],
+    ) -> Result<bool, &'static str> {
+        let expected = Self::sign(algorithm, key, payload)?;
+        Ok(expected.len() == mac.len() && memcmp::eq(&expected, mac))
+    }
+}
@@ -0,0 +1,274 @@
+use std::env;
+
+use base64::{engine::general_purpose, Engine};
+use openssl::{
+    bn::BigNum,
+    ecdsa::EcdsaSig,
+    hash::MessageDigest,
+    pkey::{PKey, Public},
+    rsa::Padding,
+    sign::{RsaPssSaltlen, Verifier},
+};
+use sha2::{Digest, Sha256, Sha512};
+
+use crate::enums::signature_algorithm::SignatureAlgorithm;
+use crate::redis::RedisClient;
+
+/// the headers of the signed requests
+pub const SIGNATURE_INPUT_HEADER: &str = "Signature-Input";
+pub const SIGNATURE_HEADER: &str = "Signature";
+pub const CONTENT_DIGEST_HEADER: &str = "Content-Digest";
+
+/// seconds a signature is accepted after its `Date` when `SIGNATURE_MAX_AGE` is not set
+const DEFAULT_MAX_AGE: i64 = 300;
+
+/// ### SignatureInput
+///
+/// a member of the `Signature-Input` header, the components a signature covers and its parameters
+#[derive(Debug, Clone, PartialEq)]
+pub struct SignatureInput {
+    pub label: String,
+    pub components: Vec<String>,
+    pub created: Option<i64>,
+    pub expires: Option<i64>,
+    pub keyid: Option<String>,
+    pub alg: Option<String>,
+    /// the inner list and its parameters as sent, the value of `@signature-params`
+    pub params: String,
+}
+
+/// ### MessageSignature
+///
+/// the HTTP message signatures of RFC 9421 and the content digests of RFC 9530
+pub struct MessageSignature;
+
+impl MessageSignature {
+    /// seconds a signature is accepted after its `Date`, and before for the clocks drifting
+    pub fn max_age() -> i64 {
+        env::var("SIGNATURE_MAX_AGE")
+            .ok()
+            .and_then(|max_age| max_age.trim().parse::<i64>().ok())
+            .filter(|max_age| *max_age > 0)
+            .unwrap_or(DEFAULT_MAX_AGE)
+    }
+
+    /// split a structured field (RFC 8941) on `separator`, out of the strings and the inner lists
+    fn split(field: &str, separator: char) -> Vec<&str> {
+        let mut parts = vec![];
+        let (mut start, mut depth, mut quoted, mut escaped) = (0, 0, false, false);
+        for (index, c) in field.char_indices() {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' if quoted => escaped = true,
+                '"' => quoted = !quoted,
+                '(' if !quoted => depth += 1,
+                ')' if !quoted => depth -= 1,
+                _ if c == separator && !quoted && depth == 0 => {
+                    parts.push(field[start..index].trim());
+                    start = index + c.len_utf8();
+                }
+                _ => {}
+            }
+        }
+        parts.push(field[start..].trim());
+        parts
+    }
+
+    /// the members of a dictionary, its keys with their raw values
+    pub fn dictionary(field: &str) -> Result<Vec<(String, String)>, &'static str> {
+        Self::split(field, ',')
+            .into_iter()
+            .filter(|member| !member.is_empty())
+            .map(|member| match member.split_once('=') {
+                Some((key, value)) if !key.trim().is_empty() => {
+                    Ok((key.trim().to_string(), value.trim().to_string()))
+                }
+                _ => Err("Bad structured field"),
+            })
+            .collect()
+    }
+
+    fn unquote(value: &str) -> Result<String, &'static str> {
+        value
+            .strip_prefix('"')
+            .and_then(|value| value.strip_suffix('"'))
+            .map(|value| value.replace("\\\"", "\"").replace("\\\\", "\\"))
+            .ok_or("Bad structured field")
+    }
+
+    /// the members of a `Signature-Input` header
+    pub fn parse_input(field: &str) -> Result<Vec<SignatureInput>, &'static str> {
+        let bad_input = "Bad Signature-Input";
+        let mut inputs = vec![];
+        for (label, value) in Self::dictionary(field).map_err(|_| bad_input)? {
+            let mut parts = Self::split(&value, ';').into_iter();
+            let list = parts
+                .next()
+                .and_then(|list| list.strip_prefix('('))
+                .and_then(|list| list.strip_suffix(')'))
+                .ok_or(bad_input)?;
+            let components = list
+                .split_whitespace()
+                .map(Self::unquote)
+                .collect::<Result<Vec<String>, _>>()
+                .map_err(|_| bad_input)?;
+            let mut input = SignatureInput {
+                label,
+                components,
+                created: None,
+                expires: None,
+                keyid: None,
+                alg: None,
+                params: value.clone(),
+            };
+            for parameter in parts {
+                let (name, value) = parameter.split_once('=').ok_or(bad_input)?;
+                match name.trim() {
+                    "created" => input.created = Some(value.parse().map_err(|_| bad_input)?),
+                    "expires" => input.expires = Some(value.parse().map_err(|_| bad_input)?),
+                    "keyid" => input.keyid = Some(Self::unquote(value)?),
+                    "alg" => input.alg = Some(Self::unquote(value)?),
+                    _ => {}
+                }
+            }
+            inputs.push(input);
+        }
+        Ok(inputs)
+    }
+
+    /// the signature of `label` in a `Signature` header
+    pub fn parse_signature(field: &str, label: &str) -> Result<Vec<u8>, &'static str> {
+        let bad_signature = "Bad Signature";
+        let (_, value) = Self::dictionary(field)
+            .map_err(|_| bad_signature)?
+            .into_iter()
+            .find(|(key, _)| key == label)
+            .ok_or("No signature for the Signature-Input")?;
+        value
+            .strip_prefix(':')
+            .and_then(|value| value.strip_suffix(':'))
+            .and_then(|value| general_purpose::STANDARD.decode(value).ok())
+            .ok_or(bad_signature)
+    }
+
+    /// the signature base (RFC 9421 section 2.5), `value` gives the value of each covered component
+    pub fn signature_base(
+        input: &SignatureInput,
+        value: impl Fn(&str) -> Option<String>,
+    ) -> Result<String, &'static str> {
+        let mut lines = vec![];
+        for (index, component) in input.components.iter().enumerate() {
+            if input.components[..index].contains(component) {
+                return Err("A component is covered twice");
+            }
+            let component_value = value(component).ok_or("A covered component is missing")?;
+            if component_value.contains('\n') {
+                return Err("Bad component value");
+            }
+            lines.push(format!("\"{}\": {}", component, component_value));
+        }
+        lines.push(format!("\"@signature-params\": {}", input.params));
+        Ok(lines.join("\n"))
+    }
+
+    /// a PEM public key of a supported algorithm
+    pub fn public_key(pem: &str) -> Option<PKey<Public>> {
+        PKey::public_key_from_pem(pem.as_bytes())
+            .ok()
+            .filter(|key| SignatureAlgorithm::of_key(key).is_some())
+    }
+
+    /// check a signature of `base` by `key`, with the algorithm it names or the one of the key
+    pub fn verify(
+        key: &PKey<Public>,
+        algorithm: Option<&str>,
+        base: &[u8],
+        signature: &[u8],
+    ) -> Result<(), &'static str> {
+        let algorithm = match algorithm {
+            Some(algorithm) => SignatureAlgorithm::from_str(algorithm)
+                .filter(|algorithm| algorithm.fits(key))
+                .ok_or("The signature algorithm does not match the key")?,
+            None => SignatureAlgorithm::of_key(key).ok_or("Unsupported signing key")?,
+        };
+        let signature = match algorithm {
+            // the signature is the concatenation of r and s, openssl checks their DER
+            SignatureAlgorithm::EcdsaP256Sha256 | SignatureAlgorithm::EcdsaP384Sha384 => {
+                Self::ecdsa_der(signature).ok_or("Bad Signature")?
+            }
+            _ => signature.to_vec(),
+        };
+        let verifier = match algorithm.digest() {
+            None => Verifier::new_without_digest(key),
+            Some(digest) => Verifier::new(digest, key),
+        };
+        let verified = verifier.and_then(|mut verifier| {
+            if algorithm == SignatureAlgorithm::RsaPssSha512 {
+                verifier.set_rsa_padding(Padding::PKCS1_PSS)?;
+                verifier.set_rsa_pss_saltlen(RsaPssSaltlen::DIGEST_LENGTH)?;
+                verifier.set_rsa_mgf1_md(MessageDigest::sha512())?;
+            }
+            verifier.verify_oneshot(&signature, base)
+        });
+        match verified {
+            Ok(true) => Ok(()),
+            _ => Err("Bad Signature"),
+        }
+    }
+
+    fn ecdsa_der(signature: &[u8]) -> Option<Vec<u8>> {
+        if signature.is_empty() || !signature.len().is_multiple_of(2) {
+            return None;
+        }
+        let (r, s) = signature.split_at(signature.len() / 2);
+        let r = BigNum::from_slice(r).ok()?;
+        let s = BigNum::from_slice(s).ok()?;
+        EcdsaSig::from_private_components(r, s).ok()?.to_der().ok()
+    }
+
+    /// whether a `Content-Digest` header matches the body, every known digest must match and one at least
+    pub fn digest_matches(field: &str, body: &[u8]) -> bool {
+        let digests = match Self::dictionary(field) {
+            Err(_) => return false,
+            Ok(digests) => digests,
+        };
+        let mut matched = false;
+        for (algorithm, value) in digests {
+            let expected = match algorithm.as_str() {
+                "sha-256" => Sha256::digest(body).to_vec(),
+                "sha-512" => Sha512::digest(body).to_vec(),
+                _ => continue,
+            };
+            let digest = value
+                .strip_prefix(':')
+                .and_then(|value| value.strip_suffix(':'))
+                .and_then(|value| general_purpose::STANDARD.decode(value).ok());
+            match digest {
+                Some(digest)
+                    if digest.len() == expected.len() && openssl::memcmp::eq(&digest, &expected) =>
+                {
+                    matched = true
+                }
+                _ => return false,
+            }
+        }
+        matched
+    }
+
+    pub fn key(keyid: &str, signature: &[u8]) -> String {
+        format!("http_signature:{}:{}", keyid, hex::encode(Sha256::digest(signature)))
+    }
+
+    /// mark a signature used until it expires, false when it already was. A failure of Redis counts as used
+    pub fn claim(keyid: &str, signature: &[u8]) -> bool {
+        let mut conn = RedisClient::get_connection();
+        redis::cmd("SET")
+            .arg(Self::key(keyid, signature))
+            .arg(1)
+            .arg("NX")
+            .arg("EX")
+            .arg(2 * Self::max_age())
+            .query::<Option<String>>(&mut conn)
+            .is_ok_and(|reply| reply.is_some())
+    }
+}
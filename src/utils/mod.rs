@@ -6,4 +6,19 @@ pub mod pq_kyber;
 pub mod crypto;
 pub mod jwt;
 pub mod open_id;
-pub mod oauth;
\ No newline at end of file
+pub mod oauth;
+pub mod recovery_share;
+pub mod key_import;
+pub mod key_encoding;
+pub mod mac;
+pub mod stream_cipher;
+pub mod cidr;
+pub mod client_assertion;
+pub mod key_ring;
+pub mod token_deny_list;
+pub mod cbor;
+pub mod webauthn;
+pub mod used_otp_steps;
+pub mod rate_limit;
+pub mod dpop;
+pub mod message_signature;pub mod backup_signing_keys;
@@ -1,69 +1,74 @@
-use std::env;
-
-use chrono::Utc;
-use jsonwebtoken::{decode, encode, Algorithm, DecodingKey, EncodingKey, Header, Validation};
-use mail_send::smtp::auth;
-use rocket::http::Status;
-use serde::{Deserialize, Serialize};
+use base64::{engine::general_purpose, Engine as _};
+use reqwest::Url;
 use sha2::{Digest, Sha256};
-use uuid::Uuid;
 
-use crate::{controlers::oauth, models::user::User};
+/// the only PKCE method accepted, `plain` would send the verifier in the authorization request
+pub const CODE_CHALLENGE_METHOD: &str = "S256";
 
-#[derive(Serialize, Deserialize, Debug)]
-pub struct Oauth {
-    pub id: Uuid,
-    pub state_control: String,
-    pub iat: i64,
-    pub exp: i64,
-}
+/// ### Oauth
+///
+/// the checks of the authorization code flow: PKCE (RFC 7636), redirect uris and scopes
+pub struct Oauth;
 
 impl Oauth {
-    fn new(user_id: Uuid, state: String) -> Self {
-        let now = Utc::now().timestamp();
-        let mut hasher = Sha256::new();
-        hasher.update(state);
-        let state_control = format!("{:x}", hasher.finalize());
-        let expiration = Utc::now()
-            .checked_add_signed(chrono::Duration::seconds(
-                env::var("OAUTH_TOKEN_DURATION")
-                    .unwrap()
-                    .parse::<i64>()
-                    .unwrap(),
-            ))
-            .expect("invalid timestamp")
-            .timestamp();
-        Self {
-            id: user_id,
-            state_control,
-            iat: now,
-            exp: expiration
+    /// a verifier is 43 to 128 unreserved characters
+    pub fn is_code_verifier(code_verifier: &str) -> bool {
+        (43..=128).contains(&code_verifier.len())
+            && code_verifier
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || "-._~".contains(c))
+    }
+
+    /// a S256 challenge is the url safe base64 of a SHA-256, 43 characters
+    pub fn is_code_challenge(code_challenge: &str) -> bool {
+        code_challenge.len() == 43
+            && general_purpose::URL_SAFE_NO_PAD
+                .decode(code_challenge)
+                .is_ok_and(|digest| digest.len() == 32)
+    }
+
+    pub fn code_challenge(code_verifier: &str) -> String {
+        general_purpose::URL_SAFE_NO_PAD.encode(Sha256::digest(code_verifier.as_bytes()))
+    }
+
+    pub fn verify_code_challenge(code_verifier: &str, code_challenge: &str) -> bool {
+        Self::is_code_verifier(code_verifier)
+            && openssl::memcmp::eq(
+                Self::code_challenge(code_verifier).as_bytes(),
+                code_challenge.as_bytes(),
+            )
+    }
+
+    /// an absolute uri without fragment: https, http on the loopback interface
+    /// or a private-use scheme of a native application (RFC 8252)
+    pub fn is_redirect_uri(redirect_uri: &str) -> bool {
+        let url = match Url::parse(redirect_uri) {
+            Err(_) => return false,
+            Ok(url) => url,
+        };
+        if url.fragment().is_some() {
+            return false;
+        }
+        match url.scheme() {
+            "https" => url.host().is_some(),
+            "http" => matches!(url.host_str(), Some("localhost" | "127.0.0.1" | "[::1]")),
+            scheme => scheme.contains('.'),
         }
     }
 
-    pub fn create(user: User, state: String) -> String {
-        let data = Self::new(user.id, state);
-        let key = EncodingKey::from_rsa_pem(include_bytes!("../../ssh_keys/private.key")).unwrap();
-        encode(&Header::new(Algorithm::RS256), &data, &key).unwrap()
+    /// the scopes are separated by spaces
+    pub fn has_scope(scope: Option<&str>, expected: &str) -> bool {
+        scope.is_some_and(|scope| scope.split(' ').any(|s| s == expected))
     }
 
-    pub async fn check(authorize_code: String, state: String) -> Result<Oauth, (Status, Option<&'static str>)> {
-        match decode::<Self>(
-            &authorize_code,
-            &DecodingKey::from_rsa_pem(include_bytes!("../../ssh_keys/public.key")).unwrap(),
-            &Validation::new(Algorithm::RS256),
-        ) {
-            Ok(authorized) => {
-                let oauth = authorized.claims;
-                let mut hasher = Sha256::new();
-                hasher.update(state);
-                let state_control = format!("{:x}", hasher.finalize());
-                match state_control == oauth.state_control {
-                    false => Err((Status::Forbidden, None)),
-                    true => Ok(oauth)
-                }
-            },
-            Err(_) => Err((Status::Forbidden, None))
+    /// the redirect uri with the response parameters added to its query
+    pub fn redirect_location(redirect_uri: &str, params: &[(&str, &str)]) -> String {
+        match Url::parse(redirect_uri) {
+            Err(_) => redirect_uri.to_string(),
+            Ok(mut url) => {
+                url.query_pairs_mut().extend_pairs(params);
+                url.to_string()
+            }
         }
     }
 }
@@ -1,59 +1,168 @@
 use chrono::Utc;
-use jsonwebtoken::{decode, encode, Algorithm, DecodingKey, EncodingKey, Header, Validation};
+use jsonwebtoken::{Algorithm, Validation};
 use rocket::http::Status;
+use rocket_okapi::okapi::schemars;
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-use crate::{
-    db::connect::DbPool,
-    models::{application::Application, user::User},
-    repositories::{application::ApplicationRepository, connexion::ConnexionRepository},
-};
+use crate::models::user::User;
+use crate::utils::{key_ring::KeyRing, oauth::Oauth};
 use dotenv::dotenv;
 use std::env;
 
+/// ### Authentication
+///
+/// when and how the user authenticated the session, carried by its tokens
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub struct Authentication {
+    pub auth_time: i64,
+    pub amr: Vec<String>,
+}
+
+impl Authentication {
+    /// a login with the password, and the one time password when 2FA is on
+    pub fn password(user: &User) -> Self {
+        let amr = match user.is_2fa_activated {
+            true => vec!["pwd", "otp", "mfa"],
+            false => vec!["pwd"],
+        };
+        Self::now(amr)
+    }
+
+    /// a login with the password and a passkey as second factor
+    pub fn password_and_passkey() -> Self {
+        Self::now(vec!["pwd", "hwk", "mfa"])
+    }
+
+    /// a passwordless login with a passkey, the authenticator verified the user
+    pub fn passkey() -> Self {
+        Self::now(vec!["hwk", "user", "mfa"])
+    }
+
+    fn now(amr: Vec<&str>) -> Self {
+        Authentication {
+            auth_time: Utc::now().timestamp(),
+            amr: amr.into_iter().map(String::from).collect(),
+        }
+    }
+}
+
+/// ### UserClaims
+///
+/// the standard profile and email claims of a user
+#[derive(Serialize, Deserialize, Debug, Clone, Default, JsonSchema)]
+pub struct UserClaims {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub given_name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub family_name: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub preferred_username: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub email: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub email_verified: Option<bool>,
+}
+
+impl UserClaims {
+    /// the claims allowed by an OAuth scope, `None` for the first party sessions which get all of them
+    pub fn new(user: &User, scope: Option<&str>) -> Self {
+        let allows = |claim_scope: &str| scope.is_none() || Oauth::has_scope(scope, claim_scope);
+        let mut claims = UserClaims::default();
+        if allows("profile") {
+            claims.name = Some(format!("{} {}", user.firstname, user.lastname).trim().to_string());
+            claims.given_name = Some(user.firstname.to_string());
+            claims.family_name = Some(user.lastname.to_string());
+            claims.preferred_username = Some(user.login.to_string());
+        }
+        if allows("email") && !user.email.is_empty() {
+            claims.email = Some(user.email.to_string());
+            claims.email_verified = Some(user.is_validated);
+        }
+        claims
+    }
+}
+
+/// ### OpenId
+///
+/// the OpenID Connect ID token of a user
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct OpenId {
+    pub iss: String,
     pub sub: Uuid,
+    /// the client the token is issued to, the application id for the first party sessions
+    pub aud: String,
     pub iat: i64,
     pub exp: i64,
+    pub auth_time: i64,
+    #[serde(default)]
+    pub amr: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nonce: Option<String>,
+    #[serde(flatten)]
+    pub claims: UserClaims,
 }
 
 impl OpenId {
-    fn new(data: User) -> Self {
+    fn new(
+        data: &User,
+        audience: String,
+        authentication: &Authentication,
+        nonce: Option<String>,
+        scope: Option<&str>,
+    ) -> Self {
         dotenv().ok();
         let now = Utc::now().timestamp();
-        let expiration = Utc::now()
-            .checked_add_signed(chrono::Duration::seconds(
-                env::var("OPENID_TOKEN_DURATION")
-                    .unwrap()
-                    .parse::<i64>()
-                    .unwrap(),
-            ))
-            .expect("invalid timestamp")
-            .timestamp();
+        let duration = env::var("OPENID_TOKEN_DURATION")
+            .unwrap_or_else(|_| "3600".to_string())
+            .parse::<i64>()
+            .unwrap_or(3600);
 
         OpenId {
+            iss: Self::issuer(),
             sub: data.id,
+            aud: audience,
             iat: now,
-            exp: expiration,
+            exp: now + duration,
+            auth_time: authentication.auth_time,
+            amr: authentication.amr.clone(),
+            nonce,
+            claims: UserClaims::new(data, scope),
         }
     }
 
-    pub async fn create(data: User) -> String {
-        let data = Self::new(data);
-        let key = EncodingKey::from_rsa_pem(include_bytes!("../../ssh_keys/private.key")).unwrap();
-        encode(&Header::new(Algorithm::RS256), &data, &key).unwrap()
+    pub async fn create(
+        data: &User,
+        audience: String,
+        authentication: &Authentication,
+        nonce: Option<String>,
+        scope: Option<&str>,
+    ) -> String {
+        KeyRing::encode(&Self::new(data, audience, authentication, nonce, scope))
     }
 
+    /// check the signature, the expiration and the issuer, the audience is left to the caller
     pub fn check(open_id_token: String) -> Result<Self, (Status, Option<&'static str>)> {
-        match decode::<Self>(
-            &open_id_token,
-            &DecodingKey::from_rsa_pem(include_bytes!("../../ssh_keys/public.key")).unwrap(),
-            &Validation::new(Algorithm::RS256),
-        ) {
+        let mut validation = Validation::new(Algorithm::RS256);
+        validation.set_issuer(&[Self::issuer()]);
+        validation.validate_aud = false;
+        match KeyRing::decode::<Self>(&open_id_token, &validation) {
             Ok(open_id) => Ok(open_id.claims),
             Err(_) => Err((Status::Forbidden, None))
         }
     }
+
+    /// the public url of the API, `API_URL`
+    pub fn issuer() -> String {
+        let api_url = env::var("API_URL").unwrap_or_else(|_| {
+            format!(
+                "http://localhost:{}",
+                env::var("PORT").unwrap_or_else(|_| "7986".to_string())
+            )
+        });
+        api_url.trim_end_matches('/').to_string()
+    }
 }
@@ -0,0 +1,173 @@
+use std::{env, net::IpAddr};
+
+use crate::enums::attempt_scope::AttemptScope;
+use crate::redis::RedisClient;
+
+/// how long the level of lockout of an account is remembered, in seconds
+const LOCKOUT_LEVEL_MEMORY: u64 = 86400;
+
+/// ### RateLimit
+///
+/// the failed attempts of each account, a login of an application, and of each IP, kept in Redis.
+/// An account failing too often in a window is locked, each new lock lasting twice the previous
+/// one. An IP failing too often is refused until its window ends
+pub struct RateLimit;
+
+impl RateLimit {
+    pub fn failures_key(scope: AttemptScope, app_id: i32, login: &str) -> String {
+        format!("attempts:{}:{}:{}", scope.as_str(), app_id, login)
+    }
+
+    pub fn ip_key(scope: AttemptScope, ip: &IpAddr) -> String {
+        format!("attempts:{}:ip:{}", scope.as_str(), ip)
+    }
+
+    pub fn lock_key(scope: AttemptScope, app_id: i32, login: &str) -> String {
+        format!("lockout:{}:{}:{}", app_id, scope.as_str(), login)
+    }
+
+    pub fn level_key(scope: AttemptScope, app_id: i32, login: &str) -> String {
+        format!("lockout_level:{}:{}:{}", app_id, scope.as_str(), login)
+    }
+
+    /// the seconds before the account or the IP can try again, `None` when they can
+    pub fn retry_after(scope: AttemptScope, app_id: i32, login: &str, ip: &IpAddr) -> Option<u64> {
+        let mut conn = RedisClient::get_connection();
+        let (lock_ttl, ip_failures, ip_ttl): (i64, Option<u32>, i64) = redis::pipe()
+            .cmd("TTL")
+            .arg(Self::lock_key(scope, app_id, login))
+            .cmd("GET")
+            .arg(Self::ip_key(scope, ip))
+            .cmd("TTL")
+            .arg(Self::ip_key(scope, ip))
+            .query(&mut conn)
+            .unwrap_or((1, None, 1));
+        if lock_ttl > 0 {
+            return Some(lock_ttl as u64);
+        }
+        match ip_failures {
+            Some(failures) if failures >= Self::max_attempts_per_ip() => Some(ip_ttl.max(1) as u64),
+            _ => None,
+        }
+    }
+
+    /// count a failed attempt, the seconds of the lock when it locks the account
+    pub fn record_failure(scope: AttemptScope, app_id: i32, login: &str, ip: &IpAddr) -> Option<u64> {
+        let window = Self::setting("RATE_LIMIT_WINDOW", 900);
+        let failures_key = Self::failures_key(scope, app_id, login);
+        let ip_key = Self::ip_key(scope, ip);
+        let mut conn = RedisClient::get_connection();
+        // the counters start at the first failure and end with their window
+        let (failures,): (u32,) = redis::pipe()
+            .atomic()
+            .cmd("SET").arg(&failures_key).arg(0).arg("NX").arg("EX").arg(window).ignore()
+            .cmd("INCR").arg(&failures_key)
+            .cmd("SET").arg(&ip_key).arg(0).arg("NX").arg("EX").arg(window).ignore()
+            .cmd("INCR").arg(&ip_key).ignore()
+            .query(&mut conn)
+            .ok()?;
+        if failures < Self::max_attempts() {
+            return None;
+        }
+        let level_key = Self::level_key(scope, app_id, login);
+        let (level,): (u32,) = redis::pipe()
+            .atomic()
+            .cmd("INCR").arg(&level_key)
+            .cmd("EXPIRE").arg(&level_key).arg(LOCKOUT_LEVEL_MEMORY).ignore()
+            .cmd("DEL").arg(&failures_key).ignore()
+            .query(&mut conn)
+            .ok()?;
+        let duration = Self::lockout_duration(
+            level,
+            Self::setting("LOCKOUT_DURATION", 60),
+            Self::setting("LOCKOUT_MAX_DURATION", 86400),
+        );
+        redis::cmd("SET")
+            .arg(Self::lock_key(scope, app_id, login))
+            .arg(level)
+            .arg("EX")
+            .arg(duration)
+            .query::<()>(&mut conn)
+            .ok()?;
+        Some(duration)
+    }
+
+    /// forget the failures of an account after a successful attempt
+    pub fn reset(scope: AttemptScope, app_id: i32, login: &str) {
+        let mut conn = RedisClient::get_connection();
+        let _ = redis::cmd("DEL")
+            .arg(Self::failures_key(scope, app_id, login))
+            .arg(Self::level_key(scope, app_id, login))
+            .query::<()>(&mut conn);
+    }
+
+    /// lift the locks of an account in every scope
+    pub fn unlock(app_id: i32, login: &str) -> Result<(), redis::RedisError> {
+        let keys: Vec<String> = AttemptScope::ALL
+            .into_iter()
+            .flat_map(|scope| {
+                [
+                    Self::lock_key(scope, app_id, login),
+                    Self::level_key(scope, app_id, login),
+                    Self::failures_key(scope, app_id, login),
+                ]
+            })
+            .collect();
+        let mut conn = RedisClient::get_connection();
+        redis::cmd("DEL").arg(keys).query(&mut conn)
+    }
+
+    /// the locked accounts of an application: the scope, the login and the seconds left
+    pub fn get_locked(app_id: i32) -> Vec<(AttemptScope, String, u64)> {
+        let mut conn = RedisClient::get_connection();
+        let pattern = format!("lockout:{}:*", app_id);
+        let keys: Vec<String> = match redis::cmd("SCAN")
+            .cursor_arg(0)
+            .arg("MATCH")
+            .arg(&pattern)
+            .arg("COUNT")
+            .arg(1000)
+            .clone()
+            .iter::<String>(&mut conn)
+        {
+            Err(_) => return vec![],
+            Ok(keys) => keys.collect(),
+        };
+        keys.into_iter()
+            .filter_map(|key| {
+                let (scope, login) = Self::parse_lock_key(&key, app_id)?;
+                let ttl: i64 = redis::cmd("TTL").arg(&key).query(&mut conn).ok()?;
+                (ttl > 0).then_some((scope, login, ttl as u64))
+            })
+            .collect()
+    }
+
+    /// the scope and the login of a lock key of the application
+    pub fn parse_lock_key(key: &str, app_id: i32) -> Option<(AttemptScope, String)> {
+        let rest = key.strip_prefix(&format!("lockout:{}:", app_id))?;
+        let (scope, login) = rest.split_once(':')?;
+        Some((AttemptScope::from_str(scope)?, login.to_string()))
+    }
+
+    /// the lock of the `level`th lock in a row, doubling from `base` up to `max` seconds
+    pub fn lockout_duration(level: u32, base: u64, max: u64) -> u64 {
+        let factor = 1u64.checked_shl(level.saturating_sub(1)).unwrap_or(u64::MAX);
+        base.saturating_mul(factor).min(max)
+    }
+
+    pub fn max_attempts() -> u32 {
+        Self::setting("RATE_LIMIT_MAX_ATTEMPTS", 5) as u32
+    }
+
+    pub fn max_attempts_per_ip() -> u32 {
+        Self::setting("RATE_LIMIT_MAX_ATTEMPTS_PER_IP", 50) as u32
+    }
+
+    fn setting(name: &str, default: u64) -> u64 {
+        env::var(name)
+            .ok()
+            .and_then(|value| value.parse::<u64>().ok())
+            .filter(|value| *value > 0)
+            .unwrap_or(default)
+    }
+}
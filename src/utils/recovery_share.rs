@@ -0,0 +1,146 @@
+use base32::Alphabet;
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+use crate::enums::sentinel_purpose::SentinelPurpose;
+
+const RECOVERY_SHARE_VERSION: u8 = 2;
+/// the shares printed before the purpose of the key was carried, their keys are encryption keys
+const RECOVERY_SHARE_VERSION_1: u8 = 1;
+const TEXT_TITLE: &str = "LAGERTHA RECOVERY SHARE";
+const CHECKSUM_SIZE: usize = 4;
+/// version + sentinel id + application id + key size + threshold + number of shares + purpose
+const HEADER_SIZE: usize = 1 + 16 + 4 + 2 + 1 + 1 + 1;
+const HEADER_SIZE_1: usize = HEADER_SIZE - 1;
+
+/// ### RecoveryShare
+///
+/// one share of a paper recovery kit, given to a human custodian
+/// the share carries everything needed to re-create the sentinel:
+/// its id, its application, its key size, its purpose and the threshold of the kit
+///
+/// the encoded form is a single RFC 4648 base32 string (QR alphanumeric friendly)
+/// ending with a 4 bytes sha256 checksum
+#[derive(Debug, Clone, PartialEq)]
+pub struct RecoveryShare {
+    pub sentinel_id: Uuid,
+    pub application_id: i32,
+    pub key_size: u16,
+    pub threshold: u8,
+    pub number_of_shares: u8,
+    pub purpose: SentinelPurpose,
+    pub share: Vec<u8>,
+}
+
+impl RecoveryShare {
+    /// the position of the share in the kit (1 to N)
+    pub fn index(&self) -> u8 {
+        self.share.first().cloned().unwrap_or(0)
+    }
+
+    /// ### Encode
+    ///
+    /// the base32 form of the share, checksum included
+    pub fn encode(&self) -> String {
+        let mut bytes = Vec::with_capacity(HEADER_SIZE + self.share.len() + CHECKSUM_SIZE);
+        bytes.push(RECOVERY_SHARE_VERSION);
+        bytes.extend_from_slice(self.sentinel_id.as_bytes());
+        bytes.extend_from_slice(&self.application_id.to_be_bytes());
+        bytes.extend_from_slice(&self.key_size.to_be_bytes());
+        bytes.push(self.threshold);
+        bytes.push(self.number_of_shares);
+        bytes.push(match self.purpose {
+            SentinelPurpose::Encryption => 0,
+            SentinelPurpose::Mac => 1,
+        });
+        bytes.extend_from_slice(&self.share);
+        let checksum = Self::checksum(&bytes);
+        bytes.extend_from_slice(&checksum);
+        base32::encode(Alphabet::Rfc4648 { padding: false }, &bytes)
+    }
+
+    /// ### Decode
+    ///
+    /// parse a share typed or scanned by a custodian, or its full printable text,
+    /// spaces, dashes and new lines are ignored and the case does not matter
+    pub fn decode(encoded: &str) -> Result<Self, &'static str> {
+        let encoded = match encoded.trim_start().starts_with(TEXT_TITLE) {
+            false => encoded,
+            true => match encoded.split_once("\n\n") {
+                None => return Err("The share text has no share block"),
+                Some((_, block)) => block,
+            },
+        };
+        let cleaned: String = encoded
+            .chars()
+            .filter(|c| c.is_ascii_alphanumeric())
+            .map(|c| c.to_ascii_uppercase())
+            .collect();
+        let bytes = match base32::decode(Alphabet::Rfc4648 { padding: false }, &cleaned) {
+            None => return Err("The share is not a valid base32 string"),
+            Some(bytes) => bytes,
+        };
+        if bytes.len() <= HEADER_SIZE_1 + CHECKSUM_SIZE + 1 {
+            return Err("The share is too short");
+        }
+        let (content, checksum) = bytes.split_at(bytes.len() - CHECKSUM_SIZE);
+        if Self::checksum(content) != checksum {
+            return Err("The share checksum does not match, check for a typo");
+        }
+        let (purpose, header_size) = match content[0] {
+            RECOVERY_SHARE_VERSION_1 => (SentinelPurpose::Encryption, HEADER_SIZE_1),
+            RECOVERY_SHARE_VERSION if content.len() > HEADER_SIZE => match content[25] {
+                0 => (SentinelPurpose::Encryption, HEADER_SIZE),
+                1 => (SentinelPurpose::Mac, HEADER_SIZE),
+                _ => return Err("Unknown key purpose"),
+            },
+            RECOVERY_SHARE_VERSION => return Err("The share is too short"),
+            _ => return Err("Unsupported share version"),
+        };
+        let sentinel_id = Uuid::from_slice(&content[1..17]).map_err(|_| "Bad sentinel id")?;
+        let application_id = i32::from_be_bytes([content[17], content[18], content[19], content[20]]);
+        let key_size = u16::from_be_bytes([content[21], content[22]]);
+        Ok(RecoveryShare {
+            sentinel_id,
+            application_id,
+            key_size,
+            threshold: content[23],
+            number_of_shares: content[24],
+            purpose,
+            share: content[header_size..].to_vec(),
+        })
+    }
+
+    /// ### Printable text
+    ///
+    /// the human form of the share: a header and the base32 string
+    /// split in groups of 4 characters, 8 groups per line
+    pub fn to_text(&self) -> String {
+        let encoded = self.encode();
+        let groups: Vec<String> = encoded
+            .as_bytes()
+            .chunks(4)
+            .map(|chunk| String::from_utf8_lossy(chunk).to_string())
+            .collect();
+        let lines: Vec<String> = groups.chunks(8).map(|line| line.join(" ")).collect();
+        format!(
+            "{} v{}\nsentinel: {}\napplication: {}\nkey size: AES-{}\npurpose: {}\nshare: {} of {} (any {} rebuild the key)\n\n{}\n",
+            TEXT_TITLE,
+            RECOVERY_SHARE_VERSION,
+            self.sentinel_id,
+            self.application_id,
+            self.key_size,
+            self.purpose.as_str(),
+            self.index(),
+            self.number_of_shares,
+            self.threshold,
+            lines.join("\n")
+        )
+    }
+
+    fn checksum(bytes: &[u8]) -> Vec<u8> {
+        let mut hasher = Sha256::new();
+        hasher.update(bytes);
+        hasher.finalize()[..CHECKSUM_SIZE].to_vec()
+    }
+}
@@ -0,0 +1,239 @@
+use std::env;
+use std::io;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use aes_gcm::aead::{OsRng, Payload};
+use aes_gcm::{
+    aead::{Aead, KeyInit},
+    Aes256Gcm, Key, Nonce,
+};
+use rand_core::RngCore;
+use rocket::tokio::io::{AsyncRead, ReadBuf};
+
+use crate::enums::mac_algorithm::MacAlgorithm;
+use crate::utils::mac::Mac;
+
+/// plaintext size of a segment
+pub const STREAM_SEGMENT_SIZE: usize = 64 * 1024;
+
+const STREAM_MAGIC: &[u8; 4] = b"LGS1";
+const SALT_SIZE: usize = 16;
+const NONCE_PREFIX_SIZE: usize = 7;
+const HEADER_SIZE: usize = STREAM_MAGIC.len() + 4 + SALT_SIZE + NONCE_PREFIX_SIZE;
+const TAG_SIZE: usize = 16;
+const MAX_SEGMENT_SIZE: usize = 16 * 1024 * 1024;
+const READ_BUFFER_SIZE: usize = 8 * 1024;
+
+enum Mode {
+    Encrypt,
+    Decrypt,
+}
+
+/// ### Stream cipher reader
+///
+/// Online AES-256-GCM encryption of a byte stream (STREAM construction).
+///
+/// The output starts with a header `magic | segment size (BE32) | salt | nonce prefix`,
+/// followed by the segments. Each segment is sealed with a key derived from the sentinel key
+/// and the salt, a nonce `prefix | counter (BE32) | last flag` and the header as associated data,
+/// so a reordered, truncated or extended stream fails to decrypt.
+pub struct StreamCipherReader<R> {
+    inner: R,
+    mode: Mode,
+    key: Vec<u8>,
+    cipher: Option<Aes256Gcm>,
+    header: Vec<u8>,
+    nonce_prefix: [u8; NONCE_PREFIX_SIZE],
+    segment_size: usize,
+    counter: u32,
+    input: Vec<u8>,
+    output: Vec<u8>,
+    output_pos: usize,
+    read_total: u64,
+    max_size: u64,
+    eof: bool,
+    done: bool,
+}
+
+impl<R: AsyncRead + Unpin> StreamCipherReader<R> {
+    /// Encrypts `inner` with the sentinel key, `max_size` bounds the plaintext size.
+    pub fn encrypt(inner: R, key: &[u8], max_size: u64) -> Result<Self, &'static str> {
+        let mut salt = [0u8; SALT_SIZE];
+        let mut nonce_prefix = [0u8; NONCE_PREFIX_SIZE];
+        OsRng.fill_bytes(&mut salt);
+        OsRng.fill_bytes(&mut nonce_prefix);
+        let mut header = Vec::with_capacity(HEADER_SIZE);
+        header.extend_from_slice(STREAM_MAGIC);
+        header.extend_from_slice(&(STREAM_SEGMENT_SIZE as u32).to_be_bytes());
+        header.extend_from_slice(&salt);
+        header.extend_from_slice(&nonce_prefix);
+
+        let mut reader = Self::new(inner, Mode::Encrypt, key, max_size);
+        reader.cipher = Some(Self::derive_cipher(key, &salt)?);
+        reader.nonce_prefix = nonce_prefix;
+        reader.segment_size = STREAM_SEGMENT_SIZE;
+        reader.output = header.clone();
+        reader.header = header;
+        Ok(reader)
+    }
+
+    /// Decrypts `inner` with the sentinel key, `max_size` bounds the ciphertext size.
+    pub fn decrypt(inner: R, key: &[u8], max_size: u64) -> Result<Self, &'static str> {
+        if key.is_empty() {
+            return Err("Bad stream key");
+        }
+        Ok(Self::new(inner, Mode::Decrypt, key, max_size))
+    }
+
+    fn new(inner: R, mode: Mode, key: &[u8], max_size: u64) -> Self {
+        Self {
+            inner,
+            mode,
+            key: key.to_vec(),
+            cipher: None,
+            header: Vec::new(),
+            nonce_prefix: [0u8; NONCE_PREFIX_SIZE],
+            segment_size: 0,
+            counter: 0,
+            input: Vec::new(),
+            output: Vec::new(),
+            output_pos: 0,
+            read_total: 0,
+            max_size,
+            eof: false,
+            done: false,
+        }
+    }
+
+    fn derive_cipher(key: &[u8], salt: &[u8]) -> Result<Aes256Gcm, &'static str> {
+        let stream_key = Mac::sign(&MacAlgorithm::HS256, key, salt)?;
+        Ok(Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(&stream_key)))
+    }
+
+    /// number of input bytes needed before the next step can run
+    fn wanted(&self) -> usize {
+        match (&self.mode, &self.cipher) {
+            (Mode::Decrypt, None) => HEADER_SIZE,
+            (Mode::Encrypt, _) => self.segment_size + 1,
+            (Mode::Decrypt, Some(_)) => self.segment_size + TAG_SIZE + 1,
+        }
+    }
+
+    fn read_header(&mut self) -> Result<(), &'static str> {
+        if self.input.len() < HEADER_SIZE || &self.input[..STREAM_MAGIC.len()] != STREAM_MAGIC {
+            return Err("Bad stream header");
+        }
+        let header: Vec<u8> = self.input.drain(..HEADER_SIZE).collect();
+        let mut size = [0u8; 4];
+        size.copy_from_slice(&header[4..8]);
+        let segment_size = u32::from_be_bytes(size) as usize;
+        if segment_size == 0 || segment_size > MAX_SEGMENT_SIZE {
+            return Err("Bad stream header");
+        }
+        let salt = &header[8..8 + SALT_SIZE];
+        self.cipher = Some(Self::derive_cipher(&self.key, salt)?);
+        self.nonce_prefix.copy_from_slice(&header[8 + SALT_SIZE..]);
+        self.segment_size = segment_size;
+        self.header = header;
+        Ok(())
+    }
+
+    fn process_segment(&mut self) -> Result<(), &'static str> {
+        let segment_len = match self.mode {
+            Mode::Encrypt => self.segment_size,
+            Mode::Decrypt => self.segment_size + TAG_SIZE,
+        };
+        let last = self.input.len() <= segment_len;
+        let segment: Vec<u8> = self
+            .input
+            .drain(..segment_len.min(self.input.len()))
+            .collect();
+        let mut nonce = [0u8; 12];
+        nonce[..NONCE_PREFIX_SIZE].copy_from_slice(&self.nonce_prefix);
+        nonce[NONCE_PREFIX_SIZE..11].copy_from_slice(&self.counter.to_be_bytes());
+        nonce[11] = last as u8;
+        let payload = Payload {
+            msg: &segment,
+            aad: &self.header,
+        };
+        let cipher = self.cipher.as_ref().ok_or("Bad stream header")?;
+        self.output = match self.mode {
+            Mode::Encrypt => cipher
+                .encrypt(Nonce::from_slice(&nonce), payload)
+                .map_err(|_| "Unable to encrypt the stream")?,
+            Mode::Decrypt => cipher
+                .decrypt(Nonce::from_slice(&nonce), payload)
+                .map_err(|_| "Unable to decrypt the stream")?,
+        };
+        self.output_pos = 0;
+        self.done = last;
+        if !last {
+            self.counter = self.counter.checked_add(1).ok_or("The stream is too large")?;
+        }
+        Ok(())
+    }
+
+    fn step(&mut self) -> Result<(), &'static str> {
+        match (&self.mode, &self.cipher) {
+            (Mode::Decrypt, None) => self.read_header(),
+            _ => self.process_segment(),
+        }
+    }
+}
+
+impl<R: AsyncRead + Unpin> AsyncRead for StreamCipherReader<R> {
+    fn poll_read(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<io::Result<()>> {
+        let this = self.get_mut();
+        loop {
+            if this.output_pos < this.output.len() {
+                let len = buf.remaining().min(this.output.len() - this.output_pos);
+                buf.put_slice(&this.output[this.output_pos..this.output_pos + len]);
+                this.output_pos += len;
+                return Poll::Ready(Ok(()));
+            }
+            if this.done {
+                return Poll::Ready(Ok(()));
+            }
+            let wanted = this.wanted();
+            while !this.eof && this.input.len() < wanted {
+                let mut chunk = [0u8; READ_BUFFER_SIZE];
+                let len = READ_BUFFER_SIZE.min(wanted - this.input.len());
+                let mut read_buf = ReadBuf::new(&mut chunk[..len]);
+                match Pin::new(&mut this.inner).poll_read(cx, &mut read_buf) {
+                    Poll::Pending => return Poll::Pending,
+                    Poll::Ready(Err(e)) => return Poll::Ready(Err(e)),
+                    Poll::Ready(Ok(())) => {}
+                }
+                let filled = read_buf.filled();
+                if filled.is_empty() {
+                    this.eof = true;
+                } else {
+                    this.read_total += filled.len() as u64;
+                    if this.read_total > this.max_size {
+                        return Poll::Ready(Err(io::Error::new(
+                            io::ErrorKind::InvalidData,
+                            "The stream is too large",
+                        )));
+                    }
+                    this.input.extend_from_slice(filled);
+                }
+            }
+            if let Err(e) = this.step() {
+                return Poll::Ready(Err(io::Error::new(io::ErrorKind::InvalidData, e)));
+            }
+        }
+    }
+}
+
+/// maximum size of a streamed payload, `STREAM_MAX_SIZE` in bytes (1 GiB by default)
+pub fn stream_max_size() -> u64 {
+    env::var("STREAM_MAX_SIZE")
+        .unwrap_or_else(|_| "1073741824".to_string())
+        .parse::<u64>()
+        .unwrap_or(1073741824)
+}
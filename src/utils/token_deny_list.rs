@@ -0,0 +1,55 @@
+use chrono::Utc;
+use uuid::Uuid;
+
+use crate::redis::RedisClient;
+
+/// ### TokenDenyList
+///
+/// the `jti` of the revoked tokens and the `sid` of the revoked sessions,
+/// kept in Redis until the tokens expire
+pub struct TokenDenyList;
+
+impl TokenDenyList {
+    pub fn key(jti: &Uuid) -> String {
+        format!("revoked_jti:{}", jti)
+    }
+
+    pub fn session_key(sid: &Uuid) -> String {
+        format!("revoked_sid:{}", sid)
+    }
+
+    /// deny a token until its expiration `exp`, an expired token is already refused
+    pub fn revoke(jti: &Uuid, exp: i64) -> Result<(), redis::RedisError> {
+        Self::deny(Self::key(jti), exp)
+    }
+
+    /// deny every token of a session until the last of them expires at `exp`
+    pub fn revoke_session(sid: &Uuid, exp: i64) -> Result<(), redis::RedisError> {
+        Self::deny(Self::session_key(sid), exp)
+    }
+
+    /// a token whose `jti` or session is denied, a failure of Redis counts as revoked
+    pub fn is_revoked(jti: &Uuid, sid: Option<&Uuid>) -> bool {
+        let mut keys = vec![Self::key(jti)];
+        keys.extend(sid.map(Self::session_key));
+        let mut conn = RedisClient::get_connection();
+        redis::cmd("EXISTS")
+            .arg(keys)
+            .query::<u32>(&mut conn)
+            .map_or(true, |count| count > 0)
+    }
+
+    fn deny(key: String, exp: i64) -> Result<(), redis::RedisError> {
+        let ttl = exp - Utc::now().timestamp();
+        if ttl <= 0 {
+            return Ok(());
+        }
+        let mut conn = RedisClient::get_connection();
+        redis::cmd("SET")
+            .arg(key)
+            .arg(1)
+            .arg("EX")
+            .arg(ttl)
+            .query(&mut conn)
+    }
+}
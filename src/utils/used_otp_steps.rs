@@ -0,0 +1,28 @@
+use uuid::Uuid;
+
+use crate::redis::RedisClient;
+use crate::utils::code::TOTP_PERIOD;
+
+/// ### UsedOtpSteps
+///
+/// the TOTP steps already used by each user, kept in Redis while their codes are accepted
+pub struct UsedOtpSteps;
+
+impl UsedOtpSteps {
+    pub fn key(user_id: &Uuid, step: u64) -> String {
+        format!("totp_used:{}:{}", user_id, step)
+    }
+
+    /// mark a step used until its codes are refused, false when it already was. A failure of Redis counts as used
+    pub fn claim(user_id: &Uuid, step: u64, window: u64) -> bool {
+        let mut conn = RedisClient::get_connection();
+        redis::cmd("SET")
+            .arg(Self::key(user_id, step))
+            .arg(1)
+            .arg("NX")
+            .arg("EX")
+            .arg((2 * window + 2) * TOTP_PERIOD)
+            .query::<Option<String>>(&mut conn)
+            .is_ok_and(|reply| reply.is_some())
+    }
+}
@@ -1,6 +1,8 @@
 use regex::Regex;
 use serde::{de::Error, Deserialize, Deserializer};
 
+use crate::utils::cidr::Cidr;
+
 /// ### Email validator
 ///
 /// validate the string to be an email string
@@ -104,20 +106,37 @@ where
 
 /// ### vec Ip validator
 ///
-/// check if an vec of string is an vec of IP string
+/// check if an vec of string is an vec of IPv4 / IPv6 addresses or CIDR ranges
 pub fn vec_ip<'de, D>(deserializer: D) -> Result<Vec<String>, D::Error>
 where
     D: Deserializer<'de>,
 {
-    let raw_value: Vec<String> = Deserialize::deserialize(deserializer).unwrap();
-    let ip_regex = Regex::new(r"^(?:[0-9]{1,3}\.){3}[0-9]{1,3}$").unwrap();
+    let raw_value: Vec<String> = Deserialize::deserialize(deserializer)?;
     for ip in &raw_value {
-        if !ip_regex.is_match(&ip) {
+        if Cidr::parse(ip).is_err() {
             return Err(Error::custom("Invalid ip"));
         }
     }
+    Ok(raw_value.into_iter().map(|ip| ip.trim().to_string()).collect())
+}
 
-    Ok(raw_value)
+/// ### Option vec Ip validator
+///
+/// check if an optional vec of string is an vec of IPv4 / IPv6 addresses or CIDR ranges
+pub fn option_vec_ip<'de, D>(deserializer: D) -> Result<Option<Vec<String>>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let raw_value: Option<Vec<String>> = Deserialize::deserialize(deserializer)?;
+    match raw_value {
+        None => Ok(None),
+        Some(raw_value) => {
+            if raw_value.iter().any(|ip| Cidr::parse(ip).is_err()) {
+                return Err(Error::custom("Invalid ip"));
+            }
+            Ok(Some(raw_value.into_iter().map(|ip| ip.trim().to_string()).collect()))
+        }
+    }
 }
 
 /// ### Ip validator
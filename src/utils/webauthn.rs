@@ -0,0 +1,264 @@
+use std::env;
+
+use base64::{engine::general_purpose, Engine as _};
+use openssl::{
+    bn::BigNum,
+    ec::{EcGroup, EcKey},
+    hash::MessageDigest,
+    nid::Nid,
+    pkey::{Id, PKey, Public},
+    rsa::Rsa,
+    sign::Verifier,
+};
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+
+use crate::utils::{cbor::Cbor, open_id::OpenId};
+
+/// the COSE algorithms accepted, ECDSA P-256, Ed25519 and RSASSA-PKCS1-v1_5
+pub const COSE_ES256: i32 = -7;
+pub const COSE_EDDSA: i32 = -8;
+pub const COSE_RS256: i32 = -257;
+
+/// the shortest RSA modulus accepted
+const MIN_RSA_BITS: i32 = 2048;
+
+/// how long a ceremony can take, in seconds
+pub const CEREMONY_TIMEOUT: u64 = 300;
+
+const FLAG_USER_PRESENT: u8 = 0x01;
+const FLAG_USER_VERIFIED: u8 = 0x04;
+const FLAG_ATTESTED_CREDENTIAL: u8 = 0x40;
+
+/// ### ClientData
+///
+/// the `clientDataJSON` the browser signs with the authenticator data
+#[derive(Deserialize, Debug)]
+pub struct ClientData {
+    #[serde(rename = "type")]
+    pub ceremony: String,
+    pub challenge: String,
+    pub origin: String,
+}
+
+/// ### AttestedCredential
+///
+/// the credential created by a registration
+#[derive(Debug)]
+pub struct AttestedCredential {
+    pub credential_id: Vec<u8>,
+    /// the PEM of the public key
+    pub public_key: String,
+    pub algorithm: i32,
+}
+
+/// ### AuthenticatorData
+///
+/// the data an authenticator signs, the credential is only present at registration
+#[derive(Debug)]
+pub struct AuthenticatorData {
+    pub rp_id_hash: Vec<u8>,
+    pub flags: u8,
+    pub sign_count: u32,
+    pub credential: Option<AttestedCredential>,
+}
+
+impl AuthenticatorData {
+    pub fn parse(bytes: &[u8]) -> Result<Self, &'static str> {
+        if bytes.len() < 37 {
+            return Err("Truncated authenticator data");
+        }
+        let flags = bytes[32];
+        let sign_count = u32::from_be_bytes([bytes[33], bytes[34], bytes[35], bytes[36]]);
+        let credential = match flags & FLAG_ATTESTED_CREDENTIAL {
+            0 => None,
+            _ => {
+                // the AAGUID of the authenticator model is not used
+                let length_at = 37 + 16;
+                let length = bytes
+                    .get(length_at..length_at + 2)
+                    .map(|length| u16::from_be_bytes([length[0], length[1]]) as usize)
+                    .ok_or("Truncated authenticator data")?;
+                let credential_id = bytes
+                    .get(length_at + 2..length_at + 2 + length)
+                    .ok_or("Truncated authenticator data")?
+                    .to_vec();
+                let (cose_key, _) = Cbor::decode(&bytes[length_at + 2 + length..])?;
+                let (public_key, algorithm) = Webauthn::cose_key_to_pem(&cose_key)?;
+                Some(AttestedCredential {
+                    credential_id,
+                    public_key,
+                    algorithm,
+                })
+            }
+        };
+        Ok(AuthenticatorData {
+            rp_id_hash: bytes[..32].to_vec(),
+            flags,
+            sign_count,
+            credential,
+        })
+    }
+
+    pub fn user_present(&self) -> bool {
+        self.flags & FLAG_USER_PRESENT != 0
+    }
+
+    pub fn user_verified(&self) -> bool {
+        self.flags & FLAG_USER_VERIFIED != 0
+    }
+}
+
+/// ### Webauthn
+///
+/// the checks of the WebAuthn ceremonies. The attestation of the authenticators is not
+/// asked for (`none`), a passkey is trusted for the key it registers, not for its model
+pub struct Webauthn;
+
+impl Webauthn {
+    /// the relying party, `WEBAUTHN_RP_ID`, by default the host of the API
+    pub fn rp_id() -> String {
+        Self::setting("WEBAUTHN_RP_ID").unwrap_or_else(|| {
+            let issuer = OpenId::issuer();
+            let host = issuer.split("://").last().unwrap_or_default();
+            host.split(['/', ':']).next().unwrap_or_default().to_string()
+        })
+    }
+
+    pub fn rp_name() -> String {
+        Self::setting("WEBAUTHN_RP_NAME").unwrap_or_else(|| "Lagertha".to_string())
+    }
+
+    /// the origins of the front ends, `WEBAUTHN_ORIGINS` comma separated, by default the API
+    pub fn origins() -> Vec<String> {
+        Self::setting("WEBAUTHN_ORIGINS")
+            .unwrap_or_else(OpenId::issuer)
+            .split(',')
+            .map(|origin| origin.trim().trim_end_matches('/').to_string())
+            .filter(|origin| !origin.is_empty())
+            .collect()
+    }
+
+    /// a setting left empty takes its default
+    fn setting(name: &str) -> Option<String> {
+        env::var(name).ok().filter(|value| !value.trim().is_empty())
+    }
+
+    /// the url safe base64 of the browsers, with or without padding
+    pub fn decode_base64(value: &str) -> Result<Vec<u8>, &'static str> {
+        general_purpose::URL_SAFE_NO_PAD
+            .decode(value.trim_end_matches('='))
+            .map_err(|_| "Bad base64url value")
+    }
+
+    pub fn encode_base64(bytes: &[u8]) -> String {
+        general_purpose::URL_SAFE_NO_PAD.encode(bytes)
+    }
+
+    /// the client data of a ceremony of type `webauthn.create` or `webauthn.get` from an allowed origin
+    pub fn client_data(client_data_json: &[u8], ceremony: &str) -> Result<ClientData, &'static str> {
+        let client_data: ClientData =
+            serde_json::from_slice(client_data_json).map_err(|_| "Bad client data")?;
+        if client_data.ceremony != ceremony {
+            return Err("Bad ceremony type");
+        }
+        if !Self::origins().contains(&client_data.origin) {
+            return Err("Origin not allowed");
+        }
+        Ok(client_data)
+    }
+
+    /// the authenticator data of the attestation object of a registration
+    pub fn attestation(attestation_object: &[u8]) -> Result<AuthenticatorData, &'static str> {
+        let (attestation, _) = Cbor::decode(attestation_object)?;
+        let authenticator_data = attestation
+            .get_text("authData")
+            .and_then(Cbor::as_bytes)
+            .ok_or("Bad attestation object")?;
+        AuthenticatorData::parse(authenticator_data)
+    }
+
+    /// the authenticator data is for this relying party, with a user present and verified when required
+    pub fn check_authenticator_data(
+        authenticator_data: &AuthenticatorData,
+        require_user_verification: bool,
+    ) -> Result<(), &'static str> {
+        if authenticator_data.rp_id_hash != Sha256::digest(Self::rp_id().as_bytes()).to_vec() {
+            return Err("Bad relying party");
+        }
+        if !authenticator_data.user_present() {
+            return Err("User not present");
+        }
+        if require_user_verification && !authenticator_data.user_verified() {
+            return Err("User not verified");
+        }
+        Ok(())
+    }
+
+    /// the signature of an assertion, over the authenticator data and the hash of the client data
+    pub fn verify_signature(
+        public_key: &str,
+        algorithm: i32,
+        authenticator_data: &[u8],
+        client_data_json: &[u8],
+        signature: &[u8],
+    ) -> bool {
+        let pkey = match PKey::public_key_from_pem(public_key.as_bytes()) {
+            Err(_) => return false,
+            Ok(pkey) => pkey,
+        };
+        let mut signed = authenticator_data.to_vec();
+        signed.extend_from_slice(&Sha256::digest(client_data_json));
+        match algorithm {
+            COSE_ES256 | COSE_RS256 => Verifier::new(MessageDigest::sha256(), &pkey)
+                .and_then(|mut verifier| verifier.verify_oneshot(signature, &signed))
+                .unwrap_or(false),
+            COSE_EDDSA => Verifier::new_without_digest(&pkey)
+                .and_then(|mut verifier| verifier.verify_oneshot(signature, &signed))
+                .unwrap_or(false),
+            _ => false,
+        }
+    }
+
+    /// the PEM and the algorithm of a COSE public key (RFC 9053)
+    pub fn cose_key_to_pem(cose_key: &Cbor) -> Result<(String, i32), &'static str> {
+        let bytes = |label: i64| cose_key.get_int(label).and_then(Cbor::as_bytes);
+        let algorithm = cose_key
+            .get_int(3)
+            .and_then(Cbor::as_int)
+            .and_then(|algorithm| i32::try_from(algorithm).ok())
+            .ok_or("Bad COSE key")?;
+        let key_type = cose_key.get_int(1).and_then(Cbor::as_int);
+        let curve = cose_key.get_int(-1).and_then(Cbor::as_int);
+        let pkey: PKey<Public> = match (key_type, algorithm) {
+            // EC2 on P-256
+            (Some(2), COSE_ES256) if curve == Some(1) => {
+                let (x, y) = bytes(-2).zip(bytes(-3)).ok_or("Bad COSE key")?;
+                let group = EcGroup::from_curve_name(Nid::X9_62_PRIME256V1).map_err(|_| "Bad COSE key")?;
+                let x = BigNum::from_slice(x).map_err(|_| "Bad COSE key")?;
+                let y = BigNum::from_slice(y).map_err(|_| "Bad COSE key")?;
+                EcKey::from_public_key_affine_coordinates(&group, &x, &y)
+                    .and_then(PKey::from_ec_key)
+                    .map_err(|_| "Bad COSE key")?
+            }
+            // OKP on Ed25519
+            (Some(1), COSE_EDDSA) if curve == Some(6) => {
+                PKey::public_key_from_raw_bytes(bytes(-2).ok_or("Bad COSE key")?, Id::ED25519)
+                    .map_err(|_| "Bad COSE key")?
+            }
+            (Some(3), COSE_RS256) => {
+                let n = BigNum::from_slice(bytes(-1).ok_or("Bad COSE key")?).map_err(|_| "Bad COSE key")?;
+                let e = BigNum::from_slice(bytes(-2).ok_or("Bad COSE key")?).map_err(|_| "Bad COSE key")?;
+                if n.num_bits() < MIN_RSA_BITS {
+                    return Err("RSA key too short");
+                }
+                Rsa::from_public_components(n, e)
+                    .and_then(PKey::from_rsa)
+                    .map_err(|_| "Bad COSE key")?
+            }
+            _ => return Err("Unsupported COSE algorithm"),
+        };
+        let pem = pkey.public_key_to_pem().map_err(|_| "Bad COSE key")?;
+        Ok((String::from_utf8_lossy(&pem).to_string(), algorithm))
+    }
+}